@@ -0,0 +1,151 @@
+#![allow(missing_docs)]
+
+use criterion::{
+    black_box, criterion_group, criterion_main, BenchmarkId, Criterion,
+};
+use html_generator::{
+    accessibility::add_aria_attributes,
+    bench::{corpora, Corpus},
+    constants::DEFAULT_SYNTAX_THEME,
+    conversion::convert,
+    footnotes::add_footnote_roles,
+    generate_html,
+    headings::{add_heading_ids, SlugStrategy},
+    highlight::{
+        highlight_code_blocks, highlight_code_blocks_parallel,
+        DEFAULT_PARALLEL_THRESHOLD,
+    },
+    tables::enhance_tables,
+    toc::{insert_toc, TocConfig},
+    HtmlConfig,
+};
+
+/// Configuration rendering Markdown without any optional stage.
+fn markdown_only() -> HtmlConfig {
+    HtmlConfig {
+        enable_syntax_highlighting: false,
+        ..HtmlConfig::default()
+    }
+}
+
+/// Returns each corpus with its HTML before post-processing.
+fn rendered() -> Vec<(Corpus, String)> {
+    let config = markdown_only();
+    corpora()
+        .into_iter()
+        .map(|corpus| {
+            let html =
+                generate_html(&corpus.markdown, &config).unwrap();
+            (corpus, html)
+        })
+        .collect()
+}
+
+fn benchmark_markdown(c: &mut Criterion) {
+    let config = markdown_only();
+    let mut group = c.benchmark_group("markdown");
+    for corpus in corpora() {
+        let _ = group.bench_with_input(
+            BenchmarkId::from_parameter(corpus.name),
+            &corpus.markdown,
+            |b, markdown| {
+                b.iter(|| generate_html(black_box(markdown), &config))
+            },
+        );
+    }
+    group.finish();
+}
+
+fn benchmark_html_stages(c: &mut Criterion) {
+    let pages = rendered();
+    let mut group = c.benchmark_group("stages");
+    for (corpus, html) in &pages {
+        let id = |stage: &str| BenchmarkId::new(stage, corpus.name);
+        let _ =
+            group.bench_with_input(id("highlight"), html, |b, html| {
+                b.iter(|| {
+                    highlight_code_blocks(
+                        black_box(html),
+                        DEFAULT_SYNTAX_THEME,
+                    )
+                })
+            });
+        let _ = group.bench_with_input(
+            id("highlight_parallel"),
+            html,
+            |b, html| {
+                b.iter(|| {
+                    highlight_code_blocks_parallel(
+                        black_box(html),
+                        DEFAULT_SYNTAX_THEME,
+                        DEFAULT_PARALLEL_THRESHOLD,
+                    )
+                })
+            },
+        );
+        let _ = group.bench_with_input(
+            id("heading_ids"),
+            html,
+            |b, html| {
+                b.iter(|| {
+                    add_heading_ids(
+                        black_box(html),
+                        false,
+                        SlugStrategy::default(),
+                    )
+                })
+            },
+        );
+        let _ = group.bench_with_input(id("toc"), html, |b, html| {
+            b.iter(|| {
+                insert_toc(
+                    black_box(html),
+                    &TocConfig::default(),
+                    SlugStrategy::default(),
+                )
+            })
+        });
+        let _ = group.bench_with_input(
+            id("enhance_tables"),
+            html,
+            |b, html| b.iter(|| enhance_tables(black_box(html))),
+        );
+        let _ = group.bench_with_input(
+            id("footnote_roles"),
+            html,
+            |b, html| b.iter(|| add_footnote_roles(black_box(html))),
+        );
+        let _ = group.bench_with_input(id("aria"), html, |b, html| {
+            b.iter(|| add_aria_attributes(black_box(html), None))
+        });
+    }
+    group.finish();
+}
+
+fn benchmark_convert(c: &mut Criterion) {
+    let config = HtmlConfig {
+        generate_toc: true,
+        enhance_tables: true,
+        heading_ids: true,
+        ..HtmlConfig::default()
+    };
+    let mut group = c.benchmark_group("convert");
+    for corpus in corpora() {
+        let _ = group.bench_with_input(
+            BenchmarkId::from_parameter(corpus.name),
+            &corpus.markdown,
+            |b, markdown| {
+                b.iter(|| convert(black_box(markdown), &config))
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    benchmark_markdown,
+    benchmark_html_stages,
+    benchmark_convert
+);
+criterion_main!(benches);
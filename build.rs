@@ -3,9 +3,33 @@
 //! If the current Rustc version is less than the minimum required version,
 //! the build script will exit the build process with a non-zero exit code.
 //!
-//! The minimum required version is specified in the `min_version` variable.
+//! The minimum required version is read from the `rust-version` field of
+//! `Cargo.toml`, falling back to `DEFAULT_MIN_VERSION` on toolchains that
+//! do not expose it.
+//!
+//! The script also records the compiler version, the version requirements
+//! of key dependencies and the enabled features so that the `build_info`
+//! module can report them at runtime. Requirements are read from
+//! `Cargo.toml`: a build script cannot tell which `Cargo.lock`, if any,
+//! resolved them when the crate is built as a dependency.
+
+use std::{env, fs, path::PathBuf, process};
+
+/// Minimum supported Rust version used when Cargo does not provide one.
+const DEFAULT_MIN_VERSION: &str = "1.56";
+
+/// Dependencies whose versions are exposed by `build_info`, paired with
+/// the environment variable each version is written to.
+const TRACKED_DEPENDENCIES: &[(&str, &str)] = &[
+    ("comrak", "HTML_GENERATOR_COMRAK_VERSION"),
+    ("minify-html", "HTML_GENERATOR_MINIFY_HTML_VERSION"),
+    ("mdx-gen", "HTML_GENERATOR_MDX_GEN_VERSION"),
+    ("syntect", "HTML_GENERATOR_SYNTECT_VERSION"),
+];
 
-use std::process;
+/// File in `OUT_DIR` holding the list of enabled features, included by
+/// `build_info`.
+const FEATURES_FILE: &str = "features.rs";
 
 /// Checks if the current Rustc version is at least the minimum required version
 ///
@@ -16,9 +40,9 @@
 /// # Returns
 ///
 /// * `Some(true)` - If the current Rustc version is at least the minimum
-///    required version.
+///   required version.
 /// * `Some(false)` - If the current Rustc version is less than the minimum
-///    required version.
+///   required version.
 /// * `None` - If the current Rustc version cannot be determined.
 ///
 /// # Errors
@@ -44,11 +68,115 @@
 /// }
 /// ```
 fn main() {
-    let min_version = "1.56";
+    let min_version = env::var("CARGO_PKG_RUST_VERSION")
+        .ok()
+        .filter(|version| !version.is_empty())
+        .unwrap_or_else(|| DEFAULT_MIN_VERSION.to_string());
 
-    if version_check::is_min_version(min_version) == Some(true) {
-    } else {
-        eprintln!("'fd' requires Rustc version >= {}", min_version);
+    match version_check::is_min_version(&min_version) {
+        Some(true) => {}
+        Some(false) => {
+            eprintln!(
+                "'html-generator' requires Rustc version >= {}",
+                min_version
+            );
+            process::exit(1);
+        }
+        None => {
+            eprintln!("Unable to determine Rustc version");
+            process::exit(1);
+        }
+    }
+
+    let rustc_version = version_check::Version::read()
+        .map(|version| version.to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+    println!(
+        "cargo:rustc-env=HTML_GENERATOR_RUSTC_VERSION={}",
+        rustc_version
+    );
+    println!("cargo:rustc-env=HTML_GENERATOR_MSRV={}", min_version);
+
+    let manifest = env::var_os("CARGO_MANIFEST_DIR")
+        .map(|dir| PathBuf::from(dir).join("Cargo.toml"))
+        .and_then(|path| fs::read_to_string(path).ok())
+        .unwrap_or_default();
+    for (name, variable) in TRACKED_DEPENDENCIES {
+        let version = section(&manifest, "dependencies")
+            .find_map(|(key, value)| {
+                (key == *name).then(|| declared_version(value))
+            })
+            .flatten()
+            .unwrap_or_else(|| "unknown".to_string());
+        println!("cargo:rustc-env={}={}", variable, version);
+    }
+
+    let mut features: Vec<&str> = section(&manifest, "features")
+        .map(|(key, _)| key)
+        .filter(|feature| {
+            let variable = format!(
+                "CARGO_FEATURE_{}",
+                feature.to_uppercase().replace('-', "_")
+            );
+            env::var_os(variable).is_some()
+        })
+        .collect();
+    features.sort_unstable();
+    let list = features
+        .iter()
+        .map(|feature| format!("{:?}", feature))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let out_dir =
+        PathBuf::from(env::var_os("OUT_DIR").unwrap_or_default());
+    if let Err(error) =
+        fs::write(out_dir.join(FEATURES_FILE), format!("&[{}]\n", list))
+    {
+        eprintln!("Unable to write the feature list: {}", error);
         process::exit(1);
     }
+
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=Cargo.toml");
+}
+
+/// Returns the `key = value` lines of a section of a TOML manifest.
+///
+/// Only the lines starting with a key are returned, which is enough for
+/// the flat `[dependencies]` and `[features]` tables of `Cargo.toml`.
+fn section<'a>(
+    manifest: &'a str,
+    name: &'a str,
+) -> impl Iterator<Item = (&'a str, &'a str)> {
+    let header = format!("[{}]", name);
+    manifest
+        .lines()
+        .map(str::trim)
+        .skip_while(move |line| *line != header)
+        .skip(1)
+        .take_while(|line| !line.starts_with('['))
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| {
+            let (key, value) = line.split_once('=')?;
+            Some((key.trim(), value.trim()))
+        })
+}
+
+/// Extracts the version requirement from a dependency declaration,
+/// written either as `"1.0"` or as `{ version = "1.0", ... }`.
+fn declared_version(value: &str) -> Option<String> {
+    let value = match value.strip_prefix('{') {
+        Some(table) => table
+            .split(',')
+            .filter_map(|field| field.split_once('='))
+            .find(|(key, _)| key.trim() == "version")?
+            .1
+            .trim(),
+        None => value,
+    };
+    value
+        .strip_prefix('"')?
+        .split('"')
+        .next()
+        .map(str::to_string)
 }
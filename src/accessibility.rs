@@ -47,19 +47,44 @@
 //! }
 //! ```
 
+use crate::patterns::accessibility::{
+    ANY_ELEMENT_SELECTOR, ANY_IMAGE_SELECTOR, ARIA_SELECTOR,
+    ATTRIBUTE_REGEX, BANNER_SELECTOR, BODY_SELECTOR, BUTTON_SELECTOR,
+    CONTENTINFO_SELECTOR, FORM_SELECTOR, HEADING_SELECTOR,
+    HTML_ELEMENT_SELECTOR, IMAGE_SELECTOR, INPUT_REGEX,
+    LABELED_BUTTON_SELECTOR, LABELED_NAV_SELECTOR, LANG_SELECTOR,
+    LINK_SELECTOR, MAIN_LANDMARK_SELECTOR, MATHML_SELECTOR,
+    MATH_FORMULA_SELECTOR, NAV_SELECTOR, OPENING_TAG_REGEX,
+    SHORTHAND_ATTRIBUTE_REGEX, TEXT_BLOCK_SELECTOR,
+};
 use crate::{
     accessibility::utils::{
         get_missing_required_aria_properties, is_valid_aria_role,
         is_valid_language_code,
     },
+    diagnostics::Diagnostic,
     emojis::load_emoji_sequences,
+    explanations::ExplanationCatalog,
+    footnotes::add_footnote_roles,
+    hydration::{hydration_attributes, Widget, WIDGET_ATTRIBUTE},
+    regexes::build_regex,
+    serializer,
+    utils::{decode_entities, generate_id, glob_match},
 };
+use memchr::memchr;
 use once_cell::sync::Lazy;
 use regex::Regex;
-use scraper::{CaseSensitivity, ElementRef, Html, Selector};
-use std::collections::{HashMap, HashSet};
+use scraper::{CaseSensitivity, ElementRef, Html, Node, Selector};
+use serde_json::{json, Value};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::path::PathBuf;
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
 use thiserror::Error;
 
+pub use crate::diagnostics::Severity;
+
 /// Constants used throughout the accessibility module
 pub mod constants {
     /// Maximum size of HTML input in bytes (1MB)
@@ -76,14 +101,34 @@ pub mod constants {
 
     /// Default ARIA role for inputs
     pub const DEFAULT_INPUT_ROLE: &str = "textbox";
+
+    /// Number of example issues kept per collapsed issue group
+    pub const MAX_GROUP_EXAMPLES: usize = 3;
+
+    /// Maximum number of threads used to validate a batch of documents
+    pub const MAX_BATCH_WORKERS: usize = 8;
+
+    /// Version of the SARIF format written by the `to_sarif` methods
+    pub const SARIF_VERSION: &str = "2.1.0";
+
+    /// JSON Schema of the SARIF format written by the `to_sarif` methods
+    pub const SARIF_SCHEMA: &str =
+        "https://json.schemastore.org/sarif-2.1.0.json";
 }
 
 /// Global counter for unique ID generation
 // static COUNTER: AtomicUsize = AtomicUsize::new(0);
-use constants::{DEFAULT_BUTTON_ROLE, DEFAULT_NAV_ROLE, MAX_HTML_SIZE};
+use constants::{
+    DEFAULT_BUTTON_ROLE, DEFAULT_NAV_ROLE, MAX_BATCH_WORKERS,
+    MAX_GROUP_EXAMPLES, MAX_HTML_SIZE, SARIF_SCHEMA, SARIF_VERSION,
+};
 
 /// WCAG Conformance Levels
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub enum WcagLevel {
     /// Level A: Minimum level of conformance
     /// Essential accessibility features that must be supported
@@ -99,7 +144,12 @@ pub enum WcagLevel {
 }
 
 /// Types of accessibility issues that can be detected
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum IssueType {
     /// Missing alternative text for images
     MissingAltText,
@@ -115,6 +165,260 @@ pub enum IssueType {
     KeyboardNavigation,
     /// Missing or invalid language declarations
     LanguageDeclaration,
+    /// Several headings of a page with the same text
+    DuplicateHeadings,
+    /// Links named by generic text or by their URL
+    LinkPurpose,
+    /// Text mixing right-to-left and left-to-right runs without
+    /// isolating either
+    MixedDirection,
+    /// Formulas without MathML or a readable `aria-label`
+    InaccessibleMath,
+}
+
+impl IssueType {
+    /// Every issue type, in declaration order.
+    pub const ALL: [IssueType; 11] = [
+        IssueType::MissingAltText,
+        IssueType::HeadingStructure,
+        IssueType::MissingLabels,
+        IssueType::InvalidAria,
+        IssueType::ColorContrast,
+        IssueType::KeyboardNavigation,
+        IssueType::LanguageDeclaration,
+        IssueType::DuplicateHeadings,
+        IssueType::LinkPurpose,
+        IssueType::MixedDirection,
+        IssueType::InaccessibleMath,
+    ];
+
+    /// Returns the stable code of the issue type, e.g.
+    /// `missing_alt_text`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            IssueType::MissingAltText => "missing_alt_text",
+            IssueType::HeadingStructure => "heading_structure",
+            IssueType::MissingLabels => "missing_labels",
+            IssueType::InvalidAria => "invalid_aria",
+            IssueType::ColorContrast => "color_contrast",
+            IssueType::KeyboardNavigation => "keyboard_navigation",
+            IssueType::LanguageDeclaration => "language_declaration",
+            IssueType::DuplicateHeadings => "duplicate_headings",
+            IssueType::LinkPurpose => "link_purpose",
+            IssueType::MixedDirection => "mixed_direction",
+            IssueType::InaccessibleMath => "inaccessible_math",
+        }
+    }
+
+    /// Returns the severity of issues of this type unless overridden by
+    /// [`AccessibilityConfig::severities`].
+    ///
+    /// Issues that block access for some users, such as missing labels
+    /// or keyboard traps, are errors; heading structure, contrast,
+    /// language, link text, text direction and math problems are
+    /// warnings.
+    pub fn default_severity(&self) -> Severity {
+        match self {
+            IssueType::MissingAltText
+            | IssueType::MissingLabels
+            | IssueType::InvalidAria
+            | IssueType::KeyboardNavigation => Severity::Error,
+            IssueType::HeadingStructure
+            | IssueType::ColorContrast
+            | IssueType::LanguageDeclaration
+            | IssueType::DuplicateHeadings
+            | IssueType::LinkPurpose
+            | IssueType::MixedDirection
+            | IssueType::InaccessibleMath => Severity::Warning,
+        }
+    }
+}
+
+/// Why an ARIA enhancement pass left an element unchanged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SkipReason {
+    /// The element already has an accessible name.
+    AlreadyLabeled,
+    /// The selector or pattern used to find elements failed to build.
+    SelectorFailed,
+    /// The enhanced element could not be substituted into the document.
+    ReplacementFailed,
+    /// The element repeats a landmark the page may only have once.
+    DuplicateLandmark,
+}
+
+impl SkipReason {
+    /// Returns the diagnostic code for this reason, e.g.
+    /// `aria_already_labeled`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            SkipReason::AlreadyLabeled => "aria_already_labeled",
+            SkipReason::SelectorFailed => "aria_selector_failed",
+            SkipReason::ReplacementFailed => "aria_replacement_failed",
+            SkipReason::DuplicateLandmark => "aria_duplicate_landmark",
+        }
+    }
+
+    /// Converts a skipped element into a [`Diagnostic`].
+    ///
+    /// Already-labeled elements are informational; the other reasons
+    /// are warnings because an enhancement was expected but not made.
+    ///
+    /// # Arguments
+    ///
+    /// * `pass` - The enhancement pass, e.g. `buttons`
+    /// * `element` - The element or selector that was skipped
+    pub fn to_diagnostic(
+        &self,
+        pass: &str,
+        element: &str,
+    ) -> Diagnostic {
+        let message = format!(
+            "{}: skipped {}: {}",
+            pass,
+            truncate_snippet(element),
+            self
+        );
+        match self {
+            SkipReason::AlreadyLabeled => {
+                Diagnostic::info(self.code(), message)
+            }
+            _ => Diagnostic::warning(self.code(), message),
+        }
+    }
+}
+
+impl std::fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SkipReason::AlreadyLabeled => {
+                write!(f, "element is already labeled")
+            }
+            SkipReason::SelectorFailed => {
+                write!(f, "selector could not be built")
+            }
+            SkipReason::ReplacementFailed => {
+                write!(f, "element could not be replaced")
+            }
+            SkipReason::DuplicateLandmark => {
+                write!(f, "landmark is already in the page")
+            }
+        }
+    }
+}
+
+/// Which custom attributes survive when an enhancement pass rewrites
+/// an element.
+///
+/// Passes such as tabs and accordions rebuild elements from scratch.
+/// Standard attributes like `class`, `type` or `href` are always carried
+/// over. Custom attributes are those whose name contains `-`, `:`, `@`
+/// or `.` (for example `hx-get`, `x-on:click` or `@click`) other than
+/// `aria-*` and standard names such as `http-equiv`; `data-*` attributes
+/// are controlled separately.
+///
+/// # Examples
+///
+/// ```
+/// use html_generator::accessibility::AttributePolicy;
+///
+/// let policy = AttributePolicy {
+///     preserve_data: true,
+///     preserve: &["hx-*"],
+/// };
+/// assert!(policy.allows("data-track-id"));
+/// assert!(policy.allows("hx-get"));
+/// assert!(!policy.allows("x-on:click"));
+/// assert!(policy.allows("class"));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
+pub struct AttributePolicy {
+    /// Keep `data-*` attributes
+    pub preserve_data: bool,
+    /// Patterns of the other custom attributes to keep, where `*`
+    /// matches any run of characters; not deserialized, as the
+    /// patterns are borrowed for the whole program
+    #[cfg_attr(feature = "serde", serde(skip_deserializing))]
+    pub preserve: &'static [&'static str],
+}
+
+impl Default for AttributePolicy {
+    /// Preserves every attribute.
+    fn default() -> Self {
+        Self {
+            preserve_data: true,
+            preserve: &["*"],
+        }
+    }
+}
+
+impl AttributePolicy {
+    /// Returns `true` if an attribute named `name` may be carried over.
+    pub fn allows(&self, name: &str) -> bool {
+        let name = name.to_ascii_lowercase();
+        if name.starts_with("data-") {
+            return self.preserve_data;
+        }
+        let is_custom = name.contains(['-', ':', '@', '.'])
+            && !name.starts_with("aria-")
+            && !matches!(
+                name.as_str(),
+                "accept-charset" | "http-equiv"
+            );
+        !is_custom
+            || self
+                .preserve
+                .iter()
+                .any(|pattern| glob_match(pattern, &name))
+    }
+}
+
+/// Formats an attribute, escaping its value for a double-quoted string.
+fn format_attribute(name: &str, value: &str) -> String {
+    format!(
+        r#"{}="{}""#,
+        name,
+        value.replace('&', "&amp;").replace('"', "&quot;")
+    )
+}
+
+/// Formats the attributes of `element` that `policy` allows, skipping
+/// those matching the `managed` patterns that the pass sets itself.
+///
+/// Each attribute is preceded by a space.
+fn carried_attributes(
+    element: &ElementRef<'_>,
+    policy: &AttributePolicy,
+    managed: &[&str],
+) -> String {
+    element
+        .value()
+        .attrs()
+        .filter(|(name, _)| {
+            policy.allows(name)
+                && !managed
+                    .iter()
+                    .any(|pattern| glob_match(pattern, name))
+        })
+        .map(|(name, value)| {
+            format!(" {}", format_attribute(name, value))
+        })
+        .collect()
+}
+
+/// Shortens an element snippet for use in diagnostics.
+fn truncate_snippet(element: &str) -> String {
+    const MAX_SNIPPET_CHARS: usize = 80;
+    let element = element.trim();
+    match element.char_indices().nth(MAX_SNIPPET_CHARS) {
+        Some((end, _)) => format!("{}...", &element[..end]),
+        None => element.to_string(),
+    }
 }
 
 /// Enum to represent possible accessibility-related errors.
@@ -160,6 +464,20 @@ pub enum Error {
         source: Option<Box<dyn std::error::Error + Send + Sync>>,
     },
 
+    /// Error indicating that an enhancement could not be applied.
+    ///
+    /// Only returned when
+    /// [`AccessibilityConfig::strict_transforms`] is enabled.
+    #[error(
+        "ARIA transform '{pass}' failed to replace element: {element}"
+    )]
+    TransformFailed {
+        /// The enhancement pass, e.g. `buttons`
+        pass: String,
+        /// The element snippet that could not be replaced
+        element: String,
+    },
+
     /// Error indicating malformed HTML input.
     #[error("Malformed HTML: {message}")]
     MalformedHtml {
@@ -175,9 +493,15 @@ pub enum Error {
 
 /// Structure representing an accessibility issue found in the HTML
 #[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub struct Issue {
     /// Type of accessibility issue
     pub issue_type: IssueType,
+    /// How serious the issue is
+    pub severity: Severity,
     /// Description of the issue
     pub message: String,
     /// WCAG guideline reference, if applicable
@@ -186,10 +510,91 @@ pub struct Issue {
     pub element: Option<String>,
     /// Suggested fix for the issue
     pub suggestion: Option<String>,
+    /// Position of the start tag of the element in the validated HTML,
+    /// if it appears there rather than being implied by the parser
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub location: Option<SourceLocation>,
+}
+
+/// A position in a source document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct SourceLocation {
+    /// Line, starting at 1
+    pub line: usize,
+    /// Column in characters, starting at 1
+    pub column: usize,
+    /// Byte offset from the start of the document
+    pub offset: usize,
+}
+
+impl SourceLocation {
+    /// Returns the location of the byte `offset` of `source`.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - The document
+    /// * `offset` - A byte offset on a character boundary of `source`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use html_generator::accessibility::SourceLocation;
+    ///
+    /// let location = SourceLocation::from_offset("<p>\n  <img>", 6);
+    /// assert_eq!((location.line, location.column), (2, 3));
+    /// ```
+    pub fn from_offset(source: &str, offset: usize) -> Self {
+        let before = &source[..offset.min(source.len())];
+        let line_start =
+            before.rfind('\n').map_or(0, |index| index + 1);
+        SourceLocation {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            offset,
+        }
+    }
+}
+
+impl std::fmt::Display for SourceLocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+impl Issue {
+    /// Returns a longer explanation of why the issue matters and how to
+    /// fix it, from the bundled [`ExplanationCatalog`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use html_generator::accessibility::{validate_wcag, AccessibilityConfig};
+    ///
+    /// let report = validate_wcag("<html><p>Hi</p></html>", &AccessibilityConfig::default(), None)?;
+    /// assert!(report.issues[0].explain().starts_with("Declare the language of the page"));
+    /// # Ok::<(), html_generator::accessibility::Error>(())
+    /// ```
+    pub fn explain(&self) -> String {
+        ExplanationCatalog::bundled().explain(self)
+    }
+
+    /// Returns a longer explanation of the issue from a custom catalog,
+    /// falling back to the bundled text for codes it does not define.
+    ///
+    /// # Arguments
+    ///
+    /// * `catalog` - The catalog with custom explanations
+    pub fn explain_with(&self, catalog: &ExplanationCatalog) -> String {
+        catalog.explain(self)
+    }
 }
 
 /// Helper function to create a `Selector`, returning an `Option` on failure.
-fn try_create_selector(selector: &str) -> Option<Selector> {
+pub(crate) fn try_create_selector(selector: &str) -> Option<Selector> {
     match Selector::parse(selector) {
         Ok(s) => Some(s),
         Err(e) => {
@@ -203,8 +608,8 @@ fn try_create_selector(selector: &str) -> Option<Selector> {
 }
 
 /// Helper function to create a `Regex`, returning an `Option` on failure.
-fn try_create_regex(pattern: &str) -> Option<Regex> {
-    match Regex::new(pattern) {
+pub(crate) fn try_create_regex(pattern: &str) -> Option<Regex> {
+    match build_regex(pattern) {
         Ok(r) => Some(r),
         Err(e) => {
             eprintln!("Failed to create regex '{}': {}", pattern, e);
@@ -213,35 +618,42 @@ fn try_create_regex(pattern: &str) -> Option<Regex> {
     }
 }
 
-/// Static selectors for HTML elements and ARIA attributes
-static BUTTON_SELECTOR: Lazy<Option<Selector>> =
-    Lazy::new(|| try_create_selector("button:not([aria-label])"));
-
-/// Selector for navigation elements without ARIA attributes
-static NAV_SELECTOR: Lazy<Option<Selector>> =
-    Lazy::new(|| try_create_selector("nav:not([aria-label])"));
-
-/// Selector for form elements without ARIA attributes
-static FORM_SELECTOR: Lazy<Option<Selector>> =
-    Lazy::new(|| try_create_selector("form:not([aria-labelledby])"));
-
-/// Regex for finding input elements
-static INPUT_REGEX: Lazy<Option<Regex>> =
-    Lazy::new(|| try_create_regex(r"<input[^>]*>"));
-
-/// Comprehensive selector for all ARIA attributes
-static ARIA_SELECTOR: Lazy<Option<Selector>> = Lazy::new(|| {
-    try_create_selector(concat!(
-        "[aria-label], [aria-labelledby], [aria-describedby], ",
-        "[aria-hidden], [aria-expanded], [aria-haspopup], ",
-        "[aria-controls], [aria-pressed], [aria-checked], ",
-        "[aria-current], [aria-disabled], [aria-dropeffect], ",
-        "[aria-grabbed], [aria-invalid], [aria-live], ",
-        "[aria-owns], [aria-relevant], [aria-required], ",
-        "[aria-role], [aria-selected], [aria-valuemax], ",
-        "[aria-valuemin], [aria-valuenow], [aria-valuetext]"
-    ))
-});
+/// Alt texts that do not describe the image
+const PLACEHOLDER_ALT_TEXTS: &[&str] = &[
+    "alt",
+    "blank",
+    "graphic",
+    "image",
+    "img",
+    "photo",
+    "picture",
+    "placeholder",
+    "spacer",
+    "todo",
+    "untitled",
+];
+
+/// File name extensions of images, whose names are not alt texts
+const IMAGE_EXTENSIONS: &[&str] =
+    &[".avif", ".gif", ".jpeg", ".jpg", ".png", ".svg", ".webp"];
+
+/// Link texts that do not describe the link target
+const GENERIC_LINK_TEXTS: &[&str] = &[
+    "click",
+    "click here",
+    "continue",
+    "details",
+    "go",
+    "here",
+    "learn more",
+    "link",
+    "more",
+    "more info",
+    "read more",
+    "see more",
+    "this",
+    "this link",
+];
 
 /// Set of valid ARIA attributes
 static VALID_ARIA_ATTRIBUTES: Lazy<HashSet<&'static str>> =
@@ -250,6 +662,7 @@ fn try_create_regex(pattern: &str) -> Option<Regex> {
             "aria-label",
             "aria-labelledby",
             "aria-describedby",
+            "aria-details",
             "aria-hidden",
             "aria-expanded",
             "aria-haspopup",
@@ -262,6 +675,7 @@ fn try_create_regex(pattern: &str) -> Option<Regex> {
             "aria-grabbed",
             "aria-invalid",
             "aria-live",
+            "aria-modal",
             "aria-owns",
             "aria-relevant",
             "aria-required",
@@ -306,6 +720,11 @@ fn try_create_regex(pattern: &str) -> Option<Regex> {
 // });
 /// Configuration for accessibility validation
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
 pub struct AccessibilityConfig {
     /// WCAG conformance level to validate against
     pub wcag_level: WcagLevel,
@@ -315,6 +734,21 @@ pub struct AccessibilityConfig {
     pub min_contrast_ratio: f64,
     /// Whether to automatically fix issues when possible
     pub auto_fix: bool,
+    /// Fail when an ARIA enhancement cannot be applied to the document
+    /// instead of skipping the element
+    pub strict_transforms: bool,
+    /// Mark enhanced tabs, accordions and modals with `data-hg-widget`
+    /// and `data-hg-config` attributes for client-side hydration
+    pub hydration_attributes: bool,
+    /// Custom attributes kept when enhancement passes rewrite elements
+    pub attribute_policy: AttributePolicy,
+    /// Severity overrides for issue types, e.g.
+    /// `&[(IssueType::HeadingStructure, Severity::Error)]`; not
+    /// deserialized, as the overrides are borrowed for the whole program
+    #[cfg_attr(feature = "serde", serde(skip_deserializing))]
+    pub severities: &'static [(IssueType, Severity)],
+    /// How the elements of a validated document are counted
+    pub element_accounting: ElementAccounting,
 }
 
 impl Default for AccessibilityConfig {
@@ -324,34 +758,386 @@ fn default() -> Self {
             max_heading_jump: 1,
             min_contrast_ratio: 4.5, // WCAG AA standard
             auto_fix: true,
+            strict_transforms: false,
+            hydration_attributes: false,
+            attribute_policy: AttributePolicy::default(),
+            severities: &[],
+            element_accounting: ElementAccounting::AllElements,
+        }
+    }
+}
+
+impl AccessibilityConfig {
+    /// Returns the severity of issues of the given type, taking
+    /// [`severities`](Self::severities) overrides into account.
+    ///
+    /// # Arguments
+    ///
+    /// * `issue_type` - The type of issue
+    pub fn severity(&self, issue_type: IssueType) -> Severity {
+        self.severities
+            .iter()
+            .find(|(overridden, _)| *overridden == issue_type)
+            .map_or_else(
+                || issue_type.default_severity(),
+                |(_, severity)| *severity,
+            )
+    }
+}
+
+/// How [`AccessibilityReport::elements_checked`] is counted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum ElementAccounting {
+    /// Every element of the document
+    AllElements,
+    /// Only the elements examined by at least one check that ran
+    Examined,
+}
+
+impl Default for ElementAccounting {
+    fn default() -> Self {
+        ElementAccounting::AllElements
+    }
+}
+
+/// Whether a check took part in a validation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum CheckStatus {
+    /// The check ran
+    Ran,
+    /// The check was disabled by the caller
+    Disabled,
+    /// The check was skipped because its selector failed to build
+    SelectorUnavailable,
+    /// [`validate_wcag`] does not implement the check yet
+    Unsupported,
+}
+
+impl CheckStatus {
+    /// Returns the stable code of the status, e.g.
+    /// `selector_unavailable`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            CheckStatus::Ran => "ran",
+            CheckStatus::Disabled => "disabled",
+            CheckStatus::SelectorUnavailable => "selector_unavailable",
+            CheckStatus::Unsupported => "unsupported",
         }
     }
 }
 
+/// Coverage of one check in an [`AccessibilityReport`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct CheckCoverage {
+    /// The check, identified by the issues it reports
+    pub issue_type: IssueType,
+    /// Whether the check ran
+    pub status: CheckStatus,
+    /// Number of distinct elements the check examined
+    pub elements_examined: usize,
+}
+
 /// A comprehensive accessibility check result
 #[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub struct AccessibilityReport {
     /// List of accessibility issues found
     pub issues: Vec<Issue>,
     /// WCAG conformance level checked
     pub wcag_level: WcagLevel,
-    /// Total number of elements checked
+    /// Number of elements checked, counted as configured by
+    /// [`AccessibilityConfig::element_accounting`]
     pub elements_checked: usize,
+    /// Number of elements in the document
+    pub elements_total: usize,
+    /// Number of distinct elements examined by at least one check
+    pub elements_examined: usize,
+    /// Per-check coverage, one entry per [`IssueType`]
+    pub coverage: Vec<CheckCoverage>,
     /// Number of issues found
     pub issue_count: usize,
     /// Time taken for the check (in milliseconds)
     pub check_duration_ms: u64,
 }
 
+impl AccessibilityReport {
+    /// Returns the share of the document's elements examined by at
+    /// least one check, from `0.0` to `1.0`.
+    ///
+    /// An empty document counts as fully audited.
+    pub fn audited_fraction(&self) -> f64 {
+        if self.elements_total == 0 {
+            return 1.0;
+        }
+        self.elements_examined as f64 / self.elements_total as f64
+    }
+
+    /// Returns the coverage of a check, if it is part of the report.
+    ///
+    /// # Arguments
+    ///
+    /// * `issue_type` - The check
+    pub fn check_coverage(
+        &self,
+        issue_type: IssueType,
+    ) -> Option<&CheckCoverage> {
+        self.coverage
+            .iter()
+            .find(|coverage| coverage.issue_type == issue_type)
+    }
+
+    /// Returns the issues with the given severity.
+    ///
+    /// # Arguments
+    ///
+    /// * `severity` - The severity to keep
+    pub fn by_severity(
+        &self,
+        severity: Severity,
+    ) -> impl Iterator<Item = &Issue> {
+        self.issues
+            .iter()
+            .filter(move |issue| issue.severity == severity)
+    }
+
+    /// Returns the issues with [`Severity::Error`].
+    pub fn errors(&self) -> impl Iterator<Item = &Issue> {
+        self.by_severity(Severity::Error)
+    }
+
+    /// Returns the issues with [`Severity::Warning`].
+    pub fn warnings(&self) -> impl Iterator<Item = &Issue> {
+        self.by_severity(Severity::Warning)
+    }
+
+    /// Returns `true` if any issue is an error, so that CI gates can
+    /// fail on errors only.
+    pub fn has_errors(&self) -> bool {
+        self.errors().next().is_some()
+    }
+
+    /// Returns the report as JSON, with the field names of the report
+    /// and the codes of issue types, severities and check statuses.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use html_generator::accessibility::{validate_wcag, AccessibilityConfig};
+    ///
+    /// let report = validate_wcag("<html><p>Hi</p></html>", &AccessibilityConfig::default(), None)?;
+    /// let json = report.to_json();
+    /// assert_eq!(json["wcag_level"], "AA");
+    /// assert_eq!(json["issues"][0]["issue_type"], "language_declaration");
+    /// # Ok::<(), html_generator::accessibility::Error>(())
+    /// ```
+    pub fn to_json(&self) -> Value {
+        let issues: Vec<Value> = self
+            .issues
+            .iter()
+            .map(|issue| {
+                json!({
+                    "issue_type": issue.issue_type.code(),
+                    "severity": issue.severity.to_string(),
+                    "message": issue.message,
+                    "guideline": issue.guideline,
+                    "element": issue.element,
+                    "suggestion": issue.suggestion,
+                    "location": issue.location.map(|location| json!({
+                        "line": location.line,
+                        "column": location.column,
+                        "offset": location.offset,
+                    })),
+                })
+            })
+            .collect();
+        let coverage: Vec<Value> = self
+            .coverage
+            .iter()
+            .map(|coverage| {
+                json!({
+                    "issue_type": coverage.issue_type.code(),
+                    "status": coverage.status.code(),
+                    "elements_examined": coverage.elements_examined,
+                })
+            })
+            .collect();
+        json!({
+            "issues": issues,
+            "wcag_level": self.wcag_level.to_string(),
+            "elements_checked": self.elements_checked,
+            "elements_total": self.elements_total,
+            "elements_examined": self.elements_examined,
+            "coverage": coverage,
+            "issue_count": self.issue_count,
+            "check_duration_ms": self.check_duration_ms,
+        })
+    }
+
+    /// Returns the issues as a [SARIF] log, the format read by GitHub
+    /// code scanning and other CI systems, attributing them to
+    /// `artifact_uri`.
+    ///
+    /// Every issue type is a rule, described by the bundled
+    /// [`ExplanationCatalog`]; the element, guideline and suggestion of
+    /// an issue are kept in the `properties` of its result. Issues with a
+    /// [`location`](Issue::location) are narrowed to a region at its line
+    /// and column.
+    ///
+    /// [SARIF]: https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html
+    ///
+    /// # Arguments
+    ///
+    /// * `artifact_uri` - The validated file, relative to the
+    ///   repository root for code scanning
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use html_generator::accessibility::{validate_wcag, AccessibilityConfig};
+    ///
+    /// let report = validate_wcag("<html><p>Hi</p></html>", &AccessibilityConfig::default(), None)?;
+    /// let sarif = report.to_sarif("site/index.html");
+    /// let result = &sarif["runs"][0]["results"][0];
+    /// assert_eq!(result["ruleId"], "language_declaration");
+    /// assert_eq!(
+    ///     result["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+    ///     "site/index.html"
+    /// );
+    /// # Ok::<(), html_generator::accessibility::Error>(())
+    /// ```
+    pub fn to_sarif(&self, artifact_uri: &str) -> Value {
+        sarif_log(sarif_results(artifact_uri, self), Vec::new())
+    }
+
+    /// Groups the issues by WCAG guideline, e.g. `WCAG 2.4.6`.
+    ///
+    /// Issues without a guideline are grouped under `None`.
+    pub fn by_guideline(&self) -> BTreeMap<Option<&str>, Vec<&Issue>> {
+        let mut groups: BTreeMap<Option<&str>, Vec<&Issue>> =
+            BTreeMap::new();
+        for issue in &self.issues {
+            groups
+                .entry(issue.guideline.as_deref())
+                .or_default()
+                .push(issue);
+        }
+        groups
+    }
+
+    /// Groups identical issues, i.e. those with the same type and the
+    /// same suggestion.
+    ///
+    /// Large pages often repeat one issue hundreds of times, such as
+    /// images without alternative text. Each group counts its
+    /// occurrences and keeps up to [`MAX_GROUP_EXAMPLES`] of them as
+    /// examples, or all of them if `expand` is set.
+    ///
+    /// # Arguments
+    ///
+    /// * `expand` - Keep every occurrence instead of a few examples
+    ///
+    /// # Returns
+    ///
+    /// The groups, in order of their first occurrence.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use html_generator::accessibility::{validate_wcag, AccessibilityConfig};
+    ///
+    /// let html = r#"<html lang="en"><h1>A</h1><h3>B</h3><h1>C</h1><h4>D</h4></html>"#;
+    /// let report = validate_wcag(html, &AccessibilityConfig::default(), None)?;
+    /// let groups = report.grouped(false);
+    /// assert_eq!(groups.len(), 1);
+    /// assert_eq!(groups[0].count, 2);
+    /// assert_eq!(groups[0].to_string(), "warning[heading_structure]: Skipped heading level from h1 to h3 (2 occurrences)");
+    /// # Ok::<(), html_generator::accessibility::Error>(())
+    /// ```
+    pub fn grouped(&self, expand: bool) -> Vec<IssueGroup<'_>> {
+        let mut groups: Vec<IssueGroup<'_>> = Vec::new();
+        for issue in &self.issues {
+            let existing = groups.iter_mut().find(|group| {
+                group.issue_type == issue.issue_type
+                    && group.suggestion == issue.suggestion.as_deref()
+            });
+            match existing {
+                Some(group) => {
+                    group.count += 1;
+                    if expand
+                        || group.examples.len() < MAX_GROUP_EXAMPLES
+                    {
+                        group.examples.push(issue);
+                    }
+                }
+                None => groups.push(IssueGroup {
+                    issue_type: issue.issue_type,
+                    severity: issue.severity,
+                    suggestion: issue.suggestion.as_deref(),
+                    count: 1,
+                    examples: vec![issue],
+                }),
+            }
+        }
+        groups
+    }
+}
+
+/// Identical issues grouped by [`AccessibilityReport::grouped`].
+#[derive(Debug, Clone)]
+pub struct IssueGroup<'a> {
+    /// Type of the grouped issues
+    pub issue_type: IssueType,
+    /// Severity of the grouped issues
+    pub severity: Severity,
+    /// Suggested fix shared by the grouped issues
+    pub suggestion: Option<&'a str>,
+    /// Number of occurrences
+    pub count: usize,
+    /// The first occurrences, or all of them if expanded
+    pub examples: Vec<&'a Issue>,
+}
+
+impl std::fmt::Display for IssueGroup<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}[{}]: ", self.severity, self.issue_type.code())?;
+        if let Some(example) = self.examples.first() {
+            write!(f, "{} ", example.message)?;
+        }
+        match self.count {
+            1 => write!(f, "(1 occurrence)"),
+            count => write!(f, "({} occurrences)", count),
+        }
+    }
+}
+
 /// Add ARIA attributes to HTML for improved accessibility.
 ///
 /// This function performs a comprehensive analysis of the HTML content and adds
 /// appropriate ARIA attributes to improve accessibility. It handles:
 /// - Button labeling
 /// - Navigation landmarks
+/// - Main, banner and contentinfo landmarks (at WCAG AA and AAA)
 /// - Form controls
 /// - Input elements
 /// - Dynamic content
+/// - Footnote references and back-links
 ///
 /// # Arguments
 ///
@@ -372,6 +1158,46 @@ pub fn add_aria_attributes(
     html: &str,
     config: Option<AccessibilityConfig>,
 ) -> Result<String> {
+    add_aria_attributes_with_diagnostics(html, config)
+        .map(|(html, _)| html)
+}
+
+/// Add ARIA attributes to HTML and report the elements left unchanged.
+///
+/// Behaves like [`add_aria_attributes`], and additionally returns a
+/// [`Diagnostic`] for every element a pass skipped, with a
+/// [`SkipReason`] code explaining why: the element was already labeled,
+/// a selector failed to build, or the enhanced element could not be
+/// substituted into the document.
+///
+/// # Arguments
+///
+/// * `html` - A string slice representing the HTML content
+/// * `config` - Optional configuration for the enhancement process
+///
+/// # Returns
+///
+/// * `Result<(String, Vec<Diagnostic>)>` - The modified HTML and the
+///   skip diagnostics
+///
+/// # Errors
+///
+/// Returns the same errors as [`add_aria_attributes`].
+///
+/// # Examples
+///
+/// ```
+/// use html_generator::accessibility::add_aria_attributes_with_diagnostics;
+///
+/// let html = r#"<button aria-label="Close">X</button>"#;
+/// let (_, diagnostics) = add_aria_attributes_with_diagnostics(html, None)?;
+/// assert_eq!(diagnostics[0].code, "aria_already_labeled");
+/// # Ok::<(), html_generator::accessibility::Error>(())
+/// ```
+pub fn add_aria_attributes_with_diagnostics(
+    html: &str,
+    config: Option<AccessibilityConfig>,
+) -> Result<(String, Vec<Diagnostic>)> {
     let config = config.unwrap_or_default();
 
     if html.len() > MAX_HTML_SIZE {
@@ -382,6 +1208,9 @@ pub fn add_aria_attributes(
     }
 
     let mut html_builder = HtmlBuilder::new(html);
+    html_builder.strict = config.strict_transforms;
+    html_builder.hydrate = config.hydration_attributes;
+    html_builder.attributes = config.attribute_policy;
 
     // Apply transformations
     html_builder = add_aria_to_accordions(html_builder)?;
@@ -393,6 +1222,7 @@ pub fn add_aria_attributes(
     html_builder = add_aria_to_tabs(html_builder)?;
     html_builder = add_aria_to_toggle(html_builder)?;
     html_builder = add_aria_to_tooltips(html_builder)?;
+    html_builder.content = add_footnote_roles(&html_builder.content);
 
     // Additional transformations for stricter WCAG levels
     if matches!(config.wcag_level, WcagLevel::AA | WcagLevel::AAA) {
@@ -405,6 +1235,7 @@ pub fn add_aria_attributes(
     }
 
     // Validate and clean up
+    let diagnostics = std::mem::take(&mut html_builder.diagnostics);
     let new_html =
         remove_invalid_aria_attributes(&html_builder.build());
 
@@ -415,13 +1246,21 @@ pub fn add_aria_attributes(
         });
     }
 
-    Ok(new_html)
+    Ok((new_html, diagnostics))
 }
 
 /// A builder struct for constructing HTML content.
 #[derive(Debug, Clone)]
 struct HtmlBuilder {
     content: String,
+    /// Elements skipped by the enhancement passes.
+    diagnostics: Vec<Diagnostic>,
+    /// Fail on replacements that do not apply instead of skipping them.
+    strict: bool,
+    /// Add hydration attributes to enhanced widgets.
+    hydrate: bool,
+    /// Custom attributes kept on rewritten elements.
+    attributes: AttributePolicy,
 }
 
 impl HtmlBuilder {
@@ -429,6 +1268,83 @@ impl HtmlBuilder {
     fn new(initial_content: &str) -> Self {
         HtmlBuilder {
             content: initial_content.to_string(),
+            diagnostics: Vec::new(),
+            strict: false,
+            hydrate: false,
+            attributes: AttributePolicy::default(),
+        }
+    }
+
+    /// Records that `pass` left `element` unchanged.
+    fn skip(&mut self, pass: &str, reason: SkipReason, element: &str) {
+        self.diagnostics.push(reason.to_diagnostic(pass, element));
+    }
+
+    /// Handles a replacement of `element` that did not apply.
+    ///
+    /// Records a skip, or fails with [`Error::TransformFailed`] in
+    /// strict mode.
+    fn replacement_failed(
+        &mut self,
+        pass: &str,
+        element: &str,
+    ) -> Result<()> {
+        if self.strict {
+            return Err(Error::TransformFailed {
+                pass: pass.to_string(),
+                element: element.to_string(),
+            });
+        }
+        self.skip(pass, SkipReason::ReplacementFailed, element);
+        Ok(())
+    }
+
+    /// Replaces the first occurrence of `old` using
+    /// [`replace_html_element_resilient`].
+    ///
+    /// # Errors
+    ///
+    /// Fails in strict mode if nothing was replaced.
+    fn replace_element(
+        &mut self,
+        pass: &str,
+        old: &str,
+        new: &str,
+    ) -> Result<()> {
+        let replaced =
+            replace_html_element_resilient(&self.content, old, new);
+        if replaced == self.content && old != new {
+            return self.replacement_failed(pass, old);
+        }
+        self.content = replaced;
+        Ok(())
+    }
+
+    /// Replaces every occurrence of `old`.
+    ///
+    /// # Errors
+    ///
+    /// Fails in strict mode if `old` does not occur.
+    fn replace_all(
+        &mut self,
+        pass: &str,
+        old: &str,
+        new: &str,
+    ) -> Result<()> {
+        if !self.content.contains(old) {
+            return self.replacement_failed(pass, old);
+        }
+        self.content = self.content.replace(old, new);
+        Ok(())
+    }
+
+    /// Returns the hydration attributes for `widget`, preceded by a
+    /// space, or an empty string if hydration is disabled.
+    fn hydration(&self, widget: Widget, config: Value) -> String {
+        if self.hydrate {
+            format!(" {}", hydration_attributes(widget, &config))
+        } else {
+            String::new()
         }
     }
 
@@ -438,48 +1354,435 @@ fn build(self) -> String {
     }
 }
 
+/// Returns the elements a check of [`validate_wcag`] examines.
+fn examined_elements(
+    document: &Html,
+    issue_type: IssueType,
+) -> (CheckStatus, Vec<ElementRef<'_>>) {
+    let selectors: &[&Lazy<Option<Selector>>] = match issue_type {
+        IssueType::LanguageDeclaration => {
+            &[&HTML_ELEMENT_SELECTOR, &LANG_SELECTOR]
+        }
+        IssueType::HeadingStructure | IssueType::DuplicateHeadings => {
+            &[&HEADING_SELECTOR]
+        }
+        IssueType::LinkPurpose => &[&LINK_SELECTOR],
+        IssueType::MissingAltText => &[&ANY_IMAGE_SELECTOR],
+        IssueType::MixedDirection => &[&TEXT_BLOCK_SELECTOR],
+        IssueType::InaccessibleMath => &[&MATH_FORMULA_SELECTOR],
+        _ => return (CheckStatus::Unsupported, Vec::new()),
+    };
+    let mut elements = Vec::new();
+    for selector in selectors {
+        match selector.as_ref() {
+            Some(selector) => {
+                elements.extend(document.select(selector))
+            }
+            None => {
+                return (CheckStatus::SelectorUnavailable, Vec::new())
+            }
+        }
+    }
+    (CheckStatus::Ran, elements)
+}
+
 /// Helper function to count total elements checked during validation
 fn count_checked_elements(document: &Html) -> usize {
-    document.select(&Selector::parse("*").unwrap()).count()
+    ANY_ELEMENT_SELECTOR
+        .as_ref()
+        .map_or(0, |selector| document.select(selector).count())
 }
 
-/// Add landmark regions to improve navigation
-const fn enhance_landmarks(
-    html_builder: HtmlBuilder,
+/// Add landmark regions to improve navigation.
+///
+/// Wraps the content of the body of a document in `main`, leaving out
+/// its leading header and navigation and its trailing footer, unless
+/// the page has a main landmark. Fragments, without a `<body>` tag, are
+/// not wrapped. The page header and footer get the `banner` and
+/// `contentinfo` roles, landmarks a page may only have once are
+/// reported when repeated, and navigation landmarks sharing a label
+/// are numbered so that each has a distinct one.
+fn enhance_landmarks(
+    mut html_builder: HtmlBuilder,
 ) -> Result<HtmlBuilder> {
-    // Implementation for adding landmarks
-    Ok(html_builder)
-}
+    wrap_main_content(&mut html_builder)?;
+
+    let content = html_builder.content.clone();
+    let document = Html::parse_document(&content);
+    let source = SourceMap::new(&content, &document);
+    let mut edits = Vec::new();
+
+    let landmarks = [
+        (&MAIN_LANDMARK_SELECTOR, "main", "main"),
+        (&BANNER_SELECTOR, "header", "banner"),
+        (&CONTENTINFO_SELECTOR, "footer", "contentinfo"),
+    ];
+    for (selector, name, role) in landmarks {
+        let selector = match selector.as_ref() {
+            Some(selector) => selector,
+            None => {
+                html_builder.skip(
+                    "landmarks",
+                    SkipReason::SelectorFailed,
+                    name,
+                );
+                continue;
+            }
+        };
+        let mut found = false;
+        for element in document
+            .select(selector)
+            .filter(|element| is_page_landmark(element, role))
+        {
+            if found {
+                html_builder.skip(
+                    "landmarks",
+                    SkipReason::DuplicateLandmark,
+                    &element.html(),
+                );
+                continue;
+            }
+            found = true;
+            if name == role || element.value().attr("role").is_some() {
+                continue;
+            }
+            match source.offset(&element) {
+                Some(offset) => edits.push((
+                    offset + 1 + name.len(),
+                    offset + 1 + name.len(),
+                    format!(r#" role="{}""#, role),
+                )),
+                None => html_builder
+                    .replacement_failed("landmarks", &element.html())?,
+            }
+        }
+    }
 
-/// Add live regions for dynamic content
-const fn add_live_regions(
-    html_builder: HtmlBuilder,
-) -> Result<HtmlBuilder> {
-    // Implementation for adding live regions
-    Ok(html_builder)
-}
+    label_navs(&document, &source, &mut html_builder, &mut edits)?;
 
-/// Enhance element descriptions for better accessibility
-const fn enhance_descriptions(
-    html_builder: HtmlBuilder,
-) -> Result<HtmlBuilder> {
-    // Implementation for enhancing descriptions
+    edits.sort_by_key(|edit| std::cmp::Reverse(edit.0));
+    for (start, end, replacement) in edits {
+        html_builder.content.replace_range(start..end, &replacement);
+    }
     Ok(html_builder)
 }
 
-/// Check heading structure
-fn check_heading_structure(document: &Html, issues: &mut Vec<Issue>) {
-    let mut prev_level: Option<u8> = None;
-
-    let selector = match Selector::parse("h1, h2, h3, h4, h5, h6") {
-        Ok(selector) => selector,
-        Err(e) => {
-            eprintln!("Failed to parse selector: {}", e);
-            return; // Skip checking if the selector is invalid
+/// Elements whose `header` and `footer` descendants are not landmarks
+/// of the page.
+const SECTIONING_ELEMENTS: &[&str] =
+    &["article", "aside", "main", "nav", "section"];
+
+/// Returns `true` if `element` is a landmark of the whole page with
+/// `role`: a `main` element or an element with the role, or a `header`
+/// or `footer` outside sectioning elements.
+fn is_page_landmark(element: &ElementRef<'_>, role: &str) -> bool {
+    match element.value().attr("role") {
+        Some(explicit) => explicit.trim().eq_ignore_ascii_case(role),
+        None => {
+            element.value().name() == "main"
+                || !element
+                    .ancestors()
+                    .filter_map(ElementRef::wrap)
+                    .any(|ancestor| {
+                        SECTIONING_ELEMENTS
+                            .contains(&ancestor.value().name())
+                    })
         }
-    };
-
-    for heading in document.select(&selector) {
+    }
+}
+
+/// Wraps the content of the body of a document in `main`, between its
+/// leading header and navigation and its trailing footer and scripts.
+fn wrap_main_content(html_builder: &mut HtmlBuilder) -> Result<()> {
+    let document = Html::parse_document(&html_builder.content);
+    let has_main =
+        MAIN_LANDMARK_SELECTOR.as_ref().map_or(true, |selector| {
+            document.select(selector).next().is_some()
+        });
+    let body = match BODY_SELECTOR.as_ref() {
+        Some(selector) if !has_main => document.select(selector).next(),
+        _ => None,
+    };
+    let source = SourceMap::new(&html_builder.content, &document);
+    let body = match body {
+        Some(body) if source.offset(&body).is_some() => body,
+        _ => return Ok(()),
+    };
+
+    let is_element = |node: &ego_tree::NodeRef<'_, Node>,
+                      names: &[&str]| {
+        ElementRef::wrap(*node).map_or(false, |element| {
+            names.contains(&element.value().name())
+        })
+    };
+    let children: Vec<_> = body
+        .children()
+        .filter(|node| match node.value() {
+            Node::Text(text) => !text.trim().is_empty(),
+            Node::Comment(_) => false,
+            _ => true,
+        })
+        .collect();
+    let leading = children
+        .iter()
+        .take_while(|node| is_element(node, &["header", "nav"]))
+        .count();
+    let trailing = children[leading..]
+        .iter()
+        .rev()
+        .take_while(|node| {
+            is_element(node, &["footer", "script", "template"])
+        })
+        .count();
+    if leading + trailing == children.len() {
+        return Ok(());
+    }
+
+    let content = &html_builder.content;
+    let offset = |node: &ego_tree::NodeRef<'_, Node>| {
+        ElementRef::wrap(*node)
+            .and_then(|element| source.offset(&element))
+    };
+    let start = offset(&children[leading]);
+    let end = match children.get(children.len() - trailing) {
+        Some(node) => offset(node),
+        None => {
+            let lowercase = content.to_ascii_lowercase();
+            Some(
+                lowercase
+                    .rfind("</body")
+                    .or_else(|| lowercase.rfind("</html"))
+                    .unwrap_or(content.len()),
+            )
+        }
+    };
+    match (start, end) {
+        (Some(start), Some(end)) if start < end => {
+            let end = start + content[start..end].trim_end().len();
+            let mut wrapped = String::with_capacity(content.len() + 13);
+            wrapped.push_str(&content[..start]);
+            wrapped.push_str("<main>");
+            wrapped.push_str(&content[start..end]);
+            wrapped.push_str("</main>");
+            wrapped.push_str(&content[end..]);
+            html_builder.content = wrapped;
+            Ok(())
+        }
+        _ => html_builder.replacement_failed("landmarks", "body"),
+    }
+}
+
+/// Numbers the labels shared by several navigation landmarks, e.g.
+/// `navigation` and `navigation 2`.
+fn label_navs(
+    document: &Html,
+    source: &SourceMap<'_>,
+    html_builder: &mut HtmlBuilder,
+    edits: &mut Vec<(usize, usize, String)>,
+) -> Result<()> {
+    let selector = match LABELED_NAV_SELECTOR.as_ref() {
+        Some(selector) => selector,
+        None => return Ok(()),
+    };
+    let navs: Vec<_> = document
+        .select(selector)
+        .map(|nav| {
+            let label =
+                nav.value().attr("aria-label").unwrap_or_default();
+            (nav, label.trim())
+        })
+        .collect();
+    let mut used: HashSet<String> =
+        navs.iter().map(|(_, label)| label.to_lowercase()).collect();
+    let mut seen = HashSet::new();
+    for (nav, label) in &navs {
+        if seen.insert(label.to_lowercase()) {
+            continue;
+        }
+        let distinct = (2..)
+            .map(|number| format!("{} {}", label, number))
+            .find(|candidate| !used.contains(&candidate.to_lowercase()))
+            .unwrap_or_default();
+        let _ = used.insert(distinct.to_lowercase());
+
+        let start = match source.offset(nav) {
+            Some(start) => start,
+            None => {
+                html_builder
+                    .replacement_failed("landmarks", &nav.html())?;
+                continue;
+            }
+        };
+        let attributes = start + "<nav".len();
+        let end = attributes + tag_end(&source.source[attributes..]);
+        let mut tag = String::from("<nav");
+        for (name, value) in nav.value().attrs() {
+            let value = if name == "aria-label" {
+                &distinct
+            } else {
+                value
+            };
+            tag.push(' ');
+            tag.push_str(&format_attribute(name, value));
+        }
+        tag.push('>');
+        edits.push((start, end, tag));
+    }
+    Ok(())
+}
+
+/// Add live regions for dynamic content
+const fn add_live_regions(
+    html_builder: HtmlBuilder,
+) -> Result<HtmlBuilder> {
+    // Implementation for adding live regions
+    Ok(html_builder)
+}
+
+/// Enhance element descriptions for better accessibility
+const fn enhance_descriptions(
+    html_builder: HtmlBuilder,
+) -> Result<HtmlBuilder> {
+    // Implementation for enhancing descriptions
+    Ok(html_builder)
+}
+
+/// Elements the parser creates when their start tag is omitted.
+const IMPLIED_ELEMENTS: &[&str] = &["html", "head", "body", "tbody"];
+
+/// Elements whose content is text, so `<` in it starts no tag.
+const RAW_TEXT_ELEMENTS: &[&str] = &[
+    "iframe", "noembed", "noframes", "script", "style", "textarea",
+    "title", "xmp",
+];
+
+/// Start tags of a document, matched to the elements parsed from it.
+///
+/// The parser keeps no positions, so the start tags found in the source
+/// are paired with the elements of the same name in tree order. Elements
+/// the parser implied, or moved, e.g. out of a table, get no location.
+struct SourceMap<'a> {
+    source: &'a str,
+    offsets: HashMap<ego_tree::NodeId, usize>,
+}
+
+impl<'a> SourceMap<'a> {
+    /// Matches the start tags of `source` to the elements of `document`.
+    fn new(source: &'a str, document: &Html) -> Self {
+        let tags = start_tags(source);
+        let mut offsets = HashMap::new();
+        let mut next = 0;
+        for element in document.root_element().descendent_elements() {
+            let name = element.value().name();
+            let found = tags[next.min(tags.len())..]
+                .iter()
+                .position(|(tag, _)| tag.eq_ignore_ascii_case(name));
+            let index = match found {
+                Some(0) => next,
+                Some(_) if IMPLIED_ELEMENTS.contains(&name) => continue,
+                Some(skipped) => next + skipped,
+                None => continue,
+            };
+            let _ = offsets.insert(element.id(), tags[index].1);
+            next = index + 1;
+        }
+        SourceMap { source, offsets }
+    }
+
+    /// Returns the byte offset of the start tag of `element`.
+    fn offset(&self, element: &ElementRef<'_>) -> Option<usize> {
+        self.offsets.get(&element.id()).copied()
+    }
+
+    /// Returns the location of the start tag of `element`.
+    fn locate(
+        &self,
+        element: &ElementRef<'_>,
+    ) -> Option<SourceLocation> {
+        self.offset(element).map(|offset| {
+            SourceLocation::from_offset(self.source, offset)
+        })
+    }
+}
+
+/// Returns the lowercase name and byte offset of each start tag of
+/// `source`, skipping comments, declarations, end tags and the content
+/// of raw text elements.
+fn start_tags(source: &str) -> Vec<(String, usize)> {
+    let bytes = source.as_bytes();
+    let mut tags = Vec::new();
+    let mut position = 0;
+    while let Some(found) = memchr(b'<', &bytes[position..]) {
+        let open = position + found;
+        let rest = &source[open..];
+        position = open + 1;
+        if rest.starts_with("<!--") {
+            position = rest
+                .find("-->")
+                .map_or(source.len(), |end| open + end + 3);
+            continue;
+        }
+        let name_length = rest[1..]
+            .find(|c: char| {
+                c.is_ascii_whitespace() || c == '/' || c == '>'
+            })
+            .unwrap_or(rest.len() - 1);
+        let name = &rest[1..=name_length];
+        if !name.starts_with(|c: char| c.is_ascii_alphabetic()) {
+            continue;
+        }
+        let name = name.to_ascii_lowercase();
+        position =
+            open + tag_end(&rest[1 + name_length..]) + 1 + name_length;
+        if RAW_TEXT_ELEMENTS.contains(&name.as_str()) {
+            position = source[position..]
+                .match_indices("</")
+                .map(|(index, _)| position + index)
+                .find(|&close| {
+                    bytes.get(close + 2..close + 2 + name.len()).map_or(
+                        false,
+                        |closing| {
+                            closing
+                                .eq_ignore_ascii_case(name.as_bytes())
+                        },
+                    )
+                })
+                .unwrap_or(source.len());
+        }
+        tags.push((name, open));
+    }
+    tags
+}
+
+/// Returns the length of the attributes of a start tag up to and
+/// including its `>`, which is not searched for in quoted values.
+fn tag_end(attributes: &str) -> usize {
+    let mut quote = None;
+    for (index, c) in attributes.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(open), _) if c == open => quote = None,
+            (None, '>') => return index + 1,
+            _ => {}
+        }
+    }
+    attributes.len()
+}
+
+/// Check heading structure
+fn check_heading_structure(
+    document: &Html,
+    source: &SourceMap<'_>,
+    issues: &mut Vec<Issue>,
+) {
+    let mut prev_level: Option<u8> = None;
+
+    let selector = match HEADING_SELECTOR.as_ref() {
+        Some(selector) => selector,
+        None => return, // Skip checking if the selector is invalid
+    };
+
+    for heading in document.select(selector) {
         let current_level = heading
             .value()
             .name()
@@ -493,6 +1796,8 @@ fn check_heading_structure(document: &Html, issues: &mut Vec<Issue>) {
                 if current_level > prev_level + 1 {
                     issues.push(Issue {
                         issue_type: IssueType::HeadingStructure,
+                        severity: IssueType::HeadingStructure
+                            .default_severity(),
                         message: format!(
                             "Skipped heading level from h{} to h{}",
                             prev_level, current_level
@@ -502,6 +1807,7 @@ fn check_heading_structure(document: &Html, issues: &mut Vec<Issue>) {
                         suggestion: Some(
                             "Use sequential heading levels".to_string(),
                         ),
+                        location: source.locate(&heading),
                     });
                 }
             }
@@ -510,6 +1816,345 @@ fn check_heading_structure(document: &Html, issues: &mut Vec<Issue>) {
     }
 }
 
+/// Check that images have alt text, unless marked decorative
+fn check_alt_text(
+    document: &Html,
+    source: &SourceMap<'_>,
+    issues: &mut Vec<Issue>,
+) {
+    let selector = match ANY_IMAGE_SELECTOR.as_ref() {
+        Some(selector) => selector,
+        None => return,
+    };
+
+    for image in document.select(selector) {
+        let value = image.value();
+        let labelled = is_labelled(&image);
+        let decorative = value.attr("role").map_or(false, |role| {
+            role.split_whitespace().next().map_or(false, |role| {
+                matches!(role, "presentation" | "none")
+            })
+        });
+        let (message, suggestion) = match value.attr("alt").map(str::trim) {
+            None if labelled => continue,
+            None => (
+                "Image is missing alt text".to_string(),
+                "Add an alt attribute describing the image, or alt=\"\" \
+                 with role=\"presentation\" if it is decorative",
+            ),
+            Some("") if decorative || labelled => continue,
+            Some("") => (
+                "Image has empty alt text but is not marked decorative"
+                    .to_string(),
+                "Describe the image in its alt text, or add \
+                 role=\"presentation\" if it is decorative",
+            ),
+            Some(alt) if is_placeholder_alt(alt, value.attr("src")) => (
+                format!("Alt text '{}' does not describe the image", alt),
+                "Replace the placeholder with a description of what the \
+                 image shows or does",
+            ),
+            Some(_) => continue,
+        };
+        issues.push(Issue {
+            issue_type: IssueType::MissingAltText,
+            severity: IssueType::MissingAltText.default_severity(),
+            message,
+            guideline: Some("WCAG 1.1.1".to_string()),
+            element: Some(image.html()),
+            suggestion: Some(suggestion.to_string()),
+            location: source.locate(&image),
+        });
+    }
+}
+
+/// Returns `true` if `alt` is a placeholder rather than a description:
+/// a generic word such as "image", or a file name, e.g. that of `src`.
+fn is_placeholder_alt(alt: &str, src: Option<&str>) -> bool {
+    let alt = alt.to_lowercase();
+    let alt = alt.trim_end_matches(|c: char| !c.is_alphanumeric());
+    if PLACEHOLDER_ALT_TEXTS.contains(&alt) {
+        return true;
+    }
+    let file_name = src
+        .and_then(|src| src.split(['?', '#']).next())
+        .and_then(|path| path.rsplit('/').next())
+        .map(str::to_lowercase);
+    file_name.map_or(false, |name| !name.is_empty() && name == alt)
+        || IMAGE_EXTENSIONS.iter().any(|extension| {
+            alt.ends_with(extension) && !alt.contains(' ')
+        })
+}
+
+/// Returns the whitespace-collapsed text of `element`.
+fn collapsed_text(element: &ElementRef<'_>) -> String {
+    element
+        .text()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Check that no two headings of a page have the same text
+fn check_duplicate_headings(
+    document: &Html,
+    source: &SourceMap<'_>,
+    issues: &mut Vec<Issue>,
+) {
+    let selector = match HEADING_SELECTOR.as_ref() {
+        Some(selector) => selector,
+        None => return,
+    };
+
+    let mut seen: HashMap<String, String> = HashMap::new();
+    for heading in document.select(selector) {
+        let text = collapsed_text(&heading);
+        if text.is_empty() {
+            continue;
+        }
+        let name = heading.value().name();
+        match seen.get(&text.to_lowercase()) {
+            Some(first) => issues.push(Issue {
+                issue_type: IssueType::DuplicateHeadings,
+                severity: IssueType::DuplicateHeadings.default_severity(),
+                message: format!(
+                    "Heading text '{}' is already used by an earlier {}",
+                    text, first
+                ),
+                guideline: Some("WCAG 2.4.6".to_string()),
+                element: Some(heading.html()),
+                suggestion: Some(
+                    "Give each heading a text that names its own section"
+                        .to_string(),
+                ),
+                location: source.locate(&heading),
+            }),
+            None => {
+                let _ = seen.insert(text.to_lowercase(), name.to_string());
+            }
+        }
+    }
+}
+
+/// Returns the accessible name of a link: its `aria-label`, or its text
+/// and the alt text of its images.
+fn link_name(link: &ElementRef<'_>) -> String {
+    if let Some(label) = link.value().attr("aria-label") {
+        return label.split_whitespace().collect::<Vec<_>>().join(" ");
+    }
+    let mut name = collapsed_text(link);
+    if let Some(selector) = IMAGE_SELECTOR.as_ref() {
+        for image in link.select(selector) {
+            let alt =
+                image.value().attr("alt").unwrap_or_default().trim();
+            if !alt.is_empty() {
+                if !name.is_empty() {
+                    name.push(' ');
+                }
+                name.push_str(alt);
+            }
+        }
+    }
+    name
+}
+
+/// Check that links describe their target rather than using generic
+/// text or the bare URL
+fn check_link_purpose(
+    document: &Html,
+    source: &SourceMap<'_>,
+    issues: &mut Vec<Issue>,
+) {
+    let selector = match LINK_SELECTOR.as_ref() {
+        Some(selector) => selector,
+        None => return,
+    };
+
+    for link in document.select(selector) {
+        let name = link_name(&link);
+        let normalized = name
+            .trim_end_matches(|c: char| !c.is_alphanumeric())
+            .to_lowercase();
+        let href = link.value().attr("href").unwrap_or_default();
+        let (message, suggestion) =
+            if GENERIC_LINK_TEXTS.contains(&normalized.as_str()) {
+                (
+                format!(
+                    "Link text '{}' does not describe its target",
+                    name
+                ),
+                "Use link text that names the destination, such as \
+                 'Read the installation guide'",
+            )
+            } else if !normalized.is_empty()
+                && (normalized.starts_with("http://")
+                    || normalized.starts_with("https://")
+                    || normalized.starts_with("www.")
+                    || name == href)
+            {
+                (
+                format!("Link is named by its URL '{}'", name),
+                "Replace the URL with text describing the linked page",
+            )
+            } else {
+                continue;
+            };
+        issues.push(Issue {
+            issue_type: IssueType::LinkPurpose,
+            severity: IssueType::LinkPurpose.default_severity(),
+            message,
+            guideline: Some("WCAG 2.4.4".to_string()),
+            element: Some(link.html()),
+            suggestion: Some(suggestion.to_string()),
+            location: source.locate(&link),
+        });
+    }
+}
+
+/// Appends the text of `element` outside `bdi` elements and elements
+/// with a `dir` attribute to `text`.
+fn unisolated_text(element: &ElementRef<'_>, text: &mut String) {
+    for child in element.children() {
+        if let Some(child_text) = child.value().as_text() {
+            text.push_str(child_text);
+        } else if let Some(child) = ElementRef::wrap(child) {
+            let value = child.value();
+            if value.name() != "bdi" && value.attr("dir").is_none() {
+                unisolated_text(&child, text);
+            }
+        }
+    }
+}
+
+/// Check that runs of the opposite direction within a block are
+/// isolated with `bdi` or `dir`
+fn check_mixed_direction(
+    document: &Html,
+    source: &SourceMap<'_>,
+    issues: &mut Vec<Issue>,
+) {
+    let selector = match TEXT_BLOCK_SELECTOR.as_ref() {
+        Some(selector) => selector,
+        None => return,
+    };
+
+    for block in document.select(selector) {
+        if block.select(selector).next().is_some() {
+            continue;
+        }
+        let mut text = String::new();
+        unisolated_text(&block, &mut text);
+        if !crate::bidi::has_mixed_direction(&text) {
+            continue;
+        }
+        issues.push(Issue {
+            issue_type: IssueType::MixedDirection,
+            severity: IssueType::MixedDirection.default_severity(),
+            message: format!(
+                "Text mixes right-to-left and left-to-right runs \
+                 without isolation: '{}'",
+                collapsed_text(&block)
+            ),
+            guideline: Some("WCAG 1.3.2".to_string()),
+            element: Some(block.html()),
+            suggestion: Some(
+                "Wrap the run in the other direction in <bdi> or an \
+                 element with a dir attribute"
+                    .to_string(),
+            ),
+            location: source.locate(&block),
+        });
+    }
+}
+
+/// Check that formulas carry MathML or a readable `aria-label`, rather
+/// than only an image or TeX source in spans
+fn check_math(
+    document: &Html,
+    source: &SourceMap<'_>,
+    issues: &mut Vec<Issue>,
+) {
+    let (formulas, mathml, images) = match (
+        MATH_FORMULA_SELECTOR.as_ref(),
+        MATHML_SELECTOR.as_ref(),
+        ANY_IMAGE_SELECTOR.as_ref(),
+    ) {
+        (Some(formulas), Some(mathml), Some(images)) => {
+            (formulas, mathml, images)
+        }
+        _ => return,
+    };
+
+    for formula in document.select(formulas) {
+        let nested = formula
+            .ancestors()
+            .filter_map(ElementRef::wrap)
+            .any(|ancestor| formulas.matches(&ancestor));
+        if nested
+            || is_labelled(&formula)
+            || formula.select(mathml).next().is_some()
+        {
+            continue;
+        }
+        let pictures: Vec<_> = if formula.value().name() == "img" {
+            vec![formula]
+        } else {
+            formula.select(images).collect()
+        };
+        let (message, guideline) = if pictures.is_empty() {
+            (
+                format!(
+                    "Formula '{}' is only TeX source, without MathML or \
+                     an aria-label",
+                    collapsed_text(&formula)
+                ),
+                "WCAG 1.3.1",
+            )
+        } else if pictures.iter().any(|picture| {
+            !is_labelled(picture)
+                && picture
+                    .value()
+                    .attr("alt")
+                    .map_or(true, |alt| alt.trim().is_empty())
+        }) {
+            (
+                "Formula is an image without alt text or an aria-label"
+                    .to_string(),
+                "WCAG 1.1.1",
+            )
+        } else {
+            continue;
+        };
+        issues.push(Issue {
+            issue_type: IssueType::InaccessibleMath,
+            severity: IssueType::InaccessibleMath.default_severity(),
+            message,
+            guideline: Some(guideline.to_string()),
+            element: Some(formula.html()),
+            suggestion: Some(
+                "Render the formula to MathML, e.g. with KaTeX or MathJax \
+                 on the server, or add an aria-label reading the \
+                 expression, such as \"x squared plus one\""
+                    .to_string(),
+            ),
+            location: source.locate(&formula),
+        });
+    }
+}
+
+/// Returns `true` if `element` is named by a non-empty `aria-label` or
+/// `aria-labelledby`.
+fn is_labelled(element: &ElementRef<'_>) -> bool {
+    ["aria-label", "aria-labelledby"].iter().any(|name| {
+        element
+            .value()
+            .attr(name)
+            .map_or(false, |label| !label.trim().is_empty())
+    })
+}
+
 /// Validate HTML against WCAG guidelines with detailed reporting.
 ///
 /// Performs a comprehensive accessibility check based on WCAG guidelines and
@@ -546,30 +2191,81 @@ pub fn validate_wcag(
 ) -> Result<AccessibilityReport> {
     let start_time = std::time::Instant::now();
     let mut issues = Vec::new();
-    let mut elements_checked = 0;
 
     if html.trim().is_empty() {
         return Ok(AccessibilityReport {
             issues: Vec::new(),
             wcag_level: config.wcag_level,
             elements_checked: 0,
+            elements_total: 0,
+            elements_examined: 0,
+            coverage: Vec::new(),
             issue_count: 0,
             check_duration_ms: 0,
         });
     }
 
     let document = Html::parse_document(html);
+    let source = SourceMap::new(html, &document);
+    let enabled = |issue_type: IssueType| {
+        disable_checks.map_or(true, |d| !d.contains(&issue_type))
+    };
 
-    if disable_checks
-        .map_or(true, |d| !d.contains(&IssueType::LanguageDeclaration))
-    {
-        check_language_attributes(&document, &mut issues)?; // Returns Result<()>, so `?` works.
+    if enabled(IssueType::MissingAltText) {
+        check_alt_text(&document, &source, &mut issues);
+    }
+
+    if enabled(IssueType::LanguageDeclaration) {
+        check_language_attributes(&document, &source, &mut issues)?; // Returns Result<()>, so `?` works.
+    }
+
+    if enabled(IssueType::HeadingStructure) {
+        // This function returns `()`, so no `?`.
+        check_heading_structure(&document, &source, &mut issues);
     }
 
-    // This function returns `()`, so no `?`.
-    check_heading_structure(&document, &mut issues);
+    if enabled(IssueType::DuplicateHeadings) {
+        check_duplicate_headings(&document, &source, &mut issues);
+    }
+
+    if enabled(IssueType::LinkPurpose) {
+        check_link_purpose(&document, &source, &mut issues);
+    }
+
+    if enabled(IssueType::MixedDirection) {
+        check_mixed_direction(&document, &source, &mut issues);
+    }
+
+    if enabled(IssueType::InaccessibleMath) {
+        check_math(&document, &source, &mut issues);
+    }
+
+    let mut examined = HashSet::new();
+    let mut coverage = Vec::with_capacity(IssueType::ALL.len());
+    for issue_type in IssueType::ALL {
+        let (status, elements) = if enabled(issue_type) {
+            examined_elements(&document, issue_type)
+        } else {
+            (CheckStatus::Disabled, Vec::new())
+        };
+        let ids: HashSet<_> =
+            elements.iter().map(|element| element.id()).collect();
+        coverage.push(CheckCoverage {
+            issue_type,
+            status,
+            elements_examined: ids.len(),
+        });
+        examined.extend(ids);
+    }
+    let elements_total = count_checked_elements(&document);
+    let elements_checked = match config.element_accounting {
+        ElementAccounting::AllElements => elements_total,
+        ElementAccounting::Examined => examined.len(),
+    };
 
-    elements_checked += count_checked_elements(&document);
+    for issue in &mut issues {
+        issue.severity = config.severity(issue.issue_type);
+    }
 
     // Explicit error conversion for u64::try_from
     let check_duration_ms = u64::try_from(
@@ -585,77 +2281,392 @@ pub fn validate_wcag(
         issues: issues.clone(),
         wcag_level: config.wcag_level,
         elements_checked,
+        elements_total,
+        elements_examined: examined.len(),
+        coverage,
         issue_count: issues.len(),
         check_duration_ms,
     })
 }
 
-/// From implementation for TryFromIntError
-impl From<std::num::TryFromIntError> for Error {
-    fn from(err: std::num::TryFromIntError) -> Self {
-        Error::HtmlProcessingError {
-            message: "Integer conversion error".to_string(),
-            source: Some(Box::new(err)),
-        }
-    }
+/// A document to validate with [`validate_wcag_batch`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WcagSource {
+    /// An HTML file, read by the worker that validates it
+    File(PathBuf),
+    /// HTML already in memory
+    Html {
+        /// Name identifying the document in the site report
+        name: String,
+        /// The HTML content
+        html: String,
+    },
 }
 
-/// Display implementation for WCAG levels
-impl std::fmt::Display for WcagLevel {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl WcagSource {
+    /// Returns the name identifying the document: the file path or the
+    /// given name.
+    pub fn name(&self) -> String {
         match self {
-            WcagLevel::A => write!(f, "A"),
-            WcagLevel::AA => write!(f, "AA"),
-            WcagLevel::AAA => write!(f, "AAA"),
+            WcagSource::File(path) => path.display().to_string(),
+            WcagSource::Html { name, .. } => name.clone(),
         }
     }
 }
 
-/// Internal helper functions for accessibility checks
-impl AccessibilityReport {
-    /// Creates a new accessibility issue
-    fn add_issue(
-        issues: &mut Vec<Issue>,
-        issue_type: IssueType,
-        message: impl Into<String>,
-        guideline: Option<String>,
-        element: Option<String>,
-        suggestion: Option<String>,
-    ) {
-        issues.push(Issue {
-            issue_type,
-            message: message.into(),
-            guideline,
-            element,
-            suggestion,
-        });
-    }
+/// The validation result of one document of a batch.
+#[derive(Debug)]
+pub struct PageReport {
+    /// Name of the document, see [`WcagSource::name`]
+    pub name: String,
+    /// The report, or the error that prevented validation
+    pub result: Result<AccessibilityReport>,
 }
 
-/// Regex for matching HTML tags
-static HTML_TAG_REGEX: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(r"<[^>]*>").expect("Failed to compile HTML tag regex")
-});
+/// Aggregated results of [`validate_wcag_batch`].
+#[derive(Debug, Default)]
+pub struct SiteReport {
+    /// One entry per document, in input order
+    pub pages: Vec<PageReport>,
+}
 
-// We'll assume you call `load_emoji_sequences("data/emoji-sequences.txt")` once, and store it here in a static for simplicity.
-static EMOJI_MAP: Lazy<
-    std::result::Result<HashMap<String, String>, std::io::Error>,
-> = Lazy::new(|| load_emoji_sequences("data/emoji-data.txt"));
+impl SiteReport {
+    /// Returns the reports of the documents that could be validated.
+    pub fn reports(
+        &self,
+    ) -> impl Iterator<Item = (&str, &AccessibilityReport)> {
+        self.pages.iter().filter_map(|page| {
+            page.result
+                .as_ref()
+                .ok()
+                .map(|report| (page.name.as_str(), report))
+        })
+    }
 
-/// Normalizes content for ARIA labels by removing HTML tags and converting to a standardized format.
-///
-/// # Arguments
-///
-/// * `content` - The content to normalize
-///
-/// # Returns
-///
-/// Returns a normalized string suitable for use as an ARIA label
+    /// Returns the documents that could not be validated.
+    pub fn failures(&self) -> impl Iterator<Item = (&str, &Error)> {
+        self.pages.iter().filter_map(|page| {
+            page.result
+                .as_ref()
+                .err()
+                .map(|error| (page.name.as_str(), error))
+        })
+    }
+
+    /// Returns the total number of issues across all documents.
+    pub fn issue_count(&self) -> usize {
+        self.reports().map(|(_, report)| report.issue_count).sum()
+    }
+
+    /// Returns the total number of elements checked.
+    pub fn elements_checked(&self) -> usize {
+        self.reports()
+            .map(|(_, report)| report.elements_checked)
+            .sum()
+    }
+
+    /// Returns `true` if any document has an error-level issue or
+    /// could not be validated.
+    pub fn has_errors(&self) -> bool {
+        self.failures().next().is_some()
+            || self.reports().any(|(_, report)| report.has_errors())
+    }
+
+    /// Returns the issues of every document as one [SARIF] log, see
+    /// [`AccessibilityReport::to_sarif`].
+    ///
+    /// Documents are identified by their names, and documents that
+    /// could not be validated are reported as tool execution errors.
+    ///
+    /// [SARIF]: https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html
+    pub fn to_sarif(&self) -> Value {
+        let results = self
+            .reports()
+            .flat_map(|(name, report)| sarif_results(name, report))
+            .collect();
+        let notifications = self
+            .failures()
+            .map(|(name, error)| {
+                json!({
+                    "level": "error",
+                    "message": { "text": format!("{}: {}", name, error) },
+                    "locations": [sarif_location(name, None)],
+                })
+            })
+            .collect();
+        sarif_log(results, notifications)
+    }
+}
+
+/// Returns a SARIF log of one run with `results`, reporting
+/// `notifications` as tool execution errors.
+fn sarif_log(results: Vec<Value>, notifications: Vec<Value>) -> Value {
+    let catalog = ExplanationCatalog::bundled();
+    let rules: Vec<Value> = IssueType::ALL
+        .iter()
+        .map(|issue_type| {
+            let code = issue_type.code();
+            let mut rule = json!({
+                "id": code,
+                "defaultConfiguration": {
+                    "level": sarif_level(issue_type.default_severity()),
+                },
+            });
+            if let Some(explanation) = catalog.get(code) {
+                rule["shortDescription"] =
+                    json!({ "text": explanation.title });
+                rule["fullDescription"] =
+                    json!({ "text": explanation.why });
+                rule["help"] =
+                    json!({ "text": explanation.how_to_fix });
+            }
+            rule
+        })
+        .collect();
+    json!({
+        "$schema": SARIF_SCHEMA,
+        "version": SARIF_VERSION,
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "html-generator",
+                    "version": crate::build_info::CRATE_VERSION,
+                    "informationUri": env!("CARGO_PKG_REPOSITORY"),
+                    "rules": rules,
+                },
+            },
+            "invocations": [{
+                "executionSuccessful": notifications.is_empty(),
+                "toolExecutionNotifications": notifications,
+            }],
+            "columnKind": "unicodeCodePoints",
+            "results": results,
+        }],
+    })
+}
+
+/// Returns the SARIF results of the issues of `report`, located in
+/// `artifact_uri`.
+fn sarif_results(
+    artifact_uri: &str,
+    report: &AccessibilityReport,
+) -> Vec<Value> {
+    report
+        .issues
+        .iter()
+        .map(|issue| {
+            let rule_index = IssueType::ALL
+                .iter()
+                .position(|issue_type| *issue_type == issue.issue_type);
+            json!({
+                "ruleId": issue.issue_type.code(),
+                "ruleIndex": rule_index,
+                "level": sarif_level(issue.severity),
+                "message": { "text": issue.message },
+                "locations": [sarif_location(artifact_uri, issue.location)],
+                "properties": {
+                    "element": issue.element,
+                    "guideline": issue.guideline,
+                    "suggestion": issue.suggestion,
+                },
+            })
+        })
+        .collect()
+}
+
+/// Returns the SARIF location of the file `artifact_uri`, narrowed to
+/// the start tag at `location` when known.
+fn sarif_location(
+    artifact_uri: &str,
+    location: Option<SourceLocation>,
+) -> Value {
+    let mut physical = json!({
+        "artifactLocation": { "uri": artifact_uri },
+    });
+    if let Some(location) = location {
+        physical["region"] = json!({
+            "startLine": location.line,
+            "startColumn": location.column,
+        });
+    }
+    json!({ "physicalLocation": physical })
+}
+
+/// Returns the SARIF level of issues of `severity`.
+fn sarif_level(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+        Severity::Info => "note",
+    }
+}
+
+/// Validates many documents concurrently.
+///
+/// Documents are distributed over up to [`MAX_BATCH_WORKERS`] threads,
+/// which share the compiled selectors of the checks. Files are read by
+/// the worker validating them, so a missing file only fails its own
+/// entry in the report.
+///
+/// # Arguments
+///
+/// * `sources` - The documents to validate
+/// * `config` - Configuration applied to every document
+///
+/// # Returns
+///
+/// A [`SiteReport`] with one [`PageReport`] per source, in input order.
+///
+/// # Examples
+///
+/// ```
+/// use html_generator::accessibility::{
+///     validate_wcag_batch, AccessibilityConfig, WcagSource,
+/// };
+///
+/// let sources = (0..20).map(|index| WcagSource::Html {
+///     name: format!("page-{}.html", index),
+///     html: r#"<html lang="en"><h1>Title</h1><h3>Skipped</h3></html>"#
+///         .to_string(),
+/// });
+/// let site = validate_wcag_batch(sources, &AccessibilityConfig::default());
+/// assert_eq!(site.pages.len(), 20);
+/// assert_eq!(site.pages[7].name, "page-7.html");
+/// assert_eq!(site.issue_count(), 20);
+/// ```
+pub fn validate_wcag_batch<I>(
+    sources: I,
+    config: &AccessibilityConfig,
+) -> SiteReport
+where
+    I: IntoIterator<Item = WcagSource>,
+{
+    let queue: VecDeque<(usize, WcagSource)> =
+        sources.into_iter().enumerate().collect();
+    let workers = queue.len().min(MAX_BATCH_WORKERS);
+    let queue = Arc::new(Mutex::new(queue));
+    let (sender, receiver) = mpsc::channel();
+
+    let handles: Vec<_> = (0..workers)
+        .map(|_| {
+            let queue = Arc::clone(&queue);
+            let sender = sender.clone();
+            let config = *config;
+            thread::spawn(move || loop {
+                let next = queue
+                    .lock()
+                    .unwrap_or_else(|poisoned| poisoned.into_inner())
+                    .pop_front();
+                let (index, source) = match next {
+                    Some(next) => next,
+                    None => break,
+                };
+                let page = validate_source(source, &config);
+                if sender.send((index, page)).is_err() {
+                    break;
+                }
+            })
+        })
+        .collect();
+    drop(sender);
+
+    let mut pages: Vec<(usize, PageReport)> = receiver.iter().collect();
+    for handle in handles {
+        if let Err(panic) = handle.join() {
+            std::panic::resume_unwind(panic);
+        }
+    }
+    pages.sort_by_key(|(index, _)| *index);
+    SiteReport {
+        pages: pages.into_iter().map(|(_, page)| page).collect(),
+    }
+}
+
+/// Reads and validates a single batch document.
+fn validate_source(
+    source: WcagSource,
+    config: &AccessibilityConfig,
+) -> PageReport {
+    let name = source.name();
+    let result = match source {
+        WcagSource::File(path) => std::fs::read_to_string(&path)
+            .map_err(|err| Error::HtmlProcessingError {
+                message: format!("Failed to read {}", path.display()),
+                source: Some(Box::new(err)),
+            })
+            .and_then(|html| validate_wcag(&html, config, None)),
+        WcagSource::Html { html, .. } => {
+            validate_wcag(&html, config, None)
+        }
+    };
+    PageReport { name, result }
+}
+
+/// From implementation for TryFromIntError
+impl From<std::num::TryFromIntError> for Error {
+    fn from(err: std::num::TryFromIntError) -> Self {
+        Error::HtmlProcessingError {
+            message: "Integer conversion error".to_string(),
+            source: Some(Box::new(err)),
+        }
+    }
+}
+
+/// Display implementation for WCAG levels
+impl std::fmt::Display for WcagLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WcagLevel::A => write!(f, "A"),
+            WcagLevel::AA => write!(f, "AA"),
+            WcagLevel::AAA => write!(f, "AAA"),
+        }
+    }
+}
+
+/// Internal helper functions for accessibility checks
+impl AccessibilityReport {
+    /// Creates a new accessibility issue
+    fn add_issue(
+        issues: &mut Vec<Issue>,
+        issue_type: IssueType,
+        message: impl Into<String>,
+        guideline: Option<String>,
+        element: Option<String>,
+        suggestion: Option<String>,
+        location: Option<SourceLocation>,
+    ) {
+        issues.push(Issue {
+            issue_type,
+            severity: issue_type.default_severity(),
+            message: message.into(),
+            guideline,
+            element,
+            suggestion,
+            location,
+        });
+    }
+}
+
+// We'll assume you call `load_emoji_sequences("data/emoji-sequences.txt")` once, and store it here in a static for simplicity.
+static EMOJI_MAP: Lazy<
+    std::result::Result<HashMap<String, String>, std::io::Error>,
+> = Lazy::new(|| load_emoji_sequences("data/emoji-data.txt"));
+
+/// Normalizes content for ARIA labels by removing HTML tags and converting to a standardized format.
+///
+/// # Arguments
+///
+/// * `content` - The content to normalize
+///
+/// # Returns
+///
+/// Returns a normalized string suitable for use as an ARIA label
 fn normalize_aria_label(content: &str) -> String {
-    // 1. Remove HTML
-    let no_html = HTML_TAG_REGEX.replace_all(content, "");
+    // 1. Remove HTML and decode entities
+    let no_html = strip_tags(content);
+    let decoded = decode_entities(&no_html);
     // 2. Trim
-    let text_only = no_html.trim();
+    let text_only = decoded.trim();
 
     // 3. If empty, fallback
     if text_only.is_empty() {
@@ -663,28 +2674,51 @@ fn normalize_aria_label(content: &str) -> String {
     }
 
     // 4. Check each loaded emoji mapping
-    //    If the user input contains that emoji, return the mapped label
-    match &*EMOJI_MAP {
-        Ok(map) => {
-            for (emoji, label) in map.iter() {
-                if text_only.contains(emoji) {
-                    return label.clone();
+    //    If the user input contains that emoji, return the mapped label.
+    //    Every emoji contains a non-ASCII character, so ASCII text is
+    //    skipped.
+    if !text_only.is_ascii() {
+        match &*EMOJI_MAP {
+            Ok(map) => {
+                for (emoji, label) in map.iter() {
+                    if text_only.contains(emoji) {
+                        return label.clone();
+                    }
                 }
             }
-        }
-        Err(e) => {
-            // Handle the error (e.g., log it)
-            eprintln!("Error loading emoji sequences: {}", e);
+            Err(e) => {
+                // Handle the error (e.g., log it)
+                eprintln!("Error loading emoji sequences: {}", e);
+            }
         }
     }
 
     // 5. If no match, do your fallback normalization
-    text_only
-        .to_lowercase()
-        .replace(|c: char| !c.is_alphanumeric(), "-")
-        .replace("--", "-")
-        .trim_matches('-')
-        .to_string()
+    generate_id(text_only)
+}
+
+/// Removes the tags of `html`, like replacing `<[^>]*>` with nothing.
+///
+/// Scans for `<` and `>` with `memchr` and borrows `html` when it has
+/// no tags.
+fn strip_tags(html: &str) -> Cow<'_, str> {
+    let bytes = html.as_bytes();
+    let mut open = match memchr(b'<', bytes) {
+        Some(open) => open,
+        None => return Cow::Borrowed(html),
+    };
+    let mut text = String::with_capacity(html.len());
+    let mut copied = 0;
+    while let Some(length) = memchr(b'>', &bytes[open..]) {
+        text.push_str(&html[copied..open]);
+        copied = open + length + 1;
+        open = match memchr(b'<', &bytes[copied..]) {
+            Some(offset) => copied + offset,
+            None => break,
+        };
+    }
+    text.push_str(&html[copied..]);
+    Cow::Owned(text)
 }
 
 /// Adds ARIA attributes for buttons that have a `title="..."`, converting
@@ -756,11 +2790,11 @@ fn add_aria_to_tooltips(
         );
 
         // 6) Replace the old button snippet
-        html_builder.content = replace_html_element_resilient(
-            &html_builder.content,
+        html_builder.replace_element(
+            "tooltips",
             &old_button_html,
             &new_button_snippet,
-        );
+        )?;
     }
 
     Ok(html_builder)
@@ -792,10 +2826,18 @@ fn add_aria_to_toggle(
 
     // Use your desired selector. Here we look for `.toggle-button`.
     // If you want `[data-toggle="button"]` or something else, just change it.
-    if let Ok(selector) = Selector::parse(".toggle-button") {
+    let selector = Selector::parse(".toggle-button");
+    if selector.is_err() {
+        html_builder.skip(
+            "toggle",
+            SkipReason::SelectorFailed,
+            ".toggle-button",
+        );
+    }
+    if let Ok(selector) = selector {
         for toggle_elem in document.select(&selector) {
             let old_html = toggle_elem.html();
-            let content = toggle_elem.inner_html();
+            let content = serializer::inner_html(&toggle_elem);
 
             // Collect new attributes
             let mut attributes = Vec::new();
@@ -830,11 +2872,8 @@ fn add_aria_to_toggle(
             );
 
             // 5) Replace old element in HTML
-            html_builder.content = replace_html_element_resilient(
-                &html_builder.content,
-                &old_html,
-                &new_html,
-            );
+            html_builder
+                .replace_element("toggle", &old_html, &new_html)?;
         }
     }
 
@@ -860,11 +2899,28 @@ fn add_aria_to_buttons(
 ) -> Result<HtmlBuilder> {
     let document = Html::parse_document(&html_builder.content);
 
+    if let Some(labeled) = LABELED_BUTTON_SELECTOR.as_ref() {
+        for button in document.select(labeled) {
+            html_builder.skip(
+                "buttons",
+                SkipReason::AlreadyLabeled,
+                &button.html(),
+            );
+        }
+    }
+
     // Our selector targets <button> elements lacking an aria-label
+    if BUTTON_SELECTOR.is_none() {
+        html_builder.skip(
+            "buttons",
+            SkipReason::SelectorFailed,
+            "button:not([aria-label])",
+        );
+    }
     if let Some(selector) = BUTTON_SELECTOR.as_ref() {
         for button in document.select(selector) {
             let original_button_html = button.html();
-            let mut inner_content = button.inner_html();
+            let mut inner_content = serializer::inner_html(&button);
             let mut aria_label = normalize_aria_label(&inner_content);
 
             // 1) Add aria-hidden="true" to any <span class="icon">
@@ -916,10 +2972,12 @@ fn add_aria_to_buttons(
             // 4) Preserve existing attributes (except flipping or re-adding aria-pressed)
             for (key, value) in button.value().attrs() {
                 // If we're flipping aria-pressed, skip the old one
-                if key == "aria-pressed" {
+                if key == "aria-pressed"
+                    || !html_builder.attributes.allows(key)
+                {
                     continue;
                 }
-                attributes.push(format!(r#"{}="{}""#, key, value));
+                attributes.push(format_attribute(key, value));
             }
 
             // 5) Generate the new button HTML
@@ -930,11 +2988,11 @@ fn add_aria_to_buttons(
             );
 
             // 6) Replace the old <button> in the HTML
-            html_builder.content = replace_html_element_resilient(
-                &html_builder.content,
+            html_builder.replace_element(
+                "buttons",
                 &original_button_html,
                 &new_button_html,
-            );
+            )?;
         }
     }
 
@@ -973,21 +3031,31 @@ fn replace_html_element_resilient(
     original_html.replacen(old_element, new_element, 1)
 }
 
+/// Writes boolean attributes in their `name=""` form, as the parser
+/// serializes them. Only opening tags are rewritten, so text content
+/// such as "this field is required" is left alone.
 fn normalize_shorthand_attributes(html: &str) -> String {
-    let re = Regex::new(
-    r"\b(disabled|checked|readonly|multiple|selected|autofocus|required)([\s>])"
-).unwrap();
-
-    re.replace_all(html, |caps: &regex::Captures| {
-        let attr = &caps[1]; // e.g. "disabled"
-        let delim = &caps[2]; // e.g. ">" or " "
-
-        // Insert ="" right before the delimiter
-        // So <button disabled> becomes <button disabled="">
-        // but <button disabled=""> won't match, so remains as-is
-        format!(r#"{}=""{}"#, attr, delim)
-    })
-    .to_string()
+    OPENING_TAG_REGEX
+        .replace_all(html, |tag: &regex::Captures| {
+            SHORTHAND_ATTRIBUTE_REGEX
+                .replace_all(&tag[0], |caps: &regex::Captures| {
+                    match caps.get(1) {
+                        // Insert ="" right before the delimiter
+                        // So <button disabled> becomes <button disabled="">
+                        // but <button disabled=""> won't match, so remains as-is
+                        Some(attr) => {
+                            format!(
+                                r#"{}=""{}"#,
+                                attr.as_str(),
+                                &caps[2]
+                            )
+                        }
+                        None => caps[0].to_string(),
+                    }
+                })
+                .into_owned()
+        })
+        .to_string()
 }
 
 /// Add ARIA attributes to navigation elements.
@@ -996,6 +3064,23 @@ fn add_aria_to_navs(
 ) -> Result<HtmlBuilder> {
     let document = Html::parse_document(&html_builder.content);
 
+    if let Some(labeled) = LABELED_NAV_SELECTOR.as_ref() {
+        for nav in document.select(labeled) {
+            html_builder.skip(
+                "navs",
+                SkipReason::AlreadyLabeled,
+                &nav.html(),
+            );
+        }
+    }
+
+    if NAV_SELECTOR.is_none() {
+        html_builder.skip(
+            "navs",
+            SkipReason::SelectorFailed,
+            "nav:not([aria-label])",
+        );
+    }
     if let Some(selector) = NAV_SELECTOR.as_ref() {
         for nav in document.select(selector) {
             let nav_html = nav.html();
@@ -1006,8 +3091,11 @@ fn add_aria_to_navs(
                     DEFAULT_NAV_ROLE
                 ),
             );
-            html_builder.content =
-                html_builder.content.replace(&nav_html, &new_nav_html);
+            html_builder.replace_all(
+                "navs",
+                &nav_html,
+                &new_nav_html,
+            )?;
         }
     }
 
@@ -1021,8 +3109,18 @@ fn add_aria_to_forms(
     let document = Html::parse_document(&html_builder.content);
 
     // Traverse form elements and add ARIA attributes
-    let forms = document.select(FORM_SELECTOR.as_ref().unwrap());
-    for form in forms {
+    let selector = match FORM_SELECTOR.as_ref() {
+        Some(selector) => selector,
+        None => {
+            html_builder.skip(
+                "forms",
+                SkipReason::SelectorFailed,
+                "form:not([aria-label])",
+            );
+            return Ok(html_builder);
+        }
+    };
+    for form in document.select(selector) {
         // Generate a unique ID for the form
         let form_id = format!("form-{}", generate_unique_id());
 
@@ -1047,11 +3145,14 @@ fn add_aria_to_forms(
                 .map(|&(k, v)| format!(r#"{}="{}""#, k, v))
                 .collect::<Vec<_>>()
                 .join(" "),
-            form.inner_html()
+            serializer::inner_html(&form)
         );
 
-        html_builder.content =
-            html_builder.content.replace(&form.html(), &new_form_html);
+        html_builder.replace_all(
+            "forms",
+            &form.html(),
+            &new_form_html,
+        )?;
     }
 
     Ok(html_builder)
@@ -1077,29 +3178,57 @@ fn add_aria_to_tabs(
         for tablist in document.select(&tablist_selector) {
             let tablist_html = tablist.html();
 
-            // We'll build up the new HTML string for the tablist container
-            let mut new_html = String::new();
-            new_html.push_str("<div role=\"tablist\">");
-
             // 1) Gather all <button> elements inside this tablist
             let mut button_texts = Vec::new();
             if let Ok(button_selector) = Selector::parse("button") {
                 for button in tablist.select(&button_selector) {
-                    // Save the button's inner text (or HTML) for later
-                    button_texts.push(button.inner_html());
+                    // Save the button's inner HTML and user attributes
+                    button_texts.push((
+                        serializer::inner_html(&button),
+                        carried_attributes(
+                            &button,
+                            &html_builder.attributes,
+                            &["role", "id", "aria-*", "tabindex"],
+                        ),
+                    ));
                 }
             }
 
+            // We'll build up the new HTML string for the tablist container
+            let ids = |prefix: &str| {
+                (1..=button_texts.len())
+                    .map(|num| format!("{}{}", prefix, num))
+                    .collect::<Vec<_>>()
+            };
+            let hydration = html_builder.hydration(
+                Widget::Tabs,
+                json!({
+                    "tabs": ids("tab"),
+                    "panels": ids("panel"),
+                    "selected": 0,
+                }),
+            );
+            let mut new_html = format!(
+                "<div role=\"tablist\"{}{}>",
+                carried_attributes(
+                    &tablist,
+                    &html_builder.attributes,
+                    &["role", "data-hg-*"],
+                ),
+                hydration
+            );
+
             // 2) Create the enhanced tab buttons
-            for (i, text) in button_texts.iter().enumerate() {
+            for (i, (text, attrs)) in button_texts.iter().enumerate() {
                 let is_selected = i == 0;
                 let num = i + 1;
                 new_html.push_str(&format!(
-                    r#"<button role="tab" id="tab{}" aria-selected="{}" aria-controls="panel{}" tabindex="{}">{}</button>"#,
+                    r#"<button role="tab" id="tab{}" aria-selected="{}" aria-controls="panel{}" tabindex="{}"{}>{}</button>"#,
                     num,
                     is_selected,
                     num,
                     if is_selected { "0" } else { "-1" },
+                    attrs,
                     text
                 ));
             }
@@ -1121,8 +3250,11 @@ fn add_aria_to_tabs(
             }
 
             // 4) Replace the original tablist in the HTML
-            html_builder.content =
-                html_builder.content.replace(&tablist_html, &new_html);
+            html_builder.replace_all(
+                "tabs",
+                &tablist_html,
+                &new_html,
+            )?;
         }
     }
 
@@ -1147,6 +3279,11 @@ fn add_aria_to_modals(
         Ok(s) => s,
         Err(e) => {
             eprintln!("Failed to parse .modal selector: {}", e);
+            html_builder.skip(
+                "modals",
+                SkipReason::SelectorFailed,
+                ".modal",
+            );
             return Ok(html_builder); // If selector fails, just return original
         }
     };
@@ -1166,17 +3303,13 @@ fn add_aria_to_modals(
             if attr_name.eq_ignore_ascii_case("role") {
                 found_role = true;
                 existing_role_value = attr_value.to_string();
-                new_attrs
-                    .push(format!(r#"{}="{}""#, attr_name, attr_value));
             } else if attr_name.eq_ignore_ascii_case("aria-modal") {
                 found_aria_modal = true;
-                new_attrs
-                    .push(format!(r#"{}="{}""#, attr_name, attr_value));
-            } else {
-                // Preserve everything else (class="modal", aria-hidden, etc.)
-                new_attrs
-                    .push(format!(r#"{}="{}""#, attr_name, attr_value));
+            } else if !html_builder.attributes.allows(attr_name) {
+                continue;
             }
+            // Preserve everything else (class="modal", aria-hidden, etc.)
+            new_attrs.push(format_attribute(attr_name, attr_value));
         }
 
         // 4) Determine if it's an alert dialog (role="alertdialog" or .alert class is present)
@@ -1216,15 +3349,15 @@ fn add_aria_to_modals(
             doc_inner.select(&p_selector).next()
         {
             // If it has an ID, use it. Else generate a new ID, inject it into the snippet.
-            let desc_id: String = if let Some(id_val) =
+            let desc_id = if let Some(id_val) =
                 descriptive_elem.value().attr("id")
             {
                 // Branch A: existing ID
-                id_val.to_string()
+                Some(id_val.to_string())
             } else {
                 // Branch B: generate a new ID, insert it into the snippet
                 let generated_id =
-                    format!("dialog-desc-{}", uuid::Uuid::new_v4());
+                    format!("dialog-desc-{}", unique_id_suffix());
                 let old_snippet = descriptive_elem.html();
 
                 // Build a new opening tag with the ID
@@ -1249,17 +3382,24 @@ fn add_aria_to_modals(
                     format!("{}{}", new_opening_tag, rest_of_tag);
 
                 // Replace old snippet with the new snippet that has an ID
+                let inner_html = modal_elem.inner_html();
                 let updated_inner = replace_html_element_resilient(
-                    &modal_elem.inner_html(),
+                    &inner_html,
                     &old_snippet,
                     &new_snippet,
                 );
-                doc_inner = Html::parse_fragment(&updated_inner);
-
-                generated_id
+                if updated_inner == inner_html {
+                    // Without the ID, aria-describedby would dangle
+                    html_builder
+                        .replacement_failed("modals", &old_snippet)?;
+                    None
+                } else {
+                    doc_inner = Html::parse_fragment(&updated_inner);
+                    Some(generated_id)
+                }
             };
 
-            maybe_describedby = Some(desc_id);
+            maybe_describedby = desc_id;
         }
 
         // If we found a descriptive block but no `aria-describedby` yet, add one
@@ -1274,9 +3414,31 @@ fn add_aria_to_modals(
             }
         }
 
+        // Mark the dialog for client-side hydration, once
+        let has_hydration = new_attrs
+            .iter()
+            .any(|attr| attr.starts_with(WIDGET_ATTRIBUTE));
+        if html_builder.hydrate && !has_hydration {
+            let attr_value = |name: &str| {
+                let prefix = format!("{}=\"", name);
+                new_attrs.iter().rev().find_map(|attr| {
+                    attr.strip_prefix(prefix.as_str()).map(|value| {
+                        value.trim_end_matches('"').to_string()
+                    })
+                })
+            };
+            let config = json!({
+                "role": attr_value("role"),
+                "describedby": attr_value("aria-describedby"),
+            });
+            new_attrs
+                .push(hydration_attributes(Widget::Modal, &config));
+        }
+
         // 7) Rebuild the final <div ...> snippet with new attributes & updated inner content.
         //    Just use `doc_inner.root_element().inner_html()` to avoid .html() calls on NodeRef.
-        let children_html = doc_inner.root_element().inner_html();
+        let children_html =
+            serializer::inner_html(&doc_inner.root_element());
 
         let new_modal_html = format!(
             r#"<div {}>{}</div>"#,
@@ -1290,11 +3452,11 @@ fn add_aria_to_modals(
         );
 
         // 8) Replace the old snippet in the top-level HTML with the new snippet
-        html_builder.content = replace_html_element_resilient(
-            &html_builder.content,
+        html_builder.replace_element(
+            "modals",
             &old_modal_html,
             &new_modal_html,
-        );
+        )?;
     }
 
     Ok(html_builder)
@@ -1309,8 +3471,8 @@ fn add_aria_to_accordions(
     if let Ok(accordion_selector) = Selector::parse(".accordion") {
         for accordion in document.select(&accordion_selector) {
             let accordion_html = accordion.html();
-            let mut new_html =
-                String::from("<div class=\"accordion\">");
+            let mut sections_html = String::new();
+            let mut sections = Vec::new();
 
             // Find button and content pairs
             if let (Ok(button_selector), Ok(content_selector)) = (
@@ -1324,25 +3486,54 @@ fn add_aria_to_accordions(
                 for (i, (button, content)) in
                     buttons.zip(contents).enumerate()
                 {
-                    let button_text = button.inner_html();
-                    let content_text = content.inner_html();
+                    let button_text = serializer::inner_html(&button);
+                    let content_text = serializer::inner_html(&content);
+                    let button_attrs = carried_attributes(
+                        &button,
+                        &html_builder.attributes,
+                        &["id", "aria-*"],
+                    );
+                    let content_attrs = carried_attributes(
+                        &content,
+                        &html_builder.attributes,
+                        &["id", "aria-*", "hidden"],
+                    );
                     let section_num = i + 1;
 
                     // Add button with ARIA attributes
-                    new_html.push_str(&format!(
-                        r#"<button aria-expanded="false" aria-controls="section-{}-content" id="section-{}-button">{}</button><div id="section-{}-content" aria-labelledby="section-{}-button" hidden>{}</div>"#,
-                        section_num, section_num, button_text,
-                        section_num, section_num, content_text
+                    sections.push(json!({
+                        "button": format!("section-{}-button", section_num),
+                        "panel": format!("section-{}-content", section_num),
+                    }));
+                    sections_html.push_str(&format!(
+                        r#"<button aria-expanded="false" aria-controls="section-{}-content" id="section-{}-button"{}>{}</button><div id="section-{}-content" aria-labelledby="section-{}-button" hidden{}>{}</div>"#,
+                        section_num, section_num, button_attrs, button_text,
+                        section_num, section_num, content_attrs, content_text
                     ));
                 }
             }
 
-            new_html.push_str("</div>");
+            let hydration = html_builder.hydration(
+                Widget::Accordion,
+                json!({ "sections": sections }),
+            );
+            let new_html = format!(
+                "<div{}{}>{}</div>",
+                carried_attributes(
+                    &accordion,
+                    &html_builder.attributes,
+                    &["data-hg-*"],
+                ),
+                hydration,
+                sections_html
+            );
 
             // Replace the original accordion with the enhanced version
-            html_builder.content = html_builder
-                .content
-                .replace(&accordion_html, &new_html);
+            html_builder.replace_all(
+                "accordions",
+                &accordion_html,
+                &new_html,
+            )?;
         }
     }
 
@@ -1353,8 +3544,16 @@ fn add_aria_to_accordions(
 fn add_aria_to_inputs(
     mut html_builder: HtmlBuilder,
 ) -> Result<HtmlBuilder> {
+    if INPUT_REGEX.is_none() {
+        html_builder.skip(
+            "inputs",
+            SkipReason::SelectorFailed,
+            "<input[^>]*>",
+        );
+    }
     if let Some(regex) = INPUT_REGEX.as_ref() {
         let mut replacements: Vec<(String, String)> = Vec::new();
+        let mut labeled = Vec::new();
         let mut id_counter = 0;
 
         // Find all <input> tags via the regex
@@ -1368,6 +3567,7 @@ fn add_aria_to_inputs(
                     &html_builder.content,
                 )
             {
+                labeled.push(input_tag.to_string());
                 continue;
             }
 
@@ -1386,18 +3586,23 @@ fn add_aria_to_inputs(
                 // For checkbox/radio, ensure ID + label, avoiding duplicates
                 "checkbox" | "radio" => {
                     // Preserve all existing attributes
-                    let attributes = preserve_attributes(input_tag);
+                    let attributes = preserve_attributes(
+                        input_tag,
+                        &html_builder.attributes,
+                    );
 
                     // 1) Check if there's already an id="..." in the attributes
-                    let re_id = Regex::new(r#"id="([^"]+)""#).unwrap();
-                    if let Some(id_match) = re_id.captures(&attributes)
+                    use crate::patterns::accessibility::ID_REGEX;
+                    if let Some(id_match) =
+                        ID_REGEX.captures(&attributes)
                     {
                         // Already has an ID, so just use it—no duplicates
                         let existing_id = &id_match[1];
                         // Also remove the old id= from the attribute string
                         // so we only insert it once in the final <input ...>
-                        let attributes_no_id =
-                            re_id.replace(&attributes, "").to_string();
+                        let attributes_no_id = ID_REGEX
+                            .replace(&attributes, "")
+                            .to_string();
 
                         // Decide the label text
                         let label_text = if input_type == "checkbox" {
@@ -1441,7 +3646,10 @@ fn add_aria_to_inputs(
 
                 // For any other types, automatically add `aria-label` with the type name
                 _ => {
-                    let attributes = preserve_attributes(input_tag);
+                    let attributes = preserve_attributes(
+                        input_tag,
+                        &html_builder.attributes,
+                    );
                     let enhanced_input = format!(
                         r#"<input {} aria-label="{}">"#,
                         attributes, input_type
@@ -1452,10 +3660,20 @@ fn add_aria_to_inputs(
             }
         }
 
-        // Perform all replacements
+        for input_tag in labeled {
+            html_builder.skip(
+                "inputs",
+                SkipReason::AlreadyLabeled,
+                &input_tag,
+            );
+        }
+
+        // Perform all replacements; identical tags are replaced together
+        let mut replaced = HashSet::new();
         for (old, new) in replacements {
-            html_builder.content =
-                html_builder.content.replace(&old, &new);
+            if replaced.insert(old.clone()) {
+                html_builder.replace_all("inputs", &old, &new)?;
+            }
         }
     }
 
@@ -1464,30 +3682,27 @@ fn add_aria_to_inputs(
 
 // Helper function to check for associated labels (using string manipulation)
 fn has_associated_label(input_tag: &str, html_content: &str) -> bool {
-    if let Some(id_match) =
-        Regex::new(r#"id="([^"]+)""#).unwrap().captures(input_tag)
-    {
-        let id = &id_match[1];
-        Regex::new(&format!(r#"<label\s+for="{}"\s*>"#, id))
-            .unwrap()
-            .is_match(html_content)
-    } else {
-        false
-    }
-}
+    use crate::patterns::accessibility::ID_REGEX;
 
-// Regex to capture all key-value pairs in the tag
-static ATTRIBUTE_REGEX: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(
-        r#"(?:data-\w+|[a-zA-Z]+)(?:\s*=\s*(?:"[^"]*"|'[^']*'|\S+))?"#,
-    )
-    .unwrap()
-});
+    let id = match ID_REGEX.captures(input_tag) {
+        Some(id_match) => id_match[1].to_string(),
+        None => return false,
+    };
+    build_regex(&format!(
+        r#"<label\s+for="{}"\s*>"#,
+        regex::escape(&id)
+    ))
+    .map_or(false, |label| label.is_match(html_content))
+}
 
 /// Extract and preserve existing attributes from an input tag.
-fn preserve_attributes(input_tag: &str) -> String {
+fn preserve_attributes(
+    input_tag: &str,
+    policy: &AttributePolicy,
+) -> String {
     ATTRIBUTE_REGEX
         .captures_iter(input_tag)
+        .filter(|cap| policy.allows(&cap[1]))
         .map(|cap| cap[0].to_string())
         .collect::<Vec<String>>()
         .join(" ")
@@ -1495,10 +3710,7 @@ fn preserve_attributes(input_tag: &str) -> String {
 
 /// Extract input type from an input tag.
 fn extract_input_type(input_tag: &str) -> Option<String> {
-    static TYPE_REGEX: Lazy<Regex> = Lazy::new(|| {
-        Regex::new(r#"type=["']([^"']+)["']"#)
-            .expect("Failed to create type regex")
-    });
+    use crate::patterns::accessibility::TYPE_REGEX;
 
     TYPE_REGEX
         .captures(input_tag)
@@ -1508,7 +3720,30 @@ fn extract_input_type(input_tag: &str) -> Option<String> {
 
 /// Generate a unique ID prefixed with "aria-" and UUIDs.
 fn generate_unique_id() -> String {
-    format!("aria-{}", uuid::Uuid::new_v4())
+    format!("aria-{}", unique_id_suffix())
+}
+
+/// Returns a suffix for generated element IDs.
+///
+/// With the `std` feature this is a random UUID. Without it, a
+/// process-wide counter is used so that no random number source is
+/// required.
+#[cfg(feature = "std")]
+fn unique_id_suffix() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
+
+/// Returns a suffix for generated element IDs.
+///
+/// With the `std` feature this is a random UUID. Without it, a
+/// process-wide counter is used so that no random number source is
+/// required.
+#[cfg(not(feature = "std"))]
+fn unique_id_suffix() -> String {
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    format!("{:016x}", COUNTER.fetch_add(1, Ordering::Relaxed))
 }
 
 /// Validate ARIA attributes within the HTML.
@@ -1562,7 +3797,7 @@ fn is_valid_aria_attribute(name: &str, value: &str) -> bool {
 
     match name {
         "aria-hidden" | "aria-expanded" | "aria-pressed"
-        | "aria-invalid" => {
+        | "aria-invalid" | "aria-modal" => {
             matches!(value, "true" | "false") // Only "true" or "false" are valid
         }
         "aria-level" => value.parse::<u32>().is_ok(), // Must be a valid integer
@@ -1572,10 +3807,12 @@ fn is_valid_aria_attribute(name: &str, value: &str) -> bool {
 
 fn check_language_attributes(
     document: &Html,
+    source: &SourceMap<'_>,
     issues: &mut Vec<Issue>,
 ) -> Result<()> {
-    if let Some(html_element) =
-        document.select(&Selector::parse("html").unwrap()).next()
+    if let Some(html_element) = HTML_ELEMENT_SELECTOR
+        .as_ref()
+        .and_then(|selector| document.select(selector).next())
     {
         if html_element.value().attr("lang").is_none() {
             AccessibilityReport::add_issue(
@@ -1585,12 +3822,16 @@ fn check_language_attributes(
                 Some("WCAG 3.1.1".to_string()),
                 Some("<html>".to_string()),
                 Some("Add lang attribute to HTML element".to_string()),
+                source.locate(&html_element),
             );
         }
     }
 
-    for element in document.select(&Selector::parse("[lang]").unwrap())
-    {
+    let lang_selector = match LANG_SELECTOR.as_ref() {
+        Some(selector) => selector,
+        None => return Ok(()),
+    };
+    for element in document.select(lang_selector) {
         if let Some(lang) = element.value().attr("lang") {
             if !is_valid_language_code(lang) {
                 AccessibilityReport::add_issue(
@@ -1600,6 +3841,7 @@ fn check_language_attributes(
                     Some("WCAG 3.1.2".to_string()),
                     Some(element.html()),
                     Some("Use valid BCP 47 language code".to_string()),
+                    source.locate(&element),
                 );
             }
         }
@@ -1627,10 +3869,12 @@ pub fn check_keyboard_navigation(
                     if index < 0 {
                         issues.push(Issue {
                         issue_type: IssueType::KeyboardNavigation,
+                        severity: IssueType::KeyboardNavigation.default_severity(),
                         message: "Negative tabindex prevents keyboard focus".to_string(),
                         guideline: Some("WCAG 2.1.1".to_string()),
                         element: Some(element.html()),
                         suggestion: Some("Remove negative tabindex value".to_string()),
+                        location: None,
                     });
                     }
                 }
@@ -1643,6 +3887,8 @@ pub fn check_keyboard_navigation(
             {
                 issues.push(Issue {
                     issue_type: IssueType::KeyboardNavigation,
+                    severity: IssueType::KeyboardNavigation
+                        .default_severity(),
                     message:
                         "Click handler without keyboard equivalent"
                             .to_string(),
@@ -1651,6 +3897,7 @@ pub fn check_keyboard_navigation(
                     suggestion: Some(
                         "Add keyboard event handlers".to_string(),
                     ),
+                    location: None,
                 });
             }
         }
@@ -1677,6 +3924,7 @@ pub fn check_language_attributes(
                         "Add lang attribute to html element"
                             .to_string(),
                     ),
+                    None,
                 );
             }
         }
@@ -1697,6 +3945,7 @@ pub fn check_language_attributes(
                             "Use valid BCP 47 language code"
                                 .to_string(),
                         ),
+                        None,
                     );
                 }
             }
@@ -1725,6 +3974,7 @@ pub fn check_advanced_aria(
                         Some("WCAG 4.1.2".to_string()),
                         Some(element.html()),
                         Some("Use appropriate ARIA role".to_string()),
+                        None,
                     );
                 }
             }
@@ -1747,6 +3997,7 @@ pub fn check_advanced_aria(
                     Some("WCAG 4.1.2".to_string()),
                     Some(element.html()),
                     Some("Add required ARIA properties".to_string()),
+                    None,
                 );
             }
         }
@@ -1756,19 +4007,13 @@ pub fn check_advanced_aria(
 
 /// Utility functions for accessibility checks
 pub mod utils {
+    use once_cell::sync::Lazy;
     use scraper::ElementRef;
     use std::collections::HashMap;
 
-    /// Validate language code against BCP 47
-    use once_cell::sync::Lazy;
-    use regex::Regex;
-
     /// Validate language code against simplified BCP 47 rules.
     pub(crate) fn is_valid_language_code(lang: &str) -> bool {
-        static LANGUAGE_CODE_REGEX: Lazy<Regex> = Lazy::new(|| {
-            // Match primary language and optional subtags
-            Regex::new(r"(?i)^[a-z]{2,3}(-[a-z0-9]{2,8})*$").unwrap()
-        });
+        use crate::patterns::accessibility::LANGUAGE_CODE_REGEX;
 
         // Ensure the regex matches and the code does not end with a hyphen
         LANGUAGE_CODE_REGEX.is_match(lang) && !lang.ends_with('-')
@@ -1795,6 +4040,8 @@ pub(crate) fn is_valid_aria_role(
                     vec!["alert", "tooltip", "dialog", "slider"],
                 );
                 _ = map.insert("a", vec!["link", "button", "menuitem"]);
+                _ = map.insert("header", vec!["banner"]);
+                _ = map.insert("footer", vec!["contentinfo"]);
                 map
             });
 
@@ -1895,6 +4142,11 @@ fn test_custom_config() {
                 max_heading_jump: 2,
                 min_contrast_ratio: 7.0,
                 auto_fix: false,
+                strict_transforms: false,
+                hydration_attributes: false,
+                attribute_policy: AttributePolicy::default(),
+                severities: &[],
+                element_accounting: ElementAccounting::AllElements,
             };
             assert_eq!(config.wcag_level, WcagLevel::AAA);
             assert_eq!(config.max_heading_jump, 2);
@@ -1921,6 +4173,40 @@ fn test_empty_aria_value() {
             assert!(!is_valid_aria_attribute("aria-label", ""));
             assert!(!is_valid_aria_attribute("aria-label", "  "));
         }
+
+        #[test]
+        fn test_normalize_aria_label() {
+            assert_eq!(
+                normalize_aria_label(
+                    "<b>Save</b>  &amp; <i>close</i>!"
+                ),
+                "save-close"
+            );
+            assert_eq!(
+                normalize_aria_label("Caf&eacute; &#x26; bar"),
+                normalize_aria_label("Café & bar")
+            );
+            assert_eq!(
+                normalize_aria_label("Open -- menu"),
+                "open-menu"
+            );
+            assert_eq!(
+                normalize_aria_label("<span></span> "),
+                "button"
+            );
+            assert_eq!(
+                normalize_aria_label("Ünïcode Text"),
+                "ünïcode-text"
+            );
+        }
+
+        #[test]
+        fn test_strip_tags() {
+            assert_eq!(strip_tags("no tags"), "no tags");
+            assert_eq!(strip_tags("<a href=\"x\">A</a><br/>B"), "AB");
+            assert_eq!(strip_tags("a < b <c> d"), "a  d");
+            assert_eq!(strip_tags("x<y>z<unclosed"), "xz<unclosed");
+        }
     }
 
     // Test HTML modification functions
@@ -2299,6 +4585,11 @@ fn test_validate_wcag_with_minimal_config() {
                     max_heading_jump: 0, // No heading enforcement
                     min_contrast_ratio: 0.0, // No contrast enforcement
                     auto_fix: false,
+                    strict_transforms: false,
+                    hydration_attributes: false,
+                    attribute_policy: AttributePolicy::default(),
+                    severities: &[],
+                    element_accounting: ElementAccounting::AllElements,
                 };
                 let report =
                     validate_wcag(html, &config, None).unwrap();
@@ -2547,7 +4838,12 @@ fn test_validate_language_with_edge_cases() {
             let mut issues = Vec::new();
             let document = Html::parse_document(html);
 
-            check_language_attributes(&document, &mut issues).unwrap();
+            check_language_attributes(
+                &document,
+                &SourceMap::new(html, &document),
+                &mut issues,
+            )
+            .unwrap();
             assert_eq!(
                 issues.len(),
                 0,
@@ -2562,7 +4858,12 @@ fn test_validate_invalid_language_code() {
             let mut issues = Vec::new();
             let document = Html::parse_document(html);
 
-            check_language_attributes(&document, &mut issues).unwrap();
+            check_language_attributes(
+                &document,
+                &SourceMap::new(html, &document),
+                &mut issues,
+            )
+            .unwrap();
             assert!(
                 issues
                     .iter()
@@ -2879,7 +5180,11 @@ fn test_deeply_nested_headings() {
         "#;
             let mut issues = Vec::new();
             let document = Html::parse_document(html);
-            check_heading_structure(&document, &mut issues);
+            check_heading_structure(
+                &document,
+                &SourceMap::new(html, &document),
+                &mut issues,
+            );
 
             assert!(
             issues.iter().any(|issue| issue.issue_type == IssueType::HeadingStructure),
@@ -2945,12 +5250,14 @@ fn test_invalid_selector() {
         fn test_issue_type_in_issue_struct() {
             let issue = Issue {
                 issue_type: IssueType::MissingAltText,
+                severity: Severity::Error,
                 message: "Alt text is missing".to_string(),
                 guideline: Some("WCAG 1.1.1".to_string()),
                 element: Some("<img>".to_string()),
                 suggestion: Some(
                     "Add descriptive alt text".to_string(),
                 ),
+                location: None,
             };
             assert_eq!(issue.issue_type, IssueType::MissingAltText);
         }
@@ -3172,28 +5479,33 @@ fn test_has_associated_label() {
         fn test_preserve_attributes() {
             // Test with typical HTML attributes (type, class)
             let input = r#"<input type="text" class="form-control">"#;
-            let result = preserve_attributes(input);
+            let result =
+                preserve_attributes(input, &AttributePolicy::default());
             assert!(result.contains("type=\"text\""));
             assert!(result.contains("class=\"form-control\""));
 
             // Test single attributes
             let input = r#"<input type="text">"#;
-            let result = preserve_attributes(input);
+            let result =
+                preserve_attributes(input, &AttributePolicy::default());
             assert!(result.contains("type=\"text\""));
 
             // Test with single quotes
             let input = r#"<input type='text'>"#;
-            let result = preserve_attributes(input);
+            let result =
+                preserve_attributes(input, &AttributePolicy::default());
             assert!(result.contains("type='text'"));
 
             // Test boolean attributes
             let input = r#"<input required>"#;
-            let result = preserve_attributes(input);
+            let result =
+                preserve_attributes(input, &AttributePolicy::default());
             assert!(result.contains("required"));
 
             // Test with bare input tag
             let input = "<input>";
-            let result = preserve_attributes(input);
+            let result =
+                preserve_attributes(input, &AttributePolicy::default());
             assert!(
                 result.contains("input"),
                 "Should preserve the input tag name"
@@ -3201,7 +5513,8 @@ fn test_preserve_attributes() {
 
             // Test complex attribute values
             let input = r#"<input name="test" value="multiple words">"#;
-            let result = preserve_attributes(input);
+            let result =
+                preserve_attributes(input, &AttributePolicy::default());
             assert!(result.contains("name=\"test\""));
             assert!(result.contains("value=\"multiple words\""));
         }
@@ -3216,8 +5529,24 @@ fn test_preserve_attributes_with_data_attributes() {
                 .collect();
             println!("Actual matches: {:?}", matches);
 
-            let result = preserve_attributes(input);
+            let result =
+                preserve_attributes(input, &AttributePolicy::default());
             println!("Preserved attributes: {}", result);
+            assert!(result.contains(r#"data-test="value""#));
+
+            let input =
+                r#"<input data-user-id="5" x-on:change='go()'>"#;
+            let result =
+                preserve_attributes(input, &AttributePolicy::default());
+            assert_eq!(
+                result,
+                r#"input data-user-id="5" x-on:change='go()'"#
+            );
+            let policy = AttributePolicy {
+                preserve_data: false,
+                preserve: &[],
+            };
+            assert_eq!(preserve_attributes(input, &policy), "input");
         }
 
         #[test]
@@ -3517,9 +5846,12 @@ fn test_check_language_attributes_valid() {
             let html = r#"<html lang="en"><body></body></html>"#;
             let document = Html::parse_document(html);
             let mut issues = vec![];
-            let result =
-                check_language_attributes(&document, &mut issues);
-            assert!(result.is_ok());
+            let result = check_language_attributes(
+                &document,
+                &SourceMap::new(html, &document),
+                &mut issues,
+            );
+            assert!(result.is_ok());
             assert_eq!(issues.len(), 0, "No issues for valid lang");
         }
 
@@ -3757,9 +6089,7 @@ fn test_has_alert_class_sets_alertdialog() -> Result<()> {
                 <div class="modal-content"><h2>Warning</h2><button>OK</button></div>
             </div>
         "#;
-            let builder = HtmlBuilder {
-                content: original_html.to_string(),
-            };
+            let builder = HtmlBuilder::new(original_html);
 
             let result = add_aria_to_modals(builder)?;
             let output = result.content;
@@ -3784,9 +6114,7 @@ fn test_preserves_role_dialog() -> Result<()> {
                 <div class="modal-content"><button>Close</button></div>
             </div>
         "#;
-            let builder = HtmlBuilder {
-                content: original_html.to_string(),
-            };
+            let builder = HtmlBuilder::new(original_html);
 
             let result = add_aria_to_modals(builder)?;
             let output = result.content;
@@ -3812,9 +6140,7 @@ fn test_preserves_role_alertdialog() -> Result<()> {
                 <div class="modal-content"><h2>Warning</h2></div>
             </div>
         "#;
-            let builder = HtmlBuilder {
-                content: original_html.to_string(),
-            };
+            let builder = HtmlBuilder::new(original_html);
 
             let result = add_aria_to_modals(builder)?;
             let output = result.content;
@@ -3841,9 +6167,7 @@ fn test_already_has_aria_modal_does_not_duplicate() -> Result<()>
                 <div class="modal-content"><button>Close</button></div>
             </div>
         "#;
-            let builder = HtmlBuilder {
-                content: original_html.to_string(),
-            };
+            let builder = HtmlBuilder::new(original_html);
 
             let result = add_aria_to_modals(builder)?;
             let output = result.content;
@@ -3868,9 +6192,7 @@ fn test_adds_aria_describedby_for_dialog_description(
                 <div class="modal-content"><button>Close</button></div>
             </div>
         "#;
-            let builder = HtmlBuilder {
-                content: original_html.to_string(),
-            };
+            let builder = HtmlBuilder::new(original_html);
 
             let result = add_aria_to_modals(builder)?;
             let output = result.content;
@@ -3906,9 +6228,7 @@ fn test_dialog_description_missing_does_not_add_aria_describedby(
             </div>
         "#;
 
-            let builder = HtmlBuilder {
-                content: original_html.to_string(),
-            };
+            let builder = HtmlBuilder::new(original_html);
 
             let result = add_aria_to_modals(builder)?;
             let output = result.content;
@@ -3931,9 +6251,7 @@ fn test_paragraph_as_dialog_description() -> Result<()> {
             </div>
         "#;
 
-            let builder = HtmlBuilder {
-                content: original_html.to_string(),
-            };
+            let builder = HtmlBuilder::new(original_html);
 
             let result = add_aria_to_modals(builder)?;
             let output = result.content;
@@ -3952,4 +6270,1175 @@ fn test_paragraph_as_dialog_description() -> Result<()> {
             Ok(())
         }
     }
+
+    mod skip_diagnostics_tests {
+        use super::*;
+
+        #[test]
+        fn test_already_labeled_elements_are_reported() {
+            let html = concat!(
+                r#"<nav aria-label="Main"><a href="/">Home</a></nav>"#,
+                r#"<button aria-label="Close">X</button>"#,
+                r#"<input type="range" aria-label="Volume">"#
+            );
+            let (_, diagnostics) =
+                add_aria_attributes_with_diagnostics(html, None)
+                    .unwrap();
+            let skipped: Vec<_> = diagnostics
+                .iter()
+                .filter(|d| d.code == "aria_already_labeled")
+                .map(|d| d.message.split(':').next().unwrap())
+                .collect();
+            assert_eq!(skipped, vec!["buttons", "inputs", "navs"]);
+        }
+
+        #[test]
+        fn test_failed_replacement_is_reported() {
+            let mut builder = HtmlBuilder::new("<p>Text</p>");
+            builder
+                .replace_element(
+                    "buttons",
+                    "<button>Gone</button>",
+                    r#"<button aria-label="Gone">Gone</button>"#,
+                )
+                .unwrap();
+            builder
+                .replace_all("navs", "<nav></nav>", "<nav x></nav>")
+                .unwrap();
+
+            assert_eq!(builder.diagnostics.len(), 2);
+            assert!(builder
+                .diagnostics
+                .iter()
+                .all(|d| d.code == "aria_replacement_failed"
+                    && d.severity == Severity::Warning));
+            assert_eq!(builder.build(), "<p>Text</p>");
+        }
+
+        #[test]
+        fn test_enhanced_elements_are_not_reported() {
+            let (html, diagnostics) =
+                add_aria_attributes_with_diagnostics(
+                    "<button>Save</button>",
+                    None,
+                )
+                .unwrap();
+            assert!(html.contains("aria-label"));
+            assert!(diagnostics.is_empty());
+        }
+
+        #[test]
+        fn test_strict_transforms_fail_on_missed_replacement() {
+            let mut builder = HtmlBuilder::new("<p>Text</p>");
+            builder.strict = true;
+            let err = builder
+                .replace_element(
+                    "buttons",
+                    "<button>Gone</button>",
+                    "<button aria-label=\"Gone\">Gone</button>",
+                )
+                .unwrap_err();
+            assert!(matches!(
+                err,
+                Error::TransformFailed { ref pass, ref element }
+                    if pass == "buttons" && element == "<button>Gone</button>"
+            ));
+            assert!(builder.diagnostics.is_empty());
+        }
+
+        #[test]
+        fn test_strict_transforms_allow_successful_enhancement() {
+            let config = AccessibilityConfig {
+                strict_transforms: true,
+                ..Default::default()
+            };
+            let html =
+                r#"<nav><a href="/">Home</a></nav><button>Go</button>"#;
+            let result =
+                add_aria_attributes(html, Some(config)).unwrap();
+            assert!(result.contains("aria-label"));
+        }
+
+        #[test]
+        fn test_long_snippets_are_truncated() {
+            let diagnostic = SkipReason::ReplacementFailed
+                .to_diagnostic("modals", &"x".repeat(200));
+            assert!(diagnostic
+                .message
+                .contains(&format!("{}...", "x".repeat(80))));
+            assert!(!diagnostic.message.contains(&"x".repeat(81)));
+        }
+    }
+
+    mod hydration_tests {
+        use super::*;
+
+        fn widget_config(html: &str, widget: &str) -> Value {
+            let document = Html::parse_fragment(html);
+            let selector = Selector::parse(&format!(
+                r#"[data-hg-widget="{}"]"#,
+                widget
+            ))
+            .unwrap();
+            let element = document
+                .select(&selector)
+                .next()
+                .unwrap_or_else(|| panic!("no {} widget", widget));
+            serde_json::from_str(
+                element.value().attr("data-hg-config").unwrap(),
+            )
+            .unwrap()
+        }
+
+        fn hydrate(html: &str) -> String {
+            let config = AccessibilityConfig {
+                hydration_attributes: true,
+                ..Default::default()
+            };
+            add_aria_attributes(html, Some(config)).unwrap()
+        }
+
+        #[test]
+        fn test_tabs_and_accordions_are_marked() {
+            let html = hydrate(concat!(
+                r#"<div role="tablist"><button>A</button><button>B</button></div>"#,
+                r#"<div class="accordion"><button>One</button><div>1</div></div>"#
+            ));
+            assert_eq!(
+                widget_config(&html, "tabs"),
+                json!({
+                    "tabs": ["tab1", "tab2"],
+                    "panels": ["panel1", "panel2"],
+                    "selected": 0,
+                })
+            );
+            assert_eq!(
+                widget_config(&html, "accordion")["sections"][0]
+                    ["panel"],
+                "section-1-content"
+            );
+        }
+
+        #[test]
+        fn test_modals_are_marked_once() {
+            let html = hydrate(
+                r#"<div class="modal alert"><p id="d">Sure?</p></div>"#,
+            );
+            assert_eq!(
+                widget_config(&html, "modal"),
+                json!({
+                    "role": "alertdialog",
+                    "describedby": "d",
+                })
+            );
+            assert_eq!(html.matches(WIDGET_ATTRIBUTE).count(), 1);
+        }
+
+        #[test]
+        fn test_disabled_by_default() {
+            let html = add_aria_attributes(
+                r#"<div class="modal"><p>Hi</p></div><div role="tablist"><button>A</button></div>"#,
+                None,
+            )
+            .unwrap();
+            assert!(!html.contains(WIDGET_ATTRIBUTE));
+        }
+    }
+
+    mod attribute_policy_tests {
+        use super::*;
+
+        const WIDGETS: &str = concat!(
+            r#"<div role="tablist" data-tabs="main"><button data-tab="a">A</button><button>B</button></div>"#,
+            r#"<div class="accordion" data-faq="1"><button data-q="1">Q</button><div data-a="1">A</div></div>"#,
+            r#"<div class="modal" data-modal="m"><p>Sure?</p></div>"#,
+            r#"<button data-action="save" hx-post="/save">Save</button>"#,
+            r#"<input type="date" data-field-name="when">"#,
+            r#"<input type="checkbox" data-check="c" id="c">"#
+        );
+
+        const DATA_ATTRIBUTES: [&str; 9] = [
+            r#"data-tabs="main""#,
+            r#"data-tab="a""#,
+            r#"data-faq="1""#,
+            r#"data-q="1""#,
+            r#"data-a="1""#,
+            r#"data-modal="m""#,
+            r#"data-action="save""#,
+            r#"data-field-name="when""#,
+            r#"data-check="c""#,
+        ];
+
+        #[test]
+        fn test_passes_never_drop_data_attributes() {
+            for level in [WcagLevel::A, WcagLevel::AA, WcagLevel::AAA] {
+                let config = AccessibilityConfig {
+                    wcag_level: level,
+                    ..Default::default()
+                };
+                let html =
+                    add_aria_attributes(WIDGETS, Some(config)).unwrap();
+                for attribute in DATA_ATTRIBUTES {
+                    assert!(
+                        html.contains(attribute),
+                        "{} missing from {}",
+                        attribute,
+                        html
+                    );
+                }
+                assert!(html.contains(r#"hx-post="/save""#));
+            }
+        }
+
+        #[test]
+        fn test_policy_filters_custom_attributes() {
+            let config = AccessibilityConfig {
+                attribute_policy: AttributePolicy {
+                    preserve_data: true,
+                    preserve: &[],
+                },
+                ..Default::default()
+            };
+            let html =
+                add_aria_attributes(WIDGETS, Some(config)).unwrap();
+            assert!(html.contains(r#"data-action="save""#));
+            assert!(!html.contains("hx-post"));
+
+            let config = AccessibilityConfig {
+                attribute_policy: AttributePolicy {
+                    preserve_data: false,
+                    preserve: &["hx-*"],
+                },
+                ..Default::default()
+            };
+            let html =
+                add_aria_attributes(WIDGETS, Some(config)).unwrap();
+            assert!(!html.contains("data-action"));
+            assert!(html.contains(r#"hx-post="/save""#));
+        }
+
+        #[test]
+        fn test_attribute_values_are_escaped() {
+            let html = add_aria_attributes(
+                r#"<button data-json='{"a":"&amp;"}'>Go</button>"#,
+                None,
+            )
+            .unwrap();
+            let document = Html::parse_fragment(&html);
+            let button = document
+                .select(&Selector::parse("button").unwrap())
+                .next()
+                .unwrap();
+            assert_eq!(
+                button.value().attr("data-json"),
+                Some(r#"{"a":"&"}"#)
+            );
+        }
+    }
+
+    mod inline_whitespace_tests {
+        use super::*;
+
+        const INLINE: &str =
+            "<em>a</em><strong>b</strong> <code> c </code>\n<i>d</i>&nbsp;e";
+
+        #[test]
+        fn test_text_around_inline_elements_is_unchanged() {
+            let html = format!(
+                "<p>This field is required <em>now</em> {}</p><button disabled>Go</button>",
+                INLINE
+            );
+            let result = add_aria_attributes(&html, None).unwrap();
+            assert!(result.contains(&format!(
+                "<p>This field is required <em>now</em> {}</p>",
+                INLINE
+            )));
+            assert!(result.contains(r#"aria-disabled="true""#));
+        }
+
+        #[test]
+        fn test_rebuilt_elements_keep_inline_whitespace() {
+            let html = format!(
+                concat!(
+                    "<div class=\"modal\"><p id=\"d\">{0}</p><pre>\n\ncode</pre></div>",
+                    "<div class=\"accordion\"><button>{0}</button><div>{0}</div></div>",
+                    "<button>{0}</button>"
+                ),
+                INLINE
+            );
+            let result = add_aria_attributes(&html, None).unwrap();
+            assert_eq!(result.matches(INLINE).count(), 4, "{}", result);
+            assert!(result.contains("<pre>\n\ncode</pre>"));
+        }
+
+        #[test]
+        fn test_shorthand_normalization_only_touches_tags() {
+            assert_eq!(
+                normalize_shorthand_attributes(
+                    r#"<input required title="required field"> required <b>checked</b>"#
+                ),
+                r#"<input required="" title="required field"> required <b>checked</b>"#
+            );
+        }
+    }
+
+    mod severity_tests {
+        use super::*;
+
+        const PAGE: &str = concat!(
+            "<html><body><h1>Title</h1><h3>Skipped</h3>",
+            r#"<div lang="not a code">x</div></body></html>"#
+        );
+
+        #[test]
+        fn test_default_severities() {
+            let report = validate_wcag(
+                PAGE,
+                &AccessibilityConfig::default(),
+                None,
+            )
+            .unwrap();
+            assert!(report.issue_count >= 3);
+            assert!(!report.has_errors());
+            assert_eq!(report.warnings().count(), report.issue_count);
+            assert_eq!(
+                IssueType::MissingAltText.default_severity(),
+                Severity::Error
+            );
+        }
+
+        #[test]
+        fn test_severity_overrides() {
+            let config = AccessibilityConfig {
+                severities: &[
+                    (IssueType::HeadingStructure, Severity::Error),
+                    (IssueType::LanguageDeclaration, Severity::Info),
+                ],
+                ..Default::default()
+            };
+            let report = validate_wcag(PAGE, &config, None).unwrap();
+            assert!(report.has_errors());
+            assert!(report
+                .errors()
+                .all(|issue| issue.issue_type
+                    == IssueType::HeadingStructure));
+            assert_eq!(report.warnings().count(), 0);
+            assert_eq!(
+                report.by_severity(Severity::Info).count(),
+                report.issue_count - 1
+            );
+        }
+
+        #[test]
+        fn test_by_guideline() {
+            let report = validate_wcag(
+                PAGE,
+                &AccessibilityConfig::default(),
+                None,
+            )
+            .unwrap();
+            let groups = report.by_guideline();
+            assert_eq!(groups[&Some("WCAG 2.4.6")].len(), 1);
+            assert_eq!(groups[&Some("WCAG 3.1.1")].len(), 1);
+            assert_eq!(groups[&Some("WCAG 3.1.2")].len(), 1);
+        }
+    }
+
+    mod grouping_tests {
+        use super::*;
+
+        fn report(count: usize) -> AccessibilityReport {
+            let mut issues = Vec::new();
+            for index in 0..count {
+                AccessibilityReport::add_issue(
+                    &mut issues,
+                    IssueType::MissingAltText,
+                    "Image is missing alt text",
+                    Some("WCAG 1.1.1".to_string()),
+                    Some(format!("<img src=\"{}.png\">", index)),
+                    Some("Add descriptive alt text".to_string()),
+                    None,
+                );
+            }
+            AccessibilityReport::add_issue(
+                &mut issues,
+                IssueType::MissingAltText,
+                "Image is missing alt text",
+                None,
+                None,
+                Some(
+                    "Mark decorative images with alt=\"\"".to_string(),
+                ),
+                None,
+            );
+            AccessibilityReport {
+                issue_count: issues.len(),
+                issues,
+                wcag_level: WcagLevel::AA,
+                elements_checked: count + 1,
+                elements_total: count + 1,
+                elements_examined: count + 1,
+                coverage: Vec::new(),
+                check_duration_ms: 0,
+            }
+        }
+
+        #[test]
+        fn test_groups_by_type_and_suggestion() {
+            let report = report(200);
+            let groups = report.grouped(false);
+            assert_eq!(groups.len(), 2);
+            assert_eq!(groups[0].count, 200);
+            assert_eq!(groups[0].examples.len(), MAX_GROUP_EXAMPLES);
+            assert_eq!(
+                groups[0].examples[1].element.as_deref(),
+                Some("<img src=\"1.png\">")
+            );
+            assert_eq!(groups[1].count, 1);
+            assert_eq!(
+                groups[1].to_string(),
+                "error[missing_alt_text]: Image is missing alt text (1 occurrence)"
+            );
+        }
+
+        #[test]
+        fn test_expanded_groups_keep_every_issue() {
+            let report = report(10);
+            let groups = report.grouped(true);
+            assert_eq!(groups[0].count, 10);
+            assert_eq!(groups[0].examples.len(), 10);
+            assert!(report.grouped(false).len() == groups.len());
+        }
+    }
+
+    mod batch_validation_tests {
+        use super::*;
+
+        #[test]
+        fn test_aggregates_pages_in_input_order() {
+            let sources: Vec<_> = (0..25)
+                .map(|index| WcagSource::Html {
+                    name: format!("{}.html", index),
+                    html: if index % 5 == 0 {
+                        "<html><h1>A</h1><h4>B</h4></html>".to_string()
+                    } else {
+                        r#"<html lang="en"><p>ok</p></html>"#
+                            .to_string()
+                    },
+                })
+                .collect();
+            let site = validate_wcag_batch(
+                sources,
+                &AccessibilityConfig::default(),
+            );
+            let names: Vec<_> = site
+                .pages
+                .iter()
+                .map(|page| page.name.clone())
+                .collect();
+            let expected: Vec<_> = (0..25)
+                .map(|index| format!("{}.html", index))
+                .collect();
+            assert_eq!(names, expected);
+            assert_eq!(site.issue_count(), 10);
+            assert!(site.elements_checked() > 0);
+            assert!(!site.has_errors());
+        }
+
+        #[test]
+        fn test_unreadable_files_fail_their_own_page() {
+            let site = validate_wcag_batch(
+                vec![
+                    WcagSource::File(PathBuf::from(
+                        "does/not/exist.html",
+                    )),
+                    WcagSource::Html {
+                        name: "inline".to_string(),
+                        html: "<p>text</p>".to_string(),
+                    },
+                ],
+                &AccessibilityConfig::default(),
+            );
+            let failures: Vec<_> = site.failures().collect();
+            assert_eq!(failures.len(), 1);
+            assert_eq!(failures[0].0, "does/not/exist.html");
+            assert_eq!(site.reports().count(), 1);
+            assert!(site.has_errors());
+            assert!(validate_wcag_batch(
+                Vec::new(),
+                &AccessibilityConfig::default()
+            )
+            .pages
+            .is_empty());
+        }
+    }
+
+    mod alt_text_tests {
+        use super::*;
+
+        fn alt_issues(html: &str) -> Vec<Issue> {
+            validate_wcag(html, &AccessibilityConfig::default(), None)
+                .unwrap()
+                .issues
+                .into_iter()
+                .filter(|issue| {
+                    issue.issue_type == IssueType::MissingAltText
+                })
+                .collect()
+        }
+
+        #[test]
+        fn test_missing_empty_and_placeholder_alt_text() {
+            let issues = alt_issues(concat!(
+                r#"<img src="a.png">"#,
+                r#"<img src="b.png" alt=" ">"#,
+                r#"<img src="c.png" alt="Image">"#,
+                r#"<img src="photos/IMG_0042.JPG?w=2" alt="IMG_0042.jpg">"#,
+                r#"<img src="d.png" alt="A red bicycle against a wall">"#,
+            ));
+            let messages: Vec<_> = issues
+                .iter()
+                .map(|issue| issue.message.as_str())
+                .collect();
+            assert_eq!(
+                messages,
+                [
+                    "Image is missing alt text",
+                    "Image has empty alt text but is not marked decorative",
+                    "Alt text 'Image' does not describe the image",
+                    "Alt text 'IMG_0042.jpg' does not describe the image",
+                ]
+            );
+            assert!(issues.iter().all(|issue| {
+                issue.guideline.as_deref() == Some("WCAG 1.1.1")
+                    && issue.severity == Severity::Error
+            }));
+            assert_eq!(issues[0].location.unwrap().column, 1);
+        }
+
+        #[test]
+        fn test_decorative_and_labelled_images_pass() {
+            assert!(alt_issues(concat!(
+                r#"<img src="a.png" alt="" role="presentation">"#,
+                r#"<img src="b.png" alt="" role="none">"#,
+                r#"<img src="c.png" aria-label="Company logo">"#,
+                r#"<img src="d.png" alt="" aria-labelledby="caption">"#,
+            ))
+            .is_empty());
+            assert_eq!(
+                alt_issues(r#"<img src="e.png" role="presentation">"#)
+                    .len(),
+                1
+            );
+        }
+
+        #[test]
+        fn test_alt_text_check_can_be_disabled() {
+            let report = validate_wcag(
+                r#"<img src="a.png">"#,
+                &AccessibilityConfig::default(),
+                Some(&[IssueType::MissingAltText]),
+            )
+            .unwrap();
+            assert!(report
+                .issues
+                .iter()
+                .all(|issue| issue.issue_type
+                    != IssueType::MissingAltText));
+            let coverage = report
+                .check_coverage(IssueType::MissingAltText)
+                .unwrap();
+            assert_eq!(coverage.status, CheckStatus::Disabled);
+
+            let report = validate_wcag(
+                r#"<img src="a.png" alt="A"><img src="b.png" alt="B">"#,
+                &AccessibilityConfig::default(),
+                None,
+            )
+            .unwrap();
+            let coverage = report
+                .check_coverage(IssueType::MissingAltText)
+                .unwrap();
+            assert_eq!(coverage.status, CheckStatus::Ran);
+            assert_eq!(coverage.elements_examined, 2);
+        }
+    }
+
+    mod math_tests {
+        use super::*;
+
+        fn math_issues(html: &str) -> Vec<Issue> {
+            validate_wcag(html, &AccessibilityConfig::default(), None)
+                .unwrap()
+                .issues
+                .into_iter()
+                .filter(|issue| {
+                    issue.issue_type == IssueType::InaccessibleMath
+                })
+                .collect()
+        }
+
+        #[test]
+        fn test_span_only_and_image_only_formulas() {
+            let issues = math_issues(concat!(
+                r#"<p><span class="math inline">\(x^2\)</span></p>"#,
+                r#"<div class="math display"><img src="f.png"></div>"#,
+                r#"<img class="math" src="g.png" alt="">"#,
+            ));
+            assert_eq!(issues.len(), 3);
+            assert_eq!(
+                issues[0].message,
+                r"Formula '\(x^2\)' is only TeX source, without MathML or an aria-label"
+            );
+            assert_eq!(
+                issues[0].guideline.as_deref(),
+                Some("WCAG 1.3.1")
+            );
+            assert_eq!(
+                issues[1].guideline.as_deref(),
+                Some("WCAG 1.1.1")
+            );
+            assert_eq!(issues[2].severity, Severity::Warning);
+            assert!(issues[2].location.is_some());
+        }
+
+        #[test]
+        fn test_formulas_with_alternatives_pass() {
+            assert!(math_issues(concat!(
+                r#"<span class="katex"><span class="katex-mathml"><math><mi>x</mi></math></span>"#,
+                r#"<span class="katex-html" aria-hidden="true">x</span></span>"#,
+                r#"<mjx-container><mjx-assistive-mml><math><mn>1</mn></math></mjx-assistive-mml></mjx-container>"#,
+                r#"<span class="math inline" aria-label="x squared">\(x^2\)</span>"#,
+                r#"<div class="math display"><img src="f.png" alt="a over b"></div>"#,
+                r#"<p>No formulas here</p>"#,
+            ))
+            .is_empty());
+        }
+
+        #[test]
+        fn test_generated_math_is_reported_once() {
+            let config = crate::HtmlConfig {
+                math: true,
+                ..crate::HtmlConfig::default()
+            };
+            let html =
+                crate::conversion::convert("Area: $\\pi r^2$", &config)
+                    .unwrap();
+            let report = validate_wcag(
+                &html,
+                &AccessibilityConfig::default(),
+                None,
+            )
+            .unwrap();
+            assert_eq!(math_issues(&html).len(), 1);
+            let coverage = report
+                .check_coverage(IssueType::InaccessibleMath)
+                .unwrap();
+            assert_eq!(coverage.status, CheckStatus::Ran);
+            assert_eq!(coverage.elements_examined, 1);
+        }
+    }
+
+    mod landmark_tests {
+        use super::*;
+
+        fn enhance(html: &str) -> (String, Vec<Diagnostic>) {
+            let builder =
+                enhance_landmarks(HtmlBuilder::new(html)).unwrap();
+            (builder.content.clone(), builder.diagnostics)
+        }
+
+        #[test]
+        fn test_wraps_main_content() {
+            let html = "<html><body>\n<header><h1>Site</h1></header>\n<nav><a href=\"/\">Home</a></nav>\n<h2>Intro</h2>\n<p>Text</p>\n<footer>Legal</footer>\n<script src=\"app.js\"></script>\n</body></html>";
+            let (enhanced, diagnostics) = enhance(html);
+            assert_eq!(
+                enhanced,
+                "<html><body>\n<header role=\"banner\"><h1>Site</h1></header>\n<nav><a href=\"/\">Home</a></nav>\n<main><h2>Intro</h2>\n<p>Text</p></main>\n<footer role=\"contentinfo\">Legal</footer>\n<script src=\"app.js\"></script>\n</body></html>"
+            );
+            assert!(diagnostics.is_empty());
+
+            let unclosed = enhance("<body><p>Text</p>").0;
+            assert_eq!(unclosed, "<body><main><p>Text</p></main>");
+        }
+
+        #[test]
+        fn test_keeps_existing_main_and_fragments() {
+            for html in [
+                "<body><div role=\"main\"><p>Text</p></div></body>",
+                "<p>Text</p><footer>Legal</footer>",
+            ] {
+                let enhanced = enhance(html).0;
+                assert!(!enhanced.contains("<main>"), "{}", enhanced);
+            }
+            assert_eq!(
+                enhance("<p>Text</p><footer>Legal</footer>").0,
+                "<p>Text</p><footer role=\"contentinfo\">Legal</footer>"
+            );
+        }
+
+        #[test]
+        fn test_scoped_and_duplicate_landmarks() {
+            let html = "<header>A</header><article><header>B</header></article><header>C</header><main></main><main></main>";
+            let (enhanced, diagnostics) = enhance(html);
+            assert!(enhanced.starts_with("<header role=\"banner\">A"));
+            assert!(enhanced.contains("<article><header>B"));
+            assert!(enhanced.contains("<header>C"));
+            let codes: Vec<_> = diagnostics
+                .iter()
+                .map(|diagnostic| diagnostic.code.as_str())
+                .collect();
+            assert_eq!(
+                codes,
+                ["aria_duplicate_landmark", "aria_duplicate_landmark"]
+            );
+            assert!(diagnostics[1].message.contains("<header>C"));
+        }
+
+        #[test]
+        fn test_distinct_nav_labels() {
+            let html = r#"<html lang="en"><body><nav>A</nav><p>Text</p><nav aria-label="Navigation 2" class="x">B</nav><footer><nav>C</nav></footer></body></html>"#;
+            let enhanced = add_aria_attributes(html, None).unwrap();
+            let document = Html::parse_document(&enhanced);
+            let selector = Selector::parse("nav").unwrap();
+            let labels: Vec<_> = document
+                .select(&selector)
+                .map(|nav| nav.value().attr("aria-label").unwrap())
+                .collect();
+            assert_eq!(
+                labels,
+                ["navigation", "Navigation 2", "navigation 3"]
+            );
+            assert!(enhanced.contains("<main><p>Text</p>"));
+            assert!(enhanced.contains("<footer role=\"contentinfo\">"));
+        }
+    }
+
+    mod location_tests {
+        use super::*;
+
+        fn locations(html: &str) -> Vec<(IssueType, Option<String>)> {
+            validate_wcag(html, &AccessibilityConfig::default(), None)
+                .unwrap()
+                .issues
+                .iter()
+                .map(|issue| {
+                    (
+                        issue.issue_type,
+                        issue
+                            .location
+                            .map(|location| location.to_string()),
+                    )
+                })
+                .collect()
+        }
+
+        #[test]
+        fn test_issues_are_located_on_their_start_tags() {
+            let html = "<html lang=\"en\">\n<body>\n  <h1>A</h1>\n  \
+                        <h1>B</h1> <h4>C</h4>\n  <p lang=\"x-\">é <a href=\"/\">click here</a></p>\n";
+            assert_eq!(
+                locations(html),
+                [
+                    (
+                        IssueType::LanguageDeclaration,
+                        Some("5:3".to_string())
+                    ),
+                    (
+                        IssueType::HeadingStructure,
+                        Some("4:14".to_string())
+                    ),
+                    (IssueType::LinkPurpose, Some("5:18".to_string())),
+                ]
+            );
+        }
+
+        #[test]
+        fn test_implied_elements_have_no_location() {
+            let issues = validate_wcag(
+                "<p>Hi</p>",
+                &AccessibilityConfig::default(),
+                None,
+            )
+            .unwrap()
+            .issues;
+            assert_eq!(
+                issues[0].issue_type,
+                IssueType::LanguageDeclaration
+            );
+            assert_eq!(issues[0].location, None);
+        }
+
+        #[test]
+        fn test_tags_in_comments_and_raw_text_are_skipped() {
+            let html = "<!-- <h1>x</h1> --><script>if (a <h2) {}</script>\n\
+                        <style>/* <h5> */</style><title><h6></title>\
+                        <table><tr><td><h2>A</h2></td></tr></table><h4 class='>'>B</h4>";
+            let issue = validate_wcag(
+                html,
+                &AccessibilityConfig::default(),
+                Some(&[IssueType::LanguageDeclaration]),
+            )
+            .unwrap()
+            .issues
+            .remove(0);
+            assert_eq!(issue.issue_type, IssueType::HeadingStructure);
+            let location = issue.location.unwrap();
+            assert_eq!(
+                &html[location.offset..location.offset + 3],
+                "<h4"
+            );
+            assert_eq!(location.line, 2);
+        }
+
+        #[test]
+        fn test_location_from_offset() {
+            let location = SourceLocation::from_offset("ab\néé<p>", 7);
+            assert_eq!(
+                location,
+                SourceLocation {
+                    line: 2,
+                    column: 3,
+                    offset: 7
+                }
+            );
+            assert_eq!(
+                SourceLocation::from_offset("", 0).to_string(),
+                "1:1"
+            );
+        }
+
+        #[test]
+        fn test_locations_in_json_and_sarif() {
+            let report = validate_wcag(
+                "<html lang=\"en\">\n<h1>A</h1>\n<h3>B</h3>",
+                &AccessibilityConfig::default(),
+                None,
+            )
+            .unwrap();
+            let json = report.to_json();
+            assert_eq!(json["issues"][0]["location"]["line"], 3);
+            assert_eq!(json["issues"][0]["location"]["column"], 1);
+            let sarif = report.to_sarif("index.html");
+            let region = &sarif["runs"][0]["results"][0]["locations"]
+                [0]["physicalLocation"]["region"];
+            assert_eq!(region["startLine"], 3);
+            assert_eq!(region["startColumn"], 1);
+        }
+    }
+
+    mod ci_output_tests {
+        use super::*;
+
+        fn report() -> AccessibilityReport {
+            validate_wcag(
+                "<html><h1>A</h1><h4>B</h4></html>",
+                &AccessibilityConfig::default(),
+                None,
+            )
+            .unwrap()
+        }
+
+        #[test]
+        fn test_to_json() {
+            let report = report();
+            let json = report.to_json();
+            assert_eq!(json["issue_count"], report.issue_count);
+            assert_eq!(json["wcag_level"], "AA");
+            let types: Vec<_> = json["issues"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|issue| issue["issue_type"].as_str().unwrap())
+                .collect();
+            assert!(types.contains(&"heading_structure"));
+            assert!(types.contains(&"language_declaration"));
+            assert_eq!(
+                json["coverage"].as_array().unwrap().len(),
+                report.coverage.len()
+            );
+            assert!(json["coverage"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .all(|coverage| coverage["status"].is_string()));
+        }
+
+        #[test]
+        fn test_to_sarif() {
+            let report = report();
+            let sarif = report.to_sarif("docs/index.html");
+            assert_eq!(sarif["version"], SARIF_VERSION);
+            assert_eq!(sarif["$schema"], SARIF_SCHEMA);
+            let run = &sarif["runs"][0];
+            let rules =
+                run["tool"]["driver"]["rules"].as_array().unwrap();
+            assert_eq!(rules.len(), IssueType::ALL.len());
+            assert_eq!(
+                run["invocations"][0]["executionSuccessful"],
+                true
+            );
+
+            let results = run["results"].as_array().unwrap();
+            assert_eq!(results.len(), report.issues.len());
+            for (result, issue) in results.iter().zip(&report.issues) {
+                assert_eq!(result["ruleId"], issue.issue_type.code());
+                let rule = &rules
+                    [result["ruleIndex"].as_u64().unwrap() as usize];
+                assert_eq!(rule["id"], result["ruleId"]);
+                assert_eq!(result["message"]["text"], issue.message);
+                assert_eq!(
+                    result["locations"][0]["physicalLocation"]
+                        ["artifactLocation"]["uri"],
+                    "docs/index.html"
+                );
+            }
+            let language = results
+                .iter()
+                .find(|result| {
+                    result["ruleId"] == "language_declaration"
+                })
+                .unwrap();
+            assert_eq!(language["level"], "warning");
+            assert_eq!(sarif_level(Severity::Info), "note");
+        }
+
+        #[test]
+        fn test_site_report_to_sarif() {
+            let site = validate_wcag_batch(
+                vec![
+                    WcagSource::Html {
+                        name: "a.html".to_string(),
+                        html: "<p>text</p>".to_string(),
+                    },
+                    WcagSource::File(PathBuf::from("missing.html")),
+                ],
+                &AccessibilityConfig::default(),
+            );
+            let sarif = site.to_sarif();
+            let run = &sarif["runs"][0];
+            assert!(run["results"].as_array().unwrap().iter().all(
+                |result| result["locations"][0]["physicalLocation"]
+                    ["artifactLocation"]["uri"]
+                    == "a.html"
+            ));
+            assert_eq!(run["results"].as_array().unwrap().len(), 1);
+            let invocation = &run["invocations"][0];
+            assert_eq!(invocation["executionSuccessful"], false);
+            let notification =
+                &invocation["toolExecutionNotifications"][0];
+            assert_eq!(notification["level"], "error");
+            assert!(notification["message"]["text"]
+                .as_str()
+                .unwrap()
+                .starts_with("missing.html: "));
+        }
+    }
+
+    mod coverage_tests {
+        use super::*;
+
+        const PAGE: &str = concat!(
+            r#"<html lang="en"><body><h1>A</h1><p>Text</p>"#,
+            r#"<div lang="fr"><h2>B</h2></div></body></html>"#
+        );
+
+        #[test]
+        fn test_reports_per_check_coverage() {
+            let report = validate_wcag(
+                PAGE,
+                &AccessibilityConfig::default(),
+                None,
+            )
+            .unwrap();
+            assert_eq!(report.coverage.len(), IssueType::ALL.len());
+            let language = report
+                .check_coverage(IssueType::LanguageDeclaration)
+                .unwrap();
+            assert_eq!(language.status, CheckStatus::Ran);
+            assert_eq!(language.elements_examined, 2);
+            let headings = report
+                .check_coverage(IssueType::HeadingStructure)
+                .unwrap();
+            assert_eq!(headings.elements_examined, 2);
+            assert_eq!(
+                report
+                    .check_coverage(IssueType::ColorContrast)
+                    .unwrap()
+                    .status,
+                CheckStatus::Unsupported
+            );
+
+            // html, head, body, h1, p, div, h2
+            assert_eq!(report.elements_total, 7);
+            assert_eq!(report.elements_checked, 7);
+            assert_eq!(report.elements_examined, 5);
+            assert!(
+                (report.audited_fraction() - 5.0 / 7.0).abs() < 1e-9
+            );
+        }
+
+        #[test]
+        fn test_disabled_checks_and_examined_accounting() {
+            let config = AccessibilityConfig {
+                element_accounting: ElementAccounting::Examined,
+                ..Default::default()
+            };
+            let report = validate_wcag(
+                "<html><h1>A</h1><h3>B</h3></html>",
+                &config,
+                Some(&[
+                    IssueType::HeadingStructure,
+                    IssueType::DuplicateHeadings,
+                    IssueType::MixedDirection,
+                ]),
+            )
+            .unwrap();
+            assert_eq!(
+                report
+                    .check_coverage(IssueType::HeadingStructure)
+                    .unwrap()
+                    .status,
+                CheckStatus::Disabled
+            );
+            assert!(report
+                .issues
+                .iter()
+                .all(|issue| issue.issue_type
+                    != IssueType::HeadingStructure));
+            assert_eq!(report.elements_checked, 1);
+            assert_eq!(report.elements_examined, 1);
+        }
+    }
+
+    mod content_tests {
+        use super::*;
+
+        fn issues(html: &str, issue_type: IssueType) -> Vec<Issue> {
+            validate_wcag(html, &AccessibilityConfig::default(), None)
+                .unwrap()
+                .issues
+                .into_iter()
+                .filter(|issue| issue.issue_type == issue_type)
+                .collect()
+        }
+
+        #[test]
+        fn test_duplicate_heading_text() {
+            let found = issues(
+                concat!(
+                    "<h1>Guide</h1><h2>Setup</h2><h3>Linux</h3>",
+                    "<h2> setup </h2><h2>Usage</h2><h2>SETUP</h2>"
+                ),
+                IssueType::DuplicateHeadings,
+            );
+            assert_eq!(found.len(), 2);
+            assert_eq!(
+                found[0].message,
+                "Heading text 'setup' is already used by an earlier h2"
+            );
+            assert_eq!(
+                found[0].guideline.as_deref(),
+                Some("WCAG 2.4.6")
+            );
+            assert!(found[0].suggestion.is_some());
+        }
+
+        #[test]
+        fn test_generic_and_url_link_text() {
+            let found = issues(
+                concat!(
+                    r#"<a href="/a">Click here</a>"#,
+                    r#"<a href="/b">Read more…</a>"#,
+                    r#"<a href="/c">read more.</a>"#,
+                    r#"<a href="https://x.org/p">https://x.org/p</a>"#,
+                    r#"<a href="/d">www.example.com</a>"#,
+                    r#"<a href="/e" aria-label="Pricing">here</a>"#,
+                    r#"<a href="/f"><img src="l.png" alt="Home"></a>"#,
+                    r#"<a href="/g">Installation guide</a>"#
+                ),
+                IssueType::LinkPurpose,
+            );
+            let messages: Vec<_> = found
+                .iter()
+                .map(|issue| issue.message.as_str())
+                .collect();
+            assert_eq!(
+                messages,
+                [
+                    "Link text 'Click here' does not describe its target",
+                    "Link text 'Read more…' does not describe its target",
+                    "Link text 'read more.' does not describe its target",
+                    "Link is named by its URL 'https://x.org/p'",
+                    "Link is named by its URL 'www.example.com'",
+                ]
+            );
+            assert!(found.iter().all(|issue| issue.severity
+                == Severity::Warning
+                && issue.guideline.as_deref() == Some("WCAG 2.4.4")));
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    mod serde_tests {
+        use super::*;
+        use serde_json::json;
+
+        #[test]
+        fn test_report_serializes_with_codes() {
+            let report = validate_wcag(
+                "<html><p>Hi</p></html>",
+                &AccessibilityConfig::default(),
+                None,
+            )
+            .unwrap();
+            let value = serde_json::to_value(&report).unwrap();
+            assert_eq!(value["wcag_level"], "AA");
+            assert_eq!(
+                value["issues"][0]["issue_type"],
+                IssueType::LanguageDeclaration.code()
+            );
+            assert_eq!(value["issues"][0]["severity"], "warning");
+            let language = value["coverage"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .find(|c| c["issue_type"] == "language_declaration")
+                .unwrap();
+            assert_eq!(language["status"], "ran");
+
+            let parsed: AccessibilityReport =
+                serde_json::from_value(value).unwrap();
+            assert_eq!(parsed.issues.len(), report.issues.len());
+            assert_eq!(parsed.coverage, report.coverage);
+        }
+
+        #[test]
+        fn test_config_layers_over_defaults() {
+            let config: AccessibilityConfig =
+                serde_json::from_value(json!({
+                    "wcag_level": "AAA",
+                    "element_accounting": "examined",
+                    "attribute_policy": {"preserve_data": false}
+                }))
+                .unwrap();
+            assert_eq!(config.wcag_level, WcagLevel::AAA);
+            assert_eq!(
+                config.element_accounting,
+                ElementAccounting::Examined
+            );
+            assert!(!config.attribute_policy.preserve_data);
+            assert_eq!(config.attribute_policy.preserve, ["*"]);
+            assert_eq!(config.max_heading_jump, 1);
+
+            let value =
+                serde_json::to_value(AccessibilityConfig::default())
+                    .unwrap();
+            assert_eq!(value["severities"], json!([]));
+            assert!(serde_json::from_value::<AccessibilityConfig>(
+                json!({"level": "AA"})
+            )
+            .is_err());
+        }
+    }
 }
@@ -0,0 +1,358 @@
+// Copyright © 2025 HTML Generator. All rights reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Admonitions: notes, tips, warnings and other callout blocks.
+//!
+//! With [`HtmlConfig::admonitions`](crate::HtmlConfig::admonitions) set,
+//! triple-colon containers are rendered through an
+//! [`AdmonitionRegistry`]:
+//!
+//! ```markdown
+//! :::warning Back up **first**
+//! Formatting erases the disk.
+//!
+//! :::tip-
+//! Hidden until the reader opens it.
+//! :::
+//! :::
+//! ```
+//!
+//! The text after the type is the title, which may contain inline
+//! Markdown; without one, the title of the registered type is used.
+//! Blocks nest, and their content is converted with the rest of the
+//! document, so lists, code and tables work inside them. A `-` after
+//! the type renders a collapsed `<details>` element, and a `+` an
+//! expanded one.
+//!
+//! Types without an entry in the registry are rendered like the
+//! registered ones, titled with their capitalized name.
+//!
+//! # Examples
+//!
+//! ```
+//! use html_generator::admonitions::{Admonition, AdmonitionRegistry};
+//! use html_generator::{conversion::convert, HtmlConfig};
+//!
+//! let mut registry = AdmonitionRegistry::bundled();
+//! registry.insert(
+//!     Admonition::new("aside", "Aside")
+//!         .with_template("<aside class=\"{kind}\">\n<h4>{title}</h4>\n{body}\n</aside>"),
+//! );
+//! let config = HtmlConfig {
+//!     admonitions: Some(registry),
+//!     ..HtmlConfig::default()
+//! };
+//!
+//! let html = convert(":::note\nRead this.\n:::", &config)?;
+//! assert!(html.starts_with("<div class=\"admonition note\">\n<p class=\"admonition-title\">"));
+//!
+//! let html = convert(":::aside By the way\nText.\n:::", &config)?;
+//! assert_eq!(html, "<aside class=\"aside\">\n<h4>By the way</h4>\n<p>Text.</p>\n</aside>\n");
+//! # Ok::<(), html_generator::error::HtmlError>(())
+//! ```
+
+use crate::containers::find_containers;
+use crate::generator::process_markdown_inline;
+use std::collections::HashMap;
+
+/// Template of admonitions without a template of their own.
+pub const DEFAULT_TEMPLATE: &str = "<div class=\"admonition {kind}\">\n<p class=\"admonition-title\">{icon}{title}</p>\n{body}\n</div>";
+
+/// Template of collapsible admonitions, marked with `+` or `-`.
+pub const COLLAPSIBLE_TEMPLATE: &str = "<details class=\"admonition {kind}\"{open}>\n<summary class=\"admonition-title\">{icon}{title}</summary>\n{body}\n</details>";
+
+/// A type of admonition.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Admonition {
+    /// The name following `:::`, e.g. `warning`; also its CSS class.
+    pub kind: String,
+    /// Title of blocks that do not have one.
+    pub title: String,
+    /// Text or HTML shown before the title, e.g. an emoji or an SVG.
+    pub icon: Option<String>,
+    /// HTML with the placeholders `{kind}`, `{title}`, `{icon}` and
+    /// `{body}`, replacing [`DEFAULT_TEMPLATE`]. The content is Markdown
+    /// converted with the rest of the document, so `{body}` should
+    /// stand on a line of its own between block-level tags. Collapsible
+    /// blocks always use [`COLLAPSIBLE_TEMPLATE`].
+    pub template: Option<String>,
+}
+
+impl Admonition {
+    /// Creates an admonition type without icon or template.
+    ///
+    /// # Arguments
+    ///
+    /// * `kind` - The name following `:::`
+    /// * `title` - Title of blocks that do not have one
+    pub fn new(
+        kind: impl Into<String>,
+        title: impl Into<String>,
+    ) -> Self {
+        Self {
+            kind: kind.into(),
+            title: title.into(),
+            icon: None,
+            template: None,
+        }
+    }
+
+    /// Sets the icon shown before the title.
+    #[must_use]
+    pub fn with_icon(mut self, icon: impl Into<String>) -> Self {
+        self.icon = Some(icon.into());
+        self
+    }
+
+    /// Sets the HTML template, see [`Admonition::template`].
+    #[must_use]
+    pub fn with_template(
+        mut self,
+        template: impl Into<String>,
+    ) -> Self {
+        self.template = Some(template.into());
+        self
+    }
+}
+
+/// Admonition types keyed by name.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AdmonitionRegistry {
+    entries: HashMap<String, Admonition>,
+}
+
+impl AdmonitionRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a registry with the types bundled with the crate:
+    /// `note`, `info`, `tip`, `important`, `warning`, `caution` and
+    /// `danger` with icons, and `details`, rendered as a plain
+    /// `<details>` element.
+    pub fn bundled() -> Self {
+        let mut registry = Self::new();
+        for (kind, title, icon) in [
+            ("note", "Note", "\u{1F4DD}"),
+            ("info", "Info", "\u{2139}\u{FE0F}"),
+            ("tip", "Tip", "\u{1F4A1}"),
+            ("important", "Important", "\u{2757}"),
+            ("warning", "Warning", "\u{26A0}\u{FE0F}"),
+            ("caution", "Caution", "\u{270B}"),
+            ("danger", "Danger", "\u{26D4}"),
+        ] {
+            registry
+                .insert(Admonition::new(kind, title).with_icon(icon));
+        }
+        registry
+            .insert(Admonition::new("details", "Details").with_template(
+            "<details>\n<summary>{title}</summary>\n{body}\n</details>",
+        ));
+        registry
+    }
+
+    /// Adds an admonition type, replacing any of the same kind.
+    pub fn insert(&mut self, admonition: Admonition) {
+        let _ =
+            self.entries.insert(admonition.kind.clone(), admonition);
+    }
+
+    /// Returns the admonition type named `kind`.
+    pub fn get(&self, kind: &str) -> Option<&Admonition> {
+        self.entries.get(kind)
+    }
+
+    /// Returns the admonition types, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = &Admonition> {
+        self.entries.values()
+    }
+
+    /// Returns the number of admonition types.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the registry is empty.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Replaces the triple-colon containers of `markdown` with the HTML
+    /// of their admonition type, keeping their content as Markdown.
+    ///
+    /// Containers that are never closed, and `:::` lines in fenced code
+    /// blocks, are left unchanged.
+    ///
+    /// # Arguments
+    ///
+    /// * `markdown` - The Markdown to expand
+    pub fn expand(&self, markdown: &str) -> String {
+        let lines: Vec<&str> = markdown.split('\n').collect();
+        let mut replacements = vec![None; lines.len()];
+        for span in find_containers(&lines) {
+            let (before, after) =
+                self.render(span.name, span.marker, span.arguments);
+            replacements[span.start] = Some(format!("{}\n", before));
+            replacements[span.end] = Some(format!("\n{}\n", after));
+        }
+
+        lines
+            .iter()
+            .zip(replacements)
+            .map(|(line, replacement)| {
+                replacement.unwrap_or_else(|| line.to_string())
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Returns the HTML before and after the content of a block.
+    fn render(
+        &self,
+        kind: &str,
+        collapsible: Option<&str>,
+        title: &str,
+    ) -> (String, String) {
+        let admonition = self.get(kind);
+        let title = if title.is_empty() {
+            admonition
+                .map_or_else(|| capitalize(kind), |a| a.title.clone())
+        } else {
+            render_title(title)
+        };
+        let icon = admonition
+            .and_then(|a| a.icon.as_deref())
+            .map_or_else(String::new, |icon| {
+                format!(
+                    r#"<span class="admonition-icon" aria-hidden="true">{}</span>"#,
+                    icon
+                )
+            });
+        let template = match collapsible {
+            Some(_) => COLLAPSIBLE_TEMPLATE,
+            None => admonition
+                .and_then(|a| a.template.as_deref())
+                .unwrap_or(DEFAULT_TEMPLATE),
+        };
+        let (before, after) =
+            template.split_once("{body}").unwrap_or((template, ""));
+        let fill = |part: &str| {
+            part.replace("{kind}", kind)
+                .replace("{icon}", &icon)
+                .replace(
+                    "{open}",
+                    if collapsible == Some("+") {
+                        " open"
+                    } else {
+                        ""
+                    },
+                )
+                .replace("{title}", &title)
+        };
+        (fill(before.trim_end()), fill(after.trim_start()))
+    }
+}
+
+/// Renders the inline Markdown of a title.
+fn render_title(title: &str) -> String {
+    process_markdown_inline(title)
+        .map(|html| {
+            html.trim()
+                .trim_start_matches("<p>")
+                .trim_end_matches("</p>")
+                .to_string()
+        })
+        .unwrap_or_else(|_| title.to_string())
+}
+
+/// Returns `kind` with its first letter in uppercase.
+fn capitalize(kind: &str) -> String {
+    let mut chars = kind.chars();
+    chars.next().map_or_else(String::new, |first| {
+        first.to_uppercase().chain(chars).collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conversion::convert;
+    use crate::HtmlConfig;
+
+    fn config() -> HtmlConfig {
+        HtmlConfig {
+            admonitions: Some(AdmonitionRegistry::bundled()),
+            ..HtmlConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_titles_and_icons() {
+        let html = convert(
+            ":::warning Back up **first**\n- one\n- two\n:::\n\nAfter",
+            &config(),
+        )
+        .unwrap();
+        assert!(html.starts_with(concat!(
+            "<div class=\"admonition warning\">\n",
+            "<p class=\"admonition-title\"><span class=\"admonition-icon\" ",
+            "aria-hidden=\"true\">\u{26A0}\u{FE0F}</span>",
+            "Back up <strong>first</strong></p>\n<ul>\n<li>one</li>"
+        )));
+        assert!(html.ends_with("</ul>\n</div>\n<p>After</p>\n"));
+
+        let html =
+            convert(":::custom-box\nText\n:::", &config()).unwrap();
+        assert!(html.contains(
+            "<p class=\"admonition-title\">Custom-box</p>\n<p>Text</p>"
+        ));
+    }
+
+    #[test]
+    fn test_nested_and_collapsible_blocks() {
+        let markdown = "::::ignored\n:::note\nOuter\n\n:::tip- More\nInner\n:::\n\n:::danger+\nOpen\n:::\n:::\n";
+        let html = convert(markdown, &config()).unwrap();
+        let note = html.find("admonition note").unwrap();
+        let tip = html
+            .find("<details class=\"admonition tip\">\n<summary class=\"admonition-title\">")
+            .unwrap();
+        let danger = html
+            .find("<details class=\"admonition danger\" open>")
+            .unwrap();
+        assert!(note < tip && tip < danger);
+        assert!(
+            html.contains("More</summary>\n<p>Inner</p>\n</details>")
+        );
+        assert!(html.trim_end().ends_with("</details>\n</div>"));
+        assert!(html.contains("::::ignored"));
+    }
+
+    #[test]
+    fn test_code_and_unclosed_blocks_are_left_alone() {
+        let expanded = AdmonitionRegistry::bundled()
+            .expand("```\n:::note\n:::\n```\n\n:::tip\nnever closed");
+        assert_eq!(
+            expanded,
+            "```\n:::note\n:::\n```\n\n:::tip\nnever closed"
+        );
+
+        let html = convert(
+            ":::details Show the **log**\n:::note\nInside\n:::\n:::",
+            &config(),
+        )
+        .unwrap();
+        assert!(html.starts_with(
+            "<details>\n<summary>Show the <strong>log</strong></summary>\n<div class=\"admonition note\">"
+        ));
+    }
+
+    #[test]
+    fn test_default_rendering_is_unchanged() {
+        let html =
+            convert(":::note\nHello\n:::", &HtmlConfig::default())
+                .unwrap();
+        assert!(html.contains(r#"<div class="note">"#));
+        assert!(!html.contains("admonition"));
+    }
+}
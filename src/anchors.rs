@@ -0,0 +1,298 @@
+// Copyright © 2025 HTML Generator. All rights reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Anchor IDs across pages.
+//!
+//! Every `id` attribute is a link target. IDs only need to be unique
+//! within a page, but once pages are merged into one document, such as
+//! a single-page manual or a print edition, an `id` defined by two
+//! pages makes every link to the second one jump to the first. An
+//! [`AnchorRegistry`] collects the IDs of a set of pages and reports
+//! each ID defined more than once as an [`AnchorConflict`].
+//!
+//! [`namespace_anchors`] avoids conflicts by prefixing the IDs of a page,
+//! and the same-page references to them, with a namespace derived from
+//! the page path by [`anchor_namespace`].
+//!
+//! # Examples
+//!
+//! ```
+//! use html_generator::anchors::{namespace_anchors, AnchorRegistry};
+//!
+//! let mut registry = AnchorRegistry::new();
+//! registry.register("intro.md", r#"<h2 id="setup">Setup</h2>"#);
+//! registry.register("guide.md", r#"<h2 id="setup">Setup</h2>"#);
+//! let conflicts = registry.conflicts();
+//! assert_eq!(conflicts[0].id, "setup");
+//! assert_eq!(conflicts[0].pages, ["guide.md", "intro.md"]);
+//!
+//! let html = namespace_anchors(
+//!     r##"<h2 id="setup">Setup</h2><a href="#setup">Back</a>"##,
+//!     "guide",
+//! );
+//! assert_eq!(
+//!     html,
+//!     r##"<h2 id="guide-setup">Setup</h2><a href="#guide-setup">Back</a>"##
+//! );
+//! ```
+
+use crate::diagnostics::Diagnostic;
+use crate::patterns::anchors::{
+    ID_ATTRIBUTE_REGEX, ID_REFERENCE_REGEX, START_TAG_REGEX,
+};
+use crate::utils::slugify_path;
+use regex::Captures;
+use std::{
+    collections::{BTreeMap, HashSet},
+    fmt,
+    path::Path,
+};
+
+/// Returns the `id` attribute values of an HTML document, in document
+/// order.
+///
+/// # Arguments
+///
+/// * `html` - The HTML to scan
+pub fn anchor_ids(html: &str) -> Vec<String> {
+    START_TAG_REGEX
+        .find_iter(html)
+        .filter_map(|tag| ID_ATTRIBUTE_REGEX.captures(tag.as_str()))
+        .map(|caps| caps[2].to_string())
+        .filter(|id| !id.is_empty())
+        .collect()
+}
+
+/// Returns the namespace used for the anchors of a page.
+///
+/// The namespace is the slugified path of the source file without its
+/// extension, so `docs/Getting Started.md` becomes
+/// `docs-getting-started`.
+///
+/// # Arguments
+///
+/// * `source` - The source file, relative to the input root
+pub fn anchor_namespace(source: impl AsRef<Path>) -> String {
+    slugify_path(&source.as_ref().with_extension("").to_string_lossy())
+}
+
+/// Prefixes every anchor ID of a page with `namespace`.
+///
+/// References to those IDs within the page, in fragment links and in
+/// the `for`, `aria-labelledby`, `aria-describedby`, `aria-details`,
+/// `aria-controls` and `aria-owns` attributes, are updated too. References to IDs the
+/// page does not define are left alone.
+///
+/// # Arguments
+///
+/// * `html` - The HTML of the page
+/// * `namespace` - The prefix, usually from [`anchor_namespace`]
+///
+/// # Returns
+///
+/// The HTML with `id="x"` replaced by `id="namespace-x"`.
+pub fn namespace_anchors(html: &str, namespace: &str) -> String {
+    let ids: HashSet<String> = anchor_ids(html).into_iter().collect();
+    if ids.is_empty() || namespace.is_empty() {
+        return html.to_string();
+    }
+    let prefixed = |id: &str| format!("{}-{}", namespace, id);
+
+    START_TAG_REGEX
+        .replace_all(html, |tag: &Captures<'_>| {
+            let tag = ID_ATTRIBUTE_REGEX.replace(
+                &tag[0],
+                |caps: &Captures<'_>| match &caps[2] {
+                    "" => caps[0].to_string(),
+                    id => {
+                        format!(
+                            "{}{}{}",
+                            &caps[1],
+                            prefixed(id),
+                            &caps[3]
+                        )
+                    }
+                },
+            );
+            ID_REFERENCE_REGEX
+                .replace_all(&tag, |caps: &Captures<'_>| {
+                    let value = if caps[1].ends_with('#') {
+                        if ids.contains(&caps[2]) {
+                            prefixed(&caps[2])
+                        } else {
+                            caps[2].to_string()
+                        }
+                    } else {
+                        caps[2]
+                            .split_whitespace()
+                            .map(|id| {
+                                if ids.contains(id) {
+                                    prefixed(id)
+                                } else {
+                                    id.to_string()
+                                }
+                            })
+                            .collect::<Vec<_>>()
+                            .join(" ")
+                    };
+                    format!("{}{}{}", &caps[1], value, &caps[3])
+                })
+                .into_owned()
+        })
+        .into_owned()
+}
+
+/// An anchor ID defined more than once.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnchorConflict {
+    /// The conflicting ID.
+    pub id: String,
+    /// The pages defining the ID, sorted. A page defining the ID
+    /// several times is listed once per definition.
+    pub pages: Vec<String>,
+}
+
+impl AnchorConflict {
+    /// Converts the conflict into a warning [`Diagnostic`] with the
+    /// code `anchor_conflict`, attributed to the first page.
+    pub fn to_diagnostic(&self) -> Diagnostic {
+        let diagnostic =
+            Diagnostic::warning("anchor_conflict", self.to_string());
+        match self.pages.first() {
+            Some(page) => diagnostic.with_path(page),
+            None => diagnostic,
+        }
+    }
+}
+
+impl fmt::Display for AnchorConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Anchor '#{}' is defined {} times, in {}",
+            self.id,
+            self.pages.len(),
+            self.pages.join(", ")
+        )
+    }
+}
+
+/// The anchor IDs of a set of pages.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AnchorRegistry {
+    anchors: BTreeMap<String, Vec<String>>,
+}
+
+impl AnchorRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records every anchor ID of a page.
+    ///
+    /// # Arguments
+    ///
+    /// * `page` - The page, e.g. its source path
+    /// * `html` - The HTML of the page
+    pub fn register(&mut self, page: &str, html: &str) {
+        for id in anchor_ids(html) {
+            let pages = self.anchors.entry(id).or_default();
+            let index = pages
+                .iter()
+                .rposition(|existing| existing.as_str() <= page)
+                .map_or(0, |index| index + 1);
+            pages.insert(index, page.to_string());
+        }
+    }
+
+    /// Returns the pages defining an ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The anchor ID, without `#`
+    pub fn pages(&self, id: &str) -> &[String] {
+        self.anchors.get(id).map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns the number of distinct IDs.
+    pub fn len(&self) -> usize {
+        self.anchors.len()
+    }
+
+    /// Returns `true` if no IDs were registered.
+    pub fn is_empty(&self) -> bool {
+        self.anchors.is_empty()
+    }
+
+    /// Returns the IDs defined more than once, sorted by ID.
+    pub fn conflicts(&self) -> Vec<AnchorConflict> {
+        self.anchors
+            .iter()
+            .filter(|(_, pages)| pages.len() > 1)
+            .map(|(id, pages)| AnchorConflict {
+                id: id.clone(),
+                pages: pages.clone(),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_anchor_ids_ignore_text_and_other_attributes() {
+        let html = r#"<h2 id="a" data-id="x">id="b"</h2><p id="">c</p><div class="d" id="e"></div>"#;
+        assert_eq!(anchor_ids(html), ["a", "e"]);
+    }
+
+    #[test]
+    fn test_registry_reports_conflicts_within_and_across_pages() {
+        let mut registry = AnchorRegistry::new();
+        registry
+            .register("b.md", r#"<h2 id="x"></h2><h2 id="y"></h2>"#);
+        registry
+            .register("a.md", r#"<h2 id="x"></h2><h2 id="z"></h2>"#);
+        registry.register("c.md", r#"<h2 id="z"></h2><p id="z"></p>"#);
+        assert_eq!(registry.len(), 3);
+        assert_eq!(registry.pages("y"), ["b.md"]);
+        assert!(registry.pages("missing").is_empty());
+
+        let conflicts = registry.conflicts();
+        assert_eq!(conflicts.len(), 2);
+        assert_eq!(conflicts[0].pages, ["a.md", "b.md"]);
+        assert_eq!(conflicts[1].pages, ["a.md", "c.md", "c.md"]);
+        let diagnostic = conflicts[1].to_diagnostic();
+        assert_eq!(diagnostic.code, "anchor_conflict");
+        assert_eq!(
+            diagnostic.message,
+            "Anchor '#z' is defined 3 times, in a.md, c.md, c.md"
+        );
+    }
+
+    #[test]
+    fn test_namespace_updates_references_to_own_ids() {
+        let html = concat!(
+            r#"<label for="name">Name</label><input id="name">"#,
+            r##"<a href="#name">Jump</a><a href="#elsewhere">Out</a>"##,
+            r#"<div aria-labelledby="name other">x</div>"#,
+            r#"<a href="other.html#name">Page</a>"#
+        );
+        assert_eq!(
+            namespace_anchors(html, "form"),
+            concat!(
+                r#"<label for="form-name">Name</label><input id="form-name">"#,
+                r##"<a href="#form-name">Jump</a><a href="#elsewhere">Out</a>"##,
+                r#"<div aria-labelledby="form-name other">x</div>"#,
+                r#"<a href="other.html#name">Page</a>"#
+            )
+        );
+        assert_eq!(namespace_anchors("<p>x</p>", "ns"), "<p>x</p>");
+        assert_eq!(
+            anchor_namespace("docs/Getting Started.md"),
+            "docs-getting-started"
+        );
+    }
+}
@@ -0,0 +1,551 @@
+// Copyright © 2025 HTML Generator. All rights reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Self-contained, single-file pages.
+//!
+//! [`archive_html`] embeds the local assets of a page so that the HTML
+//! file alone renders the same anywhere, e.g. when shared as a report or
+//! kept as an offline snapshot of documentation:
+//!
+//! - images and media referenced by `src` or `poster` become Base64
+//!   `data:` URIs;
+//! - stylesheets referenced by `<link rel="stylesheet">` are inlined as
+//!   `<style>` elements;
+//! - `url()` references in stylesheets, such as fonts and background
+//!   images, become `data:` URIs, resolved relative to the stylesheet.
+//!
+//! Only relative references inside the base directory are embedded.
+//! Remote URLs are left alone, and local files that cannot be read are
+//! reported as `missing_asset` warnings.
+//!
+//! With the `mhtml` feature, [`mhtml_archive`] packages a page and the
+//! same assets as separate parts of an MHTML (RFC 2557) document
+//! instead, the format browsers open as `.mht` files and mail clients
+//! accept as attachments.
+//!
+//! # Examples
+//!
+//! ```
+//! use html_generator::archive::archive_html;
+//! use std::fs;
+//!
+//! let dir = std::env::temp_dir().join("html-generator-archive-doc");
+//! fs::create_dir_all(&dir)?;
+//! fs::write(dir.join("dot.gif"), b"GIF89a")?;
+//!
+//! let archive = archive_html(r#"<img src="dot.gif" alt="Dot">"#, &dir);
+//! assert_eq!(
+//!     archive.html,
+//!     r#"<img src="data:image/gif;base64,R0lGODlh" alt="Dot">"#
+//! );
+//! assert_eq!(archive.assets, [dir.join("dot.gif")]);
+//! # fs::remove_dir_all(&dir)?;
+//! # Ok::<(), std::io::Error>(())
+//! ```
+
+use crate::diagnostics::Diagnostic;
+use crate::patterns::archive::{
+    ATTRIBUTE_REGEX, CSS_URL_REGEX, LINK_TAG_REGEX, MEDIA_SRC_REGEX,
+    STYLE_BLOCK_REGEX,
+};
+#[cfg(feature = "mhtml")]
+use crate::utils::content_hash;
+use crate::utils::{base64_encode, decode_entities};
+use regex::Captures;
+use std::{
+    fs,
+    path::{Component, Path, PathBuf},
+};
+
+/// A page with its local assets embedded.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Archive {
+    /// The self-contained HTML.
+    pub html: String,
+    /// The embedded files, in the order they were first referenced.
+    pub assets: Vec<PathBuf>,
+    /// Warnings about assets that could not be embedded.
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl Archive {
+    /// Reads a local asset, recording it or a `missing_asset` warning.
+    fn read(
+        &mut self,
+        base_dir: &Path,
+        reference: &str,
+    ) -> Option<(PathBuf, Vec<u8>)> {
+        let path = resolve_asset(base_dir, reference)?;
+        match fs::read(&path) {
+            Ok(content) => {
+                if !self.assets.contains(&path) {
+                    self.assets.push(path.clone());
+                }
+                Some((path, content))
+            }
+            Err(error) => {
+                self.diagnostics.push(
+                    Diagnostic::warning(
+                        "missing_asset",
+                        format!(
+                            "Cannot embed '{}': {}",
+                            reference, error
+                        ),
+                    )
+                    .with_path(path),
+                );
+                None
+            }
+        }
+    }
+
+    /// Returns a `data:` URI for a local asset, or only records the
+    /// asset unless `embed` is set.
+    fn data_uri(
+        &mut self,
+        base_dir: &Path,
+        reference: &str,
+        embed: bool,
+    ) -> Option<String> {
+        let (path, content) = self.read(base_dir, reference)?;
+        if !embed {
+            return None;
+        }
+        Some(format!(
+            "data:{};base64,{}",
+            mime_type(&path),
+            base64_encode(&content)
+        ))
+    }
+
+    /// Embeds the `url()` references of a stylesheet located in the
+    /// `css_dir` directory, relative to `base_dir`.
+    fn embed_css(
+        &mut self,
+        base_dir: &Path,
+        css_dir: &str,
+        css: &str,
+        embed: bool,
+    ) -> String {
+        CSS_URL_REGEX
+            .replace_all(css, |caps: &Captures<'_>| {
+                let reference = caps
+                    .get(1)
+                    .or_else(|| caps.get(2))
+                    .or_else(|| caps.get(3))
+                    .map_or("", |m| m.as_str());
+                let reference = if is_relative(reference) {
+                    format!("{}{}", css_dir, reference)
+                } else {
+                    reference.to_string()
+                };
+                match self.data_uri(base_dir, &reference, embed) {
+                    Some(uri) => format!("url(\"{}\")", uri),
+                    None => caps[0].to_string(),
+                }
+            })
+            .into_owned()
+    }
+}
+
+/// Embeds the local images, stylesheets and fonts of a page.
+///
+/// # Arguments
+///
+/// * `html` - The HTML of the page
+/// * `base_dir` - The directory relative references are resolved
+///   against, usually the directory of the page
+///
+/// # Returns
+///
+/// The self-contained page, the embedded files and warnings about the
+/// files that could not be read.
+pub fn archive_html(html: &str, base_dir: impl AsRef<Path>) -> Archive {
+    collect_assets(html, base_dir.as_ref(), true)
+}
+
+/// A page packaged as an MHTML document.
+#[cfg(feature = "mhtml")]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MhtmlArchive {
+    /// The MHTML document, with CRLF line endings.
+    pub content: String,
+    /// The files packaged as parts, in the order they were first
+    /// referenced.
+    pub assets: Vec<PathBuf>,
+    /// Warnings about assets that could not be packaged.
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// Packages a page and its local assets as an MHTML document.
+///
+/// The page is the first part and keeps its references unchanged. Each
+/// asset is a Base64 part whose `Content-Location` is its reference
+/// resolved against `page_url`, so the relative references of the page
+/// and its stylesheets still match.
+///
+/// # Arguments
+///
+/// * `html` - The HTML of the page
+/// * `base_dir` - The directory relative references are resolved
+///   against, usually the directory of the page
+/// * `page_url` - The URL of the page, e.g.
+///   `https://example.com/docs/guide.html`
+///
+/// # Returns
+///
+/// The MHTML document, the packaged files and warnings about the files
+/// that could not be read.
+///
+/// # Examples
+///
+/// ```
+/// use html_generator::archive::mhtml_archive;
+///
+/// let archive = mhtml_archive(
+///     "<p>Hello</p>",
+///     ".",
+///     "https://example.com/hello.html",
+/// );
+/// assert!(archive.content.starts_with("MIME-Version: 1.0\r\n"));
+/// assert!(archive
+///     .content
+///     .contains("Content-Location: https://example.com/hello.html\r\n"));
+/// ```
+#[cfg(feature = "mhtml")]
+pub fn mhtml_archive(
+    html: &str,
+    base_dir: impl AsRef<Path>,
+    page_url: &str,
+) -> MhtmlArchive {
+    let base_dir = base_dir.as_ref();
+    let Archive {
+        assets,
+        mut diagnostics,
+        ..
+    } = collect_assets(html, base_dir, false);
+    let boundary =
+        format!("----=_Part_{}", content_hash(html.as_bytes()));
+    let directory_url = match page_url.rfind('/') {
+        Some(index) => &page_url[..=index],
+        None => "",
+    };
+
+    let mut content = format!(
+        "MIME-Version: 1.0\r\nContent-Type: multipart/related; \
+         type=\"text/html\"; boundary=\"{}\"\r\n\r\n",
+        boundary
+    );
+    let mut add_part =
+        |content_type: &str, location: &str, bytes: &[u8]| {
+            content.push_str(&format!(
+                "--{}\r\nContent-Type: {}\r\n\
+             Content-Transfer-Encoding: base64\r\n\
+             Content-Location: {}\r\n\r\n",
+                boundary, content_type, location
+            ));
+            let encoded = base64_encode(bytes);
+            for line in encoded.as_bytes().chunks(76) {
+                content.push_str(&String::from_utf8_lossy(line));
+                content.push_str("\r\n");
+            }
+        };
+
+    add_part("text/html; charset=utf-8", page_url, html.as_bytes());
+    let mut packaged = Vec::new();
+    for path in assets {
+        match fs::read(&path) {
+            Ok(bytes) => {
+                let relative = path
+                    .strip_prefix(base_dir)
+                    .unwrap_or(&path)
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/");
+                add_part(
+                    mime_type(&path),
+                    &format!(
+                        "{}{}",
+                        directory_url,
+                        relative.replace(' ', "%20")
+                    ),
+                    &bytes,
+                );
+                packaged.push(path);
+            }
+            Err(error) => diagnostics.push(
+                Diagnostic::warning(
+                    "missing_asset",
+                    format!("Cannot package asset: {}", error),
+                )
+                .with_path(path),
+            ),
+        }
+    }
+    content.push_str(&format!("--{}--\r\n", boundary));
+
+    MhtmlArchive {
+        content,
+        assets: packaged,
+        diagnostics,
+    }
+}
+
+/// Finds the local assets of a page, embedding them if `embed` is set.
+fn collect_assets(html: &str, base_dir: &Path, embed: bool) -> Archive {
+    let mut archive = Archive::default();
+
+    let html =
+        STYLE_BLOCK_REGEX.replace_all(html, |caps: &Captures<'_>| {
+            format!(
+                "{}{}{}",
+                &caps[1],
+                archive.embed_css(base_dir, "", &caps[2], embed),
+                &caps[3]
+            )
+        });
+
+    let html =
+        LINK_TAG_REGEX.replace_all(&html, |caps: &Captures<'_>| {
+            let tag = &caps[0];
+            let attribute = |name: &str| {
+                ATTRIBUTE_REGEX
+                    .captures_iter(tag)
+                    .find(|attr| attr[1].eq_ignore_ascii_case(name))
+                    .and_then(|attr| {
+                        attr.get(2).or_else(|| attr.get(3))
+                    })
+                    .map(|value| value.as_str().to_string())
+            };
+            let is_stylesheet = attribute("rel").map_or(false, |rel| {
+                rel.split_whitespace().any(|token| {
+                    token.eq_ignore_ascii_case("stylesheet")
+                })
+            });
+            let href = match attribute("href") {
+                Some(href) if is_stylesheet => href,
+                _ => return tag.to_string(),
+            };
+            match archive.read(base_dir, &href) {
+                Some((_, content)) => {
+                    let css = String::from_utf8_lossy(&content);
+                    let css_dir = href
+                        .rfind('/')
+                        .map_or("", |index| &href[..=index]);
+                    let css = archive
+                        .embed_css(base_dir, css_dir, &css, embed)
+                        .replace("</style", "<\\/style");
+                    if !embed {
+                        return tag.to_string();
+                    }
+                    let media = attribute("media")
+                        .map(|media| format!(" media=\"{}\"", media))
+                        .unwrap_or_default();
+                    format!("<style{}>{}</style>", media, css)
+                }
+                None => tag.to_string(),
+            }
+        });
+
+    let html =
+        MEDIA_SRC_REGEX.replace_all(&html, |caps: &Captures<'_>| {
+            match archive.data_uri(base_dir, &caps[2], embed) {
+                Some(uri) => format!("{}{}{}", &caps[1], uri, &caps[3]),
+                None => caps[0].to_string(),
+            }
+        });
+
+    archive.html = html.into_owned();
+    archive
+}
+
+/// Resolves a relative reference inside `base_dir`.
+///
+/// Returns `None` for remote URLs, `data:` URIs, fragment-only
+/// references and paths escaping `base_dir`.
+fn resolve_asset(base_dir: &Path, reference: &str) -> Option<PathBuf> {
+    let reference = decode_entities(reference.trim());
+    let path = reference
+        .split(['?', '#'])
+        .next()
+        .unwrap_or_default()
+        .replace("%20", " ");
+    if path.is_empty() || !is_relative(&path) {
+        return None;
+    }
+
+    let mut relative = PathBuf::new();
+    for component in Path::new(&path).components() {
+        match component {
+            Component::Normal(part) => relative.push(part),
+            Component::ParentDir => {
+                if !relative.pop() {
+                    return None;
+                }
+            }
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+    Some(base_dir.join(relative))
+}
+
+/// Returns `true` if a reference is a relative path rather than a URL
+/// with a scheme, a root-relative path or a fragment.
+fn is_relative(reference: &str) -> bool {
+    let has_scheme = reference
+        .split_once(':')
+        .map_or(false, |(scheme, _)| !scheme.contains('/'));
+    !has_scheme
+        && !reference.is_empty()
+        && !reference.starts_with(['/', '#'])
+}
+
+/// Returns the MIME type of a file from its extension.
+pub(crate) fn mime_type(path: &Path) -> &'static str {
+    let extension = path
+        .extension()
+        .map(|extension| {
+            extension.to_string_lossy().to_ascii_lowercase()
+        })
+        .unwrap_or_default();
+    match extension.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "svg" => "image/svg+xml",
+        "webp" => "image/webp",
+        "avif" => "image/avif",
+        "ico" => "image/x-icon",
+        "bmp" => "image/bmp",
+        "css" => "text/css",
+        "html" | "htm" => "text/html",
+        "js" | "mjs" => "text/javascript",
+        "woff" => "font/woff",
+        "woff2" => "font/woff2",
+        "ttf" => "font/ttf",
+        "otf" => "font/otf",
+        "eot" => "application/vnd.ms-fontobject",
+        "mp4" => "video/mp4",
+        "webm" => "video/webm",
+        "mp3" => "audio/mpeg",
+        "ogg" => "audio/ogg",
+        "wav" => "audio/wav",
+        _ => "application/octet-stream",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_embeds_stylesheets_and_fonts() {
+        let dir = tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("css")).unwrap();
+        fs::create_dir_all(dir.path().join("fonts")).unwrap();
+        fs::write(dir.path().join("fonts/a.woff2"), b"ab").unwrap();
+        fs::write(
+            dir.path().join("css/site.css"),
+            "@font-face { src: url('../fonts/a.woff2?v=1') }\nbody { background: url(https://e.com/x.png) }",
+        )
+        .unwrap();
+
+        let archive = archive_html(
+            r#"<link href="css/site.css" media="print" rel="stylesheet"><link rel="icon" href="x.ico"><style>p { background: url("fonts/a.woff2") }</style>"#,
+            dir.path(),
+        );
+        assert_eq!(
+            archive.html,
+            concat!(
+                "<style media=\"print\">@font-face { src: url(\"data:font/woff2;base64,YWI=\") }\n",
+                "body { background: url(https://e.com/x.png) }</style>",
+                "<link rel=\"icon\" href=\"x.ico\">",
+                "<style>p { background: url(\"data:font/woff2;base64,YWI=\") }</style>"
+            )
+        );
+        assert_eq!(
+            archive.assets,
+            [
+                dir.path().join("fonts/a.woff2"),
+                dir.path().join("css/site.css")
+            ]
+        );
+        assert!(archive.diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_reports_missing_and_skips_remote_assets() {
+        let dir = tempdir().unwrap();
+        let html = concat!(
+            r#"<img src="missing.png"><img src="https://e.com/a.png">"#,
+            r#"<img src="data:image/png;base64,AA=="><img src="../outside.png">"#,
+            "<style>p { filter: url(#f) }</style>"
+        );
+        let archive = archive_html(html, dir.path());
+        assert_eq!(archive.html, html);
+        assert!(archive.assets.is_empty());
+        assert_eq!(archive.diagnostics.len(), 1);
+        assert_eq!(archive.diagnostics[0].code, "missing_asset");
+        assert_eq!(
+            archive.diagnostics[0].path.as_deref(),
+            Some(dir.path().join("missing.png").as_path())
+        );
+    }
+
+    #[test]
+    fn test_mime_types() {
+        assert_eq!(mime_type(Path::new("a/B.JPG")), "image/jpeg");
+        assert_eq!(mime_type(Path::new("f.woff")), "font/woff");
+        assert_eq!(
+            mime_type(Path::new("none")),
+            "application/octet-stream"
+        );
+    }
+
+    #[cfg(feature = "mhtml")]
+    #[test]
+    fn test_mhtml_parts() {
+        let dir = tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("img")).unwrap();
+        fs::write(dir.path().join("img/a b.png"), b"png").unwrap();
+        fs::write(
+            dir.path().join("site.css"),
+            "p { background: url(img/a%20b.png) }",
+        )
+        .unwrap();
+        let html = r#"<link rel="stylesheet" href="site.css"><img src="img/a%20b.png"><img src="gone.png">"#;
+
+        let archive = mhtml_archive(
+            html,
+            dir.path(),
+            "https://example.com/docs/page.html",
+        );
+        assert_eq!(
+            archive.assets,
+            [
+                dir.path().join("site.css"),
+                dir.path().join("img/a b.png")
+            ]
+        );
+        assert_eq!(archive.diagnostics.len(), 1);
+        let content = &archive.content;
+        let boundary =
+            format!("----=_Part_{}", content_hash(html.as_bytes()));
+        assert_eq!(
+            content.matches(&format!("--{}\r\n", boundary)).count(),
+            3
+        );
+        assert!(content.ends_with(&format!("--{}--\r\n", boundary)));
+        assert!(content.contains(concat!(
+            "Content-Type: text/css\r\nContent-Transfer-Encoding: base64\r\n",
+            "Content-Location: https://example.com/docs/site.css\r\n"
+        )));
+        assert!(content.contains(concat!(
+            "Content-Location: https://example.com/docs/img/a%20b.png\r\n",
+            "\r\ncG5n\r\n"
+        )));
+    }
+}
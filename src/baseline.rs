@@ -0,0 +1,413 @@
+// Copyright © 2025 HTML Generator. All rights reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Baselines that suppress known accessibility and SEO issues.
+//!
+//! Turning on validation for an existing site usually reports many
+//! legacy issues at once. A [`Baseline`] records those issues so that
+//! CI only fails on new ones. Each [`Suppression`] names a page, an
+//! issue code and, optionally, a hash of the offending element, so a
+//! suppression for one image does not hide the same problem on a new
+//! image of the same page.
+//!
+//! A baseline file holds one suppression per line: the path, the code
+//! and an optional element hash, separated by tab characters. Blank
+//! lines and lines starting with `#` are ignored.
+//!
+//! # Examples
+//!
+//! ```
+//! use html_generator::accessibility::{validate_wcag, AccessibilityConfig};
+//! use html_generator::baseline::Baseline;
+//!
+//! let config = AccessibilityConfig::default();
+//! let page = r#"<html lang="en"><h1>A</h1><h3>B</h3></html>"#;
+//! let old = validate_wcag(page, &config, None)?;
+//! let baseline = Baseline::from_report("index.html", &old);
+//! assert!(baseline.to_string().starts_with("index.html\theading_structure\t"));
+//!
+//! // The known issues are suppressed...
+//! let report = baseline.filter_report("index.html", old);
+//! assert_eq!(report.issue_count, 0);
+//!
+//! // ...while new ones still fail.
+//! let page = r#"<html lang="en"><h1>A</h1><h3>B</h3><h5>C</h5></html>"#;
+//! let new = validate_wcag(page, &config, None)?;
+//! assert_eq!(baseline.filter_report("index.html", new).issue_count, 1);
+//! # Ok::<(), html_generator::accessibility::Error>(())
+//! ```
+
+use crate::accessibility::{AccessibilityReport, Issue};
+use crate::diagnostics::Diagnostic;
+use crate::error::{HtmlError, Result};
+use crate::utils::content_hash;
+use std::fmt;
+
+/// Returns the hash identifying an element in a [`Suppression`].
+///
+/// Runs of whitespace are collapsed first, so reformatting the page
+/// does not invalidate the baseline.
+///
+/// # Arguments
+///
+/// * `element` - The HTML of the element
+pub fn element_hash(element: &str) -> String {
+    let normalized: Vec<&str> = element.split_whitespace().collect();
+    content_hash(normalized.join(" ").as_bytes())
+}
+
+/// A known issue that should not be reported.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Suppression {
+    /// The page, e.g. `docs/index.html`, using `/` as separator.
+    pub path: String,
+    /// The issue code, e.g. `missing_alt_text`.
+    pub code: String,
+    /// The [`element_hash`] of the offending element, or `None` to
+    /// suppress every issue with this code on the page.
+    pub element_hash: Option<String>,
+}
+
+impl Suppression {
+    /// Returns `true` if the suppression covers an issue.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The page the issue was found on
+    /// * `code` - The issue code
+    /// * `element` - The HTML of the offending element, if known
+    pub fn matches(
+        &self,
+        path: &str,
+        code: &str,
+        element: Option<&str>,
+    ) -> bool {
+        self.path == normalize_path(path)
+            && self.code == code
+            && self.element_hash.as_ref().map_or(true, |hash| {
+                element.map_or(false, |element| {
+                    *hash == element_hash(element)
+                })
+            })
+    }
+}
+
+impl fmt::Display for Suppression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}\t{}", self.path, self.code)?;
+        if let Some(hash) = &self.element_hash {
+            write!(f, "\t{}", hash)?;
+        }
+        Ok(())
+    }
+}
+
+/// A set of suppressed issues.
+///
+/// The [`Display`](fmt::Display) output is a baseline file that
+/// [`Baseline::parse`] reads back.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Baseline {
+    suppressions: Vec<Suppression>,
+}
+
+impl Baseline {
+    /// Creates an empty baseline.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses a baseline file.
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - The content of the file
+    ///
+    /// # Errors
+    ///
+    /// Returns [`HtmlError::InvalidInput`] for a line without a code or
+    /// with more than three fields.
+    pub fn parse(content: &str) -> Result<Self> {
+        let mut baseline = Self::new();
+        for (index, line) in content.lines().enumerate() {
+            let line = line.trim_end_matches('\r');
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let fields: Vec<&str> = line.split('\t').collect();
+            match fields.as_slice() {
+                [path, code] | [path, code, ""] if !code.is_empty() => {
+                    baseline.add(path, code, None)
+                }
+                [path, code, hash] if !code.is_empty() => {
+                    baseline.add(path, code, Some(hash.to_string()))
+                }
+                _ => {
+                    return Err(HtmlError::InvalidInput(format!(
+                        "Invalid baseline entry on line {}: expected \
+                         path, code and optional hash separated by tabs",
+                        index + 1
+                    )))
+                }
+            }
+        }
+        Ok(baseline)
+    }
+
+    /// Builds a baseline suppressing every issue of a report.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The page the report is for
+    /// * `report` - The accessibility report
+    pub fn from_report(
+        path: &str,
+        report: &AccessibilityReport,
+    ) -> Self {
+        let mut baseline = Self::new();
+        baseline.add_report(path, report);
+        baseline
+    }
+
+    /// Adds a suppression, unless an identical one exists.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The page
+    /// * `code` - The issue code
+    /// * `element_hash` - The [`element_hash`] of the element, if any
+    pub fn add(
+        &mut self,
+        path: &str,
+        code: &str,
+        element_hash: Option<String>,
+    ) {
+        let suppression = Suppression {
+            path: normalize_path(path),
+            code: code.to_string(),
+            element_hash,
+        };
+        if !self.suppressions.contains(&suppression) {
+            self.suppressions.push(suppression);
+        }
+    }
+
+    /// Suppresses every issue of a report.
+    ///
+    /// Issues with an element are suppressed for that element only.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The page the report is for
+    /// * `report` - The accessibility report
+    pub fn add_report(
+        &mut self,
+        path: &str,
+        report: &AccessibilityReport,
+    ) {
+        for issue in &report.issues {
+            self.add(
+                path,
+                issue.issue_type.code(),
+                issue.element.as_deref().map(element_hash),
+            );
+        }
+    }
+
+    /// Suppresses diagnostics, such as SEO warnings, by page and code.
+    ///
+    /// Diagnostics without a path are recorded with an empty path.
+    ///
+    /// # Arguments
+    ///
+    /// * `diagnostics` - The diagnostics to suppress
+    pub fn add_diagnostics(&mut self, diagnostics: &[Diagnostic]) {
+        for diagnostic in diagnostics {
+            self.add(
+                &diagnostic_path(diagnostic),
+                &diagnostic.code,
+                None,
+            );
+        }
+    }
+
+    /// Returns the suppressions.
+    pub fn suppressions(&self) -> &[Suppression] {
+        &self.suppressions
+    }
+
+    /// Returns the number of suppressions.
+    pub fn len(&self) -> usize {
+        self.suppressions.len()
+    }
+
+    /// Returns `true` if nothing is suppressed.
+    pub fn is_empty(&self) -> bool {
+        self.suppressions.is_empty()
+    }
+
+    /// Returns `true` if an accessibility issue is suppressed.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The page the issue was found on
+    /// * `issue` - The issue
+    pub fn suppresses(&self, path: &str, issue: &Issue) -> bool {
+        self.suppressions.iter().any(|suppression| {
+            suppression.matches(
+                path,
+                issue.issue_type.code(),
+                issue.element.as_deref(),
+            )
+        })
+    }
+
+    /// Removes the suppressed issues from a report.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The page the report is for
+    /// * `report` - The accessibility report
+    ///
+    /// # Returns
+    ///
+    /// The report with only the issues not in the baseline.
+    pub fn filter_report(
+        &self,
+        path: &str,
+        mut report: AccessibilityReport,
+    ) -> AccessibilityReport {
+        report.issues.retain(|issue| !self.suppresses(path, issue));
+        report.issue_count = report.issues.len();
+        report
+    }
+
+    /// Removes the suppressed diagnostics.
+    ///
+    /// # Arguments
+    ///
+    /// * `diagnostics` - The diagnostics to filter
+    pub fn filter_diagnostics(
+        &self,
+        diagnostics: Vec<Diagnostic>,
+    ) -> Vec<Diagnostic> {
+        diagnostics
+            .into_iter()
+            .filter(|diagnostic| {
+                let path = diagnostic_path(diagnostic);
+                !self.suppressions.iter().any(|suppression| {
+                    suppression.matches(&path, &diagnostic.code, None)
+                })
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for Baseline {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for suppression in &self.suppressions {
+            writeln!(f, "{}", suppression)?;
+        }
+        Ok(())
+    }
+}
+
+/// Uses `/` as separator and drops a leading `./`.
+fn normalize_path(path: &str) -> String {
+    let path = path.replace('\\', "/");
+    path.strip_prefix("./").unwrap_or(&path).to_string()
+}
+
+/// Returns the path of a diagnostic, or an empty string.
+fn diagnostic_path(diagnostic: &Diagnostic) -> String {
+    diagnostic
+        .path
+        .as_ref()
+        .map_or_else(String::new, |path| path.to_string_lossy().into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::accessibility::{validate_wcag, AccessibilityConfig};
+
+    fn report(html: &str) -> AccessibilityReport {
+        validate_wcag(html, &AccessibilityConfig::default(), None)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_round_trips_through_file_format() {
+        let mut baseline = Baseline::from_report(
+            "./docs\\index.html",
+            &report("<html><h1>A</h1><h3>B</h3></html>"),
+        );
+        baseline.add("docs/legacy.html", "heading_structure", None);
+        baseline.add("docs/legacy.html", "heading_structure", None);
+        assert_eq!(baseline.len(), 3);
+
+        let file = format!("# Known issues\n\n{}", baseline);
+        assert!(
+            file.contains("docs/index.html\tlanguage_declaration\t")
+        );
+        assert!(file.ends_with("docs/legacy.html\theading_structure\n"));
+        assert_eq!(Baseline::parse(&file).unwrap(), baseline);
+    }
+
+    #[test]
+    fn test_rejects_malformed_lines() {
+        assert!(Baseline::parse("page.html").is_err());
+        assert!(Baseline::parse("page.html\t").is_err());
+        assert!(Baseline::parse("a\tb\tc\td").is_err());
+        assert!(Baseline::parse("# only\n\n").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_element_hash_targets_one_element() {
+        let baseline = Baseline::from_report(
+            "index.html",
+            &report("<html lang=\"en\"><h1>A</h1><h3>B</h3></html>"),
+        );
+        let changed = report(
+            "<html lang=\"en\"><h1>A</h1><h3>B</h3><h5>New</h5></html>",
+        );
+        let remaining = baseline.filter_report("index.html", changed);
+        assert_eq!(remaining.issue_count, 1);
+        assert!(remaining.issues[0]
+            .element
+            .as_deref()
+            .unwrap()
+            .contains("New"));
+
+        let other_page =
+            report("<html lang=\"en\"><h1>A</h1><h3>B</h3></html>");
+        assert_eq!(
+            baseline
+                .filter_report("other.html", other_page)
+                .issue_count,
+            1
+        );
+        assert_eq!(
+            element_hash("<h3>\n  B </h3>"),
+            element_hash("<h3> B </h3>")
+        );
+    }
+
+    #[test]
+    fn test_suppresses_diagnostics() {
+        let diagnostics = vec![
+            Diagnostic::warning(
+                "missing_description",
+                "No description",
+            )
+            .with_path("blog/post.html"),
+            Diagnostic::warning("missing_title", "No title")
+                .with_path("blog/post.html"),
+        ];
+        let mut baseline = Baseline::new();
+        baseline.add_diagnostics(&diagnostics[..1]);
+        let remaining = baseline.filter_diagnostics(diagnostics);
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].code, "missing_title");
+    }
+}
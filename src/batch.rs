@@ -0,0 +1,2481 @@
+// Copyright © 2025 HTML Generator. All rights reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Batch conversion of Markdown directories.
+//!
+//! This module converts every Markdown file below an input directory
+//! into HTML, mirroring the directory structure in an output directory,
+//! and returns a [`BatchReport`] describing what was produced.
+//!
+//! By default the first failing file aborts the batch. With
+//! [`BatchConfig::keep_going`] enabled, individual failures are recorded
+//! in the report (with an error code and the stage that failed) while
+//! the rest of the site still builds.
+//!
+//! With [`BatchConfig::dry_run`] enabled, every file is converted and
+//! validated but nothing is written. The report then lists the would-be
+//! output paths along with diagnostics such as files that would be
+//! overwritten.
+//!
+//! Output paths follow [`BatchConfig::naming`]: `guide.md` becomes
+//! either `guide.html` or, with pretty URLs, `guide/index.html`. With
+//! [`BatchConfig::slug_from_title`] enabled, output file names are
+//! derived from the front matter `title` (or the source file name) using
+//! [`slugify_path`](crate::utils::slugify_path), with numeric suffixes
+//! for collisions. A front matter `slug:` replaces the file name and a
+//! `permalink:` replaces the whole output path. File names that are
+//! kept count as used slugs, and a source whose output path is already
+//! taken by another fails with an `output_conflict` error rather than
+//! overwriting it.
+//!
+//! With [`BatchConfig::copy_assets`] enabled, other files such as
+//! images and stylesheets are copied alongside the pages, filtered by
+//! glob include/exclude patterns. Assets whose copy is already up to
+//! date are skipped.
+//!
+//! Symbolic links are skipped unless [`BatchConfig::symlinks`] says
+//! otherwise. When they are followed, links are only walked if their
+//! target stays inside the input directory and does not form a cycle,
+//! so untrusted content trees cannot pull in files from elsewhere.
+//! On Unix the same policy applies to hard links, which are files with
+//! several links: by default only the first path to a file, in path
+//! order, is used and the others are reported as `hard_link_duplicate`
+//! warnings; [`SymlinkPolicy::Error`] rejects hard-linked files, since
+//! their other links may be outside the input directory.
+//!
+//! Files and directories matched by [`BatchConfig::ignore`] or by a
+//! `.htmlgenignore` file are neither converted nor copied.
+//!
+//! With [`BatchConfig::rewrite_links`] enabled, links such as
+//! `[guide](./guide.md#setup)` are rewritten to the URL of the page
+//! generated from `guide.md`, using [`LinkMap`].
+//!
+//! Anchor IDs of every page are collected in an [`AnchorRegistry`].
+//! With [`BatchConfig::check_anchors`] enabled, IDs defined more than
+//! once across the batch are reported as `anchor_conflict` warnings.
+//! With [`BatchConfig::namespace_anchors`] enabled, the IDs of each page
+//! are prefixed with its [`anchor_namespace`], and rewritten links point
+//! at the prefixed IDs, so pages can be merged into one document.
+//!
+//! Links to other sites are checked against [`BatchConfig::url_policy`]
+//! after link rewriting, and violations are reported as warnings.
+//!
+//! With [`BatchConfig::single_file`] enabled, the local images,
+//! stylesheets and fonts of every page are embedded with
+//! [`archive_html`], so each page can be shared on its own.
+//!
+//! The links between pages form a [`LinkGraph`]. With
+//! [`BatchConfig::check_link_structure`] enabled, orphan pages that no
+//! other page links to and dead-end pages that link nowhere are reported
+//! as `orphan_page` and `dead_end_page` diagnostics.
+//!
+//! Sites mixing content types can convert each type differently: a
+//! front matter `pipeline: landing-page` converts the page with the
+//! `landing-page` entry of [`BatchConfig::profiles`], such as other
+//! post-processing steps or [plugins](crate::plugins), layered over the
+//! settings shared by every page.
+//!
+//! A [`Profiler`](crate::profiler::Profiler) set in the HTML
+//! configuration records the stages of every page, labelled with its
+//! path, including link rewriting, archiving and writing.
+//!
+//! [`BatchReport::stats`] summarises a run: pages, bytes in and out,
+//! conversion times, the slowest pages and how many outputs were
+//! already up to date. Nothing leaves the machine, so the numbers can be
+//! logged or compared between builds freely.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use html_generator::batch::{convert_directory, BatchConfig};
+//!
+//! let config = BatchConfig {
+//!     keep_going: true,
+//!     ..BatchConfig::new("docs", "site")
+//! };
+//! let report = convert_directory(&config)?;
+//! for failure in report.failures() {
+//!     eprintln!("{}", failure);
+//! }
+//! # Ok::<(), html_generator::error::HtmlError>(())
+//! ```
+
+use crate::anchors::{
+    anchor_namespace, namespace_anchors, AnchorRegistry,
+};
+use crate::archive::archive_html;
+use crate::conversion::{convert_detailed, run_hook, Conversion};
+use crate::diagnostics::Diagnostic;
+use crate::error::{HtmlError, Result};
+use crate::hooks::HookStage;
+use crate::ignore::{IgnoreRules, IGNORE_FILE};
+use crate::includes::expand_includes;
+use crate::links::{LinkGraph, LinkMap, UrlPolicy};
+use crate::manifest::BuildManifest;
+use crate::profiler::profile;
+use crate::unicode::find_invisible_characters;
+use crate::utils::{
+    content_hash, front_matter_value, glob_match, PathSlugger,
+};
+use crate::writing_mode::check_writing_mode;
+use crate::{HtmlConfig, MarkdownConfig, PartialHtmlConfig};
+use std::{
+    borrow::Cow,
+    cmp::Reverse,
+    collections::{BTreeMap, HashMap},
+    fmt, fs,
+    path::{Component, Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+/// Front matter key naming the [`BatchConfig::profiles`] entry of a
+/// page.
+pub const PROFILE_KEY: &str = "pipeline";
+
+/// How output file names are derived from source file names.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum OutputNaming {
+    /// `guide.md` is written to `guide.html`.
+    Mirror,
+    /// `guide.md` is written to `guide/index.html` and linked as
+    /// `/guide/`. Files named `index.md` stay `index.html`.
+    PrettyUrls,
+}
+
+impl Default for OutputNaming {
+    fn default() -> Self {
+        OutputNaming::Mirror
+    }
+}
+
+/// How the directory walker treats symbolic links.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum SymlinkPolicy {
+    /// Ignore symbolic links, and every hard link to a file but the
+    /// first in path order.
+    Skip,
+    /// Follow symbolic links whose target is inside the input
+    /// directory, and use every hard link to a file.
+    ///
+    /// Links that point outside the input directory, dangling links and
+    /// links that form a directory cycle are skipped with a warning.
+    Follow,
+    /// Fail the batch when a symbolic link or a file with several hard
+    /// links is found.
+    Error,
+}
+
+impl Default for SymlinkPolicy {
+    fn default() -> Self {
+        SymlinkPolicy::Skip
+    }
+}
+
+/// Configuration for converting a directory of Markdown files.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schema", schemars(deny_unknown_fields))]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
+pub struct BatchConfig {
+    /// Directory containing the Markdown sources.
+    pub input_dir: PathBuf,
+
+    /// Directory the generated HTML is written to.
+    pub output_dir: PathBuf,
+
+    /// Configuration applied to every converted file.
+    pub markdown_config: MarkdownConfig,
+
+    /// Record per-file failures in the report instead of aborting.
+    pub keep_going: bool,
+
+    /// Convert and validate every file without writing anything.
+    ///
+    /// Setting `markdown_config.dry_run` has the same effect.
+    pub dry_run: bool,
+
+    /// How output file names are derived from source file names.
+    pub naming: OutputNaming,
+
+    /// Name each output file after the slugified front matter `title`,
+    /// falling back to the slugified source file name.
+    pub slug_from_title: bool,
+
+    /// Rewrite relative links to `.md` files into links to the
+    /// generated pages.
+    ///
+    /// Links to Markdown files that are not part of the batch are
+    /// reported as `broken_link` warnings.
+    pub rewrite_links: bool,
+
+    /// Rules for links to other sites, checked on every page.
+    pub url_policy: UrlPolicy,
+
+    /// Copy non-Markdown files, such as images, stylesheets and
+    /// downloads, to the same relative location in the output directory.
+    pub copy_assets: bool,
+
+    /// Glob patterns selecting the assets to copy, matched against the
+    /// path relative to the input directory (see
+    /// [`glob_match`](crate::utils::glob_match)). An empty list selects
+    /// every asset.
+    pub asset_include: Vec<String>,
+
+    /// Glob patterns of assets that are never copied, applied after
+    /// [`asset_include`](Self::asset_include).
+    pub asset_exclude: Vec<String>,
+
+    /// Patterns of files and directories to skip entirely, in
+    /// [`IGNORE_FILE`] syntax. Ignore files found in the input
+    /// directory are applied after these patterns.
+    pub ignore: Vec<String>,
+
+    /// How symbolic and hard links below the input directory are
+    /// treated.
+    pub symlinks: SymlinkPolicy,
+
+    /// Report anchor IDs defined more than once across the batch as
+    /// `anchor_conflict` warnings.
+    pub check_anchors: bool,
+
+    /// Prefix the anchor IDs of each page with the
+    /// [`anchor_namespace`] of its source, so IDs are unique across the
+    /// batch.
+    pub namespace_anchors: bool,
+
+    /// Embed the local images, stylesheets and fonts of every page,
+    /// resolved relative to its source file, so pages are
+    /// self-contained.
+    pub single_file: bool,
+
+    /// Report orphan pages, which no other page links to, and dead-end
+    /// pages, which link to no other page, as informational
+    /// diagnostics.
+    pub check_link_structure: bool,
+
+    /// Named conversion settings, layered over
+    /// `markdown_config.html_config` for the pages whose front matter
+    /// selects them with a `pipeline:` key. Pages naming a profile that
+    /// is not listed fail to convert.
+    pub profiles: BTreeMap<String, PartialHtmlConfig>,
+}
+
+impl BatchConfig {
+    /// Creates a batch configuration with default conversion settings.
+    ///
+    /// # Arguments
+    ///
+    /// * `input_dir` - Directory containing the Markdown sources
+    /// * `output_dir` - Directory the generated HTML is written to
+    pub fn new(
+        input_dir: impl Into<PathBuf>,
+        output_dir: impl Into<PathBuf>,
+    ) -> Self {
+        Self {
+            input_dir: input_dir.into(),
+            output_dir: output_dir.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Returns `true` if the batch must not write any files.
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run || self.markdown_config.dry_run
+    }
+}
+
+/// The stage of the conversion at which a file failed.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum BatchStage {
+    /// Reading the source file.
+    Read,
+    /// Converting Markdown to HTML.
+    Convert,
+    /// Writing the generated HTML.
+    Write,
+    /// Copying a static asset.
+    Copy,
+}
+
+impl fmt::Display for BatchStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BatchStage::Read => write!(f, "read"),
+            BatchStage::Convert => write!(f, "convert"),
+            BatchStage::Write => write!(f, "write"),
+            BatchStage::Copy => write!(f, "copy"),
+        }
+    }
+}
+
+/// A file that was converted successfully.
+///
+/// In a dry run, `output` is the path that would have been written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConvertedFile {
+    /// Path of the Markdown source.
+    pub source: PathBuf,
+    /// Path of the generated HTML file.
+    pub output: PathBuf,
+    /// Root-relative URL of the generated page, e.g. `/guide.html` or
+    /// `/guide/` with pretty URLs.
+    pub url: String,
+    /// The document title, when [`HtmlConfig::strip_title`] removed an
+    /// `h1` from the page.
+    ///
+    /// [`HtmlConfig::strip_title`]: crate::HtmlConfig::strip_title
+    pub title: Option<String>,
+    /// Size of the Markdown source in bytes.
+    pub input_bytes: u64,
+    /// Size of the generated HTML in bytes.
+    pub output_bytes: u64,
+    /// Time spent converting the page and writing it.
+    pub duration: Duration,
+}
+
+/// A static asset copied to the output directory.
+///
+/// In a dry run, `output` is the path that would have been written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CopiedAsset {
+    /// Path of the source file.
+    pub source: PathBuf,
+    /// Path of the copy in the output directory.
+    pub output: PathBuf,
+    /// `true` if the copy was skipped because the output already had
+    /// identical content.
+    pub unchanged: bool,
+}
+
+/// Number of pages listed in [`BatchStats::slowest`].
+pub const SLOWEST_PAGES: usize = 5;
+
+/// Statistics of a batch run, see [`BatchReport::stats`].
+///
+/// They are computed from the report alone, so builds can be tracked
+/// over time without sending data anywhere.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BatchStats {
+    /// Number of converted pages.
+    pub pages: usize,
+    /// Number of files that failed.
+    pub failures: usize,
+    /// Total size of the converted Markdown sources in bytes.
+    pub input_bytes: u64,
+    /// Total size of the generated pages in bytes.
+    pub output_bytes: u64,
+    /// Total time spent converting and writing pages.
+    pub conversion_time: Duration,
+    /// The [`SLOWEST_PAGES`] slowest pages, slowest first.
+    pub slowest: Vec<ConvertedFile>,
+    /// Outputs found up to date and left alone, such as unchanged
+    /// assets.
+    pub cache_hits: usize,
+    /// Outputs that had to be written.
+    pub cache_misses: usize,
+}
+
+impl BatchStats {
+    /// Returns the average time spent on a page, if any was converted.
+    pub fn average_conversion_time(&self) -> Option<Duration> {
+        u32::try_from(self.pages)
+            .ok()
+            .filter(|pages| *pages > 0)
+            .map(|pages| self.conversion_time / pages)
+    }
+
+    /// Returns the share of outputs found up to date, between 0 and 1,
+    /// if any output was considered.
+    pub fn cache_hit_rate(&self) -> Option<f64> {
+        let total = self.cache_hits + self.cache_misses;
+        (total > 0).then(|| self.cache_hits as f64 / total as f64)
+    }
+}
+
+impl fmt::Display for BatchStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} pages, {} failures, {} bytes in, {} bytes out, {:?} total",
+            self.pages,
+            self.failures,
+            self.input_bytes,
+            self.output_bytes,
+            self.conversion_time
+        )?;
+        if let Some(average) = self.average_conversion_time() {
+            write!(f, ", {:?} per page", average)?;
+        }
+        if let Some(rate) = self.cache_hit_rate() {
+            write!(f, ", {:.0}% cache hits", rate * 100.0)?;
+        }
+        Ok(())
+    }
+}
+
+/// A file that could not be converted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchFailure {
+    /// Path of the Markdown source.
+    pub source: PathBuf,
+    /// The stage at which the conversion failed.
+    pub stage: BatchStage,
+    /// Stable error code, see [`HtmlError::code`].
+    pub code: &'static str,
+    /// Human-readable error message.
+    pub message: String,
+}
+
+impl fmt::Display for BatchFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: [{}] {} failed: {}",
+            self.source.display(),
+            self.code,
+            self.stage,
+            self.message
+        )
+    }
+}
+
+/// Outcome of a batch conversion.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BatchReport {
+    converted: Vec<ConvertedFile>,
+    assets: Vec<CopiedAsset>,
+    failures: Vec<BatchFailure>,
+    diagnostics: Vec<Diagnostic>,
+    manifest: BuildManifest,
+    anchors: AnchorRegistry,
+    link_graph: LinkGraph,
+    dry_run: bool,
+}
+
+impl BatchReport {
+    /// Returns the files that were converted successfully.
+    pub fn converted(&self) -> &[ConvertedFile] {
+        &self.converted
+    }
+
+    /// Returns the static assets that were copied or found unchanged.
+    pub fn assets(&self) -> &[CopiedAsset] {
+        &self.assets
+    }
+
+    /// Returns the files that failed to convert.
+    pub fn failures(&self) -> &[BatchFailure] {
+        &self.failures
+    }
+
+    /// Returns non-fatal diagnostics collected during the batch.
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+
+    /// Returns `true` if the report describes a dry run.
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    /// Returns the manifest of generated pages.
+    pub fn manifest(&self) -> &BuildManifest {
+        &self.manifest
+    }
+
+    /// Returns the anchor IDs of the converted pages, keyed by source
+    /// path relative to the input directory.
+    pub fn anchors(&self) -> &AnchorRegistry {
+        &self.anchors
+    }
+
+    /// Returns the links between the converted pages, keyed by source
+    /// path relative to the input directory.
+    pub fn link_graph(&self) -> &LinkGraph {
+        &self.link_graph
+    }
+
+    /// Returns `true` if every file was converted.
+    pub fn is_success(&self) -> bool {
+        self.failures.is_empty()
+    }
+
+    /// Returns the statistics of the run.
+    pub fn stats(&self) -> BatchStats {
+        let mut slowest: Vec<_> = self.converted.iter().collect();
+        slowest.sort_by_key(|file| Reverse(file.duration));
+        let cache_hits =
+            self.assets.iter().filter(|asset| asset.unchanged).count();
+        BatchStats {
+            pages: self.converted.len(),
+            failures: self.failures.len(),
+            input_bytes: self
+                .converted
+                .iter()
+                .map(|f| f.input_bytes)
+                .sum(),
+            output_bytes: self
+                .converted
+                .iter()
+                .map(|f| f.output_bytes)
+                .sum(),
+            conversion_time: self
+                .converted
+                .iter()
+                .map(|f| f.duration)
+                .sum(),
+            slowest: slowest
+                .into_iter()
+                .take(SLOWEST_PAGES)
+                .cloned()
+                .collect(),
+            cache_hits,
+            cache_misses: self.converted.len() + self.assets.len()
+                - cache_hits,
+        }
+    }
+}
+
+/// Converts every Markdown file below `config.input_dir`.
+///
+/// Files with an `.md` or `.markdown` extension are converted and
+/// written to the same relative location below `config.output_dir`
+/// with an `.html` extension. Symbolic links are handled according to
+/// [`BatchConfig::symlinks`], and paths matched by [`BatchConfig::ignore`] or an [`IGNORE_FILE`] are
+/// skipped.
+///
+/// In a dry run no directories or files are created; an `overwrite`
+/// warning is reported for each output file that already exists.
+///
+/// # Arguments
+///
+/// * `config` - The batch configuration
+///
+/// # Returns
+///
+/// A [`BatchReport`] listing converted files, failures and a manifest
+/// of the generated pages.
+///
+/// # Errors
+///
+/// Returns an error if the input directory cannot be read, if a
+/// symbolic link is found under [`SymlinkPolicy::Error`], or if any
+/// file fails while `keep_going` is disabled. Two sources resolving to
+/// the same output path fail with [`HtmlError::OutputConflict`].
+pub fn convert_directory(config: &BatchConfig) -> Result<BatchReport> {
+    let mut walker = Walker::new(config)?;
+    walker.walk(&config.input_dir)?;
+    walker.apply_hard_link_policy()?;
+    let Walker {
+        markdown: sources,
+        assets,
+        diagnostics,
+        ..
+    } = walker;
+
+    let mut report = BatchReport {
+        dry_run: config.is_dry_run(),
+        diagnostics,
+        ..Default::default()
+    };
+
+    // Resolve every output path first so links can point at any page.
+    let mut pages = Vec::new();
+    let mut links = LinkMap::new();
+    links.set_namespace_fragments(config.namespace_anchors);
+    let mut read = Vec::new();
+    for source in sources {
+        match read_source(config, &source) {
+            Ok((relative_source, content)) => {
+                read.push((source, relative_source, content))
+            }
+            Err((stage, error)) => record_failure(
+                config,
+                &mut report,
+                source,
+                stage,
+                error,
+            )?,
+        }
+    }
+
+    // Kept file names claim their slugs before any slug is derived, so
+    // `slug: guide` next to `guide.md` becomes `guide-2`.
+    let mut sluggers = HashMap::new();
+    for (_, relative_source, content) in &read {
+        reserve_file_name(
+            config,
+            relative_source,
+            content,
+            &mut sluggers,
+        );
+    }
+    let mut planned: HashMap<PathBuf, PathBuf> = HashMap::new();
+    for (source, relative_source, content) in read {
+        match output_path(
+            config,
+            &relative_source,
+            &content,
+            &mut sluggers,
+        ) {
+            Ok(relative) => {
+                if let Some(first) = planned.get(&relative) {
+                    let error = HtmlError::OutputConflict {
+                        output: relative,
+                        first: first.clone(),
+                    };
+                    record_failure(
+                        config,
+                        &mut report,
+                        source,
+                        BatchStage::Write,
+                        error,
+                    )?;
+                    continue;
+                }
+                let _ = planned
+                    .insert(relative.clone(), relative_source.clone());
+                links.insert(
+                    &relative_source,
+                    output_url(config.naming, &relative),
+                );
+                pages.push((
+                    source,
+                    relative_source,
+                    relative,
+                    content,
+                ));
+            }
+            Err(error) => record_failure(
+                config,
+                &mut report,
+                source,
+                BatchStage::Write,
+                error,
+            )?,
+        }
+    }
+
+    for (source, relative_source, relative, content) in pages {
+        let output = config.output_dir.join(&relative);
+        let html_config =
+            match page_config(config, &relative_source, &content) {
+                Ok(html_config) => html_config,
+                Err(error) => {
+                    record_failure(
+                        config,
+                        &mut report,
+                        source,
+                        BatchStage::Convert,
+                        error,
+                    )?;
+                    continue;
+                }
+            };
+        let profiler = html_config.profiler.as_ref();
+        let started = Instant::now();
+        let conversion = convert_detailed(&content, &html_config)
+            .map_err(|e| (BatchStage::Convert, e))
+            .and_then(|conversion| {
+                let Conversion {
+                    html,
+                    repairs,
+                    title,
+                    removed_urls,
+                    blocked_iframes,
+                    ..
+                } = conversion;
+                let targets = links.targets(&html, &relative_source);
+                let (html, unresolved) = if config.rewrite_links {
+                    links.rewrite(&html, &relative_source)
+                } else {
+                    (html, Vec::new())
+                };
+                let (html, violations) = config.url_policy.apply(&html);
+                let mut link_diagnostics: Vec<Diagnostic> =
+                    removed_urls
+                        .iter()
+                        .map(|url| url.to_diagnostic())
+                        .chain(
+                            blocked_iframes
+                                .iter()
+                                .map(|iframe| iframe.to_diagnostic()),
+                        )
+                        .chain(
+                            unresolved
+                                .iter()
+                                .map(|link| link.to_diagnostic()),
+                        )
+                        .chain(
+                            violations
+                                .iter()
+                                .map(|v| v.to_diagnostic()),
+                        )
+                        .collect();
+                let html = if config.namespace_anchors {
+                    namespace_anchors(
+                        &html,
+                        &anchor_namespace(&relative_source),
+                    )
+                } else {
+                    html
+                };
+                let html = if config.single_file {
+                    let archive =
+                        profile(profiler, "single_file", || {
+                            archive_html(
+                                &html,
+                                source
+                                    .parent()
+                                    .unwrap_or(&config.input_dir),
+                            )
+                        });
+                    link_diagnostics.extend(archive.diagnostics);
+                    archive.html
+                } else {
+                    html
+                };
+                let mut html = html;
+                run_hook(
+                    &html_config,
+                    HookStage::BeforeWrite,
+                    &mut html,
+                )
+                .map_err(|e| (BatchStage::Write, e))?;
+                profile(profiler, "write", || {
+                    write_output(config, &output, &html)
+                })?;
+                Ok((html, repairs, link_diagnostics, title, targets))
+            });
+        let duration = started.elapsed();
+        let (html, repairs, link_diagnostics, title, targets) =
+            match conversion {
+                Ok(result) => result,
+                Err((stage, error)) => {
+                    record_failure(
+                        config,
+                        &mut report,
+                        source,
+                        stage,
+                        error,
+                    )?;
+                    continue;
+                }
+            };
+
+        for repair in &repairs {
+            report
+                .diagnostics
+                .push(repair.to_diagnostic().with_path(&source));
+        }
+        for diagnostic in link_diagnostics {
+            report.diagnostics.push(diagnostic.with_path(&source));
+        }
+        for finding in find_invisible_characters(&html) {
+            report
+                .diagnostics
+                .push(finding.to_diagnostic().with_path(&output));
+        }
+        if html_config.writing_mode.is_vertical() {
+            for conflict in check_writing_mode(&html) {
+                report
+                    .diagnostics
+                    .push(conflict.to_diagnostic().with_path(&output));
+            }
+        }
+        if report.dry_run && output.exists() {
+            report.diagnostics.push(
+                Diagnostic::warning(
+                    "overwrite",
+                    "Existing file would be overwritten",
+                )
+                .with_path(&output),
+            );
+        }
+        report
+            .anchors
+            .register(&slash_path(&relative_source), &html);
+        report.link_graph.add_page(&relative_source);
+        for target in targets {
+            report.link_graph.add_link(&relative_source, target);
+        }
+        report
+            .manifest
+            .add_page(relative.to_string_lossy(), html.as_bytes());
+        let url = output_url(config.naming, &relative);
+        report.converted.push(ConvertedFile {
+            source,
+            output,
+            url,
+            title,
+            input_bytes: content.len() as u64,
+            output_bytes: html.len() as u64,
+            duration,
+        });
+    }
+
+    if config.check_anchors {
+        for conflict in report.anchors.conflicts() {
+            let mut diagnostic = conflict.to_diagnostic();
+            diagnostic.path =
+                diagnostic.path.map(|page| config.input_dir.join(page));
+            report.diagnostics.push(diagnostic);
+        }
+    }
+
+    if config.check_link_structure {
+        for page in report.link_graph.orphans() {
+            report.diagnostics.push(
+                Diagnostic::info(
+                    "orphan_page",
+                    "No other page links to this page",
+                )
+                .with_path(config.input_dir.join(page)),
+            );
+        }
+        for page in report.link_graph.dead_ends() {
+            report.diagnostics.push(
+                Diagnostic::info(
+                    "dead_end_page",
+                    "This page links to no other page",
+                )
+                .with_path(config.input_dir.join(page)),
+            );
+        }
+    }
+
+    if config.copy_assets {
+        copy_assets(config, assets, &mut report)?;
+    }
+
+    Ok(report)
+}
+
+/// Copies the selected non-Markdown files to the output directory.
+///
+/// Assets whose output already has identical content are left alone,
+/// so repeated builds only touch files that changed.
+fn copy_assets(
+    config: &BatchConfig,
+    assets: Vec<PathBuf>,
+    report: &mut BatchReport,
+) -> Result<()> {
+    for source in assets {
+        let relative = match source.strip_prefix(&config.input_dir) {
+            Ok(relative) => relative.to_path_buf(),
+            Err(_) => continue,
+        };
+        if !is_selected_asset(config, &slash_path(&relative)) {
+            continue;
+        }
+
+        let output = config.output_dir.join(&relative);
+        match copy_asset(config, &source, &output) {
+            Ok((content, unchanged)) => {
+                report
+                    .manifest
+                    .add_asset(relative.to_string_lossy(), &content);
+                report.assets.push(CopiedAsset {
+                    source,
+                    output,
+                    unchanged,
+                });
+            }
+            Err(error) => record_failure(
+                config,
+                report,
+                source,
+                BatchStage::Copy,
+                error,
+            )?,
+        }
+    }
+    Ok(())
+}
+
+/// Returns `true` if an asset path passes the include and exclude
+/// patterns.
+fn is_selected_asset(config: &BatchConfig, path: &str) -> bool {
+    let included = config.asset_include.is_empty()
+        || config
+            .asset_include
+            .iter()
+            .any(|pattern| glob_match(pattern, path));
+    included
+        && !config
+            .asset_exclude
+            .iter()
+            .any(|pattern| glob_match(pattern, path))
+}
+
+/// Copies a single asset unless the output is already up to date.
+///
+/// Returns the asset content and whether the copy was skipped because
+/// the output was unchanged.
+fn copy_asset(
+    config: &BatchConfig,
+    source: &Path,
+    output: &Path,
+) -> Result<(Vec<u8>, bool)> {
+    let content = fs::read(source)?;
+    let unchanged = fs::read(output).map_or(false, |existing| {
+        existing.len() == content.len()
+            && content_hash(&existing) == content_hash(&content)
+    });
+    if unchanged || config.is_dry_run() {
+        return Ok((content, unchanged));
+    }
+
+    if let Some(parent) = output.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(output, &content)?;
+    Ok((content, false))
+}
+
+/// Records a failed file, or returns the error if `keep_going` is
+/// disabled.
+fn record_failure(
+    config: &BatchConfig,
+    report: &mut BatchReport,
+    source: PathBuf,
+    stage: BatchStage,
+    error: HtmlError,
+) -> Result<()> {
+    if !config.keep_going {
+        return Err(error);
+    }
+    report.failures.push(BatchFailure {
+        source,
+        stage,
+        code: error.code(),
+        message: error.to_string(),
+    });
+    Ok(())
+}
+
+/// Reads a source file, expanding its includes.
+///
+/// Returns the source path relative to the input directory and the
+/// file content.
+fn read_source(
+    config: &BatchConfig,
+    source: &Path,
+) -> std::result::Result<(PathBuf, String), (BatchStage, HtmlError)> {
+    let content = fs::read_to_string(source)
+        .map_err(|e| (BatchStage::Read, HtmlError::Io(e)))?;
+
+    let relative_source = source
+        .strip_prefix(&config.input_dir)
+        .map_err(|_| {
+            HtmlError::InvalidInput(format!(
+                "Source '{}' is outside the input directory",
+                source.display()
+            ))
+        })
+        .map_err(|e| (BatchStage::Write, e))?;
+    let content = if config.markdown_config.expand_includes {
+        expand_includes(
+            &content,
+            &config.input_dir,
+            Some(relative_source),
+            config.markdown_config.max_include_depth,
+            config.symlinks,
+        )
+        .map_err(|e| (BatchStage::Read, e))?
+    } else {
+        content
+    };
+
+    Ok((relative_source.to_path_buf(), content))
+}
+
+/// Writes generated HTML unless the batch is a dry run.
+fn write_output(
+    config: &BatchConfig,
+    output: &Path,
+    html: &str,
+) -> std::result::Result<(), (BatchStage, HtmlError)> {
+    if config.is_dry_run() {
+        return Ok(());
+    }
+
+    if let Some(parent) = output.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| (BatchStage::Write, HtmlError::Io(e)))?;
+    }
+    fs::write(output, html.as_bytes())
+        .map_err(|e| (BatchStage::Write, HtmlError::Io(e)))
+}
+
+/// Computes the output path of a source, relative to the output
+/// directory.
+///
+/// A front matter `permalink:` takes precedence, then `slug:`, then the
+/// title when [`BatchConfig::slug_from_title`] is enabled, and finally
+/// the source file name. The result is laid out according to
+/// [`BatchConfig::naming`]. `sluggers` tracks the slugs used in each
+/// output directory, including the file names reserved by
+/// [`reserve_file_name`].
+fn output_path(
+    config: &BatchConfig,
+    relative_source: &Path,
+    content: &str,
+    sluggers: &mut HashMap<PathBuf, PathSlugger>,
+) -> Result<PathBuf> {
+    if let Some(permalink) = front_matter_value(content, "permalink") {
+        return permalink_path(&permalink);
+    }
+
+    let parent = relative_source.parent().unwrap_or(Path::new(""));
+    let stem = file_stem(relative_source);
+    let name = match slug_source(config, &stem, content) {
+        Some(name) => sluggers
+            .entry(parent.to_path_buf())
+            .or_default()
+            .slugify(&name),
+        None => stem,
+    };
+
+    Ok(match config.naming {
+        OutputNaming::PrettyUrls if name != "index" => {
+            parent.join(name).join("index.html")
+        }
+        _ => parent.join(format!("{}.html", name)),
+    })
+}
+
+/// Marks the file name of a source as a used slug in its directory
+/// when its output keeps that name.
+fn reserve_file_name(
+    config: &BatchConfig,
+    relative_source: &Path,
+    content: &str,
+    sluggers: &mut HashMap<PathBuf, PathSlugger>,
+) {
+    let stem = file_stem(relative_source);
+    if front_matter_value(content, "permalink").is_none()
+        && slug_source(config, &stem, content).is_none()
+    {
+        let parent = relative_source.parent().unwrap_or(Path::new(""));
+        let _ = sluggers
+            .entry(parent.to_path_buf())
+            .or_default()
+            .reserve(&stem);
+    }
+}
+
+/// Returns the file name of a source without its extension.
+fn file_stem(relative_source: &Path) -> String {
+    relative_source
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// Returns the text a source's output name is slugified from, or
+/// `None` if the file name is kept.
+fn slug_source(
+    config: &BatchConfig,
+    stem: &str,
+    content: &str,
+) -> Option<String> {
+    front_matter_value(content, "slug").or_else(|| {
+        config.slug_from_title.then(|| {
+            front_matter_value(content, "title")
+                .unwrap_or_else(|| stem.to_string())
+        })
+    })
+}
+
+/// Converts a front matter permalink into an output path.
+///
+/// `/blog/hello/` and `/blog/hello` map to `blog/hello/index.html`,
+/// while permalinks ending in `.html` are used as given.
+fn permalink_path(permalink: &str) -> Result<PathBuf> {
+    let trimmed = permalink.trim_start_matches('/');
+    let path = Path::new(trimmed);
+    if trimmed.is_empty()
+        || path
+            .components()
+            .any(|c| !matches!(c, Component::Normal(_)))
+    {
+        return Err(HtmlError::InvalidInput(format!(
+            "Invalid permalink '{}'",
+            permalink
+        )));
+    }
+
+    if path.extension().map_or(false, |ext| ext == "html")
+        && !trimmed.ends_with('/')
+    {
+        Ok(path.to_path_buf())
+    } else {
+        Ok(path.join("index.html"))
+    }
+}
+
+/// Returns the root-relative URL of an output path.
+///
+/// With [`OutputNaming::PrettyUrls`], `index.html` is dropped so the URL
+/// ends with the directory, e.g. `/guide/`.
+fn output_url(naming: OutputNaming, relative_output: &Path) -> String {
+    let path = slash_path(relative_output);
+    match naming {
+        OutputNaming::PrettyUrls if path == "index.html" => {
+            "/".to_string()
+        }
+        OutputNaming::PrettyUrls if path.ends_with("/index.html") => {
+            format!("/{}", path.trim_end_matches("index.html"))
+        }
+        _ => format!("/{}", path),
+    }
+}
+
+/// Recursive directory walker collecting Markdown files and assets.
+///
+/// Reads the [`IGNORE_FILE`] of each visited directory into `rules`,
+/// skips every ignored file and directory and applies the
+/// [`SymlinkPolicy`] to symbolic links while walking and to hard links
+/// afterwards.
+#[derive(Debug)]
+struct Walker<'a> {
+    root: &'a Path,
+    canonical_root: PathBuf,
+    symlinks: SymlinkPolicy,
+    rules: IgnoreRules,
+    /// Canonical paths of the directories being walked, for detecting
+    /// symlink cycles.
+    ancestors: Vec<PathBuf>,
+    markdown: Vec<PathBuf>,
+    assets: Vec<PathBuf>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl<'a> Walker<'a> {
+    /// Creates a walker for `config.input_dir`.
+    fn new(config: &'a BatchConfig) -> Result<Self> {
+        let mut rules = IgnoreRules::new();
+        for pattern in &config.ignore {
+            rules.add(pattern);
+        }
+        Ok(Self {
+            root: &config.input_dir,
+            canonical_root: fs::canonicalize(&config.input_dir)?,
+            symlinks: config.symlinks,
+            rules,
+            ancestors: Vec::new(),
+            markdown: Vec::new(),
+            assets: Vec::new(),
+            diagnostics: Vec::new(),
+        })
+    }
+
+    /// Walks `dir` and everything below it.
+    fn walk(&mut self, dir: &Path) -> Result<()> {
+        self.ancestors.push(fs::canonicalize(dir)?);
+        let base =
+            slash_path(dir.strip_prefix(self.root).unwrap_or(dir));
+        let ignore_file = dir.join(IGNORE_FILE);
+        if ignore_file.is_file() {
+            self.rules
+                .add_file(&fs::read_to_string(&ignore_file)?, &base);
+        }
+
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            let path = entry.path();
+            let mut file_type = entry.file_type()?;
+            if file_type.is_symlink() {
+                file_type = match self.resolve_symlink(&path)? {
+                    Some(metadata) => metadata.file_type(),
+                    None => continue,
+                };
+            }
+
+            let relative = slash_path(
+                path.strip_prefix(self.root).unwrap_or(&path),
+            );
+            if entry.file_name() == IGNORE_FILE
+                || self.rules.is_ignored(&relative, file_type.is_dir())
+            {
+                continue;
+            }
+            if file_type.is_dir() {
+                if self.is_cycle(&path)? {
+                    continue;
+                }
+                self.walk(&path)?;
+            } else if file_type.is_file() && is_markdown(&path) {
+                self.markdown.push(path);
+            } else if file_type.is_file() {
+                self.assets.push(path);
+            }
+        }
+
+        let _ = self.ancestors.pop();
+        Ok(())
+    }
+
+    /// Applies the symlink policy to the link at `path`.
+    ///
+    /// Returns the metadata of the link target if it should be walked.
+    fn resolve_symlink(
+        &mut self,
+        path: &Path,
+    ) -> Result<Option<fs::Metadata>> {
+        match self.symlinks {
+            SymlinkPolicy::Skip => return Ok(None),
+            SymlinkPolicy::Error => {
+                return Err(HtmlError::InvalidInput(format!(
+                    "Symbolic link '{}' is not allowed",
+                    path.display()
+                )))
+            }
+            SymlinkPolicy::Follow => {}
+        }
+
+        let target = match fs::canonicalize(path) {
+            Ok(target) => target,
+            Err(_) => {
+                self.diagnostics.push(
+                    Diagnostic::warning(
+                        "broken_symlink",
+                        "Symbolic link target does not exist",
+                    )
+                    .with_path(path),
+                );
+                return Ok(None);
+            }
+        };
+        if !target.starts_with(&self.canonical_root) {
+            self.diagnostics.push(
+                Diagnostic::warning(
+                    "symlink_outside_root",
+                    format!(
+                        "Symbolic link points to '{}', outside the input directory",
+                        target.display()
+                    ),
+                )
+                .with_path(path),
+            );
+            return Ok(None);
+        }
+        Ok(Some(fs::metadata(&target)?))
+    }
+
+    /// Sorts the files found and applies the symlink policy to files
+    /// with several hard links, keeping the first path to each file
+    /// under [`SymlinkPolicy::Skip`].
+    fn apply_hard_link_policy(&mut self) -> Result<()> {
+        self.markdown.sort();
+        self.assets.sort();
+        if self.symlinks == SymlinkPolicy::Follow {
+            return Ok(());
+        }
+        let mut seen: HashMap<(u64, u64), PathBuf> = HashMap::new();
+        for files in [&mut self.markdown, &mut self.assets] {
+            let mut kept = Vec::with_capacity(files.len());
+            for path in files.drain(..) {
+                let id = match hard_link_id(&path)? {
+                    Some(id) => id,
+                    None => {
+                        kept.push(path);
+                        continue;
+                    }
+                };
+                if self.symlinks == SymlinkPolicy::Error {
+                    return Err(HtmlError::InvalidInput(format!(
+                        "Hard-linked file '{}' is not allowed",
+                        path.display()
+                    )));
+                }
+                match seen.get(&id) {
+                    Some(first) => self.diagnostics.push(
+                        Diagnostic::warning(
+                            "hard_link_duplicate",
+                            format!(
+                                "Hard link to '{}' skipped",
+                                slash_path(
+                                    first
+                                        .strip_prefix(self.root)
+                                        .unwrap_or(first)
+                                )
+                            ),
+                        )
+                        .with_path(&path),
+                    ),
+                    None => {
+                        let _ = seen.insert(id, path.clone());
+                        kept.push(path);
+                    }
+                }
+            }
+            *files = kept;
+        }
+        Ok(())
+    }
+
+    /// Returns `true`, and records a warning, if entering `dir` would
+    /// revisit a directory that is already being walked.
+    fn is_cycle(&mut self, dir: &Path) -> Result<bool> {
+        let canonical = fs::canonicalize(dir)?;
+        if !self.ancestors.contains(&canonical) {
+            return Ok(false);
+        }
+        self.diagnostics.push(
+            Diagnostic::warning(
+                "symlink_cycle",
+                "Symbolic link creates a directory cycle",
+            )
+            .with_path(dir),
+        );
+        Ok(true)
+    }
+}
+
+/// Returns the device and inode of the file at `path` if it has several
+/// hard links.
+#[cfg(unix)]
+pub(crate) fn hard_link_id(path: &Path) -> Result<Option<(u64, u64)>> {
+    use std::os::unix::fs::MetadataExt;
+
+    let metadata = fs::metadata(path)?;
+    Ok(
+        (metadata.nlink() > 1)
+            .then(|| (metadata.dev(), metadata.ino())),
+    )
+}
+
+/// Hard links are not detected on this platform.
+#[cfg(not(unix))]
+pub(crate) fn hard_link_id(_path: &Path) -> Result<Option<(u64, u64)>> {
+    Ok(None)
+}
+
+/// Returns the conversion settings for a page: the profile selected
+/// by its front matter, with its source path in the provenance comment
+/// and passed to the hooks.
+fn page_config<'a>(
+    config: &'a BatchConfig,
+    relative_source: &Path,
+    content: &str,
+) -> Result<Cow<'a, HtmlConfig>> {
+    let base = config.markdown_config.conversion_config();
+    let profile = match front_matter_value(content, PROFILE_KEY) {
+        Some(name) => {
+            Some(config.profiles.get(&name).ok_or_else(|| {
+                HtmlError::InvalidInput(format!(
+                    "Unknown pipeline profile: {}",
+                    name
+                ))
+            })?)
+        }
+        None => None,
+    };
+    if profile.is_none()
+        && base.provenance.is_none()
+        && base.hooks.is_empty()
+        && base.profiler.is_none()
+    {
+        return Ok(base);
+    }
+    let mut html_config = match profile {
+        Some(profile) => base.merge(profile.clone()),
+        None => base.into_owned(),
+    };
+    let source = slash_path(relative_source);
+    if let Some(provenance) = html_config.provenance.take() {
+        html_config.provenance =
+            Some(provenance.with_source(source.clone()));
+    }
+    if let Some(profiler) = html_config.profiler.take() {
+        html_config.profiler =
+            Some(profiler.with_document(source.clone()));
+    }
+    if !html_config.hooks.is_empty() {
+        html_config.hooks = html_config.hooks.with_source(source);
+    }
+    Ok(Cow::Owned(html_config))
+}
+
+/// Joins the components of a relative path with `/`.
+fn slash_path(path: &Path) -> String {
+    path.components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Returns `true` if the path has a Markdown extension.
+fn is_markdown(path: &Path) -> bool {
+    path.extension()
+        .map(|ext| {
+            let ext = ext.to_string_lossy().to_ascii_lowercase();
+            ext == "md" || ext == "markdown"
+        })
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::profiler::Profiler;
+    use tempfile::{tempdir, TempDir};
+
+    /// Creates an input directory with one valid and one empty file.
+    fn create_site() -> (TempDir, TempDir) {
+        let input = tempdir().expect("Failed to create input dir");
+        let output = tempdir().expect("Failed to create output dir");
+        fs::create_dir_all(input.path().join("guide")).unwrap();
+        fs::write(input.path().join("index.md"), "# Home").unwrap();
+        fs::write(input.path().join("guide/empty.md"), "").unwrap();
+        fs::write(input.path().join("notes.txt"), "skip").unwrap();
+        (input, output)
+    }
+
+    mod naming_tests {
+        use super::*;
+
+        #[test]
+        fn test_permalink_path() {
+            assert_eq!(
+                permalink_path("/about/").unwrap(),
+                Path::new("about/index.html")
+            );
+            assert_eq!(
+                permalink_path("docs/faq").unwrap(),
+                Path::new("docs/faq/index.html")
+            );
+            assert_eq!(
+                permalink_path("/legal.html").unwrap(),
+                Path::new("legal.html")
+            );
+            assert!(permalink_path("/").is_err());
+            assert!(permalink_path("a/../../b").is_err());
+        }
+
+        #[test]
+        fn test_output_url() {
+            let url =
+                |naming, path| output_url(naming, Path::new(path));
+            assert_eq!(
+                url(OutputNaming::Mirror, "a/b.html"),
+                "/a/b.html"
+            );
+            assert_eq!(
+                url(OutputNaming::Mirror, "a/index.html"),
+                "/a/index.html"
+            );
+            assert_eq!(
+                url(OutputNaming::PrettyUrls, "a/index.html"),
+                "/a/"
+            );
+            assert_eq!(
+                url(OutputNaming::PrettyUrls, "index.html"),
+                "/"
+            );
+        }
+    }
+
+    mod convert_directory_tests {
+        use super::*;
+
+        #[test]
+        fn test_converts_all_files() {
+            let (input, output) = create_site();
+            fs::write(input.path().join("guide/empty.md"), "## Guide")
+                .unwrap();
+            let config = BatchConfig::new(input.path(), output.path());
+
+            let report = convert_directory(&config).unwrap();
+            assert!(report.is_success());
+            assert_eq!(report.converted().len(), 2);
+            assert_eq!(report.manifest().len(), 2);
+            assert!(output.path().join("index.html").exists());
+            assert!(output.path().join("guide/empty.html").exists());
+            assert!(!output.path().join("notes.html").exists());
+        }
+
+        #[test]
+        fn test_failure_aborts_without_keep_going() {
+            let (input, output) = create_site();
+            let config = BatchConfig::new(input.path(), output.path());
+
+            let result = convert_directory(&config);
+            assert!(matches!(result, Err(HtmlError::InvalidInput(_))));
+        }
+
+        #[test]
+        fn test_keep_going_collects_failures() {
+            let (input, output) = create_site();
+            let config = BatchConfig {
+                keep_going: true,
+                ..BatchConfig::new(input.path(), output.path())
+            };
+
+            let report = convert_directory(&config).unwrap();
+            assert!(!report.is_success());
+            assert_eq!(report.converted().len(), 1);
+            assert_eq!(report.failures().len(), 1);
+
+            let failure = &report.failures()[0];
+            assert_eq!(failure.stage, BatchStage::Convert);
+            assert_eq!(failure.code, "invalid_input");
+            assert!(failure.source.ends_with("guide/empty.md"));
+            assert!(failure.to_string().contains("[invalid_input]"));
+            assert!(output.path().join("index.html").exists());
+        }
+
+        #[test]
+        fn test_report_stats() {
+            let (input, output) = create_site();
+            let config = BatchConfig {
+                keep_going: true,
+                copy_assets: true,
+                ..BatchConfig::new(input.path(), output.path())
+            };
+
+            let stats = convert_directory(&config).unwrap().stats();
+            assert_eq!(stats.pages, 1);
+            assert_eq!(stats.failures, 1);
+            assert!(stats.input_bytes > 0);
+            assert!(stats.output_bytes > 0);
+            assert_eq!(stats.slowest.len(), 1);
+            assert!(stats.slowest[0].source.ends_with("index.md"));
+            assert!(stats.average_conversion_time().is_some());
+            assert_eq!(stats.cache_hit_rate(), Some(0.0));
+            assert!(stats
+                .to_string()
+                .starts_with("1 pages, 1 failures"));
+
+            let stats = convert_directory(&config).unwrap().stats();
+            assert_eq!((stats.cache_hits, stats.cache_misses), (1, 1));
+            assert_eq!(stats.cache_hit_rate(), Some(0.5));
+            assert_eq!(BatchStats::default().cache_hit_rate(), None);
+        }
+
+        #[test]
+        fn test_profiler_labels_pages() {
+            let (input, output) = create_site();
+            let profiler = Profiler::new();
+            let mut config = BatchConfig {
+                keep_going: true,
+                ..BatchConfig::new(input.path(), output.path())
+            };
+            config.markdown_config.html_config.profiler =
+                Some(profiler.clone());
+
+            let _ = convert_directory(&config).unwrap();
+            let spans = profiler.spans();
+            assert!(spans.iter().any(
+                |s| s.document == "index.md" && s.stage == "write"
+            ));
+            assert!(spans
+                .iter()
+                .all(|s| s.document != "guide/empty.md"
+                    || s.stage != "write"));
+        }
+
+        #[test]
+        fn test_keep_going_records_read_failures() {
+            let (input, output) = create_site();
+            fs::write(
+                input.path().join("guide/empty.md"),
+                [0xff, 0xfe],
+            )
+            .unwrap();
+            let config = BatchConfig {
+                keep_going: true,
+                ..BatchConfig::new(input.path(), output.path())
+            };
+
+            let report = convert_directory(&config).unwrap();
+            assert_eq!(report.failures()[0].stage, BatchStage::Read);
+            assert_eq!(report.failures()[0].code, "io");
+        }
+
+        #[test]
+        fn test_invisible_characters_are_reported() {
+            let (input, output) = create_site();
+            fs::write(input.path().join("guide/empty.md"), "## Guide")
+                .unwrap();
+            fs::write(input.path().join("bidi.md"), "abc \u{202E}def")
+                .unwrap();
+
+            let report = convert_directory(&BatchConfig::new(
+                input.path(),
+                output.path(),
+            ))
+            .unwrap();
+            let diagnostics = report.diagnostics();
+            assert_eq!(diagnostics.len(), 1);
+            assert_eq!(diagnostics[0].code, "bidi_control");
+            assert_eq!(
+                diagnostics[0].path.as_deref(),
+                Some(output.path().join("bidi.html").as_path())
+            );
+        }
+
+        #[test]
+        fn test_mojibake_repairs_are_reported() {
+            let (input, output) = create_site();
+            fs::write(input.path().join("guide/empty.md"), "## Guide")
+                .unwrap();
+            fs::write(input.path().join("legacy.md"), "cafÃ©").unwrap();
+            let mut config =
+                BatchConfig::new(input.path(), output.path());
+            config.markdown_config.html_config.repair_mojibake = true;
+
+            let report = convert_directory(&config).unwrap();
+            let diagnostics = report.diagnostics();
+            assert_eq!(diagnostics.len(), 1);
+            assert_eq!(diagnostics[0].code, "mojibake");
+            assert_eq!(
+                diagnostics[0].path.as_deref(),
+                Some(input.path().join("legacy.md").as_path())
+            );
+            assert!(fs::read_to_string(
+                output.path().join("legacy.html")
+            )
+            .unwrap()
+            .contains("café"));
+        }
+
+        #[test]
+        fn test_slug_from_title() {
+            let (input, output) = create_site();
+            fs::write(
+                input.path().join("guide/empty.md"),
+                "---\ntitle: Café Guide\n---\n# Guide",
+            )
+            .unwrap();
+            fs::write(
+                input.path().join("guide/other.md"),
+                "---\ntitle: \"Cafe guide\"\n---\n# Other",
+            )
+            .unwrap();
+            fs::write(input.path().join("Con.md"), "# Reserved")
+                .unwrap();
+            let config = BatchConfig {
+                slug_from_title: true,
+                ..BatchConfig::new(input.path(), output.path())
+            };
+
+            let report = convert_directory(&config).unwrap();
+            let outputs: Vec<_> = report
+                .converted()
+                .iter()
+                .map(|file| {
+                    file.output.strip_prefix(output.path()).unwrap()
+                })
+                .collect();
+            assert_eq!(
+                outputs,
+                vec![
+                    Path::new("con-page.html"),
+                    Path::new("guide/cafe-guide.html"),
+                    Path::new("guide/cafe-guide-2.html"),
+                    Path::new("index.html"),
+                ]
+            );
+            assert!(output
+                .path()
+                .join("guide/cafe-guide-2.html")
+                .exists());
+        }
+
+        #[test]
+        fn test_pretty_urls() {
+            let (input, output) = create_site();
+            fs::write(input.path().join("guide/empty.md"), "## Guide")
+                .unwrap();
+            let config = BatchConfig {
+                naming: OutputNaming::PrettyUrls,
+                ..BatchConfig::new(input.path(), output.path())
+            };
+
+            let report = convert_directory(&config).unwrap();
+            let urls: Vec<_> = report
+                .converted()
+                .iter()
+                .map(|file| file.url.as_str())
+                .collect();
+            assert_eq!(urls, vec!["/guide/empty/", "/"]);
+            assert!(output
+                .path()
+                .join("guide/empty/index.html")
+                .exists());
+            assert!(output.path().join("index.html").exists());
+        }
+
+        #[test]
+        fn test_rewrite_links() {
+            let (input, output) = create_site();
+            fs::write(
+                input.path().join("index.md"),
+                "[Guide](guide/empty.md#setup) [Gone](missing.md)",
+            )
+            .unwrap();
+            fs::write(
+                input.path().join("guide/empty.md"),
+                "[Home](../index.md)",
+            )
+            .unwrap();
+            let config = BatchConfig {
+                naming: OutputNaming::PrettyUrls,
+                rewrite_links: true,
+                ..BatchConfig::new(input.path(), output.path())
+            };
+
+            let report = convert_directory(&config).unwrap();
+            let index =
+                fs::read_to_string(output.path().join("index.html"))
+                    .unwrap();
+            let guide = fs::read_to_string(
+                output.path().join("guide/empty/index.html"),
+            )
+            .unwrap();
+            assert!(index.contains(r#"href="guide/empty/#setup""#));
+            assert!(index.contains(r#"href="missing.md""#));
+            assert!(guide.contains(r#"href="../../""#));
+
+            let broken: Vec<_> = report
+                .diagnostics()
+                .iter()
+                .filter(|d| d.code == "broken_link")
+                .collect();
+            assert_eq!(broken.len(), 1);
+            assert_eq!(
+                broken[0].path.as_deref(),
+                Some(input.path().join("index.md").as_path())
+            );
+        }
+
+        #[test]
+        fn test_anchor_conflicts_and_namespacing() {
+            let (input, output) = create_site();
+            fs::write(
+                input.path().join("index.md"),
+                "<h2 id=\"setup\">Setup</h2>\n\n[Guide](guide/empty.md#setup)",
+            )
+            .unwrap();
+            fs::write(
+                input.path().join("guide/empty.md"),
+                "<h2 id=\"setup\">Setup</h2>\n\n[Top](#setup)",
+            )
+            .unwrap();
+            let config = BatchConfig {
+                rewrite_links: true,
+                check_anchors: true,
+                ..BatchConfig::new(input.path(), output.path())
+            };
+
+            let report = convert_directory(&config).unwrap();
+            assert_eq!(
+                report.anchors().pages("setup"),
+                ["guide/empty.md", "index.md"]
+            );
+            let conflicts: Vec<_> = report
+                .diagnostics()
+                .iter()
+                .filter(|d| d.code == "anchor_conflict")
+                .collect();
+            assert_eq!(conflicts.len(), 1);
+            assert_eq!(
+                conflicts[0].path.as_deref(),
+                Some(input.path().join("guide/empty.md").as_path())
+            );
+
+            let config = BatchConfig {
+                namespace_anchors: true,
+                ..config
+            };
+            let report = convert_directory(&config).unwrap();
+            assert!(report.anchors().conflicts().is_empty());
+            assert!(report
+                .diagnostics()
+                .iter()
+                .all(|d| d.code != "anchor_conflict"));
+            let index =
+                fs::read_to_string(output.path().join("index.html"))
+                    .unwrap();
+            let guide = fs::read_to_string(
+                output.path().join("guide/empty.html"),
+            )
+            .unwrap();
+            assert!(index.contains(r#"id="index-setup""#));
+            assert!(index.contains(
+                r#"href="guide/empty.html#guide-empty-setup""#
+            ));
+            assert!(guide.contains(r#"id="guide-empty-setup""#));
+            assert!(guide.contains(r##"href="#guide-empty-setup""##));
+        }
+
+        #[test]
+        fn test_link_structure() {
+            let (input, output) = create_site();
+            fs::write(
+                input.path().join("index.md"),
+                "[Guide](guide.md)",
+            )
+            .unwrap();
+            fs::write(
+                input.path().join("guide.md"),
+                "[Home](index.md) [Self](guide.md)",
+            )
+            .unwrap();
+            fs::write(input.path().join("guide/empty.md"), "# Lost")
+                .unwrap();
+            let config = BatchConfig {
+                check_link_structure: true,
+                ..BatchConfig::new(input.path(), output.path())
+            };
+
+            let report = convert_directory(&config).unwrap();
+            let graph = report.link_graph();
+            assert_eq!(graph.len(), 3);
+            assert_eq!(
+                graph.links_from("guide.md").collect::<Vec<_>>(),
+                [Path::new("index.md")]
+            );
+            let found = |code: &str| -> Vec<PathBuf> {
+                report
+                    .diagnostics()
+                    .iter()
+                    .filter(|d| d.code == code)
+                    .filter_map(|d| d.path.clone())
+                    .collect()
+            };
+            assert_eq!(
+                found("orphan_page"),
+                [input.path().join("guide/empty.md")]
+            );
+            assert_eq!(
+                found("dead_end_page"),
+                [input.path().join("guide/empty.md")]
+            );
+
+            let config = BatchConfig::new(input.path(), output.path());
+            let report = convert_directory(&config).unwrap();
+            assert!(report
+                .diagnostics()
+                .iter()
+                .all(|d| d.code != "orphan_page"));
+            assert_eq!(report.link_graph().len(), 3);
+        }
+
+        #[test]
+        fn test_url_policy() {
+            let (input, output) = create_site();
+            fs::write(
+                input.path().join("index.md"),
+                "[a](http://example.com/a) [b](https://bit.ly/b)",
+            )
+            .unwrap();
+            fs::remove_file(input.path().join("guide/empty.md"))
+                .unwrap();
+            let config = BatchConfig {
+                url_policy: UrlPolicy {
+                    deny: vec!["bit.ly".to_string()],
+                    require_https: true,
+                    upgrade_to_https: true,
+                    ..Default::default()
+                },
+                ..BatchConfig::new(input.path(), output.path())
+            };
+
+            let report = convert_directory(&config).unwrap();
+            let index =
+                fs::read_to_string(output.path().join("index.html"))
+                    .unwrap();
+            assert!(index.contains(r#"href="https://example.com/a""#));
+            let denied: Vec<_> = report
+                .diagnostics()
+                .iter()
+                .filter(|d| d.code == "denied_url")
+                .collect();
+            assert_eq!(denied.len(), 1);
+            assert_eq!(
+                denied[0].path.as_deref(),
+                Some(input.path().join("index.md").as_path())
+            );
+        }
+
+        #[test]
+        fn test_provenance_names_each_source() {
+            let (input, output) = create_site();
+            fs::write(input.path().join("guide/empty.md"), "## Guide")
+                .unwrap();
+            let mut config =
+                BatchConfig::new(input.path(), output.path());
+            config.markdown_config.html_config.provenance =
+                Some(crate::provenance::Provenance::default());
+
+            let report = convert_directory(&config).unwrap();
+            assert!(report.is_success());
+            let page = fs::read_to_string(
+                output.path().join("guide/empty.html"),
+            )
+            .unwrap();
+            assert!(
+                page.starts_with("<!-- generated by html-generator v")
+            );
+            assert!(page.contains(" from guide/empty.md at hash "));
+        }
+
+        #[test]
+        fn test_front_matter_selects_profile() {
+            let (input, output) = create_site();
+            fs::write(
+                input.path().join("guide/empty.md"),
+                "---\npipeline: landing-page\n---\n# Launch\n\nSoon",
+            )
+            .unwrap();
+            fs::write(
+                input.path().join("other.md"),
+                "---\npipeline: missing\n---\nText",
+            )
+            .unwrap();
+            let mut config = BatchConfig {
+                keep_going: true,
+                ..BatchConfig::new(input.path(), output.path())
+            };
+            let _ = config.profiles.insert(
+                "landing-page".to_string(),
+                PartialHtmlConfig {
+                    heading_ids: Some(true),
+                    ..PartialHtmlConfig::default()
+                },
+            );
+
+            let report = convert_directory(&config).unwrap();
+            let landing = fs::read_to_string(
+                output.path().join("guide/empty.html"),
+            )
+            .unwrap();
+            assert!(landing.contains(r#"<h1 id="launch">Launch</h1>"#));
+            let index =
+                fs::read_to_string(output.path().join("index.html"))
+                    .unwrap();
+            assert_eq!(index, "<h1>Home</h1>\n");
+            assert_eq!(report.failures().len(), 1);
+            assert!(report.failures()[0]
+                .message
+                .contains("Unknown pipeline profile: missing"));
+        }
+
+        #[test]
+        fn test_single_file() {
+            let (input, output) = create_site();
+            fs::write(input.path().join("guide/dot.gif"), b"GIF89a")
+                .unwrap();
+            fs::write(
+                input.path().join("guide/empty.md"),
+                "![Dot](dot.gif) ![Gone](gone.png)",
+            )
+            .unwrap();
+            let config = BatchConfig {
+                single_file: true,
+                ..BatchConfig::new(input.path(), output.path())
+            };
+
+            let report = convert_directory(&config).unwrap();
+            let guide = fs::read_to_string(
+                output.path().join("guide/empty.html"),
+            )
+            .unwrap();
+            assert!(guide
+                .contains("src=\"data:image/gif;base64,R0lGODlh\""));
+            let missing: Vec<_> = report
+                .diagnostics()
+                .iter()
+                .filter(|d| d.code == "missing_asset")
+                .collect();
+            assert_eq!(missing.len(), 1);
+            assert_eq!(
+                missing[0].path.as_deref(),
+                Some(input.path().join("guide/empty.md").as_path())
+            );
+        }
+
+        #[test]
+        fn test_front_matter_slug_and_permalink() {
+            let (input, output) = create_site();
+            fs::write(
+                input.path().join("guide/empty.md"),
+                "---\nslug: Getting Started\n---\n# Guide",
+            )
+            .unwrap();
+            fs::write(
+                input.path().join("post.md"),
+                "---\npermalink: /blog/2025/hello/\n---\n# Post",
+            )
+            .unwrap();
+            fs::write(
+                input.path().join("escape.md"),
+                "---\npermalink: ../outside.html\n---\n# Nope",
+            )
+            .unwrap();
+            let config = BatchConfig {
+                keep_going: true,
+                ..BatchConfig::new(input.path(), output.path())
+            };
+
+            let report = convert_directory(&config).unwrap();
+            let urls: Vec<_> = report
+                .converted()
+                .iter()
+                .map(|file| file.url.as_str())
+                .collect();
+            assert_eq!(
+                urls,
+                vec![
+                    "/guide/getting-started.html",
+                    "/index.html",
+                    "/blog/2025/hello/index.html"
+                ]
+            );
+            assert_eq!(report.failures().len(), 1);
+            assert_eq!(report.failures()[0].stage, BatchStage::Write);
+            assert!(!output.path().join("../outside.html").exists());
+        }
+
+        #[test]
+        fn test_slug_does_not_take_a_kept_file_name() {
+            let (input, output) = create_site();
+            fs::write(input.path().join("guide.md"), "# Guide")
+                .unwrap();
+            fs::write(
+                input.path().join("a-page.md"),
+                "---\nslug: guide\n---\n# Page",
+            )
+            .unwrap();
+            let config = BatchConfig {
+                keep_going: true,
+                ..BatchConfig::new(input.path(), output.path())
+            };
+
+            let report = convert_directory(&config).unwrap();
+            let urls: Vec<_> = report
+                .converted()
+                .iter()
+                .map(|file| file.url.as_str())
+                .collect();
+            assert_eq!(
+                urls,
+                vec!["/guide-2.html", "/guide.html", "/index.html"]
+            );
+            assert!(fs::read_to_string(
+                output.path().join("guide.html")
+            )
+            .unwrap()
+            .contains("Guide"));
+        }
+
+        #[test]
+        fn test_permalink_output_conflict() {
+            let (input, output) = create_site();
+            fs::write(input.path().join("guide.md"), "# Guide")
+                .unwrap();
+            fs::write(
+                input.path().join("post.md"),
+                "---\npermalink: /guide.html\n---\n# Post",
+            )
+            .unwrap();
+            let config = BatchConfig {
+                keep_going: true,
+                ..BatchConfig::new(input.path(), output.path())
+            };
+
+            let report = convert_directory(&config).unwrap();
+            let failure = report
+                .failures()
+                .iter()
+                .find(|failure| failure.source.ends_with("post.md"))
+                .unwrap();
+            assert_eq!(failure.code, "output_conflict");
+            assert_eq!(failure.stage, BatchStage::Write);
+            assert_eq!(report.manifest().len(), 2);
+            assert!(fs::read_to_string(
+                output.path().join("guide.html")
+            )
+            .unwrap()
+            .contains("Guide"));
+        }
+
+        #[test]
+        fn test_output_conflict_aborts_without_keep_going() {
+            let (input, output) = create_site();
+            fs::write(input.path().join("guide/empty.md"), "## Guide")
+                .unwrap();
+            fs::write(
+                input.path().join("post.md"),
+                "---\npermalink: /index.html\n---\n# Post",
+            )
+            .unwrap();
+            let config = BatchConfig::new(input.path(), output.path());
+
+            let result = convert_directory(&config);
+            assert!(matches!(
+                result,
+                Err(HtmlError::OutputConflict { .. })
+            ));
+        }
+
+        #[test]
+        fn test_pretty_urls_output_conflict() {
+            let (input, output) = create_site();
+            fs::write(input.path().join("guide.md"), "# Guide")
+                .unwrap();
+            fs::write(input.path().join("guide/index.md"), "# Index")
+                .unwrap();
+            let config = BatchConfig {
+                keep_going: true,
+                naming: OutputNaming::PrettyUrls,
+                ..BatchConfig::new(input.path(), output.path())
+            };
+
+            let report = convert_directory(&config).unwrap();
+            let conflicts: Vec<_> = report
+                .failures()
+                .iter()
+                .filter(|failure| failure.code == "output_conflict")
+                .collect();
+            assert_eq!(conflicts.len(), 1);
+            assert!(conflicts[0].message.contains(
+                &Path::new("guide")
+                    .join("index.html")
+                    .display()
+                    .to_string()
+            ));
+            let urls: Vec<_> = report
+                .converted()
+                .iter()
+                .map(|file| file.url.as_str())
+                .collect();
+            assert_eq!(
+                urls.iter().filter(|url| **url == "/guide/").count(),
+                1
+            );
+        }
+
+        #[test]
+        fn test_copy_assets() {
+            let (input, output) = create_site();
+            fs::create_dir_all(input.path().join("img/drafts"))
+                .unwrap();
+            fs::write(input.path().join("img/logo.png"), b"png")
+                .unwrap();
+            fs::write(input.path().join("img/drafts/wip.png"), b"wip")
+                .unwrap();
+            fs::write(input.path().join("site.css"), "body{}").unwrap();
+            let config = BatchConfig {
+                keep_going: true,
+                copy_assets: true,
+                asset_include: vec!["*.png".into(), "*.css".into()],
+                asset_exclude: vec!["**/drafts/**".into()],
+                ..BatchConfig::new(input.path(), output.path())
+            };
+
+            let report = convert_directory(&config).unwrap();
+            let copied: Vec<_> = report
+                .assets()
+                .iter()
+                .map(|asset| asset.output.clone())
+                .collect();
+            assert_eq!(
+                copied,
+                vec![
+                    output.path().join("img/logo.png"),
+                    output.path().join("site.css"),
+                ]
+            );
+            assert!(report.assets().iter().all(|a| !a.unchanged));
+            assert!(!output.path().join("notes.txt").exists());
+            assert!(!output.path().join("img/drafts").exists());
+            assert_eq!(report.manifest().assets().count(), 2);
+
+            fs::write(input.path().join("site.css"), "body{x}")
+                .unwrap();
+            let report = convert_directory(&config).unwrap();
+            let unchanged: Vec<_> =
+                report.assets().iter().map(|a| a.unchanged).collect();
+            assert_eq!(unchanged, vec![true, false]);
+            assert_eq!(
+                fs::read_to_string(output.path().join("site.css"))
+                    .unwrap(),
+                "body{x}"
+            );
+        }
+
+        #[test]
+        fn test_assets_are_not_copied_by_default() {
+            let (input, output) = create_site();
+            let config = BatchConfig {
+                keep_going: true,
+                ..BatchConfig::new(input.path(), output.path())
+            };
+
+            let report = convert_directory(&config).unwrap();
+            assert!(report.assets().is_empty());
+            assert!(!output.path().join("notes.txt").exists());
+        }
+
+        #[test]
+        fn test_ignore_rules() {
+            let (input, output) = create_site();
+            fs::create_dir_all(input.path().join("drafts")).unwrap();
+            fs::create_dir_all(input.path().join("node_modules/x"))
+                .unwrap();
+            fs::write(input.path().join("drafts/wip.md"), "# W")
+                .unwrap();
+            fs::write(input.path().join("node_modules/x/r.md"), "# R")
+                .unwrap();
+            fs::write(input.path().join("guide/empty.md"), "# G")
+                .unwrap();
+            fs::write(input.path().join("guide/empty.md~"), "x")
+                .unwrap();
+            fs::write(
+                input.path().join("guide/.htmlgenignore"),
+                "*~\n",
+            )
+            .unwrap();
+            fs::write(input.path().join(IGNORE_FILE), "drafts/\n")
+                .unwrap();
+            let config = BatchConfig {
+                copy_assets: true,
+                ignore: vec!["node_modules/".into(), "*.txt".into()],
+                ..BatchConfig::new(input.path(), output.path())
+            };
+
+            let report = convert_directory(&config).unwrap();
+            let sources: Vec<_> = report
+                .converted()
+                .iter()
+                .map(|file| file.source.clone())
+                .collect();
+            assert_eq!(
+                sources,
+                vec![
+                    input.path().join("guide/empty.md"),
+                    input.path().join("index.md"),
+                ]
+            );
+            assert!(report.assets().is_empty());
+        }
+
+        #[cfg(unix)]
+        #[test]
+        fn test_symlink_policies() {
+            use std::os::unix::fs::symlink;
+
+            let (input, output) = create_site();
+            let outside = tempdir().unwrap();
+            fs::write(outside.path().join("secret.md"), "# S").unwrap();
+            fs::write(input.path().join("guide/empty.md"), "# G")
+                .unwrap();
+            symlink(
+                outside.path().join("secret.md"),
+                input.path().join("secret.md"),
+            )
+            .unwrap();
+            symlink(
+                input.path().join("guide"),
+                input.path().join("alias"),
+            )
+            .unwrap();
+            symlink(input.path(), input.path().join("guide/loop"))
+                .unwrap();
+
+            let skip = BatchConfig::new(input.path(), output.path());
+            let report = convert_directory(&skip).unwrap();
+            assert_eq!(report.converted().len(), 2);
+            assert!(report.diagnostics().is_empty());
+
+            let follow = BatchConfig {
+                symlinks: SymlinkPolicy::Follow,
+                ..skip.clone()
+            };
+            let report = convert_directory(&follow).unwrap();
+            let sources: Vec<_> = report
+                .converted()
+                .iter()
+                .map(|file| file.source.clone())
+                .collect();
+            assert_eq!(
+                sources,
+                vec![
+                    input.path().join("alias/empty.md"),
+                    input.path().join("guide/empty.md"),
+                    input.path().join("index.md"),
+                ]
+            );
+            let codes: Vec<_> = report
+                .diagnostics()
+                .iter()
+                .map(|d| d.code.as_str())
+                .collect();
+            assert!(codes.contains(&"symlink_outside_root"));
+            assert!(codes.contains(&"symlink_cycle"));
+            assert!(!output.path().join("secret.html").exists());
+
+            let error = BatchConfig {
+                symlinks: SymlinkPolicy::Error,
+                ..skip
+            };
+            assert!(matches!(
+                convert_directory(&error),
+                Err(HtmlError::InvalidInput(_))
+            ));
+        }
+
+        #[cfg(unix)]
+        #[test]
+        fn test_hard_link_policies() {
+            let (input, output) = create_site();
+            fs::write(input.path().join("guide/empty.md"), "# G")
+                .unwrap();
+            fs::hard_link(
+                input.path().join("index.md"),
+                input.path().join("guide/copy.md"),
+            )
+            .unwrap();
+
+            let skip = BatchConfig::new(input.path(), output.path());
+            let report = convert_directory(&skip).unwrap();
+            let sources: Vec<_> = report
+                .converted()
+                .iter()
+                .map(|file| file.source.clone())
+                .collect();
+            assert_eq!(
+                sources,
+                vec![
+                    input.path().join("guide/copy.md"),
+                    input.path().join("guide/empty.md"),
+                ]
+            );
+            let duplicate = &report.diagnostics()[0];
+            assert_eq!(duplicate.code, "hard_link_duplicate");
+            assert!(duplicate.message.contains("guide/copy.md"));
+
+            let follow = BatchConfig {
+                symlinks: SymlinkPolicy::Follow,
+                ..skip.clone()
+            };
+            let report = convert_directory(&follow).unwrap();
+            assert_eq!(report.converted().len(), 3);
+            assert!(report.diagnostics().is_empty());
+
+            let error = BatchConfig {
+                symlinks: SymlinkPolicy::Error,
+                ..skip
+            };
+            assert!(matches!(
+                convert_directory(&error),
+                Err(HtmlError::InvalidInput(_))
+            ));
+        }
+
+        #[test]
+        fn test_includes_are_relative_to_the_page() {
+            let (input, output) = create_site();
+            fs::write(
+                input.path().join("guide/empty.md"),
+                "# Guide\n\n{{include \"part.md\"}}\n",
+            )
+            .unwrap();
+            fs::write(
+                input.path().join("guide/part.md"),
+                "Shared part",
+            )
+            .unwrap();
+            let config = BatchConfig {
+                markdown_config: MarkdownConfig {
+                    expand_includes: true,
+                    ..Default::default()
+                },
+                ..BatchConfig::new(input.path(), output.path())
+            };
+
+            let _ = convert_directory(&config).unwrap();
+            let html = fs::read_to_string(
+                output.path().join("guide/empty.html"),
+            )
+            .unwrap();
+            assert!(html.contains("Shared part"));
+        }
+
+        #[test]
+        fn test_dry_run_writes_nothing() {
+            let (input, output) = create_site();
+            fs::write(input.path().join("guide/empty.md"), "## Guide")
+                .unwrap();
+            fs::write(output.path().join("index.html"), "old").unwrap();
+            let config = BatchConfig {
+                dry_run: true,
+                ..BatchConfig::new(input.path(), output.path())
+            };
+
+            let report = convert_directory(&config).unwrap();
+            assert!(report.is_dry_run());
+            assert_eq!(report.converted().len(), 2);
+            assert!(!output.path().join("guide").exists());
+            assert_eq!(
+                fs::read_to_string(output.path().join("index.html"))
+                    .unwrap(),
+                "old"
+            );
+
+            assert_eq!(report.diagnostics().len(), 1);
+            assert_eq!(report.diagnostics()[0].code, "overwrite");
+        }
+
+        #[test]
+        fn test_markdown_config_dry_run_is_honoured() {
+            let (input, output) = create_site();
+            let mut config = BatchConfig {
+                keep_going: true,
+                ..BatchConfig::new(input.path(), output.path())
+            };
+            config.markdown_config.dry_run = true;
+
+            let report = convert_directory(&config).unwrap();
+            assert!(report.is_dry_run());
+            assert_eq!(report.failures().len(), 1);
+            assert!(!output.path().join("index.html").exists());
+        }
+
+        #[test]
+        fn test_missing_input_directory() {
+            let output = tempdir().unwrap();
+            let config = BatchConfig {
+                keep_going: true,
+                ..BatchConfig::new(
+                    output.path().join("missing"),
+                    output.path(),
+                )
+            };
+
+            assert!(matches!(
+                convert_directory(&config),
+                Err(HtmlError::Io(_))
+            ));
+        }
+    }
+}
@@ -0,0 +1,334 @@
+// Copyright © 2025 HTML Generator. All rights reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Benchmark corpora and a performance regression harness.
+//!
+//! Available with the `bench` feature. The generators build documents
+//! that stress one part of the conversion each: wide tables, deeply
+//! nested lists, code-heavy pages and pages of headings, which exercise
+//! ID generation. They are deterministic, so
+//! measurements of different versions of this crate are comparable.
+//! The criterion benches of the repository (`cargo bench --features
+//! bench`) run every stage on [`corpora`].
+//!
+//! Without criterion, [`measure_stages`] times each stage of a
+//! conversion with a [`Profiler`], and [`regressions`] compares the
+//! result to timings recorded earlier, e.g. by a previous release.
+//!
+//! # Examples
+//!
+//! ```
+//! use html_generator::bench::{code_heavy, measure_stages, regressions};
+//! use html_generator::HtmlConfig;
+//!
+//! let markdown = code_heavy(4);
+//! let baseline = measure_stages(&markdown, &HtmlConfig::default(), 3)?;
+//! assert!(baseline.contains_key("highlight"));
+//!
+//! let current = measure_stages(&markdown, &HtmlConfig::default(), 3)?;
+//! for regression in regressions(&baseline, &current, 10.0) {
+//!     println!("{}", regression);
+//! }
+//! # Ok::<(), html_generator::error::HtmlError>(())
+//! ```
+
+use crate::conversion::convert;
+use crate::error::Result;
+use crate::profiler::Profiler;
+use crate::HtmlConfig;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::time::Duration;
+
+/// Languages cycled through by [`code_heavy`].
+const CODE_SAMPLES: [(&str, &str); 3] = [
+    (
+        "rust",
+        "fn fibonacci(n: u64) -> u64 {\n    match n {\n        0 | 1 => n,\n        _ => fibonacci(n - 1) + fibonacci(n - 2),\n    }\n}\n",
+    ),
+    (
+        "python",
+        "def fibonacci(n):\n    if n < 2:\n        return n\n    return fibonacci(n - 1) + fibonacci(n - 2)\n",
+    ),
+    (
+        "javascript",
+        "function fibonacci(n) {\n  return n < 2 ? n : fibonacci(n - 1) + fibonacci(n - 2);\n}\n",
+    ),
+];
+
+/// A named benchmark document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Corpus {
+    /// Name of the document, used as benchmark ID.
+    pub name: &'static str,
+    /// The Markdown.
+    pub markdown: String,
+}
+
+/// Average duration of each stage of a conversion, by stage name.
+pub type StageTimings = BTreeMap<String, Duration>;
+
+/// A stage slower than in the baseline, see [`regressions`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Regression {
+    /// The stage.
+    pub stage: String,
+    /// Its duration in the baseline.
+    pub baseline: Duration,
+    /// Its current duration.
+    pub current: Duration,
+}
+
+impl Regression {
+    /// Returns how many percent slower the stage became.
+    pub fn slowdown_percent(&self) -> f64 {
+        let baseline = self.baseline.as_secs_f64();
+        if baseline == 0.0 {
+            return f64::INFINITY;
+        }
+        (self.current.as_secs_f64() / baseline - 1.0) * 100.0
+    }
+}
+
+impl fmt::Display for Regression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {:?} -> {:?} (+{:.1}%)",
+            self.stage,
+            self.baseline,
+            self.current,
+            self.slowdown_percent()
+        )
+    }
+}
+
+/// Returns a table of `rows` rows and `columns` columns.
+pub fn large_table(rows: usize, columns: usize) -> String {
+    let mut markdown = String::new();
+    let row = |cell: &dyn Fn(usize) -> String| {
+        let cells: Vec<_> = (0..columns).map(cell).collect();
+        format!("| {} |\n", cells.join(" | "))
+    };
+    markdown.push_str(&row(&|column| format!("Column {}", column + 1)));
+    markdown.push_str(&row(&|_| "---".to_string()));
+    for index in 0..rows {
+        markdown.push_str(&row(&|column| {
+            format!("*Cell* {}.{} with `code`", index + 1, column + 1)
+        }));
+    }
+    markdown
+}
+
+/// Returns a list nested `depth` levels deep, with `width` items at
+/// each level.
+pub fn deep_list(depth: usize, width: usize) -> String {
+    let mut markdown = String::new();
+    for level in 0..depth {
+        let indent = "  ".repeat(level);
+        for item in 0..width {
+            markdown.push_str(&format!(
+                "{}- Level {} item {} with a [link](https://example.com/{}/{})\n",
+                indent,
+                level + 1,
+                item + 1,
+                level,
+                item
+            ));
+        }
+    }
+    markdown
+}
+
+/// Returns `blocks` sections, each with a heading, a paragraph and a
+/// fenced code block in Rust, Python or JavaScript.
+pub fn code_heavy(blocks: usize) -> String {
+    let mut markdown = String::new();
+    for index in 0..blocks {
+        let (language, code) = CODE_SAMPLES[index % CODE_SAMPLES.len()];
+        markdown.push_str(&format!(
+            "## Example {}\n\nComputing Fibonacci numbers in {}.\n\n```{}\n{}```\n\n",
+            index + 1,
+            language,
+            language,
+            code
+        ));
+    }
+    markdown
+}
+
+/// Returns `headings` short sections under headings of levels two to
+/// four, one in three with non-ASCII text and punctuation.
+pub fn heading_heavy(headings: usize) -> String {
+    let mut markdown = String::new();
+    for index in 0..headings {
+        let level = "#".repeat(2 + index % 3);
+        let title = if index % 3 == 2 {
+            format!(
+                "Überblick {} — Größe und „Einstellungen“",
+                index + 1
+            )
+        } else {
+            format!(
+                "Section {}: Getting started (part {})",
+                index + 1,
+                index % 7
+            )
+        };
+        markdown.push_str(&format!(
+            "{} {}\n\nSee [the next section](#section-{}).\n\n",
+            level,
+            title,
+            index + 2
+        ));
+    }
+    markdown
+}
+
+/// Returns the representative documents benchmarked by the repository.
+pub fn corpora() -> Vec<Corpus> {
+    vec![
+        Corpus {
+            name: "large_table",
+            markdown: large_table(500, 8),
+        },
+        Corpus {
+            name: "deep_list",
+            markdown: deep_list(12, 40),
+        },
+        Corpus {
+            name: "code_heavy",
+            markdown: code_heavy(60),
+        },
+        Corpus {
+            name: "heading_heavy",
+            markdown: heading_heavy(1_000),
+        },
+    ]
+}
+
+/// Converts `markdown` `iterations` times and returns the average
+/// duration of each stage.
+///
+/// # Arguments
+///
+/// * `markdown` - The document
+/// * `config` - The configuration, whose profiler is replaced
+/// * `iterations` - Number of conversions, at least one is made
+///
+/// # Errors
+///
+/// Returns the error of the conversion.
+pub fn measure_stages(
+    markdown: &str,
+    config: &HtmlConfig,
+    iterations: u32,
+) -> Result<StageTimings> {
+    let iterations = iterations.max(1);
+    let profiler = Profiler::new();
+    let config = HtmlConfig {
+        profiler: Some(profiler.clone()),
+        ..config.clone()
+    };
+    for _ in 0..iterations {
+        let _ = convert(markdown, &config)?;
+    }
+    Ok(profiler
+        .stage_totals()
+        .into_iter()
+        .map(|(stage, total)| (stage, total / iterations))
+        .collect())
+}
+
+/// Returns the stages more than `tolerance_percent` slower in `current`
+/// than in `baseline`, most slowed down first.
+///
+/// Stages missing from either side are ignored.
+pub fn regressions(
+    baseline: &StageTimings,
+    current: &StageTimings,
+    tolerance_percent: f64,
+) -> Vec<Regression> {
+    let mut regressions: Vec<_> = current
+        .iter()
+        .filter_map(|(stage, current)| {
+            baseline.get(stage).map(|baseline| Regression {
+                stage: stage.clone(),
+                baseline: *baseline,
+                current: *current,
+            })
+        })
+        .filter(|regression| {
+            regression.slowdown_percent() > tolerance_percent
+        })
+        .collect();
+    regressions.sort_by(|a, b| {
+        b.slowdown_percent()
+            .partial_cmp(&a.slowdown_percent())
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    regressions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_corpora_render() {
+        let config = HtmlConfig {
+            enhance_tables: true,
+            ..HtmlConfig::default()
+        };
+        let table = convert(&large_table(3, 2), &config).unwrap();
+        assert_eq!(table.matches("<tr").count(), 4);
+        let list = convert(&deep_list(3, 2), &config).unwrap();
+        assert_eq!(list.matches("<ul>").count(), 3);
+        let code = convert(&code_heavy(3), &config).unwrap();
+        assert_eq!(code.matches("<pre").count(), 3);
+        let config = HtmlConfig {
+            heading_ids: true,
+            ..config
+        };
+        let headings = convert(&heading_heavy(3), &config).unwrap();
+        assert!(headings
+            .contains("id=\"section-1-getting-started-part-0\""));
+        assert!(headings
+            .contains("id=\"überblick-3-größe-und-einstellungen\""));
+        assert_eq!(corpora().len(), 4);
+    }
+
+    #[test]
+    fn test_regressions() {
+        let timings = |entries: &[(&str, u64)]| -> StageTimings {
+            entries
+                .iter()
+                .map(|(stage, ms)| {
+                    (stage.to_string(), Duration::from_millis(*ms))
+                })
+                .collect()
+        };
+        let baseline =
+            timings(&[("markdown", 10), ("highlight", 10), ("toc", 5)]);
+        let current = timings(&[
+            ("markdown", 10),
+            ("highlight", 30),
+            ("toc", 6),
+            ("math", 1),
+        ]);
+        let found = regressions(&baseline, &current, 10.0);
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0].stage, "highlight");
+        assert_eq!(
+            found[0].to_string(),
+            "highlight: 10ms -> 30ms (+200.0%)"
+        );
+        assert_eq!(found[1].stage, "toc");
+        assert!(regressions(&baseline, &current, 500.0).is_empty());
+
+        let measured =
+            measure_stages(&code_heavy(2), &HtmlConfig::default(), 2)
+                .unwrap();
+        assert!(measured.contains_key("markdown"));
+    }
+}
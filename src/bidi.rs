@@ -0,0 +1,261 @@
+// Copyright © 2025 HTML Generator. All rights reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Text direction for right-to-left languages.
+//!
+//! [`HtmlConfig::direction`] sets the base direction of the output,
+//! written as the `dir` attribute of a `<div>` wrapping the fragment,
+//! so Arabic or Hebrew documents lay out right to left.
+//! [`HtmlConfig::auto_direction`] adds `dir="auto"` to paragraphs, list
+//! items, headings, quotes and table cells, letting browsers pick the
+//! direction of each block from its first strong character; blocks that
+//! already have a `dir` are left alone.
+//!
+//! Inline runs in the other direction, such as an English product name
+//! in an Arabic sentence, still need a `<bdi>` or an element with a
+//! `dir` attribute around them to be ordered correctly. The
+//! `mixed_direction` check of
+//! [`validate_wcag`](crate::accessibility::validate_wcag) reports
+//! blocks where such a run is not isolated.
+//!
+//! [`HtmlConfig::direction`]: crate::HtmlConfig::direction
+//! [`HtmlConfig::auto_direction`]: crate::HtmlConfig::auto_direction
+//!
+//! # Examples
+//!
+//! ```
+//! use html_generator::bidi::TextDirection;
+//! use html_generator::{conversion::convert, HtmlConfig};
+//!
+//! let config = HtmlConfig {
+//!     direction: Some(TextDirection::Rtl),
+//!     auto_direction: true,
+//!     ..HtmlConfig::default()
+//! };
+//! let html = convert("مرحبا بالعالم", &config)?;
+//! assert_eq!(html, "<div dir=\"rtl\"><p dir=\"auto\">مرحبا بالعالم</p>\n</div>");
+//! # Ok::<(), html_generator::error::HtmlError>(())
+//! ```
+
+use crate::error::{HtmlError, Result};
+use crate::patterns::bidi::{BLOCK_REGEX, DIR_ATTRIBUTE_REGEX};
+use regex::Captures;
+use std::fmt;
+use std::str::FromStr;
+
+/// Elements holding user content whose direction is set by
+/// [`add_auto_direction`].
+pub(crate) const USER_CONTENT_BLOCKS: &[&str] = &[
+    "p",
+    "li",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "blockquote",
+    "dt",
+    "dd",
+    "td",
+    "th",
+    "figcaption",
+];
+
+/// Base direction of text.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum TextDirection {
+    /// Left to right.
+    Ltr,
+    /// Right to left, for Arabic, Hebrew, Persian or Urdu.
+    Rtl,
+    /// Chosen by the browser from the first strong character.
+    Auto,
+}
+
+impl Default for TextDirection {
+    fn default() -> Self {
+        TextDirection::Ltr
+    }
+}
+
+impl fmt::Display for TextDirection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            TextDirection::Ltr => "ltr",
+            TextDirection::Rtl => "rtl",
+            TextDirection::Auto => "auto",
+        })
+    }
+}
+
+impl FromStr for TextDirection {
+    type Err = HtmlError;
+
+    /// Parses `ltr`, `rtl` or `auto`.
+    fn from_str(value: &str) -> Result<Self> {
+        match value {
+            "ltr" => Ok(TextDirection::Ltr),
+            "rtl" => Ok(TextDirection::Rtl),
+            "auto" => Ok(TextDirection::Auto),
+            _ => Err(HtmlError::InvalidInput(format!(
+                "Unknown text direction: {}",
+                value
+            ))),
+        }
+    }
+}
+
+/// Returns `true` if `c` is a strong right-to-left character.
+pub fn is_rtl_char(c: char) -> bool {
+    matches!(
+        c,
+        '\u{0590}'..='\u{08FF}'
+            | '\u{FB1D}'..='\u{FDFF}'
+            | '\u{FE70}'..='\u{FEFF}'
+            | '\u{10800}'..='\u{10FFF}'
+            | '\u{1E800}'..='\u{1EFFF}'
+    ) && c.is_alphabetic()
+}
+
+/// Returns `true` if `text` contains strong characters of both
+/// directions.
+///
+/// Digits, punctuation and whitespace are neutral or weak and do not
+/// count.
+pub fn has_mixed_direction(text: &str) -> bool {
+    let (mut rtl, mut ltr) = (false, false);
+    for c in text.chars().filter(|c| c.is_alphabetic()) {
+        if is_rtl_char(c) {
+            rtl = true;
+        } else {
+            ltr = true;
+        }
+        if rtl && ltr {
+            return true;
+        }
+    }
+    false
+}
+
+/// Adds `dir="auto"` to the user-content blocks of `html` that have no
+/// `dir` attribute.
+///
+/// # Arguments
+///
+/// * `html` - The HTML to rewrite
+///
+/// # Returns
+///
+/// The HTML with per-block directions.
+pub fn add_auto_direction(html: &str) -> String {
+    BLOCK_REGEX
+        .replace_all(html, |caps: &Captures<'_>| {
+            if DIR_ATTRIBUTE_REGEX.is_match(&caps[2]) {
+                caps[0].to_string()
+            } else {
+                format!(r#"<{}{} dir="auto">"#, &caps[1], &caps[2])
+            }
+        })
+        .into_owned()
+}
+
+/// Wraps `html` in a `<div>` with the base direction `direction`.
+///
+/// # Arguments
+///
+/// * `html` - The HTML fragment
+/// * `direction` - The base direction
+///
+/// # Returns
+///
+/// The wrapped fragment.
+pub fn set_direction(html: &str, direction: TextDirection) -> String {
+    format!(r#"<div dir="{}">{}</div>"#, direction, html)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::accessibility::{
+        validate_wcag, AccessibilityConfig, IssueType,
+    };
+    use crate::conversion::convert;
+    use crate::HtmlConfig;
+
+    #[test]
+    fn test_direction_round_trip() {
+        for direction in [
+            TextDirection::Ltr,
+            TextDirection::Rtl,
+            TextDirection::Auto,
+        ] {
+            assert_eq!(
+                direction.to_string().parse::<TextDirection>().unwrap(),
+                direction
+            );
+        }
+        assert!("right".parse::<TextDirection>().is_err());
+    }
+
+    #[test]
+    fn test_mixed_direction_detection() {
+        assert!(is_rtl_char('ש'));
+        assert!(is_rtl_char('ع'));
+        assert!(!is_rtl_char('٣'));
+        assert!(has_mixed_direction("שלום world"));
+        assert!(!has_mixed_direction("שלום 2025!"));
+        assert!(!has_mixed_direction("hello world"));
+    }
+
+    #[test]
+    fn test_auto_direction() {
+        let html = add_auto_direction(concat!(
+            r#"<p>a</p><P class="x">b</P><li dir="rtl">c</li>"#,
+            r#"<pre><code>d</code></pre><table><thead><tr><th>e</th>"#,
+            r#"<td data-x='>'>f</td></tr></thead></table>"#
+        ));
+        assert_eq!(
+            html,
+            concat!(
+                r#"<p dir="auto">a</p><P class="x" dir="auto">b</P>"#,
+                r#"<li dir="rtl">c</li><pre><code>d</code></pre><table>"#,
+                r#"<thead><tr><th dir="auto">e</th>"#,
+                r#"<td data-x='>' dir="auto">f</td></tr></thead></table>"#
+            )
+        );
+        assert_eq!(add_auto_direction(&html), html);
+    }
+
+    #[test]
+    fn test_conversion_and_isolation_check() {
+        let config = HtmlConfig {
+            direction: Some(TextDirection::Rtl),
+            auto_direction: true,
+            ..HtmlConfig::default()
+        };
+        let html = convert(
+            "# عنوان\n\nاستخدم <bdi>html-generator</bdi> الآن.\n\nاستخدم Rust الآن.\n",
+            &config,
+        )
+        .unwrap();
+        assert!(html.starts_with(r#"<div dir="rtl"><h1 dir="auto">"#));
+        assert!(html.ends_with("</div>"));
+
+        let report =
+            validate_wcag(&html, &AccessibilityConfig::default(), None)
+                .unwrap();
+        let mixed: Vec<_> = report
+            .issues
+            .iter()
+            .filter(|issue| {
+                issue.issue_type == IssueType::MixedDirection
+            })
+            .collect();
+        assert_eq!(mixed.len(), 1);
+        assert!(mixed[0].element.as_deref().unwrap().contains("Rust"));
+
+        let plain = convert("שלום", &HtmlConfig::default()).unwrap();
+        assert_eq!(plain, "<p>שלום</p>\n");
+    }
+}
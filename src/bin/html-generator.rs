@@ -0,0 +1,348 @@
+// Copyright © 2025 HTML Generator. All rights reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! The `html-generator` command line tool.
+//!
+//! Built with the `cli` feature:
+//!
+//! ```text
+//! html-generator convert README.md -o README.html --config site.toml
+//! cat page.html | html-generator validate-wcag --level aa
+//! html-generator validate-wcag site/index.html --format sarif -o wcag.sarif
+//! html-generator minify page.html
+//! html-generator seo page.html --structured-data
+//! html-generator toc guide.md
+//! ```
+//!
+//! Inputs default to standard input, or `-`, and outputs to standard
+//! output. The exit code is 0 on success, 1 when `validate-wcag` finds
+//! issues, 2 for invalid arguments and 3 when a command fails.
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use html_generator::accessibility::{
+    validate_wcag, AccessibilityConfig, Severity, WcagLevel,
+};
+use html_generator::conversion::convert_detailed;
+use html_generator::error::{HtmlError, Result};
+use html_generator::front_matter::{
+    parse_front_matter, FrontMatterFormat,
+};
+use html_generator::performance::minify_html_str;
+use html_generator::seo::{
+    generate_meta_tags, generate_structured_data,
+};
+use html_generator::templates::DocumentTemplate;
+use html_generator::toc::TocPlacement;
+use html_generator::{
+    markdown_to_html, markdown_to_html_document, HtmlConfig,
+    MarkdownConfig,
+};
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::PathBuf;
+use std::process;
+
+/// Exit code when a check finds issues.
+const EXIT_ISSUES: i32 = 1;
+
+/// Exit code when a command fails.
+const EXIT_FAILURE: i32 = 3;
+
+/// Converts Markdown to accessible HTML and checks HTML pages.
+#[derive(Debug, Parser)]
+#[command(
+    name = "html-generator",
+    version,
+    after_help = "Exit codes: 0 on success, 1 when validate-wcag finds issues, 2 for invalid arguments, 3 when a command fails."
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Converts Markdown to HTML
+    Convert {
+        #[command(flatten)]
+        io: InputOutput,
+        #[command(flatten)]
+        config: ConfigFile,
+        /// Inserts a table of contents
+        #[arg(long)]
+        toc: bool,
+        /// Writes a complete HTML document instead of a fragment
+        #[arg(long)]
+        standalone: bool,
+    },
+    /// Checks HTML against the WCAG guidelines
+    ValidateWcag {
+        #[command(flatten)]
+        io: InputOutput,
+        /// Conformance level to check
+        #[arg(long, value_enum, default_value = "aa")]
+        level: Level,
+        /// Lowest severity of the issues failing the check
+        #[arg(long, value_enum, default_value = "error")]
+        fail_on: FailOn,
+        /// Format of the issues
+        #[arg(long, value_enum, default_value = "text")]
+        format: ReportFormat,
+    },
+    /// Minifies HTML
+    Minify {
+        #[command(flatten)]
+        io: InputOutput,
+    },
+    /// Generates meta tags from the title and description of HTML
+    Seo {
+        #[command(flatten)]
+        io: InputOutput,
+        /// Also generates JSON-LD structured data
+        #[arg(long)]
+        structured_data: bool,
+    },
+    /// Prints the table of contents of Markdown
+    Toc {
+        #[command(flatten)]
+        io: InputOutput,
+        #[command(flatten)]
+        config: ConfigFile,
+    },
+}
+
+#[derive(Debug, Args)]
+struct InputOutput {
+    /// Input file; standard input if omitted or `-`
+    input: Option<PathBuf>,
+    /// Output file; standard output if omitted
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+struct ConfigFile {
+    /// TOML, YAML or JSON file of `HtmlConfig` settings
+    #[arg(short, long)]
+    config: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Level {
+    A,
+    Aa,
+    Aaa,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum FailOn {
+    Error,
+    Warning,
+    Info,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum ReportFormat {
+    /// One line per issue
+    Text,
+    /// The report as JSON
+    Json,
+    /// A SARIF log, e.g. for GitHub code scanning
+    Sarif,
+}
+
+impl FailOn {
+    /// Returns the lowest severity failing the check.
+    fn severity(self) -> Severity {
+        match self {
+            Self::Error => Severity::Error,
+            Self::Warning => Severity::Warning,
+            Self::Info => Severity::Info,
+        }
+    }
+}
+
+impl InputOutput {
+    /// Reads the input file or standard input.
+    fn read(&self) -> Result<String> {
+        match &self.input {
+            Some(path) if path.as_os_str() != "-" => {
+                Ok(fs::read_to_string(path)?)
+            }
+            _ => {
+                let mut input = String::new();
+                let _ = io::stdin().read_to_string(&mut input)?;
+                Ok(input)
+            }
+        }
+    }
+
+    /// Returns the name of the input in reports.
+    fn input_name(&self) -> String {
+        match &self.input {
+            Some(path) if path.as_os_str() != "-" => {
+                path.display().to_string()
+            }
+            _ => "stdin".to_string(),
+        }
+    }
+
+    /// Writes `content` to the output file or standard output.
+    fn write(&self, content: &str) -> Result<()> {
+        match &self.output {
+            Some(path) => fs::write(path, content)?,
+            None => {
+                let mut stdout = io::stdout().lock();
+                stdout.write_all(content.as_bytes())?;
+                if !content.ends_with('\n') {
+                    stdout.write_all(b"\n")?;
+                }
+                stdout.flush()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl ConfigFile {
+    /// Returns the configuration of the file, or the default one.
+    fn load(&self) -> Result<HtmlConfig> {
+        match &self.config {
+            Some(path) => HtmlConfig::from_file(path),
+            None => Ok(HtmlConfig::default()),
+        }
+    }
+}
+
+fn main() {
+    let code = match run(Cli::parse()) {
+        Ok(code) => code,
+        Err(error) => {
+            eprintln!("html-generator: {}", error);
+            EXIT_FAILURE
+        }
+    };
+    process::exit(code);
+}
+
+/// Returns `value` as indented JSON.
+fn to_json(value: &serde_json::Value) -> Result<String> {
+    serde_json::to_string_pretty(value)
+        .map_err(|e| HtmlError::InvalidInput(e.to_string()))
+}
+
+/// Runs `cli`, returning the exit code.
+fn run(cli: Cli) -> Result<i32> {
+    match cli.command {
+        Command::Convert {
+            io,
+            config,
+            toc,
+            standalone,
+        } => {
+            let mut html_config = config.load()?;
+            html_config.generate_toc |= toc;
+            let config = MarkdownConfig {
+                html_config,
+                ..MarkdownConfig::default()
+            };
+            let markdown = io.read()?;
+            let html = if standalone {
+                markdown_to_html_document(
+                    &markdown,
+                    Some(config),
+                    &DocumentTemplate::new(),
+                )?
+            } else {
+                markdown_to_html(&markdown, Some(config))?
+            };
+            io.write(&html)?;
+        }
+        Command::ValidateWcag {
+            io,
+            level,
+            fail_on,
+            format,
+        } => {
+            let config = AccessibilityConfig {
+                wcag_level: match level {
+                    Level::A => WcagLevel::A,
+                    Level::Aa => WcagLevel::AA,
+                    Level::Aaa => WcagLevel::AAA,
+                },
+                ..AccessibilityConfig::default()
+            };
+            let report = validate_wcag(&io.read()?, &config, None)
+                .map_err(|e| HtmlError::InvalidInput(e.to_string()))?;
+            let output = match format {
+                ReportFormat::Text => {
+                    let input = io.input_name();
+                    let lines: Vec<_> = report
+                        .issues
+                        .iter()
+                        .map(|issue| {
+                            let mut line = issue
+                                .location
+                                .map(|location| {
+                                    format!("{}:{}: ", input, location)
+                                })
+                                .unwrap_or_default();
+                            line.push_str(&format!(
+                                "{} [{}] {}",
+                                issue.severity,
+                                issue.issue_type.code(),
+                                issue.message
+                            ));
+                            if let Some(guideline) = &issue.guideline {
+                                line.push_str(&format!(
+                                    " ({})",
+                                    guideline
+                                ));
+                            }
+                            line
+                        })
+                        .collect();
+                    lines.join("\n")
+                }
+                ReportFormat::Json => to_json(&report.to_json())?,
+                ReportFormat::Sarif => {
+                    to_json(&report.to_sarif(&io.input_name()))?
+                }
+            };
+            io.write(&output)?;
+            let failing = report
+                .issues
+                .iter()
+                .any(|issue| issue.severity <= fail_on.severity());
+            if failing {
+                return Ok(EXIT_ISSUES);
+            }
+        }
+        Command::Minify { io } => {
+            io.write(&minify_html_str(&io.read()?)?)?
+        }
+        Command::Seo {
+            io,
+            structured_data,
+        } => {
+            let html = io.read()?;
+            let mut head = generate_meta_tags(&html)?;
+            if structured_data {
+                head.push('\n');
+                head.push_str(&generate_structured_data(&html, None)?);
+            }
+            io.write(&head)?;
+        }
+        Command::Toc { io, config } => {
+            let mut config = config.load()?;
+            config.generate_toc = true;
+            config.toc.placement = TocPlacement::Separate;
+            let markdown = io.read()?;
+            let (_, body) =
+                parse_front_matter(&markdown, FrontMatterFormat::Auto)?;
+            let toc = convert_detailed(body, &config)?.toc;
+            io.write(&toc.unwrap_or_default())?;
+        }
+    }
+    Ok(0)
+}
@@ -0,0 +1,184 @@
+// Copyright © 2025 HTML Generator. All rights reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Information about how this crate was built.
+//!
+//! Applications embedding the crate can use this module to report
+//! exactly which pipeline produced a given output: the crate version,
+//! the compiler, the enabled features and the versions of the Markdown,
+//! highlighting and minification dependencies. [`BuildInfo::fingerprint`]
+//! condenses all of it into a short string suitable for cache keys.
+//!
+//! Dependency versions are the requirements declared in `Cargo.toml`:
+//! the build script cannot tell which lock file resolved them when the
+//! crate is built as a dependency of another workspace.
+//!
+//! # Examples
+//!
+//! ```
+//! use html_generator::build_info;
+//!
+//! let info = build_info::build_info();
+//! assert_eq!(info.crate_version, env!("CARGO_PKG_VERSION"));
+//! println!("{}", info);
+//! ```
+
+use crate::utils::content_hash;
+use alloc::string::String;
+use core::fmt;
+
+/// Version of this crate.
+pub const CRATE_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Minimum supported Rust version enforced by the build script.
+pub const MSRV: &str = env!("HTML_GENERATOR_MSRV");
+
+/// Version of the Rust compiler used to build the crate.
+pub const RUSTC_VERSION: &str = env!("HTML_GENERATOR_RUSTC_VERSION");
+
+/// Required version of the `comrak` Markdown parser.
+pub const COMRAK_VERSION: &str = env!("HTML_GENERATOR_COMRAK_VERSION");
+
+/// Required version of the `minify-html` minifier.
+pub const MINIFY_HTML_VERSION: &str =
+    env!("HTML_GENERATOR_MINIFY_HTML_VERSION");
+
+/// Required version of the `mdx-gen` Markdown extensions.
+pub const MDX_GEN_VERSION: &str =
+    env!("HTML_GENERATOR_MDX_GEN_VERSION");
+
+/// Required version of the `syntect` syntax highlighter.
+pub const SYNTECT_VERSION: &str =
+    env!("HTML_GENERATOR_SYNTECT_VERSION");
+
+/// Cargo features enabled for this build, in alphabetical order.
+pub const FEATURES: &[&str] =
+    include!(concat!(env!("OUT_DIR"), "/features.rs"));
+
+/// A snapshot of the build configuration.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct BuildInfo {
+    /// Version of this crate.
+    pub crate_version: &'static str,
+    /// Minimum supported Rust version.
+    pub msrv: &'static str,
+    /// Version of the Rust compiler used for the build.
+    pub rustc_version: &'static str,
+    /// Required version of `comrak`.
+    pub comrak_version: &'static str,
+    /// Required version of `minify-html`.
+    pub minify_html_version: &'static str,
+    /// Required version of `mdx-gen`.
+    pub mdx_gen_version: &'static str,
+    /// Required version of `syntect`.
+    pub syntect_version: &'static str,
+    /// Enabled Cargo features.
+    pub features: &'static [&'static str],
+}
+
+impl BuildInfo {
+    /// Returns `true` if the named Cargo feature is enabled.
+    pub fn has_feature(&self, feature: &str) -> bool {
+        self.features.contains(&feature)
+    }
+
+    /// Returns a stable hash of every field.
+    ///
+    /// Two builds share a fingerprint only if they use the same crate,
+    /// compiler, dependency versions and features, which makes the value
+    /// suitable as part of an output cache key.
+    pub fn fingerprint(&self) -> String {
+        content_hash(self.to_string().as_bytes())
+    }
+}
+
+impl fmt::Display for BuildInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "html-generator {} (rustc {}, msrv {}, comrak {}, minify-html {}, mdx-gen {}, syntect {}, features [{}])",
+            self.crate_version,
+            self.rustc_version,
+            self.msrv,
+            self.comrak_version,
+            self.minify_html_version,
+            self.mdx_gen_version,
+            self.syntect_version,
+            self.features.join(", ")
+        )
+    }
+}
+
+/// Returns information about the current build.
+pub fn build_info() -> BuildInfo {
+    BuildInfo {
+        crate_version: CRATE_VERSION,
+        msrv: MSRV,
+        rustc_version: RUSTC_VERSION,
+        comrak_version: COMRAK_VERSION,
+        minify_html_version: MINIFY_HTML_VERSION,
+        mdx_gen_version: MDX_GEN_VERSION,
+        syntect_version: SYNTECT_VERSION,
+        features: FEATURES,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_info_matches_constants() {
+        let info = build_info();
+        assert_eq!(info.crate_version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(info.comrak_version, COMRAK_VERSION);
+        assert!(!info.rustc_version.is_empty());
+        assert!(info.msrv.starts_with("1."));
+    }
+
+    #[test]
+    fn test_dependency_versions_are_read() {
+        assert!(COMRAK_VERSION.starts_with("0.35"));
+        assert!(MINIFY_HTML_VERSION.starts_with("0.15"));
+        assert!(SYNTECT_VERSION.starts_with("5."));
+    }
+
+    #[test]
+    fn test_features() {
+        let info = build_info();
+        assert_eq!(info.has_feature("std"), cfg!(feature = "std"));
+        assert_eq!(
+            info.has_feature("schema"),
+            cfg!(feature = "schema")
+        );
+        assert_eq!(
+            info.has_feature("config-file"),
+            cfg!(feature = "config-file")
+        );
+        assert!(!info.has_feature("unknown"));
+
+        let mut sorted = info.features.to_vec();
+        sorted.sort_unstable();
+        assert_eq!(info.features, sorted);
+    }
+
+    #[test]
+    fn test_fingerprint_is_stable() {
+        let info = build_info();
+        assert_eq!(info.fingerprint(), build_info().fingerprint());
+        assert_eq!(info.fingerprint().len(), 16);
+
+        let other = BuildInfo {
+            crate_version: "0.0.0",
+            ..info
+        };
+        assert_ne!(info.fingerprint(), other.fingerprint());
+    }
+
+    #[test]
+    fn test_display() {
+        let text = build_info().to_string();
+        assert!(text.starts_with("html-generator "));
+        assert!(text.contains("comrak"));
+    }
+}
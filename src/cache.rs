@@ -0,0 +1,743 @@
+// Copyright © 2025 HTML Generator. All rights reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Output caching for repeated conversions.
+//!
+//! Web applications often render the same Markdown snippets over and
+//! over. This module caches generated HTML under a [`CacheKey`] derived
+//! from the Markdown, the effective configuration and the build of this
+//! crate, so a cached entry is never reused after any of them change.
+//!
+//! - [`OutputCache`] is the storage trait; implement it to back the
+//!   cache with an external store such as Redis or memcached.
+//! - [`LruCache`] is a bounded, thread-safe in-process implementation.
+//! - [`DiskCache`] keeps entries as files, so they survive restarts.
+//! - [`markdown_to_html_cached`] converts through any cache.
+//!
+//! # Examples
+//!
+//! ```
+//! use html_generator::cache::{markdown_to_html_cached, LruCache};
+//!
+//! let cache = LruCache::new(128);
+//! let first = markdown_to_html_cached("# Hello", None, &cache)?;
+//! let second = markdown_to_html_cached("# Hello", None, &cache)?;
+//!
+//! assert_eq!(first, second);
+//! assert_eq!(cache.stats().hits, 1);
+//! # Ok::<(), html_generator::error::HtmlError>(())
+//! ```
+
+use crate::admonitions::Admonition;
+use crate::build_info::build_info;
+use crate::error::Result;
+use crate::headings::SlugStrategy;
+use crate::{markdown_to_html, HtmlConfig, MarkdownConfig};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+    sync::{Mutex, MutexGuard},
+};
+#[cfg(feature = "std")]
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// Key identifying a cached conversion.
+///
+/// A SHA-256 digest of the Markdown input and of every setting of the
+/// effective [`MarkdownConfig`] that affects the output, prefixed with
+/// the [`build_info`] fingerprint. Settings are listed field by field,
+/// so the key is the same in every process and adding a setting to the
+/// configuration requires deciding how it is keyed. The profiler does
+/// not change the output and is left out.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct CacheKey(String);
+
+impl CacheKey {
+    /// Computes the key for converting `markdown` with `config`.
+    ///
+    /// Returns `None` if the output also depends on code the key cannot
+    /// describe: hooks, plugins, block renderers or a custom slug
+    /// strategy. Such conversions are not cached.
+    pub fn new(
+        markdown: &str,
+        config: &MarkdownConfig,
+    ) -> Option<Self> {
+        let description = describe_config(config)?;
+        let mut hasher = Sha256::new();
+        for part in [description.as_bytes(), markdown.as_bytes()] {
+            hasher.update((part.len() as u64).to_le_bytes());
+            hasher.update(part);
+        }
+        Some(Self(format!(
+            "{}-{:x}",
+            build_info().fingerprint(),
+            hasher.finalize()
+        )))
+    }
+
+    /// Returns the key under which the key of the last conversion of the
+    /// document at `path` is stored.
+    fn for_path(path: &str) -> Self {
+        Self(format!(
+            "{}-path-{:x}",
+            build_info().fingerprint(),
+            Sha256::digest(path.as_bytes())
+        ))
+    }
+
+    /// Returns the key as a string, e.g. for use with external stores.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for CacheKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Describes every setting of `config` affecting the output, one per
+/// line, or returns `None` if the output also depends on callbacks.
+///
+/// The settings of [`HtmlConfig`] are listed by
+/// [`HtmlConfig::describe_settings`], generated from the same field
+/// list as the struct, so new settings are described automatically;
+/// its runtime fields are handled here.
+fn describe_config(config: &MarkdownConfig) -> Option<String> {
+    let MarkdownConfig {
+        encoding,
+        html_config,
+        dry_run,
+        front_matter_format,
+        expand_includes,
+        max_include_depth,
+    } = config;
+    let HtmlConfig {
+        admonitions,
+        block_renderers,
+        plugins,
+        hooks,
+        profiler: _,
+        ..
+    } = html_config;
+    if !block_renderers.is_empty()
+        || !plugins.is_empty()
+        || !hooks.is_empty()
+        || matches!(html_config.slug_strategy, SlugStrategy::Custom(_))
+    {
+        return None;
+    }
+
+    let mut description = String::new();
+    macro_rules! describe {
+        ($prefix:literal; $($field:ident),+ $(,)?) => {$(
+            description.push_str(&format!(
+                "{}{}={:?}\n",
+                $prefix,
+                stringify!($field),
+                $field
+            ));
+        )+};
+    }
+    describe!(""; encoding, dry_run, front_matter_format, expand_includes,
+        max_include_depth);
+    html_config.describe_settings(&mut description);
+    if let Some(admonitions) = admonitions {
+        let mut kinds: Vec<&Admonition> = admonitions.iter().collect();
+        kinds.sort_by(|a, b| a.kind.cmp(&b.kind));
+        for Admonition {
+            kind,
+            title,
+            icon,
+            template,
+        } in kinds
+        {
+            describe!("admonition."; kind, title, icon, template);
+        }
+    }
+    Some(description)
+}
+
+/// Storage for converted HTML.
+///
+/// Methods take `&self` so that implementations can be shared between
+/// threads; use interior mutability for any local state.
+pub trait OutputCache: Send + Sync {
+    /// Returns the cached HTML for `key`, if any.
+    fn get(&self, key: &CacheKey) -> Option<String>;
+
+    /// Stores `html` under `key`.
+    fn put(&self, key: CacheKey, html: String);
+
+    /// Removes the entry for `key`, returning `true` if one existed.
+    fn remove(&self, key: &CacheKey) -> bool;
+
+    /// Removes every entry.
+    fn clear(&self);
+}
+
+/// Hit and miss counters for an [`LruCache`].
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Lookups that found an entry.
+    pub hits: u64,
+    /// Lookups that found nothing.
+    pub misses: u64,
+    /// Entries dropped to stay within capacity.
+    pub evictions: u64,
+    /// Entries currently stored.
+    pub entries: usize,
+}
+
+/// Internal state of an [`LruCache`].
+#[derive(Debug, Default)]
+struct LruState {
+    entries: HashMap<CacheKey, (String, u64)>,
+    recency: BTreeMap<u64, CacheKey>,
+    tick: u64,
+    stats: CacheStats,
+}
+
+impl LruState {
+    /// Marks `key` as most recently used.
+    fn touch(&mut self, key: &CacheKey) {
+        self.tick += 1;
+        let tick = self.tick;
+        if let Some((_, used)) = self.entries.get_mut(key) {
+            let _ = self.recency.remove(used);
+            *used = tick;
+            let _ = self.recency.insert(tick, key.clone());
+        }
+    }
+}
+
+/// A bounded, thread-safe least-recently-used cache.
+#[derive(Debug)]
+pub struct LruCache {
+    capacity: usize,
+    state: Mutex<LruState>,
+}
+
+impl LruCache {
+    /// Creates a cache holding at most `capacity` entries.
+    ///
+    /// A capacity of zero disables caching.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            state: Mutex::new(LruState::default()),
+        }
+    }
+
+    /// Returns the maximum number of entries.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the number of entries currently stored.
+    pub fn len(&self) -> usize {
+        self.lock().entries.len()
+    }
+
+    /// Returns `true` if the cache holds no entries.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns `true` if the cache holds an entry for `key`, without
+    /// counting a lookup or marking the entry as used.
+    pub fn contains(&self, key: &CacheKey) -> bool {
+        self.lock().entries.contains_key(key)
+    }
+
+    /// Returns a snapshot of the cache counters.
+    pub fn stats(&self) -> CacheStats {
+        let state = self.lock();
+        CacheStats {
+            entries: state.entries.len(),
+            ..state.stats
+        }
+    }
+
+    /// Locks the state, recovering from a poisoned lock.
+    fn lock(&self) -> MutexGuard<'_, LruState> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl OutputCache for LruCache {
+    fn get(&self, key: &CacheKey) -> Option<String> {
+        let mut state = self.lock();
+        let html = state.entries.get(key).map(|(html, _)| html.clone());
+        if html.is_some() {
+            state.stats.hits += 1;
+            state.touch(key);
+        } else {
+            state.stats.misses += 1;
+        }
+        html
+    }
+
+    fn put(&self, key: CacheKey, html: String) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut state = self.lock();
+        if let Some((_, used)) = state.entries.remove(&key) {
+            let _ = state.recency.remove(&used);
+        }
+        while state.entries.len() >= self.capacity {
+            let oldest = match state.recency.keys().next() {
+                Some(&tick) => tick,
+                None => break,
+            };
+            if let Some(key) = state.recency.remove(&oldest) {
+                let _ = state.entries.remove(&key);
+            }
+            state.stats.evictions += 1;
+        }
+        state.tick += 1;
+        let tick = state.tick;
+        let _ = state.recency.insert(tick, key.clone());
+        let _ = state.entries.insert(key, (html, tick));
+    }
+
+    fn remove(&self, key: &CacheKey) -> bool {
+        let mut state = self.lock();
+        match state.entries.remove(key) {
+            Some((_, used)) => {
+                let _ = state.recency.remove(&used);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn clear(&self) {
+        let mut state = self.lock();
+        state.entries.clear();
+        state.recency.clear();
+    }
+}
+
+/// A cache storing each entry as an HTML file in a directory.
+///
+/// Entries are named after their [`CacheKey`], so the directory can be
+/// shared between processes and kept between builds. Write failures are
+/// ignored: the entry is simply converted again next time.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone)]
+pub struct DiskCache {
+    dir: PathBuf,
+}
+
+#[cfg(feature = "std")]
+impl DiskCache {
+    /// Opens the cache in `dir`, creating the directory if needed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory cannot be created.
+    pub fn new(dir: impl Into<PathBuf>) -> io::Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+        Ok(Self { dir })
+    }
+
+    /// Returns the directory of the cache.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Returns the path of the entry for `key`.
+    fn path(&self, key: &CacheKey) -> PathBuf {
+        self.dir.join(format!("{}.html", key))
+    }
+}
+
+#[cfg(feature = "std")]
+impl OutputCache for DiskCache {
+    fn get(&self, key: &CacheKey) -> Option<String> {
+        fs::read_to_string(self.path(key)).ok()
+    }
+
+    fn put(&self, key: CacheKey, html: String) {
+        let _ = fs::write(self.path(&key), html);
+    }
+
+    fn remove(&self, key: &CacheKey) -> bool {
+        fs::remove_file(self.path(key)).is_ok()
+    }
+
+    fn clear(&self) {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(_) => return,
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().map_or(false, |ext| ext == "html") {
+                let _ = fs::remove_file(path);
+            }
+        }
+    }
+}
+
+/// Converts Markdown to HTML, reusing a cached result when available.
+///
+/// Behaves exactly like [`markdown_to_html`]; successful results are
+/// stored in `cache` and failures are never cached. Configurations
+/// without a [`CacheKey`], because they run callbacks, bypass the
+/// cache.
+///
+/// # Arguments
+///
+/// * `content` - The Markdown content
+/// * `config` - Optional configuration for the conversion
+/// * `cache` - The cache to read from and write to
+///
+/// # Errors
+///
+/// Returns the same errors as [`markdown_to_html`].
+pub fn markdown_to_html_cached(
+    content: &str,
+    config: Option<MarkdownConfig>,
+    cache: &dyn OutputCache,
+) -> Result<String> {
+    convert_cached(content, config.unwrap_or_default(), cache)
+        .map(|conversion| conversion.html)
+}
+
+/// A conversion made by [`convert_cached`].
+#[derive(Debug)]
+pub(crate) struct CachedConversion {
+    /// The generated or cached HTML.
+    pub(crate) html: String,
+    /// The key of the entry, or `None` if the conversion bypassed the
+    /// cache.
+    pub(crate) key: Option<CacheKey>,
+    /// Whether the HTML came from the cache.
+    pub(crate) hit: bool,
+}
+
+/// Converts `content` through `cache` like [`markdown_to_html_cached`],
+/// also returning the key of the entry and whether it was a hit.
+pub(crate) fn convert_cached(
+    content: &str,
+    config: MarkdownConfig,
+    cache: &dyn OutputCache,
+) -> Result<CachedConversion> {
+    let key = match CacheKey::new(content, &config) {
+        Some(key) => key,
+        None => {
+            return Ok(CachedConversion {
+                html: markdown_to_html(content, Some(config))?,
+                key: None,
+                hit: false,
+            })
+        }
+    };
+    if let Some(html) = cache.get(&key) {
+        return Ok(CachedConversion {
+            html,
+            key: Some(key),
+            hit: true,
+        });
+    }
+
+    let html = markdown_to_html(content, Some(config))?;
+    cache.put(key.clone(), html.clone());
+    Ok(CachedConversion {
+        html,
+        key: Some(key),
+        hit: false,
+    })
+}
+
+/// Converts the document at `path` through `cache` like
+/// [`convert_cached`], removing the entry of its previous content when
+/// it changed.
+///
+/// The key of the last conversion of each path is stored in `cache` as
+/// well, so a [`DiskCache`] keeps one entry per document across runs
+/// instead of one per edit.
+pub(crate) fn convert_document_cached(
+    path: &str,
+    content: &str,
+    config: MarkdownConfig,
+    cache: &dyn OutputCache,
+) -> Result<CachedConversion> {
+    let conversion = convert_cached(content, config, cache)?;
+    let index = CacheKey::for_path(path);
+    let previous = cache.get(&index);
+    let current = conversion.key.as_ref().map(CacheKey::as_str);
+    if previous.as_deref() != current {
+        if let Some(previous) = previous {
+            let _ = cache.remove(&CacheKey(previous));
+        }
+        match &conversion.key {
+            Some(key) => cache.put(index, key.0.clone()),
+            None => {
+                let _ = cache.remove(&index);
+            }
+        }
+    }
+    Ok(conversion)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hooks::PipelineHooks;
+    use crate::profiler::Profiler;
+
+    /// Creates a key from a short label.
+    fn key(label: &str) -> CacheKey {
+        CacheKey::new(label, &MarkdownConfig::default()).unwrap()
+    }
+
+    mod cache_key_tests {
+        use super::*;
+
+        #[test]
+        fn test_key_depends_on_markdown_and_config() {
+            let config = MarkdownConfig::default();
+            let other = MarkdownConfig {
+                html_config: HtmlConfig {
+                    minify_output: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+
+            assert_eq!(CacheKey::new("# A", &config), Some(key("# A")));
+            assert_ne!(CacheKey::new("# A", &config), Some(key("# B")));
+            assert_ne!(
+                CacheKey::new("# A", &config),
+                CacheKey::new("# A", &other)
+            );
+        }
+
+        #[test]
+        fn test_key_ignores_profiler() {
+            let config = MarkdownConfig {
+                html_config: HtmlConfig {
+                    profiler: Some(Profiler::new()),
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            let profiled = CacheKey::new("# A", &config);
+            let _ =
+                markdown_to_html("# A", Some(config.clone())).unwrap();
+
+            assert_eq!(profiled, CacheKey::new("# A", &config));
+            assert_eq!(profiled, Some(key("# A")));
+        }
+
+        #[test]
+        fn test_callbacks_are_not_keyed() {
+            let hooks = MarkdownConfig {
+                html_config: HtmlConfig {
+                    hooks: PipelineHooks::new()
+                        .after_enhance(|_| Ok(())),
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            let custom_slugs = MarkdownConfig {
+                html_config: HtmlConfig {
+                    slug_strategy: SlugStrategy::Custom(|text| {
+                        text.to_string()
+                    }),
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+
+            assert_eq!(CacheKey::new("# A", &hooks), None);
+            assert_eq!(CacheKey::new("# A", &custom_slugs), None);
+        }
+
+        #[test]
+        fn test_key_includes_build_fingerprint() {
+            assert!(key("x")
+                .as_str()
+                .starts_with(&build_info().fingerprint()));
+        }
+    }
+
+    mod lru_cache_tests {
+        use super::*;
+
+        #[test]
+        fn test_get_and_put() {
+            let cache = LruCache::new(2);
+            assert_eq!(cache.get(&key("a")), None);
+            cache.put(key("a"), "A".to_string());
+
+            assert_eq!(cache.get(&key("a")), Some("A".to_string()));
+            let stats = cache.stats();
+            assert_eq!((stats.hits, stats.misses), (1, 1));
+        }
+
+        #[test]
+        fn test_evicts_least_recently_used() {
+            let cache = LruCache::new(2);
+            cache.put(key("a"), "A".to_string());
+            cache.put(key("b"), "B".to_string());
+            let _ = cache.get(&key("a"));
+            cache.put(key("c"), "C".to_string());
+
+            assert!(cache.get(&key("a")).is_some());
+            assert!(cache.get(&key("b")).is_none());
+            assert!(cache.get(&key("c")).is_some());
+            assert_eq!(cache.stats().evictions, 1);
+            assert_eq!(cache.len(), 2);
+        }
+
+        #[test]
+        fn test_replacing_entry_does_not_evict() {
+            let cache = LruCache::new(2);
+            cache.put(key("a"), "A".to_string());
+            cache.put(key("b"), "B".to_string());
+            cache.put(key("a"), "A2".to_string());
+
+            assert_eq!(cache.get(&key("a")), Some("A2".to_string()));
+            assert!(cache.get(&key("b")).is_some());
+            assert_eq!(cache.stats().evictions, 0);
+        }
+
+        #[test]
+        fn test_zero_capacity_disables_cache() {
+            let cache = LruCache::new(0);
+            cache.put(key("a"), "A".to_string());
+            assert!(cache.is_empty());
+        }
+
+        #[test]
+        fn test_remove_and_clear() {
+            let cache = LruCache::new(4);
+            cache.put(key("a"), "A".to_string());
+            cache.put(key("b"), "B".to_string());
+
+            assert!(cache.remove(&key("a")));
+            assert!(!cache.remove(&key("a")));
+            cache.clear();
+            assert!(cache.is_empty());
+        }
+    }
+
+    #[cfg(feature = "std")]
+    mod disk_cache_tests {
+        use super::*;
+        use tempfile::tempdir;
+
+        #[test]
+        fn test_entries_persist_on_disk() {
+            let dir = tempdir().unwrap();
+            let cache =
+                DiskCache::new(dir.path().join("cache")).unwrap();
+            cache.put(key("a"), "A".to_string());
+            cache.put(key("b"), "B".to_string());
+
+            let reopened = DiskCache::new(cache.dir()).unwrap();
+            assert_eq!(reopened.get(&key("a")), Some("A".to_string()));
+            assert!(reopened.remove(&key("a")));
+            assert!(!reopened.remove(&key("a")));
+            reopened.clear();
+            assert_eq!(cache.get(&key("b")), None);
+        }
+    }
+
+    mod cached_conversion_tests {
+        use super::*;
+
+        #[test]
+        fn test_cached_conversion() {
+            let cache = LruCache::new(8);
+            let first =
+                markdown_to_html_cached("# Hi", None, &cache).unwrap();
+            let second =
+                markdown_to_html_cached("# Hi", None, &cache).unwrap();
+
+            assert_eq!(first, second);
+            assert_eq!(cache.stats().hits, 1);
+            assert_eq!(cache.len(), 1);
+        }
+
+        #[test]
+        fn test_conversions_with_hooks_bypass_cache() {
+            let cache = LruCache::new(8);
+            let banner = |text: &'static str| MarkdownConfig {
+                html_config: HtmlConfig {
+                    hooks: PipelineHooks::new().after_enhance(
+                        move |context| {
+                            context.content.insert_str(0, text);
+                            Ok(())
+                        },
+                    ),
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+
+            let first = markdown_to_html_cached(
+                "Hi",
+                Some(banner("A")),
+                &cache,
+            )
+            .unwrap();
+            let second = markdown_to_html_cached(
+                "Hi",
+                Some(banner("B")),
+                &cache,
+            )
+            .unwrap();
+
+            assert!(first.starts_with('A'));
+            assert!(second.starts_with('B'));
+            assert!(cache.is_empty());
+        }
+
+        #[test]
+        fn test_errors_are_not_cached() {
+            let cache = LruCache::new(8);
+            assert!(markdown_to_html_cached("", None, &cache).is_err());
+            assert!(cache.is_empty());
+        }
+
+        #[test]
+        fn test_custom_cache_implementation() {
+            #[derive(Debug, Default)]
+            struct MapCache(Mutex<HashMap<CacheKey, String>>);
+
+            impl OutputCache for MapCache {
+                fn get(&self, key: &CacheKey) -> Option<String> {
+                    self.0.lock().unwrap().get(key).cloned()
+                }
+                fn put(&self, key: CacheKey, html: String) {
+                    let _ = self.0.lock().unwrap().insert(key, html);
+                }
+                fn remove(&self, key: &CacheKey) -> bool {
+                    self.0.lock().unwrap().remove(key).is_some()
+                }
+                fn clear(&self) {
+                    self.0.lock().unwrap().clear();
+                }
+            }
+
+            let cache = MapCache::default();
+            let _ =
+                markdown_to_html_cached("# Hi", None, &cache).unwrap();
+            assert_eq!(cache.0.lock().unwrap().len(), 1);
+        }
+    }
+}
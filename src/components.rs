@@ -0,0 +1,338 @@
+// Copyright © 2025 HTML Generator. All rights reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Accessible navigation snippets for templates.
+//!
+//! Breadcrumbs, pagination and tag lists are easy to get subtly wrong:
+//! the current page needs `aria-current="page"`, each `<nav>` needs a
+//! distinct label and decorative separators must be hidden from screen
+//! readers. The generators in this module produce that markup so that
+//! templates do not have to reimplement it. All labels and URLs are
+//! HTML-escaped.
+//!
+//! # Examples
+//!
+//! ```
+//! use html_generator::components::{breadcrumbs, Link};
+//!
+//! let html = breadcrumbs(&[
+//!     Link::new("Home", "/"),
+//!     Link::new("Guide", "/guide/"),
+//! ]);
+//! assert!(html.contains(r#"<a href="/guide/" aria-current="page">Guide</a>"#));
+//! ```
+
+use crate::error::{HtmlError, Result};
+use crate::seo::escape_html;
+
+/// Number of pages shown on each side of the current page by
+/// [`pagination`] before the range is shortened with an ellipsis.
+const PAGINATION_WINDOW: usize = 2;
+
+/// A labelled link.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Link {
+    /// The visible text.
+    pub label: String,
+    /// The target URL.
+    pub href: String,
+}
+
+impl Link {
+    /// Creates a link.
+    pub fn new(
+        label: impl Into<String>,
+        href: impl Into<String>,
+    ) -> Self {
+        Self {
+            label: label.into(),
+            href: href.into(),
+        }
+    }
+}
+
+/// Generates a breadcrumb trail.
+///
+/// The items are rendered in order as an ordered list inside
+/// `<nav aria-label="Breadcrumb">`. The last item is the current page and
+/// is marked with `aria-current="page"`.
+///
+/// # Arguments
+///
+/// * `items` - The trail, from the site root to the current page
+///
+/// # Returns
+///
+/// The HTML snippet, or an empty string if `items` is empty.
+pub fn breadcrumbs(items: &[Link]) -> String {
+    if items.is_empty() {
+        return String::new();
+    }
+
+    let mut html = String::from(
+        r#"<nav aria-label="Breadcrumb"><ol class="breadcrumbs">"#,
+    );
+    for (index, item) in items.iter().enumerate() {
+        let current = if index + 1 == items.len() {
+            r#" aria-current="page""#
+        } else {
+            ""
+        };
+        html.push_str(&format!(
+            r#"<li><a href="{}"{}>{}</a></li>"#,
+            escape_html(&item.href),
+            current,
+            escape_html(&item.label)
+        ));
+    }
+    html.push_str("</ol></nav>");
+    html
+}
+
+/// Generates pagination links.
+///
+/// Renders previous and next links, the first and last page, and the
+/// pages within two of the current page; gaps are shown as a hidden
+/// ellipsis. The current page is marked with `aria-current="page"` and
+/// every page link has an accessible name such as `Page 3`.
+///
+/// # Arguments
+///
+/// * `current` - The current page, starting at 1
+/// * `total` - The number of pages
+/// * `url` - Returns the URL of a page
+///
+/// # Returns
+///
+/// The HTML snippet, or an empty string if there is only one page.
+///
+/// # Errors
+///
+/// Returns [`HtmlError::InvalidInput`] if `current` is not between 1 and
+/// `total`.
+///
+/// # Examples
+///
+/// ```
+/// use html_generator::components::pagination;
+///
+/// let html = pagination(2, 3, |page| format!("/blog/{}/", page))?;
+/// assert!(html.contains(r#"rel="prev""#));
+/// assert!(html.contains(r#"aria-current="page" aria-label="Page 2""#));
+/// # Ok::<(), html_generator::error::HtmlError>(())
+/// ```
+pub fn pagination<F>(
+    current: usize,
+    total: usize,
+    url: F,
+) -> Result<String>
+where
+    F: Fn(usize) -> String,
+{
+    if current == 0 || current > total {
+        return Err(HtmlError::InvalidInput(format!(
+            "Page {} is out of range 1..={}",
+            current, total
+        )));
+    }
+    if total == 1 {
+        return Ok(String::new());
+    }
+
+    let link = |page: usize, attrs: &str, label: &str, text: &str| {
+        format!(
+            r#"<li><a href="{}"{} aria-label="{}">{}</a></li>"#,
+            escape_html(&url(page)),
+            attrs,
+            label,
+            text
+        )
+    };
+
+    let mut html = String::from(
+        r#"<nav aria-label="Pagination"><ul class="pagination">"#,
+    );
+    if current > 1 {
+        html.push_str(&link(
+            current - 1,
+            r#" rel="prev""#,
+            "Previous page",
+            "&laquo; Previous",
+        ));
+    }
+    let mut last_shown = 0;
+    for page in 1..=total {
+        let visible = page == 1
+            || page == total
+            || (page + PAGINATION_WINDOW >= current
+                && page <= current + PAGINATION_WINDOW);
+        if !visible {
+            continue;
+        }
+        if page > last_shown + 1 {
+            html.push_str(r#"<li aria-hidden="true">&hellip;</li>"#);
+        }
+        let attrs = if page == current {
+            r#" aria-current="page""#
+        } else {
+            ""
+        };
+        let label = format!("Page {}", page);
+        html.push_str(&link(page, attrs, &label, &page.to_string()));
+        last_shown = page;
+    }
+    if current < total {
+        html.push_str(&link(
+            current + 1,
+            r#" rel="next""#,
+            "Next page",
+            "Next &raquo;",
+        ));
+    }
+    html.push_str("</ul></nav>");
+    Ok(html)
+}
+
+/// Generates a list of tag links.
+///
+/// Tags are rendered as `<a rel="tag">` pills in a list labelled `Tags`.
+///
+/// # Arguments
+///
+/// * `tags` - The tags and the URLs of their index pages
+///
+/// # Returns
+///
+/// The HTML snippet, or an empty string if `tags` is empty.
+pub fn tag_pills(tags: &[Link]) -> String {
+    if tags.is_empty() {
+        return String::new();
+    }
+
+    let mut html =
+        String::from(r#"<ul class="tags" aria-label="Tags">"#);
+    for tag in tags {
+        html.push_str(&format!(
+            r#"<li><a class="tag" href="{}" rel="tag">{}</a></li>"#,
+            escape_html(&tag.href),
+            escape_html(&tag.label)
+        ));
+    }
+    html.push_str("</ul>");
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod breadcrumbs_tests {
+        use super::*;
+
+        #[test]
+        fn test_marks_last_item_as_current() {
+            let html = breadcrumbs(&[
+                Link::new("Home", "/"),
+                Link::new("Docs & API", "/docs/?a=1&b=2"),
+            ]);
+            assert_eq!(
+                html,
+                concat!(
+                    r#"<nav aria-label="Breadcrumb"><ol class="breadcrumbs">"#,
+                    r#"<li><a href="/">Home</a></li>"#,
+                    r#"<li><a href="/docs/?a=1&amp;b=2" aria-current="page">"#,
+                    r#"Docs &amp; API</a></li></ol></nav>"#
+                )
+            );
+        }
+
+        #[test]
+        fn test_empty_trail() {
+            assert_eq!(breadcrumbs(&[]), "");
+        }
+    }
+
+    mod pagination_tests {
+        use super::*;
+
+        fn pages(html: &str) -> Vec<String> {
+            html.split("<li")
+                .skip(1)
+                .map(|item| {
+                    let mut text = String::new();
+                    let mut in_tag = true;
+                    for c in item.chars() {
+                        match c {
+                            '<' => in_tag = true,
+                            '>' => in_tag = false,
+                            _ if !in_tag => text.push(c),
+                            _ => {}
+                        }
+                    }
+                    text
+                })
+                .collect()
+        }
+
+        #[test]
+        fn test_first_page() {
+            let html = pagination(1, 3, |p| format!("/{}", p)).unwrap();
+            assert!(!html.contains("rel=\"prev\""));
+            assert!(html.contains(
+                r#"<li><a href="/1" aria-current="page" aria-label="Page 1">1</a></li>"#
+            ));
+            assert!(html.contains(
+                r#"<a href="/2" rel="next" aria-label="Next page">"#
+            ));
+        }
+
+        #[test]
+        fn test_long_ranges_are_shortened() {
+            let html =
+                pagination(10, 20, |p| format!("/{}", p)).unwrap();
+            assert_eq!(
+                pages(&html),
+                vec![
+                    "&laquo; Previous",
+                    "1",
+                    "&hellip;",
+                    "8",
+                    "9",
+                    "10",
+                    "11",
+                    "12",
+                    "&hellip;",
+                    "20",
+                    "Next &raquo;"
+                ]
+            );
+        }
+
+        #[test]
+        fn test_single_page_and_out_of_range() {
+            assert_eq!(
+                pagination(1, 1, |_| String::new()).unwrap(),
+                ""
+            );
+            assert!(pagination(0, 3, |_| String::new()).is_err());
+            assert!(pagination(4, 3, |_| String::new()).is_err());
+        }
+    }
+
+    mod tag_pills_tests {
+        use super::*;
+
+        #[test]
+        fn test_tag_pills() {
+            assert_eq!(
+                tag_pills(&[Link::new("<rust>", "/tags/rust/")]),
+                concat!(
+                    r#"<ul class="tags" aria-label="Tags">"#,
+                    r#"<li><a class="tag" href="/tags/rust/" rel="tag">"#,
+                    r#"&lt;rust&gt;</a></li></ul>"#
+                )
+            );
+            assert_eq!(tag_pills(&[]), "");
+        }
+    }
+}
@@ -0,0 +1,172 @@
+// Copyright © 2025 HTML Generator. All rights reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Loading of configurations from TOML, YAML and JSON files.
+//!
+//! Available with the `config-file` feature. The format is chosen by
+//! the file extension. Keys are the field names, as in the JSON Schemas
+//! of the `schema` feature, and every key is optional: missing keys
+//! keep their defaults, so a file only lists what it changes. Unknown
+//! keys are rejected, so typos surface as errors.
+
+use crate::error::{HtmlError, Result};
+use crate::{HtmlConfig, MarkdownConfig};
+use serde::de::DeserializeOwned;
+use std::fs;
+use std::path::Path;
+
+impl HtmlConfig {
+    /// Reads a configuration from a TOML, YAML or JSON file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - A file ending in `.toml`, `.yaml`, `.yml` or `.json`
+    ///
+    /// # Errors
+    ///
+    /// Returns [`HtmlError::Io`] if the file cannot be read, and
+    /// [`HtmlError::InvalidInput`] for other extensions, syntax errors,
+    /// unknown keys, values of the wrong type and configurations that
+    /// fail [`HtmlConfig::validate`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use html_generator::HtmlConfig;
+    ///
+    /// let path = std::env::temp_dir().join("html-generator-docs.toml");
+    /// std::fs::write(
+    ///     &path,
+    ///     "generate_toc = true\nhighlight_mode = \"classes\"\n\n[toc]\nmax_level = 3\n",
+    /// )?;
+    ///
+    /// let config = HtmlConfig::from_file(&path)?;
+    /// assert!(config.generate_toc);
+    /// assert_eq!(config.toc.max_level, 3);
+    /// assert_eq!(config.language, HtmlConfig::default().language);
+    /// # Ok::<(), html_generator::error::HtmlError>(())
+    /// ```
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let config: Self = read_config(path.as_ref())?;
+        config.validate()?;
+        Ok(config)
+    }
+}
+
+impl MarkdownConfig {
+    /// Reads a configuration from a TOML, YAML or JSON file, with the
+    /// settings of [`HtmlConfig::from_file`] under `html_config`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`HtmlConfig::from_file`].
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let config: Self = read_config(path.as_ref())?;
+        config.html_config.validate()?;
+        Ok(config)
+    }
+}
+
+/// Reads `path` in the format given by its extension.
+fn read_config<T: DeserializeOwned>(path: &Path) -> Result<T> {
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(str::to_ascii_lowercase)
+        .unwrap_or_default();
+    let content = fs::read_to_string(path)?;
+    let parsed = match extension.as_str() {
+        "toml" => toml::from_str(&content).map_err(|e| e.to_string()),
+        "yaml" | "yml" => {
+            serde_yaml::from_str(&content).map_err(|e| e.to_string())
+        }
+        "json" => {
+            serde_json::from_str(&content).map_err(|e| e.to_string())
+        }
+        _ => {
+            return Err(HtmlError::InvalidInput(format!(
+                "Unsupported configuration file '{}': expected a .toml, .yaml, .yml or .json extension",
+                path.display()
+            )))
+        }
+    };
+    parsed.map_err(|message| {
+        HtmlError::InvalidInput(format!(
+            "Invalid configuration file '{}': {}",
+            path.display(),
+            message.trim_end()
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::highlight::HighlightMode;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_formats() {
+        let dir = tempdir().unwrap();
+        let files = [
+            (
+                "site.toml",
+                "minify_output = true\nhighlight_mode = \"none\"\n",
+            ),
+            (
+                "site.yaml",
+                "minify_output: true\nhighlight_mode: none\n",
+            ),
+            ("site.yml", "minify_output: true\nhighlight_mode: none\n"),
+            (
+                "site.json",
+                r#"{"minify_output": true, "highlight_mode": "none"}"#,
+            ),
+        ];
+        for (name, content) in files {
+            let path = dir.path().join(name);
+            fs::write(&path, content).unwrap();
+            let config = HtmlConfig::from_file(&path).unwrap();
+            assert!(config.minify_output, "{}", name);
+            assert_eq!(config.highlight_mode, HighlightMode::None);
+        }
+    }
+
+    #[test]
+    fn test_markdown_config() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("markdown.yaml");
+        fs::write(
+            &path,
+            "expand_includes: true\nhtml_config:\n  language: fr-FR\n",
+        )
+        .unwrap();
+        let config = MarkdownConfig::from_file(&path).unwrap();
+        assert!(config.expand_includes);
+        assert_eq!(config.html_config.language, "fr-FR");
+        assert_eq!(config.encoding, "utf-8");
+    }
+
+    #[test]
+    fn test_errors() {
+        let dir = tempdir().unwrap();
+        let error = |name: &str, content: &str| {
+            let path = dir.path().join(name);
+            fs::write(&path, content).unwrap();
+            HtmlConfig::from_file(&path).unwrap_err().to_string()
+        };
+        assert!(error("a.ini", "").contains("Unsupported"));
+        assert!(error("b.toml", "generate_tco = true")
+            .contains("generate_tco"));
+        assert!(
+            error("c.json", r#"{"math": "yes"}"#).contains("Invalid")
+        );
+        assert!(
+            error("d.toml", "language = \"x\"").contains("language")
+        );
+        assert!(matches!(
+            HtmlConfig::from_file(dir.path().join("missing.toml")),
+            Err(HtmlError::Io(_))
+        ));
+    }
+}
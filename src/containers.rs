@@ -0,0 +1,371 @@
+// Copyright © 2025 HTML Generator. All rights reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Custom renderers for `:::name` containers.
+//!
+//! A [`BlockRenderer`] registered in
+//! [`HtmlConfig::block_renderers`](crate::HtmlConfig::block_renderers)
+//! turns every container of its name into HTML of its own, taking
+//! precedence over [admonitions](crate::admonitions) and the plain
+//! `<div class="name">` written otherwise:
+//!
+//! ```markdown
+//! :::video https://example.com/intro.mp4
+//! A short introduction.
+//! :::
+//! ```
+//!
+//! The renderer receives the text after the name and the raw body,
+//! including any containers nested in it, and its output is inserted
+//! as is.
+//!
+//! # Examples
+//!
+//! ```
+//! use html_generator::containers::{BlockRenderer, Container};
+//! use html_generator::{conversion::convert, HtmlConfig, Result};
+//!
+//! struct Video;
+//!
+//! impl BlockRenderer for Video {
+//!     fn render(&self, container: &Container<'_>) -> Result<String> {
+//!         Ok(format!(
+//!             "<figure><video src=\"{}\" controls></video><figcaption>{}</figcaption></figure>",
+//!             container.arguments, container.body
+//!         ))
+//!     }
+//! }
+//!
+//! let mut config = HtmlConfig::default();
+//! config.block_renderers.register("video", Video);
+//! let html = convert(":::video intro.mp4\nThe tour\n:::\n\nAfter", &config)?;
+//! assert_eq!(
+//!     html,
+//!     "<figure><video src=\"intro.mp4\" controls></video><figcaption>The tour</figcaption></figure>\n<p>After</p>\n"
+//! );
+//! # Ok::<(), html_generator::error::HtmlError>(())
+//! ```
+
+use crate::error::Result;
+use crate::patterns::containers::{
+    CLOSING_REGEX, FENCE_REGEX, OPENING_REGEX,
+};
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Arc;
+
+/// Marks the place of rendered containers until the Markdown is
+/// converted; a private-use character that Markdown leaves alone.
+const PLACEHOLDER_MARK: char = '\u{E000}';
+
+/// A `:::name` container passed to a [`BlockRenderer`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Container<'a> {
+    /// The name following `:::`, e.g. `video`.
+    pub name: &'a str,
+    /// The trimmed text after the name, possibly empty.
+    pub arguments: &'a str,
+    /// The Markdown between the opening and closing lines.
+    pub body: &'a str,
+}
+
+/// Renders `:::name` containers to HTML.
+pub trait BlockRenderer: Send + Sync {
+    /// Renders `container`.
+    ///
+    /// # Errors
+    ///
+    /// An error fails the conversion of the document.
+    fn render(&self, container: &Container<'_>) -> Result<String>;
+}
+
+/// Block renderers keyed by container name.
+#[derive(Clone, Default)]
+pub struct BlockRendererRegistry {
+    renderers: BTreeMap<String, Arc<dyn BlockRenderer>>,
+}
+
+impl BlockRendererRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the renderer of `name` containers, replacing any
+    /// previous one.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name following `:::`
+    /// * `renderer` - The renderer
+    pub fn register(
+        &mut self,
+        name: impl Into<String>,
+        renderer: impl BlockRenderer + 'static,
+    ) {
+        let _ = self.renderers.insert(name.into(), Arc::new(renderer));
+    }
+
+    /// Returns the renderer of `name` containers.
+    pub fn get(&self, name: &str) -> Option<&dyn BlockRenderer> {
+        self.renderers.get(name).map(|renderer| &**renderer)
+    }
+
+    /// Returns the registered container names, in order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.renderers.keys().map(String::as_str)
+    }
+
+    /// Returns the number of renderers.
+    pub fn len(&self) -> usize {
+        self.renderers.len()
+    }
+
+    /// Returns `true` if no renderer is registered.
+    pub fn is_empty(&self) -> bool {
+        self.renderers.is_empty()
+    }
+
+    /// Replaces the containers with a registered renderer by
+    /// placeholders, returning the Markdown and the rendered HTML of
+    /// each placeholder for [`restore_placeholders`].
+    pub(crate) fn render_containers<'a>(
+        &self,
+        markdown: &'a str,
+    ) -> Result<(Cow<'a, str>, Vec<String>)> {
+        if self.is_empty() {
+            return Ok((Cow::Borrowed(markdown), Vec::new()));
+        }
+        let lines: Vec<&str> = markdown.split('\n').collect();
+        let mut output = Vec::with_capacity(lines.len());
+        let mut rendered = Vec::new();
+        let mut next = 0;
+        for span in find_containers(&lines) {
+            if span.start < next {
+                continue;
+            }
+            let renderer = match self.get(span.name) {
+                Some(renderer) => renderer,
+                None => continue,
+            };
+            let body = lines[span.start + 1..span.end].join("\n");
+            let html = renderer.render(&Container {
+                name: span.name,
+                arguments: span.arguments,
+                body: &body,
+            })?;
+            output.extend(
+                lines[next..span.start].iter().map(|l| l.to_string()),
+            );
+            output.push(format!(
+                "\n{}{}{}\n",
+                PLACEHOLDER_MARK,
+                rendered.len(),
+                PLACEHOLDER_MARK
+            ));
+            rendered.push(html);
+            next = span.end + 1;
+        }
+        output.extend(lines[next..].iter().map(|l| l.to_string()));
+        Ok((Cow::Owned(output.join("\n")), rendered))
+    }
+}
+
+impl fmt::Debug for BlockRendererRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BlockRendererRegistry")
+            .field("names", &self.names().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl PartialEq for BlockRendererRegistry {
+    /// Registries are equal if they hold the same renderer instances
+    /// under the same names.
+    fn eq(&self, other: &Self) -> bool {
+        self.renderers.len() == other.renderers.len()
+            && self.renderers.iter().zip(&other.renderers).all(
+                |((name, renderer), (other_name, other_renderer))| {
+                    name == other_name
+                        && Arc::ptr_eq(renderer, other_renderer)
+                },
+            )
+    }
+}
+
+impl Eq for BlockRendererRegistry {}
+
+/// Replaces the placeholders left by
+/// [`BlockRendererRegistry::render_containers`] in the converted HTML.
+pub(crate) fn restore_placeholders(
+    html: &str,
+    rendered: &[String],
+) -> String {
+    let mut html = html.to_string();
+    for (index, block) in rendered.iter().enumerate() {
+        let placeholder = format!(
+            "{}{}{}",
+            PLACEHOLDER_MARK, index, PLACEHOLDER_MARK
+        );
+        html = html
+            .replace(&format!("<p>{}</p>", placeholder), block)
+            .replace(&placeholder, block);
+    }
+    html
+}
+
+/// A closed `:::name` container found by [`find_containers`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ContainerSpan<'a> {
+    /// Index of the opening line.
+    pub(crate) start: usize,
+    /// Index of the closing line.
+    pub(crate) end: usize,
+    /// The name following `:::`.
+    pub(crate) name: &'a str,
+    /// The `+` or `-` following the name, if any.
+    pub(crate) marker: Option<&'a str>,
+    /// The trimmed text after the name.
+    pub(crate) arguments: &'a str,
+}
+
+/// Finds the closed containers of `lines`, ordered by opening line.
+///
+/// Containers nest; `:::` lines in fenced code blocks, unclosed
+/// containers and unmatched closing lines are ignored.
+pub(crate) fn find_containers<'a>(
+    lines: &[&'a str],
+) -> Vec<ContainerSpan<'a>> {
+    let mut spans = Vec::new();
+    let mut open: Vec<ContainerSpan<'a>> = Vec::new();
+    let mut fence: Option<&str> = None;
+
+    for (index, line) in lines.iter().enumerate() {
+        if let Some(caps) = FENCE_REGEX.captures(line) {
+            let marker = caps.get(1).map_or("", |m| m.as_str());
+            match fence {
+                None => fence = Some(marker),
+                Some(opening)
+                    if marker.starts_with(opening)
+                        && line.trim_end().ends_with(marker) =>
+                {
+                    fence = None;
+                }
+                Some(_) => {}
+            }
+            continue;
+        }
+        if fence.is_some() {
+            continue;
+        }
+        if CLOSING_REGEX.is_match(line) {
+            if let Some(mut span) = open.pop() {
+                span.end = index;
+                spans.push(span);
+            }
+        } else if let Some(caps) = OPENING_REGEX.captures(line) {
+            open.push(ContainerSpan {
+                start: index,
+                end: index,
+                name: caps.get(1).map_or("", |m| m.as_str()),
+                marker: caps.get(2).map(|m| m.as_str()),
+                arguments: caps
+                    .get(3)
+                    .map_or("", |m| m.as_str().trim()),
+            });
+        }
+    }
+    spans.sort_by_key(|span| span.start);
+    spans
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::admonitions::AdmonitionRegistry;
+    use crate::conversion::convert;
+    use crate::error::HtmlError;
+    use crate::HtmlConfig;
+
+    struct Gallery;
+
+    impl BlockRenderer for Gallery {
+        fn render(&self, container: &Container<'_>) -> Result<String> {
+            let images: Vec<_> = container
+                .body
+                .lines()
+                .filter(|line| !line.is_empty())
+                .map(|src| format!("<img src=\"{}\" alt=\"\">", src))
+                .collect();
+            Ok(format!(
+                "<div class=\"gallery\" data-title=\"{}\">{}</div>",
+                container.arguments,
+                images.join("")
+            ))
+        }
+    }
+
+    struct Failing;
+
+    impl BlockRenderer for Failing {
+        fn render(&self, _: &Container<'_>) -> Result<String> {
+            Err(HtmlError::InvalidInput("bad container".to_string()))
+        }
+    }
+
+    #[test]
+    fn test_find_containers() {
+        let lines: Vec<_> =
+            ":::a x\n:::b+\n:::\n```\n:::\n```\n:::\n:::c\n"
+                .split('\n')
+                .collect();
+        let spans = find_containers(&lines);
+        let found: Vec<_> = spans
+            .iter()
+            .map(|s| (s.name, s.marker, s.arguments, s.start, s.end))
+            .collect();
+        assert_eq!(
+            found,
+            [("a", None, "x", 0, 6), ("b", Some("+"), "", 1, 2)]
+        );
+    }
+
+    #[test]
+    fn test_renderers_take_precedence() {
+        let mut config = HtmlConfig {
+            admonitions: Some(AdmonitionRegistry::bundled()),
+            ..HtmlConfig::default()
+        };
+        config.block_renderers.register("gallery", Gallery);
+        let html = convert(
+            ":::note\nPhotos:\n\n:::gallery Trip\na.png\n\nb.png\n:::\n:::\n\n:::gallery\n:::",
+            &config,
+        )
+        .unwrap();
+        assert!(html.contains(concat!(
+            "<p>Photos:</p>\n<div class=\"gallery\" data-title=\"Trip\">",
+            "<img src=\"a.png\" alt=\"\"><img src=\"b.png\" alt=\"\"></div>\n</div>"
+        )));
+        assert!(html.ends_with(
+            "<div class=\"gallery\" data-title=\"\"></div>\n"
+        ));
+
+        let mut other = config.clone();
+        assert_eq!(other, config);
+        other.block_renderers.register("gallery", Gallery);
+        assert_ne!(other, config);
+        assert_eq!(
+            format!("{:?}", other.block_renderers),
+            r#"BlockRendererRegistry { names: ["gallery"] }"#
+        );
+    }
+
+    #[test]
+    fn test_renderer_errors_fail_the_conversion() {
+        let mut config = HtmlConfig::default();
+        config.block_renderers.register("bad", Failing);
+        assert!(convert(":::bad\n:::", &config).is_err());
+        assert!(convert("```\n:::bad\n:::\n```", &config).is_ok());
+    }
+}
@@ -0,0 +1,310 @@
+// Copyright © 2025 HTML Generator. All rights reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Core string-to-string conversion.
+//!
+//! This module contains the Markdown to HTML conversion path shared by
+//! every API. It reads and writes no files, so it is always available,
+//! including when the crate is built with `default-features = false`
+//! for constrained targets such as WASM.
+//!
+//! The crate is not `no_std` and the conversion uses the standard
+//! library. Without the `std` feature, code blocks are highlighted on
+//! the calling thread and generated element IDs come from a counter
+//! instead of random UUIDs. With a
+//! [`Profiler`](crate::profiler::Profiler) set, stages are timed with
+//! the system clock.
+//!
+//! Filesystem, batch and async APIs build on top of this module and are
+//! only available with the `std` feature.
+//!
+//! # Examples
+//!
+//! ```
+//! use html_generator::conversion::convert;
+//! use html_generator::HtmlConfig;
+//!
+//! let html = convert("# Hello", &HtmlConfig::default())?;
+//! assert!(html.contains("<h1>Hello</h1>"));
+//! # Ok::<(), html_generator::error::HtmlError>(())
+//! ```
+
+use crate::directives::apply_directives;
+use crate::error::{HtmlError, Result};
+use crate::generator::generate_html_with_title;
+use crate::hooks::HookStage;
+use crate::iframes::BlockedIframe;
+use crate::pipeline::Pipeline;
+use crate::profiler::profile;
+use crate::sanitize::{apply_url_scheme_policy, RemovedUrl};
+use crate::unicode::{repair_mojibake, MojibakeRepair};
+use crate::HtmlConfig;
+use alloc::{
+    borrow::Cow,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+/// The result of [`convert_detailed`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Conversion {
+    /// The generated HTML.
+    pub html: String,
+    /// Mis-encoded sequences repaired in the input, when
+    /// [`HtmlConfig::repair_mojibake`] is enabled.
+    pub repairs: Vec<MojibakeRepair>,
+    /// Text of the `h1` removed from the body, when
+    /// [`HtmlConfig::strip_title`] is enabled and the document has one.
+    pub title: Option<String>,
+    /// The table of contents, when [`HtmlConfig::generate_toc`] is
+    /// enabled and the document has headings to list. It is also part
+    /// of `html` unless [`TocPlacement::Separate`] is used.
+    ///
+    /// [`TocPlacement::Separate`]: crate::toc::TocPlacement::Separate
+    pub toc: Option<String>,
+    /// Script URLs removed from the output, when
+    /// [`HtmlConfig::url_scheme_policy`] is
+    /// [`UrlSchemePolicy::Strip`].
+    ///
+    /// [`UrlSchemePolicy::Strip`]: crate::sanitize::UrlSchemePolicy::Strip
+    pub removed_urls: Vec<RemovedUrl>,
+    /// Iframes removed from the output, when
+    /// [`HtmlConfig::iframe_policy`] restricts the embeddable hosts.
+    pub blocked_iframes: Vec<BlockedIframe>,
+}
+
+/// Converts Markdown to HTML without touching any external resources.
+///
+/// # Arguments
+///
+/// * `markdown` - The Markdown content to convert
+/// * `config` - The HTML generation settings
+///
+/// # Returns
+///
+/// The generated HTML.
+///
+/// # Errors
+///
+/// Returns an error if the input is empty, exceeds
+/// `config.max_input_size` or `config.limits`, or conversion fails.
+pub fn convert(markdown: &str, config: &HtmlConfig) -> Result<String> {
+    convert_detailed(markdown, config).map(|conversion| conversion.html)
+}
+
+/// Converts Markdown to HTML and reports the repairs made to the input
+/// and the extracted title.
+///
+/// # Arguments
+///
+/// * `markdown` - The Markdown content to convert
+/// * `config` - The HTML generation settings
+///
+/// # Returns
+///
+/// The generated HTML together with any input repairs and the title.
+///
+/// # Errors
+///
+/// Returns the same errors as [`convert`].
+pub fn convert_detailed(
+    markdown: &str,
+    config: &HtmlConfig,
+) -> Result<Conversion> {
+    if markdown.is_empty() {
+        return Err(HtmlError::InvalidInput(
+            "Input content is empty".to_string(),
+        ));
+    }
+
+    if markdown.len() > config.max_input_size {
+        return Err(HtmlError::InputTooLarge(markdown.len()));
+    }
+
+    let (config, markdown) = if config.comment_directives {
+        let (config, markdown) =
+            profile(config.profiler.as_ref(), "directives", || {
+                apply_directives(markdown, config)
+            })?;
+        (Cow::Owned(config), Cow::Owned(markdown))
+    } else {
+        (Cow::Borrowed(config), Cow::Borrowed(markdown))
+    };
+    let config = &*config;
+    let profiler = config.profiler.as_ref();
+
+    let (mut markdown, repairs) = if config.repair_mojibake {
+        profile(profiler, "repair_mojibake", || {
+            repair_mojibake(&markdown)
+        })
+    } else {
+        (markdown.to_string(), Vec::new())
+    };
+
+    run_hook(config, HookStage::BeforeParse, &mut markdown)?;
+    let (mut html, title, toc) =
+        generate_html_with_title(&markdown, config)?;
+    run_hook(config, HookStage::AfterMarkdown, &mut html)?;
+    config.limits.check_output(markdown.len(), html.len())?;
+    let mut html = Pipeline::from_config(config)
+        .with_plugins(&config.plugins)?
+        .run_profiled(&html, profiler)?;
+    run_hook(config, HookStage::AfterEnhance, &mut html)?;
+    let (html, removed_urls) =
+        profile(profiler, "url_scheme_policy", || {
+            apply_url_scheme_policy(html, config.url_scheme_policy)
+        })?;
+    let (mut html, blocked_iframes) = match &config.iframe_policy {
+        Some(policy) => {
+            profile(profiler, "iframe_policy", || policy.apply(&html))
+        }
+        None => (html, Vec::new()),
+    };
+    if let Some(provenance) = &config.provenance {
+        if !config.minify_output {
+            html.insert_str(
+                0,
+                &format!("{}\n", provenance.comment(&markdown)),
+            );
+        }
+    }
+    Ok(Conversion {
+        html,
+        repairs,
+        title,
+        toc,
+        removed_urls,
+        blocked_iframes,
+    })
+}
+
+/// Runs the hook of `stage`, timing it if one is set.
+pub(crate) fn run_hook(
+    config: &HtmlConfig,
+    stage: HookStage,
+    content: &mut String,
+) -> Result<()> {
+    if !config.hooks.has(stage) {
+        return Ok(());
+    }
+    profile(config.profiler.as_ref(), &stage.to_string(), || {
+        config.hooks.run(stage, content)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::emojis::EmojiPolicy;
+
+    #[test]
+    fn test_convert_basic() {
+        let html = convert("# Title", &HtmlConfig::default()).unwrap();
+        assert!(html.contains("<h1>Title</h1>"));
+    }
+
+    #[test]
+    fn test_convert_strips_title() {
+        let config = HtmlConfig {
+            strip_title: true,
+            heading_offset: 1,
+            ..Default::default()
+        };
+        let conversion = convert_detailed(
+            "Intro\n\n# The *Title*\n\n# Second\n\n## Part",
+            &config,
+        )
+        .unwrap();
+        assert_eq!(conversion.title.as_deref(), Some("The Title"));
+        assert!(!conversion.html.contains("Title"));
+        assert!(conversion.html.contains("<h2>Second</h2>"));
+        assert!(conversion.html.contains("<h3>Part</h3>"));
+
+        let conversion =
+            convert_detailed("## Only", &HtmlConfig::default())
+                .unwrap();
+        assert_eq!(conversion.title, None);
+        let conversion = convert_detailed("## Only", &config).unwrap();
+        assert_eq!(conversion.title, None);
+    }
+
+    #[test]
+    fn test_convert_applies_comment_directives() {
+        let markdown =
+            "<!-- html-generator: heading_offset=1 strip_title=on -->\n# T\n\n## S";
+        let config = HtmlConfig {
+            comment_directives: true,
+            ..Default::default()
+        };
+        let conversion = convert_detailed(markdown, &config).unwrap();
+        assert_eq!(conversion.title.as_deref(), Some("T"));
+        assert!(conversion.html.contains("<h3>S</h3>"));
+        assert!(!conversion.html.contains("html-generator:"));
+
+        let html = convert(markdown, &HtmlConfig::default()).unwrap();
+        assert!(html.contains("<h1>T</h1>"));
+    }
+
+    #[test]
+    fn test_convert_rejects_empty_input() {
+        assert!(matches!(
+            convert("", &HtmlConfig::default()),
+            Err(HtmlError::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn test_convert_rejects_oversized_input() {
+        let config = HtmlConfig {
+            max_input_size: 1024,
+            ..Default::default()
+        };
+        assert!(matches!(
+            convert(&"a".repeat(1025), &config),
+            Err(HtmlError::InputTooLarge(1025))
+        ));
+    }
+
+    #[test]
+    fn test_convert_applies_emoji_policy() {
+        let config = HtmlConfig {
+            emoji_policy: EmojiPolicy::StripWithLabel,
+            ..Default::default()
+        };
+        let html = convert("# Done ✅\n\n`✅`", &config).unwrap();
+        assert!(html.contains("Done [white heavy check mark]"));
+        assert!(html.contains("<code>✅</code>"));
+    }
+
+    #[test]
+    fn test_convert_applies_unicode_cleanup() {
+        let markdown = "Cafe\u{301} \u{202E}txt";
+        let raw = convert(markdown, &HtmlConfig::default()).unwrap();
+        assert!(raw.contains('\u{202E}'));
+
+        let config = HtmlConfig {
+            normalize_unicode: true,
+            strip_invisible_characters: true,
+            ..Default::default()
+        };
+        let html = convert(markdown, &config).unwrap();
+        assert!(html.contains("Caf\u{e9} txt"));
+    }
+
+    #[test]
+    fn test_convert_detailed_repairs_mojibake() {
+        let markdown = "It\u{e2}\u{20ac}\u{2122}s here";
+        let untouched =
+            convert_detailed(markdown, &HtmlConfig::default()).unwrap();
+        assert!(untouched.repairs.is_empty());
+
+        let config = HtmlConfig {
+            repair_mojibake: true,
+            ..Default::default()
+        };
+        let repaired = convert_detailed(markdown, &config).unwrap();
+        assert!(repaired.html.contains("It\u{2019}s here"));
+        assert_eq!(repaired.repairs.len(), 1);
+    }
+}
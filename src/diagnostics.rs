@@ -0,0 +1,164 @@
+// Copyright © 2025 HTML Generator. All rights reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Non-fatal diagnostics reported during conversion.
+//!
+//! Unlike [`HtmlError`](crate::HtmlError), a [`Diagnostic`] does not stop
+//! processing. Diagnostics describe conditions worth surfacing to the
+//! user, such as an output file that would be overwritten, and carry a
+//! stable code so tooling can filter or match on them.
+//!
+//! # Examples
+//!
+//! ```
+//! use html_generator::diagnostics::{Diagnostic, Severity};
+//!
+//! let diagnostic = Diagnostic::warning("overwrite", "File exists")
+//!     .with_path("site/index.html");
+//! assert_eq!(diagnostic.severity, Severity::Warning);
+//! assert_eq!(
+//!     diagnostic.to_string(),
+//!     "warning[overwrite]: File exists (site/index.html)"
+//! );
+//! ```
+
+use std::{fmt, path::PathBuf};
+
+/// How serious a diagnostic is.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Severity {
+    /// A problem that prevents correct output.
+    Error,
+    /// A likely problem that does not prevent output.
+    Warning,
+    /// Informational message.
+    Info,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Info => write!(f, "info"),
+        }
+    }
+}
+
+/// A non-fatal message produced during conversion.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// How serious the diagnostic is.
+    pub severity: Severity,
+    /// Stable, machine-readable code.
+    pub code: String,
+    /// Human-readable description.
+    pub message: String,
+    /// The file the diagnostic refers to, if any.
+    pub path: Option<PathBuf>,
+}
+
+impl Diagnostic {
+    /// Creates a new diagnostic.
+    ///
+    /// # Arguments
+    ///
+    /// * `severity` - How serious the diagnostic is
+    /// * `code` - Stable, machine-readable code
+    /// * `message` - Human-readable description
+    pub fn new(
+        severity: Severity,
+        code: impl Into<String>,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            severity,
+            code: code.into(),
+            message: message.into(),
+            path: None,
+        }
+    }
+
+    /// Creates an error diagnostic.
+    pub fn error(
+        code: impl Into<String>,
+        message: impl Into<String>,
+    ) -> Self {
+        Self::new(Severity::Error, code, message)
+    }
+
+    /// Creates a warning diagnostic.
+    pub fn warning(
+        code: impl Into<String>,
+        message: impl Into<String>,
+    ) -> Self {
+        Self::new(Severity::Warning, code, message)
+    }
+
+    /// Creates an informational diagnostic.
+    pub fn info(
+        code: impl Into<String>,
+        message: impl Into<String>,
+    ) -> Self {
+        Self::new(Severity::Info, code, message)
+    }
+
+    /// Attaches the file the diagnostic refers to.
+    #[must_use]
+    pub fn with_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}[{}]: {}",
+            self.severity, self.code, self.message
+        )?;
+        if let Some(path) = &self.path {
+            write!(f, " ({})", path.display())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_constructors_set_severity() {
+        assert_eq!(
+            Diagnostic::error("e", "m").severity,
+            Severity::Error
+        );
+        assert_eq!(
+            Diagnostic::warning("w", "m").severity,
+            Severity::Warning
+        );
+        assert_eq!(Diagnostic::info("i", "m").severity, Severity::Info);
+    }
+
+    #[test]
+    fn test_display_without_path() {
+        let diagnostic = Diagnostic::info("dry_run", "Nothing written");
+        assert_eq!(
+            diagnostic.to_string(),
+            "info[dry_run]: Nothing written"
+        );
+    }
+
+    #[test]
+    fn test_severity_ordering() {
+        assert!(Severity::Error < Severity::Warning);
+        assert!(Severity::Warning < Severity::Info);
+    }
+}
@@ -0,0 +1,123 @@
+// Copyright © 2025 HTML Generator. All rights reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Diagram code blocks for client-side renderers.
+//!
+//! Code blocks in a language of
+//! [`HtmlConfig::diagram_languages`](crate::HtmlConfig::diagram_languages)
+//! are not highlighted. They are written as a `<pre>` with the class
+//! mapped to the language, which Mermaid and similar renderers pick up:
+//!
+//! | Markdown | HTML |
+//! |----------|------|
+//! | ```` ```mermaid ```` | `<pre class="mermaid">...</pre>` |
+//! | ```` ```graphviz ```` | `<pre class="graphviz">...</pre>` |
+//! | ```` ```plantuml ```` | `<pre class="plantuml">...</pre>` |
+//!
+//! The diagram source is kept as escaped text, so pages remain readable
+//! without scripts.
+//!
+//! # Examples
+//!
+//! ```
+//! use html_generator::{conversion::convert, HtmlConfig};
+//!
+//! let html = convert("```mermaid\ngraph TD; A-->B;\n```", &HtmlConfig::default())?;
+//! assert_eq!(html, "<pre class=\"mermaid\">graph TD; A--&gt;B;\n</pre>\n");
+//! # Ok::<(), html_generator::error::HtmlError>(())
+//! ```
+
+use crate::patterns::diagrams::DIAGRAM_BLOCK_REGEX;
+use regex::Captures;
+use std::collections::BTreeMap;
+
+/// Returns the default mapping of diagram languages to the class of
+/// their `<pre>`: `mermaid`, `graphviz` and `plantuml` to themselves.
+pub fn default_diagram_languages() -> BTreeMap<String, String> {
+    ["mermaid", "graphviz", "plantuml"]
+        .iter()
+        .map(|language| (language.to_string(), language.to_string()))
+        .collect()
+}
+
+/// Rewrites the code blocks of `html` in one of `languages` as
+/// `<pre class="...">` elements.
+///
+/// # Arguments
+///
+/// * `html` - HTML rendered from Markdown
+/// * `languages` - The class of the `<pre>` for each diagram language
+///
+/// # Returns
+///
+/// The HTML with diagram blocks.
+pub fn render_diagram_blocks(
+    html: &str,
+    languages: &BTreeMap<String, String>,
+) -> String {
+    if languages.is_empty() {
+        return html.to_string();
+    }
+    DIAGRAM_BLOCK_REGEX
+        .replace_all(html, |caps: &Captures<'_>| {
+            match languages.get(&caps[1]) {
+                Some(class) => {
+                    format!(
+                        r#"<pre class="{}">{}</pre>"#,
+                        class, &caps[2]
+                    )
+                }
+                None => caps[0].to_string(),
+            }
+        })
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conversion::convert;
+    use crate::HtmlConfig;
+
+    #[test]
+    fn test_diagram_blocks_skip_highlighting() {
+        let markdown = "```graphviz\ndigraph { a -> b }\n```\n\n```rust\nfn main() {}\n```\n\n```plantuml\n@startuml\n@enduml\n```\n";
+        let html = convert(markdown, &HtmlConfig::default()).unwrap();
+        assert!(html.contains(
+            "<pre class=\"graphviz\">digraph { a -&gt; b }\n</pre>"
+        ));
+        assert!(html.contains("<pre class=\"plantuml\">@startuml\n"));
+        assert!(html.contains(r#"<div class="highlight""#));
+        assert!(!html.contains("language-graphviz"));
+
+        let config = HtmlConfig {
+            diagram_languages: [(
+                "dot".to_string(),
+                "diagram diagram-dot".to_string(),
+            )]
+            .into_iter()
+            .collect(),
+            ..HtmlConfig::default()
+        };
+        let html = convert(
+            "```dot\na -> b\n```\n\n```mermaid\ngraph TD\n```\n",
+            &config,
+        )
+        .unwrap();
+        assert!(html.contains(
+            "<pre class=\"diagram diagram-dot\">a -&gt; b\n</pre>"
+        ));
+        assert!(!html.contains(r#"<pre class="mermaid">"#));
+    }
+
+    #[test]
+    fn test_empty_mapping_leaves_code_blocks() {
+        let html =
+            "<pre><code class=\"language-mermaid\">x</code></pre>";
+        assert_eq!(render_diagram_blocks(html, &BTreeMap::new()), html);
+        assert_eq!(
+            render_diagram_blocks(html, &default_diagram_languages()),
+            "<pre class=\"mermaid\">x</pre>"
+        );
+    }
+}
@@ -0,0 +1,428 @@
+// Copyright © 2025 HTML Generator. All rights reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Per-document configuration overrides in HTML comments.
+//!
+//! A few pages of a large site often need different settings. With
+//! [`HtmlConfig::comment_directives`] enabled, a document can override
+//! the configuration with comments at its top, before any content and
+//! after the optional front matter:
+//!
+//! ```markdown
+//! <!-- html-generator: minify=false toc=true -->
+//! <!-- html-generator: heading_offset=1 emoji=strip_with_label -->
+//! # Release notes
+//! ```
+//!
+//! Each directive is a space-separated list of `key=value` pairs.
+//! Booleans accept `true`/`false`, `yes`/`no` and `on`/`off`. Only
+//! presentation settings can be overridden; size and resource limits
+//! always come from the caller. Directive comments are removed from the
+//! document before conversion.
+//!
+//! | Key | Field |
+//! |-----|-------|
+//! | `highlight`, `syntax_highlighting` | [`HtmlConfig::enable_syntax_highlighting`] |
+//! | `theme`, `syntax_theme` | [`HtmlConfig::syntax_theme`] (`none` restores the default, `css` writes classes) |
+//! | `highlight_mode` | [`HtmlConfig::highlight_mode`] (`inline`, `classes` or `none`) |
+//! | `minify`, `minify_output` | [`HtmlConfig::minify_output`] |
+//! | `aria`, `add_aria_attributes` | [`HtmlConfig::add_aria_attributes`] |
+//! | `structured_data`, `generate_structured_data` | [`HtmlConfig::generate_structured_data`] |
+//! | `lang`, `language` | [`HtmlConfig::language`] |
+//! | `toc`, `generate_toc` | [`HtmlConfig::generate_toc`] |
+//! | `emoji`, `emoji_policy` | [`HtmlConfig::emoji_policy`] |
+//! | `normalize_unicode` | [`HtmlConfig::normalize_unicode`] |
+//! | `strip_invisible`, `strip_invisible_characters` | [`HtmlConfig::strip_invisible_characters`] |
+//! | `repair_mojibake` | [`HtmlConfig::repair_mojibake`] |
+//! | `heading_offset` | [`HtmlConfig::heading_offset`] |
+//! | `strip_title` | [`HtmlConfig::strip_title`] |
+//! | `markdown_in_html` | [`HtmlConfig::markdown_in_html`] |
+//! | `tables`, `enhance_tables` | [`HtmlConfig::enhance_tables`] |
+//! | `table_chunk_rows` | [`HtmlConfig::table_chunk_rows`] |
+//! | `footnote_popovers` | [`HtmlConfig::footnote_popovers`] |
+//! | `math` | [`HtmlConfig::math`] |
+//! | `dir`, `direction` | [`HtmlConfig::direction`] (`ltr`, `rtl`, `auto` or `none`) |
+//! | `auto_dir`, `auto_direction` | [`HtmlConfig::auto_direction`] |
+//! | `writing_mode` | [`HtmlConfig::writing_mode`] (`horizontal-tb`, `vertical-rl` or `vertical-lr`) |
+//! | `heading_ids` | [`HtmlConfig::heading_ids`] |
+//! | `heading_anchors`, `heading_anchor_links` | [`HtmlConfig::heading_anchor_links`] |
+//! | `section_toc`, `section_tocs` | [`HtmlConfig::section_tocs`] |
+//! | `slugs`, `slug_strategy` | [`HtmlConfig::slug_strategy`] |
+//! | `provenance` | [`HtmlConfig::provenance`] (`on` adds a reproducible comment) |
+//!
+//! # Examples
+//!
+//! ```
+//! use html_generator::directives::extract_directives;
+//!
+//! let (overrides, body) = extract_directives(
+//!     "<!-- html-generator: toc=true lang=fr-FR -->\n# Bonjour",
+//! )?;
+//! assert_eq!(overrides.generate_toc, Some(true));
+//! assert_eq!(overrides.language.as_deref(), Some("fr-FR"));
+//! assert_eq!(body, "# Bonjour");
+//! # Ok::<(), html_generator::error::HtmlError>(())
+//! ```
+
+use crate::error::{HtmlError, Result};
+use crate::front_matter::{split_front_matter_as, FrontMatterFormat};
+use crate::{HtmlConfig, PartialHtmlConfig};
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+/// The marker that identifies a directive comment.
+pub const DIRECTIVE_PREFIX: &str = "html-generator:";
+
+/// Extracts the directives at the top of a Markdown document.
+///
+/// Directives are recognised on the leading lines of the document,
+/// after the optional `---` front matter block, as long as only blank
+/// lines and other directives precede them.
+///
+/// # Arguments
+///
+/// * `markdown` - The Markdown document
+///
+/// # Returns
+///
+/// The overrides, and the document with the directive lines removed.
+///
+/// # Errors
+///
+/// Returns [`HtmlError::InvalidInput`] if a directive contains an
+/// unknown key, a pair without `=`, or an invalid value.
+pub fn extract_directives(
+    markdown: &str,
+) -> Result<(PartialHtmlConfig, String)> {
+    extract_directives_after(markdown, FrontMatterFormat::Auto)
+}
+
+/// Extracts the directives following front matter in `format`.
+fn extract_directives_after(
+    markdown: &str,
+    format: FrontMatterFormat,
+) -> Result<(PartialHtmlConfig, String)> {
+    let lines: Vec<&str> = markdown.lines().collect();
+    let mut overrides = PartialHtmlConfig::default();
+    let mut index = front_matter_end(markdown, format);
+    let mut directive_lines = Vec::new();
+
+    while let Some(line) = lines.get(index) {
+        let trimmed = line.trim();
+        if let Some(body) = directive_body(trimmed) {
+            parse_directive(body, &mut overrides)?;
+            directive_lines.push(index);
+        } else if !trimmed.is_empty() {
+            break;
+        }
+        index += 1;
+    }
+
+    if directive_lines.is_empty() {
+        return Ok((overrides, markdown.to_string()));
+    }
+    let body = lines
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| !directive_lines.contains(index))
+        .map(|(_, line)| *line)
+        .collect::<Vec<_>>()
+        .join("\n");
+    Ok((overrides, body))
+}
+
+/// Applies the directives of `markdown` to `config`.
+///
+/// # Returns
+///
+/// The merged and validated configuration, and the document without its
+/// directive lines.
+///
+/// # Errors
+///
+/// Returns the errors of [`extract_directives`], or a validation error
+/// if the merged configuration is invalid.
+pub fn apply_directives(
+    markdown: &str,
+    config: &HtmlConfig,
+) -> Result<(HtmlConfig, String)> {
+    let (overrides, body) = extract_directives_after(
+        markdown,
+        config.markdown_extensions.front_matter_format,
+    )?;
+    if overrides.is_empty() {
+        return Ok((config.clone(), body));
+    }
+    let config = config.merge(overrides);
+    config.validate()?;
+    Ok((config, body))
+}
+
+/// Returns the index of the first line after the front matter in
+/// `format`, as split off by the generator.
+fn front_matter_end(
+    markdown: &str,
+    format: FrontMatterFormat,
+) -> usize {
+    match split_front_matter_as(markdown, format) {
+        (Some(_), body) => {
+            let offset =
+                body.as_ptr() as usize - markdown.as_ptr() as usize;
+            markdown[..offset].matches('\n').count()
+        }
+        (None, _) => 0,
+    }
+}
+
+/// Returns the `key=value` list of a directive comment.
+fn directive_body(line: &str) -> Option<&str> {
+    line.strip_prefix("<!--")?
+        .strip_suffix("-->")?
+        .trim()
+        .strip_prefix(DIRECTIVE_PREFIX)
+        .map(str::trim)
+}
+
+/// Parses one directive into `overrides`.
+fn parse_directive(
+    body: &str,
+    overrides: &mut PartialHtmlConfig,
+) -> Result<()> {
+    for pair in body.split_whitespace() {
+        let (key, value) = pair.split_once('=').ok_or_else(|| {
+            HtmlError::InvalidInput(format!(
+                "Directive `{}` is not a key=value pair",
+                pair
+            ))
+        })?;
+        let invalid = || {
+            HtmlError::InvalidInput(format!(
+                "Invalid value `{}` for directive `{}`",
+                value, key
+            ))
+        };
+        let flag = || match value {
+            "true" | "yes" | "on" => Ok(true),
+            "false" | "no" | "off" => Ok(false),
+            _ => Err(invalid()),
+        };
+        match key {
+            "highlight" | "syntax_highlighting" => {
+                overrides.enable_syntax_highlighting = Some(flag()?);
+            }
+            "theme" | "syntax_theme" => {
+                overrides.syntax_theme = Some(match value {
+                    "none" => None,
+                    theme => Some(theme.to_string()),
+                });
+            }
+            "highlight_mode" => {
+                overrides.highlight_mode = Some(value.parse()?);
+            }
+            "minify" | "minify_output" => {
+                overrides.minify_output = Some(flag()?);
+            }
+            "aria" | "add_aria_attributes" => {
+                overrides.add_aria_attributes = Some(flag()?);
+            }
+            "structured_data" | "generate_structured_data" => {
+                overrides.generate_structured_data = Some(flag()?);
+            }
+            "lang" | "language" => {
+                overrides.language = Some(value.to_string());
+            }
+            "toc" | "generate_toc" => {
+                overrides.generate_toc = Some(flag()?);
+            }
+            "emoji" | "emoji_policy" => {
+                overrides.emoji_policy = Some(value.parse()?);
+            }
+            "normalize_unicode" => {
+                overrides.normalize_unicode = Some(flag()?);
+            }
+            "strip_invisible" | "strip_invisible_characters" => {
+                overrides.strip_invisible_characters = Some(flag()?);
+            }
+            "repair_mojibake" => {
+                overrides.repair_mojibake = Some(flag()?);
+            }
+            "heading_offset" => {
+                overrides.heading_offset =
+                    Some(value.parse().map_err(|_| invalid())?);
+            }
+            "strip_title" => overrides.strip_title = Some(flag()?),
+            "markdown_in_html" => {
+                overrides.markdown_in_html = Some(flag()?);
+            }
+            "tables" | "enhance_tables" => {
+                overrides.enhance_tables = Some(flag()?);
+            }
+            "heading_ids" => overrides.heading_ids = Some(flag()?),
+            "heading_anchors" | "heading_anchor_links" => {
+                overrides.heading_anchor_links = Some(flag()?);
+            }
+            "section_toc" | "section_tocs" => {
+                overrides.section_tocs = Some(flag()?);
+            }
+            "slugs" | "slug_strategy" => {
+                overrides.slug_strategy = Some(value.parse()?);
+            }
+            "provenance" => {
+                overrides.provenance = Some(
+                    flag()?
+                        .then(crate::provenance::Provenance::default),
+                );
+            }
+            "footnote_popovers" => {
+                overrides.footnote_popovers = Some(flag()?);
+            }
+            "math" => overrides.math = Some(flag()?),
+            "dir" | "direction" => {
+                overrides.direction = Some(match value {
+                    "none" => None,
+                    direction => Some(direction.parse()?),
+                });
+            }
+            "auto_dir" | "auto_direction" => {
+                overrides.auto_direction = Some(flag()?);
+            }
+            "writing_mode" => {
+                overrides.writing_mode = Some(value.parse()?);
+            }
+            "table_chunk_rows" => {
+                overrides.table_chunk_rows =
+                    Some(value.parse().map_err(|_| invalid())?);
+            }
+            _ => {
+                return Err(HtmlError::InvalidInput(format!(
+                    "Unknown directive `{}`",
+                    key
+                )))
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod extract_directives_tests {
+        use super::*;
+
+        #[test]
+        fn test_multiple_directives_and_aliases() {
+            let (overrides, body) = extract_directives(
+                "\n<!-- html-generator: minify=false toc=yes -->\n  <!--html-generator: heading_offset=-1 theme=none emoji=remove -->\n\n# Title\n<!-- html-generator: toc=false -->",
+            )
+            .unwrap();
+            assert_eq!(overrides.minify_output, Some(false));
+            assert_eq!(overrides.generate_toc, Some(true));
+            assert_eq!(overrides.heading_offset, Some(-1));
+            assert_eq!(overrides.syntax_theme, Some(None));
+            assert_eq!(
+                overrides.emoji_policy,
+                Some(crate::emojis::EmojiPolicy::Remove)
+            );
+            assert_eq!(
+                body,
+                "\n\n# Title\n<!-- html-generator: toc=false -->"
+            );
+        }
+
+        #[test]
+        fn test_directives_after_front_matter() {
+            let (overrides, body) = extract_directives(
+                "---\ntitle: A\n---\n<!-- html-generator: strip_title=on -->\n# A",
+            )
+            .unwrap();
+            assert_eq!(overrides.strip_title, Some(true));
+            assert_eq!(body, "---\ntitle: A\n---\n# A");
+        }
+
+        #[test]
+        fn test_directives_after_toml_and_json_front_matter() {
+            for markdown in [
+                "+++\ntitle = \"A\"\n+++\n\n<!-- html-generator: toc=on -->\n# A",
+                "{\"title\": \"A\"}\n<!-- html-generator: toc=on -->\n# A",
+            ] {
+                let (overrides, body) =
+                    extract_directives(markdown).unwrap();
+                assert_eq!(overrides.generate_toc, Some(true), "{}", markdown);
+                assert!(!body.contains("html-generator"));
+            }
+        }
+
+        #[test]
+        fn test_front_matter_in_another_format_is_content() {
+            let mut config = HtmlConfig::default();
+            config.markdown_extensions.front_matter_format =
+                FrontMatterFormat::Yaml;
+            let (config, _) = apply_directives(
+                "+++\ntitle = \"A\"\n+++\n<!-- html-generator: toc=on -->\n# A",
+                &config,
+            )
+            .unwrap();
+            assert!(!config.generate_toc);
+        }
+
+        #[test]
+        fn test_documents_without_directives() {
+            let markdown = "<!-- note -->\n# Title\n";
+            let (overrides, body) =
+                extract_directives(markdown).unwrap();
+            assert!(overrides.is_empty());
+            assert_eq!(body, markdown);
+        }
+
+        #[test]
+        fn test_invalid_directives() {
+            for directive in [
+                "max_input_size=1",
+                "toc",
+                "toc=maybe",
+                "heading_offset=x",
+                "emoji=sparkle",
+            ] {
+                let markdown = format!(
+                    "<!-- html-generator: {} -->\n# A",
+                    directive
+                );
+                assert!(
+                    matches!(
+                        extract_directives(&markdown),
+                        Err(HtmlError::InvalidInput(_))
+                    ),
+                    "{}",
+                    directive
+                );
+            }
+        }
+    }
+
+    mod apply_directives_tests {
+        use super::*;
+
+        #[test]
+        fn test_merges_and_validates() {
+            let base = HtmlConfig::default();
+            let (config, _) = apply_directives(
+                "<!-- html-generator: lang=de-DE -->\n# A",
+                &base,
+            )
+            .unwrap();
+            assert_eq!(config.language, "de-DE");
+            assert_eq!(config.max_input_size, base.max_input_size);
+
+            assert!(apply_directives(
+                "<!-- html-generator: lang=nope -->\n# A",
+                &base
+            )
+            .is_err());
+        }
+    }
+}
@@ -0,0 +1,340 @@
+// Copyright © 2025 HTML Generator. All rights reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Extraction of the fenced code blocks of documentation.
+//!
+//! Documentation examples rot unless they are run. [`extract_code_blocks`]
+//! lists the fenced code blocks of a Markdown document with their
+//! language, the attributes of their info string and their position, so
+//! tooling can compile or run them and report failures at the right
+//! line, as mdBook does for the Rust examples of a book.
+//!
+//! The first word of the info string is the language and the others are
+//! attributes, separated by commas or spaces. Attributes may also be
+//! written in braces, as in `rust {test}`, and both the language and
+//! attributes in Pandoc style, as in `{.rust .test}`. Blocks marked with
+//! the [`TEST_ATTRIBUTE`] are the examples to run, and
+//! [`CodeBlockFilter::tests`] selects them for one language.
+//!
+//! Front matter is skipped, and lines are counted from the start of the
+//! whole document.
+//!
+//! # Examples
+//!
+//! ```
+//! use html_generator::doctests::{extract_code_blocks, CodeBlockFilter};
+//!
+//! let markdown = "---\ntitle: Guide\n---\n\n```rust,test\nassert_eq!(1 + 1, 2);\n```\n\n```rust\nlet shown = true;\n```\n";
+//!
+//! let blocks = extract_code_blocks(markdown, &CodeBlockFilter::tests("rust"));
+//! assert_eq!(blocks.len(), 1);
+//! assert_eq!(blocks[0].code, "assert_eq!(1 + 1, 2);\n");
+//! assert_eq!(blocks[0].line, 5);
+//! assert!(blocks[0].has_attribute("test"));
+//!
+//! let all = extract_code_blocks(markdown, &CodeBlockFilter::default());
+//! assert_eq!(all.len(), 2);
+//! ```
+
+use crate::front_matter::split_front_matter;
+use comrak::nodes::NodeValue;
+use comrak::{parse_document, Arena, ComrakOptions};
+
+/// Attribute marking the code blocks to run, as in ` ```rust,test `.
+pub const TEST_ATTRIBUTE: &str = "test";
+
+/// A fenced code block of a Markdown document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct CodeBlock {
+    /// The language, the first word of the info string.
+    pub language: Option<String>,
+    /// The other words of the info string, e.g. `test`, `ignore` or
+    /// `title=main.rs`, without braces or leading dots.
+    pub attributes: Vec<String>,
+    /// The info string as written.
+    pub info: String,
+    /// The code, without the fences and the indentation of the block.
+    pub code: String,
+    /// Line of the opening fence, starting at 1. The code starts on the
+    /// next line.
+    pub line: usize,
+    /// Column of the opening fence in characters, starting at 1.
+    pub column: usize,
+    /// Line of the closing fence, or the last line of the block if it is
+    /// not closed.
+    pub end_line: usize,
+}
+
+impl CodeBlock {
+    /// Returns `true` if the block has the attribute `name`, written
+    /// alone or as `name=value`.
+    pub fn has_attribute(&self, name: &str) -> bool {
+        self.attributes.iter().any(|attribute| {
+            attribute == name
+                || attribute
+                    .strip_prefix(name)
+                    .map_or(false, |rest| rest.starts_with('='))
+        })
+    }
+
+    /// Returns `true` if the block has the [`TEST_ATTRIBUTE`].
+    pub fn is_test(&self) -> bool {
+        self.has_attribute(TEST_ATTRIBUTE)
+    }
+}
+
+/// The code blocks returned by [`extract_code_blocks`].
+///
+/// The default filter keeps every fenced code block.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CodeBlockFilter {
+    /// Only keeps blocks in this language, compared ignoring ASCII case.
+    pub language: Option<String>,
+    /// Only keeps blocks with this attribute.
+    pub attribute: Option<String>,
+}
+
+impl CodeBlockFilter {
+    /// Returns a filter keeping the blocks in `language` with the
+    /// [`TEST_ATTRIBUTE`].
+    pub fn tests(language: &str) -> Self {
+        Self {
+            language: Some(language.to_string()),
+            attribute: Some(TEST_ATTRIBUTE.to_string()),
+        }
+    }
+
+    /// Returns `true` if the filter keeps `block`.
+    pub fn matches(&self, block: &CodeBlock) -> bool {
+        let language = match (&self.language, &block.language) {
+            (None, _) => true,
+            (Some(wanted), Some(language)) => {
+                wanted.eq_ignore_ascii_case(language)
+            }
+            (Some(_), None) => false,
+        };
+        language
+            && self.attribute.as_deref().map_or(true, |attribute| {
+                block.has_attribute(attribute)
+            })
+    }
+}
+
+/// Returns the fenced code blocks of a Markdown document kept by
+/// `filter`, in document order.
+///
+/// Indented code blocks are not returned, as they have no info string.
+///
+/// # Arguments
+///
+/// * `markdown` - The document, with or without front matter
+/// * `filter` - The blocks to keep
+pub fn extract_code_blocks(
+    markdown: &str,
+    filter: &CodeBlockFilter,
+) -> Vec<CodeBlock> {
+    let (body, first_line) = skip_front_matter(markdown);
+    let lines: Vec<&str> = body.lines().collect();
+
+    let mut options = ComrakOptions::default();
+    options.extension.table = true;
+    let arena = Arena::new();
+    let root = parse_document(&arena, body, &options);
+
+    root.descendants()
+        .filter_map(|node| {
+            let data = node.data.borrow();
+            let code_block = match &data.value {
+                NodeValue::CodeBlock(code_block)
+                    if code_block.fenced =>
+                {
+                    code_block
+                }
+                _ => return None,
+            };
+            let (language, attributes) = parse_info(&code_block.info);
+            let start = data.sourcepos.start;
+            let column = lines
+                .get(start.line - 1)
+                .and_then(|line| line.get(..start.column - 1))
+                .map_or(start.column, |prefix| {
+                    prefix.chars().count() + 1
+                });
+            Some(CodeBlock {
+                language,
+                attributes,
+                info: code_block.info.clone(),
+                code: code_block.literal.clone(),
+                line: first_line + start.line - 1,
+                column,
+                end_line: first_line + data.sourcepos.end.line - 1,
+            })
+        })
+        .filter(|block| filter.matches(block))
+        .collect()
+}
+
+/// Returns the body of `markdown` after any front matter, starting at
+/// the beginning of its line, and the number of that line.
+fn skip_front_matter(markdown: &str) -> (&str, usize) {
+    let (block, body) = split_front_matter(markdown);
+    if block.is_none() {
+        return (markdown, 1);
+    }
+    let mut offset =
+        body.as_ptr() as usize - markdown.as_ptr() as usize;
+    let line_start =
+        markdown[..offset].rfind('\n').map_or(0, |i| i + 1);
+    if markdown[line_start..offset].trim().is_empty() {
+        offset = line_start;
+    }
+    let line = markdown[..offset].matches('\n').count() + 1;
+    (&markdown[offset..], line)
+}
+
+/// Splits an info string into the language and the attributes.
+fn parse_info(info: &str) -> (Option<String>, Vec<String>) {
+    let info = info.trim();
+    let pandoc = info.starts_with('{');
+    let mut words = info
+        .split(|c: char| {
+            c.is_whitespace() || matches!(c, ',' | '{' | '}')
+        })
+        .filter(|word| !word.is_empty());
+    let language = if pandoc {
+        None
+    } else {
+        words.next().map(str::to_string)
+    };
+    let mut attributes: Vec<String> = words
+        .map(|word| word.strip_prefix('.').unwrap_or(word).to_string())
+        .collect();
+    if pandoc && !attributes.is_empty() {
+        return (Some(attributes.remove(0)), attributes);
+    }
+    (language, attributes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod info_tests {
+        use super::*;
+
+        #[test]
+        fn test_info_strings() {
+            assert_eq!(parse_info(""), (None, vec![]));
+            assert_eq!(
+                parse_info("rust"),
+                (Some("rust".to_string()), vec![])
+            );
+            for written in [
+                "rust,test,ignore",
+                "rust test ignore",
+                "rust {test, ignore}",
+            ] {
+                assert_eq!(
+                    parse_info(written),
+                    (
+                        Some("rust".to_string()),
+                        vec!["test".to_string(), "ignore".to_string()]
+                    ),
+                    "{}",
+                    written
+                );
+            }
+            assert_eq!(
+                parse_info("{.python .test title=x.py}"),
+                (
+                    Some("python".to_string()),
+                    vec!["test".to_string(), "title=x.py".to_string()]
+                )
+            );
+        }
+
+        #[test]
+        fn test_has_attribute() {
+            let block = extract_code_blocks(
+                "```rust should_panic title=main.rs\n```\n",
+                &CodeBlockFilter::default(),
+            )
+            .remove(0);
+            assert!(block.has_attribute("should_panic"));
+            assert!(block.has_attribute("title"));
+            assert!(!block.has_attribute("tit"));
+            assert!(!block.is_test());
+        }
+    }
+
+    mod extraction_tests {
+        use super::*;
+
+        #[test]
+        fn test_positions() {
+            let markdown = "# Guide\n\n- Item\n\n  ```rust,test\n  let x = 1;\n  ```\n\n> ~~~python test\n> print(1)\n> ~~~\n\n    indented\n\n```sh\nunclosed\n";
+            let blocks = extract_code_blocks(
+                markdown,
+                &CodeBlockFilter::default(),
+            );
+            let positions: Vec<_> = blocks
+                .iter()
+                .map(|block| (block.line, block.column, block.end_line))
+                .collect();
+            assert_eq!(positions, [(5, 3, 7), (9, 3, 11), (15, 1, 16)]);
+            assert_eq!(blocks[0].code, "let x = 1;\n");
+            assert_eq!(blocks[1].code, "print(1)\n");
+            assert_eq!(blocks[2].info, "sh");
+        }
+
+        #[test]
+        fn test_filters() {
+            let markdown = "```Rust,test\na\n```\n\n```rust\nb\n```\n\n```python,test\nc\n```\n\n```\nd\n```\n";
+            let codes = |filter: CodeBlockFilter| -> Vec<String> {
+                extract_code_blocks(markdown, &filter)
+                    .into_iter()
+                    .map(|block| block.code)
+                    .collect()
+            };
+            assert_eq!(codes(CodeBlockFilter::tests("rust")), ["a\n"]);
+            assert_eq!(
+                codes(CodeBlockFilter {
+                    language: Some("rust".to_string()),
+                    attribute: None,
+                }),
+                ["a\n", "b\n"]
+            );
+            assert_eq!(
+                codes(CodeBlockFilter {
+                    language: None,
+                    attribute: Some("test".to_string()),
+                }),
+                ["a\n", "c\n"]
+            );
+            assert_eq!(codes(CodeBlockFilter::default()).len(), 4);
+        }
+
+        #[test]
+        fn test_front_matter_lines() {
+            let markdown =
+                "+++\ntitle = \"Guide\"\n+++\n\n```rust\nfn main() {}\n```\n";
+            let blocks = extract_code_blocks(
+                markdown,
+                &CodeBlockFilter::default(),
+            );
+            assert_eq!(blocks.len(), 1);
+            assert_eq!((blocks[0].line, blocks[0].end_line), (5, 7));
+
+            let unterminated = "---\n```rust\nx\n```\n";
+            let blocks = extract_code_blocks(
+                unterminated,
+                &CodeBlockFilter::default(),
+            );
+            assert_eq!(blocks[0].line, 2);
+        }
+    }
+}
@@ -29,10 +29,31 @@
 //! 26A1 ; emoji ; L1 ; none ; a j # V4.0 (⚡) HIGH VOLTAGE SIGN
 //! ```
 //! The descriptive label derived would be: `"high-voltage-sign"`.
-
+//!
+//! ### Catalog:
+//! [`EmojiCatalog`] keeps the full metadata of each emoji and supports
+//! lookup by emoji, code point, shortcode and name, as well as selecting
+//! the emoji available in a given Unicode version. Newer data files can
+//! be fetched with `tools/update_emoji_data.sh <version>`.
+
+use crate::error::HtmlError;
+use once_cell::sync::Lazy;
 use std::collections::HashMap;
+use std::fmt;
 use std::fs;
 use std::path::Path;
+use std::str::FromStr;
+
+/// Emoji data bundled with the crate, parsed on first use.
+static BUNDLED_CATALOG: Lazy<EmojiCatalog> = Lazy::new(|| {
+    EmojiCatalog::parse(include_str!("../data/emoji-data.txt"))
+});
+
+/// The variation selector requesting emoji presentation.
+const EMOJI_PRESENTATION_SELECTOR: char = '\u{FE0F}';
+
+/// Elements whose content is never rewritten by [`apply_emoji_policy`].
+const VERBATIM_ELEMENTS: &[&str] = &["code", "pre", "script", "style"];
 
 /// Loads emoji sequences and their descriptive labels from a file.
 ///
@@ -68,63 +89,555 @@ pub fn load_emoji_sequences<P: AsRef<Path>>(
     let contents = fs::read_to_string(filepath)?;
 
     let mut map = HashMap::new();
+    for parsed in contents.lines().filter_map(parse_line) {
+        // Insert the emoji string and its label into the map
+        let _ = map.insert(parsed.emoji, parsed.label);
+    }
 
-    for raw_line in contents.lines() {
-        let line = raw_line.trim();
+    Ok(map)
+}
 
-        // Skip empty lines or comments
-        if line.is_empty() || line.starts_with('#') {
-            continue;
+/// A single data line split into its useful parts.
+#[derive(Debug)]
+struct ParsedLine {
+    emoji: String,
+    codepoints: Vec<u32>,
+    label: String,
+    name: String,
+    version: Option<String>,
+    text_presentation: bool,
+}
+
+/// Parses one line of an emoji data file.
+///
+/// Returns `None` for blank lines, comments and lines without a valid
+/// code point sequence.
+fn parse_line(raw_line: &str) -> Option<ParsedLine> {
+    let line = raw_line.trim();
+
+    // Skip empty lines or comments
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+
+    // Separate the data portion from the comment portion (if any)
+    let (data_part, comment_part) = match line.split_once('#') {
+        Some((before, after)) => (before.trim(), after.trim()),
+        None => (line, ""),
+    };
+
+    // Extract the label from the comment portion
+    let (before_paren, raw_label_after_paren) =
+        match comment_part.find(')') {
+            Some(close_paren_idx) => (
+                &comment_part[..close_paren_idx],
+                &comment_part[close_paren_idx + 1..],
+            ),
+            None => ("", comment_part),
+        };
+    let name = raw_label_after_paren
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    // Normalize the label
+    let label = name.to_lowercase().replace(' ', "-");
+
+    // The first token starting with `V` or `E` followed by a digit is the
+    // version that introduced the emoji, e.g. `V4.0` or `E0.6`.
+    let version = before_paren.split_whitespace().find_map(|token| {
+        let rest = token
+            .strip_prefix('V')
+            .or_else(|| token.strip_prefix('E'))?;
+        parse_version(rest).map(|_| rest.to_string())
+    });
+
+    // Extract the hexadecimal code points and the default style
+    let mut data_fields = data_part.split(';').map(str::trim);
+    let hex_seq = data_fields.next()?;
+    let text_presentation = data_fields.next() == Some("text");
+
+    // Convert hex code points into a UTF-8 emoji string
+    let codepoints: Vec<u32> = hex_seq
+        .split_whitespace()
+        .filter_map(|hex| u32::from_str_radix(hex, 16).ok())
+        .filter(|&codepoint| char::from_u32(codepoint).is_some())
+        .collect();
+    let emoji: String = codepoints
+        .iter()
+        .copied()
+        .flat_map(char::from_u32)
+        .collect();
+
+    if emoji.is_empty() {
+        return None; // Skip invalid sequences
+    }
+
+    Some(ParsedLine {
+        emoji,
+        codepoints,
+        label,
+        name,
+        version,
+        text_presentation,
+    })
+}
+
+/// Parses a `major.minor` version string into a comparable tuple.
+fn parse_version(version: &str) -> Option<(u32, u32)> {
+    let (major, minor) = match version.split_once('.') {
+        Some((major, minor)) => (major, minor),
+        None => (version, "0"),
+    };
+    Some((major.parse().ok()?, minor.parse().ok()?))
+}
+
+/// Normalizes a shortcode for lookup.
+///
+/// Surrounding colons are removed, the code is lowercased and spaces and
+/// underscores become dashes, so `:thumbs_up_sign:` and
+/// `thumbs-up-sign` are equivalent.
+fn normalize_shortcode(shortcode: &str) -> String {
+    shortcode
+        .trim()
+        .trim_matches(':')
+        .to_lowercase()
+        .replace([' ', '_'], "-")
+}
+
+/// An emoji and its metadata from a Unicode data file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmojiEntry {
+    /// The emoji as a UTF-8 string, e.g. `"⚡"`.
+    pub emoji: String,
+    /// The code points forming the emoji sequence.
+    pub codepoints: Vec<u32>,
+    /// The Unicode name, e.g. `"HIGH VOLTAGE SIGN"`.
+    pub name: String,
+    /// The normalized label used as shortcode, e.g. `"high-voltage-sign"`.
+    pub shortcode: String,
+    /// The Unicode or Emoji version that introduced the emoji, if known.
+    pub version: Option<String>,
+    /// Whether the emoji is displayed as text unless followed by the
+    /// emoji presentation selector `U+FE0F`, e.g. `©` or `™`.
+    pub text_presentation: bool,
+}
+
+/// A searchable collection of emoji loaded from Unicode data.
+///
+/// Entries can be looked up by emoji, code point, shortcode and name,
+/// and the catalog can be narrowed to the emoji available in a given
+/// Unicode version.
+///
+/// # Examples
+///
+/// ```
+/// use html_generator::emojis::EmojiCatalog;
+///
+/// let catalog = EmojiCatalog::parse(
+///     "# Version: 1.0\n26A1 ; emoji # V4.0 (⚡) HIGH VOLTAGE SIGN\n",
+/// );
+///
+/// assert_eq!(catalog.describe("⚡"), Some("HIGH VOLTAGE SIGN"));
+/// assert_eq!(
+///     catalog.by_shortcode(":high_voltage_sign:").map(|e| e.emoji.as_str()),
+///     Some("⚡")
+/// );
+/// assert!(catalog.up_to_version("3.0").is_empty());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct EmojiCatalog {
+    data_version: Option<String>,
+    entries: Vec<EmojiEntry>,
+    by_emoji: HashMap<String, usize>,
+    by_shortcode: HashMap<String, usize>,
+    by_name: HashMap<String, usize>,
+    max_sequence_len: usize,
+}
+
+impl EmojiCatalog {
+    /// Creates an empty catalog.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the catalog built from the emoji data bundled with the
+    /// crate.
+    pub fn bundled() -> &'static Self {
+        &BUNDLED_CATALOG
+    }
+
+    /// Builds a catalog from the contents of an emoji data file.
+    ///
+    /// Lines are parsed exactly as by [`load_emoji_sequences`]. A
+    /// `# Version:` header, if present, is recorded as the data version.
+    pub fn parse(contents: &str) -> Self {
+        let mut catalog = Self::new();
+        for line in contents.lines() {
+            if let Some(version) =
+                line.trim().strip_prefix("# Version:")
+            {
+                if catalog.data_version.is_none() {
+                    catalog.data_version =
+                        Some(version.trim().to_string());
+                }
+            } else if let Some(parsed) = parse_line(line) {
+                catalog.insert(EmojiEntry {
+                    emoji: parsed.emoji,
+                    codepoints: parsed.codepoints,
+                    name: parsed.name,
+                    shortcode: parsed.label,
+                    version: parsed.version,
+                    text_presentation: parsed.text_presentation,
+                });
+            }
         }
+        catalog
+    }
 
-        // Separate the data portion from the comment portion (if any)
-        let (data_part, comment_part) = match line.split_once('#') {
-            Some((before, after)) => (before.trim(), after.trim()),
-            None => (line, ""),
+    /// Loads a catalog from an emoji data file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read.
+    pub fn from_file<P: AsRef<Path>>(
+        filepath: P,
+    ) -> Result<Self, std::io::Error> {
+        Ok(Self::parse(&fs::read_to_string(filepath)?))
+    }
+
+    /// Adds an entry, replacing any existing entry for the same emoji.
+    pub fn insert(&mut self, entry: EmojiEntry) {
+        let index = match self.by_emoji.get(&entry.emoji) {
+            Some(&index) => {
+                let old = &self.entries[index];
+                let _ = self.by_shortcode.remove(&old.shortcode);
+                let _ = self.by_name.remove(&old.name.to_lowercase());
+                index
+            }
+            None => {
+                self.entries.push(entry.clone());
+                self.entries.len() - 1
+            }
         };
 
-        // Extract the label from the comment portion
-        let raw_label_after_paren =
-            if let Some(close_paren_idx) = comment_part.find(')') {
-                &comment_part[close_paren_idx + 1..]
-            } else {
-                comment_part
-            };
+        if !entry.shortcode.is_empty() {
+            let _ = self
+                .by_shortcode
+                .insert(normalize_shortcode(&entry.shortcode), index);
+        }
+        if !entry.name.is_empty() {
+            let _ =
+                self.by_name.insert(entry.name.to_lowercase(), index);
+        }
+        let _ = self.by_emoji.insert(entry.emoji.clone(), index);
+        self.max_sequence_len =
+            self.max_sequence_len.max(entry.emoji.chars().count());
+        self.entries[index] = entry;
+    }
 
-        // Normalize the label
-        let short_label = raw_label_after_paren
-            .trim()
-            .to_lowercase()
-            .split_whitespace()
-            .collect::<Vec<_>>()
-            .join("-");
-
-        // Parse data fields
-        let data_fields: Vec<&str> =
-            data_part.split(';').map(|s| s.trim()).collect();
-        if data_fields.is_empty() {
-            continue;
+    /// Registers an additional shortcode for an existing emoji.
+    ///
+    /// Returns `false` if the emoji is not in the catalog.
+    pub fn add_alias(&mut self, shortcode: &str, emoji: &str) -> bool {
+        match self.by_emoji.get(emoji) {
+            Some(&index) => {
+                let _ = self
+                    .by_shortcode
+                    .insert(normalize_shortcode(shortcode), index);
+                true
+            }
+            None => false,
         }
+    }
+
+    /// Returns the version declared in the data file header, if any.
+    pub fn data_version(&self) -> Option<&str> {
+        self.data_version.as_deref()
+    }
+
+    /// Returns the number of emoji in the catalog.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the catalog holds no emoji.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Iterates over the entries in file order.
+    pub fn entries(&self) -> impl Iterator<Item = &EmojiEntry> {
+        self.entries.iter()
+    }
+
+    /// Looks up an emoji by its UTF-8 string.
+    pub fn get(&self, emoji: &str) -> Option<&EmojiEntry> {
+        self.by_emoji.get(emoji).map(|&index| &self.entries[index])
+    }
+
+    /// Returns the Unicode name of an emoji (reverse lookup).
+    pub fn describe(&self, emoji: &str) -> Option<&str> {
+        self.get(emoji)
+            .map(|entry| entry.name.as_str())
+            .filter(|name| !name.is_empty())
+    }
+
+    /// Looks up a single-code-point emoji.
+    pub fn by_codepoint(&self, codepoint: u32) -> Option<&EmojiEntry> {
+        self.by_codepoints(&[codepoint])
+    }
 
-        // Extract the hexadecimal code points
-        let hex_seq = data_fields[0];
+    /// Looks up an emoji by its full code point sequence.
+    pub fn by_codepoints(
+        &self,
+        codepoints: &[u32],
+    ) -> Option<&EmojiEntry> {
+        let emoji = codepoints
+            .iter()
+            .map(|&codepoint| char::from_u32(codepoint))
+            .collect::<Option<String>>()?;
+        self.get(&emoji)
+    }
 
-        // Convert hex code points into a UTF-8 emoji string
-        let emoji_string: String = hex_seq
-            .split_whitespace()
-            .filter_map(|hex| u32::from_str_radix(hex, 16).ok())
-            .flat_map(char::from_u32)
-            .collect();
+    /// Looks up an emoji by shortcode, with or without colons.
+    pub fn by_shortcode(&self, shortcode: &str) -> Option<&EmojiEntry> {
+        self.by_shortcode
+            .get(&normalize_shortcode(shortcode))
+            .map(|&index| &self.entries[index])
+    }
 
-        if emoji_string.is_empty() {
-            continue; // Skip invalid sequences
+    /// Looks up an emoji by its Unicode name, ignoring case.
+    pub fn by_name(&self, name: &str) -> Option<&EmojiEntry> {
+        self.by_name
+            .get(&name.trim().to_lowercase())
+            .map(|&index| &self.entries[index])
+    }
+
+    /// Returns a catalog containing only the emoji introduced in
+    /// `version` or earlier.
+    ///
+    /// Entries without a known version are kept. An unparseable
+    /// `version` keeps every entry.
+    pub fn up_to_version(&self, version: &str) -> Self {
+        let limit = match parse_version(version) {
+            Some(limit) => limit,
+            None => return self.clone(),
+        };
+
+        let mut catalog = Self {
+            data_version: self.data_version.clone(),
+            ..Self::default()
+        };
+        for entry in &self.entries {
+            let introduced =
+                entry.version.as_deref().and_then(parse_version);
+            if introduced.map_or(true, |introduced| introduced <= limit)
+            {
+                catalog.insert(entry.clone());
+            }
         }
+        catalog
+    }
 
-        // Insert the emoji string and its label into the map
-        let _ = map.insert(emoji_string, short_label);
+    /// Finds the emoji starting at `chars[0]`, preferring the longest
+    /// sequence.
+    ///
+    /// Returns the entry and the number of characters it spans,
+    /// including a trailing emoji presentation selector. Emoji with text
+    /// presentation only match when followed by that selector.
+    fn match_at(&self, chars: &[char]) -> Option<(&EmojiEntry, usize)> {
+        let longest = self.max_sequence_len.min(chars.len());
+        (1..=longest).rev().find_map(|len| {
+            let candidate: String = chars[..len].iter().collect();
+            let entry = self.get(&candidate)?;
+            let selected =
+                chars.get(len) == Some(&EMOJI_PRESENTATION_SELECTOR);
+            if entry.text_presentation
+                && entry.codepoints.len() == 1
+                && !selected
+            {
+                return None;
+            }
+            Some((entry, if selected { len + 1 } else { len }))
+        })
     }
 
-    Ok(map)
+    /// Converts the catalog into the emoji → label map returned by
+    /// [`load_emoji_sequences`].
+    pub fn to_sequence_map(&self) -> HashMap<String, String> {
+        self.entries
+            .iter()
+            .map(|entry| (entry.emoji.clone(), entry.shortcode.clone()))
+            .collect()
+    }
+}
+
+/// How emoji in generated HTML are treated.
+///
+/// Set [`HtmlConfig::emoji_policy`](crate::HtmlConfig::emoji_policy) to
+/// strip emoji from documents that need a professional register.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum EmojiPolicy {
+    /// Leave emoji untouched.
+    Keep,
+    /// Replace each emoji with its name in brackets, e.g. `⚡` becomes
+    /// `[high voltage sign]`.
+    StripWithLabel,
+    /// Remove emoji entirely.
+    Remove,
+}
+
+impl Default for EmojiPolicy {
+    fn default() -> Self {
+        EmojiPolicy::Keep
+    }
+}
+
+impl EmojiPolicy {
+    /// Returns the policy name as used in configuration files.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EmojiPolicy::Keep => "keep",
+            EmojiPolicy::StripWithLabel => "strip_with_label",
+            EmojiPolicy::Remove => "remove",
+        }
+    }
+}
+
+impl fmt::Display for EmojiPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for EmojiPolicy {
+    type Err = HtmlError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "keep" => Ok(EmojiPolicy::Keep),
+            "strip_with_label" => Ok(EmojiPolicy::StripWithLabel),
+            "remove" => Ok(EmojiPolicy::Remove),
+            _ => Err(HtmlError::InvalidInput(format!(
+                "Unknown emoji policy: {}",
+                value
+            ))),
+        }
+    }
+}
+
+/// Applies an [`EmojiPolicy`] to generated HTML.
+///
+/// Only text content is rewritten: tags, attribute values and the
+/// content of `<code>`, `<pre>`, `<script>` and `<style>` elements are
+/// copied unchanged. Emoji with text presentation, such as `©` or `™`,
+/// are only treated as emoji when followed by `U+FE0F`.
+///
+/// # Arguments
+///
+/// * `html` - The HTML to transform
+/// * `policy` - The policy to apply
+/// * `catalog` - The emoji to recognise, usually [`EmojiCatalog::bundled`]
+///
+/// # Returns
+///
+/// The transformed HTML.
+///
+/// # Examples
+///
+/// ```
+/// use html_generator::emojis::{apply_emoji_policy, EmojiCatalog, EmojiPolicy};
+///
+/// let html = "<p>Fast ⚡ <code>⚡</code></p>";
+/// let stripped = apply_emoji_policy(
+///     html,
+///     EmojiPolicy::StripWithLabel,
+///     EmojiCatalog::bundled(),
+/// );
+/// assert_eq!(stripped, "<p>Fast [high voltage sign] <code>⚡</code></p>");
+/// ```
+pub fn apply_emoji_policy(
+    html: &str,
+    policy: EmojiPolicy,
+    catalog: &EmojiCatalog,
+) -> String {
+    if policy == EmojiPolicy::Keep || catalog.is_empty() {
+        return html.to_string();
+    }
+
+    let mut output = String::with_capacity(html.len());
+    let mut verbatim_depth = 0usize;
+    let mut rest = html;
+    while !rest.is_empty() {
+        if rest.starts_with('<') {
+            let end = rest.find('>').map_or(rest.len(), |idx| idx + 1);
+            let tag = &rest[..end];
+            let closing = tag.starts_with("</");
+            let name: String = tag
+                .trim_start_matches(['<', '/'])
+                .chars()
+                .take_while(char::is_ascii_alphanumeric)
+                .collect::<String>()
+                .to_ascii_lowercase();
+            if VERBATIM_ELEMENTS.contains(&name.as_str())
+                && !tag.ends_with("/>")
+            {
+                if closing {
+                    verbatim_depth = verbatim_depth.saturating_sub(1);
+                } else {
+                    verbatim_depth += 1;
+                }
+            }
+            output.push_str(tag);
+            rest = &rest[end..];
+            continue;
+        }
+
+        let end = rest.find('<').unwrap_or(rest.len());
+        let text = &rest[..end];
+        if verbatim_depth > 0 {
+            output.push_str(text);
+        } else {
+            replace_emoji(text, policy, catalog, &mut output);
+        }
+        rest = &rest[end..];
+    }
+    output
+}
+
+/// Rewrites the emoji in a run of text according to `policy`.
+fn replace_emoji(
+    text: &str,
+    policy: EmojiPolicy,
+    catalog: &EmojiCatalog,
+    output: &mut String,
+) {
+    let chars: Vec<char> = text.chars().collect();
+    let mut index = 0;
+    while index < chars.len() {
+        match catalog.match_at(&chars[index..]) {
+            Some((entry, len)) => {
+                if policy == EmojiPolicy::StripWithLabel {
+                    let label = if entry.name.is_empty() {
+                        entry.shortcode.replace('-', " ")
+                    } else {
+                        entry.name.to_lowercase()
+                    };
+                    if !label.is_empty() {
+                        output.push('[');
+                        output.push_str(&label);
+                        output.push(']');
+                    }
+                }
+                index += len;
+            }
+            None => {
+                output.push(chars[index]);
+                index += 1;
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -416,6 +929,196 @@ fn test_load_emoji_sequences_empty_fields() {
         assert_eq!(result, expected);
     }
 
+    mod catalog_tests {
+        use super::*;
+
+        const DATA: &str = r#"
+# Version: 1.0
+26A1 ; emoji ; L1 ; none ; a j # V4.0 (⚡) HIGH VOLTAGE SIGN
+1F600 ; emoji ; L1 ; none ; j # V6.1 (😀) GRINNING FACE
+1F1E6 1F1FA ; emoji ; L2 ; none ; x # V6.0 (🇦🇺) FLAG FOR AUSTRALIA
+1F602 ; emoji ; L1 ; none ; j
+"#;
+
+        #[test]
+        fn test_parse_metadata() {
+            let catalog = EmojiCatalog::parse(DATA);
+            assert_eq!(catalog.len(), 4);
+            assert_eq!(catalog.data_version(), Some("1.0"));
+
+            let entry = catalog.get("⚡").unwrap();
+            assert_eq!(entry.codepoints, vec![0x26A1]);
+            assert_eq!(entry.name, "HIGH VOLTAGE SIGN");
+            assert_eq!(entry.shortcode, "high-voltage-sign");
+            assert_eq!(entry.version.as_deref(), Some("4.0"));
+        }
+
+        #[test]
+        fn test_lookups() {
+            let catalog = EmojiCatalog::parse(DATA);
+            let emoji_of = |entry: Option<&EmojiEntry>| {
+                entry.map(|entry| entry.emoji.clone())
+            };
+
+            assert_eq!(
+                emoji_of(catalog.by_codepoint(0x1F600)),
+                Some("😀".to_string())
+            );
+            assert_eq!(
+                emoji_of(catalog.by_codepoints(&[0x1F1E6, 0x1F1FA])),
+                Some("🇦🇺".to_string())
+            );
+            assert_eq!(
+                emoji_of(catalog.by_shortcode(":grinning_face:")),
+                Some("😀".to_string())
+            );
+            assert_eq!(
+                emoji_of(catalog.by_name("grinning face")),
+                Some("😀".to_string())
+            );
+            assert!(catalog.by_codepoint(0xD800).is_none());
+            assert!(catalog.by_shortcode("").is_none());
+        }
+
+        #[test]
+        fn test_describe() {
+            let catalog = EmojiCatalog::parse(DATA);
+            assert_eq!(
+                catalog.describe("⚡"),
+                Some("HIGH VOLTAGE SIGN")
+            );
+            assert_eq!(catalog.describe("😂"), None);
+            assert_eq!(catalog.describe("x"), None);
+        }
+
+        #[test]
+        fn test_aliases_and_replacement() {
+            let mut catalog = EmojiCatalog::parse(DATA);
+            assert!(catalog.add_alias(":zap:", "⚡"));
+            assert!(!catalog.add_alias("missing", "🦀"));
+            assert_eq!(
+                catalog.by_shortcode("zap").map(|e| e.emoji.as_str()),
+                Some("⚡")
+            );
+
+            let mut entry = catalog.get("😀").unwrap().clone();
+            entry.name = "SMILE".to_string();
+            entry.shortcode = "smile".to_string();
+            catalog.insert(entry);
+            assert_eq!(catalog.len(), 4);
+            assert!(catalog.by_shortcode("grinning-face").is_none());
+            assert!(catalog.by_name("smile").is_some());
+        }
+
+        #[test]
+        fn test_up_to_version() {
+            let catalog = EmojiCatalog::parse(DATA);
+            let selected = catalog.up_to_version("6.0");
+
+            assert!(selected.get("⚡").is_some());
+            assert!(selected.get("🇦🇺").is_some());
+            assert!(selected.get("😀").is_none());
+            // Entries without a version are kept.
+            assert!(selected.get("😂").is_some());
+            assert_eq!(catalog.up_to_version("latest").len(), 4);
+        }
+
+        #[test]
+        fn test_matches_sequence_loader() {
+            let file = create_temp_file(DATA);
+            let catalog = EmojiCatalog::from_file(file.path()).unwrap();
+            assert_eq!(
+                catalog.to_sequence_map(),
+                load_emoji_sequences(file.path()).unwrap()
+            );
+        }
+
+        #[test]
+        fn test_bundled_data() {
+            let catalog =
+                EmojiCatalog::from_file("data/emoji-data.txt").unwrap();
+            assert!(!catalog.is_empty());
+            assert_eq!(catalog.data_version(), Some("1.0"));
+            assert_eq!(catalog.describe("©"), Some("COPYRIGHT SIGN"));
+        }
+    }
+
+    mod policy_tests {
+        use super::*;
+
+        fn apply(html: &str, policy: EmojiPolicy) -> String {
+            apply_emoji_policy(html, policy, EmojiCatalog::bundled())
+        }
+
+        #[test]
+        fn test_keep_is_identity() {
+            let html = "<p>Hi 😀</p>";
+            assert_eq!(apply(html, EmojiPolicy::Keep), html);
+        }
+
+        #[test]
+        fn test_strip_with_label() {
+            assert_eq!(
+                apply("<p>Hi 😀!</p>", EmojiPolicy::StripWithLabel),
+                "<p>Hi [grinning face]!</p>"
+            );
+        }
+
+        #[test]
+        fn test_remove() {
+            assert_eq!(
+                apply("<h1>⚡ Fast</h1>", EmojiPolicy::Remove),
+                "<h1> Fast</h1>"
+            );
+        }
+
+        #[test]
+        fn test_code_and_attributes_are_untouched() {
+            let html = concat!(
+                "<img alt=\"😀\"><pre><code>let x = \"😀\";</code></pre>",
+                "<p>😀</p>"
+            );
+            assert_eq!(
+                apply(html, EmojiPolicy::Remove),
+                concat!(
+                    "<img alt=\"😀\"><pre><code>let x = \"😀\";</code></pre>",
+                    "<p></p>"
+                )
+            );
+        }
+
+        #[test]
+        fn test_text_presentation_requires_selector() {
+            assert_eq!(
+                apply("<p>© 2025 ©\u{FE0F}</p>", EmojiPolicy::Remove),
+                "<p>© 2025 </p>"
+            );
+        }
+
+        #[test]
+        fn test_longest_sequence_wins() {
+            assert_eq!(
+                apply("<p>🇦🇺</p>", EmojiPolicy::StripWithLabel),
+                "<p>[flag for australia]</p>"
+            );
+        }
+
+        #[test]
+        fn test_policy_names_round_trip() {
+            for policy in [
+                EmojiPolicy::Keep,
+                EmojiPolicy::StripWithLabel,
+                EmojiPolicy::Remove,
+            ] {
+                assert_eq!(
+                    policy.as_str().parse::<EmojiPolicy>().unwrap(),
+                    policy
+                );
+            }
+            assert!("loud".parse::<EmojiPolicy>().is_err());
+        }
+    }
+
     #[test]
     fn test_load_emoji_sequences_whitespace_variations() {
         let test_data = r#"
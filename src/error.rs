@@ -160,6 +160,38 @@ pub enum HtmlError {
     #[error("Validation error: {0}")]
     ValidationError(String),
 
+    /// Error indicating that a document exceeded a resource limit.
+    ///
+    /// This variant is used to reject pathological input, see
+    /// [`ResourceLimits`](crate::limits::ResourceLimits).
+    #[error(
+        "Resource limit exceeded: {kind} is {actual}, limit is {limit}"
+    )]
+    LimitExceeded {
+        /// The limit that was exceeded
+        kind: LimitKind,
+        /// The measured value
+        actual: usize,
+        /// The configured limit
+        limit: usize,
+    },
+
+    /// Error indicating that two sources would write the same output.
+    ///
+    /// This variant is used by [`convert_directory`](crate::batch::convert_directory)
+    /// when file names, slugs or permalinks resolve to the same path.
+    #[error(
+        "Output '{}' is already written by '{}'",
+        output.display(),
+        first.display()
+    )]
+    OutputConflict {
+        /// The output path, relative to the output directory
+        output: std::path::PathBuf,
+        /// The source that claimed the output first
+        first: std::path::PathBuf,
+    },
+
     /// A catch-all error for unexpected failures.
     ///
     /// This variant is used for errors that do not fit into other categories.
@@ -184,6 +216,27 @@ pub enum SeoErrorKind {
     Other,
 }
 
+/// Resource limits that can be exceeded by a document
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum LimitKind {
+    /// Nesting depth of blockquotes and lists
+    NestingDepth,
+    /// Number of table cells
+    TableCells,
+    /// Size of the generated HTML
+    OutputSize,
+}
+
+impl std::fmt::Display for LimitKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LimitKind::NestingDepth => write!(f, "nesting depth"),
+            LimitKind::TableCells => write!(f, "table cells"),
+            LimitKind::OutputSize => write!(f, "output size"),
+        }
+    }
+}
+
 /// Types of accessibility-related errors
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum ErrorKind {
@@ -297,6 +350,53 @@ pub fn markdown_conversion(
             source,
         }
     }
+
+    /// Returns a stable, machine-readable code for this error.
+    ///
+    /// Codes do not change between releases and are intended for
+    /// reports, logs and tooling that must match on error categories
+    /// without parsing messages.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use html_generator::HtmlError;
+    ///
+    /// let error = HtmlError::InputTooLarge(42);
+    /// assert_eq!(error.code(), "input_too_large");
+    /// ```
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::RegexCompilationError(_) => "regex_compilation",
+            Self::FrontMatterExtractionError(_) => {
+                "front_matter_extraction"
+            }
+            Self::HeaderFormattingError(_) => "header_formatting",
+            Self::SelectorParseError(_, _) => "selector_parse",
+            Self::MinificationError(_) | Self::Minification { .. } => {
+                "minification"
+            }
+            Self::MarkdownConversion { .. } => "markdown_conversion",
+            Self::Seo { .. } => "seo",
+            Self::Accessibility { .. } => "accessibility",
+            Self::MissingHtmlElement(_) => "missing_html_element",
+            Self::InvalidStructuredData(_) => "invalid_structured_data",
+            Self::Io(_) => "io",
+            Self::InvalidInput(_) => "invalid_input",
+            Self::InvalidFrontMatterFormat(_) => {
+                "invalid_front_matter_format"
+            }
+            Self::InputTooLarge(_) => "input_too_large",
+            Self::InvalidHeaderFormat(_) => "invalid_header_format",
+            Self::Utf8ConversionError(_) => "utf8_conversion",
+            Self::ParsingError(_) => "parsing",
+            Self::TemplateRendering { .. } => "template_rendering",
+            Self::ValidationError(_) => "validation",
+            Self::LimitExceeded { .. } => "limit_exceeded",
+            Self::OutputConflict { .. } => "output_conflict",
+            Self::UnexpectedError(_) => "unexpected",
+        }
+    }
 }
 
 /// Type alias for a result using the `HtmlError` error type.
@@ -570,6 +670,26 @@ fn test_template_rendering_error() {
                 .to_string()
                 .contains("Template rendering failed"));
         }
+
+        #[test]
+        fn test_error_codes() {
+            assert_eq!(
+                HtmlError::InvalidInput("x".to_string()).code(),
+                "invalid_input"
+            );
+            assert_eq!(
+                HtmlError::Io(io::Error::new(
+                    io::ErrorKind::NotFound,
+                    "missing"
+                ))
+                .code(),
+                "io"
+            );
+            assert_eq!(
+                HtmlError::markdown_conversion("failed", None).code(),
+                "markdown_conversion"
+            );
+        }
     }
 
     // Miscellaneous Error Tests
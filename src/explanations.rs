@@ -0,0 +1,328 @@
+// Copyright © 2025 HTML Generator. All rights reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Long-form explanations of accessibility rules.
+//!
+//! An [`Issue`] carries a one-line message and suggestion. Report
+//! renderers and editor plugins usually also want to show why a rule
+//! matters and how to fix it. This module bundles that text for every
+//! issue code, keyed by
+//! [`IssueType::code`](crate::accessibility::IssueType::code), and
+//! lets applications replace or extend it through an
+//! [`ExplanationCatalog`].
+//!
+//! # Examples
+//!
+//! ```
+//! use html_generator::accessibility::IssueType;
+//! use html_generator::explanations::{ExplanationCatalog, RuleExplanation};
+//!
+//! let bundled = ExplanationCatalog::bundled();
+//! let rule = bundled.get(IssueType::MissingAltText.code()).unwrap();
+//! assert_eq!(rule.title, "Images need alternative text");
+//!
+//! let mut catalog = ExplanationCatalog::new();
+//! catalog.insert(RuleExplanation::new(
+//!     "missing_alt_text",
+//!     "Describe every image",
+//!     "Our style guide requires alt text on all images.",
+//!     "Write a short description; see the style guide for examples.",
+//! ));
+//! assert_eq!(
+//!     catalog.get("missing_alt_text").unwrap().title,
+//!     "Describe every image"
+//! );
+//! ```
+
+use crate::accessibility::Issue;
+use once_cell::sync::Lazy;
+use std::{collections::HashMap, fmt};
+
+/// Bundled explanations: code, title, why it matters, how to fix.
+const BUNDLED_EXPLANATIONS: &[(&str, &str, &str, &str)] = &[
+    (
+        "missing_alt_text",
+        "Images need alternative text",
+        "Screen readers announce images by their alt attribute. Without \
+         it they read the file name or skip the image, so blind users \
+         miss the information it conveys (WCAG 1.1.1).",
+        "Add an alt attribute that describes the purpose of the image. \
+         Use alt=\"\" for purely decorative images so they are skipped.",
+    ),
+    (
+        "heading_structure",
+        "Headings should not skip levels",
+        "Screen reader users navigate by headings and infer the outline \
+         of the page from their levels. A jump from h1 to h3 suggests a \
+         missing section and makes the structure hard to follow \
+         (WCAG 1.3.1, 2.4.6).",
+        "Use heading levels in order, increasing by one at a time. \
+         Change the appearance of a heading with CSS rather than by \
+         picking another level.",
+    ),
+    (
+        "missing_labels",
+        "Form controls need labels",
+        "A control without a label is announced only by its type, such \
+         as \"edit text\", so users cannot tell what to enter. Labels \
+         also enlarge the click target (WCAG 1.3.1, 4.1.2).",
+        "Associate a <label for=\"...\"> with the control, wrap the \
+         control in its label, or add aria-label when no visible label \
+         is possible.",
+    ),
+    (
+        "invalid_aria",
+        "ARIA roles and attributes must be valid",
+        "Assistive technologies rely on ARIA to announce custom widgets. \
+         An unknown role, a role that does not fit the element or a \
+         missing required property makes the widget announce wrongly or \
+         not at all (WCAG 4.1.2).",
+        "Prefer native elements such as <button> over ARIA roles. When a \
+         role is needed, use one allowed on the element and add every \
+         property it requires.",
+    ),
+    (
+        "color_contrast",
+        "Text needs sufficient contrast",
+        "Text with low contrast against its background is hard to read \
+         for users with low vision or color blindness and in bright \
+         light (WCAG 1.4.3, 1.4.6).",
+        "Use a contrast ratio of at least 4.5:1 for normal text and 3:1 \
+         for large text, or 7:1 and 4.5:1 for level AAA.",
+    ),
+    (
+        "keyboard_navigation",
+        "Everything must work with a keyboard",
+        "Many users cannot use a mouse. Elements removed from the tab \
+         order or only reacting to clicks cannot be reached or operated \
+         (WCAG 2.1.1).",
+        "Use native interactive elements, avoid negative tabindex on \
+         controls, and pair click handlers with keyboard handlers.",
+    ),
+    (
+        "language_declaration",
+        "Declare the language of the page",
+        "Screen readers choose pronunciation rules from the lang \
+         attribute, and browsers use it for hyphenation and \
+         translation. A missing or invalid language makes text read \
+         with the wrong voice (WCAG 3.1.1, 3.1.2).",
+        "Add a valid BCP 47 code to the html element, such as \
+         lang=\"en\", and mark passages in other languages with their \
+         own lang attribute.",
+    ),
+    (
+        "duplicate_headings",
+        "Headings should be distinct",
+        "Screen reader users jump between headings by their text, and \
+         search engines use headings to understand and link to the \
+         sections of a page. Two sections called \"Overview\" cannot be \
+         told apart in a heading list (WCAG 2.4.6).",
+        "Make each heading name the topic of its own section, for \
+         example \"Installing on Linux\" and \"Installing on macOS\" \
+         rather than two \"Installation\" headings.",
+    ),
+    (
+        "link_purpose",
+        "Link text should describe its target",
+        "Screen reader users often list the links of a page out of \
+         context. \"Click here\", \"read more\" or a long URL do not say \
+         where a link goes, and search engines use link text to \
+         describe the target page (WCAG 2.4.4).",
+        "Write link text that names the destination, such as \"Read the \
+         release notes\", and avoid pasting bare URLs as link text.",
+    ),
+    (
+        "mixed_direction",
+        "Runs in the other text direction should be isolated",
+        "In Arabic, Hebrew or Persian text, an embedded English name or \
+         number can pull the surrounding punctuation and words into the \
+         wrong order, so the sentence reads differently than written \
+         (WCAG 1.3.2).",
+        "Wrap the embedded run in <bdi>, or in an element with a dir \
+         attribute, and set dir=\"auto\" or dir=\"rtl\" on the block.",
+    ),
+    (
+        "inaccessible_math",
+        "Formulas need a readable representation",
+        "Screen readers cannot read a formula drawn as an image, and read \
+         TeX source such as \\frac{a}{b} symbol by symbol, so blind users \
+         cannot follow the mathematics (WCAG 1.1.1 and 1.3.1).",
+        "Render formulas to MathML, which KaTeX and MathJax include for \
+         assistive technology, or give the formula an aria-label that \
+         reads the expression, such as \"a over b\".",
+    ),
+];
+
+static BUNDLED_CATALOG: Lazy<ExplanationCatalog> = Lazy::new(|| {
+    let mut catalog = ExplanationCatalog::new();
+    for (code, title, why, how_to_fix) in BUNDLED_EXPLANATIONS {
+        catalog.insert(RuleExplanation::new(
+            *code,
+            *title,
+            *why,
+            *how_to_fix,
+        ));
+    }
+    catalog
+});
+
+/// The explanation of one rule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleExplanation {
+    /// The issue code, e.g. `missing_alt_text`.
+    pub code: String,
+    /// Short title of the rule.
+    pub title: String,
+    /// Why the rule matters.
+    pub why: String,
+    /// How to fix violations.
+    pub how_to_fix: String,
+}
+
+impl RuleExplanation {
+    /// Creates an explanation.
+    ///
+    /// # Arguments
+    ///
+    /// * `code` - The issue code
+    /// * `title` - Short title of the rule
+    /// * `why` - Why the rule matters
+    /// * `how_to_fix` - How to fix violations
+    pub fn new(
+        code: impl Into<String>,
+        title: impl Into<String>,
+        why: impl Into<String>,
+        how_to_fix: impl Into<String>,
+    ) -> Self {
+        Self {
+            code: code.into(),
+            title: title.into(),
+            why: why.into(),
+            how_to_fix: how_to_fix.into(),
+        }
+    }
+}
+
+impl fmt::Display for RuleExplanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}\n\n{}\n\nHow to fix: {}",
+            self.title, self.why, self.how_to_fix
+        )
+    }
+}
+
+/// Explanations keyed by issue code.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExplanationCatalog {
+    entries: HashMap<String, RuleExplanation>,
+}
+
+impl ExplanationCatalog {
+    /// Creates an empty catalog.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the catalog bundled with the crate, which explains
+    /// every [`IssueType`](crate::accessibility::IssueType).
+    pub fn bundled() -> &'static Self {
+        &BUNDLED_CATALOG
+    }
+
+    /// Adds an explanation, replacing any with the same code.
+    pub fn insert(&mut self, explanation: RuleExplanation) {
+        let _ =
+            self.entries.insert(explanation.code.clone(), explanation);
+    }
+
+    /// Returns the explanation for an issue code.
+    pub fn get(&self, code: &str) -> Option<&RuleExplanation> {
+        self.entries.get(code)
+    }
+
+    /// Returns the number of explanations.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the catalog is empty.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Explains an issue.
+    ///
+    /// Codes missing from this catalog fall back to the bundled one, so
+    /// a custom catalog only needs the rules it changes. If neither has
+    /// an entry, the issue message and suggestion are used.
+    ///
+    /// # Arguments
+    ///
+    /// * `issue` - The issue to explain
+    pub fn explain(&self, issue: &Issue) -> String {
+        let code = issue.issue_type.code();
+        match self.get(code).or_else(|| Self::bundled().get(code)) {
+            Some(explanation) => explanation.to_string(),
+            None => match &issue.suggestion {
+                Some(suggestion) => format!(
+                    "{}\n\nHow to fix: {}",
+                    issue.message, suggestion
+                ),
+                None => issue.message.clone(),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::accessibility::{IssueType, Severity};
+
+    fn issue(issue_type: IssueType) -> Issue {
+        Issue {
+            issue_type,
+            severity: issue_type.default_severity(),
+            message: "Message".to_string(),
+            guideline: None,
+            element: None,
+            suggestion: Some("Suggestion".to_string()),
+            location: None,
+        }
+    }
+
+    #[test]
+    fn test_bundled_catalog_covers_every_issue_type() {
+        let catalog = ExplanationCatalog::bundled();
+        assert_eq!(catalog.len(), IssueType::ALL.len());
+        for issue_type in IssueType::ALL {
+            let explanation = catalog.get(issue_type.code()).unwrap();
+            assert!(!explanation.why.is_empty());
+            assert!(!explanation.how_to_fix.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_custom_entries_override_bundled_text() {
+        let mut catalog = ExplanationCatalog::new();
+        catalog.insert(RuleExplanation::new(
+            "heading_structure",
+            "Title",
+            "Why",
+            "Fix",
+        ));
+        assert_eq!(
+            catalog.explain(&issue(IssueType::HeadingStructure)),
+            "Title\n\nWhy\n\nHow to fix: Fix"
+        );
+        assert!(catalog
+            .explain(&issue(IssueType::ColorContrast))
+            .starts_with("Text needs sufficient contrast\n\n"));
+        assert_eq!(
+            issue(IssueType::MissingAltText).severity,
+            Severity::Error
+        );
+    }
+}
@@ -0,0 +1,234 @@
+// Copyright © 2025 HTML Generator. All rights reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Footnote markup for assistive technologies and popover display.
+//!
+//! Footnotes are rendered by the footnotes extension of
+//! [`MarkdownExtensions`](crate::generator::MarkdownExtensions).
+//! [`add_footnote_roles`] gives references, back-links and the footnote
+//! section their DPUB-ARIA roles (`doc-noteref`, `doc-backlink` and
+//! `doc-endnotes`), so screen readers announce them as footnotes rather
+//! than as plain links, and labels every back-link. It runs during
+//! conversion when [`HtmlConfig::add_aria_attributes`] is set, and as
+//! part of [`add_aria_attributes`].
+//!
+//! [`HtmlConfig::add_aria_attributes`]: crate::HtmlConfig::add_aria_attributes
+//! [`add_aria_attributes`]: crate::accessibility::add_aria_attributes
+//!
+//! Footnotes are rendered as a numbered list at the end of the
+//! document, which means a round trip to the bottom of the page for
+//! every reference. [`add_footnote_popovers`] prepares the markup for
+//! showing a footnote next to its reference instead: the content of
+//! each footnote is wrapped in an element with its own `id`, and every
+//! reference points to it with `aria-details`, so scripts or CSS anchor
+//! positioning can display it in a popover and assistive technologies
+//! announce the relationship. The end-of-document list is kept as it
+//! is, so pages still work without scripts.
+//!
+//! # Examples
+//!
+//! ```
+//! use html_generator::footnotes::add_footnote_popovers;
+//!
+//! let html = add_footnote_popovers(concat!(
+//!     r##"<p>Text<sup class="footnote-ref"><a href="#fn-1" id="fnref-1" data-footnote-ref>1</a></sup></p>"##,
+//!     r#"<section class="footnotes" data-footnotes><ol><li id="fn-1"><p>Note</p></li></ol></section>"#,
+//! ));
+//! assert!(html.contains(r#"data-footnote-ref aria-details="fn-1-content""#));
+//! assert!(html.contains(
+//!     r#"<li id="fn-1"><div class="footnote-content" id="fn-1-content"><p>Note</p></div></li>"#
+//! ));
+//! ```
+//!
+//! ```
+//! use html_generator::footnotes::add_footnote_roles;
+//!
+//! let html = add_footnote_roles(
+//!     r##"<a href="#fn-1" id="fnref-1" data-footnote-ref>1</a> <a href="#fnref-1" class="footnote-backref">↩</a>"##,
+//! );
+//! assert_eq!(
+//!     html,
+//!     r##"<a href="#fn-1" id="fnref-1" data-footnote-ref role="doc-noteref">1</a> <a href="#fnref-1" class="footnote-backref" role="doc-backlink" aria-label="Back to reference">↩</a>"##
+//! );
+//! ```
+
+use crate::patterns::footnotes::{
+    FOOTNOTE_BACKREF_REGEX, FOOTNOTE_ITEM_REGEX, FOOTNOTE_REF_REGEX,
+    FOOTNOTE_SECTION_REGEX,
+};
+use regex::Captures;
+use std::collections::HashSet;
+
+/// Suffix of the `id` given to the content of a footnote.
+const CONTENT_SUFFIX: &str = "-content";
+
+/// Prepares footnotes for popover display.
+///
+/// Wraps the content of each footnote list item in
+/// `<div class="footnote-content" id="<footnote id>-content">` and adds
+/// `aria-details` pointing to it on every reference. References to
+/// footnotes missing from the document, and footnotes already wrapped,
+/// are left alone.
+///
+/// # Arguments
+///
+/// * `html` - HTML rendered with the footnotes extension
+///
+/// # Returns
+///
+/// The HTML with popover markup.
+pub fn add_footnote_popovers(html: &str) -> String {
+    let mut footnotes = HashSet::new();
+    let html = FOOTNOTE_ITEM_REGEX.replace_all(html, |caps: &Captures<'_>| {
+        let id = &caps[1];
+        let _ = footnotes.insert(id.to_string());
+        let content = caps[2].trim();
+        if content.starts_with(r#"<div class="footnote-content""#) {
+            return caps[0].to_string();
+        }
+        format!(
+            r#"<li id="{id}"><div class="footnote-content" id="{id}{suffix}">{content}</div></li>"#,
+            id = id,
+            suffix = CONTENT_SUFFIX,
+            content = content
+        )
+    });
+
+    FOOTNOTE_REF_REGEX
+        .replace_all(&html, |caps: &Captures<'_>| {
+            if !footnotes.contains(&caps[1])
+                || caps[2].contains("aria-details=")
+            {
+                return caps[0].to_string();
+            }
+            format!(
+                r##"<a href="#{}"{} aria-details="{}{}">"##,
+                &caps[1], &caps[2], &caps[1], CONTENT_SUFFIX
+            )
+        })
+        .into_owned()
+}
+
+/// Adds DPUB-ARIA roles to footnote markup.
+///
+/// References get `role="doc-noteref"`, back-links
+/// `role="doc-backlink"` and, unless already labelled,
+/// `aria-label="Back to reference"`, and the footnote section
+/// `role="doc-endnotes"`. Elements that already have a role keep it.
+///
+/// # Arguments
+///
+/// * `html` - HTML rendered with the footnotes extension
+///
+/// # Returns
+///
+/// The HTML with footnote roles.
+pub fn add_footnote_roles(html: &str) -> String {
+    let html =
+        FOOTNOTE_REF_REGEX.replace_all(html, |caps: &Captures<'_>| {
+            with_attribute(&caps[0], r#" role="doc-noteref""#)
+        });
+    let html = FOOTNOTE_BACKREF_REGEX.replace_all(
+        &html,
+        |caps: &Captures<'_>| {
+            let tag =
+                with_attribute(&caps[0], r#" role="doc-backlink""#);
+            if tag.contains(" aria-label=") {
+                tag
+            } else {
+                with_attribute(
+                    &tag,
+                    r#" aria-label="Back to reference""#,
+                )
+            }
+        },
+    );
+    FOOTNOTE_SECTION_REGEX
+        .replace_all(&html, |caps: &Captures<'_>| {
+            with_attribute(&caps[0], r#" role="doc-endnotes""#)
+        })
+        .into_owned()
+}
+
+/// Appends `attribute` to the start tag `tag` unless it already has
+/// an attribute of that name.
+fn with_attribute(tag: &str, attribute: &str) -> String {
+    let name =
+        attribute.trim_start().split('=').next().unwrap_or_default();
+    if tag.contains(&format!(" {}=", name)) {
+        return tag.to_string();
+    }
+    format!("{}{}>", &tag[..tag.len() - 1], attribute)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conversion::convert;
+    use crate::HtmlConfig;
+
+    #[test]
+    fn test_popovers_for_rendered_footnotes() {
+        let config = HtmlConfig {
+            footnote_popovers: true,
+            ..Default::default()
+        };
+        let html = convert(
+            "One[^a], two[^b] and one again[^a].\n\n[^a]: First *note*.\n\n[^b]: Second\n\n    with two paragraphs.\n",
+            &config,
+        )
+        .unwrap();
+        assert_eq!(
+            html.matches(r#"aria-details="fn-a-content""#).count(),
+            2
+        );
+        assert_eq!(
+            html.matches(r#"aria-details="fn-b-content""#).count(),
+            1
+        );
+        assert!(html.contains(r#"<div class="footnote-content" id="fn-a-content"><p>First <em>note</em>."#));
+        assert!(html.contains(r#"<section class="footnotes""#));
+        assert_eq!(add_footnote_popovers(&html), html);
+
+        let plain =
+            convert("One[^a].\n\n[^a]: Note.", &HtmlConfig::default())
+                .unwrap();
+        assert!(!plain.contains("footnote"));
+    }
+
+    #[test]
+    fn test_roles_for_rendered_footnotes() {
+        let config = HtmlConfig {
+            markdown_extensions: crate::generator::MarkdownExtensions {
+                footnotes: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let html =
+            convert("One[^a] and again[^a].\n\n[^a]: Note.", &config)
+                .unwrap();
+        assert_eq!(html.matches(r#"role="doc-noteref""#).count(), 2);
+        assert_eq!(html.matches(r#"role="doc-backlink""#).count(), 2);
+        assert!(html.contains(r#"aria-label="Back to reference 1-2""#));
+        assert!(html.contains(
+            r#"<section class="footnotes" data-footnotes role="doc-endnotes">"#
+        ));
+        assert_eq!(add_footnote_roles(&html), html);
+
+        let config = HtmlConfig {
+            add_aria_attributes: false,
+            ..config
+        };
+        let html = convert("One[^a].\n\n[^a]: Note.", &config).unwrap();
+        assert!(html.contains("data-footnote-ref"));
+        assert!(!html.contains("doc-noteref"));
+    }
+
+    #[test]
+    fn test_references_without_footnotes_are_unchanged() {
+        let html =
+            r##"<a href="#fn-x" id="fnref-x" data-footnote-ref>1</a>"##;
+        assert_eq!(add_footnote_popovers(html), html);
+    }
+}
@@ -0,0 +1,920 @@
+// Copyright © 2025 HTML Generator. All rights reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Front matter in YAML, TOML and JSON.
+//!
+//! Documents may start with a metadata block in one of three formats:
+//!
+//! - YAML-style `key: value` lines between `---` lines;
+//! - TOML between `+++` lines;
+//! - a JSON object, starting on the first line with `{`.
+//!
+//! [`parse_front_matter`] splits such a block from the body and parses
+//! it into a map of JSON values, whatever the source format, so callers
+//! can read metadata with one API. Only the subset of YAML and TOML used
+//! for page metadata is understood: scalars, lists and one level of
+//! nested keys.
+//!
+//! # Examples
+//!
+//! ```
+//! use html_generator::front_matter::{parse_front_matter, FrontMatterFormat};
+//!
+//! let (metadata, body) = parse_front_matter(
+//!     "+++\ntitle = \"Hello\"\ntags = [\"a\", \"b\"]\n+++\n# Hello",
+//!     FrontMatterFormat::Auto,
+//! )?;
+//! assert_eq!(metadata["title"], "Hello");
+//! assert_eq!(metadata["tags"][1], "b");
+//! assert_eq!(body, "# Hello");
+//! # Ok::<(), html_generator::HtmlError>(())
+//! ```
+
+use crate::error::{HtmlError, Result};
+use crate::patterns::front_matter::{
+    TOML_BLOCK_REGEX, YAML_BLOCK_REGEX,
+};
+use regex::Regex;
+use serde_json::{Map, Number, Value};
+
+/// The format of a front matter block.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum FrontMatterFormat {
+    /// Detect the format from the first line: `---` for YAML, `+++` for
+    /// TOML and `{` for JSON. Text starting with `{` that is not a JSON
+    /// object is treated as content.
+    Auto,
+    /// YAML-style `key: value` lines between `---` lines.
+    Yaml,
+    /// TOML between `+++` lines.
+    Toml,
+    /// A JSON object at the start of the document.
+    Json,
+}
+
+impl Default for FrontMatterFormat {
+    fn default() -> Self {
+        FrontMatterFormat::Auto
+    }
+}
+
+impl FrontMatterFormat {
+    /// Returns the format of the front matter starting `content`, if
+    /// any, judging by its opening delimiter only.
+    ///
+    /// YAML and TOML blocks open with a line holding only `---` or
+    /// `+++`, so `--- text` or `+++ text` is not front matter.
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - The document
+    pub fn detect(content: &str) -> Option<Self> {
+        let first_line = content
+            .split('\n')
+            .next()
+            .unwrap_or_default()
+            .trim_end_matches('\r')
+            .trim_end_matches([' ', '\t']);
+        match first_line {
+            "---" => Some(FrontMatterFormat::Yaml),
+            "+++" => Some(FrontMatterFormat::Toml),
+            _ if content.starts_with('{') => {
+                Some(FrontMatterFormat::Json)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Page metadata from front matter.
+///
+/// The common keys are available as fields; every other key is kept in
+/// [`FrontMatter::extra`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FrontMatter {
+    /// The `title` key.
+    pub title: Option<String>,
+    /// The `description` key.
+    pub description: Option<String>,
+    /// The `author` key.
+    pub author: Option<String>,
+    /// All other keys.
+    pub extra: Map<String, Value>,
+}
+
+impl FrontMatter {
+    /// Builds the metadata from parsed front matter.
+    ///
+    /// `title`, `description` and `author` are moved into their fields
+    /// when they are strings, numbers or booleans; otherwise they stay
+    /// in [`FrontMatter::extra`].
+    ///
+    /// # Arguments
+    ///
+    /// * `map` - The front matter, e.g. from [`parse_front_matter`]
+    pub fn from_map(mut map: Map<String, Value>) -> Self {
+        let mut take = |key: &str| {
+            let text = match map.get(key)? {
+                Value::String(text) => text.clone(),
+                Value::Number(number) => number.to_string(),
+                Value::Bool(flag) => flag.to_string(),
+                _ => return None,
+            };
+            let _ = map.remove(key);
+            Some(text)
+        };
+        let title = take("title");
+        let description = take("description");
+        let author = take("author");
+        Self {
+            title,
+            description,
+            author,
+            extra: map,
+        }
+    }
+
+    /// Returns the value of any key, including the common ones.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The front matter key
+    pub fn get(&self, key: &str) -> Option<Value> {
+        let field = match key {
+            "title" => &self.title,
+            "description" => &self.description,
+            "author" => &self.author,
+            _ => return self.extra.get(key).cloned(),
+        };
+        field
+            .clone()
+            .map(Value::String)
+            .or_else(|| self.extra.get(key).cloned())
+    }
+
+    /// Returns `true` if the document had no metadata.
+    pub fn is_empty(&self) -> bool {
+        self.title.is_none()
+            && self.description.is_none()
+            && self.author.is_none()
+            && self.extra.is_empty()
+    }
+}
+
+/// Splits the front matter from a document and parses it.
+///
+/// # Arguments
+///
+/// * `content` - The document
+/// * `format` - The expected format, or [`FrontMatterFormat::Auto`]
+///
+/// # Returns
+///
+/// The metadata, empty if the document has no front matter in the
+/// expected format, and the body following the block.
+///
+/// # Errors
+///
+/// Returns [`HtmlError::InvalidFrontMatterFormat`] if the document
+/// opens a front matter block that is unterminated or cannot be parsed.
+/// With [`FrontMatterFormat::Auto`], text starting with `{` that is not
+/// a JSON object is not an error.
+pub fn parse_front_matter(
+    content: &str,
+    format: FrontMatterFormat,
+) -> Result<(Map<String, Value>, &str)> {
+    let detected = match FrontMatterFormat::detect(content) {
+        Some(detected)
+            if format == FrontMatterFormat::Auto
+                || format == detected =>
+        {
+            detected
+        }
+        _ => return Ok((Map::new(), content)),
+    };
+
+    match detected {
+        FrontMatterFormat::Yaml => {
+            let (block, body) =
+                split_block(content, &YAML_BLOCK_REGEX, "YAML")?;
+            Ok((parse_yaml(block)?, body))
+        }
+        FrontMatterFormat::Toml => {
+            let (block, body) =
+                split_block(content, &TOML_BLOCK_REGEX, "TOML")?;
+            Ok((parse_toml(block)?, body))
+        }
+        _ => match split_json(content) {
+            Ok(result) => Ok(result),
+            Err(_) if format == FrontMatterFormat::Auto => {
+                Ok((Map::new(), content))
+            }
+            Err(error) => Err(error),
+        },
+    }
+}
+
+/// Splits the front matter from a document without parsing or copying
+/// it, detecting its format.
+///
+/// Same as [`split_front_matter_as`] with [`FrontMatterFormat::Auto`].
+///
+/// # Arguments
+///
+/// * `content` - The document
+///
+/// # Returns
+///
+/// The text of the front matter block, without the `---` or `+++`
+/// delimiters of YAML and TOML blocks, and the body following it with
+/// surrounding whitespace trimmed, both borrowed from `content`.
+/// Documents without a complete block, including unterminated blocks
+/// and text starting with `{` that is not a JSON object, are returned
+/// whole as the body.
+///
+/// # Examples
+///
+/// ```
+/// use html_generator::front_matter::split_front_matter;
+///
+/// let (block, body) = split_front_matter("---\ntitle: Hi\n---\n# Hi\n");
+/// assert_eq!(block, Some("title: Hi"));
+/// assert_eq!(body, "# Hi");
+///
+/// assert_eq!(split_front_matter("# Hi"), (None, "# Hi"));
+/// ```
+pub fn split_front_matter(content: &str) -> (Option<&str>, &str) {
+    split_front_matter_as(content, FrontMatterFormat::Auto)
+}
+
+/// Splits the front matter in `format` from a document without parsing
+/// or copying it.
+///
+/// A block in another format is not front matter: the document is
+/// returned whole as the body, as [`parse_front_matter`] does.
+///
+/// # Arguments
+///
+/// * `content` - The document
+/// * `format` - The expected format, or [`FrontMatterFormat::Auto`]
+///
+/// # Examples
+///
+/// ```
+/// use html_generator::front_matter::{split_front_matter_as, FrontMatterFormat};
+///
+/// let json = "{\"a\": 1}\n\nText";
+/// let (block, body) = split_front_matter_as(json, FrontMatterFormat::Json);
+/// assert_eq!((block, body), (Some("{\"a\": 1}"), "Text"));
+///
+/// assert_eq!(split_front_matter_as(json, FrontMatterFormat::Yaml), (None, json));
+/// ```
+pub fn split_front_matter_as(
+    content: &str,
+    format: FrontMatterFormat,
+) -> (Option<&str>, &str) {
+    let detected =
+        FrontMatterFormat::detect(content).filter(|detected| {
+            format == FrontMatterFormat::Auto || format == *detected
+        });
+    let split = match detected {
+        Some(FrontMatterFormat::Yaml) => {
+            split_block(content, &YAML_BLOCK_REGEX, "YAML").ok()
+        }
+        Some(FrontMatterFormat::Toml) => {
+            split_block(content, &TOML_BLOCK_REGEX, "TOML").ok()
+        }
+        Some(_) => split_json(content).ok().map(|(_, body)| {
+            (content[..content.len() - body.len()].trim_end(), body)
+        }),
+        None => None,
+    };
+    match split {
+        Some((block, body)) => (Some(block), body.trim()),
+        None => (None, content),
+    }
+}
+
+/// Splits a delimited block from the body.
+fn split_block<'a>(
+    content: &'a str,
+    regex: &Regex,
+    name: &str,
+) -> Result<(&'a str, &'a str)> {
+    let captures = regex.captures(content).ok_or_else(|| {
+        HtmlError::InvalidFrontMatterFormat(format!(
+            "Unterminated {} front matter",
+            name
+        ))
+    })?;
+    let block = captures.get(1).map_or("", |m| m.as_str());
+    let end = captures.get(0).map_or(0, |m| m.end());
+    Ok((block, &content[end..]))
+}
+
+/// Splits a leading JSON object from the body.
+///
+/// The object must end its line, so that a paragraph opening with an
+/// inline JSON example is not taken for front matter.
+fn split_json(content: &str) -> Result<(Map<String, Value>, &str)> {
+    let mut values = serde_json::Deserializer::from_str(content)
+        .into_iter::<Value>();
+    match values.next() {
+        Some(Ok(Value::Object(map))) => {
+            let rest = &content[values.byte_offset()..];
+            let line_end =
+                rest.find('\n').map_or(rest.len(), |i| i + 1);
+            if !rest[..line_end].trim().is_empty() {
+                return Err(HtmlError::InvalidFrontMatterFormat(
+                    "JSON front matter must be followed by a line break"
+                        .to_string(),
+                ));
+            }
+            Ok((map, &rest[line_end..]))
+        }
+        Some(Err(error)) => Err(HtmlError::InvalidFrontMatterFormat(
+            format!("Invalid JSON front matter: {}", error),
+        )),
+        _ => Err(HtmlError::InvalidFrontMatterFormat(
+            "JSON front matter must be an object".to_string(),
+        )),
+    }
+}
+
+/// Parses YAML-style front matter.
+///
+/// Each line is a `key: value` pair. A key without a value may be
+/// followed by indented `- item` lines, forming a list, or indented
+/// `key: value` lines, forming a nested map. Blank lines and `#`
+/// comments are ignored.
+fn parse_yaml(block: &str) -> Result<Map<String, Value>> {
+    let mut map = Map::new();
+    let mut open_key: Option<String> = None;
+
+    for (index, line) in block.lines().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        let invalid = || {
+            HtmlError::InvalidFrontMatterFormat(format!(
+                "Invalid line {} in front matter: {}",
+                index + 1,
+                line
+            ))
+        };
+        let indented = line.starts_with([' ', '\t']);
+        let parent = match &open_key {
+            Some(key) if indented => map.get_mut(key),
+            _ => None,
+        };
+
+        if let Some(parent) = parent {
+            if let Some(item) = trimmed
+                .strip_prefix("- ")
+                .or_else(|| (trimmed == "-").then(|| ""))
+            {
+                match parent {
+                    Value::Null => *parent = Value::Array(Vec::new()),
+                    Value::Array(_) => {}
+                    _ => return Err(invalid()),
+                }
+                if let Value::Array(items) = parent {
+                    items.push(yaml_scalar(item));
+                }
+                continue;
+            }
+            let (key, value) =
+                trimmed.split_once(':').ok_or_else(invalid)?;
+            match parent {
+                Value::Null => *parent = Value::Object(Map::new()),
+                Value::Object(_) => {}
+                _ => return Err(invalid()),
+            }
+            if let Value::Object(nested) = parent {
+                let _ = nested
+                    .insert(key.trim().to_string(), yaml_scalar(value));
+            }
+            continue;
+        }
+
+        let (key, value) =
+            trimmed.split_once(':').ok_or_else(invalid)?;
+        let key = key.trim().to_string();
+        let value = value.trim();
+        open_key = value.is_empty().then(|| key.clone());
+        let _ = map.insert(key, yaml_scalar(value));
+    }
+    Ok(map)
+}
+
+/// Parses a YAML scalar or inline `[a, b]` list.
+fn yaml_scalar(value: &str) -> Value {
+    let value = value.trim();
+    if let Some(unquoted) = strip_quotes(value) {
+        return Value::String(unquoted.to_string());
+    }
+    if let Some(items) =
+        value.strip_prefix('[').and_then(|v| v.strip_suffix(']'))
+    {
+        return Value::Array(
+            items
+                .split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(yaml_scalar)
+                .collect(),
+        );
+    }
+    match value {
+        "" | "~" | "null" => Value::Null,
+        "true" => Value::Bool(true),
+        "false" => Value::Bool(false),
+        _ => number(value)
+            .unwrap_or_else(|| Value::String(value.to_string())),
+    }
+}
+
+/// Removes matching single or double quotes.
+fn strip_quotes(value: &str) -> Option<&str> {
+    value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .or_else(|| {
+            value.strip_prefix('\'').and_then(|v| v.strip_suffix('\''))
+        })
+}
+
+/// Parses an integer or a finite float.
+fn number(value: &str) -> Option<Value> {
+    if let Ok(integer) = value.parse::<i64>() {
+        return Some(Value::Number(integer.into()));
+    }
+    if !value.contains(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+    value
+        .parse::<f64>()
+        .ok()
+        .and_then(Number::from_f64)
+        .map(Value::Number)
+}
+
+/// Parses TOML front matter.
+///
+/// Supports `key = value` pairs with bare, quoted and dotted keys,
+/// `[table]` headers, strings, numbers, booleans, arrays and inline
+/// tables. Dates are kept as strings.
+fn parse_toml(block: &str) -> Result<Map<String, Value>> {
+    let mut root = Map::new();
+    let mut table: Vec<String> = Vec::new();
+
+    for (index, line) in block.lines().enumerate() {
+        let invalid = |reason: &str| {
+            HtmlError::InvalidFrontMatterFormat(format!(
+                "Invalid TOML front matter on line {}: {}",
+                index + 1,
+                reason
+            ))
+        };
+        let mut parser = TomlParser::new(line);
+        parser.skip_whitespace();
+        if parser.at_end() || parser.peek() == Some('#') {
+            continue;
+        }
+
+        if parser.eat('[') {
+            table = parser.key().map_err(|e| invalid(&e))?;
+            if !parser.eat(']') || !parser.at_line_end() {
+                return Err(invalid("expected ']'"));
+            }
+            let _ = table_mut(&mut root, &table)
+                .ok_or_else(|| invalid("key is not a table"))?;
+            continue;
+        }
+
+        let mut key = parser.key().map_err(|e| invalid(&e))?;
+        if !parser.eat('=') {
+            return Err(invalid("expected '='"));
+        }
+        let value = parser.value().map_err(|e| invalid(&e))?;
+        if !parser.at_line_end() {
+            return Err(invalid("unexpected text after value"));
+        }
+        let last = key.pop().unwrap_or_default();
+        let mut path = table.clone();
+        path.extend(key);
+        let target = table_mut(&mut root, &path)
+            .ok_or_else(|| invalid("key is not a table"))?;
+        let _ = target.insert(last, value);
+    }
+    Ok(root)
+}
+
+/// Returns the table at `path`, creating missing tables.
+fn table_mut<'a>(
+    root: &'a mut Map<String, Value>,
+    path: &[String],
+) -> Option<&'a mut Map<String, Value>> {
+    let mut table = root;
+    for name in path {
+        table = match table
+            .entry(name.clone())
+            .or_insert_with(|| Value::Object(Map::new()))
+        {
+            Value::Object(map) => map,
+            _ => return None,
+        };
+    }
+    Some(table)
+}
+
+/// A cursor over one line of TOML.
+struct TomlParser<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+}
+
+impl<'a> TomlParser<'a> {
+    fn new(line: &'a str) -> Self {
+        Self {
+            chars: line.chars().peekable(),
+        }
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.chars.peek().copied()
+    }
+
+    fn at_end(&mut self) -> bool {
+        self.peek().is_none()
+    }
+
+    /// Returns `true` if only whitespace or a comment is left.
+    fn at_line_end(&mut self) -> bool {
+        self.skip_whitespace();
+        matches!(self.peek(), None | Some('#'))
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(' ') | Some('\t')) {
+            let _ = self.chars.next();
+        }
+    }
+
+    /// Consumes `c`, after optional whitespace.
+    fn eat(&mut self, c: char) -> bool {
+        self.skip_whitespace();
+        if self.peek() == Some(c) {
+            let _ = self.chars.next();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Parses a bare, quoted or dotted key.
+    fn key(&mut self) -> std::result::Result<Vec<String>, String> {
+        let mut parts = Vec::new();
+        loop {
+            self.skip_whitespace();
+            let part = match self.peek() {
+                Some('"') | Some('\'') => self.string()?,
+                _ => {
+                    let mut bare = String::new();
+                    while let Some(c) = self.peek() {
+                        if c.is_ascii_alphanumeric()
+                            || c == '_'
+                            || c == '-'
+                        {
+                            bare.push(c);
+                            let _ = self.chars.next();
+                        } else {
+                            break;
+                        }
+                    }
+                    if bare.is_empty() {
+                        return Err("expected a key".to_string());
+                    }
+                    bare
+                }
+            };
+            parts.push(part);
+            if !self.eat('.') {
+                return Ok(parts);
+            }
+        }
+    }
+
+    /// Parses a basic or literal string.
+    fn string(&mut self) -> std::result::Result<String, String> {
+        let quote = self.chars.next().unwrap_or('"');
+        let mut value = String::new();
+        loop {
+            match self.chars.next() {
+                None => return Err("unterminated string".to_string()),
+                Some(c) if c == quote => return Ok(value),
+                Some('\\') if quote == '"' => match self.chars.next() {
+                    Some('n') => value.push('\n'),
+                    Some('t') => value.push('\t'),
+                    Some('r') => value.push('\r'),
+                    Some('"') => value.push('"'),
+                    Some('\\') => value.push('\\'),
+                    Some('u') => {
+                        let code: String =
+                            self.chars.by_ref().take(4).collect();
+                        let c = u32::from_str_radix(&code, 16)
+                            .ok()
+                            .and_then(char::from_u32)
+                            .ok_or("invalid unicode escape")?;
+                        value.push(c);
+                    }
+                    _ => return Err("invalid escape".to_string()),
+                },
+                Some(c) => value.push(c),
+            }
+        }
+    }
+
+    /// Parses a value.
+    fn value(&mut self) -> std::result::Result<Value, String> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('"') | Some('\'') => self.string().map(Value::String),
+            Some('[') => {
+                let _ = self.chars.next();
+                let mut items = Vec::new();
+                loop {
+                    if self.eat(']') {
+                        return Ok(Value::Array(items));
+                    }
+                    items.push(self.value()?);
+                    if !self.eat(',') {
+                        return if self.eat(']') {
+                            Ok(Value::Array(items))
+                        } else {
+                            Err("expected ',' or ']'".to_string())
+                        };
+                    }
+                }
+            }
+            Some('{') => {
+                let _ = self.chars.next();
+                let mut table = Map::new();
+                if self.eat('}') {
+                    return Ok(Value::Object(table));
+                }
+                loop {
+                    let mut key = self.key()?;
+                    if !self.eat('=') {
+                        return Err("expected '='".to_string());
+                    }
+                    let value = self.value()?;
+                    let last = key.pop().unwrap_or_default();
+                    let key: Vec<String> = key;
+                    let target = table_mut(&mut table, &key)
+                        .ok_or("key is not a table")?;
+                    let _ = target.insert(last, value);
+                    if self.eat('}') {
+                        return Ok(Value::Object(table));
+                    }
+                    if !self.eat(',') {
+                        return Err("expected ',' or '}'".to_string());
+                    }
+                }
+            }
+            _ => {
+                let mut raw = String::new();
+                while let Some(c) = self.peek() {
+                    if matches!(c, ',' | ']' | '}' | '#') {
+                        break;
+                    }
+                    raw.push(c);
+                    let _ = self.chars.next();
+                }
+                let raw = raw.trim();
+                match raw {
+                    "" => Err("expected a value".to_string()),
+                    "true" => Ok(Value::Bool(true)),
+                    "false" => Ok(Value::Bool(false)),
+                    _ => Ok(number(&raw.replace('_', ""))
+                        .unwrap_or_else(|| {
+                            Value::String(raw.to_string())
+                        })),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_yaml_front_matter() {
+        let content = "---\ntitle: \"Hello: World\"\ndraft: false\nweight: 3\n# note\n\ntags:\n  - rust\n  - 'web'\nauthor:\n  name: Ada\nkeywords: [a, b]\n---\n# Body";
+        let (metadata, body) =
+            parse_front_matter(content, FrontMatterFormat::Auto)
+                .unwrap();
+        assert_eq!(
+            Value::Object(metadata),
+            json!({
+                "title": "Hello: World",
+                "draft": false,
+                "weight": 3,
+                "tags": ["rust", "web"],
+                "author": { "name": "Ada" },
+                "keywords": ["a", "b"],
+            })
+        );
+        assert_eq!(body, "# Body");
+
+        let (metadata, body) =
+            parse_front_matter("---\n---\nx", FrontMatterFormat::Yaml)
+                .unwrap();
+        assert!(metadata.is_empty());
+        assert_eq!(body, "x");
+    }
+
+    #[test]
+    fn test_toml_front_matter() {
+        let content = concat!(
+            "+++\n",
+            "title = \"Say \\\"hi\\\"\" # comment\n",
+            "date = 2024-01-02\n",
+            "count = 1_000\n",
+            "ratio = 0.5\n",
+            "tags = [ 'a', \"b\", ]\n",
+            "author = { name = \"Ada\", social.github = \"ada\" }\n",
+            "\n",
+            "[extra.seo]\n",
+            "index = true\n",
+            "+++\n",
+            "Body"
+        );
+        let (metadata, body) =
+            parse_front_matter(content, FrontMatterFormat::Toml)
+                .unwrap();
+        assert_eq!(
+            Value::Object(metadata),
+            json!({
+                "title": "Say \"hi\"",
+                "date": "2024-01-02",
+                "count": 1000,
+                "ratio": 0.5,
+                "tags": ["a", "b"],
+                "author": { "name": "Ada", "social": { "github": "ada" } },
+                "extra": { "seo": { "index": true } },
+            })
+        );
+        assert_eq!(body, "Body");
+    }
+
+    #[test]
+    fn test_json_front_matter() {
+        let content =
+            "{\n  \"title\": \"Hi\",\n  \"tags\": [\"x\"]\n}\n# Hi";
+        let (metadata, body) =
+            parse_front_matter(content, FrontMatterFormat::Auto)
+                .unwrap();
+        assert_eq!(metadata["tags"], json!(["x"]));
+        assert_eq!(body, "# Hi");
+
+        let text = "{not json} is a paragraph";
+        let (metadata, body) =
+            parse_front_matter(text, FrontMatterFormat::Auto).unwrap();
+        assert!(metadata.is_empty());
+        assert_eq!(body, text);
+        assert!(
+            parse_front_matter(text, FrontMatterFormat::Json).is_err()
+        );
+    }
+
+    #[test]
+    fn test_format_selection_and_errors() {
+        let toml = "+++\ntitle = \"T\"\n+++\nBody";
+        let (metadata, body) =
+            parse_front_matter(toml, FrontMatterFormat::Yaml).unwrap();
+        assert!(metadata.is_empty());
+        assert_eq!(body, toml);
+
+        for (content, format) in [
+            ("---\ntitle: x\n", FrontMatterFormat::Auto),
+            ("---\ntitle x\n---\n", FrontMatterFormat::Yaml),
+            ("+++\ntitle = \n+++\n", FrontMatterFormat::Toml),
+            ("+++\ntitle = 'x' y\n+++\n", FrontMatterFormat::Toml),
+            ("+++\na = 1\n[a]\n+++\n", FrontMatterFormat::Toml),
+        ] {
+            assert!(
+                matches!(
+                    parse_front_matter(content, format),
+                    Err(HtmlError::InvalidFrontMatterFormat(_))
+                ),
+                "{:?} should be rejected",
+                content
+            );
+        }
+        assert_eq!(
+            FrontMatterFormat::detect("+++\n"),
+            Some(FrontMatterFormat::Toml)
+        );
+        assert_eq!(FrontMatterFormat::detect("# Title"), None);
+        assert_eq!(
+            FrontMatterFormat::detect("--- \r\ntitle: x"),
+            Some(FrontMatterFormat::Yaml)
+        );
+        for content in ["+++ plus\n", "---- rule", "--- a\n---\n"] {
+            assert_eq!(FrontMatterFormat::detect(content), None);
+            assert_eq!(
+                parse_front_matter(content, FrontMatterFormat::Auto)
+                    .unwrap(),
+                (Map::new(), content)
+            );
+        }
+    }
+
+    #[test]
+    fn test_json_must_end_its_line() {
+        let inline = "{\"a\": 1} trailing\n\n# H";
+        assert_eq!(split_front_matter(inline), (None, inline));
+        assert_eq!(
+            parse_front_matter(inline, FrontMatterFormat::Auto)
+                .unwrap(),
+            (Map::new(), inline)
+        );
+        assert!(parse_front_matter(inline, FrontMatterFormat::Json)
+            .is_err());
+
+        for (content, body) in [
+            ("{\"a\": 1}  \r\n# H", "# H"),
+            ("{\"a\": 1}", ""),
+            ("{\"a\": 1}\n", ""),
+        ] {
+            let (map, rest) =
+                parse_front_matter(content, FrontMatterFormat::Auto)
+                    .unwrap();
+            assert_eq!(map["a"], 1);
+            assert_eq!(rest, body);
+        }
+    }
+
+    #[test]
+    fn test_front_matter_struct() {
+        let (map, _) = parse_front_matter(
+            "---\ntitle: Hi\nauthor: [a, b]\nweight: 2\n---\n",
+            FrontMatterFormat::Auto,
+        )
+        .unwrap();
+        let metadata = FrontMatter::from_map(map);
+        assert_eq!(metadata.title.as_deref(), Some("Hi"));
+        assert_eq!(metadata.description, None);
+        assert_eq!(metadata.author, None);
+        assert_eq!(metadata.get("author"), Some(json!(["a", "b"])));
+        assert_eq!(metadata.get("title"), Some(json!("Hi")));
+        assert_eq!(metadata.get("weight"), Some(json!(2)));
+        assert!(!metadata.is_empty());
+        assert!(FrontMatter::default().is_empty());
+    }
+
+    #[test]
+    fn test_split_front_matter_borrows() {
+        let content = "+++\ntitle = \"Hi\"\n+++\n\n# Hi\n";
+        let (block, body) = split_front_matter(content);
+        assert_eq!(block, Some("title = \"Hi\""));
+        assert_eq!(body, "# Hi");
+        assert!(content
+            .as_bytes()
+            .as_ptr_range()
+            .contains(&body.as_ptr()));
+
+        let (block, body) = split_front_matter("{\"a\": 1}\n# Hi");
+        assert_eq!(block, Some("{\"a\": 1}"));
+        assert_eq!(body, "# Hi");
+
+        for content in ["---\ntitle: x\n# Hi", "{not json", "# Hi"] {
+            assert_eq!(split_front_matter(content), (None, content));
+        }
+    }
+
+    #[test]
+    fn test_split_front_matter_in_format() {
+        let toml = "+++\ntitle = \"Hi\"\n+++\n# Hi";
+        assert_eq!(
+            split_front_matter_as(toml, FrontMatterFormat::Toml),
+            (Some("title = \"Hi\""), "# Hi")
+        );
+        for format in [FrontMatterFormat::Yaml, FrontMatterFormat::Json]
+        {
+            assert_eq!(
+                split_front_matter_as(toml, format),
+                (None, toml)
+            );
+        }
+    }
+}
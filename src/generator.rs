@@ -7,61 +7,521 @@
 //! using the `mdx-gen` library. It supports various Markdown extensions
 //! and custom configuration options.
 
-use crate::{error::HtmlError, extract_front_matter, Result};
+use crate::admonitions::AdmonitionRegistry;
+use crate::constants::DEFAULT_SYNTAX_THEME;
+use crate::containers::{restore_placeholders, BlockRendererRegistry};
+use crate::diagrams::render_diagram_blocks;
+use crate::front_matter::{split_front_matter_as, FrontMatterFormat};
+use crate::headings::add_heading_ids;
+use crate::highlight::{
+    highlight_code_blocks, highlight_code_blocks_parallel,
+    HighlightMode, CSS_CLASSES,
+};
+use crate::limits::{OutputBuffer, ResourceLimits};
+use crate::patterns::generator::{
+    CUSTOM_BLOCK_REGEX, DETAILS_BLOCK_REGEX, FIRST_H1_REGEX,
+    HEADING_TAG_REGEX, HTML_TAG_REGEX, IMAGE_CLASS_REGEX,
+    MARKDOWN_CONTAINER_REGEX,
+};
+use crate::profiler::profile;
+use crate::toc::{insert_section_tocs, insert_toc};
+use crate::{error::HtmlError, Result};
+use comrak::{format_html, parse_document, Arena};
+use mdx_gen::extensions::{process_custom_blocks, process_tables};
 use mdx_gen::{process_markdown, ComrakOptions, MarkdownOptions};
-use regex::Regex;
+use scraper::Html;
+use std::borrow::Cow;
 use std::error::Error;
 
+/// The Markdown syntax extensions enabled during conversion.
+///
+/// The defaults match the historic behaviour: GitHub-style tables,
+/// strikethrough, autolinks and task lists, `^superscript^`, front
+/// matter and raw HTML are on; footnotes, description lists and math
+/// are off.
+///
+/// Turn `raw_html` off for untrusted input: raw HTML is then escaped and
+/// shown as text. As `:::` containers and `.class="…"`
+/// images are written as raw HTML before parsing, they are left as
+/// plain text too.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schema", schemars(deny_unknown_fields))]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
+pub struct MarkdownExtensions {
+    /// `| a | b |` tables.
+    pub tables: bool,
+    /// `~~deleted~~` text.
+    pub strikethrough: bool,
+    /// Bare URLs and e-mail addresses become links.
+    pub autolinks: bool,
+    /// `- [x] done` list items.
+    pub task_lists: bool,
+    /// `^superscript^` text.
+    pub superscript: bool,
+    /// `[^1]` footnote references and definitions; always on with
+    /// [`HtmlConfig::footnote_popovers`].
+    ///
+    /// [`HtmlConfig::footnote_popovers`]: crate::HtmlConfig::footnote_popovers
+    pub footnotes: bool,
+    /// `Term` followed by `: definition` lines become `<dl>` lists.
+    pub description_lists: bool,
+    /// A leading front matter block is removed from the output.
+    pub front_matter: bool,
+    /// The format of the front matter block removed with
+    /// `front_matter`; a block in another format is rendered as
+    /// content. [`MarkdownConfig::front_matter_format`] takes precedence
+    /// when it is not [`FrontMatterFormat::Auto`].
+    ///
+    /// [`MarkdownConfig::front_matter_format`]: crate::MarkdownConfig::front_matter_format
+    pub front_matter_format: FrontMatterFormat,
+    /// `$inline$`, `$$display$$` and ```` ```math ```` blocks are marked
+    /// up with `data-math-style`; always on with [`HtmlConfig::math`],
+    /// which rewrites them for KaTeX or MathJax.
+    ///
+    /// [`HtmlConfig::math`]: crate::HtmlConfig::math
+    pub math: bool,
+    /// Raw HTML in the Markdown is passed through.
+    pub raw_html: bool,
+}
+
+impl Default for MarkdownExtensions {
+    fn default() -> Self {
+        Self {
+            tables: true,
+            strikethrough: true,
+            autolinks: true,
+            task_lists: true,
+            superscript: true,
+            footnotes: false,
+            description_lists: false,
+            front_matter: true,
+            front_matter_format: FrontMatterFormat::Auto,
+            math: false,
+            raw_html: true,
+        }
+    }
+}
+
 /// Generate HTML from Markdown content using `mdx-gen`.
 ///
 /// This function takes Markdown content and a configuration object,
 /// converts the Markdown into HTML, and returns the resulting HTML string.
+/// Headings are shifted by [`HtmlConfig::heading_offset`] levels, the
+/// first `h1` is removed when [`HtmlConfig::strip_title`] is set, and a
+/// table of contents is added when [`HtmlConfig::generate_toc`] is set.
+///
+/// [`HtmlConfig::heading_offset`]: crate::HtmlConfig::heading_offset
+/// [`HtmlConfig::generate_toc`]: crate::HtmlConfig::generate_toc
+/// [`HtmlConfig::strip_title`]: crate::HtmlConfig::strip_title
 pub fn generate_html(
     markdown: &str,
-    _config: &crate::HtmlConfig,
+    config: &crate::HtmlConfig,
 ) -> Result<String> {
-    markdown_to_html_with_extensions(markdown)
+    generate_html_with_title(markdown, config).map(|(html, _, _)| html)
 }
 
-/// Convert Markdown to HTML with specified extensions using `mdx-gen`.
-pub fn markdown_to_html_with_extensions(
+/// Generates HTML like [`generate_html`] and also returns the text of
+/// the `h1` removed by [`HtmlConfig::strip_title`](crate::HtmlConfig)
+/// and the table of contents.
+pub(crate) fn generate_html_with_title(
     markdown: &str,
-) -> Result<String> {
-    // 1) Extract front matter
-    let content_without_front_matter = extract_front_matter(markdown)
-        .unwrap_or_else(|_| markdown.to_string());
+    config: &crate::HtmlConfig,
+) -> Result<(String, Option<String>, Option<String>)> {
+    let profiler = config.profiler.as_ref();
+    let mut extensions = config.markdown_extensions;
+    extensions.footnotes |= config.footnote_popovers;
+    extensions.math |= config.math;
+    let mut html = profile(profiler, "markdown", || {
+        if config.markdown_in_html {
+            render_markdown(
+                &enable_markdown_in_html(markdown),
+                extensions,
+                &config.block_renderers,
+                config.admonitions.as_ref(),
+                &config.limits,
+            )
+        } else {
+            render_markdown(
+                markdown,
+                extensions,
+                &config.block_renderers,
+                config.admonitions.as_ref(),
+                &config.limits,
+            )
+        }
+    })?;
+    html = profile(profiler, "diagrams", || {
+        render_diagram_blocks(&html, &config.diagram_languages)
+    });
+    if config.enable_syntax_highlighting {
+        let theme = match config.highlight_mode {
+            HighlightMode::InlineStyles => Some(
+                config
+                    .syntax_theme
+                    .as_deref()
+                    .unwrap_or(DEFAULT_SYNTAX_THEME),
+            ),
+            HighlightMode::CssClasses => Some(CSS_CLASSES),
+            HighlightMode::None => None,
+        };
+        if let Some(theme) = theme {
+            html = profile(profiler, "highlight", || {
+                if config.parallel_highlighting {
+                    highlight_code_blocks_parallel(
+                        &html,
+                        theme,
+                        config.parallel_highlighting_threshold,
+                    )
+                } else {
+                    highlight_code_blocks(&html, theme)
+                }
+            })?;
+        }
+    }
+    let mut title = None;
+    if config.strip_title {
+        let (stripped, text) = strip_title(&html);
+        html = stripped;
+        title = text;
+    }
+    let html = match offset_headings(&html, config.heading_offset) {
+        Cow::Borrowed(_) => html,
+        Cow::Owned(shifted) => shifted,
+    };
+    let html = if config.heading_ids || config.heading_anchor_links {
+        profile(profiler, "heading_ids", || {
+            add_heading_ids(
+                &html,
+                config.heading_anchor_links,
+                config.slug_strategy,
+            )
+        })
+    } else {
+        html
+    };
+    let html = if config.section_tocs {
+        profile(profiler, "section_tocs", || {
+            insert_section_tocs(&html, config.slug_strategy)
+        })
+    } else {
+        html
+    };
+    if config.generate_toc {
+        let (html, toc) = profile(profiler, "toc", || {
+            insert_toc(&html, &config.toc, config.slug_strategy)
+        });
+        return Ok((html, title, toc));
+    }
+    Ok((html, title, None))
+}
 
-    // 2) Convert triple-colon blocks, re-parsing inline Markdown inside them
-    let markdown_with_classes =
-        add_custom_classes(&content_without_front_matter);
+/// Enables Markdown inside HTML containers marked with `markdown="1"`.
+///
+/// CommonMark treats everything up to the next blank line after an HTML
+/// tag as raw HTML, so Markdown written inside a layout `<div>` is left
+/// unconverted. For each container whose opening tag is alone on its
+/// line and carries `markdown="1"`, the attribute is removed and blank
+/// lines are inserted after the opening tag and before the matching
+/// closing tag, so the content is parsed as Markdown. Containers may be
+/// nested; fenced code blocks are left untouched. Content indented by
+/// four or more spaces is still treated as a code block.
+///
+/// # Arguments
+///
+/// * `markdown` - The Markdown source
+///
+/// # Returns
+///
+/// The rewritten Markdown source.
+///
+/// # Examples
+///
+/// ```
+/// use html_generator::generator::enable_markdown_in_html;
+///
+/// let markdown = enable_markdown_in_html(
+///     "<div class=\"card\" markdown=\"1\">\n**Bold**\n</div>",
+/// );
+/// assert_eq!(markdown, "<div class=\"card\">\n\n**Bold**\n\n</div>");
+/// ```
+pub fn enable_markdown_in_html(markdown: &str) -> String {
+    let mut lines: Vec<Cow<'_, str>> = Vec::new();
+    // Open containers with the nesting depth of their tag name
+    let mut open: Vec<(String, usize)> = Vec::new();
+    let mut in_fence = false;
+
+    for line in markdown.lines() {
+        let trimmed = line.trim_start();
+        let is_fence =
+            trimmed.starts_with("```") || trimmed.starts_with("~~~");
+        if is_fence {
+            in_fence = !in_fence;
+        }
+        if in_fence || is_fence {
+            lines.push(line.into());
+            continue;
+        }
 
-    // 3) Convert images with `.class="..."`
-    let markdown_with_images =
-        process_images_with_classes(&markdown_with_classes);
+        if let Some(caps) = MARKDOWN_CONTAINER_REGEX.captures(line) {
+            let name = caps[2].to_ascii_lowercase();
+            for (open_name, depth) in &mut open {
+                if *open_name == name {
+                    *depth += 1;
+                }
+            }
+            open.push((name, 1));
+            lines.push(format!("{}{}", &caps[1], &caps[3]).into());
+            lines.push("".into());
+            continue;
+        }
 
-    // 4) Configure Comrak/Markdown Options
-    let mut comrak_options = ComrakOptions::default();
-    comrak_options.extension.strikethrough = true;
-    comrak_options.extension.table = true;
-    comrak_options.extension.autolink = true;
-    comrak_options.extension.tasklist = true;
-    comrak_options.extension.superscript = true;
+        let mut start = 0;
+        for caps in HTML_TAG_REGEX.captures_iter(line) {
+            if open.is_empty() || !caps[3].is_empty() {
+                continue;
+            }
+            let name = caps[2].to_ascii_lowercase();
+            let closing = !caps[1].is_empty();
+            for (open_name, depth) in &mut open {
+                if *open_name == name {
+                    if closing {
+                        *depth = depth.saturating_sub(1);
+                    } else {
+                        *depth += 1;
+                    }
+                }
+            }
+            if open.last().map_or(false, |(_, depth)| *depth == 0) {
+                let tag_start = caps.get(0).map_or(0, |m| m.start());
+                let before = &line[start..tag_start];
+                if !before.trim().is_empty() {
+                    lines.push(before.into());
+                }
+                lines.push("".into());
+                start = tag_start;
+                let _ = open.pop();
+            }
+        }
+        lines.push(line[start..].into());
+    }
+    lines.join("\n")
+}
 
-    comrak_options.render.unsafe_ = true; // raw HTML allowed
-    comrak_options.render.escape = false;
+/// Removes the first `<h1>` element from `html` and returns its text.
+///
+/// The title is the text content of the heading with markup removed,
+/// entities decoded and whitespace collapsed. Use this when a template
+/// renders the page title separately from the body.
+///
+/// # Arguments
+///
+/// * `html` - The HTML to rewrite
+///
+/// # Returns
+///
+/// The HTML without the heading, and the title if an `h1` was found.
+///
+/// # Examples
+///
+/// ```
+/// use html_generator::generator::strip_title;
+///
+/// let (html, title) =
+///     strip_title("<h1>Fish &amp; <em>Chips</em></h1>\n<p>Menu</p>");
+/// assert_eq!(html, "<p>Menu</p>");
+/// assert_eq!(title.as_deref(), Some("Fish & Chips"));
+/// ```
+pub fn strip_title(html: &str) -> (String, Option<String>) {
+    let caps = match FIRST_H1_REGEX.captures(html) {
+        Some(caps) => caps,
+        None => return (html.to_string(), None),
+    };
+    let heading = caps.get(0).map_or(0..0, |m| m.range());
+    let fragment = Html::parse_fragment(&caps[1]);
+    let text = fragment.root_element().text().collect::<String>();
+    let title = text.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    let mut stripped = String::with_capacity(html.len());
+    stripped.push_str(&html[..heading.start]);
+    stripped.push_str(&html[heading.end..]);
+    (stripped, Some(title))
+}
 
-    let options =
-        MarkdownOptions::default().with_comrak_options(comrak_options);
+/// Shifts every `<h1>`..`<h6>` tag in `html` by `offset` levels.
+///
+/// Opening and closing tags are rewritten together, so the document
+/// stays well formed, and levels are clamped to the range 1..=6. Tables
+/// of contents built from the result, such as with
+/// [`generate_table_of_contents`](crate::utils::generate_table_of_contents),
+/// reflect the shifted levels.
+///
+/// # Arguments
+///
+/// * `html` - The HTML to rewrite
+/// * `offset` - Levels to demote by; negative values promote
+///
+/// # Examples
+///
+/// ```
+/// use html_generator::generator::offset_headings;
+///
+/// let html = offset_headings("<h1>Title</h1><h5>Deep</h5>", 2);
+/// assert_eq!(html, "<h3>Title</h3><h6>Deep</h6>");
+/// ```
+pub fn offset_headings(html: &str, offset: i8) -> Cow<'_, str> {
+    if offset == 0 {
+        return Cow::Borrowed(html);
+    }
+    HEADING_TAG_REGEX.replace_all(html, |caps: &regex::Captures| {
+        let level = i16::from(caps[2].as_bytes()[0] - b'0');
+        let shifted = (level + i16::from(offset)).clamp(1, 6);
+        format!("<{}h{}", &caps[1], shifted)
+    })
+}
 
-    // 5) Convert final Markdown to HTML
-    match process_markdown(&markdown_with_images, &options) {
-        Ok(html_output) => Ok(html_output),
-        Err(err) => {
-            Err(HtmlError::markdown_conversion(err.to_string(), None))
-        }
+/// Convert Markdown to HTML with specified extensions using `mdx-gen`.
+///
+/// Code blocks are highlighted in the default theme.
+pub fn markdown_to_html_with_extensions(
+    markdown: &str,
+) -> Result<String> {
+    let html = render_markdown(
+        markdown,
+        MarkdownExtensions::default(),
+        &BlockRendererRegistry::new(),
+        None,
+        &ResourceLimits::unlimited(),
+    )?;
+    highlight_code_blocks(&html, DEFAULT_SYNTAX_THEME)
+}
+
+/// Converts Markdown to HTML with the given `extensions`, rendering
+/// `:::` containers through `renderers`, then `admonitions` if given.
+///
+/// The parsed document is checked against `limits`, and writing the
+/// HTML stops as soon as it exceeds their output budget.
+fn render_markdown(
+    markdown: &str,
+    extensions: MarkdownExtensions,
+    renderers: &BlockRendererRegistry,
+    admonitions: Option<&AdmonitionRegistry>,
+    limits: &ResourceLimits,
+) -> Result<String> {
+    // 1) Split off front matter, borrowing the body
+    let body = if extensions.front_matter {
+        split_front_matter_as(markdown, extensions.front_matter_format)
+            .1
+    } else {
+        markdown
+    };
+
+    // 2) Render containers with a registered renderer, then convert
+    //    admonitions, or `:::details` containers and the remaining
+    //    triple-colon blocks, re-parsing inline Markdown inside them,
+    //    and 3) images with `.class="..."`; all of them produce raw HTML
+    let mut rendered = Vec::new();
+    let markdown_with_images = if extensions.raw_html {
+        let (markdown, blocks) = renderers.render_containers(body)?;
+        rendered = blocks;
+        let markdown_with_classes = match admonitions {
+            Some(registry) => registry.expand(&markdown),
+            None => {
+                add_custom_classes(&expand_details_blocks(&markdown))
+            }
+        };
+        Cow::Owned(process_images_with_classes(&markdown_with_classes))
+    } else {
+        Cow::Borrowed(body)
+    };
+
+    // 4) Configure Comrak Options
+    let mut comrak_options = comrak::Options::default();
+    comrak_options.extension.strikethrough = extensions.strikethrough;
+    comrak_options.extension.table = extensions.tables;
+    comrak_options.extension.autolink = extensions.autolinks;
+    comrak_options.extension.tasklist = extensions.task_lists;
+    comrak_options.extension.superscript = extensions.superscript;
+    comrak_options.extension.footnotes = extensions.footnotes;
+    comrak_options.extension.description_lists =
+        extensions.description_lists;
+    comrak_options.extension.math_dollars = extensions.math;
+    comrak_options.extension.math_code = extensions.math;
+
+    // Raw HTML is escaped rather than omitted when disabled
+    comrak_options.render.unsafe_ = true;
+    comrak_options.render.escape = !extensions.raw_html;
+
+    // 5) Parse once, check the document against the limits and write
+    //    it within the output budget
+    let arena = Arena::new();
+    let root =
+        parse_document(&arena, &markdown_with_images, &comrak_options);
+    limits.check_document(root)?;
+    let mut output = OutputBuffer::new(limits, markdown.len());
+    let written = format_html(root, &comrak_options, &mut output);
+    let mut html_output = output.into_html()?;
+    if let Err(err) = written {
+        return Err(HtmlError::markdown_conversion(
+            err.to_string(),
+            None,
+        ));
+    }
+
+    // 6) Apply the table and custom block enhancements of `mdx-gen`
+    if extensions.tables {
+        html_output = process_tables(&html_output);
+    }
+    html_output = process_custom_blocks(&html_output);
+    if rendered.is_empty() {
+        Ok(html_output)
+    } else {
+        Ok(restore_placeholders(&html_output, &rendered))
     }
 }
 
+/// Converts `:::details` containers into native `<details>` elements:
+///
+/// ```markdown
+/// :::details Show the **full** log
+/// - first
+/// - second
+/// :::
+/// ```
+///
+/// The text after `details` becomes the `<summary>` (defaulting to
+/// `Details`) and may contain inline Markdown. The body is kept as
+/// Markdown, separated from the surrounding tags by blank lines, so that
+/// block-level syntax such as lists, code and tables is converted with
+/// the rest of the document.
+fn expand_details_blocks(markdown: &str) -> String {
+    DETAILS_BLOCK_REGEX
+        .replace_all(markdown, |caps: &regex::Captures| {
+            let summary = caps.get(1).map_or("", |m| m.as_str()).trim();
+            let summary = if summary.is_empty() {
+                "Details".to_string()
+            } else {
+                process_markdown_inline(summary)
+                    .map(|html| {
+                        html.trim()
+                            .trim_start_matches("<p>")
+                            .trim_end_matches("</p>")
+                            .to_string()
+                    })
+                    .unwrap_or_else(|_| summary.to_string())
+            };
+            format!(
+                "<details>\n<summary>{}</summary>\n\n{}\n\n</details>",
+                summary, &caps[2]
+            )
+        })
+        .to_string()
+}
+
 /// Re-parse inline Markdown for triple-colon blocks, e.g.:
 ///
 /// ```markdown
@@ -82,9 +542,7 @@ fn add_custom_classes(markdown: &str) -> String {
     //   :::<class_name>\n
     //   (block content, possibly multiline)
     //   \n:::
-    let re = Regex::new(r":::(\w+)\n([\s\S]*?)\n:::").unwrap();
-
-    re.replace_all(markdown, |caps: &regex::Captures| {
+    CUSTOM_BLOCK_REGEX.replace_all(markdown, |caps: &regex::Captures| {
         let class_name = &caps[1];
         let block_content = &caps[2];
 
@@ -129,23 +587,22 @@ pub fn process_markdown_inline(
 /// Replaces image patterns like
 /// `![Alt text](URL).class="some-class"` with `<img src="URL" alt="Alt text" class="some-class" />`.
 fn process_images_with_classes(markdown: &str) -> String {
-    let re =
-        Regex::new(r#"!\[(.*?)\]\((.*?)\)\.class="(.*?)""#).unwrap();
-    re.replace_all(markdown, |caps: &regex::Captures| {
-        format!(
-            r#"<img src="{}" alt="{}" class="{}" />"#,
-            &caps[2], // URL
-            &caps[1], // alt text
-            &caps[3], // class attribute
-        )
-    })
-    .to_string()
+    IMAGE_CLASS_REGEX
+        .replace_all(markdown, |caps: &regex::Captures| {
+            format!(
+                r#"<img src="{}" alt="{}" class="{}" />"#,
+                &caps[2], // URL
+                &caps[1], // alt text
+                &caps[3], // class attribute
+            )
+        })
+        .to_string()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::HtmlConfig;
+    use crate::{extract_front_matter, HtmlConfig};
 
     /// Test basic Markdown to HTML conversion.
     ///
@@ -161,6 +618,38 @@ fn test_generate_html_basic() {
         assert!(html.contains("<p>This is a test.</p>"));
     }
 
+    /// Test toggling the Markdown extensions on `HtmlConfig`.
+    #[test]
+    fn test_markdown_extension_toggles() {
+        let markdown = "---\ntitle: T\n---\n$x^2$ ~~old~~ <b onclick=\"x()\">raw</b>\n\n:::note\nhi\n:::\n\nApple\n\n: A fruit\n";
+        let default =
+            generate_html(markdown, &HtmlConfig::default()).unwrap();
+        assert!(!default.contains("title: T"));
+        assert!(default.contains("<del>old</del>"));
+        assert!(default.contains("<b onclick"));
+        assert!(default.contains(r#"<div class="note">"#));
+        assert!(!default.contains("<dl>"));
+
+        let config = HtmlConfig {
+            markdown_extensions: MarkdownExtensions {
+                strikethrough: false,
+                description_lists: true,
+                front_matter: false,
+                math: true,
+                raw_html: false,
+                ..MarkdownExtensions::default()
+            },
+            ..HtmlConfig::default()
+        };
+        let html = generate_html(markdown, &config).unwrap();
+        assert!(html.contains("title: T"));
+        assert!(html.contains("<dl>"));
+        assert!(html.contains(r#"data-math-style="inline""#));
+        assert!(html.contains("~~old~~"));
+        assert!(html.contains("&lt;b onclick"));
+        assert!(!html.contains(r#"<div class="note">"#));
+    }
+
     /// Test conversion with Markdown extensions.
     ///
     /// This test ensures that the Markdown extensions (e.g., custom blocks, enhanced tables, etc.)
@@ -282,12 +771,12 @@ fn main() {
             "Code block with language-rust class not found"
         );
         assert!(
-            html.contains(r#"<span style="color:#b48ead;">fn </span>"#),
+            html.contains(r#"<span style="font-weight:bold;color:#a71d5d;">fn </span>"#),
             "`fn` keyword with syntax highlighting not found"
         );
         assert!(
             html.contains(
-                r#"<span style="color:#8fa1b3;">main</span>"#
+                r#"<span style="font-weight:bold;color:#795da3;">main</span>"#
             ),
             "`main` function name with syntax highlighting not found"
         );
@@ -886,4 +1375,131 @@ fn test_triple_colon_block_forcing_inline_error() {
             "Expected either parsed content or a fallback error message");
         }
     }
+
+    mod heading_offset_tests {
+        use super::*;
+        use crate::utils::generate_table_of_contents;
+
+        #[test]
+        fn test_demotes_and_clamps_headings() {
+            let config = HtmlConfig {
+                heading_offset: 2,
+                ..Default::default()
+            };
+            let html = generate_html(
+                "# Title\n\n## Part\n\n##### Deep",
+                &config,
+            )
+            .unwrap();
+            assert!(html.contains("<h3>Title</h3>"));
+            assert!(html.contains("<h4>Part</h4>"));
+            assert!(html.contains("<h6>Deep</h6>"));
+            assert!(!html.contains("<h1"));
+
+            let toc = generate_table_of_contents(&html).unwrap();
+            assert!(toc.contains(r#"<li class="toc-h3">"#));
+            assert!(toc.contains(r#"<li class="toc-h4">"#));
+        }
+
+        #[test]
+        fn test_promotes_and_leaves_other_tags_alone() {
+            let html = offset_headings(
+                r#"<header><H2 id="a">A</H2><hr><h1>B</h1></header>"#,
+                -1,
+            );
+            assert_eq!(
+                html,
+                r#"<header><h1 id="a">A</h1><hr><h1>B</h1></header>"#
+            );
+            assert!(matches!(
+                offset_headings("<h1>x</h1>", 0),
+                Cow::Borrowed(_)
+            ));
+        }
+
+        #[test]
+        fn test_code_blocks_are_not_rewritten() {
+            let config = HtmlConfig {
+                heading_offset: 1,
+                ..Default::default()
+            };
+            let html =
+                generate_html("```html\n<h1>x</h1>\n```", &config)
+                    .unwrap();
+            assert!(!html.contains("h2"));
+        }
+    }
+
+    mod details_tests {
+        use super::*;
+
+        #[test]
+        fn test_details_block_with_summary() {
+            let html = markdown_to_html_with_extensions(
+                ":::details Show the **log**\n- one\n- two\n\n```\ncode\n```\n:::\n\nAfter",
+            )
+            .unwrap();
+            assert!(html.contains(
+                "<details>\n<summary>Show the <strong>log</strong></summary>"
+            ));
+            assert!(html.contains("<li>one</li>"));
+            assert!(html.contains("<pre><code>code\n</code></pre>"));
+            assert!(html.contains("</details>\n<p>After</p>"));
+        }
+
+        #[test]
+        fn test_details_block_default_summary() {
+            let html = markdown_to_html_with_extensions(
+                ":::details\n# Inside\n:::",
+            )
+            .unwrap();
+            assert!(html.contains("<summary>Details</summary>"));
+            assert!(html.contains("<h1>Inside</h1>"));
+            assert!(!html.contains(r#"<div class="details">"#));
+        }
+
+        #[test]
+        fn test_other_custom_blocks_are_unchanged() {
+            let html =
+                markdown_to_html_with_extensions(":::note\nHello\n:::")
+                    .unwrap();
+            assert!(html.contains(r#"<div class="note">"#));
+            assert!(!html.contains("<details>"));
+        }
+    }
+
+    mod markdown_in_html_tests {
+        use super::*;
+
+        #[test]
+        fn test_markdown_inside_marked_containers() {
+            let config = HtmlConfig {
+                markdown_in_html: true,
+                ..Default::default()
+            };
+            let markdown = "<section markdown=\"1\">\n<div class=\"col\" markdown='1'>\n## Left\n\n- item\n</div>\n<div>*raw*</div>\n</section>\n\n<div>\n*untouched*\n</div>";
+            let html = generate_html(markdown, &config).unwrap();
+            assert!(html.contains("<section>"));
+            assert!(html.contains(r#"<div class="col">"#));
+            assert!(html.contains("<h2>Left</h2>"));
+            assert!(html.contains("<li>item</li>"));
+            assert!(html.contains("<div>*raw*</div>"));
+            assert!(html.contains("*untouched*"));
+            assert!(!html.contains("markdown="));
+
+            let html = generate_html(markdown, &HtmlConfig::default())
+                .unwrap();
+            assert!(html.contains("## Left"));
+        }
+
+        #[test]
+        fn test_closing_tag_after_content_and_fences() {
+            assert_eq!(
+                enable_markdown_in_html(
+                    "<div markdown=\"1\">\n```\n</div>\n```\n*a*</div>\nafter"
+                ),
+                "<div>\n\n```\n</div>\n```\n*a*\n\n</div>\nafter"
+            );
+        }
+    }
 }
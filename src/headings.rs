@@ -0,0 +1,345 @@
+// Copyright © 2025 HTML Generator. All rights reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Heading IDs and anchor links.
+//!
+//! Links into a page need stable targets. [`add_heading_ids`] gives
+//! every `h1`–`h6` without an `id` one derived from its text by a
+//! [`SlugStrategy`]. The default keeps Unicode letters, like
+//! [`format_header_with_id_class`](crate::utils::format_header_with_id_class);
+//! other strategies follow GitHub's anchors, produce ASCII-only IDs or
+//! call a function of your own. Repeated headings get numbered IDs
+//! (`setup`, `setup-1`, `setup-2`), and IDs already in the page are
+//! never reused. Optionally, a visible `¶` link to the heading is
+//! appended, so readers can copy a link to the section.
+//!
+//! # Examples
+//!
+//! ```
+//! use html_generator::headings::{add_heading_ids, SlugStrategy};
+//!
+//! let html = add_heading_ids(
+//!     "<h2>Setup</h2><h2>Setup</h2>",
+//!     true,
+//!     SlugStrategy::default(),
+//! );
+//! assert_eq!(
+//!     html,
+//!     concat!(
+//!         r##"<h2 id="setup">Setup <a class="heading-anchor" href="#setup" aria-label="Link to section: Setup">¶</a></h2>"##,
+//!         r##"<h2 id="setup-1">Setup <a class="heading-anchor" href="#setup-1" aria-label="Link to section: Setup">¶</a></h2>"##,
+//!     )
+//! );
+//!
+//! assert_eq!(SlugStrategy::Unicode.slugify("Über uns"), "über-uns");
+//! assert_eq!(SlugStrategy::Transliterate.slugify("Über uns"), "uber-uns");
+//! assert_eq!(SlugStrategy::GithubStyle.slugify("A & B"), "a--b");
+//! ```
+
+use crate::error::{HtmlError, Result};
+use crate::patterns::headings::{
+    ANCHOR_LINK_REGEX, HEADING_REGEX, ID_REGEX, TAG_REGEX,
+};
+use crate::utils::{ascii_slug, generate_id};
+use regex::Captures;
+use std::{collections::HashSet, fmt, str::FromStr};
+
+/// Class of the anchor links added by [`add_heading_ids`].
+pub const ANCHOR_CLASS: &str = "heading-anchor";
+
+/// How heading text is turned into an `id`.
+#[derive(Debug, Copy, Clone)]
+pub enum SlugStrategy {
+    /// GitHub's anchors: lowercase, punctuation removed and each space
+    /// replaced by a hyphen, so `A & B` becomes `a--b`.
+    GithubStyle,
+    /// Lowercase, with every run of characters other than Unicode
+    /// letters and digits replaced by one hyphen.
+    Unicode,
+    /// Like [`SlugStrategy::Unicode`], but accented letters are
+    /// transliterated to ASCII and other non-ASCII characters dropped,
+    /// for IDs that must stay readable in any tool.
+    Transliterate,
+    /// A function of your own, given the plain text of the heading.
+    Custom(fn(&str) -> String),
+}
+
+impl Default for SlugStrategy {
+    fn default() -> Self {
+        SlugStrategy::Unicode
+    }
+}
+
+impl PartialEq for SlugStrategy {
+    /// Custom strategies are equal when they call the same function.
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (SlugStrategy::Custom(a), SlugStrategy::Custom(b)) => {
+                *a as usize == *b as usize
+            }
+            _ => {
+                std::mem::discriminant(self)
+                    == std::mem::discriminant(other)
+            }
+        }
+    }
+}
+
+impl Eq for SlugStrategy {}
+
+impl SlugStrategy {
+    /// Returns the slug for `text`, which may be empty.
+    pub fn slugify(&self, text: &str) -> String {
+        match self {
+            SlugStrategy::GithubStyle => text
+                .trim()
+                .to_lowercase()
+                .chars()
+                .filter_map(|c| match c {
+                    ' ' => Some('-'),
+                    '-' | '_' => Some(c),
+                    _ if c.is_alphanumeric() => Some(c),
+                    _ => None,
+                })
+                .collect(),
+            SlugStrategy::Unicode => generate_id(text),
+            SlugStrategy::Transliterate => ascii_slug(text),
+            SlugStrategy::Custom(slugify) => slugify(text),
+        }
+    }
+}
+
+impl fmt::Display for SlugStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SlugStrategy::GithubStyle => "github",
+            SlugStrategy::Unicode => "unicode",
+            SlugStrategy::Transliterate => "transliterate",
+            SlugStrategy::Custom(_) => "custom",
+        })
+    }
+}
+
+impl FromStr for SlugStrategy {
+    type Err = HtmlError;
+
+    /// Parses `github`, `unicode` or `transliterate`; custom
+    /// strategies can only be set in code.
+    fn from_str(value: &str) -> Result<Self> {
+        match value {
+            "github" => Ok(SlugStrategy::GithubStyle),
+            "unicode" => Ok(SlugStrategy::Unicode),
+            "transliterate" => Ok(SlugStrategy::Transliterate),
+            _ => Err(HtmlError::InvalidInput(format!(
+                "Unknown slug strategy `{}`",
+                value
+            ))),
+        }
+    }
+}
+
+/// Hands out heading IDs unique within a page.
+#[derive(Debug, Clone)]
+pub(crate) struct HeadingIds {
+    used: HashSet<String>,
+    slugs: SlugStrategy,
+}
+
+impl HeadingIds {
+    /// Creates an allocator that avoids the heading IDs already in
+    /// `html`.
+    pub(crate) fn for_page(html: &str, slugs: SlugStrategy) -> Self {
+        let used = HEADING_REGEX
+            .captures_iter(html)
+            .filter_map(|caps| heading_id(caps.get(2)?.as_str()))
+            .collect();
+        Self { used, slugs }
+    }
+
+    /// Returns an unused ID for a heading with the text `text`.
+    pub(crate) fn next(&mut self, text: &str) -> String {
+        let base = self.slugs.slugify(text);
+        let base = if base.is_empty() { "section" } else { &base };
+        let mut id = base.to_string();
+        let mut counter = 1;
+        while !self.used.insert(id.clone()) {
+            id = format!("{}-{}", base, counter);
+            counter += 1;
+        }
+        id
+    }
+}
+
+/// Returns the `id` in the attributes of a heading.
+pub(crate) fn heading_id(attributes: &str) -> Option<String> {
+    ID_REGEX.captures(attributes).map(|id| id[1].to_string())
+}
+
+/// Returns the plain text of a heading's content, without tags or the
+/// anchor link.
+pub(crate) fn heading_text(content: &str) -> String {
+    let content = ANCHOR_LINK_REGEX.replace_all(content, "");
+    TAG_REGEX.replace_all(&content, "").trim().to_string()
+}
+
+/// Adds an `id` to every heading without one.
+///
+/// # Arguments
+///
+/// * `html` - The HTML to process
+/// * `anchor_links` - Whether to append a `¶` link to each heading
+///   that does not have one yet
+/// * `slugs` - How IDs are derived from heading text
+///
+/// # Returns
+///
+/// The HTML with heading IDs.
+pub fn add_heading_ids(
+    html: &str,
+    anchor_links: bool,
+    slugs: SlugStrategy,
+) -> String {
+    let mut ids = HeadingIds::for_page(html, slugs);
+    HEADING_REGEX
+        .replace_all(html, |caps: &Captures<'_>| {
+            let attributes = caps.get(2).map_or("", |m| m.as_str());
+            let content = &caps[3];
+            let existing = heading_id(attributes);
+            let has_link = content.contains(ANCHOR_CLASS);
+            if existing.is_some() && (!anchor_links || has_link) {
+                return caps[0].to_string();
+            }
+
+            let text = heading_text(content);
+            let (id, id_attribute) = match existing {
+                Some(id) => (id, String::new()),
+                None => {
+                    let id = ids.next(&text);
+                    let attribute = format!(" id=\"{}\"", id);
+                    (id, attribute)
+                }
+            };
+            let link = if anchor_links && !has_link {
+                format!(
+                    r##" <a class="{}" href="#{}" aria-label="Link to section: {}">¶</a>"##,
+                    ANCHOR_CLASS,
+                    id,
+                    text.replace('"', "&quot;")
+                )
+            } else {
+                String::new()
+            };
+            format!(
+                "<h{level}{id}{attributes}>{content}{link}</h{level}>",
+                level = &caps[1],
+                id = id_attribute,
+                attributes = attributes,
+                content = content,
+                link = link
+            )
+        })
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ids_avoid_existing_and_repeated_slugs() {
+        let html = concat!(
+            r#"<h1 class="t">Hello, <em>World</em>!</h1>"#,
+            r#"<h2 id="hello-world">Other</h2>"#,
+            "<h3>Hello World</h3><h4></h4>"
+        );
+        assert_eq!(
+            add_heading_ids(html, false, SlugStrategy::default()),
+            concat!(
+                r#"<h1 id="hello-world-1" class="t">Hello, <em>World</em>!</h1>"#,
+                r#"<h2 id="hello-world">Other</h2>"#,
+                r#"<h3 id="hello-world-2">Hello World</h3><h4 id="section"></h4>"#
+            )
+        );
+    }
+
+    #[test]
+    fn test_anchor_links_are_idempotent() {
+        let once = add_heading_ids(
+            r#"<h2 id="a">A "quoted"</h2>"#,
+            true,
+            SlugStrategy::default(),
+        );
+        assert_eq!(
+            once,
+            r##"<h2 id="a">A "quoted" <a class="heading-anchor" href="#a" aria-label="Link to section: A &quot;quoted&quot;">¶</a></h2>"##
+        );
+        assert_eq!(
+            add_heading_ids(&once, true, SlugStrategy::default()),
+            once
+        );
+        assert_eq!(heading_text(&once), r#"A "quoted""#);
+    }
+
+    #[test]
+    fn test_convert_with_heading_ids() {
+        let config = crate::HtmlConfig {
+            heading_ids: true,
+            generate_toc: true,
+            ..Default::default()
+        };
+        let html =
+            crate::conversion::convert("# Intro\n\n## Intro", &config)
+                .unwrap();
+        assert!(html.contains(r#"<h1 id="intro">Intro</h1>"#));
+        assert!(html.contains(r#"<h2 id="intro-1">Intro</h2>"#));
+        assert!(html.contains(r##"<a href="#intro-1">Intro</a>"##));
+    }
+
+    #[test]
+    fn test_slug_strategies() {
+        let text = "Crème Brûlée: 2 façons_à la française";
+        assert_eq!(
+            SlugStrategy::GithubStyle.slugify(text),
+            "crème-brûlée-2-façons_à-la-française"
+        );
+        assert_eq!(
+            SlugStrategy::Unicode.slugify(text),
+            "crème-brûlée-2-façons-à-la-française"
+        );
+        assert_eq!(
+            SlugStrategy::Transliterate.slugify(text),
+            "creme-brulee-2-facons-a-la-francaise"
+        );
+        assert_eq!(SlugStrategy::Transliterate.slugify("日本語"), "");
+        let custom =
+            SlugStrategy::Custom(|text| format!("h-{}", text.len()));
+        assert_eq!(custom.slugify("abc"), "h-3");
+        assert_eq!(
+            add_heading_ids(
+                "<h2>日本語</h2>",
+                false,
+                SlugStrategy::Transliterate
+            ),
+            r#"<h2 id="section">日本語</h2>"#
+        );
+
+        for strategy in ["github", "unicode", "transliterate"] {
+            let parsed: SlugStrategy = strategy.parse().unwrap();
+            assert_eq!(parsed.to_string(), strategy);
+        }
+        assert!("custom".parse::<SlugStrategy>().is_err());
+    }
+
+    #[test]
+    fn test_convert_with_slug_strategy() {
+        let config = crate::HtmlConfig {
+            generate_toc: true,
+            slug_strategy: SlugStrategy::Transliterate,
+            ..Default::default()
+        };
+        let html =
+            crate::conversion::convert("# Ça va?", &config).unwrap();
+        assert!(html.contains(r##"<a href="#ca-va">Ça va?</a>"##));
+        assert!(html.contains(r#"<h1 id="ca-va">"#));
+    }
+}
@@ -0,0 +1,587 @@
+// Copyright © 2025 HTML Generator. All rights reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Syntax highlighting of fenced code blocks.
+//!
+//! With [`HtmlConfig::enable_syntax_highlighting`] set, every fenced
+//! code block with a language is highlighted in the theme named by
+//! [`HtmlConfig::syntax_theme`]:
+//!
+//! | Name | Theme |
+//! |------|-------|
+//! | `github` (default) | GitHub's light theme |
+//! | `monokai` | Monokai |
+//! | `dracula` | Dracula |
+//! | `solarized`, `solarized-light` | Solarized dark and light |
+//!
+//! The names of the themes bundled with Syntect, such as
+//! `base16-ocean.dark`, are accepted too. Colours are written as inline
+//! `style` attributes, and the block is wrapped in a
+//! `<div class="highlight">` carrying the theme background.
+//!
+//! Inline styles are blocked by a Content Security Policy without
+//! `style-src 'unsafe-inline'`. [`HighlightMode::CssClasses`] in
+//! [`HtmlConfig::highlight_mode`], or the theme [`CSS_CLASSES`], writes
+//! classes instead, prefixed with `hl-`
+//! (`<span class="hl-keyword hl-control hl-rust">`), for sites shipping
+//! their own stylesheet; [`theme_css`] generates one from any theme.
+//! [`HighlightMode::None`] leaves code blocks unhighlighted.
+//!
+//! Highlighting dominates the conversion of code-heavy pages. With
+//! [`HtmlConfig::parallel_highlighting`] set, pages with at least
+//! [`HtmlConfig::parallel_highlighting_threshold`] blocks are
+//! highlighted on the threads of a [rayon] pool, see
+//! [`highlight_code_blocks_parallel`]. The output is the same as
+//! highlighting the blocks one after the other.
+//!
+//! [rayon]: https://docs.rs/rayon
+//! [`HtmlConfig::enable_syntax_highlighting`]: crate::HtmlConfig::enable_syntax_highlighting
+//! [`HtmlConfig::syntax_theme`]: crate::HtmlConfig::syntax_theme
+//! [`HtmlConfig::highlight_mode`]: crate::HtmlConfig::highlight_mode
+//! [`HtmlConfig::parallel_highlighting`]: crate::HtmlConfig::parallel_highlighting
+//! [`HtmlConfig::parallel_highlighting_threshold`]: crate::HtmlConfig::parallel_highlighting_threshold
+//!
+//! # Examples
+//!
+//! ```
+//! use html_generator::highlight::{highlight_code_blocks, theme_css};
+//!
+//! let html = highlight_code_blocks(
+//!     "<pre><code class=\"language-rust\">let x = 1;\n</code></pre>",
+//!     "css",
+//! )?;
+//! assert!(html.starts_with(r#"<div class="highlight hl-code"><pre><code class="language-rust">"#));
+//! assert!(html.contains(r#"<span class="hl-storage hl-type hl-rust">let</span>"#));
+//! assert!(theme_css("dracula")?.contains(".hl-code {"));
+//! # Ok::<(), html_generator::error::HtmlError>(())
+//! ```
+
+use crate::error::{HtmlError, Result};
+use crate::patterns::highlight::CODE_BLOCK_REGEX;
+use crate::utils::decode_entities;
+use once_cell::sync::Lazy;
+#[cfg(feature = "std")]
+use rayon::prelude::*;
+use regex::Captures;
+use std::{fmt, str::FromStr};
+use syntect::easy::HighlightLines;
+use syntect::highlighting::{
+    Color, FontStyle, ScopeSelectors, StyleModifier, Theme, ThemeItem,
+    ThemeSet, ThemeSettings,
+};
+use syntect::html::{
+    append_highlighted_html_for_styled_line,
+    css_for_theme_with_class_style, ClassStyle, ClassedHTMLGenerator,
+    IncludeBackground,
+};
+use syntect::parsing::SyntaxSet;
+use syntect::util::LinesWithEndings;
+
+/// Theme name selecting class-based output instead of inline styles.
+pub const CSS_CLASSES: &str = "css";
+
+/// Default number of code blocks from which a page is highlighted in
+/// parallel.
+pub const DEFAULT_PARALLEL_THRESHOLD: usize = 8;
+
+/// How highlighted code blocks are written.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum HighlightMode {
+    /// Colours of [`HtmlConfig::syntax_theme`] in `style` attributes.
+    ///
+    /// [`HtmlConfig::syntax_theme`]: crate::HtmlConfig::syntax_theme
+    InlineStyles,
+    /// `hl-` classes, styled by a stylesheet such as [`theme_css`].
+    CssClasses,
+    /// No highlighting.
+    None,
+}
+
+impl Default for HighlightMode {
+    fn default() -> Self {
+        HighlightMode::InlineStyles
+    }
+}
+
+impl fmt::Display for HighlightMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            HighlightMode::InlineStyles => "inline",
+            HighlightMode::CssClasses => "classes",
+            HighlightMode::None => "none",
+        })
+    }
+}
+
+impl FromStr for HighlightMode {
+    type Err = HtmlError;
+
+    /// Parses `inline`, `classes` or `none`.
+    fn from_str(value: &str) -> Result<Self> {
+        match value {
+            "inline" => Ok(HighlightMode::InlineStyles),
+            "classes" => Ok(HighlightMode::CssClasses),
+            "none" => Ok(HighlightMode::None),
+            _ => Err(HtmlError::InvalidInput(format!(
+                "Unknown highlight mode: {}",
+                value
+            ))),
+        }
+    }
+}
+
+/// Class style of the class-based output.
+const CLASS_STYLE: ClassStyle =
+    ClassStyle::SpacedPrefixed { prefix: "hl-" };
+
+static SYNTAXES: Lazy<SyntaxSet> =
+    Lazy::new(SyntaxSet::load_defaults_newlines);
+
+static THEMES: Lazy<ThemeSet> = Lazy::new(|| {
+    let mut themes = ThemeSet::load_defaults();
+    let _ = themes
+        .themes
+        .insert("monokai".to_string(), palette_theme(&MONOKAI));
+    let _ = themes
+        .themes
+        .insert("dracula".to_string(), palette_theme(&DRACULA));
+    themes
+});
+
+/// Colours of a theme defined by this crate.
+#[derive(Debug)]
+struct Palette {
+    name: &'static str,
+    background: u32,
+    foreground: u32,
+    /// Scope selectors with their colour and whether they are italic.
+    rules: &'static [(&'static str, u32, bool)],
+}
+
+static MONOKAI: Palette = Palette {
+    name: "Monokai",
+    background: 0x27_28_22,
+    foreground: 0xf8_f8_f2,
+    rules: &[
+        ("comment", 0x75_71_5e, false),
+        ("string", 0xe6_db_74, false),
+        ("constant.numeric, constant.language, constant.character", 0xae_81_ff, false),
+        ("keyword, storage, entity.name.tag", 0xf9_26_72, false),
+        ("storage.type, support.type, support.class", 0x66_d9_ef, true),
+        ("entity.name.function, entity.name.type, entity.other.attribute-name, support.function", 0xa6_e2_2e, false),
+        ("variable.parameter", 0xfd_97_1f, true),
+    ],
+};
+
+static DRACULA: Palette = Palette {
+    name: "Dracula",
+    background: 0x28_2a_36,
+    foreground: 0xf8_f8_f2,
+    rules: &[
+        ("comment", 0x62_72_a4, false),
+        ("string", 0xf1_fa_8c, false),
+        ("constant.numeric, constant.language, constant.character", 0xbd_93_f9, false),
+        ("keyword, storage, entity.name.tag", 0xff_79_c6, false),
+        ("storage.type, support.type, support.class", 0x8b_e9_fd, true),
+        ("entity.name.function, entity.name.type, entity.other.attribute-name, support.function", 0x50_fa_7b, false),
+        ("variable.parameter", 0xff_b8_6c, true),
+    ],
+};
+
+/// Builds a Syntect theme from a palette.
+fn palette_theme(palette: &Palette) -> Theme {
+    let scopes = palette
+        .rules
+        .iter()
+        .filter_map(|&(selector, colour, italic)| {
+            Some(ThemeItem {
+                scope: ScopeSelectors::from_str(selector).ok()?,
+                style: StyleModifier {
+                    foreground: Some(rgb(colour)),
+                    background: None,
+                    font_style: italic.then(|| FontStyle::ITALIC),
+                },
+            })
+        })
+        .collect();
+    Theme {
+        name: Some(palette.name.to_string()),
+        author: None,
+        settings: ThemeSettings {
+            foreground: Some(rgb(palette.foreground)),
+            background: Some(rgb(palette.background)),
+            ..ThemeSettings::default()
+        },
+        scopes,
+    }
+}
+
+/// Converts `0xRRGGBB` into an opaque colour.
+fn rgb(colour: u32) -> Color {
+    let [_, r, g, b] = colour.to_be_bytes();
+    Color { r, g, b, a: 0xff }
+}
+
+/// Returns the Syntect theme called `name`.
+fn theme(name: &str) -> Result<&'static Theme> {
+    let key = match name {
+        "github" => "InspiredGitHub",
+        "solarized" => "Solarized (dark)",
+        "solarized-light" => "Solarized (light)",
+        other => other,
+    };
+    THEMES.themes.get(key).ok_or_else(|| {
+        HtmlError::InvalidInput(format!(
+            "Unknown syntax theme: {}",
+            name
+        ))
+    })
+}
+
+/// Checks that `name` is a supported theme or [`CSS_CLASSES`].
+///
+/// # Errors
+///
+/// Returns [`HtmlError::InvalidInput`] for unknown themes.
+pub fn validate_theme(name: &str) -> Result<()> {
+    if name == CSS_CLASSES {
+        return Ok(());
+    }
+    theme(name).map(|_| ())
+}
+
+/// Returns a stylesheet for the class-based output in the theme `name`.
+///
+/// # Errors
+///
+/// Returns [`HtmlError::InvalidInput`] for unknown themes.
+pub fn theme_css(name: &str) -> Result<String> {
+    css_for_theme_with_class_style(theme(name)?, CLASS_STYLE)
+        .map_err(|e| HtmlError::InvalidInput(e.to_string()))
+}
+
+/// Highlights the fenced code blocks of `html` in the theme `name`.
+///
+/// Code blocks without a language are left alone. Code in languages
+/// Syntect does not know is kept as plain text in the themed block.
+///
+/// # Arguments
+///
+/// * `html` - HTML with `<pre><code class="language-…">` blocks
+/// * `name` - A theme name, or [`CSS_CLASSES`]
+///
+/// # Errors
+///
+/// Returns [`HtmlError::InvalidInput`] for unknown themes.
+pub fn highlight_code_blocks(html: &str, name: &str) -> Result<String> {
+    highlight_blocks(html, name, None)
+}
+
+/// Highlights code blocks like [`highlight_code_blocks`], on several
+/// threads when `html` has at least `threshold` of them.
+///
+/// The blocks are highlighted independently and stitched back in
+/// document order, so the output is the same as that of
+/// [`highlight_code_blocks`]. Without the `std` feature, blocks are
+/// always highlighted on the calling thread.
+///
+/// # Arguments
+///
+/// * `html` - HTML with `<pre><code class="language-…">` blocks
+/// * `name` - A theme name, or [`CSS_CLASSES`]
+/// * `threshold` - The minimum number of blocks worth the threads,
+///   such as [`DEFAULT_PARALLEL_THRESHOLD`]
+///
+/// # Errors
+///
+/// Returns [`HtmlError::InvalidInput`] for unknown themes.
+///
+/// # Examples
+///
+/// ```
+/// use html_generator::highlight::{
+///     highlight_code_blocks, highlight_code_blocks_parallel,
+/// };
+///
+/// let html = "<pre><code class=\"language-rust\">let x = 1;\n</code></pre>\n".repeat(4);
+/// assert_eq!(
+///     highlight_code_blocks_parallel(&html, "github", 2)?,
+///     highlight_code_blocks(&html, "github")?
+/// );
+/// # Ok::<(), html_generator::error::HtmlError>(())
+/// ```
+pub fn highlight_code_blocks_parallel(
+    html: &str,
+    name: &str,
+    threshold: usize,
+) -> Result<String> {
+    highlight_blocks(html, name, Some(threshold))
+}
+
+/// Highlights the code blocks of `html`, in parallel from `threshold`
+/// blocks if given.
+fn highlight_blocks(
+    html: &str,
+    name: &str,
+    threshold: Option<usize>,
+) -> Result<String> {
+    let theme = if name == CSS_CLASSES {
+        None
+    } else {
+        Some(theme(name)?)
+    };
+    let blocks: Vec<Captures<'_>> =
+        CODE_BLOCK_REGEX.captures_iter(html).collect();
+    let render = |caps: &Captures<'_>| highlight_block(caps, theme);
+    let rendered: Vec<String> = match threshold {
+        #[cfg(feature = "std")]
+        Some(threshold) if blocks.len() >= threshold.max(2) => {
+            blocks.par_iter().map(render).collect()
+        }
+        _ => blocks.iter().map(render).collect(),
+    };
+
+    let mut highlighted = String::with_capacity(
+        html.len() + rendered.iter().map(String::len).sum::<usize>(),
+    );
+    let mut last = 0;
+    for (caps, block) in blocks.iter().zip(&rendered) {
+        let range = caps.get(0).map_or(0..0, |found| found.range());
+        highlighted.push_str(&html[last..range.start]);
+        highlighted.push_str(block);
+        last = range.end;
+    }
+    highlighted.push_str(&html[last..]);
+    Ok(highlighted)
+}
+
+/// Returns the highlighted markup of the code block `caps`, styled by
+/// `theme`, or with classes if `None`.
+fn highlight_block(
+    caps: &Captures<'_>,
+    theme: Option<&Theme>,
+) -> String {
+    let code = decode_entities(&caps[2]);
+    let (wrapper, body) = match theme {
+        Some(theme) => (
+            theme_wrapper(theme),
+            highlight_inline(&caps[1], &code, theme),
+        ),
+        None => (
+            r#"<div class="highlight hl-code">"#.to_string(),
+            highlight_classes(&caps[1], &code),
+        ),
+    };
+    format!(
+        r#"{}<pre><code class="language-{}">{}</code></pre></div>"#,
+        wrapper,
+        &caps[1],
+        body.unwrap_or_else(|| caps[2].to_string())
+    )
+}
+
+/// Returns the opening tag of the element carrying the theme colours.
+fn theme_wrapper(theme: &Theme) -> String {
+    let mut style = String::new();
+    if let Some(Color { r, g, b, .. }) = theme.settings.background {
+        style.push_str(&format!(
+            "background-color:#{:02x}{:02x}{:02x};",
+            r, g, b
+        ));
+    }
+    if let Some(Color { r, g, b, .. }) = theme.settings.foreground {
+        style.push_str(&format!("color:#{:02x}{:02x}{:02x};", r, g, b));
+    }
+    format!(r#"<div class="highlight" style="{}">"#, style)
+}
+
+/// Highlights `code` with inline styles, or returns `None` on failure.
+fn highlight_inline(
+    language: &str,
+    code: &str,
+    theme: &Theme,
+) -> Option<String> {
+    let syntax = SYNTAXES
+        .find_syntax_by_token(language)
+        .unwrap_or_else(|| SYNTAXES.find_syntax_plain_text());
+    let mut highlighter = HighlightLines::new(syntax, theme);
+    let background = theme.settings.background.unwrap_or(Color::WHITE);
+    let mut output = String::with_capacity(code.len() * 4);
+    for line in LinesWithEndings::from(code) {
+        let regions =
+            highlighter.highlight_line(line, &SYNTAXES).ok()?;
+        append_highlighted_html_for_styled_line(
+            &regions,
+            IncludeBackground::IfDifferent(background),
+            &mut output,
+        )
+        .ok()?;
+    }
+    Some(output)
+}
+
+/// Highlights `code` with classes, or returns `None` on failure.
+fn highlight_classes(language: &str, code: &str) -> Option<String> {
+    let syntax = SYNTAXES
+        .find_syntax_by_token(language)
+        .unwrap_or_else(|| SYNTAXES.find_syntax_plain_text());
+    let mut generator = ClassedHTMLGenerator::new_with_class_style(
+        syntax,
+        &SYNTAXES,
+        CLASS_STYLE,
+    );
+    for line in LinesWithEndings::from(code) {
+        generator
+            .parse_html_for_line_which_includes_newline(line)
+            .ok()?;
+    }
+    Some(generator.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conversion::convert;
+    use crate::HtmlConfig;
+
+    const RUST: &str = "```rust\nfn main() { let s = \"<&>\"; }\n```";
+
+    #[test]
+    fn test_themes_are_applied() {
+        for (name, background) in [
+            ("github", "#ffffff"),
+            ("monokai", "#272822"),
+            ("dracula", "#282a36"),
+            ("solarized", "#002b36"),
+            ("solarized-light", "#fdf6e3"),
+        ] {
+            let config = HtmlConfig {
+                syntax_theme: Some(name.to_string()),
+                ..Default::default()
+            };
+            let html = convert(RUST, &config).unwrap();
+            assert!(
+                html.contains(&format!(
+                    "background-color:{};",
+                    background
+                )),
+                "{}: {}",
+                name,
+                html
+            );
+            assert!(
+                html.contains(r#"<pre><code class="language-rust">"#)
+            );
+            assert!(html.contains("&lt;&amp;&gt;"));
+        }
+
+        let monokai = highlight_code_blocks(
+            "<pre><code class=\"language-rust\">fn f() {}\n</code></pre>",
+            "monokai",
+        )
+        .unwrap();
+        assert!(monokai
+            .contains(r#"<span style="color:#a6e22e;">f</span>"#));
+    }
+
+    #[test]
+    fn test_css_classes_and_stylesheet() {
+        let config = HtmlConfig {
+            syntax_theme: Some(CSS_CLASSES.to_string()),
+            ..Default::default()
+        };
+        let html = convert(RUST, &config).unwrap();
+        assert!(html.contains(r#"<div class="highlight hl-code">"#));
+        assert!(html.contains("hl-keyword"));
+        assert!(!html.contains("style="));
+
+        let config = HtmlConfig {
+            syntax_theme: Some("monokai".to_string()),
+            highlight_mode: HighlightMode::CssClasses,
+            ..Default::default()
+        };
+        assert_eq!(convert(RUST, &config).unwrap(), html);
+
+        let config = HtmlConfig {
+            highlight_mode: HighlightMode::None,
+            ..Default::default()
+        };
+        let html = convert(RUST, &config).unwrap();
+        assert!(!html.contains("highlight"));
+        assert!(html.contains("&quot;&lt;&amp;&gt;&quot;"));
+        assert_eq!(
+            "classes".parse::<HighlightMode>().unwrap(),
+            HighlightMode::CssClasses
+        );
+        assert!("css".parse::<HighlightMode>().is_err());
+
+        let css = theme_css("monokai").unwrap();
+        assert!(css.contains("background-color: #272822;"));
+        assert!(css.contains(".hl-comment {"));
+        assert!(theme_css(CSS_CLASSES).is_err());
+    }
+
+    #[test]
+    fn test_unknown_themes_and_disabled_highlighting() {
+        assert!(validate_theme("base16-ocean.dark").is_ok());
+        assert!(validate_theme(CSS_CLASSES).is_ok());
+        assert!(matches!(
+            highlight_code_blocks("", "neon"),
+            Err(HtmlError::InvalidInput(_))
+        ));
+
+        let config = HtmlConfig {
+            enable_syntax_highlighting: false,
+            ..Default::default()
+        };
+        let html = convert(RUST, &config).unwrap();
+        assert!(html.contains(
+            "<pre><code class=\"language-rust\">fn main() { let s = &quot;&lt;&amp;&gt;&quot;; }\n</code></pre>"
+        ));
+        assert_eq!(
+            highlight_code_blocks(
+                "<pre><code>x</code></pre>",
+                "github"
+            )
+            .unwrap(),
+            "<pre><code>x</code></pre>"
+        );
+    }
+
+    #[test]
+    fn test_parallel_highlighting_matches_sequential() {
+        let markdown = ["rust", "python", "javascript", "toml"]
+            .iter()
+            .cycle()
+            .take(12)
+            .map(|language| {
+                format!("```{}\nlet x = \"<1>\";\n```\n", language)
+            })
+            .collect::<Vec<_>>()
+            .join("\nText\n\n");
+        let sequential =
+            convert(&markdown, &HtmlConfig::default()).unwrap();
+        for threshold in [0, 1, 12, 13] {
+            let config = HtmlConfig {
+                parallel_highlighting: true,
+                parallel_highlighting_threshold: threshold,
+                ..Default::default()
+            };
+            assert_eq!(
+                convert(&markdown, &config).unwrap(),
+                sequential
+            );
+        }
+
+        let html = "<p>a</p><pre><code class=\"language-python\">x = 1\n</code></pre><p>b</p>".repeat(3);
+        for name in ["monokai", CSS_CLASSES] {
+            assert_eq!(
+                highlight_code_blocks_parallel(&html, name, 2).unwrap(),
+                highlight_code_blocks(&html, name).unwrap()
+            );
+        }
+        assert!(
+            highlight_code_blocks_parallel(&html, "neon", 2).is_err()
+        );
+    }
+}
@@ -0,0 +1,331 @@
+// Copyright © 2025 HTML Generator. All rights reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Callbacks between the stages of a conversion.
+//!
+//! [`PipelineHooks`] set in
+//! [`HtmlConfig::hooks`](crate::HtmlConfig::hooks) receive the
+//! intermediate content of every conversion and may change it in place:
+//!
+//! | Stage | Content |
+//! |-------|---------|
+//! | [`HookStage::BeforeParse`] | The Markdown, after comment directives and repairs |
+//! | [`HookStage::AfterMarkdown`] | The HTML rendered from the Markdown |
+//! | [`HookStage::AfterEnhance`] | The HTML after the post-processing [pipeline](crate::pipeline) |
+//! | [`HookStage::BeforeWrite`] | The final HTML of a page written to a file or stream |
+//!
+//! [`HookStage::BeforeWrite`] only runs in the file and batch APIs,
+//! where [`HookContext::source`] is the path of the page; the string
+//! APIs leave it unset unless [`PipelineHooks::with_source`] is used.
+//! Hooks are meant for small, targeted changes; reusable
+//! post-processors are better written as [plugins](crate::plugins).
+//!
+//! # Examples
+//!
+//! ```
+//! use html_generator::hooks::PipelineHooks;
+//! use html_generator::{conversion::convert, HtmlConfig};
+//!
+//! let hooks = PipelineHooks::new().after_enhance(|context| {
+//!     if context.source == Some("drafts/post.md") {
+//!         context.content.insert_str(0, "<p class=\"banner\">Draft</p>\n");
+//!     }
+//!     Ok(())
+//! });
+//! let config = HtmlConfig {
+//!     hooks: hooks.with_source("drafts/post.md"),
+//!     ..HtmlConfig::default()
+//! };
+//! assert_eq!(
+//!     convert("Hello", &config)?,
+//!     "<p class=\"banner\">Draft</p>\n<p>Hello</p>\n"
+//! );
+//! # Ok::<(), html_generator::error::HtmlError>(())
+//! ```
+
+use crate::error::Result;
+use alloc::{
+    string::{String, ToString},
+    sync::Arc,
+    vec::Vec,
+};
+use core::fmt;
+
+/// A stage of a conversion at which [`PipelineHooks`] run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HookStage {
+    /// Before the Markdown is parsed.
+    BeforeParse,
+    /// After the Markdown is rendered to HTML.
+    AfterMarkdown,
+    /// After the post-processing pipeline.
+    AfterEnhance,
+    /// Before the HTML is written.
+    BeforeWrite,
+}
+
+impl HookStage {
+    /// All stages, in the order they run.
+    pub const ALL: [Self; 4] = [
+        Self::BeforeParse,
+        Self::AfterMarkdown,
+        Self::AfterEnhance,
+        Self::BeforeWrite,
+    ];
+}
+
+impl fmt::Display for HookStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::BeforeParse => "before_parse",
+            Self::AfterMarkdown => "after_markdown",
+            Self::AfterEnhance => "after_enhance",
+            Self::BeforeWrite => "before_write",
+        })
+    }
+}
+
+/// The state passed to a hook.
+#[derive(Debug)]
+pub struct HookContext<'a> {
+    /// The stage the hook runs at.
+    pub stage: HookStage,
+    /// Path of the page being converted, when known.
+    pub source: Option<&'a str>,
+    /// The Markdown or HTML of the stage, to change in place.
+    pub content: &'a mut String,
+}
+
+/// A hook run at one [`HookStage`].
+pub type Hook =
+    dyn Fn(&mut HookContext<'_>) -> Result<()> + Send + Sync;
+
+/// Callbacks run at each [`HookStage`] of a conversion.
+#[derive(Clone, Default)]
+pub struct PipelineHooks {
+    hooks: [Option<Arc<Hook>>; 4],
+    source: Option<String>,
+}
+
+impl PipelineHooks {
+    /// Creates hooks that do nothing.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the hook run at `stage`, replacing any previous one.
+    ///
+    /// # Arguments
+    ///
+    /// * `stage` - When the hook runs
+    /// * `hook` - Receives the content of the stage
+    #[must_use]
+    pub fn on<F>(mut self, stage: HookStage, hook: F) -> Self
+    where
+        F: Fn(&mut HookContext<'_>) -> Result<()>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.hooks[stage as usize] = Some(Arc::new(hook));
+        self
+    }
+
+    /// Sets the hook run on the Markdown before it is parsed.
+    #[must_use]
+    pub fn before_parse<F>(self, hook: F) -> Self
+    where
+        F: Fn(&mut HookContext<'_>) -> Result<()>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.on(HookStage::BeforeParse, hook)
+    }
+
+    /// Sets the hook run on the HTML rendered from the Markdown.
+    #[must_use]
+    pub fn after_markdown<F>(self, hook: F) -> Self
+    where
+        F: Fn(&mut HookContext<'_>) -> Result<()>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.on(HookStage::AfterMarkdown, hook)
+    }
+
+    /// Sets the hook run on the HTML after the post-processing
+    /// pipeline.
+    #[must_use]
+    pub fn after_enhance<F>(self, hook: F) -> Self
+    where
+        F: Fn(&mut HookContext<'_>) -> Result<()>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.on(HookStage::AfterEnhance, hook)
+    }
+
+    /// Sets the hook run on the HTML before it is written.
+    #[must_use]
+    pub fn before_write<F>(self, hook: F) -> Self
+    where
+        F: Fn(&mut HookContext<'_>) -> Result<()>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.on(HookStage::BeforeWrite, hook)
+    }
+
+    /// Sets the path of the page passed to the hooks.
+    ///
+    /// The file and batch APIs set it to the path of each input.
+    #[must_use]
+    pub fn with_source(mut self, source: impl Into<String>) -> Self {
+        self.source = Some(source.into());
+        self
+    }
+
+    /// Returns the path of the page passed to the hooks.
+    pub fn source(&self) -> Option<&str> {
+        self.source.as_deref()
+    }
+
+    /// Returns `true` if a hook is set for `stage`.
+    pub fn has(&self, stage: HookStage) -> bool {
+        self.hooks[stage as usize].is_some()
+    }
+
+    /// Returns `true` if no hook is set.
+    pub fn is_empty(&self) -> bool {
+        self.hooks.iter().all(Option::is_none)
+    }
+
+    /// Runs the hook of `stage` on `content`, if one is set.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the hook.
+    pub fn run(
+        &self,
+        stage: HookStage,
+        content: &mut String,
+    ) -> Result<()> {
+        match &self.hooks[stage as usize] {
+            Some(hook) => hook(&mut HookContext {
+                stage,
+                source: self.source.as_deref(),
+                content,
+            }),
+            None => Ok(()),
+        }
+    }
+}
+
+impl fmt::Debug for PipelineHooks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let stages: Vec<_> = HookStage::ALL
+            .iter()
+            .filter(|stage| self.has(**stage))
+            .map(ToString::to_string)
+            .collect();
+        f.debug_struct("PipelineHooks")
+            .field("stages", &stages)
+            .field("source", &self.source)
+            .finish()
+    }
+}
+
+impl PartialEq for PipelineHooks {
+    /// Hooks are equal if they share the same callback instances and
+    /// source.
+    fn eq(&self, other: &Self) -> bool {
+        self.source == other.source
+            && self.hooks.iter().zip(&other.hooks).all(
+                |pair| match pair {
+                    (Some(hook), Some(other)) => {
+                        Arc::ptr_eq(hook, other)
+                    }
+                    (None, None) => true,
+                    _ => false,
+                },
+            )
+    }
+}
+
+impl Eq for PipelineHooks {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conversion::convert;
+    use crate::error::HtmlError;
+    use crate::HtmlConfig;
+    use std::sync::Mutex;
+
+    #[test]
+    fn test_hooks_run_at_each_stage() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let record = |seen: &Arc<Mutex<Vec<String>>>| {
+            let seen = Arc::clone(seen);
+            move |context: &mut HookContext<'_>| {
+                seen.lock().unwrap().push(format!(
+                    "{}: {}",
+                    context.stage,
+                    context.content.trim_end()
+                ));
+                Ok(())
+            }
+        };
+        let hooks = PipelineHooks::new()
+            .before_parse(|context| {
+                context.content.push_str(" :smile:");
+                Ok(())
+            })
+            .after_markdown(record(&seen))
+            .after_enhance(record(&seen))
+            .before_write(|_| {
+                Err(HtmlError::InvalidInput("not written".to_string()))
+            });
+        let config = HtmlConfig {
+            hooks,
+            ..HtmlConfig::default()
+        };
+        let html = convert("Hi", &config).unwrap();
+        assert_eq!(html, "<p>Hi :smile:</p>\n");
+        assert_eq!(
+            *seen.lock().unwrap(),
+            [
+                "after_markdown: <p>Hi :smile:</p>",
+                "after_enhance: <p>Hi :smile:</p>"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_hook_errors_and_equality() {
+        let hooks = PipelineHooks::new().after_markdown(|_| {
+            Err(HtmlError::InvalidInput("rejected".to_string()))
+        });
+        let config = HtmlConfig {
+            hooks: hooks.clone(),
+            ..HtmlConfig::default()
+        };
+        assert!(convert("Hi", &config).is_err());
+
+        assert_eq!(hooks.clone(), hooks);
+        assert_ne!(hooks.clone().with_source("a.md"), hooks);
+        assert_ne!(
+            PipelineHooks::new().after_markdown(|_| Ok(())),
+            PipelineHooks::new().after_markdown(|_| Ok(()))
+        );
+        assert!(PipelineHooks::new().is_empty());
+        assert_eq!(
+            format!("{:?}", hooks.with_source("a.md")),
+            r#"PipelineHooks { stages: ["after_markdown"], source: Some("a.md") }"#
+        );
+    }
+}
@@ -0,0 +1,220 @@
+// Copyright © 2025 HTML Generator. All rights reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Framework-agnostic hydration markers for interactive widgets.
+//!
+//! The accessibility passes turn tabs, accordions and modals into
+//! correctly labelled markup, but the markup is static. With
+//! [`AccessibilityConfig::hydration_attributes`] enabled, each widget's
+//! root element also carries a `data-hg-widget` attribute naming the
+//! widget and a `data-hg-config` attribute holding a JSON object with
+//! the IDs the widget needs, so any frontend script can attach behaviour
+//! without re-deriving the structure:
+//!
+//! ```html
+//! <div role="tablist" data-hg-widget="tabs"
+//!      data-hg-config="{&quot;panels&quot;:[&quot;panel1&quot;],...}">
+//! ```
+//!
+//! | Widget | Config |
+//! |--------|--------|
+//! | `tabs` | `tabs` and `panels` (element IDs), `selected` (index) |
+//! | `accordion` | `sections`, each with `button` and `panel` IDs |
+//! | `modal` | `role`, and `describedby` (ID or `null`) |
+//!
+//! With the `hydration-script` feature, `hydration_script` generates a
+//! small dependency-free script implementing the behaviour.
+//!
+//! [`AccessibilityConfig::hydration_attributes`]:
+//!     crate::accessibility::AccessibilityConfig::hydration_attributes
+
+use crate::seo::escape_html;
+use serde_json::Value;
+use std::fmt;
+
+/// Attribute naming the widget type.
+pub const WIDGET_ATTRIBUTE: &str = "data-hg-widget";
+
+/// Attribute holding the widget's JSON configuration.
+pub const CONFIG_ATTRIBUTE: &str = "data-hg-config";
+
+/// An interactive widget produced by the accessibility passes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Widget {
+    /// A `role="tablist"` with its tab panels.
+    Tabs,
+    /// An `.accordion` of buttons and collapsible sections.
+    Accordion,
+    /// A `.modal` dialog.
+    Modal,
+}
+
+impl Widget {
+    /// All widget types.
+    pub const ALL: [Widget; 3] =
+        [Widget::Tabs, Widget::Accordion, Widget::Modal];
+
+    /// Returns the value used in the `data-hg-widget` attribute.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Widget::Tabs => "tabs",
+            Widget::Accordion => "accordion",
+            Widget::Modal => "modal",
+        }
+    }
+}
+
+impl fmt::Display for Widget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Formats the hydration attributes of a widget.
+///
+/// # Arguments
+///
+/// * `widget` - The widget type
+/// * `config` - The widget configuration
+///
+/// # Returns
+///
+/// The attributes, separated by a space and without surrounding
+/// whitespace, with the JSON escaped for use in a double-quoted value.
+///
+/// # Examples
+///
+/// ```
+/// use html_generator::hydration::{hydration_attributes, Widget};
+/// use serde_json::json;
+///
+/// assert_eq!(
+///     hydration_attributes(Widget::Modal, &json!({"role": "dialog"})),
+///     r#"data-hg-widget="modal" data-hg-config="{&quot;role&quot;:&quot;dialog&quot;}""#
+/// );
+/// ```
+pub fn hydration_attributes(widget: Widget, config: &Value) -> String {
+    format!(
+        r#"{}="{}" {}="{}""#,
+        WIDGET_ATTRIBUTE,
+        widget,
+        CONFIG_ATTRIBUTE,
+        escape_html(&config.to_string())
+    )
+}
+
+#[cfg(feature = "hydration-script")]
+const TABS_SCRIPT: &str = r#"  document.querySelectorAll('[data-hg-widget="tabs"]').forEach(function (list) {
+    var config = JSON.parse(list.getAttribute('data-hg-config'));
+    var byId = function (id) { return document.getElementById(id); };
+    var tabs = config.tabs.map(byId);
+    var panels = config.panels.map(byId);
+    function select(index) {
+      tabs.forEach(function (tab, i) {
+        tab.setAttribute('aria-selected', String(i === index));
+        tab.tabIndex = i === index ? 0 : -1;
+        if (panels[i]) { panels[i].hidden = i !== index; }
+      });
+      tabs[index].focus();
+    }
+    tabs.forEach(function (tab, i) {
+      tab.addEventListener('click', function () { select(i); });
+      tab.addEventListener('keydown', function (event) {
+        if (event.key === 'ArrowRight') { select((i + 1) % tabs.length); }
+        if (event.key === 'ArrowLeft') { select((i + tabs.length - 1) % tabs.length); }
+      });
+    });
+  });
+"#;
+
+#[cfg(feature = "hydration-script")]
+const ACCORDION_SCRIPT: &str = r#"  document.querySelectorAll('[data-hg-widget="accordion"]').forEach(function (accordion) {
+    var config = JSON.parse(accordion.getAttribute('data-hg-config'));
+    config.sections.forEach(function (section) {
+      var button = document.getElementById(section.button);
+      var panel = document.getElementById(section.panel);
+      button.addEventListener('click', function () {
+        var expanded = button.getAttribute('aria-expanded') === 'true';
+        button.setAttribute('aria-expanded', String(!expanded));
+        panel.hidden = expanded;
+      });
+    });
+  });
+"#;
+
+#[cfg(feature = "hydration-script")]
+const MODAL_SCRIPT: &str = r#"  document.querySelectorAll('[data-hg-widget="modal"]').forEach(function (modal) {
+    modal.addEventListener('keydown', function (event) {
+      if (event.key === 'Escape') { modal.hidden = true; }
+    });
+  });
+"#;
+
+/// Generates a vanilla JavaScript snippet that hydrates `widgets`.
+///
+/// The script has no dependencies and only handles the listed widget
+/// types; include it at the end of the page body.
+///
+/// # Arguments
+///
+/// * `widgets` - The widget types to support
+///
+/// # Examples
+///
+/// ```
+/// use html_generator::hydration::{hydration_script, Widget};
+///
+/// let script = hydration_script(&[Widget::Tabs]);
+/// assert!(script.contains(r#"[data-hg-widget="tabs"]"#));
+/// assert!(!script.contains("accordion"));
+/// ```
+#[cfg(feature = "hydration-script")]
+pub fn hydration_script(widgets: &[Widget]) -> String {
+    let mut script = String::from("(function () {\n  'use strict';\n");
+    for widget in Widget::ALL {
+        if widgets.contains(&widget) {
+            script.push_str(match widget {
+                Widget::Tabs => TABS_SCRIPT,
+                Widget::Accordion => ACCORDION_SCRIPT,
+                Widget::Modal => MODAL_SCRIPT,
+            });
+        }
+    }
+    script.push_str("})();\n");
+    script
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_hydration_attributes_escape_json() {
+        let attributes = hydration_attributes(
+            Widget::Tabs,
+            &json!({"tabs": ["a\"b"], "selected": 0}),
+        );
+        assert!(attributes.starts_with(r#"data-hg-widget="tabs" "#));
+        assert!(!attributes["data-hg-widget=\"tabs\" ".len()..]
+            .trim_start_matches("data-hg-config=\"")
+            .trim_end_matches('"')
+            .contains('"'));
+    }
+
+    #[cfg(feature = "hydration-script")]
+    #[test]
+    fn test_hydration_script_includes_requested_widgets() {
+        let script = hydration_script(&Widget::ALL);
+        for widget in Widget::ALL {
+            assert!(script.contains(&format!(
+                r#"[data-hg-widget="{}"]"#,
+                widget
+            )));
+        }
+        assert_eq!(
+            hydration_script(&[]),
+            "(function () {\n  'use strict';\n})();\n"
+        );
+    }
+}
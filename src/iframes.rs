@@ -0,0 +1,257 @@
+// Copyright © 2025 HTML Generator. All rights reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Sandboxing of embedded frames.
+//!
+//! Raw HTML in Markdown can embed any page with an `<iframe>`. With
+//! [`HtmlConfig::iframe_policy`](crate::HtmlConfig::iframe_policy) set,
+//! every iframe of the output gets the `sandbox`, `loading` and
+//! `referrerpolicy` attributes of the [`IframePolicy`], replacing those
+//! written by the author, and iframes embedding a host that is not on
+//! the allowlist are removed and reported as [`BlockedIframe`]s.
+//!
+//! # Examples
+//!
+//! ```
+//! use html_generator::iframes::IframePolicy;
+//!
+//! let policy = IframePolicy {
+//!     allowed_hosts: vec!["youtube-nocookie.com".to_string()],
+//!     ..IframePolicy::default()
+//! };
+//! let (html, blocked) = policy.apply(concat!(
+//!     r#"<iframe src="https://www.youtube-nocookie.com/embed/x"></iframe>"#,
+//!     r#"<iframe src="https://evil.example/"></iframe>"#,
+//! ));
+//! assert_eq!(
+//!     html,
+//!     concat!(
+//!         r#"<iframe src="https://www.youtube-nocookie.com/embed/x" "#,
+//!         r#"sandbox="allow-scripts allow-same-origin allow-presentation" "#,
+//!         r#"loading="lazy" referrerpolicy="strict-origin-when-cross-origin">"#,
+//!         "</iframe>"
+//!     )
+//! );
+//! assert_eq!(blocked[0].host, "evil.example");
+//! ```
+
+use crate::diagnostics::Diagnostic;
+use crate::patterns::iframes::{ATTRIBUTE_REGEX, IFRAME_REGEX};
+use crate::utils::{domain_matches, external_host};
+use regex::Captures;
+use std::fmt;
+
+/// Attributes set by the policy.
+const ENFORCED_ATTRIBUTES: &[&str] =
+    &["sandbox", "loading", "referrerpolicy"];
+
+/// Rules for the iframes of the generated HTML.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schema", schemars(deny_unknown_fields))]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
+pub struct IframePolicy {
+    /// Value of the `sandbox` attribute; an empty string applies every
+    /// restriction.
+    pub sandbox: String,
+    /// Defer loading frames until they are near the viewport.
+    pub lazy_loading: bool,
+    /// Value of the `referrerpolicy` attribute.
+    pub referrer_policy: String,
+    /// Hosts that may be embedded, matching their subdomains too. An
+    /// empty list allows every host. Frames with a relative `src` or
+    /// none are always kept.
+    pub allowed_hosts: Vec<String>,
+}
+
+impl Default for IframePolicy {
+    fn default() -> Self {
+        Self {
+            sandbox:
+                "allow-scripts allow-same-origin allow-presentation"
+                    .to_string(),
+            lazy_loading: true,
+            referrer_policy: "strict-origin-when-cross-origin"
+                .to_string(),
+            allowed_hosts: Vec::new(),
+        }
+    }
+}
+
+impl IframePolicy {
+    /// Applies the policy to the iframes of `html`.
+    ///
+    /// # Arguments
+    ///
+    /// * `html` - The HTML to rewrite
+    ///
+    /// # Returns
+    ///
+    /// The rewritten HTML and the removed iframes, in document order.
+    pub fn apply(&self, html: &str) -> (String, Vec<BlockedIframe>) {
+        let mut blocked = Vec::new();
+        let html =
+            IFRAME_REGEX.replace_all(html, |caps: &Captures<'_>| {
+                let mut src = None;
+                let mut attributes = String::new();
+                for attribute in ATTRIBUTE_REGEX.captures_iter(&caps[1])
+                {
+                    let name = attribute[1].to_ascii_lowercase();
+                    if name == "src" {
+                        src = attribute
+                            .get(2)
+                            .or_else(|| attribute.get(3))
+                            .or_else(|| attribute.get(4))
+                            .map(|m| m.as_str().to_string());
+                    }
+                    if !ENFORCED_ATTRIBUTES.contains(&name.as_str()) {
+                        attributes.push_str(&attribute[0]);
+                    }
+                }
+                if let Some((src, host)) =
+                    src.as_deref().and_then(|src| {
+                        let (_, host) = external_host(src)?;
+                        Some((src, host))
+                    })
+                {
+                    if !self.allows(&host) {
+                        blocked.push(BlockedIframe {
+                            src: src.to_string(),
+                            host,
+                        });
+                        return String::new();
+                    }
+                }
+                attributes.push_str(&format!(
+                    r#" sandbox="{}""#,
+                    self.sandbox
+                ));
+                if self.lazy_loading {
+                    attributes.push_str(r#" loading="lazy""#);
+                }
+                attributes.push_str(&format!(
+                    r#" referrerpolicy="{}""#,
+                    self.referrer_policy
+                ));
+                format!("<iframe{}></iframe>", attributes)
+            });
+        (html.into_owned(), blocked)
+    }
+
+    /// Returns `true` if frames from `host` may be embedded.
+    fn allows(&self, host: &str) -> bool {
+        self.allowed_hosts.is_empty()
+            || self
+                .allowed_hosts
+                .iter()
+                .any(|allowed| domain_matches(host, allowed))
+    }
+}
+
+/// An iframe removed by [`IframePolicy::apply`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockedIframe {
+    /// The `src` of the frame.
+    pub src: String,
+    /// The lowercase host of `src`.
+    pub host: String,
+}
+
+impl BlockedIframe {
+    /// Converts the finding into a warning [`Diagnostic`] with the code
+    /// `blocked_iframe`.
+    pub fn to_diagnostic(&self) -> Diagnostic {
+        Diagnostic::warning("blocked_iframe", self.to_string())
+    }
+}
+
+impl fmt::Display for BlockedIframe {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Removed iframe '{}': '{}' is not an allowed host",
+            self.src, self.host
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conversion::convert_detailed;
+    use crate::HtmlConfig;
+
+    #[test]
+    fn test_attributes_are_enforced() {
+        let policy = IframePolicy {
+            sandbox: String::new(),
+            lazy_loading: false,
+            referrer_policy: "no-referrer".to_string(),
+            ..IframePolicy::default()
+        };
+        let html = concat!(
+            r#"<iframe title="Map" SANDBOX="allow-top-navigation" "#,
+            r#"loading=eager src='/map.html' allowfullscreen>"#,
+            "fallback</iframe><p>after</p><iframe srcdoc=\"x\">"
+        );
+        let (html, blocked) = policy.apply(html);
+        assert_eq!(
+            html,
+            concat!(
+                r#"<iframe title="Map" src='/map.html' allowfullscreen "#,
+                r#"sandbox="" referrerpolicy="no-referrer"></iframe>"#,
+                r#"<p>after</p><iframe srcdoc="x" sandbox="" "#,
+                r#"referrerpolicy="no-referrer"></iframe>"#
+            )
+        );
+        assert!(blocked.is_empty());
+        assert_eq!(policy.apply(&html).0, html);
+    }
+
+    #[test]
+    fn test_hosts_outside_the_allowlist_are_removed() {
+        let policy = IframePolicy {
+            allowed_hosts: vec!["player.example".to_string()],
+            ..IframePolicy::default()
+        };
+        let (html, blocked) = policy.apply(concat!(
+            r#"<iframe src="//cdn.player.example/v/1"></iframe>"#,
+            r#"<iframe src="https://player.example.evil.org/"></iframe>"#,
+            r#"<IFRAME SRC="HTTP://Tracker.Example/x"/>"#
+        ));
+        assert!(html.contains("cdn.player.example"));
+        assert_eq!(html.matches("<iframe").count(), 1);
+        let hosts: Vec<_> =
+            blocked.iter().map(|b| b.host.as_str()).collect();
+        assert_eq!(
+            hosts,
+            ["player.example.evil.org", "tracker.example"]
+        );
+        assert_eq!(blocked[1].to_diagnostic().code, "blocked_iframe");
+    }
+
+    #[test]
+    fn test_conversion_applies_the_policy() {
+        let markdown = "<iframe src=\"https://other.example/\"></iframe>\n\n<iframe src=\"https://ok.example/\"></iframe>\n";
+        let config = HtmlConfig {
+            iframe_policy: Some(IframePolicy {
+                allowed_hosts: vec!["ok.example".to_string()],
+                ..IframePolicy::default()
+            }),
+            ..HtmlConfig::default()
+        };
+        let conversion = convert_detailed(markdown, &config).unwrap();
+        assert!(!conversion.html.contains("other.example"));
+        assert!(conversion.html.contains(r#"loading="lazy""#));
+        assert_eq!(conversion.blocked_iframes.len(), 1);
+
+        let conversion =
+            convert_detailed(markdown, &HtmlConfig::default()).unwrap();
+        assert!(conversion.html.contains("other.example"));
+        assert!(conversion.blocked_iframes.is_empty());
+    }
+}
@@ -0,0 +1,201 @@
+// Copyright © 2025 HTML Generator. All rights reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! `.gitignore`-style ignore rules for batch conversion.
+//!
+//! The batch directory walker reads a [`IGNORE_FILE`] in every
+//! directory it visits and skips matching files and directories, so
+//! drafts, `node_modules` or editor temp files are neither converted
+//! nor copied.
+//!
+//! Each line of an ignore file holds one pattern:
+//!
+//! - Blank lines and lines starting with `#` are ignored
+//! - A leading `!` re-includes paths excluded by an earlier pattern
+//! - A trailing `/` only matches directories
+//! - A pattern containing `/` is relative to the directory of the
+//!   ignore file; other patterns match a name at any depth
+//!
+//! Patterns use the syntax of [`glob_match`]. When several patterns
+//! match a path, the last one wins.
+//!
+//! # Examples
+//!
+//! ```
+//! use html_generator::ignore::IgnoreRules;
+//!
+//! let mut rules = IgnoreRules::new();
+//! rules.add_file("drafts/\n*.swp\n!keep.swp", "");
+//!
+//! assert!(rules.is_ignored("blog/drafts", true));
+//! assert!(rules.is_ignored("notes.swp", false));
+//! assert!(!rules.is_ignored("keep.swp", false));
+//! assert!(!rules.is_ignored("index.md", false));
+//! ```
+
+use crate::utils::glob_match;
+
+/// Name of the ignore file read in each input directory.
+pub const IGNORE_FILE: &str = ".htmlgenignore";
+
+/// A single ignore pattern.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct IgnoreRule {
+    /// Directory the pattern is relative to, without a trailing `/`.
+    base: String,
+    /// The glob pattern.
+    pattern: String,
+    /// `true` for `!` patterns that re-include paths.
+    negated: bool,
+    /// `true` for patterns that only match directories.
+    dir_only: bool,
+}
+
+impl IgnoreRule {
+    /// Returns `true` if the rule applies to `path`.
+    fn matches(&self, path: &str, is_dir: bool) -> bool {
+        if self.dir_only && !is_dir {
+            return false;
+        }
+        let relative = if self.base.is_empty() {
+            path
+        } else {
+            match path
+                .strip_prefix(self.base.as_str())
+                .and_then(|rest| rest.strip_prefix('/'))
+            {
+                Some(rest) => rest,
+                None => return false,
+            }
+        };
+        glob_match(&self.pattern, relative)
+    }
+}
+
+/// An ordered set of ignore patterns.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IgnoreRules {
+    rules: Vec<IgnoreRule>,
+}
+
+impl IgnoreRules {
+    /// Creates an empty rule set that ignores nothing.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a single pattern relative to the input root.
+    ///
+    /// # Arguments
+    ///
+    /// * `pattern` - A pattern in ignore file syntax.
+    pub fn add(&mut self, pattern: &str) {
+        self.add_relative(pattern, "");
+    }
+
+    /// Adds every pattern of an ignore file.
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - The content of the ignore file.
+    /// * `base` - Directory of the ignore file, relative to the input
+    ///   root, using `/` separators. Use `""` for the root.
+    pub fn add_file(&mut self, content: &str, base: &str) {
+        for line in content.lines() {
+            self.add_relative(line, base);
+        }
+    }
+
+    /// Returns the number of patterns.
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    /// Returns `true` if there are no patterns.
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Returns `true` if `path` is ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path relative to the input root, using `/`
+    ///   separators.
+    /// * `is_dir` - Whether the path is a directory.
+    pub fn is_ignored(&self, path: &str, is_dir: bool) -> bool {
+        let path = path.trim_matches('/');
+        self.rules
+            .iter()
+            .rev()
+            .find(|rule| rule.matches(path, is_dir))
+            .map_or(false, |rule| !rule.negated)
+    }
+
+    /// Parses and adds a pattern relative to `base`.
+    fn add_relative(&mut self, line: &str, base: &str) {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            return;
+        }
+        let (negated, pattern) = match line.strip_prefix('!') {
+            Some(pattern) => (true, pattern),
+            None => (false, line),
+        };
+        let dir_only = pattern.ends_with('/');
+        let pattern = pattern.trim_end_matches('/');
+        if pattern.is_empty() {
+            return;
+        }
+        self.rules.push(IgnoreRule {
+            base: base.trim_matches('/').to_string(),
+            pattern: pattern.to_string(),
+            negated,
+            dir_only,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_comments_and_blank_lines() {
+        let mut rules = IgnoreRules::new();
+        rules.add_file("# drafts\n\n   \n", "");
+        assert!(rules.is_empty());
+    }
+
+    #[test]
+    fn test_dir_only_patterns() {
+        let mut rules = IgnoreRules::new();
+        rules.add("node_modules/");
+        assert!(rules.is_ignored("node_modules", true));
+        assert!(rules.is_ignored("a/node_modules", true));
+        assert!(!rules.is_ignored("node_modules", false));
+    }
+
+    #[test]
+    fn test_negation_last_match_wins() {
+        let mut rules = IgnoreRules::new();
+        rules.add_file("*.md\n!index.md", "");
+        assert!(rules.is_ignored("a.md", false));
+        assert!(!rules.is_ignored("index.md", false));
+
+        rules.add("index.md");
+        assert!(rules.is_ignored("index.md", false));
+    }
+
+    #[test]
+    fn test_nested_ignore_file_is_scoped() {
+        let mut rules = IgnoreRules::new();
+        rules.add_file("*~\n/private.md", "docs");
+        assert!(rules.is_ignored("docs/a.md~", false));
+        assert!(rules.is_ignored("docs/deep/a.md~", false));
+        assert!(rules.is_ignored("docs/private.md", false));
+        assert!(!rules.is_ignored("docs/deep/private.md", false));
+        assert!(!rules.is_ignored("a.md~", false));
+        assert!(!rules.is_ignored("private.md", false));
+    }
+}
@@ -0,0 +1,540 @@
+// Copyright © 2025 HTML Generator. All rights reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Inclusion of shared Markdown snippets.
+//!
+//! With [`MarkdownConfig::expand_includes`](crate::MarkdownConfig::expand_includes)
+//! set, the file and batch APIs replace every
+//!
+//! ```markdown
+//! {{include "snippets/install.md"}}
+//! ```
+//!
+//! with the content of the named file, without its front matter,
+//! before the document is converted. Paths are relative to the file
+//! containing the directive and must stay under the include root: the
+//! directory of the input file, or the input directory of a batch. They
+//! cannot be absolute, and must name `.md` or `.html` files.
+//!
+//! Symbolic and hard links are treated as the [`SymlinkPolicy`] says:
+//! includes read through a symbolic link are rejected unless it is
+//! followed, and then its target must be under the include root too.
+//! [`SymlinkPolicy::Error`] also rejects hard-linked files.
+//!
+//! Included files may include others, up to
+//! [`MarkdownConfig::max_include_depth`](crate::MarkdownConfig::max_include_depth)
+//! levels; a file including itself, directly or not, is an error.
+//! Directives in fenced code blocks are left alone, so the syntax can
+//! be documented.
+//!
+//! Snippets below the input directory of a batch are converted like
+//! any other page unless they are excluded with
+//! [`BatchConfig::ignore`](crate::batch::BatchConfig::ignore).
+//!
+//! # Examples
+//!
+//! ```
+//! use html_generator::batch::SymlinkPolicy;
+//! use html_generator::includes::expand_includes;
+//!
+//! let dir = tempfile::tempdir()?;
+//! std::fs::write(dir.path().join("note.md"), "---\ntitle: Note\n---\n> Shared note\n")?;
+//!
+//! let markdown = expand_includes(
+//!     "# Guide\n{{include \"note.md\"}}\n",
+//!     dir.path(),
+//!     None,
+//!     8,
+//!     SymlinkPolicy::Skip,
+//! )?;
+//! assert_eq!(markdown, "# Guide\n> Shared note\n");
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use crate::batch::{hard_link_id, SymlinkPolicy};
+use crate::error::{HtmlError, Result};
+use crate::front_matter::{parse_front_matter, FrontMatterFormat};
+use crate::patterns::includes::{FENCE_REGEX, INCLUDE_REGEX};
+use crate::HtmlConfig;
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+/// Replaces the include directives of `markdown` with the files they
+/// name.
+///
+/// # Arguments
+///
+/// * `markdown` - The document
+/// * `root` - The directory included files must be under
+/// * `source` - Path of the document relative to `root`, if it is a
+///   file there. Its includes are relative to its directory, and it
+///   cannot include itself. Includes of other documents are relative
+///   to `root`.
+/// * `max_depth` - How many levels of includes are allowed
+/// * `symlinks` - How links to included files are treated
+///
+/// # Returns
+///
+/// The document with its includes expanded.
+///
+/// # Errors
+///
+/// Returns [`HtmlError::InvalidInput`] for invalid paths, paths
+/// outside `root`, links rejected by `symlinks`, cycles and includes
+/// nested deeper than `max_depth`, and [`HtmlError::Io`] if an included
+/// file cannot be read.
+pub fn expand_includes(
+    markdown: &str,
+    root: &Path,
+    source: Option<&Path>,
+    max_depth: usize,
+    symlinks: SymlinkPolicy,
+) -> Result<String> {
+    expand_includes_tracked(markdown, root, source, max_depth, symlinks)
+        .map(|(markdown, _)| markdown)
+}
+
+/// Expands includes like [`expand_includes`] and also returns the
+/// included files.
+///
+/// # Returns
+///
+/// The expanded document and the paths of the included files relative
+/// to `root`, in the order they were first included, e.g. to watch them
+/// for changes.
+///
+/// # Errors
+///
+/// Returns the same errors as [`expand_includes`].
+pub fn expand_includes_tracked(
+    markdown: &str,
+    root: &Path,
+    source: Option<&Path>,
+    max_depth: usize,
+    symlinks: SymlinkPolicy,
+) -> Result<(String, Vec<PathBuf>)> {
+    let mut stack: Vec<PathBuf> =
+        source.map(normalize).into_iter().collect();
+    let mut included = Vec::new();
+    let context = Context {
+        root,
+        max_depth,
+        symlinks,
+    };
+    let markdown =
+        expand(markdown, &context, 0, &mut stack, &mut included)?;
+    Ok((markdown, included))
+}
+
+/// The settings shared by every level of an expansion.
+struct Context<'a> {
+    root: &'a Path,
+    max_depth: usize,
+    symlinks: SymlinkPolicy,
+}
+
+/// Expands the includes of `markdown`, included `depth` levels deep
+/// from the files of `stack`, adding the files read to `included`.
+///
+/// Include paths are relative to the directory of the last file of
+/// `stack`, or to the root if it is empty.
+fn expand(
+    markdown: &str,
+    context: &Context<'_>,
+    depth: usize,
+    stack: &mut Vec<PathBuf>,
+    included: &mut Vec<PathBuf>,
+) -> Result<String> {
+    let mut output = String::with_capacity(markdown.len());
+    let mut fence: Option<&str> = None;
+    for line in markdown.split_inclusive('\n') {
+        if let Some(caps) = FENCE_REGEX.captures(line) {
+            let marker = caps.get(1).map_or("", |m| m.as_str());
+            match fence {
+                None => fence = Some(marker),
+                Some(opening)
+                    if marker.starts_with(opening)
+                        && line.trim_end().ends_with(marker) =>
+                {
+                    fence = None;
+                }
+                Some(_) => {}
+            }
+            output.push_str(line);
+            continue;
+        }
+        if fence.is_some() {
+            output.push_str(line);
+            continue;
+        }
+        let mut last = 0;
+        for caps in INCLUDE_REGEX.captures_iter(line) {
+            let (start, end) =
+                caps.get(0).map_or((0, 0), |m| (m.start(), m.end()));
+            output.push_str(&line[last..start]);
+            if depth >= context.max_depth {
+                return Err(HtmlError::InvalidInput(format!(
+                    "Include of '{}' exceeds the maximum depth of {}",
+                    &caps[1], context.max_depth
+                )));
+            }
+            let content =
+                include(&caps[1], context, depth + 1, stack, included)?;
+            output.push_str(content.trim_end_matches('\n'));
+            last = end;
+        }
+        output.push_str(&line[last..]);
+    }
+    Ok(output)
+}
+
+/// Reads and expands the file at `path`, relative to the directory of
+/// the last file of `stack`.
+fn include(
+    path: &str,
+    context: &Context<'_>,
+    depth: usize,
+    stack: &mut Vec<PathBuf>,
+    included: &mut Vec<PathBuf>,
+) -> Result<String> {
+    let base = stack.last().and_then(|file| file.parent());
+    let path = resolve(path, base.unwrap_or_else(|| Path::new("")))?;
+    HtmlConfig::validate_file_path(&path)?;
+    if stack.contains(&path) {
+        let chain: Vec<_> = stack
+            .iter()
+            .skip_while(|included| **included != path)
+            .chain(Some(&path))
+            .map(|included| included.display().to_string())
+            .collect();
+        return Err(HtmlError::InvalidInput(format!(
+            "Include cycle: {}",
+            chain.join(" -> ")
+        )));
+    }
+
+    check_links(context, &path)?;
+    let content = fs::read_to_string(context.root.join(&path))
+        .map_err(|e| {
+            HtmlError::Io(io::Error::new(
+                e.kind(),
+                format!(
+                    "Failed to read include '{}': {}",
+                    path.display(),
+                    e
+                ),
+            ))
+        })?;
+    let (_, body) =
+        parse_front_matter(&content, FrontMatterFormat::Auto)?;
+    if !included.contains(&path) {
+        included.push(path.clone());
+    }
+    stack.push(path);
+    let expanded = expand(body, context, depth, stack, included);
+    let _ = stack.pop();
+    expanded
+}
+
+/// Returns the path of the include `path` written in a file of `base`,
+/// both relative to the include root.
+///
+/// # Errors
+///
+/// Returns [`HtmlError::InvalidInput`] if `path` is absolute or leads
+/// outside the include root.
+fn resolve(path: &str, base: &Path) -> Result<PathBuf> {
+    let mut resolved = PathBuf::new();
+    for component in base.join(path).components() {
+        match component {
+            Component::Normal(name) => resolved.push(name),
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !resolved.pop() {
+                    return Err(HtmlError::InvalidInput(format!(
+                        "Include '{}' is outside the include root",
+                        path
+                    )));
+                }
+            }
+            Component::RootDir | Component::Prefix(_) => {
+                return Err(HtmlError::InvalidInput(format!(
+                    "Include '{}' must be a relative path",
+                    path
+                )));
+            }
+        }
+    }
+    Ok(resolved)
+}
+
+/// Applies the symlink policy to the include at `path`, relative to
+/// the include root.
+///
+/// # Errors
+///
+/// Returns [`HtmlError::InvalidInput`] if `path` goes through a
+/// symbolic link that is not followed or whose target is outside the
+/// root, or is hard-linked under [`SymlinkPolicy::Error`].
+fn check_links(context: &Context<'_>, path: &Path) -> Result<()> {
+    let mut current = context.root.to_path_buf();
+    let mut linked = false;
+    for component in path.components() {
+        current.push(component);
+        match fs::symlink_metadata(&current) {
+            Ok(metadata) if metadata.file_type().is_symlink() => {
+                if context.symlinks != SymlinkPolicy::Follow {
+                    return Err(HtmlError::InvalidInput(format!(
+                        "Include '{}' goes through the symbolic link '{}', which is not followed",
+                        path.display(),
+                        current.display()
+                    )));
+                }
+                linked = true;
+            }
+            Ok(_) => {}
+            // Reading the include reports the missing file
+            Err(_) => return Ok(()),
+        }
+    }
+
+    if linked {
+        let root = if context.root.as_os_str().is_empty() {
+            Path::new(".")
+        } else {
+            context.root
+        };
+        let target = fs::canonicalize(&current)?;
+        if !target.starts_with(fs::canonicalize(root)?) {
+            return Err(HtmlError::InvalidInput(format!(
+                "Include '{}' points to '{}', outside the include root",
+                path.display(),
+                target.display()
+            )));
+        }
+    }
+    if context.symlinks == SymlinkPolicy::Error
+        && hard_link_id(&current)?.is_some()
+    {
+        return Err(HtmlError::InvalidInput(format!(
+            "Hard-linked include '{}' is not allowed",
+            path.display()
+        )));
+    }
+    Ok(())
+}
+
+/// Returns `path` without `.` components, so that the same file is
+/// always spelled the same way.
+fn normalize(path: &Path) -> PathBuf {
+    path.components()
+        .filter(|component| !matches!(component, Component::CurDir))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_nested_includes_and_code_blocks() {
+        let dir = tempdir().unwrap();
+        fs::create_dir(dir.path().join("parts")).unwrap();
+        fs::write(
+            dir.path().join("parts/install.md"),
+            "Run {{include \"cmd.md\"}} first.\n",
+        )
+        .unwrap();
+        fs::write(dir.path().join("parts/cmd.md"), "`make`\n").unwrap();
+
+        let markdown = "# Setup\n{{ include \"./parts/install.md\" }}\n```\n{{include \"x.md\"}}\n```\n";
+        let expanded = expand_includes(
+            markdown,
+            dir.path(),
+            None,
+            2,
+            SymlinkPolicy::Skip,
+        )
+        .unwrap();
+        assert_eq!(
+            expanded,
+            "# Setup\nRun `make` first.\n```\n{{include \"x.md\"}}\n```\n"
+        );
+
+        let (_, included) = expand_includes_tracked(
+            markdown,
+            dir.path(),
+            None,
+            2,
+            SymlinkPolicy::Skip,
+        )
+        .unwrap();
+        assert_eq!(
+            included,
+            [
+                PathBuf::from("parts/install.md"),
+                PathBuf::from("parts/cmd.md")
+            ]
+        );
+
+        let error = expand_includes(
+            markdown,
+            dir.path(),
+            None,
+            1,
+            SymlinkPolicy::Skip,
+        )
+        .unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("exceeds the maximum depth of 1"));
+    }
+
+    #[test]
+    fn test_invalid_includes() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("a.md"), "{{include \"b.md\"}}")
+            .unwrap();
+        fs::write(dir.path().join("b.md"), "{{include \"a.md\"}}")
+            .unwrap();
+
+        let error = expand_includes(
+            "{{include \"b.md\"}}",
+            dir.path(),
+            Some(Path::new("a.md")),
+            8,
+            SymlinkPolicy::Skip,
+        )
+        .unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("Include cycle: a.md -> b.md -> a.md"));
+
+        for path in ["../secret.md", "a/../../secret.md", "notes.txt"] {
+            let markdown = format!("{{{{include \"{}\"}}}}", path);
+            assert!(matches!(
+                expand_includes(
+                    &markdown,
+                    dir.path(),
+                    None,
+                    8,
+                    SymlinkPolicy::Skip
+                ),
+                Err(HtmlError::InvalidInput(_))
+            ));
+        }
+        assert!(matches!(
+            expand_includes(
+                "{{include \"missing.md\"}}",
+                dir.path(),
+                None,
+                8,
+                SymlinkPolicy::Skip,
+            ),
+            Err(HtmlError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn test_paths_are_relative_to_the_including_file() {
+        let dir = tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("guide/parts")).unwrap();
+        fs::create_dir(dir.path().join("shared")).unwrap();
+        fs::write(
+            dir.path().join("guide/parts/steps.md"),
+            "Steps, then {{include \"../../shared/note.md\"}}\n",
+        )
+        .unwrap();
+        fs::write(dir.path().join("shared/note.md"), "a note.\n")
+            .unwrap();
+
+        let (expanded, included) = expand_includes_tracked(
+            "{{include \"parts/steps.md\"}}\n",
+            dir.path(),
+            Some(Path::new("guide/setup.md")),
+            8,
+            SymlinkPolicy::Skip,
+        )
+        .unwrap();
+        assert_eq!(expanded, "Steps, then a note.\n");
+        assert_eq!(
+            included,
+            [
+                PathBuf::from("guide/parts/steps.md"),
+                PathBuf::from("shared/note.md")
+            ]
+        );
+
+        let error = expand_includes(
+            "{{include \"../../outside.md\"}}",
+            dir.path(),
+            Some(Path::new("guide/setup.md")),
+            8,
+            SymlinkPolicy::Skip,
+        )
+        .unwrap_err();
+        assert!(error.to_string().contains("outside the include root"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_link_policies() {
+        use std::os::unix::fs::symlink;
+
+        let outside = tempdir().unwrap();
+        fs::write(outside.path().join("secret.md"), "secret\n")
+            .unwrap();
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("note.md"), "note\n").unwrap();
+        symlink(
+            dir.path().join("note.md"),
+            dir.path().join("inside.md"),
+        )
+        .unwrap();
+        symlink(
+            outside.path().join("secret.md"),
+            dir.path().join("outside.md"),
+        )
+        .unwrap();
+        fs::hard_link(
+            dir.path().join("note.md"),
+            dir.path().join("copy.md"),
+        )
+        .unwrap();
+
+        let expand = |path: &str, symlinks| {
+            expand_includes(
+                &format!("{{{{include \"{}\"}}}}", path),
+                dir.path(),
+                None,
+                8,
+                symlinks,
+            )
+        };
+        assert_eq!(
+            expand("inside.md", SymlinkPolicy::Follow).unwrap(),
+            "note"
+        );
+        for symlinks in [SymlinkPolicy::Skip, SymlinkPolicy::Error] {
+            assert!(expand("inside.md", symlinks)
+                .unwrap_err()
+                .to_string()
+                .contains("is not followed"));
+        }
+        assert!(expand("outside.md", SymlinkPolicy::Follow)
+            .unwrap_err()
+            .to_string()
+            .contains("outside the include root"));
+
+        assert_eq!(
+            expand("copy.md", SymlinkPolicy::Skip).unwrap(),
+            "note"
+        );
+        assert!(expand("copy.md", SymlinkPolicy::Error)
+            .unwrap_err()
+            .to_string()
+            .contains("Hard-linked include"));
+    }
+}
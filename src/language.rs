@@ -0,0 +1,457 @@
+// Copyright © 2025 HTML Generator. All rights reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Detection of passages in other languages.
+//!
+//! WCAG 3.1.2 asks for every passage in another language than the page
+//! to be marked with its own `lang` attribute, so screen readers switch
+//! to the right voice. Authors rarely do this in Markdown.
+//! [`detect_language_fragments`] guesses the language of each paragraph,
+//! heading, list item, table cell, definition and caption with
+//! [`whatlang`] and reports those that differ from the document
+//! language; [`add_lang_attributes`] also adds the `lang` attributes.
+//!
+//! Only blocks without nested blocks and with at least
+//! [`MIN_TEXT_CHARS`] characters of text are examined, and only
+//! reliable guesses are kept. Blocks inside an element with a `lang`
+//! attribute are compared with that language instead of the document
+//! language. Languages are written as ISO 639-1 codes such as `fr`.
+//!
+//! This module requires the `language-detection` feature. Add the pass
+//! to a conversion with [`Pipeline::with_step`].
+//!
+//! [`Pipeline::with_step`]: crate::pipeline::Pipeline::with_step
+//!
+//! # Examples
+//!
+//! ```
+//! use html_generator::language::add_lang_attributes;
+//!
+//! let (html, fragments) = add_lang_attributes(
+//!     concat!(
+//!         "<p>The weather was lovely and we walked along the river all afternoon.</p>",
+//!         "<p>Le temps était magnifique et nous avons marché le long de la rivière.</p>",
+//!     ),
+//!     "en-GB",
+//! );
+//! assert!(html.contains(r#"<p lang="fr">Le temps"#));
+//! assert_eq!(fragments[0].language, "fr");
+//! ```
+
+use crate::diagnostics::Diagnostic;
+use crate::patterns::language::{LANG_ATTRIBUTE_REGEX, TAG_REGEX};
+use crate::utils::decode_entities;
+use std::fmt;
+use whatlang::Lang;
+
+/// Minimum number of characters of text in an examined block.
+pub const MIN_TEXT_CHARS: usize = 40;
+
+/// Number of characters of text shown in reports.
+const EXCERPT_CHARS: usize = 40;
+
+/// Elements whose text is examined.
+const BLOCK_ELEMENTS: &[&str] = &[
+    "p",
+    "li",
+    "dd",
+    "dt",
+    "td",
+    "th",
+    "figcaption",
+    "blockquote",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+];
+
+/// Elements whose text is never natural language.
+const SKIPPED_ELEMENTS: &[&str] = &[
+    "pre", "code", "kbd", "samp", "script", "style", "math", "svg",
+];
+
+/// Elements without an end tag.
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link",
+    "meta", "source", "track", "wbr",
+];
+
+/// A block whose language differs from the surrounding language.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LanguageFragment {
+    /// The lowercase element name, e.g. `p`.
+    pub element: String,
+    /// The start of the text of the block.
+    pub excerpt: String,
+    /// The detected language, as an ISO 639-1 code.
+    pub language: &'static str,
+    /// The language the block was expected to be in.
+    pub expected: String,
+    /// Confidence of the detection, from 0 to 1.
+    pub confidence: f64,
+}
+
+impl LanguageFragment {
+    /// Converts the finding into an informational [`Diagnostic`] with
+    /// the code `language_fragment`.
+    pub fn to_diagnostic(&self) -> Diagnostic {
+        Diagnostic::info("language_fragment", self.to_string())
+    }
+}
+
+impl fmt::Display for LanguageFragment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "<{}> '{}' looks like '{}' rather than '{}'; mark it with \
+             lang=\"{}\" (WCAG 3.1.2)",
+            self.element,
+            self.excerpt,
+            self.language,
+            self.expected,
+            self.language
+        )
+    }
+}
+
+/// Finds blocks of `html` written in another language.
+///
+/// # Arguments
+///
+/// * `html` - The HTML to examine
+/// * `document_language` - The language of the page, e.g. `en-GB`
+///
+/// # Returns
+///
+/// The blocks in another language, in document order.
+pub fn detect_language_fragments(
+    html: &str,
+    document_language: &str,
+) -> Vec<LanguageFragment> {
+    scan(html, document_language)
+        .into_iter()
+        .map(|(_, fragment)| fragment)
+        .collect()
+}
+
+/// Adds `lang` attributes to blocks of `html` written in another
+/// language.
+///
+/// # Arguments
+///
+/// * `html` - The HTML to annotate
+/// * `document_language` - The language of the page, e.g. `en-GB`
+///
+/// # Returns
+///
+/// The annotated HTML and the annotated blocks, in document order.
+pub fn add_lang_attributes(
+    html: &str,
+    document_language: &str,
+) -> (String, Vec<LanguageFragment>) {
+    let found = scan(html, document_language);
+    let mut annotated = html.to_string();
+    for (position, fragment) in found.iter().rev() {
+        annotated.insert_str(
+            *position,
+            &format!(r#" lang="{}""#, fragment.language),
+        );
+    }
+    (
+        annotated,
+        found.into_iter().map(|(_, fragment)| fragment).collect(),
+    )
+}
+
+/// An open element during the scan.
+#[derive(Debug)]
+struct OpenElement {
+    name: String,
+    /// The language of the element, declared or inherited.
+    language: String,
+    /// Whether the element declares its own language.
+    declared: bool,
+    /// Where the name in the start tag ends.
+    name_end: usize,
+    /// Where the content starts.
+    content_start: usize,
+    /// Whether a block element is nested inside.
+    has_block: bool,
+}
+
+/// Returns the blocks in another language, with the position at which
+/// their `lang` attribute goes.
+fn scan(
+    html: &str,
+    document_language: &str,
+) -> Vec<(usize, LanguageFragment)> {
+    let mut found = Vec::new();
+    let mut stack: Vec<OpenElement> = Vec::new();
+    for tag in TAG_REGEX.captures_iter(html) {
+        let whole = tag.get(0).map_or(0..0, |m| m.range());
+        let name = tag[2].to_ascii_lowercase();
+        if tag[1].is_empty() {
+            if VOID_ELEMENTS.contains(&name.as_str())
+                || tag[3].trim_end().ends_with('/')
+            {
+                continue;
+            }
+            let declared = LANG_ATTRIBUTE_REGEX.captures(&tag[3]);
+            let language = match &declared {
+                Some(caps) => caps
+                    .get(1)
+                    .or_else(|| caps.get(2))
+                    .or_else(|| caps.get(3))
+                    .map_or("", |m| m.as_str())
+                    .to_string(),
+                None => stack.last().map_or_else(
+                    || document_language.to_string(),
+                    |parent| parent.language.clone(),
+                ),
+            };
+            if BLOCK_ELEMENTS.contains(&name.as_str()) {
+                for open in &mut stack {
+                    open.has_block = true;
+                }
+            }
+            stack.push(OpenElement {
+                name_end: whole.start + 1 + tag[2].len(),
+                content_start: whole.end,
+                name,
+                language,
+                declared: declared.is_some(),
+                has_block: false,
+            });
+            continue;
+        }
+
+        let index =
+            match stack.iter().rposition(|open| open.name == name) {
+                Some(index) => index,
+                None => continue,
+            };
+        let open = stack.remove(index);
+        stack.truncate(index);
+        let skipped = stack.iter().any(|parent| {
+            SKIPPED_ELEMENTS.contains(&parent.name.as_str())
+        });
+        if open.declared
+            || open.has_block
+            || skipped
+            || !BLOCK_ELEMENTS.contains(&open.name.as_str())
+        {
+            continue;
+        }
+        let content = &html[open.content_start..whole.start];
+        if let Some(fragment) =
+            detect(&open.name, content, &open.language)
+        {
+            found.push((open.name_end, fragment));
+        }
+    }
+    found.sort_by_key(|(position, _)| *position);
+    found
+}
+
+/// Detects the language of the HTML `content` of an element.
+fn detect(
+    element: &str,
+    content: &str,
+    expected: &str,
+) -> Option<LanguageFragment> {
+    let text = text_of(content);
+    if text.chars().count() < MIN_TEXT_CHARS {
+        return None;
+    }
+    let info = whatlang::detect(&text)?;
+    if !info.is_reliable() {
+        return None;
+    }
+    let language = iso_639_1(info.lang());
+    let primary = expected.split('-').next().unwrap_or_default();
+    if primary.eq_ignore_ascii_case(language)
+        || (language == "nb" && primary.eq_ignore_ascii_case("no"))
+    {
+        return None;
+    }
+    Some(LanguageFragment {
+        element: element.to_string(),
+        excerpt: text.chars().take(EXCERPT_CHARS).collect(),
+        language,
+        expected: expected.to_string(),
+        confidence: info.confidence(),
+    })
+}
+
+/// Returns the text of HTML `content`, without code.
+fn text_of(content: &str) -> String {
+    let mut text = String::with_capacity(content.len());
+    let mut skipped_depth = 0_usize;
+    let mut last = 0;
+    for tag in TAG_REGEX.captures_iter(content) {
+        let whole = tag.get(0).map_or(0..0, |m| m.range());
+        if skipped_depth == 0 {
+            text.push_str(&content[last..whole.start]);
+        }
+        last = whole.end;
+        if SKIPPED_ELEMENTS
+            .contains(&tag[2].to_ascii_lowercase().as_str())
+        {
+            if tag[1].is_empty() {
+                skipped_depth += 1;
+            } else {
+                skipped_depth = skipped_depth.saturating_sub(1);
+            }
+            text.push(' ');
+        }
+    }
+    if skipped_depth == 0 {
+        text.push_str(&content[last..]);
+    }
+    decode_entities(&text)
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Returns the ISO 639-1 code of a detected language.
+fn iso_639_1(lang: Lang) -> &'static str {
+    match lang {
+        Lang::Afr => "af",
+        Lang::Aka => "ak",
+        Lang::Amh => "am",
+        Lang::Ara => "ar",
+        Lang::Aze => "az",
+        Lang::Bel => "be",
+        Lang::Ben => "bn",
+        Lang::Bul => "bg",
+        Lang::Cat => "ca",
+        Lang::Ces => "cs",
+        Lang::Cmn => "zh",
+        Lang::Cym => "cy",
+        Lang::Dan => "da",
+        Lang::Deu => "de",
+        Lang::Ell => "el",
+        Lang::Eng => "en",
+        Lang::Epo => "eo",
+        Lang::Est => "et",
+        Lang::Fin => "fi",
+        Lang::Fra => "fr",
+        Lang::Guj => "gu",
+        Lang::Heb => "he",
+        Lang::Hin => "hi",
+        Lang::Hrv => "hr",
+        Lang::Hun => "hu",
+        Lang::Hye => "hy",
+        Lang::Ind => "id",
+        Lang::Ita => "it",
+        Lang::Jav => "jv",
+        Lang::Jpn => "ja",
+        Lang::Kan => "kn",
+        Lang::Kat => "ka",
+        Lang::Khm => "km",
+        Lang::Kor => "ko",
+        Lang::Lat => "la",
+        Lang::Lav => "lv",
+        Lang::Lit => "lt",
+        Lang::Mal => "ml",
+        Lang::Mar => "mr",
+        Lang::Mkd => "mk",
+        Lang::Mya => "my",
+        Lang::Nep => "ne",
+        Lang::Nld => "nl",
+        Lang::Nob => "nb",
+        Lang::Ori => "or",
+        Lang::Pan => "pa",
+        Lang::Pes => "fa",
+        Lang::Pol => "pl",
+        Lang::Por => "pt",
+        Lang::Ron => "ro",
+        Lang::Rus => "ru",
+        Lang::Sin => "si",
+        Lang::Slk => "sk",
+        Lang::Slv => "sl",
+        Lang::Sna => "sn",
+        Lang::Spa => "es",
+        Lang::Srp => "sr",
+        Lang::Swe => "sv",
+        Lang::Tam => "ta",
+        Lang::Tel => "te",
+        Lang::Tgl => "tl",
+        Lang::Tha => "th",
+        Lang::Tuk => "tk",
+        Lang::Tur => "tr",
+        Lang::Ukr => "uk",
+        Lang::Urd => "ur",
+        Lang::Uzb => "uz",
+        Lang::Vie => "vi",
+        Lang::Yid => "yi",
+        Lang::Zul => "zu",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ENGLISH: &str =
+        "The committee will meet again next week to discuss the budget and the plans for the new library.";
+    const GERMAN: &str =
+        "Die Sitzung des Ausschusses findet nächste Woche wieder statt.";
+
+    #[test]
+    fn test_only_leaf_blocks_in_other_languages_are_annotated() {
+        let html = format!(
+            concat!(
+                "<h2>{en}</h2><ul><li><p>{de}</p></li></ul>",
+                "<p>{en}<br><code>let x = 1;</code></p>",
+                "<p>Kurz.</p><pre><code>{de}</code></pre>",
+                "<table><tr><td>{de}</td></tr></table>"
+            ),
+            en = ENGLISH,
+            de = GERMAN
+        );
+        let (annotated, fragments) = add_lang_attributes(&html, "en");
+        assert_eq!(annotated.matches(r#" lang="de""#).count(), 2);
+        assert!(annotated.contains(r#"<li><p lang="de">Die"#));
+        assert!(annotated.contains(r#"<td lang="de">Die"#));
+        assert_eq!(
+            fragments
+                .iter()
+                .map(|f| f.element.as_str())
+                .collect::<Vec<_>>(),
+            ["p", "td"]
+        );
+        assert_eq!(fragments[0].expected, "en");
+        assert_eq!(
+            fragments[0].to_diagnostic().code,
+            "language_fragment"
+        );
+        assert_eq!(add_lang_attributes(&annotated, "en").0, annotated);
+    }
+
+    #[test]
+    fn test_text_of_decodes_entities() {
+        assert_eq!(
+            text_of("<p>Caf&eacute; &ndash;<code>x</code>na&iuml;ve&#x21;</p>"),
+            "Caf\u{E9} \u{2013} na\u{EF}ve!"
+        );
+    }
+
+    #[test]
+    fn test_declared_languages_are_respected() {
+        let html = format!(
+            r#"<div lang="de-CH"><p>{de}</p><p>{en}</p></div><p lang="fr">{en}</p>"#,
+            de = GERMAN,
+            en = ENGLISH
+        );
+        let fragments = detect_language_fragments(&html, "en-US");
+        assert_eq!(fragments.len(), 1);
+        assert_eq!(fragments[0].language, "en");
+        assert_eq!(fragments[0].expected, "de-CH");
+        assert!(detect_language_fragments(&html, "de").len() == 1);
+    }
+}
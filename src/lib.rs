@@ -10,6 +10,12 @@
 #![crate_name = "html_generator"]
 #![crate_type = "lib"]
 
+extern crate alloc;
+
+use alloc::borrow::Cow;
+use alloc::collections::BTreeMap;
+
+#[cfg(feature = "std")]
 use std::{
     fmt,
     fs::File,
@@ -18,25 +24,99 @@
 };
 
 /// Maximum buffer size for reading files (16MB)
+#[cfg(feature = "std")]
 const MAX_BUFFER_SIZE: usize = 16 * 1024 * 1024;
 
 // Re-export public modules
 pub mod accessibility;
+pub mod admonitions;
+pub mod anchors;
+#[cfg(feature = "std")]
+pub mod archive;
+pub mod baseline;
+#[cfg(feature = "std")]
+pub mod batch;
+#[cfg(feature = "bench")]
+pub mod bench;
+pub mod bidi;
+pub mod build_info;
+pub mod cache;
+pub mod components;
+#[cfg(feature = "config-file")]
+mod config_file;
+pub mod containers;
+pub mod conversion;
+pub mod diagnostics;
+pub mod diagrams;
+pub mod directives;
+pub mod doctests;
 pub mod emojis;
 pub mod error;
+pub mod explanations;
+pub mod footnotes;
+pub mod front_matter;
 pub mod generator;
+pub mod headings;
+pub mod highlight;
+pub mod hooks;
+pub mod hydration;
+pub mod iframes;
+#[cfg(feature = "std")]
+pub mod ignore;
+#[cfg(feature = "std")]
+pub mod includes;
+#[cfg(feature = "language-detection")]
+pub mod language;
+pub mod limits;
+#[cfg(feature = "std")]
+pub mod links;
+#[cfg(feature = "lsp")]
+pub mod lsp;
+#[cfg(feature = "std")]
+pub mod manifest;
+pub mod math;
+#[cfg(feature = "ndjson")]
+pub mod ndjson;
+pub mod patterns;
 pub mod performance;
+pub mod pipeline;
+pub mod plugins;
+pub mod profiler;
+pub mod provenance;
+pub mod query;
+mod regexes;
+pub mod repair;
+pub mod sanitize;
+#[cfg(feature = "schema")]
+pub mod schema;
 pub mod seo;
+#[cfg(feature = "serde")]
+mod serde_impls;
+pub mod serializer;
+#[cfg(feature = "std")]
+pub mod service;
+pub mod tables;
+pub mod templates;
+pub mod toc;
+pub mod unicode;
 pub mod utils;
+#[cfg(feature = "watch")]
+pub mod watch;
+pub mod writing_mode;
 
 // Re-export primary types and functions for convenience
 pub use crate::error::HtmlError;
 pub use accessibility::{add_aria_attributes, validate_wcag};
 pub use emojis::load_emoji_sequences;
 pub use generator::generate_html;
+#[cfg(feature = "std")]
+pub use manifest::BuildManifest;
+#[cfg(feature = "std")]
 pub use performance::{async_generate_html, minify_html};
 pub use seo::{generate_meta_tags, generate_structured_data};
-pub use utils::{extract_front_matter, format_header_with_id_class};
+pub use utils::{
+    extract_front_matter, format_header_with_id_class, slugify_path,
+};
 
 /// Common constants used throughout the library.
 ///
@@ -58,6 +138,9 @@ pub mod constants {
     /// Maximum file path length
     pub const MAX_PATH_LENGTH: usize = 4096;
 
+    /// Default nesting limit of `{{include "path"}}` directives
+    pub const DEFAULT_MAX_INCLUDE_DEPTH: usize = 8;
+
     /// Regular expression pattern for validating language codes
     pub const LANGUAGE_CODE_PATTERN: &str = r"^[a-z]{2}-[A-Z]{2}$";
 
@@ -74,12 +157,35 @@ pub mod constants {
 /// This struct holds settings that control how Markdown content is processed
 /// and converted to HTML.
 #[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schema", schemars(deny_unknown_fields))]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
 pub struct MarkdownConfig {
     /// The encoding to use for input/output (defaults to "utf-8")
     pub encoding: String,
 
     /// HTML generation configuration
     pub html_config: HtmlConfig,
+
+    /// Perform conversion and validation without writing any output
+    pub dry_run: bool,
+
+    /// Format of the front matter removed from the output and returned
+    /// by [`markdown_to_html_with_front_matter`] (defaults to detecting
+    /// it); a block in another format is rendered as content
+    pub front_matter_format: front_matter::FrontMatterFormat,
+
+    /// Expand `{{include "path"}}` directives in the file and batch
+    /// APIs, see [`includes`]
+    pub expand_includes: bool,
+
+    /// How many levels of includes may be nested (defaults to
+    /// [`constants::DEFAULT_MAX_INCLUDE_DEPTH`])
+    pub max_include_depth: usize,
 }
 
 impl Default for MarkdownConfig {
@@ -87,10 +193,31 @@ fn default() -> Self {
         Self {
             encoding: String::from("utf-8"),
             html_config: HtmlConfig::default(),
+            dry_run: false,
+            front_matter_format: front_matter::FrontMatterFormat::Auto,
+            expand_includes: false,
+            max_include_depth: constants::DEFAULT_MAX_INCLUDE_DEPTH,
         }
     }
 }
 
+impl MarkdownConfig {
+    /// Returns the HTML settings for converting documents: the
+    /// `html_config`, with the front matter read in
+    /// `front_matter_format` when it is not detected.
+    pub(crate) fn conversion_config(&self) -> Cow<'_, HtmlConfig> {
+        if self.front_matter_format
+            == front_matter::FrontMatterFormat::Auto
+        {
+            return Cow::Borrowed(&self.html_config);
+        }
+        let mut html_config = self.html_config.clone();
+        html_config.markdown_extensions.front_matter_format =
+            self.front_matter_format;
+        Cow::Owned(html_config)
+    }
+}
+
 /// Errors that can occur during configuration.
 #[derive(Debug, thiserror::Error)]
 #[non_exhaustive]
@@ -139,6 +266,7 @@ pub enum ConfigError {
 ///
 /// let output = OutputDestination::Stdout;
 /// ```
+#[cfg(feature = "std")]
 #[non_exhaustive]
 pub enum OutputDestination {
     /// Write output to a file at the specified path.
@@ -183,6 +311,7 @@ pub enum OutputDestination {
 }
 
 /// Default implementation for OutputDestination.
+#[cfg(feature = "std")]
 impl Default for OutputDestination {
     fn default() -> Self {
         Self::Stdout
@@ -190,6 +319,7 @@ fn default() -> Self {
 }
 
 /// Debug implementation for OutputDestination.
+#[cfg(feature = "std")]
 impl fmt::Debug for OutputDestination {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -203,6 +333,7 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
 }
 
 /// Implements `Display` for `OutputDestination`.
+#[cfg(feature = "std")]
 impl fmt::Display for OutputDestination {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -217,35 +348,351 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
     }
 }
 
-/// Configuration options for HTML generation.
+/// Declares [`HtmlConfig`] and [`PartialHtmlConfig`] from one list of
+/// fields, along with [`HtmlConfig::merge`] and the settings described
+/// in cache keys.
 ///
-/// Controls various aspects of the HTML generation process including
-/// syntax highlighting, accessibility features, and output formatting.
-#[derive(Debug, PartialEq, Eq, Clone)]
-pub struct HtmlConfig {
-    /// Enable syntax highlighting for code blocks
-    pub enable_syntax_highlighting: bool,
-
-    /// Theme to use for syntax highlighting
-    pub syntax_theme: Option<String>,
+/// `settings` are the options read from configuration files; attributes
+/// after `=>` apply to the override only. `runtime` fields hold
+/// registries and callbacks set in code, which are neither serialized
+/// nor part of the schema.
+macro_rules! html_config {
+    (
+        $(#[$config_attr:meta])*
+        pub struct HtmlConfig {
+            settings {
+                $(
+                    $(#[doc = $setting_doc:tt])*
+                    pub $setting:ident: $setting_ty:ty
+                        $(=> $(#[$override_attr:meta])+)?,
+                )+
+            }
 
-    /// Minify the generated HTML output
-    pub minify_output: bool,
+            runtime {
+                $(
+                    $(#[doc = $runtime_doc:tt])*
+                    pub $runtime:ident: $runtime_ty:ty,
+                )+
+            }
+        }
 
-    /// Automatically add ARIA attributes for accessibility
-    pub add_aria_attributes: bool,
+        $(#[$partial_attr:meta])*
+        pub struct PartialHtmlConfig;
+    ) => {
+        $(#[$config_attr])*
+        pub struct HtmlConfig {
+            $(
+                $(#[doc = $setting_doc])*
+                pub $setting: $setting_ty,
+            )+
+            $(
+                $(#[doc = $runtime_doc])*
+                #[cfg_attr(feature = "schema", schemars(skip))]
+                #[cfg_attr(feature = "serde", serde(skip))]
+                pub $runtime: $runtime_ty,
+            )+
+        }
+
+        $(#[$partial_attr])*
+        pub struct PartialHtmlConfig {
+            $(
+                $(#[doc = $setting_doc])*
+                $($(#[$override_attr])+)?
+                pub $setting: Option<$setting_ty>,
+            )+
+            $(
+                $(#[doc = $runtime_doc])*
+                #[cfg_attr(feature = "schema", schemars(skip))]
+                #[cfg_attr(feature = "serde", serde(skip))]
+                pub $runtime: Option<$runtime_ty>,
+            )+
+        }
+
+        impl HtmlConfig {
+            /// Returns a copy of this configuration with `overrides`
+            /// applied.
+            ///
+            /// Fields set in `overrides` replace the corresponding
+            /// values; all other fields are taken from `self`. The
+            /// result is not validated; call [`HtmlConfig::validate`]
+            /// if the overrides come from an untrusted source.
+            ///
+            /// # Arguments
+            ///
+            /// * `overrides` - The fields to replace
+            pub fn merge(
+                &self,
+                overrides: PartialHtmlConfig,
+            ) -> HtmlConfig {
+                HtmlConfig {
+                    $($setting: overrides
+                        .$setting
+                        .unwrap_or_else(|| self.$setting.clone()),)+
+                    $($runtime: overrides
+                        .$runtime
+                        .unwrap_or_else(|| self.$runtime.clone()),)+
+                }
+            }
 
-    /// Generate structured data (JSON-LD) based on content
-    pub generate_structured_data: bool,
+            /// Appends `name=value` for every setting, one per line.
+            ///
+            /// Runtime fields are left to the caller, since registries
+            /// and callbacks have no stable description.
+            pub(crate) fn describe_settings(
+                &self,
+                description: &mut String,
+            ) {
+                $(description.push_str(&format!(
+                    "{}={:?}\n",
+                    stringify!($setting),
+                    self.$setting
+                ));)+
+            }
+        }
+    };
+}
 
-    /// Maximum size (in bytes) for input content
-    pub max_input_size: usize,
+html_config! {
+    /// Configuration options for HTML generation.
+    ///
+    /// Controls various aspects of the HTML generation process
+    /// including syntax highlighting, accessibility features, and
+    /// output formatting.
+    #[derive(Debug, PartialEq, Eq, Clone)]
+    #[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+    #[cfg_attr(feature = "schema", schemars(deny_unknown_fields))]
+    #[cfg_attr(
+        feature = "serde",
+        derive(serde::Serialize, serde::Deserialize)
+    )]
+    #[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
+    pub struct HtmlConfig {
+        settings {
+            /// Enable syntax highlighting for code blocks
+            pub enable_syntax_highlighting: bool,
+
+            /// Theme to use for syntax highlighting, see [`highlight`];
+            /// `None` uses the default theme
+            pub syntax_theme: Option<String>
+                => #[cfg_attr(
+                    feature = "serde",
+                    serde(
+                        deserialize_with = "serde_impls::double_option"
+                    )
+                )],
+
+            /// Whether highlighted code is coloured with inline styles
+            /// or classes, see [`highlight::HighlightMode`]
+            pub highlight_mode: highlight::HighlightMode,
+
+            /// Highlight code blocks on several threads, once a page
+            /// has at least
+            /// [`HtmlConfig::parallel_highlighting_threshold`] of them
+            pub parallel_highlighting: bool,
+
+            /// Minimum number of code blocks highlighted in parallel
+            /// when [`HtmlConfig::parallel_highlighting`] is set
+            pub parallel_highlighting_threshold: usize,
+
+            /// Minify the generated HTML output
+            pub minify_output: bool,
+
+            /// Automatically add ARIA attributes for accessibility
+            pub add_aria_attributes: bool,
+
+            /// Generate structured data (JSON-LD) based on content
+            pub generate_structured_data: bool,
+
+            /// Maximum size (in bytes) for input content
+            pub max_input_size: usize,
+
+            /// Language for generated content
+            pub language: String,
+
+            /// Enable table of contents generation
+            pub generate_toc: bool,
+
+            /// Placement, heading range and markup of the table of
+            /// contents
+            pub toc: toc::TocConfig,
+
+            /// Give every heading a unique `id` derived from its text
+            pub heading_ids: bool,
+
+            /// Append a visible `¶` link to every heading; implies
+            /// `heading_ids`
+            pub heading_anchor_links: bool,
+
+            /// Add a local table of contents after every `h2`, listing
+            /// the `h3` headings of its section
+            pub section_tocs: bool,
+
+            /// How heading IDs are derived from heading text, for
+            /// heading IDs and tables of contents
+            pub slug_strategy: headings::SlugStrategy,
+
+            /// Start the output with a comment naming the generator
+            /// version and source; ignored when `minify_output` is set
+            pub provenance: Option<provenance::Provenance>
+                => #[cfg_attr(
+                    feature = "serde",
+                    serde(
+                        deserialize_with = "serde_impls::double_option"
+                    )
+                )],
+
+            /// Remove or reject `javascript:`, `vbscript:` and
+            /// unexpected `data:` URLs, for untrusted input
+            pub url_scheme_policy: sanitize::UrlSchemePolicy,
+
+            /// Sandbox attributes and allowed hosts enforced on
+            /// iframes, see [`iframes`]; `None` leaves iframes
+            /// untouched
+            pub iframe_policy: Option<iframes::IframePolicy>
+                => #[cfg_attr(
+                    feature = "serde",
+                    serde(
+                        deserialize_with = "serde_impls::double_option"
+                    )
+                )],
+
+            /// How emoji in the generated HTML are treated
+            pub emoji_policy: emojis::EmojiPolicy,
+
+            /// Normalize the generated HTML to Unicode NFC
+            pub normalize_unicode: bool,
+
+            /// Remove invisible and bidirectional control characters
+            pub strip_invisible_characters: bool,
+
+            /// Repair UTF-8 input that was mis-decoded as Windows-1252
+            pub repair_mojibake: bool,
+
+            /// Bounds guarding against pathological input
+            pub limits: limits::ResourceLimits,
+
+            /// Number of levels by which headings are demoted (or
+            /// promoted, if negative), clamped to `h1`..`h6`
+            pub heading_offset: i8,
+
+            /// Remove the first `h1` from the body and report its text
+            /// as the document title
+            pub strip_title: bool,
+
+            /// Convert Markdown inside HTML containers marked
+            /// `markdown="1"`
+            pub markdown_in_html: bool,
+
+            /// Apply `<!-- html-generator: ... -->` directives found at
+            /// the top of a document
+            pub comment_directives: bool,
+
+            /// Which characters the output writes as entities
+            pub entity_encoding: serializer::EntityEncoding,
+
+            /// Add sorting and filtering hooks to tables, see
+            /// [`tables`]
+            pub enhance_tables: bool,
+
+            /// Maximum number of body rows shown per table before the
+            /// rest is moved into "show more" sections (`0` disables
+            /// chunking)
+            pub table_chunk_rows: usize,
+
+            /// Render footnotes, with markup for showing them in
+            /// popovers, see [`footnotes`]
+            pub footnote_popovers: bool,
+
+            /// Parse `$...$` and `$$...$$` as TeX and write it for
+            /// KaTeX or MathJax, see [`math`]
+            pub math: bool,
+
+            /// Code block languages written as a `<pre>` with the
+            /// mapped class for client-side diagram renderers instead
+            /// of being highlighted, see [`diagrams`]
+            pub diagram_languages: BTreeMap<String, String>,
+
+            /// Base text direction, written as the `dir` of a `div`
+            /// wrapping the output, see [`bidi`]; `None` leaves the
+            /// output unwrapped
+            pub direction: Option<bidi::TextDirection>
+                => #[cfg_attr(
+                    feature = "serde",
+                    serde(
+                        deserialize_with = "serde_impls::double_option"
+                    )
+                )],
+
+            /// Add `dir="auto"` to paragraphs, list items, headings and
+            /// other user-content blocks without a `dir`
+            pub auto_direction: bool,
+
+            /// Direction in which lines run; vertical modes wrap the
+            /// output in a `writing-mode-*` class, see [`writing_mode`]
+            pub writing_mode: writing_mode::WritingMode,
+
+            /// Markdown syntax extensions, including whether raw HTML
+            /// is allowed
+            pub markdown_extensions: generator::MarkdownExtensions,
+        }
+
+        runtime {
+            /// Render `:::` containers as admonitions with titles,
+            /// icons and nesting, see [`admonitions`]; `None` keeps
+            /// plain `<div>`s
+            pub admonitions: Option<admonitions::AdmonitionRegistry>,
+
+            /// Custom renderers for `:::name` containers, taking
+            /// precedence over admonitions, see [`containers`]
+            pub block_renderers: containers::BlockRendererRegistry,
+
+            /// Post-processors run by the pipeline of every conversion,
+            /// see [`plugins`]
+            pub plugins: plugins::PluginRegistry,
+
+            /// Callbacks run between the stages of every conversion,
+            /// see [`hooks`]
+            pub hooks: hooks::PipelineHooks,
 
-    /// Language for generated content
-    pub language: String,
+            /// Records the time spent in each stage of every
+            /// conversion, see [`profiler`]; `None` disables profiling
+            pub profiler: Option<profiler::Profiler>,
+        }
+    }
 
-    /// Enable table of contents generation
-    pub generate_toc: bool,
+    /// A set of optional overrides for [`HtmlConfig`].
+    ///
+    /// Every field mirrors a field of [`HtmlConfig`]; `None` leaves the
+    /// base value unchanged, and `Some(None)` clears a field that is
+    /// itself optional. Use [`HtmlConfig::merge`] to layer the
+    /// overrides over a base configuration, so a service can keep one
+    /// base config and vary a few fields per request.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use html_generator::{HtmlConfig, PartialHtmlConfig};
+    ///
+    /// let base = HtmlConfig::default();
+    /// let config = base.merge(PartialHtmlConfig {
+    ///     language: Some("fr-FR".to_string()),
+    ///     minify_output: Some(true),
+    ///     ..Default::default()
+    /// });
+    ///
+    /// assert_eq!(config.language, "fr-FR");
+    /// assert!(config.minify_output);
+    /// assert_eq!(config.max_input_size, base.max_input_size);
+    /// ```
+    #[derive(Debug, Default, PartialEq, Eq, Clone)]
+    #[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+    #[cfg_attr(feature = "schema", schemars(deny_unknown_fields))]
+    #[cfg_attr(
+        feature = "serde",
+        derive(serde::Serialize, serde::Deserialize)
+    )]
+    #[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
+    pub struct PartialHtmlConfig;
 }
 
 impl Default for HtmlConfig {
@@ -253,12 +700,49 @@ fn default() -> Self {
         Self {
             enable_syntax_highlighting: true,
             syntax_theme: Some("github".to_string()),
+            highlight_mode: highlight::HighlightMode::default(),
+            parallel_highlighting: false,
+            parallel_highlighting_threshold:
+                highlight::DEFAULT_PARALLEL_THRESHOLD,
             minify_output: false,
             add_aria_attributes: true,
             generate_structured_data: false,
             max_input_size: constants::DEFAULT_MAX_INPUT_SIZE,
             language: String::from(constants::DEFAULT_LANGUAGE),
             generate_toc: false,
+            toc: toc::TocConfig::default(),
+            heading_ids: false,
+            heading_anchor_links: false,
+            section_tocs: false,
+            slug_strategy: headings::SlugStrategy::default(),
+            provenance: None,
+            url_scheme_policy: sanitize::UrlSchemePolicy::default(),
+            iframe_policy: None,
+            emoji_policy: emojis::EmojiPolicy::Keep,
+            normalize_unicode: false,
+            strip_invisible_characters: false,
+            repair_mojibake: false,
+            limits: limits::ResourceLimits::default(),
+            heading_offset: 0,
+            strip_title: false,
+            markdown_in_html: false,
+            comment_directives: false,
+            entity_encoding: serializer::EntityEncoding::default(),
+            enhance_tables: false,
+            table_chunk_rows: 0,
+            footnote_popovers: false,
+            math: false,
+            admonitions: None,
+            block_renderers: containers::BlockRendererRegistry::new(),
+            plugins: plugins::PluginRegistry::new(),
+            hooks: hooks::PipelineHooks::new(),
+            profiler: None,
+            diagram_languages: diagrams::default_diagram_languages(),
+            direction: None,
+            auto_direction: false,
+            writing_mode: writing_mode::WritingMode::default(),
+            markdown_extensions: generator::MarkdownExtensions::default(
+            ),
         }
     }
 }
@@ -303,7 +787,12 @@ pub fn validate(&self) -> Result<()> {
                 self.language
             )));
         }
-        Ok(())
+        if let Some(theme) = &self.syntax_theme {
+            if self.enable_syntax_highlighting {
+                highlight::validate_theme(theme)?;
+            }
+        }
+        self.toc.validate()
     }
 
     /// Validates file path safety to prevent directory traversal attacks.
@@ -316,6 +805,7 @@ pub fn validate(&self) -> Result<()> {
     ///
     /// Returns `Ok(())` if the path is safe, or an appropriate error
     /// if validation fails.
+    #[cfg(feature = "std")]
     pub(crate) fn validate_file_path(
         path: impl AsRef<Path>,
     ) -> Result<()> {
@@ -362,6 +852,13 @@ pub(crate) fn validate_file_path(
     }
 }
 
+impl PartialHtmlConfig {
+    /// Returns `true` if no field is overridden.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
 /// Builder for constructing `HtmlConfig` instances.
 ///
 /// Provides a fluent interface for creating and customizing HTML
@@ -377,38 +874,492 @@ pub fn new() -> Self {
         Self::default()
     }
 
-    /// Enables or disables syntax highlighting for code blocks.
+    /// Enables or disables syntax highlighting for code blocks.
+    ///
+    /// # Arguments
+    ///
+    /// * `enable` - Whether to enable syntax highlighting
+    /// * `theme` - Optional theme name for syntax highlighting
+    #[must_use]
+    pub fn with_syntax_highlighting(
+        mut self,
+        enable: bool,
+        theme: Option<String>,
+    ) -> Self {
+        self.config.enable_syntax_highlighting = enable;
+        self.config.syntax_theme = if enable {
+            theme.or_else(|| Some("github".to_string()))
+        } else {
+            None
+        };
+        self
+    }
+
+    /// Sets how highlighted code is coloured.
+    ///
+    /// # Arguments
+    ///
+    /// * `mode` - See [`highlight::HighlightMode`]
+    #[must_use]
+    pub fn with_highlight_mode(
+        mut self,
+        mode: highlight::HighlightMode,
+    ) -> Self {
+        self.config.highlight_mode = mode;
+        self
+    }
+
+    /// Highlights code blocks in parallel on pages with at least
+    /// `threshold` of them.
+    ///
+    /// See [`highlight::highlight_code_blocks_parallel`].
+    ///
+    /// # Arguments
+    ///
+    /// * `threshold` - The minimum number of code blocks
+    #[must_use]
+    pub fn with_parallel_highlighting(
+        mut self,
+        threshold: usize,
+    ) -> Self {
+        self.config.parallel_highlighting = true;
+        self.config.parallel_highlighting_threshold = threshold;
+        self
+    }
+
+    /// Sets the language for generated content.
+    ///
+    /// # Arguments
+    ///
+    /// * `language` - The language code (e.g., "en-GB")
+    #[must_use]
+    pub fn with_language(
+        mut self,
+        language: impl Into<String>,
+    ) -> Self {
+        self.config.language = language.into();
+        self
+    }
+
+    /// Sets how emoji in the generated HTML are treated.
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - The emoji policy to apply
+    #[must_use]
+    pub fn with_emoji_policy(
+        mut self,
+        policy: emojis::EmojiPolicy,
+    ) -> Self {
+        self.config.emoji_policy = policy;
+        self
+    }
+
+    /// Enables or disables Unicode cleanup of the generated HTML.
+    ///
+    /// # Arguments
+    ///
+    /// * `normalize` - Whether to normalize the output to NFC
+    /// * `strip_invisible` - Whether to remove invisible and
+    ///   bidirectional control characters
+    #[must_use]
+    pub fn with_unicode_cleanup(
+        mut self,
+        normalize: bool,
+        strip_invisible: bool,
+    ) -> Self {
+        self.config.normalize_unicode = normalize;
+        self.config.strip_invisible_characters = strip_invisible;
+        self
+    }
+
+    /// Enables or disables repair of mis-encoded Windows-1252 input.
+    ///
+    /// # Arguments
+    ///
+    /// * `enable` - Whether to repair sequences such as `â€™`
+    #[must_use]
+    pub fn with_mojibake_repair(mut self, enable: bool) -> Self {
+        self.config.repair_mojibake = enable;
+        self
+    }
+
+    /// Sets the resource limits applied to each conversion.
+    ///
+    /// # Arguments
+    ///
+    /// * `limits` - The nesting, table and output size bounds
+    #[must_use]
+    pub fn with_limits(
+        mut self,
+        limits: limits::ResourceLimits,
+    ) -> Self {
+        self.config.limits = limits;
+        self
+    }
+
+    /// Shifts every heading by `offset` levels.
+    ///
+    /// Use a positive offset when embedding the output in a page that
+    /// already has its own `h1`. Levels are clamped to `h1`..`h6`.
+    ///
+    /// # Arguments
+    ///
+    /// * `offset` - Levels to demote by; negative values promote
+    #[must_use]
+    pub fn with_heading_offset(mut self, offset: i8) -> Self {
+        self.config.heading_offset = offset;
+        self
+    }
+
+    /// Removes the first `h1` from the body and reports its text as the
+    /// document title, for templates that render the title separately.
+    ///
+    /// # Arguments
+    ///
+    /// * `enable` - Whether to strip the title
+    #[must_use]
+    pub fn with_title_stripping(mut self, enable: bool) -> Self {
+        self.config.strip_title = enable;
+        self
+    }
+
+    /// Enables Markdown inside HTML containers marked `markdown="1"`.
+    ///
+    /// See [`generator::enable_markdown_in_html`] for the exact rules.
+    ///
+    /// # Arguments
+    ///
+    /// * `enable` - Whether to convert Markdown inside such containers
+    #[must_use]
+    pub fn with_markdown_in_html(mut self, enable: bool) -> Self {
+        self.config.markdown_in_html = enable;
+        self
+    }
+
+    /// Lets documents override settings with comment directives.
+    ///
+    /// See [`directives`] for the supported keys.
+    ///
+    /// # Arguments
+    ///
+    /// * `enable` - Whether to apply comment directives
+    #[must_use]
+    pub fn with_comment_directives(mut self, enable: bool) -> Self {
+        self.config.comment_directives = enable;
+        self
+    }
+
+    /// Sets which characters the output writes as entities.
+    ///
+    /// # Arguments
+    ///
+    /// * `encoding` - The entity encoding, see
+    ///   [`serializer::EntityEncoding`]
+    #[must_use]
+    pub fn with_entity_encoding(
+        mut self,
+        encoding: serializer::EntityEncoding,
+    ) -> Self {
+        self.config.entity_encoding = encoding;
+        self
+    }
+
+    /// Adds sorting and filtering hooks to generated tables.
+    ///
+    /// See [`tables::enhance_tables`] for the markup added.
+    ///
+    /// # Arguments
+    ///
+    /// * `enable` - Whether to decorate tables
+    #[must_use]
+    pub fn with_table_enhancements(mut self, enable: bool) -> Self {
+        self.config.enhance_tables = enable;
+        self
+    }
+
+    /// Splits tables with more than `rows` body rows into chunks.
+    ///
+    /// See [`tables::chunk_tables`] for the markup produced.
+    ///
+    /// # Arguments
+    ///
+    /// * `rows` - The number of rows per chunk, or `0` to disable
+    #[must_use]
+    pub fn with_table_chunking(mut self, rows: usize) -> Self {
+        self.config.table_chunk_rows = rows;
+        self
+    }
+
+    /// Renders footnotes with markup for popover display.
+    ///
+    /// See [`footnotes::add_footnote_popovers`] for the markup added.
+    ///
+    /// # Arguments
+    ///
+    /// * `enable` - Whether to render footnote popovers
+    #[must_use]
+    pub fn with_footnote_popovers(mut self, enable: bool) -> Self {
+        self.config.footnote_popovers = enable;
+        self
+    }
+
+    /// Enables or disables TeX math.
+    ///
+    /// See [`math`] for the markup written.
+    ///
+    /// # Arguments
+    ///
+    /// * `enable` - Whether to parse `$...$` and `$$...$$` as math
+    #[must_use]
+    pub fn with_math(mut self, enable: bool) -> Self {
+        self.config.math = enable;
+        self
+    }
+
+    /// Sets the admonition types of `:::` containers.
+    ///
+    /// # Arguments
+    ///
+    /// * `registry` - See [`admonitions::AdmonitionRegistry`]; `None`
+    ///   renders containers as plain `<div>`s
+    #[must_use]
+    pub fn with_admonitions(
+        mut self,
+        registry: Option<admonitions::AdmonitionRegistry>,
+    ) -> Self {
+        self.config.admonitions = registry;
+        self
+    }
+
+    /// Registers the renderer of `:::name` containers.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name following `:::`
+    /// * `renderer` - See [`containers::BlockRenderer`]
+    #[must_use]
+    pub fn with_block_renderer(
+        mut self,
+        name: impl Into<String>,
+        renderer: impl containers::BlockRenderer + 'static,
+    ) -> Self {
+        self.config.block_renderers.register(name, renderer);
+        self
+    }
+
+    /// Sets the post-processor plugins.
+    ///
+    /// # Arguments
+    ///
+    /// * `plugins` - See [`plugins::PluginRegistry`]
+    #[must_use]
+    pub fn with_plugins(
+        mut self,
+        plugins: plugins::PluginRegistry,
+    ) -> Self {
+        self.config.plugins = plugins;
+        self
+    }
+
+    /// Sets the callbacks run between the stages of a conversion.
+    ///
+    /// # Arguments
+    ///
+    /// * `hooks` - See [`hooks::PipelineHooks`]
+    #[must_use]
+    pub fn with_hooks(mut self, hooks: hooks::PipelineHooks) -> Self {
+        self.config.hooks = hooks;
+        self
+    }
+
+    /// Sets the profiler recording the stages of every conversion.
+    ///
+    /// # Arguments
+    ///
+    /// * `profiler` - See [`profiler::Profiler`]; `None` disables
+    ///   profiling
+    #[must_use]
+    pub fn with_profiler(
+        mut self,
+        profiler: Option<profiler::Profiler>,
+    ) -> Self {
+        self.config.profiler = profiler;
+        self
+    }
+
+    /// Sets the code block languages rendered as diagrams.
+    ///
+    /// # Arguments
+    ///
+    /// * `languages` - The class of the `<pre>` for each language; an
+    ///   empty map highlights every code block
+    #[must_use]
+    pub fn with_diagram_languages(
+        mut self,
+        languages: BTreeMap<String, String>,
+    ) -> Self {
+        self.config.diagram_languages = languages;
+        self
+    }
+
+    /// Sets the base text direction of the output.
+    ///
+    /// # Arguments
+    ///
+    /// * `direction` - See [`bidi::TextDirection`]; `None` leaves the
+    ///   output unwrapped
+    #[must_use]
+    pub fn with_direction(
+        mut self,
+        direction: Option<bidi::TextDirection>,
+    ) -> Self {
+        self.config.direction = direction;
+        self
+    }
+
+    /// Adds `dir="auto"` to user-content blocks.
+    ///
+    /// # Arguments
+    ///
+    /// * `enable` - Whether browsers pick the direction of each block
+    #[must_use]
+    pub fn with_auto_direction(mut self, enable: bool) -> Self {
+        self.config.auto_direction = enable;
+        self
+    }
+
+    /// Sets the direction in which lines of text run.
+    ///
+    /// # Arguments
+    ///
+    /// * `mode` - See [`writing_mode::WritingMode`]
+    #[must_use]
+    pub fn with_writing_mode(
+        mut self,
+        mode: writing_mode::WritingMode,
+    ) -> Self {
+        self.config.writing_mode = mode;
+        self
+    }
+
+    /// Sets the Markdown syntax extensions.
+    ///
+    /// # Arguments
+    ///
+    /// * `extensions` - See [`generator::MarkdownExtensions`]
+    #[must_use]
+    pub fn with_markdown_extensions(
+        mut self,
+        extensions: generator::MarkdownExtensions,
+    ) -> Self {
+        self.config.markdown_extensions = extensions;
+        self
+    }
+
+    /// Enables the table of contents with the given settings.
+    ///
+    /// # Arguments
+    ///
+    /// * `toc` - Placement, heading range and markup, see
+    ///   [`toc::TocConfig`]
+    #[must_use]
+    pub fn with_table_of_contents(
+        mut self,
+        toc: toc::TocConfig,
+    ) -> Self {
+        self.config.generate_toc = true;
+        self.config.toc = toc;
+        self
+    }
+
+    /// Gives every heading an `id`, optionally with a `¶` link.
+    ///
+    /// See [`headings::add_heading_ids`].
+    ///
+    /// # Arguments
+    ///
+    /// * `enable` - Whether to add heading IDs
+    /// * `anchor_links` - Whether to also append anchor links
+    #[must_use]
+    pub fn with_heading_ids(
+        mut self,
+        enable: bool,
+        anchor_links: bool,
+    ) -> Self {
+        self.config.heading_ids = enable;
+        self.config.heading_anchor_links = anchor_links;
+        self
+    }
+
+    /// Enables local tables of contents for `h2` sections.
+    ///
+    /// See [`toc::insert_section_tocs`].
+    ///
+    /// # Arguments
+    ///
+    /// * `enable` - Whether to add section tables of contents
+    #[must_use]
+    pub fn with_section_tocs(mut self, enable: bool) -> Self {
+        self.config.section_tocs = enable;
+        self
+    }
+
+    /// Sets how heading IDs are derived from heading text.
+    ///
+    /// # Arguments
+    ///
+    /// * `strategy` - See [`headings::SlugStrategy`]
+    #[must_use]
+    pub fn with_slug_strategy(
+        mut self,
+        strategy: headings::SlugStrategy,
+    ) -> Self {
+        self.config.slug_strategy = strategy;
+        self
+    }
+
+    /// Sets the provenance comment added to the output.
+    ///
+    /// # Arguments
+    ///
+    /// * `provenance` - See [`provenance::Provenance`]; `None`
+    ///   disables the comment
+    #[must_use]
+    pub fn with_provenance(
+        mut self,
+        provenance: Option<provenance::Provenance>,
+    ) -> Self {
+        self.config.provenance = provenance;
+        self
+    }
+
+    /// Sets how script URLs in the output are handled.
     ///
     /// # Arguments
     ///
-    /// * `enable` - Whether to enable syntax highlighting
-    /// * `theme` - Optional theme name for syntax highlighting
+    /// * `policy` - See [`sanitize::UrlSchemePolicy`]
     #[must_use]
-    pub fn with_syntax_highlighting(
+    pub fn with_url_scheme_policy(
         mut self,
-        enable: bool,
-        theme: Option<String>,
+        policy: sanitize::UrlSchemePolicy,
     ) -> Self {
-        self.config.enable_syntax_highlighting = enable;
-        self.config.syntax_theme = if enable {
-            theme.or_else(|| Some("github".to_string()))
-        } else {
-            None
-        };
+        self.config.url_scheme_policy = policy;
         self
     }
 
-    /// Sets the language for generated content.
+    /// Sets the policy enforced on iframes.
     ///
     /// # Arguments
     ///
-    /// * `language` - The language code (e.g., "en-GB")
+    /// * `policy` - See [`iframes::IframePolicy`]; `None` leaves
+    ///   iframes untouched
     #[must_use]
-    pub fn with_language(
+    pub fn with_iframe_policy(
         mut self,
-        language: impl Into<String>,
+        policy: Option<iframes::IframePolicy>,
     ) -> Self {
-        self.config.language = language.into();
+        self.config.iframe_policy = policy;
         self
     }
 
@@ -460,18 +1411,130 @@ pub fn markdown_to_html(
     config: Option<MarkdownConfig>,
 ) -> Result<String> {
     let config = config.unwrap_or_default();
+    conversion::convert(content, &config.conversion_config())
+}
 
-    if content.is_empty() {
-        return Err(HtmlError::InvalidInput(
-            "Input content is empty".to_string(),
-        ));
-    }
+/// Converts Markdown content to HTML and returns its front matter.
+///
+/// The front matter is parsed according to
+/// `config.front_matter_format` and removed from the output, as with
+/// [`markdown_to_html`].
+///
+/// # Arguments
+///
+/// * `content` - The Markdown content as a Unicode string
+/// * `config` - Optional configuration for the conversion
+///
+/// # Returns
+///
+/// The generated HTML and the front matter as a map of JSON values,
+/// empty if the document has none.
+///
+/// # Errors
+///
+/// Returns an error if the front matter cannot be parsed or HTML
+/// generation fails.
+///
+/// # Examples
+///
+/// ```rust
+/// use html_generator::markdown_to_html_with_front_matter;
+///
+/// let markdown = "+++\ntitle = \"Hello\"\n+++\n# Hello";
+/// let (html, metadata) = markdown_to_html_with_front_matter(markdown, None)?;
+/// assert!(html.contains("<h1>Hello</h1>"));
+/// assert_eq!(metadata["title"], "Hello");
+/// # Ok::<(), html_generator::error::HtmlError>(())
+/// ```
+pub fn markdown_to_html_with_front_matter(
+    content: &str,
+    config: Option<MarkdownConfig>,
+) -> Result<(String, serde_json::Map<String, serde_json::Value>)> {
+    let config = config.unwrap_or_default();
+    let (metadata, _) = front_matter::parse_front_matter(
+        content,
+        config.front_matter_format,
+    )?;
+    let html =
+        conversion::convert(content, &config.conversion_config())?;
+    Ok((html, metadata))
+}
 
-    if content.len() > config.html_config.max_input_size {
-        return Err(HtmlError::InputTooLarge(content.len()));
-    }
+/// Converts Markdown content to HTML and returns its metadata.
+///
+/// Like [`markdown_to_html_with_front_matter`], with the front matter
+/// as a [`FrontMatter`](front_matter::FrontMatter), so static site
+/// generators can read the title, description and author without
+/// parsing the document again.
+///
+/// # Arguments
+///
+/// * `content` - The Markdown content as a Unicode string
+/// * `config` - Optional configuration for the conversion
+///
+/// # Errors
+///
+/// Returns an error if the front matter cannot be parsed or HTML
+/// generation fails.
+///
+/// # Examples
+///
+/// ```rust
+/// use html_generator::markdown_to_html_with_metadata;
+///
+/// let markdown = "---\ntitle: Hello\ntags: [a, b]\n---\n# Hello";
+/// let (html, metadata) = markdown_to_html_with_metadata(markdown, None)?;
+/// assert!(html.contains("<h1>Hello</h1>"));
+/// assert_eq!(metadata.title.as_deref(), Some("Hello"));
+/// assert_eq!(metadata.extra["tags"][0], "a");
+/// # Ok::<(), html_generator::error::HtmlError>(())
+/// ```
+pub fn markdown_to_html_with_metadata(
+    content: &str,
+    config: Option<MarkdownConfig>,
+) -> Result<(String, front_matter::FrontMatter)> {
+    let (html, metadata) =
+        markdown_to_html_with_front_matter(content, config)?;
+    Ok((html, front_matter::FrontMatter::from_map(metadata)))
+}
 
-    generate_html(content, &config.html_config)
+/// Converts Markdown content to a complete HTML document.
+///
+/// The generated fragment is wrapped by `template`, with the front
+/// matter of the document filling its placeholders, see [`templates`].
+///
+/// # Arguments
+///
+/// * `content` - The Markdown content as a Unicode string
+/// * `config` - Optional configuration for the conversion
+/// * `template` - The document layout and head settings
+///
+/// # Errors
+///
+/// Returns an error if the front matter cannot be parsed, HTML
+/// generation fails or the head cannot be generated.
+///
+/// # Examples
+///
+/// ```rust
+/// use html_generator::markdown_to_html_document;
+/// use html_generator::templates::DocumentTemplate;
+///
+/// let markdown = "---\ntitle: Hello\n---\nWorld";
+/// let html =
+///     markdown_to_html_document(markdown, None, &DocumentTemplate::new())?;
+/// assert!(html.contains("<title>Hello</title>"));
+/// assert!(html.contains("<body>\n<p>World</p>\n</body>"));
+/// # Ok::<(), html_generator::error::HtmlError>(())
+/// ```
+pub fn markdown_to_html_document(
+    content: &str,
+    config: Option<MarkdownConfig>,
+    template: &templates::DocumentTemplate,
+) -> Result<String> {
+    let (html, metadata) =
+        markdown_to_html_with_metadata(content, config)?;
+    template.render(&html, &metadata)
 }
 
 /// Converts a Markdown file to HTML.
@@ -488,6 +1551,9 @@ pub fn markdown_to_html(
 /// # Returns
 ///
 /// Returns `Result<()>` indicating success or failure of the operation.
+/// When `config.dry_run` is set, the input is read, validated and
+/// converted but nothing is written; use [`plan_markdown_file_to_html`]
+/// to inspect the would-be output.
 ///
 /// # Errors
 ///
@@ -518,29 +1584,176 @@ pub fn markdown_to_html(
 /// )?;
 /// # Ok::<(), html_generator::error::HtmlError>(())
 /// ```
+#[cfg(feature = "std")]
 #[inline]
 pub fn markdown_file_to_html(
     input: Option<impl AsRef<Path>>,
     output: Option<OutputDestination>,
     config: Option<MarkdownConfig>,
 ) -> Result<()> {
-    let config = config.unwrap_or_default();
+    let mut config = config.unwrap_or_default();
     let output = output.unwrap_or_default();
+    let dry_run = config.dry_run;
 
     // Validate paths first
     validate_paths(&input, &output)?;
 
     // Read and process input
-    let content = read_input(input)?;
+    set_document_source(&mut config.html_config, &input);
+    let content =
+        read_includes(read_input(input.as_ref())?, &input, &config)?;
 
     // Generate HTML
-    let html = markdown_to_html(&content, Some(config))?;
+    let mut html =
+        conversion::convert(&content, &config.conversion_config())?;
+    conversion::run_hook(
+        &config.html_config,
+        hooks::HookStage::BeforeWrite,
+        &mut html,
+    )?;
+
+    if dry_run {
+        return Ok(());
+    }
 
     // Write output
-    write_output(output, html.as_bytes())
+    profiler::profile(
+        config.html_config.profiler.as_ref(),
+        "write",
+        || write_output(output, html.as_bytes()),
+    )
+}
+
+/// The output a conversion would produce, without anything written.
+///
+/// Returned by [`plan_markdown_file_to_html`].
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlannedOutput {
+    /// Description of where the output would be written.
+    pub destination: String,
+
+    /// The generated HTML.
+    pub html: String,
+
+    /// Diagnostics about the would-be write, such as overwriting an
+    /// existing file.
+    pub diagnostics: Vec<diagnostics::Diagnostic>,
+}
+
+/// Performs a dry run of [`markdown_file_to_html`].
+///
+/// Reads, validates and converts the input exactly as
+/// [`markdown_file_to_html`] does, but never writes output regardless
+/// of `config.dry_run`. Useful for CI checks and previewing writes.
+///
+/// # Arguments
+///
+/// * `input` - The input source (file path or None for stdin)
+/// * `output` - The output destination that would be written
+/// * `config` - Optional configuration including encoding settings
+///
+/// # Returns
+///
+/// The generated HTML and its destination as a [`PlannedOutput`].
+///
+/// # Errors
+///
+/// Returns an error if the paths or configuration are invalid, the input
+/// cannot be read, or conversion fails.
+///
+/// # Examples
+///
+/// ```no_run
+/// use html_generator::{plan_markdown_file_to_html, OutputDestination};
+/// use std::path::PathBuf;
+///
+/// let planned = plan_markdown_file_to_html(
+///     Some(PathBuf::from("input.md")),
+///     Some(OutputDestination::File("output.html".into())),
+///     None,
+/// )?;
+/// for diagnostic in &planned.diagnostics {
+///     println!("{}", diagnostic);
+/// }
+/// # Ok::<(), html_generator::error::HtmlError>(())
+/// ```
+#[cfg(feature = "std")]
+pub fn plan_markdown_file_to_html(
+    input: Option<impl AsRef<Path>>,
+    output: Option<OutputDestination>,
+    config: Option<MarkdownConfig>,
+) -> Result<PlannedOutput> {
+    let output = output.unwrap_or_default();
+
+    validate_paths(&input, &output)?;
+    let mut config = config.unwrap_or_default();
+    set_document_source(&mut config.html_config, &input);
+    let content =
+        read_includes(read_input(input.as_ref())?, &input, &config)?;
+    let conversion::Conversion {
+        mut html,
+        repairs,
+        removed_urls,
+        blocked_iframes,
+        ..
+    } = conversion::convert_detailed(
+        &content,
+        &config.conversion_config(),
+    )?;
+    conversion::run_hook(
+        &config.html_config,
+        hooks::HookStage::BeforeWrite,
+        &mut html,
+    )?;
+
+    let mut diagnostics: Vec<_> = repairs
+        .iter()
+        .map(unicode::MojibakeRepair::to_diagnostic)
+        .chain(
+            removed_urls
+                .iter()
+                .map(sanitize::RemovedUrl::to_diagnostic),
+        )
+        .chain(
+            blocked_iframes
+                .iter()
+                .map(iframes::BlockedIframe::to_diagnostic),
+        )
+        .collect();
+    diagnostics.extend(
+        unicode::find_invisible_characters(&html)
+            .iter()
+            .map(unicode::InvisibleCharacter::to_diagnostic),
+    );
+    if config.html_config.writing_mode.is_vertical() {
+        diagnostics.extend(
+            writing_mode::check_writing_mode(&html)
+                .iter()
+                .map(writing_mode::WritingModeConflict::to_diagnostic),
+        );
+    }
+    if let OutputDestination::File(ref path) = output {
+        if Path::new(path).exists() {
+            diagnostics.push(
+                diagnostics::Diagnostic::warning(
+                    "overwrite",
+                    "Existing file would be overwritten",
+                )
+                .with_path(path),
+            );
+        }
+    }
+
+    Ok(PlannedOutput {
+        destination: output.to_string(),
+        html,
+        diagnostics,
+    })
 }
 
 /// Validates input and output paths
+#[cfg(feature = "std")]
 fn validate_paths(
     input: &Option<impl AsRef<Path>>,
     output: &OutputDestination,
@@ -554,7 +1767,53 @@ fn validate_paths(
     Ok(())
 }
 
+/// Passes the input path to the hooks and profiler of `config`, unless
+/// they have a source already.
+#[cfg(feature = "std")]
+fn set_document_source(
+    config: &mut HtmlConfig,
+    input: &Option<impl AsRef<Path>>,
+) {
+    if let Some(path) = input {
+        let source = path.as_ref().to_string_lossy();
+        if !config.hooks.is_empty() && config.hooks.source().is_none() {
+            config.hooks =
+                config.hooks.clone().with_source(source.clone());
+        }
+        if let Some(profiler) = &config.profiler {
+            if profiler.document().is_none() {
+                config.profiler = Some(profiler.with_document(source));
+            }
+        }
+    }
+}
+
+/// Expands the includes of `content` read from `input` when enabled,
+/// under the directory of the input file, skipping links like a batch
+/// does by default.
+#[cfg(feature = "std")]
+fn read_includes(
+    content: String,
+    input: &Option<impl AsRef<Path>>,
+    config: &MarkdownConfig,
+) -> Result<String> {
+    if !config.expand_includes {
+        return Ok(content);
+    }
+    let path = input.as_ref().map(AsRef::as_ref);
+    let root =
+        path.and_then(Path::parent).unwrap_or_else(|| Path::new(""));
+    includes::expand_includes(
+        &content,
+        root,
+        path.and_then(Path::file_name).map(Path::new),
+        config.max_include_depth,
+        batch::SymlinkPolicy::default(),
+    )
+}
+
 /// Reads content from the input source
+#[cfg(feature = "std")]
 fn read_input(input: Option<impl AsRef<Path>>) -> Result<String> {
     match input {
         Some(path) => {
@@ -589,6 +1848,7 @@ fn read_input(input: Option<impl AsRef<Path>>) -> Result<String> {
 }
 
 /// Writes content to the output destination
+#[cfg(feature = "std")]
 fn write_output(
     output: OutputDestination,
     content: &[u8],
@@ -680,20 +1940,13 @@ fn write_output(
 /// assert!(!validate_language_code("en_GB")); // Invalid - wrong separator
 /// ```
 pub fn validate_language_code(lang: &str) -> bool {
-    use once_cell::sync::Lazy;
-    use regex::Regex;
-
-    // Pre-compiled regex using Lazy<Regex>
-    static LANG_REGEX: Lazy<Regex> = Lazy::new(|| {
-        Regex::new(r"^[a-z]{2}(?:-[A-Z]{2})$")
-            .expect("Failed to compile language code regex")
-    });
+    use crate::patterns::root::LANG_REGEX;
 
     // Match the input against the pre-compiled regex
     LANG_REGEX.is_match(lang)
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 mod tests {
     use super::*;
     use regex::Regex;
@@ -1001,6 +2254,146 @@ fn test_writer_output_no_input() {
 
             assert!(result.is_err());
         }
+
+        #[test]
+        fn test_dry_run_writes_nothing() -> Result<()> {
+            let temp_dir = setup_test_dir();
+            let input_path =
+                create_test_file(&temp_dir, "# Test\n\nHello world");
+            let output_path = temp_dir.path().join("test.html");
+
+            markdown_file_to_html(
+                Some(&input_path),
+                Some(OutputDestination::File(
+                    output_path.to_string_lossy().into(),
+                )),
+                Some(MarkdownConfig {
+                    dry_run: true,
+                    ..Default::default()
+                }),
+            )?;
+
+            assert!(!output_path.exists());
+            Ok(())
+        }
+
+        #[test]
+        fn test_dry_run_still_validates() {
+            let result = markdown_file_to_html(
+                Some(Path::new("nonexistent.md")),
+                None,
+                Some(MarkdownConfig {
+                    dry_run: true,
+                    ..Default::default()
+                }),
+            );
+
+            assert!(matches!(result, Err(HtmlError::Io(_))));
+        }
+
+        #[test]
+        fn test_plan_reports_output_and_overwrite() -> Result<()> {
+            let temp_dir = setup_test_dir();
+            let input_path =
+                create_test_file(&temp_dir, "# Test\n\nHello world");
+            let output_path = temp_dir.path().join("test.html");
+            let destination = || {
+                Some(OutputDestination::File(
+                    output_path.to_string_lossy().into(),
+                ))
+            };
+
+            let planned = plan_markdown_file_to_html(
+                Some(&input_path),
+                destination(),
+                None,
+            )?;
+            assert!(planned.html.contains("<h1>Test</h1>"));
+            assert!(planned.diagnostics.is_empty());
+            assert!(!output_path.exists());
+
+            std::fs::write(&output_path, "old")?;
+            let planned = plan_markdown_file_to_html(
+                Some(&input_path),
+                destination(),
+                None,
+            )?;
+            assert_eq!(planned.diagnostics.len(), 1);
+            assert_eq!(planned.diagnostics[0].code, "overwrite");
+            assert_eq!(std::fs::read_to_string(&output_path)?, "old");
+
+            Ok(())
+        }
+
+        #[test]
+        fn test_includes_are_expanded_when_enabled() -> Result<()> {
+            let temp_dir = setup_test_dir();
+            let input_path = create_test_file(
+                &temp_dir,
+                "# Test\n\n{{include \"shared.md\"}}\n",
+            );
+            std::fs::write(
+                temp_dir.path().join("shared.md"),
+                "Shared *text*",
+            )?;
+            let config = MarkdownConfig {
+                expand_includes: true,
+                ..MarkdownConfig::default()
+            };
+
+            let planned = plan_markdown_file_to_html(
+                Some(&input_path),
+                None,
+                Some(config),
+            )?;
+            assert!(planned
+                .html
+                .contains("<p>Shared <em>text</em></p>"));
+            let planned = plan_markdown_file_to_html(
+                Some(&input_path),
+                None,
+                None,
+            )?;
+            assert!(planned.html.contains("{{include"));
+            Ok(())
+        }
+
+        #[test]
+        fn test_before_write_hook_sees_the_source() -> Result<()> {
+            let temp_dir = setup_test_dir();
+            let input_path = create_test_file(&temp_dir, "# Test");
+            let output_path = temp_dir.path().join("test.html");
+            let hooks =
+                hooks::PipelineHooks::new().before_write(|context| {
+                    if context
+                        .source
+                        .map_or(false, |s| s.ends_with(".md"))
+                    {
+                        context.content.push_str("<footer></footer>\n");
+                    }
+                    Ok(())
+                });
+
+            markdown_file_to_html(
+                Some(&input_path),
+                Some(OutputDestination::File(
+                    output_path.to_string_lossy().into(),
+                )),
+                Some(MarkdownConfig {
+                    html_config: HtmlConfig {
+                        hooks,
+                        ..HtmlConfig::default()
+                    },
+                    ..MarkdownConfig::default()
+                }),
+            )?;
+
+            assert_eq!(
+                std::fs::read_to_string(output_path)?,
+                "<h1>Test</h1>\n<footer></footer>\n"
+            );
+            Ok(())
+        }
     }
 
     mod language_validation_tests {
@@ -1079,7 +2472,9 @@ fn test_end_to_end_conversion() -> Result<()> {
             )?;
 
             let html = std::fs::read_to_string(&output_path)?;
-            assert!(html.contains("<h1>Hello World</h1>"));
+            assert!(html
+                .contains(r#"<h1 id="hello-world">Hello World</h1>"#));
+            assert!(html.contains(r##"<a href="#hello-world">"##));
             assert!(html.contains("<strong>bold</strong>"));
             assert!(html.contains("<ul>"));
 
@@ -1116,6 +2511,11 @@ fn test_markdown_config_custom_encoding() {
             let config = MarkdownConfig {
                 encoding: "latin1".to_string(),
                 html_config: HtmlConfig::default(),
+                dry_run: false,
+                front_matter_format:
+                    front_matter::FrontMatterFormat::Auto,
+                expand_includes: false,
+                max_include_depth: constants::DEFAULT_MAX_INCLUDE_DEPTH,
             };
             assert_eq!(config.encoding, "latin1");
         }
@@ -1216,6 +2616,46 @@ fn test_html_config_validation_edge_cases() {
             };
             assert!(config.validate().is_err());
         }
+
+        #[test]
+        fn test_merge_empty_overrides_is_identity() {
+            let base = HtmlConfig {
+                minify_output: true,
+                ..Default::default()
+            };
+            let overrides = PartialHtmlConfig::default();
+            assert!(overrides.is_empty());
+            assert_eq!(base.merge(overrides), base);
+        }
+
+        #[test]
+        fn test_merge_overrides_selected_fields() {
+            let base = HtmlConfig::default();
+            let merged = base.merge(PartialHtmlConfig {
+                language: Some("de-DE".to_string()),
+                syntax_theme: Some(None),
+                generate_toc: Some(true),
+                heading_offset: Some(1),
+                ..Default::default()
+            });
+
+            assert_eq!(merged.language, "de-DE");
+            assert_eq!(merged.syntax_theme, None);
+            assert!(merged.generate_toc);
+            assert_eq!(merged.heading_offset, 1);
+            assert_eq!(merged.minify_output, base.minify_output);
+            assert_eq!(base.language, constants::DEFAULT_LANGUAGE);
+        }
+
+        #[test]
+        fn test_merge_result_can_be_invalid() {
+            let merged =
+                HtmlConfig::default().merge(PartialHtmlConfig {
+                    language: Some("english".to_string()),
+                    ..Default::default()
+                });
+            assert!(merged.validate().is_err());
+        }
     }
 
     mod markdown_processing_tests {
@@ -1235,6 +2675,38 @@ fn test_markdown_to_html_with_front_matter() -> Result<()> {
             Ok(())
         }
 
+        #[test]
+        fn test_front_matter_format_is_honoured() -> Result<()> {
+            let markdown = "{\"a\": 1}\n\nText";
+            let config = |format| MarkdownConfig {
+                front_matter_format: format,
+                ..MarkdownConfig::default()
+            };
+
+            let html = markdown_to_html(
+                markdown,
+                Some(config(front_matter::FrontMatterFormat::Yaml)),
+            )?;
+            assert!(html.contains("{&quot;a&quot;: 1}"), "{}", html);
+            assert!(html.contains("<p>Text</p>"));
+
+            let (html, metadata) = markdown_to_html_with_front_matter(
+                markdown,
+                Some(config(front_matter::FrontMatterFormat::Json)),
+            )?;
+            assert_eq!(html.trim(), "<p>Text</p>");
+            assert_eq!(metadata["a"], 1);
+
+            for markdown in ["+++ plus\n", "{\"a\": 1} trailing\n\n# H"]
+            {
+                let (html, metadata) =
+                    markdown_to_html_with_front_matter(markdown, None)?;
+                assert_eq!(html, markdown_to_html(markdown, None)?);
+                assert!(metadata.is_empty());
+            }
+            Ok(())
+        }
+
         #[test]
         fn test_markdown_to_html_with_code_blocks() -> Result<()> {
             let markdown = r#"```rust
@@ -1276,6 +2748,11 @@ fn test_invalid_encoding_handling() {
             let config = MarkdownConfig {
                 encoding: "unsupported-encoding".to_string(),
                 html_config: HtmlConfig::default(),
+                dry_run: false,
+                front_matter_format:
+                    front_matter::FrontMatterFormat::Auto,
+                expand_includes: false,
+                max_include_depth: constants::DEFAULT_MAX_INCLUDE_DEPTH,
             };
             // Simulate usage where encoding matters
             let result = markdown_to_html("# Test", Some(config));
@@ -1602,8 +3079,8 @@ fn main() {
 
             // Verify all expected elements are present
             println!("Generated HTML: {}", html);
-            assert!(html.contains("<h1>"));
-            assert!(html.contains("<h2>"));
+            assert!(html.contains("<h1 id="));
+            assert!(html.contains("<h2 id="));
             assert!(html.contains("<em>"));
             assert!(html.contains("<strong>"));
             assert!(html.contains("<ul>"));
@@ -1631,6 +3108,11 @@ fn test_missing_html_config_fallback() {
                     syntax_theme: None,
                     ..Default::default()
                 },
+                dry_run: false,
+                front_matter_format:
+                    front_matter::FrontMatterFormat::Auto,
+                expand_includes: false,
+                max_include_depth: constants::DEFAULT_MAX_INCLUDE_DEPTH,
             };
             let result = markdown_to_html("# Test", Some(config));
             assert!(result.is_ok());
@@ -0,0 +1,538 @@
+// Copyright © 2025 HTML Generator. All rights reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Resource limits for untrusted input.
+//!
+//! Small Markdown documents can be crafted to produce enormous or
+//! deeply nested output: thousands of nested blockquotes, tables whose
+//! cell count grows quadratically with the input, or reference-heavy
+//! text that expands many times over. [`ResourceLimits`] bounds these
+//! cases, and conversion fails with [`HtmlError::LimitExceeded`] instead
+//! of exhausting memory or the stack.
+//!
+//! Limits are set through [`HtmlConfig::limits`](crate::HtmlConfig::limits)
+//! and checked by every conversion.
+//!
+//! # Examples
+//!
+//! ```
+//! use html_generator::conversion::convert;
+//! use html_generator::error::{HtmlError, LimitKind};
+//! use html_generator::limits::ResourceLimits;
+//! use html_generator::HtmlConfig;
+//!
+//! let config = HtmlConfig {
+//!     limits: ResourceLimits {
+//!         max_nesting_depth: 3,
+//!         ..Default::default()
+//!     },
+//!     ..Default::default()
+//! };
+//!
+//! let result = convert(">>>> too deep", &config);
+//! assert!(matches!(
+//!     result,
+//!     Err(HtmlError::LimitExceeded { kind: LimitKind::NestingDepth, .. })
+//! ));
+//! ```
+
+use crate::error::{HtmlError, LimitKind, Result};
+use crate::patterns::limits::RAW_HTML_TAG_REGEX;
+use crate::serializer::VOID_ELEMENTS;
+use comrak::{
+    arena_tree::NodeEdge,
+    nodes::{AstNode, NodeValue},
+    parse_document, Arena, ComrakOptions,
+};
+use std::io;
+
+/// Default maximum nesting depth of blockquotes, lists and raw HTML
+/// elements.
+pub const DEFAULT_MAX_NESTING_DEPTH: usize = 100;
+
+/// Default maximum number of table cells in a document.
+pub const DEFAULT_MAX_TABLE_CELLS: usize = 100_000;
+
+/// Default maximum ratio of output size to input size.
+pub const DEFAULT_MAX_OUTPUT_RATIO: usize = 100;
+
+/// Output size that is always allowed, whatever the input size.
+///
+/// Without this floor, tiny inputs would trip the output ratio because
+/// even `a` becomes `<p>a</p>`.
+pub const MIN_OUTPUT_BUDGET: usize = 1024 * 1024;
+
+/// Bounds applied to each conversion.
+///
+/// A limit of `0` disables the corresponding check.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schema", schemars(deny_unknown_fields))]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
+pub struct ResourceLimits {
+    /// Maximum nesting depth of blockquotes, lists and raw HTML
+    /// elements.
+    pub max_nesting_depth: usize,
+    /// Maximum number of table cells in a document.
+    pub max_table_cells: usize,
+    /// Maximum size of the generated HTML as a multiple of the input
+    /// size. Outputs up to [`MIN_OUTPUT_BUDGET`] bytes are always
+    /// allowed.
+    pub max_output_ratio: usize,
+}
+
+impl Default for ResourceLimits {
+    fn default() -> Self {
+        Self {
+            max_nesting_depth: DEFAULT_MAX_NESTING_DEPTH,
+            max_table_cells: DEFAULT_MAX_TABLE_CELLS,
+            max_output_ratio: DEFAULT_MAX_OUTPUT_RATIO,
+        }
+    }
+}
+
+impl ResourceLimits {
+    /// Returns limits with every check disabled.
+    pub fn unlimited() -> Self {
+        Self {
+            max_nesting_depth: 0,
+            max_table_cells: 0,
+            max_output_ratio: 0,
+        }
+    }
+
+    /// Checks the structure of a Markdown document against the limits.
+    ///
+    /// Conversions check the document they parse with
+    /// [`check_document`](Self::check_document) instead, so this is only
+    /// needed to validate input without converting it.
+    ///
+    /// # Arguments
+    ///
+    /// * `markdown` - The Markdown input
+    ///
+    /// # Errors
+    ///
+    /// Returns [`HtmlError::LimitExceeded`] if the document nests
+    /// blockquotes, lists or raw HTML elements too deeply or contains
+    /// too many table cells.
+    pub fn check_markdown(&self, markdown: &str) -> Result<()> {
+        if self.max_nesting_depth == 0 && self.max_table_cells == 0 {
+            return Ok(());
+        }
+
+        let mut options = ComrakOptions::default();
+        options.extension.table = true;
+        let arena = Arena::new();
+        self.check_document(parse_document(&arena, markdown, &options))
+    }
+
+    /// Checks a parsed Markdown document against the limits.
+    ///
+    /// Blockquotes and lists add one level of nesting each, as do raw
+    /// HTML elements opened and not yet closed, except void elements
+    /// and those whose end tag may be omitted, such as `<p>` and `<li>`.
+    ///
+    /// # Arguments
+    ///
+    /// * `root` - The document node returned by
+    ///   [`comrak::parse_document`]
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`check_markdown`](Self::check_markdown).
+    pub fn check_document<'a>(
+        &self,
+        root: &'a AstNode<'a>,
+    ) -> Result<()> {
+        if self.max_nesting_depth == 0 && self.max_table_cells == 0 {
+            return Ok(());
+        }
+
+        let mut depth = 0;
+        let mut html_depth = 0;
+        let mut cells = 0;
+        for edge in root.traverse() {
+            match edge {
+                NodeEdge::Start(node) => {
+                    match &node.data.borrow().value {
+                        NodeValue::BlockQuote
+                        | NodeValue::MultilineBlockQuote(_)
+                        | NodeValue::List(_) => {
+                            depth += 1;
+                            check(
+                                LimitKind::NestingDepth,
+                                depth + html_depth,
+                                self.max_nesting_depth,
+                            )?;
+                        }
+                        NodeValue::HtmlBlock(block) => {
+                            html_depth = self.raw_html_depth(
+                                &block.literal,
+                                depth,
+                                html_depth,
+                            )?;
+                        }
+                        NodeValue::HtmlInline(html) => {
+                            html_depth = self.raw_html_depth(
+                                html, depth, html_depth,
+                            )?;
+                        }
+                        NodeValue::TableCell => {
+                            cells += 1;
+                            check(
+                                LimitKind::TableCells,
+                                cells,
+                                self.max_table_cells,
+                            )?;
+                        }
+                        _ => {}
+                    }
+                }
+                NodeEdge::End(node) => {
+                    if matches!(
+                        node.data.borrow().value,
+                        NodeValue::BlockQuote
+                            | NodeValue::MultilineBlockQuote(_)
+                            | NodeValue::List(_)
+                    ) {
+                        depth -= 1;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the number of raw HTML elements open after `html`, given
+    /// `open` before it, checking the nesting depth on each start tag.
+    fn raw_html_depth(
+        &self,
+        html: &str,
+        depth: usize,
+        mut open: usize,
+    ) -> Result<usize> {
+        for caps in RAW_HTML_TAG_REGEX.captures_iter(html) {
+            let name = caps[2].to_ascii_lowercase();
+            if VOID_ELEMENTS.contains(&name.as_str())
+                || OPTIONAL_END_TAGS.contains(&name.as_str())
+            {
+                continue;
+            }
+            if !caps[1].is_empty() {
+                open = open.saturating_sub(1);
+            } else if caps[3].is_empty() {
+                open += 1;
+                check(
+                    LimitKind::NestingDepth,
+                    depth + open,
+                    self.max_nesting_depth,
+                )?;
+            }
+        }
+        Ok(open)
+    }
+
+    /// Returns the output size allowed for an input of `input_len`
+    /// bytes, or `None` if output size is not limited.
+    pub fn output_budget(&self, input_len: usize) -> Option<usize> {
+        if self.max_output_ratio == 0 {
+            return None;
+        }
+        Some(
+            input_len
+                .saturating_mul(self.max_output_ratio)
+                .max(MIN_OUTPUT_BUDGET),
+        )
+    }
+
+    /// Checks the size of generated HTML against the output ratio.
+    ///
+    /// Conversions also stop writing Markdown output once it exceeds
+    /// the budget, through an [`OutputBuffer`].
+    ///
+    /// # Arguments
+    ///
+    /// * `input_len` - Size of the Markdown input in bytes
+    /// * `output_len` - Size of the generated HTML in bytes
+    ///
+    /// # Errors
+    ///
+    /// Returns [`HtmlError::LimitExceeded`] if the output is larger
+    /// than both `input_len * max_output_ratio` and
+    /// [`MIN_OUTPUT_BUDGET`].
+    pub fn check_output(
+        &self,
+        input_len: usize,
+        output_len: usize,
+    ) -> Result<()> {
+        match self.output_budget(input_len) {
+            Some(budget) => {
+                check(LimitKind::OutputSize, output_len, budget)
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+/// Elements whose end tag may be omitted, which are not counted as
+/// nesting because documents routinely leave them open.
+const OPTIONAL_END_TAGS: &[&str] = &[
+    "body", "caption", "colgroup", "dd", "dt", "head", "html", "li",
+    "optgroup", "option", "p", "rp", "rt", "tbody", "td", "tfoot",
+    "th", "thead", "tr",
+];
+
+/// A writer collecting generated HTML that fails as soon as the output
+/// exceeds its budget, rather than after the whole output is built.
+#[derive(Debug, Default)]
+pub struct OutputBuffer {
+    html: Vec<u8>,
+    budget: Option<usize>,
+    exceeded: Option<usize>,
+}
+
+impl OutputBuffer {
+    /// Creates a buffer for the output of an `input_len` byte input
+    /// under `limits`.
+    pub fn new(limits: &ResourceLimits, input_len: usize) -> Self {
+        Self {
+            html: Vec::new(),
+            budget: limits.output_budget(input_len),
+            exceeded: None,
+        }
+    }
+
+    /// Returns the HTML written, or the error of the write that
+    /// exceeded the budget.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`HtmlError::LimitExceeded`] if the output exceeded the
+    /// budget, or [`HtmlError::InvalidInput`] if it is not UTF-8.
+    pub fn into_html(self) -> Result<String> {
+        if let (Some(actual), Some(limit)) =
+            (self.exceeded, self.budget)
+        {
+            return Err(HtmlError::LimitExceeded {
+                kind: LimitKind::OutputSize,
+                actual,
+                limit,
+            });
+        }
+        String::from_utf8(self.html).map_err(|err| {
+            HtmlError::InvalidInput(format!(
+                "Generated HTML is not UTF-8: {}",
+                err
+            ))
+        })
+    }
+}
+
+impl io::Write for OutputBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let actual = self.html.len().saturating_add(buf.len());
+        if let Some(budget) = self.budget {
+            if actual > budget {
+                self.exceeded = Some(actual);
+                return Err(io::Error::new(
+                    io::ErrorKind::Other,
+                    "output size limit exceeded",
+                ));
+            }
+        }
+        self.html.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Fails if `actual` exceeds a non-zero `limit`.
+fn check(kind: LimitKind, actual: usize, limit: usize) -> Result<()> {
+    if limit != 0 && actual > limit {
+        return Err(HtmlError::LimitExceeded {
+            kind,
+            actual,
+            limit,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conversion::convert;
+    use crate::generator::generate_html;
+    use crate::HtmlConfig;
+    use std::io::Write;
+
+    #[test]
+    fn test_nesting_depth() {
+        let limits = ResourceLimits {
+            max_nesting_depth: 3,
+            ..Default::default()
+        };
+        assert!(limits.check_markdown("> > > ok").is_ok());
+        assert!(limits.check_markdown("- a\n  - b\n    > c").is_ok());
+        assert!(matches!(
+            limits.check_markdown("- a\n  - b\n    > c\n    > - d"),
+            Err(HtmlError::LimitExceeded {
+                kind: LimitKind::NestingDepth,
+                actual: 4,
+                limit: 3,
+            })
+        ));
+    }
+
+    #[test]
+    fn test_deep_nesting_is_rejected_by_default() {
+        let markdown = ">".repeat(10_000);
+        assert!(matches!(
+            ResourceLimits::default().check_markdown(&markdown),
+            Err(HtmlError::LimitExceeded {
+                kind: LimitKind::NestingDepth,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_table_cells() {
+        let limits = ResourceLimits {
+            max_table_cells: 6,
+            ..Default::default()
+        };
+        let table = "| a | b |\n|---|---|\n| 1 | 2 |\n";
+        assert!(limits.check_markdown(table).is_ok());
+        let err = limits
+            .check_markdown(&format!("{}| 3 | 4 |\n| 5 |\n", table))
+            .unwrap_err();
+        assert_eq!(err.code(), "limit_exceeded");
+    }
+
+    #[test]
+    fn test_raw_html_nesting() {
+        let limits = ResourceLimits {
+            max_nesting_depth: 3,
+            ..Default::default()
+        };
+        let nested = |depth: usize| {
+            format!(
+                "{}\ntext\n{}",
+                "<div>".repeat(depth),
+                "</div>".repeat(depth)
+            )
+        };
+        assert!(limits.check_markdown(&nested(3)).is_ok());
+        assert!(matches!(
+            limits.check_markdown(&nested(4)),
+            Err(HtmlError::LimitExceeded {
+                kind: LimitKind::NestingDepth,
+                actual: 4,
+                limit: 3,
+            })
+        ));
+        assert!(limits
+            .check_markdown("> > <span><em>x</em></span>")
+            .is_err());
+
+        let unclosed = "<p>a\n\n<li>b<br><img src=x />\n\n".repeat(10);
+        assert!(limits.check_markdown(&unclosed).is_ok());
+        assert!(limits
+            .check_markdown(&"<div>x</div>\n\n".repeat(10))
+            .is_ok());
+    }
+
+    #[test]
+    fn test_conversion_checks_raw_html_nesting() {
+        let config = HtmlConfig {
+            limits: ResourceLimits {
+                max_nesting_depth: 10,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let markdown = format!("{}x", "<section>".repeat(11));
+        assert!(matches!(
+            convert(&markdown, &config),
+            Err(HtmlError::LimitExceeded {
+                kind: LimitKind::NestingDepth,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_output_budget_is_enforced_while_writing() {
+        let mut buffer = OutputBuffer::new(
+            &ResourceLimits {
+                max_output_ratio: 1,
+                ..Default::default()
+            },
+            0,
+        );
+        buffer.write_all(&[b'a'; MIN_OUTPUT_BUDGET]).unwrap();
+        assert!(buffer.write_all(b"b").is_err());
+        assert!(matches!(
+            buffer.into_html(),
+            Err(HtmlError::LimitExceeded {
+                kind: LimitKind::OutputSize,
+                actual,
+                limit: MIN_OUTPUT_BUDGET,
+            }) if actual == MIN_OUTPUT_BUDGET + 1
+        ));
+
+        // Each row expands to as many cells as the header has
+        let markdown = format!(
+            "{}|\n{}|\n{}",
+            "| a ".repeat(500),
+            "|---".repeat(500),
+            "|x\n".repeat(500)
+        );
+        let config = HtmlConfig {
+            limits: ResourceLimits {
+                max_output_ratio: 10,
+                max_table_cells: 0,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert!(matches!(
+            generate_html(&markdown, &config),
+            Err(HtmlError::LimitExceeded {
+                kind: LimitKind::OutputSize,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_output_ratio() {
+        let limits = ResourceLimits {
+            max_output_ratio: 2,
+            ..Default::default()
+        };
+        assert!(limits.check_output(10, MIN_OUTPUT_BUDGET).is_ok());
+        assert!(limits
+            .check_output(10, MIN_OUTPUT_BUDGET + 1)
+            .is_err());
+        assert!(limits
+            .check_output(MIN_OUTPUT_BUDGET, 2 * MIN_OUTPUT_BUDGET)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_unlimited() {
+        let limits = ResourceLimits::unlimited();
+        assert!(limits.check_markdown(&">".repeat(500)).is_ok());
+        assert!(limits.check_output(1, usize::MAX).is_ok());
+    }
+}
@@ -0,0 +1,1001 @@
+// Copyright © 2025 HTML Generator. All rights reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Rewriting of internal Markdown links.
+//!
+//! Authors link between documents by file name, e.g.
+//! `[guide](./guide.md#setup)`. Once converted, those links must point
+//! at the generated pages instead. A [`LinkMap`] records the output URL
+//! of every source file and rewrites `href` attributes that target
+//! `.md` or `.markdown` files into relative links to those URLs,
+//! preserving query strings and fragments. Links to Markdown files that
+//! are not in the map are reported as [`UnresolvedLink`]s.
+//!
+//! The resolved links between pages form a [`LinkGraph`], which finds
+//! orphan pages, that no other page links to, and dead ends, that link
+//! to no other page. It can be exported as Graphviz DOT or JSON to
+//! visualize the structure of a site.
+//!
+//! Links to other sites are checked against a [`UrlPolicy`], which can
+//! deny domains such as trackers and URL shorteners, restrict links to
+//! an allowlist and require or enforce HTTPS.
+//!
+//! # Examples
+//!
+//! ```
+//! use html_generator::links::LinkMap;
+//!
+//! let mut links = LinkMap::new();
+//! links.insert("index.md", "/");
+//! links.insert("docs/guide.md", "/docs/guide/");
+//!
+//! let (html, unresolved) = links.rewrite(
+//!     r#"<a href="docs/guide.md#setup">Guide</a> <a href="gone.md">?</a>"#,
+//!     "index.md",
+//! );
+//! assert_eq!(
+//!     html,
+//!     r#"<a href="docs/guide/#setup">Guide</a> <a href="gone.md">?</a>"#
+//! );
+//! assert_eq!(unresolved[0].href, "gone.md");
+//! ```
+
+use crate::anchors::anchor_namespace;
+use crate::diagnostics::Diagnostic;
+use crate::error::{HtmlError, Result};
+use crate::patterns::links::HREF_REGEX;
+use crate::utils::{domain_matches, external_host};
+use regex::Captures;
+use serde_json::json;
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fmt,
+    path::{Component, Path, PathBuf},
+};
+
+/// A link to a Markdown file that is not part of the [`LinkMap`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnresolvedLink {
+    /// The `href` as written in the document.
+    pub href: String,
+    /// The linked file, relative to the input root, or `None` if the
+    /// link points outside the input root.
+    pub target: Option<PathBuf>,
+}
+
+impl UnresolvedLink {
+    /// Converts the link into a warning [`Diagnostic`] with the code
+    /// `broken_link`.
+    pub fn to_diagnostic(&self) -> Diagnostic {
+        Diagnostic::warning("broken_link", self.to_string())
+    }
+}
+
+impl fmt::Display for UnresolvedLink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.target {
+            Some(target) => write!(
+                f,
+                "Link '{}' points to '{}', which is not a converted file",
+                self.href,
+                target.display()
+            ),
+            None => write!(
+                f,
+                "Link '{}' points outside the input directory",
+                self.href
+            ),
+        }
+    }
+}
+
+/// Why a link violates a [`UrlPolicy`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum UrlViolationKind {
+    /// The host matches an entry of [`UrlPolicy::deny`].
+    Denied,
+    /// The host matches no entry of a non-empty [`UrlPolicy::allow`].
+    NotAllowed,
+    /// The link uses `http` while [`UrlPolicy::require_https`] is set.
+    Insecure,
+}
+
+impl UrlViolationKind {
+    /// Returns the diagnostic code, e.g. `denied_url`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            UrlViolationKind::Denied => "denied_url",
+            UrlViolationKind::NotAllowed => "unlisted_url",
+            UrlViolationKind::Insecure => "insecure_url",
+        }
+    }
+}
+
+/// A link that violates a [`UrlPolicy`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UrlViolation {
+    /// The `href` as written in the document.
+    pub href: String,
+    /// The lowercase host of the link.
+    pub host: String,
+    /// Why the link violates the policy.
+    pub kind: UrlViolationKind,
+}
+
+impl UrlViolation {
+    /// Converts the violation into a warning [`Diagnostic`] with the
+    /// code of its [`UrlViolationKind`].
+    pub fn to_diagnostic(&self) -> Diagnostic {
+        Diagnostic::warning(self.kind.code(), self.to_string())
+    }
+}
+
+impl fmt::Display for UrlViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            UrlViolationKind::Denied => write!(
+                f,
+                "Link '{}' points to denied domain '{}'",
+                self.href, self.host
+            ),
+            UrlViolationKind::NotAllowed => write!(
+                f,
+                "Link '{}' points to '{}', which is not on the allowlist",
+                self.href, self.host
+            ),
+            UrlViolationKind::Insecure => {
+                write!(f, "Link '{}' does not use HTTPS", self.href)
+            }
+        }
+    }
+}
+
+/// Rules for links to other sites.
+///
+/// Domains match their subdomains too, so `example.com` covers
+/// `www.example.com`. Only absolute `http`, `https` and
+/// protocol-relative links are checked. The default policy accepts
+/// every link.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schema", schemars(deny_unknown_fields))]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
+pub struct UrlPolicy {
+    /// Domains links may point to. An empty list allows every domain
+    /// that is not denied.
+    pub allow: Vec<String>,
+    /// Domains links must not point to, such as trackers or URL
+    /// shorteners. Takes precedence over [`allow`](Self::allow).
+    pub deny: Vec<String>,
+    /// Report links using `http` instead of `https`.
+    pub require_https: bool,
+    /// Rewrite `http` links to `https` instead of reporting them.
+    pub upgrade_to_https: bool,
+}
+
+impl UrlPolicy {
+    /// Returns `true` if the policy accepts every link.
+    pub fn is_permissive(&self) -> bool {
+        self.allow.is_empty()
+            && self.deny.is_empty()
+            && !self.require_https
+            && !self.upgrade_to_https
+    }
+
+    /// Checks a single URL.
+    ///
+    /// # Arguments
+    ///
+    /// * `href` - The link target
+    ///
+    /// # Returns
+    ///
+    /// The violation, or `None` if the URL is acceptable or not an
+    /// external link. With [`upgrade_to_https`](Self::upgrade_to_https)
+    /// set, `http` links are not reported as insecure.
+    pub fn check(&self, href: &str) -> Option<UrlViolation> {
+        let (scheme, host) = external_host(href)?;
+        let kind = if self.deny.iter().any(|d| domain_matches(&host, d))
+        {
+            UrlViolationKind::Denied
+        } else if !self.allow.is_empty()
+            && !self.allow.iter().any(|d| domain_matches(&host, d))
+        {
+            UrlViolationKind::NotAllowed
+        } else if scheme == "http"
+            && self.require_https
+            && !self.upgrade_to_https
+        {
+            UrlViolationKind::Insecure
+        } else {
+            return None;
+        };
+        Some(UrlViolation {
+            href: href.to_string(),
+            host,
+            kind,
+        })
+    }
+
+    /// Checks the links of an HTML document, upgrading `http` links to
+    /// `https` if [`upgrade_to_https`](Self::upgrade_to_https) is set.
+    ///
+    /// # Arguments
+    ///
+    /// * `html` - The HTML to check
+    ///
+    /// # Returns
+    ///
+    /// The HTML, with upgraded links, and the violations found.
+    pub fn apply(&self, html: &str) -> (String, Vec<UrlViolation>) {
+        if self.is_permissive() {
+            return (html.to_string(), Vec::new());
+        }
+        let mut violations = Vec::new();
+        let html =
+            HREF_REGEX.replace_all(html, |caps: &Captures<'_>| {
+                let href = &caps[2];
+                if let Some(violation) = self.check(href) {
+                    violations.push(violation);
+                }
+                match href.get(..7) {
+                    Some(prefix)
+                        if self.upgrade_to_https
+                            && prefix
+                                .eq_ignore_ascii_case("http://") =>
+                    {
+                        format!(
+                            "{}https://{}{}",
+                            &caps[1],
+                            &href[7..],
+                            &caps[3]
+                        )
+                    }
+                    _ => caps[0].to_string(),
+                }
+            });
+        (html.into_owned(), violations)
+    }
+}
+
+/// Maps Markdown source files to the URLs of their generated pages.
+///
+/// Source paths are relative to the input root; URLs are root-relative,
+/// such as `/guide.html` or `/guide/`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LinkMap {
+    urls: HashMap<PathBuf, String>,
+    namespace_fragments: bool,
+}
+
+impl LinkMap {
+    /// Creates an empty map.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the output URL of a source file.
+    pub fn insert(
+        &mut self,
+        source: impl AsRef<Path>,
+        url: impl Into<String>,
+    ) {
+        let _ =
+            self.urls.insert(normalize(source.as_ref()), url.into());
+    }
+
+    /// Returns the output URL of a source file, if known.
+    pub fn url(&self, source: impl AsRef<Path>) -> Option<&str> {
+        self.urls
+            .get(&normalize(source.as_ref()))
+            .map(String::as_str)
+    }
+
+    /// Prefixes the fragments of rewritten links with the
+    /// [`anchor_namespace`] of the target, for pages whose anchors were
+    /// namespaced with [`namespace_anchors`].
+    ///
+    /// [`namespace_anchors`]: crate::anchors::namespace_anchors
+    pub fn set_namespace_fragments(&mut self, enabled: bool) {
+        self.namespace_fragments = enabled;
+    }
+
+    /// Returns the number of mapped files.
+    pub fn len(&self) -> usize {
+        self.urls.len()
+    }
+
+    /// Returns `true` if no files are mapped.
+    pub fn is_empty(&self) -> bool {
+        self.urls.is_empty()
+    }
+
+    /// Rewrites the Markdown links in the HTML generated from `source`.
+    ///
+    /// Links are rewritten relative to the URL of `source`, so the site
+    /// works when served from any base path. Absolute URLs, links with
+    /// a scheme, fragment-only links and links to other file types are
+    /// left unchanged.
+    ///
+    /// # Arguments
+    ///
+    /// * `html` - The generated HTML
+    /// * `source` - The source file, relative to the input root
+    ///
+    /// # Returns
+    ///
+    /// The rewritten HTML and the links that could not be resolved.
+    pub fn rewrite(
+        &self,
+        html: &str,
+        source: impl AsRef<Path>,
+    ) -> (String, Vec<UnresolvedLink>) {
+        let source = source.as_ref();
+        let base_dir = source.parent().unwrap_or(Path::new(""));
+        let page_url = self.url(source).map(str::to_string);
+        let mut unresolved = Vec::new();
+
+        let rewritten =
+            HREF_REGEX.replace_all(html, |caps: &Captures<'_>| {
+                let href = &caps[2];
+                let (path, suffix) = split_suffix(href);
+                if !is_local_markdown(path) {
+                    return caps[0].to_string();
+                }
+
+                let target = resolve(base_dir, path);
+                let url =
+                    target.as_ref().and_then(|target| self.url(target));
+                match (url, &target) {
+                    (Some(url), Some(target)) => {
+                        let url = match &page_url {
+                            Some(page_url) => {
+                                relative_url(page_url, url)
+                            }
+                            None => url.to_string(),
+                        };
+                        let suffix = match suffix.split_once('#') {
+                            Some((query, fragment))
+                                if self.namespace_fragments
+                                    && !fragment.is_empty() =>
+                            {
+                                format!(
+                                    "{}#{}-{}",
+                                    query,
+                                    anchor_namespace(target),
+                                    fragment
+                                )
+                            }
+                            _ => suffix.to_string(),
+                        };
+                        format!(
+                            "{}{}{}{}",
+                            &caps[1], url, suffix, &caps[3]
+                        )
+                    }
+                    _ => {
+                        unresolved.push(UnresolvedLink {
+                            href: href.to_string(),
+                            target,
+                        });
+                        caps[0].to_string()
+                    }
+                }
+            });
+
+        (rewritten.into_owned(), unresolved)
+    }
+
+    /// Returns the mapped source files that the HTML generated from
+    /// `source` links to, sorted and without duplicates.
+    ///
+    /// # Arguments
+    ///
+    /// * `html` - The generated HTML, before [`LinkMap::rewrite`]
+    /// * `source` - The source file, relative to the input root
+    pub fn targets(
+        &self,
+        html: &str,
+        source: impl AsRef<Path>,
+    ) -> Vec<PathBuf> {
+        let base_dir =
+            source.as_ref().parent().unwrap_or(Path::new(""));
+        let targets: BTreeSet<PathBuf> = HREF_REGEX
+            .captures_iter(html)
+            .filter_map(|caps| {
+                let (path, _) = split_suffix(&caps[2]);
+                if !is_local_markdown(path) {
+                    return None;
+                }
+                resolve(base_dir, path)
+                    .filter(|target| self.url(target).is_some())
+            })
+            .collect();
+        targets.into_iter().collect()
+    }
+}
+
+/// The internal links between the pages of a site.
+///
+/// Pages are source files relative to the input root. Links from a
+/// page to itself are ignored.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LinkGraph {
+    links: BTreeMap<PathBuf, BTreeSet<PathBuf>>,
+}
+
+impl LinkGraph {
+    /// Creates an empty graph.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a page without links.
+    pub fn add_page(&mut self, page: impl AsRef<Path>) {
+        let _ = self.links.entry(normalize(page.as_ref())).or_default();
+    }
+
+    /// Adds a link between two pages, adding the pages if needed.
+    pub fn add_link(
+        &mut self,
+        from: impl AsRef<Path>,
+        to: impl AsRef<Path>,
+    ) {
+        let from = normalize(from.as_ref());
+        let to = normalize(to.as_ref());
+        self.add_page(&to);
+        let targets = self.links.entry(from.clone()).or_default();
+        if from != to {
+            let _ = targets.insert(to);
+        }
+    }
+
+    /// Returns the pages, sorted.
+    pub fn pages(&self) -> impl Iterator<Item = &Path> {
+        self.links.keys().map(PathBuf::as_path)
+    }
+
+    /// Returns the pages a page links to, sorted.
+    pub fn links_from(
+        &self,
+        page: impl AsRef<Path>,
+    ) -> impl Iterator<Item = &Path> {
+        self.links
+            .get(&normalize(page.as_ref()))
+            .into_iter()
+            .flatten()
+            .map(PathBuf::as_path)
+    }
+
+    /// Returns the pages linking to a page, sorted.
+    pub fn links_to(&self, page: impl AsRef<Path>) -> Vec<&Path> {
+        let page = normalize(page.as_ref());
+        self.links
+            .iter()
+            .filter(|(_, targets)| targets.contains(&page))
+            .map(|(from, _)| from.as_path())
+            .collect()
+    }
+
+    /// Returns the number of pages.
+    pub fn len(&self) -> usize {
+        self.links.len()
+    }
+
+    /// Returns `true` if the graph has no pages.
+    pub fn is_empty(&self) -> bool {
+        self.links.is_empty()
+    }
+
+    /// Returns the pages no other page links to, sorted.
+    ///
+    /// The home page, `index.md` or `index.markdown` at the root, is
+    /// the entry point of the site and never an orphan.
+    pub fn orphans(&self) -> Vec<&Path> {
+        let linked: BTreeSet<&PathBuf> =
+            self.links.values().flatten().collect();
+        self.links
+            .keys()
+            .filter(|page| !linked.contains(page) && !is_home(page))
+            .map(PathBuf::as_path)
+            .collect()
+    }
+
+    /// Returns the pages that link to no other page, sorted.
+    pub fn dead_ends(&self) -> Vec<&Path> {
+        self.links
+            .iter()
+            .filter(|(_, targets)| targets.is_empty())
+            .map(|(page, _)| page.as_path())
+            .collect()
+    }
+
+    /// Exports the graph in the Graphviz DOT language.
+    ///
+    /// Pages are grouped into one cluster per directory, so sections of
+    /// the site stand out when rendered, e.g. with `dot -Tsvg`.
+    pub fn to_dot(&self) -> String {
+        let mut directories: BTreeMap<String, Vec<String>> =
+            BTreeMap::new();
+        for page in self.links.keys() {
+            directories
+                .entry(page_directory(page))
+                .or_default()
+                .push(page_id(page));
+        }
+
+        let mut dot =
+            String::from("digraph links {\n    node [shape=box];\n");
+        for (directory, pages) in &directories {
+            let indent = if directory.is_empty() {
+                "    "
+            } else {
+                dot.push_str(&format!(
+                    "    subgraph {} {{\n        label={};\n",
+                    dot_quote(&format!("cluster_{}", directory)),
+                    dot_quote(directory)
+                ));
+                "        "
+            };
+            for page in pages {
+                dot.push_str(&format!(
+                    "{}{};\n",
+                    indent,
+                    dot_quote(page)
+                ));
+            }
+            if !directory.is_empty() {
+                dot.push_str("    }\n");
+            }
+        }
+        for (from, targets) in &self.links {
+            for to in targets {
+                dot.push_str(&format!(
+                    "    {} -> {};\n",
+                    dot_quote(&page_id(from)),
+                    dot_quote(&page_id(to))
+                ));
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Exports the graph as JSON.
+    ///
+    /// The output is an object with a `nodes` array of
+    /// `{ "id", "directory", "links_in", "links_out" }` objects and an
+    /// `edges` array of `{ "from", "to" }` objects. Page IDs are source
+    /// paths with `/` as separator.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the graph cannot be serialised.
+    pub fn to_json(&self) -> Result<String> {
+        let nodes: Vec<_> = self
+            .links
+            .iter()
+            .map(|(page, targets)| {
+                json!({
+                    "id": page_id(page),
+                    "directory": page_directory(page),
+                    "links_in": self.links_to(page).len(),
+                    "links_out": targets.len(),
+                })
+            })
+            .collect();
+        let edges: Vec<_> = self
+            .links
+            .iter()
+            .flat_map(|(from, targets)| {
+                targets.iter().map(move |to| {
+                    json!({ "from": page_id(from), "to": page_id(to) })
+                })
+            })
+            .collect();
+        serde_json::to_string_pretty(
+            &json!({ "nodes": nodes, "edges": edges }),
+        )
+        .map_err(|e| {
+            HtmlError::UnexpectedError(format!(
+                "Failed to serialise link graph: {}",
+                e
+            ))
+        })
+    }
+}
+
+/// Returns the ID of a page in exports: its path with `/` separators.
+fn page_id(page: &Path) -> String {
+    page.components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Returns the directory of a page in exports, empty at the root.
+fn page_directory(page: &Path) -> String {
+    page.parent().map(page_id).unwrap_or_default()
+}
+
+/// Quotes a DOT identifier.
+fn dot_quote(id: &str) -> String {
+    format!("\"{}\"", id.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Returns `true` for `index.md` or `index.markdown` at the root.
+fn is_home(page: &Path) -> bool {
+    page == Path::new("index.md") || page == Path::new("index.markdown")
+}
+
+/// Splits an `href` into its path and its query/fragment suffix.
+fn split_suffix(href: &str) -> (&str, &str) {
+    match href.find(['?', '#']) {
+        Some(index) => href.split_at(index),
+        None => (href, ""),
+    }
+}
+
+/// Returns `true` if `path` is a relative link to a Markdown file.
+fn is_local_markdown(path: &str) -> bool {
+    let lower = path.to_ascii_lowercase();
+    let has_scheme = path
+        .split_once(':')
+        .map_or(false, |(scheme, _)| !scheme.contains('/'));
+    !path.is_empty()
+        && !path.starts_with('/')
+        && !has_scheme
+        && (lower.ends_with(".md") || lower.ends_with(".markdown"))
+}
+
+/// Resolves a link relative to `base_dir`, returning `None` if it
+/// escapes the input root.
+fn resolve(base_dir: &Path, path: &str) -> Option<PathBuf> {
+    let mut resolved = PathBuf::new();
+    for component in
+        base_dir.join(path.replace("%20", " ")).components()
+    {
+        match component {
+            Component::Normal(part) => resolved.push(part),
+            Component::ParentDir => {
+                if !resolved.pop() {
+                    return None;
+                }
+            }
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+    Some(resolved)
+}
+
+/// Normalizes a source path for use as a map key.
+fn normalize(path: &Path) -> PathBuf {
+    path.components()
+        .filter(|component| matches!(component, Component::Normal(_)))
+        .collect()
+}
+
+/// Returns the URL of `to` relative to the page at `from`.
+///
+/// Both URLs are root-relative. The result never starts with `/`.
+fn relative_url(from: &str, to: &str) -> String {
+    let from_dir: Vec<&str> = match from.rfind('/') {
+        Some(index) => from[..index].split('/'),
+        None => "".split('/'),
+    }
+    .filter(|segment| !segment.is_empty())
+    .collect();
+    let to_segments: Vec<&str> =
+        to.trim_start_matches('/').split('/').collect();
+
+    let common = from_dir
+        .iter()
+        .zip(&to_segments)
+        .take_while(|(a, b)| a == b)
+        .count()
+        .min(to_segments.len().saturating_sub(1));
+
+    let mut parts: Vec<&str> = vec![".."; from_dir.len() - common];
+    parts.extend(&to_segments[common..]);
+    let url = parts.join("/");
+    if url.is_empty() {
+        "./".to_string()
+    } else {
+        url
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn site() -> LinkMap {
+        let mut links = LinkMap::new();
+        links.insert("index.md", "/");
+        links.insert("guide.md", "/guide/");
+        links.insert("docs/api.md", "/docs/api.html");
+        links.insert("docs/deep/faq.md", "/docs/deep/faq.html");
+        links
+    }
+
+    mod rewrite_tests {
+        use super::*;
+
+        #[test]
+        fn test_preserves_fragments_and_queries() {
+            let (html, unresolved) = site().rewrite(
+                r#"<a href="./guide.md#setup">a</a><a href="docs/api.md?v=2#x">b</a>"#,
+                "index.md",
+            );
+            assert_eq!(
+                html,
+                r#"<a href="guide/#setup">a</a><a href="docs/api.html?v=2#x">b</a>"#
+            );
+            assert!(unresolved.is_empty());
+        }
+
+        #[test]
+        fn test_namespaces_fragments() {
+            let mut links = site();
+            links.set_namespace_fragments(true);
+            let (html, _) = links.rewrite(
+                r#"<a href="docs/api.md?v=2#x">a</a><a href="guide.md#">b</a><a href="guide.md">c</a>"#,
+                "index.md",
+            );
+            assert_eq!(
+                html,
+                r#"<a href="docs/api.html?v=2#docs-api-x">a</a><a href="guide/#">b</a><a href="guide/">c</a>"#
+            );
+        }
+
+        #[test]
+        fn test_targets_resolve_mapped_pages() {
+            let html = concat!(
+                r#"<a href="../api.md#x">a</a><a href="../api.md">b</a>"#,
+                r#"<a href="../../missing.md">c</a><a href="https://e.com/x.md">d</a>"#,
+                r#"<a href="faq.md">self</a>"#
+            );
+            assert_eq!(
+                site().targets(html, "docs/deep/faq.md"),
+                [
+                    PathBuf::from("docs/api.md"),
+                    PathBuf::from("docs/deep/faq.md")
+                ]
+            );
+        }
+
+        #[test]
+        fn test_links_are_relative_to_the_page() {
+            let (html, _) = site().rewrite(
+                r#"<a class="x" href="../api.md">api</a> <a href="../../index.md">home</a>"#,
+                "docs/deep/faq.md",
+            );
+            assert_eq!(
+                html,
+                r#"<a class="x" href="../api.html">api</a> <a href="../../">home</a>"#
+            );
+        }
+
+        #[test]
+        fn test_other_links_are_untouched() {
+            let html = concat!(
+                r#"<a href="https://example.com/a.md">x</a>"#,
+                r##"<a href="/abs.md">x</a><a href="#top">x</a>"##,
+                r#"<a href="style.css">x</a><a href="mailto:a@b.md">x</a>"#
+            );
+            let (rewritten, unresolved) =
+                site().rewrite(html, "index.md");
+            assert_eq!(rewritten, html);
+            assert!(unresolved.is_empty());
+        }
+
+        #[test]
+        fn test_reports_unresolved_links() {
+            let (_, unresolved) = site().rewrite(
+                r#"<a href="missing.md">x</a><a href="../../out.md">y</a>"#,
+                "docs/api.md",
+            );
+            assert_eq!(
+                unresolved[0].target.as_deref(),
+                Some(Path::new("docs/missing.md"))
+            );
+            assert_eq!(unresolved[1].target, None);
+            assert_eq!(
+                unresolved[0].to_diagnostic().code,
+                "broken_link"
+            );
+        }
+    }
+
+    mod relative_url_tests {
+        use super::*;
+
+        #[test]
+        fn test_relative_url() {
+            assert_eq!(relative_url("/", "/guide/"), "guide/");
+            assert_eq!(relative_url("/guide/", "/"), "../");
+            assert_eq!(
+                relative_url("/a/b.html", "/a/c.html"),
+                "c.html"
+            );
+            assert_eq!(
+                relative_url("/a/b.html", "/a/b.html"),
+                "b.html"
+            );
+            assert_eq!(relative_url("/a/b/", "/a/b/"), "./");
+            assert_eq!(
+                relative_url("/x/y.html", "/z.html"),
+                "../z.html"
+            );
+        }
+    }
+
+    mod graph_tests {
+        use super::*;
+
+        #[test]
+        fn test_orphans_and_dead_ends() {
+            let mut graph = LinkGraph::new();
+            graph.add_link("index.md", "./guide.md");
+            graph.add_link("guide.md", "index.md");
+            graph.add_link("guide.md", "docs/api.md");
+            graph.add_link("faq.md", "faq.md");
+            graph.add_page("docs/api.md");
+            graph.add_page("index.md");
+
+            assert_eq!(graph.len(), 4);
+            assert_eq!(
+                graph.links_from("guide.md").collect::<Vec<_>>(),
+                [Path::new("docs/api.md"), Path::new("index.md")]
+            );
+            assert_eq!(
+                graph.links_to("index.md"),
+                [Path::new("guide.md")]
+            );
+            assert_eq!(graph.orphans(), [Path::new("faq.md")]);
+            assert_eq!(
+                graph.dead_ends(),
+                [Path::new("docs/api.md"), Path::new("faq.md")]
+            );
+        }
+
+        #[test]
+        fn test_home_page_is_never_an_orphan() {
+            let mut graph = LinkGraph::new();
+            graph.add_page("index.md");
+            graph.add_page("docs/index.md");
+            assert_eq!(graph.orphans(), [Path::new("docs/index.md")]);
+        }
+    }
+
+    mod export_tests {
+        use super::*;
+
+        fn graph() -> LinkGraph {
+            let mut graph = LinkGraph::new();
+            graph.add_link("index.md", "docs/api.md");
+            graph.add_link("docs/api.md", "index.md");
+            graph.add_link("docs/api.md", "docs/\"q\".md");
+            graph
+        }
+
+        #[test]
+        fn test_to_dot() {
+            assert_eq!(
+                graph().to_dot(),
+                concat!(
+                    "digraph links {\n",
+                    "    node [shape=box];\n",
+                    "    \"index.md\";\n",
+                    "    subgraph \"cluster_docs\" {\n",
+                    "        label=\"docs\";\n",
+                    "        \"docs/\\\"q\\\".md\";\n",
+                    "        \"docs/api.md\";\n",
+                    "    }\n",
+                    "    \"docs/api.md\" -> \"docs/\\\"q\\\".md\";\n",
+                    "    \"docs/api.md\" -> \"index.md\";\n",
+                    "    \"index.md\" -> \"docs/api.md\";\n",
+                    "}\n"
+                )
+            );
+        }
+
+        #[test]
+        fn test_to_json() {
+            let json: serde_json::Value =
+                serde_json::from_str(&graph().to_json().unwrap())
+                    .unwrap();
+            assert_eq!(json["nodes"].as_array().unwrap().len(), 3);
+            assert_eq!(
+                json["nodes"][1],
+                json!({
+                    "id": "docs/api.md",
+                    "directory": "docs",
+                    "links_in": 1,
+                    "links_out": 2,
+                })
+            );
+            assert_eq!(
+                json["edges"][2],
+                json!({ "from": "index.md", "to": "docs/api.md" })
+            );
+        }
+    }
+
+    mod url_policy_tests {
+        use super::*;
+
+        fn policy() -> UrlPolicy {
+            UrlPolicy {
+                allow: vec![
+                    "example.com".to_string(),
+                    "docs.rs".to_string(),
+                ],
+                deny: vec![
+                    "track.example.com".to_string(),
+                    "bit.ly".to_string(),
+                ],
+                require_https: true,
+                upgrade_to_https: false,
+            }
+        }
+
+        #[test]
+        fn test_check() {
+            let policy = policy();
+            assert_eq!(policy.check("https://www.Example.com/a"), None);
+            assert_eq!(policy.check("/local"), None);
+            assert_eq!(policy.check("mailto:x@bit.ly"), None);
+            assert_eq!(
+                policy
+                    .check("https://user@track.example.com:8080/p")
+                    .unwrap()
+                    .kind,
+                UrlViolationKind::Denied
+            );
+            assert_eq!(
+                policy.check("//bit.ly/x").unwrap().kind,
+                UrlViolationKind::Denied
+            );
+            assert_eq!(
+                policy.check("https://notexample.com").unwrap().kind,
+                UrlViolationKind::NotAllowed
+            );
+            let insecure = policy.check("http://docs.rs/x").unwrap();
+            assert_eq!(insecure.kind, UrlViolationKind::Insecure);
+            assert_eq!(insecure.to_diagnostic().code, "insecure_url");
+        }
+
+        #[test]
+        fn test_apply_upgrades_links() {
+            let policy = UrlPolicy {
+                upgrade_to_https: true,
+                ..policy()
+            };
+            let (html, violations) = policy.apply(concat!(
+                r#"<a href="HTTP://docs.rs/x">a</a> <a href="http://bit.ly/y">b</a>"#,
+                r#"<img src="http://docs.rs/i.png">"#
+            ));
+            assert_eq!(
+                html,
+                concat!(
+                    r#"<a href="https://docs.rs/x">a</a> <a href="https://bit.ly/y">b</a>"#,
+                    r#"<img src="http://docs.rs/i.png">"#
+                )
+            );
+            assert_eq!(violations.len(), 1);
+            assert_eq!(violations[0].host, "bit.ly");
+
+            let html = r#"<a href="http://x.org">x</a>"#;
+            assert_eq!(
+                UrlPolicy::default().apply(html),
+                (html.to_string(), Vec::new())
+            );
+        }
+    }
+}
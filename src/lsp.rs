@@ -0,0 +1,522 @@
+// Copyright © 2025 HTML Generator. All rights reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! A minimal language server for in-editor feedback.
+//!
+//! Available with the `lsp` feature. [`LanguageServer`] speaks the
+//! Language Server Protocol over any reader and writer, usually stdin
+//! and stdout via [`run_stdio`]. Whenever the editor opens, edits or
+//! saves a Markdown document, the server converts it and publishes
+//! conversion, accessibility and SEO problems as diagnostics, so
+//! content authors see them while writing.
+//!
+//! Only full-document synchronization is supported, and only the
+//! messages needed for diagnostics are handled: `initialize`,
+//! `shutdown`, `exit` and the `textDocument/didOpen`, `didChange`,
+//! `didSave` and `didClose` notifications. Other requests are answered
+//! with a "method not found" error.
+//!
+//! # Examples
+//!
+//! ```
+//! use html_generator::lsp::diagnose;
+//! use html_generator::HtmlConfig;
+//!
+//! let diagnostics = diagnose("# Title\n\n### Skipped\n", &HtmlConfig::default());
+//! assert_eq!(diagnostics[0].code, "heading_structure");
+//! assert_eq!(diagnostics[0].line, 2);
+//! ```
+
+use crate::accessibility::{
+    validate_wcag, AccessibilityConfig, IssueType,
+};
+use crate::conversion::convert_detailed;
+use crate::diagnostics::Severity;
+use crate::patterns::lsp::TAG_REGEX;
+use crate::HtmlConfig;
+use serde_json::{json, Value};
+use std::{
+    collections::HashMap,
+    io::{self, BufRead, Write},
+};
+
+/// Name reported as the `source` of every diagnostic.
+pub const DIAGNOSTIC_SOURCE: &str = "html-generator";
+
+/// JSON-RPC error code for unknown methods.
+const METHOD_NOT_FOUND: i64 = -32601;
+
+/// A problem in a Markdown document, positioned on a source line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LspDiagnostic {
+    /// Zero-based line in the Markdown source.
+    pub line: usize,
+    /// How serious the problem is.
+    pub severity: Severity,
+    /// Stable code, such as an [`IssueType::code`].
+    pub code: String,
+    /// Human-readable description.
+    pub message: String,
+}
+
+impl LspDiagnostic {
+    /// Converts the diagnostic into an LSP `Diagnostic` object spanning
+    /// its whole line.
+    ///
+    /// # Arguments
+    ///
+    /// * `markdown` - The document, used to measure the line
+    pub fn to_json(&self, markdown: &str) -> Value {
+        let width: usize = markdown
+            .lines()
+            .nth(self.line)
+            .map_or(0, |line| line.encode_utf16().count());
+        let severity = match self.severity {
+            Severity::Error => 1,
+            Severity::Warning => 2,
+            Severity::Info => 3,
+        };
+        json!({
+            "range": {
+                "start": { "line": self.line, "character": 0 },
+                "end": { "line": self.line, "character": width },
+            },
+            "severity": severity,
+            "code": self.code,
+            "source": DIAGNOSTIC_SOURCE,
+            "message": self.message,
+        })
+    }
+}
+
+/// Converts a Markdown document and collects its problems.
+///
+/// Reports, in this order:
+///
+/// - conversion errors, at the first line;
+/// - mojibake repairs, when [`HtmlConfig::repair_mojibake`] is set;
+/// - removed script URLs, when [`HtmlConfig::url_scheme_policy`] is
+///   set to strip them, on the first source line containing the URL;
+/// - iframes removed by [`HtmlConfig::iframe_policy`], on the first
+///   source line containing their `src`;
+/// - accessibility issues of the generated HTML, on the first source
+///   line containing the text of the offending element;
+/// - a missing top-level heading, which pages use as their title.
+///
+/// The language declaration check is skipped, as the page template
+/// sets the document language.
+///
+/// # Arguments
+///
+/// * `markdown` - The Markdown source
+/// * `config` - The conversion settings
+pub fn diagnose(
+    markdown: &str,
+    config: &HtmlConfig,
+) -> Vec<LspDiagnostic> {
+    let conversion = match convert_detailed(markdown, config) {
+        Ok(conversion) => conversion,
+        Err(error) => {
+            return vec![LspDiagnostic {
+                line: 0,
+                severity: Severity::Error,
+                code: error.code().to_string(),
+                message: error.to_string(),
+            }]
+        }
+    };
+
+    let mut diagnostics: Vec<LspDiagnostic> = conversion
+        .repairs
+        .iter()
+        .map(|repair| LspDiagnostic {
+            line: repair.line - 1,
+            severity: Severity::Info,
+            code: "mojibake".to_string(),
+            message: repair.to_string(),
+        })
+        .collect();
+    diagnostics.extend(conversion.removed_urls.iter().map(|removed| {
+        LspDiagnostic {
+            line: source_line(markdown, &removed.url),
+            severity: Severity::Warning,
+            code: "unsafe_url".to_string(),
+            message: removed.to_string(),
+        }
+    }));
+    diagnostics.extend(conversion.blocked_iframes.iter().map(
+        |iframe| LspDiagnostic {
+            line: source_line(markdown, &iframe.src),
+            severity: Severity::Warning,
+            code: "blocked_iframe".to_string(),
+            message: iframe.to_string(),
+        },
+    ));
+
+    let accessibility = AccessibilityConfig::default();
+    if let Ok(report) = validate_wcag(
+        &conversion.html,
+        &accessibility,
+        Some(&[IssueType::LanguageDeclaration]),
+    ) {
+        for issue in &report.issues {
+            let message = match &issue.suggestion {
+                Some(suggestion) => {
+                    format!("{}. {}", issue.message, suggestion)
+                }
+                None => issue.message.clone(),
+            };
+            diagnostics.push(LspDiagnostic {
+                line: issue.element.as_deref().map_or(0, |element| {
+                    source_line(markdown, element)
+                }),
+                severity: issue.severity,
+                code: issue.issue_type.code().to_string(),
+                message,
+            });
+        }
+    }
+
+    if conversion.title.is_none() && !conversion.html.contains("<h1") {
+        diagnostics.push(LspDiagnostic {
+            line: 0,
+            severity: Severity::Warning,
+            code: "missing_title".to_string(),
+            message: "Document has no top-level heading to use as the \
+                      page title"
+                .to_string(),
+        });
+    }
+    diagnostics
+}
+
+/// Returns the first line of `markdown` containing the text of
+/// `element`, or `0`.
+fn source_line(markdown: &str, element: &str) -> usize {
+    let text = TAG_REGEX.replace_all(element, "");
+    let text = text.trim();
+    if text.is_empty() {
+        return 0;
+    }
+    markdown
+        .lines()
+        .position(|line| line.contains(text))
+        .unwrap_or(0)
+}
+
+/// Reads one LSP message from `reader`.
+///
+/// # Returns
+///
+/// The parsed message, or `None` at the end of the input.
+///
+/// # Errors
+///
+/// Returns an error if reading fails, the `Content-Length` header is
+/// missing or the body is not valid JSON.
+pub fn read_message<R: BufRead>(
+    reader: &mut R,
+) -> io::Result<Option<Value>> {
+    let mut length = None;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                length = value.trim().parse::<usize>().ok();
+            }
+        }
+    }
+    let length = length.ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            "Missing Content-Length header",
+        )
+    })?;
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+    serde_json::from_slice(&body).map(Some).map_err(|error| {
+        io::Error::new(io::ErrorKind::InvalidData, error)
+    })
+}
+
+/// Writes one LSP message to `writer`.
+///
+/// # Errors
+///
+/// Returns an error if writing fails.
+pub fn write_message<W: Write>(
+    writer: &mut W,
+    message: &Value,
+) -> io::Result<()> {
+    let body = message.to_string();
+    write!(writer, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+    writer.flush()
+}
+
+/// A language server publishing diagnostics for Markdown documents.
+#[derive(Debug, Clone)]
+pub struct LanguageServer {
+    config: HtmlConfig,
+    documents: HashMap<String, String>,
+    exited: bool,
+}
+
+impl LanguageServer {
+    /// Creates a server converting documents with `config`.
+    pub fn new(config: HtmlConfig) -> Self {
+        Self {
+            config,
+            documents: HashMap::new(),
+            exited: false,
+        }
+    }
+
+    /// Returns `true` once the client has sent `exit`.
+    pub fn has_exited(&self) -> bool {
+        self.exited
+    }
+
+    /// Handles one incoming message.
+    ///
+    /// # Returns
+    ///
+    /// The messages to send back: responses to requests and
+    /// `textDocument/publishDiagnostics` notifications.
+    pub fn handle(&mut self, message: &Value) -> Vec<Value> {
+        let method = message["method"].as_str().unwrap_or_default();
+        let params = &message["params"];
+        let id = message.get("id").cloned();
+        let uri = params["textDocument"]["uri"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string();
+
+        match method {
+            "initialize" => vec![response(
+                id,
+                json!({
+                    "capabilities": {
+                        "textDocumentSync": {
+                            "openClose": true,
+                            "change": 1,
+                            "save": { "includeText": true },
+                        },
+                    },
+                    "serverInfo": {
+                        "name": DIAGNOSTIC_SOURCE,
+                        "version": env!("CARGO_PKG_VERSION"),
+                    },
+                }),
+            )],
+            "shutdown" => vec![response(id, Value::Null)],
+            "exit" => {
+                self.exited = true;
+                Vec::new()
+            }
+            "textDocument/didOpen" => {
+                let text = params["textDocument"]["text"]
+                    .as_str()
+                    .unwrap_or_default();
+                self.update(uri, text)
+            }
+            "textDocument/didChange" => {
+                match params["contentChanges"]
+                    .as_array()
+                    .and_then(|changes| changes.last())
+                    .and_then(|change| change["text"].as_str())
+                {
+                    Some(text) => self.update(uri, text),
+                    None => Vec::new(),
+                }
+            }
+            "textDocument/didSave" => match params["text"].as_str() {
+                Some(text) => self.update(uri, text),
+                None => match self.documents.get(&uri).cloned() {
+                    Some(text) => self.update(uri, &text),
+                    None => Vec::new(),
+                },
+            },
+            "textDocument/didClose" => {
+                let _ = self.documents.remove(&uri);
+                vec![publish(&uri, Vec::new())]
+            }
+            _ => match id {
+                Some(id) => vec![json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "error": {
+                        "code": METHOD_NOT_FOUND,
+                        "message": format!("Unsupported method: {}", method),
+                    },
+                })],
+                None => Vec::new(),
+            },
+        }
+    }
+
+    /// Serves messages from `reader` until the client exits or the
+    /// input ends.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reading or writing a message fails.
+    pub fn run<R: BufRead, W: Write>(
+        &mut self,
+        reader: &mut R,
+        writer: &mut W,
+    ) -> io::Result<()> {
+        while let Some(message) = read_message(reader)? {
+            for reply in self.handle(&message) {
+                write_message(writer, &reply)?;
+            }
+            if self.exited {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Stores the text of a document and diagnoses it.
+    fn update(&mut self, uri: String, text: &str) -> Vec<Value> {
+        let diagnostics: Vec<Value> = diagnose(text, &self.config)
+            .iter()
+            .map(|diagnostic| diagnostic.to_json(text))
+            .collect();
+        let message = publish(&uri, diagnostics);
+        let _ = self.documents.insert(uri, text.to_string());
+        vec![message]
+    }
+}
+
+/// Builds a JSON-RPC response.
+fn response(id: Option<Value>, result: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "result": result })
+}
+
+/// Builds a `textDocument/publishDiagnostics` notification.
+fn publish(uri: &str, diagnostics: Vec<Value>) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/publishDiagnostics",
+        "params": { "uri": uri, "diagnostics": diagnostics },
+    })
+}
+
+/// Runs a language server over stdin and stdout.
+///
+/// # Arguments
+///
+/// * `config` - The conversion settings
+///
+/// # Errors
+///
+/// Returns an error if reading or writing a message fails.
+pub fn run_stdio(config: HtmlConfig) -> io::Result<()> {
+    let stdin = io::stdin();
+    let stdout = io::stdout();
+    LanguageServer::new(config)
+        .run(&mut stdin.lock(), &mut stdout.lock())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn frame(message: &Value) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        write_message(&mut bytes, message).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn test_diagnose_positions_issues_on_source_lines() {
+        let markdown = "Intro\n\n# Title\n\nText\n\n#### Deep\n";
+        let diagnostics = diagnose(markdown, &HtmlConfig::default());
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].line, 6);
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+
+        let missing = diagnose("Just text", &HtmlConfig::default());
+        assert_eq!(missing[0].code, "missing_title");
+
+        let failed = diagnose("", &HtmlConfig::default());
+        assert_eq!(failed[0].code, "invalid_input");
+        assert_eq!(failed[0].severity, Severity::Error);
+    }
+
+    #[test]
+    fn test_diagnostic_json_spans_the_line() {
+        let diagnostic = LspDiagnostic {
+            line: 1,
+            severity: Severity::Error,
+            code: "x".to_string(),
+            message: "m".to_string(),
+        };
+        let json = diagnostic.to_json("a\n## Héllo 👋\n");
+        assert_eq!(json["range"]["end"]["character"], 11);
+        assert_eq!(json["severity"], 1);
+        assert_eq!(json["source"], DIAGNOSTIC_SOURCE);
+    }
+
+    #[test]
+    fn test_session_over_streams() {
+        let mut input = Vec::new();
+        for message in [
+            json!({"jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {}}),
+            json!({"jsonrpc": "2.0", "method": "initialized", "params": {}}),
+            json!({"jsonrpc": "2.0", "method": "textDocument/didOpen", "params": {
+                "textDocument": {"uri": "file:///a.md", "text": "# A\n\n### C\n"}
+            }}),
+            json!({"jsonrpc": "2.0", "method": "textDocument/didChange", "params": {
+                "textDocument": {"uri": "file:///a.md"},
+                "contentChanges": [{"text": "# A\n\n## B\n"}]
+            }}),
+            json!({"jsonrpc": "2.0", "id": 2, "method": "textDocument/hover", "params": {}}),
+            json!({"jsonrpc": "2.0", "id": 3, "method": "shutdown"}),
+            json!({"jsonrpc": "2.0", "method": "exit"}),
+            json!({"jsonrpc": "2.0", "id": 4, "method": "initialize"}),
+        ] {
+            input.extend(frame(&message));
+        }
+
+        let mut server = LanguageServer::new(HtmlConfig::default());
+        let mut output = Vec::new();
+        server.run(&mut Cursor::new(input), &mut output).unwrap();
+        assert!(server.has_exited());
+
+        let mut replies = Vec::new();
+        let mut reader = Cursor::new(output);
+        while let Some(message) = read_message(&mut reader).unwrap() {
+            replies.push(message);
+        }
+        assert_eq!(replies.len(), 5);
+        assert_eq!(
+            replies[0]["result"]["capabilities"]["textDocumentSync"]
+                ["change"],
+            1
+        );
+        let opened = &replies[1]["params"]["diagnostics"];
+        assert_eq!(opened[0]["code"], "heading_structure");
+        assert_eq!(opened[0]["range"]["start"]["line"], 2);
+        assert_eq!(replies[2]["params"]["diagnostics"], json!([]));
+        assert_eq!(replies[3]["error"]["code"], METHOD_NOT_FOUND);
+        assert_eq!(replies[4]["id"], 3);
+    }
+
+    #[test]
+    fn test_read_message_requires_content_length() {
+        let mut reader = Cursor::new(b"X-Other: 1\r\n\r\n{}".to_vec());
+        assert!(read_message(&mut reader).is_err());
+        assert!(read_message(&mut Cursor::new(Vec::new()))
+            .unwrap()
+            .is_none());
+    }
+}
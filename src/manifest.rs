@@ -0,0 +1,655 @@
+// Copyright © 2025 HTML Generator. All rights reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Build manifest and offline support generation.
+//!
+//! This module records every file produced by a build (generated pages
+//! and copied assets) together with a content revision, and turns that
+//! record into the artefacts needed to make a documentation site work
+//! offline:
+//!
+//! - A Workbox-compatible precache list (`[{ "url", "revision" }]`)
+//! - A self-contained `sw.js` service worker template
+//!
+//! # Examples
+//!
+//! ```
+//! use html_generator::manifest::BuildManifest;
+//!
+//! let mut manifest = BuildManifest::new();
+//! manifest.add_page("index.html", b"<h1>Home</h1>");
+//! manifest.add_asset("css/site.css", b"body { margin: 0; }");
+//!
+//! let precache = manifest.to_precache_json().unwrap();
+//! assert!(precache.contains("\"url\": \"index.html\""));
+//! ```
+
+use crate::error::{HtmlError, Result};
+use crate::utils::content_hash;
+use serde_json::json;
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Default file name for the Workbox-compatible precache list.
+pub const DEFAULT_PRECACHE_FILE: &str = "precache-manifest.json";
+
+/// Default file name for the generated service worker.
+pub const DEFAULT_SERVICE_WORKER_FILE: &str = "sw.js";
+
+/// Default prefix for the service worker cache name.
+pub const DEFAULT_CACHE_NAME: &str = "html-generator";
+
+/// The kind of file recorded in a build manifest.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum EntryKind {
+    /// An HTML page generated from Markdown.
+    Page,
+    /// A static asset copied into the output directory.
+    Asset,
+}
+
+/// A single file recorded in a build manifest.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestEntry {
+    /// URL of the file relative to the site root, using `/` separators.
+    pub url: String,
+    /// Content revision used for cache busting.
+    pub revision: String,
+    /// Whether the file is a generated page or a copied asset.
+    pub kind: EntryKind,
+    /// Size of the file in bytes.
+    pub size: usize,
+}
+
+/// Record of the files produced by a build.
+///
+/// Entries are kept in insertion order. Adding an entry for a URL that
+/// is already present replaces the earlier entry.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BuildManifest {
+    entries: Vec<ManifestEntry>,
+    /// Position of the entry of each URL in `entries`.
+    positions: HashMap<String, usize>,
+}
+
+/// Options controlling the generated offline support files.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrecacheConfig {
+    /// Prefix of the cache name used by the service worker.
+    ///
+    /// A hash of every revision in the manifest is appended so that a
+    /// new build invalidates the previous cache.
+    pub cache_name: String,
+    /// File name of the precache list, or `None` to skip it.
+    pub precache_file: Option<String>,
+    /// File name of the service worker, or `None` to skip it.
+    pub service_worker_file: Option<String>,
+    /// Generate and validate the files without writing them.
+    pub dry_run: bool,
+}
+
+impl Default for PrecacheConfig {
+    fn default() -> Self {
+        Self {
+            cache_name: DEFAULT_CACHE_NAME.to_string(),
+            precache_file: Some(DEFAULT_PRECACHE_FILE.to_string()),
+            service_worker_file: Some(
+                DEFAULT_SERVICE_WORKER_FILE.to_string(),
+            ),
+            dry_run: false,
+        }
+    }
+}
+
+impl BuildManifest {
+    /// Creates an empty manifest.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a generated page.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - Path of the page relative to the site root.
+    /// * `content` - The page contents, used to compute the revision.
+    pub fn add_page(&mut self, url: impl AsRef<str>, content: &[u8]) {
+        self.add_entry(url.as_ref(), content, EntryKind::Page);
+    }
+
+    /// Records a copied asset.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - Path of the asset relative to the site root.
+    /// * `content` - The asset contents, used to compute the revision.
+    pub fn add_asset(&mut self, url: impl AsRef<str>, content: &[u8]) {
+        self.add_entry(url.as_ref(), content, EntryKind::Asset);
+    }
+
+    /// Builds a manifest from the files already present in a directory.
+    ///
+    /// Files with an `.html` or `.htm` extension are recorded as pages,
+    /// everything else as assets. Offline support files previously
+    /// generated with the default names and symbolic links are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `root` - The output directory to scan.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory cannot be read.
+    pub fn from_directory(root: impl AsRef<Path>) -> Result<Self> {
+        let root = root.as_ref();
+        let mut files = Vec::new();
+        collect_files(root, &mut files)?;
+        files.sort();
+
+        let mut manifest = Self::new();
+        for path in files {
+            let relative = path.strip_prefix(root).map_err(|_| {
+                HtmlError::InvalidInput(format!(
+                    "Path '{}' is outside '{}'",
+                    path.display(),
+                    root.display()
+                ))
+            })?;
+            let url = to_url(relative);
+            if url == DEFAULT_PRECACHE_FILE
+                || url == DEFAULT_SERVICE_WORKER_FILE
+            {
+                continue;
+            }
+            let content = fs::read(&path)?;
+            if is_page(&path) {
+                manifest.add_page(&url, &content);
+            } else {
+                manifest.add_asset(&url, &content);
+            }
+        }
+        Ok(manifest)
+    }
+
+    /// Returns all recorded entries in insertion order.
+    pub fn entries(&self) -> &[ManifestEntry] {
+        &self.entries
+    }
+
+    /// Returns an iterator over the recorded pages.
+    pub fn pages(&self) -> impl Iterator<Item = &ManifestEntry> {
+        self.entries.iter().filter(|e| e.kind == EntryKind::Page)
+    }
+
+    /// Returns an iterator over the recorded assets.
+    pub fn assets(&self) -> impl Iterator<Item = &ManifestEntry> {
+        self.entries.iter().filter(|e| e.kind == EntryKind::Asset)
+    }
+
+    /// Returns the number of recorded entries.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if no entries have been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns a revision covering every entry in the manifest.
+    ///
+    /// The value changes whenever any entry is added, removed or
+    /// modified, which makes it suitable for versioning caches.
+    pub fn revision(&self) -> String {
+        let combined: String = self
+            .entries
+            .iter()
+            .map(|e| format!("{}@{}\n", e.url, e.revision))
+            .collect();
+        content_hash(combined.as_bytes())
+    }
+
+    /// Generates a Workbox-compatible precache list.
+    ///
+    /// The output is a JSON array of `{ "url", "revision" }` objects,
+    /// suitable for `workbox.precaching.precacheAndRoute`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the list cannot be serialised.
+    pub fn to_precache_json(&self) -> Result<String> {
+        let list: Vec<_> = self
+            .entries
+            .iter()
+            .map(|e| json!({ "url": e.url, "revision": e.revision }))
+            .collect();
+        serde_json::to_string_pretty(&list).map_err(|e| {
+            HtmlError::UnexpectedError(format!(
+                "Failed to serialise precache list: {}",
+                e
+            ))
+        })
+    }
+
+    /// Generates a dependency-free service worker script.
+    ///
+    /// The worker precaches every entry on install, removes caches from
+    /// earlier builds on activation and answers `GET` requests
+    /// cache-first, falling back to the network.
+    ///
+    /// # Arguments
+    ///
+    /// * `cache_name` - Prefix of the cache name.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the URL list cannot be serialised.
+    pub fn to_service_worker(
+        &self,
+        cache_name: &str,
+    ) -> Result<String> {
+        let urls: Vec<&str> =
+            self.entries.iter().map(|e| e.url.as_str()).collect();
+        let urls = serde_json::to_string(&urls).map_err(|e| {
+            HtmlError::UnexpectedError(format!(
+                "Failed to serialise precache URLs: {}",
+                e
+            ))
+        })?;
+        let cache = serde_json::to_string(&format!(
+            "{}-{}",
+            cache_name,
+            self.revision()
+        ))
+        .map_err(|e| {
+            HtmlError::UnexpectedError(format!(
+                "Failed to serialise cache name: {}",
+                e
+            ))
+        })?;
+
+        Ok(SERVICE_WORKER_TEMPLATE
+            .replace("{{CACHE_NAME}}", &cache)
+            .replace("{{PRECACHE_URLS}}", &urls))
+    }
+
+    /// Writes the offline support files into an output directory.
+    ///
+    /// # Arguments
+    ///
+    /// * `output_dir` - The directory to write the files into.
+    /// * `config` - Which files to generate and how to name them.
+    ///
+    /// # Returns
+    ///
+    /// The paths of the files that were written, or that would have been
+    /// written when `config.dry_run` is set.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a file name is invalid or a file cannot be
+    /// written.
+    pub fn write_offline_files(
+        &self,
+        output_dir: impl AsRef<Path>,
+        config: &PrecacheConfig,
+    ) -> Result<Vec<PathBuf>> {
+        let output_dir = output_dir.as_ref();
+        let mut written = Vec::new();
+
+        if let Some(name) = &config.precache_file {
+            let path = output_dir.join(validate_file_name(name)?);
+            let json = self.to_precache_json()?;
+            if !config.dry_run {
+                fs::write(&path, json)?;
+            }
+            written.push(path);
+        }
+        if let Some(name) = &config.service_worker_file {
+            let path = output_dir.join(validate_file_name(name)?);
+            let script = self.to_service_worker(&config.cache_name)?;
+            if !config.dry_run {
+                fs::write(&path, script)?;
+            }
+            written.push(path);
+        }
+
+        Ok(written)
+    }
+
+    fn add_entry(
+        &mut self,
+        url: &str,
+        content: &[u8],
+        kind: EntryKind,
+    ) {
+        let entry = ManifestEntry {
+            url: url
+                .replace('\\', "/")
+                .trim_start_matches('/')
+                .to_string(),
+            revision: content_hash(content),
+            kind,
+            size: content.len(),
+        };
+        match self.positions.get(&entry.url) {
+            Some(&position) => self.entries[position] = entry,
+            None => {
+                let _ = self
+                    .positions
+                    .insert(entry.url.clone(), self.entries.len());
+                self.entries.push(entry);
+            }
+        }
+    }
+}
+
+/// Service worker template used by [`BuildManifest::to_service_worker`].
+const SERVICE_WORKER_TEMPLATE: &str = r#"// Generated by html-generator. Do not edit.
+const CACHE_NAME = {{CACHE_NAME}};
+const PRECACHE_URLS = {{PRECACHE_URLS}};
+
+self.addEventListener("install", (event) => {
+  event.waitUntil(
+    caches
+      .open(CACHE_NAME)
+      .then((cache) => cache.addAll(PRECACHE_URLS))
+      .then(() => self.skipWaiting())
+  );
+});
+
+self.addEventListener("activate", (event) => {
+  event.waitUntil(
+    caches
+      .keys()
+      .then((keys) =>
+        Promise.all(
+          keys
+            .filter((key) => key !== CACHE_NAME)
+            .map((key) => caches.delete(key))
+        )
+      )
+      .then(() => self.clients.claim())
+  );
+});
+
+self.addEventListener("fetch", (event) => {
+  if (event.request.method !== "GET") {
+    return;
+  }
+  event.respondWith(
+    caches
+      .match(event.request)
+      .then((cached) => cached || fetch(event.request))
+  );
+});
+"#;
+
+/// Ensures a generated file name stays inside the output directory.
+fn validate_file_name(name: &str) -> Result<&str> {
+    let path = Path::new(name);
+    if name.is_empty()
+        || path.is_absolute()
+        || path.components().count() != 1
+        || name == ".."
+    {
+        return Err(HtmlError::InvalidInput(format!(
+            "Invalid offline file name: '{}'",
+            name
+        )));
+    }
+    Ok(name)
+}
+
+/// Recursively collects all regular files below `dir`.
+///
+/// Symbolic links are skipped, so that links pointing outside the
+/// directory are not recorded and link cycles are not followed.
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            collect_files(&entry.path(), files)?;
+        } else if file_type.is_file() {
+            files.push(entry.path());
+        }
+    }
+    Ok(())
+}
+
+/// Converts a relative path into a `/`-separated URL.
+fn to_url(path: &Path) -> String {
+    path.components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Returns `true` if the path looks like a generated HTML page.
+fn is_page(path: &Path) -> bool {
+    path.extension()
+        .map(|ext| {
+            let ext = ext.to_string_lossy().to_ascii_lowercase();
+            ext == "html" || ext == "htm"
+        })
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    mod build_manifest_tests {
+        use super::*;
+
+        #[test]
+        fn test_add_page_and_asset() {
+            let mut manifest = BuildManifest::new();
+            manifest.add_page("index.html", b"<h1>Home</h1>");
+            manifest.add_asset("css/site.css", b"body {}");
+
+            assert_eq!(manifest.len(), 2);
+            assert_eq!(manifest.pages().count(), 1);
+            assert_eq!(manifest.assets().count(), 1);
+            assert_eq!(manifest.entries()[0].size, 13);
+        }
+
+        #[test]
+        fn test_duplicate_url_replaces_entry() {
+            let mut manifest = BuildManifest::new();
+            manifest.add_page("index.html", b"old");
+            let old = manifest.entries()[0].revision.clone();
+            manifest.add_page("/index.html", b"new");
+
+            assert_eq!(manifest.len(), 1);
+            assert_ne!(manifest.entries()[0].revision, old);
+
+            manifest.add_asset("a.css", b"a");
+            manifest.add_page("index.html", b"newer");
+            let urls: Vec<_> =
+                manifest.entries().iter().map(|e| &e.url).collect();
+            assert_eq!(urls, ["index.html", "a.css"]);
+        }
+
+        #[test]
+        fn test_url_normalisation() {
+            let mut manifest = BuildManifest::new();
+            manifest.add_asset("img\\logo.png", b"png");
+            assert_eq!(manifest.entries()[0].url, "img/logo.png");
+        }
+
+        #[test]
+        fn test_revision_changes_with_content() {
+            let mut a = BuildManifest::new();
+            a.add_page("index.html", b"one");
+            let mut b = BuildManifest::new();
+            b.add_page("index.html", b"two");
+
+            assert_ne!(a.revision(), b.revision());
+            assert!(BuildManifest::new().is_empty());
+        }
+    }
+
+    mod precache_tests {
+        use super::*;
+
+        #[test]
+        fn test_precache_json_is_workbox_compatible() {
+            let mut manifest = BuildManifest::new();
+            manifest.add_page("index.html", b"<p>Hi</p>");
+
+            let json = manifest.to_precache_json().unwrap();
+            let parsed: serde_json::Value =
+                serde_json::from_str(&json).unwrap();
+            let list = parsed.as_array().unwrap();
+            assert_eq!(list.len(), 1);
+            assert_eq!(list[0]["url"], "index.html");
+            assert_eq!(
+                list[0]["revision"],
+                manifest.entries()[0].revision.as_str()
+            );
+        }
+
+        #[test]
+        fn test_service_worker_contains_urls_and_cache() {
+            let mut manifest = BuildManifest::new();
+            manifest.add_page("index.html", b"<p>Hi</p>");
+            manifest.add_asset("app.js", b"console.log(1)");
+
+            let sw = manifest.to_service_worker("docs").unwrap();
+            assert!(sw.contains(r#"["index.html","app.js"]"#));
+            assert!(sw.contains(&format!(
+                "\"docs-{}\"",
+                manifest.revision()
+            )));
+            assert!(!sw.contains("{{"));
+        }
+
+        #[test]
+        fn test_service_worker_escapes_urls() {
+            let mut manifest = BuildManifest::new();
+            manifest.add_page("a\"b.html", b"x");
+
+            let sw = manifest.to_service_worker("docs").unwrap();
+            assert!(sw.contains(r#"["a\"b.html"]"#));
+        }
+    }
+
+    mod file_tests {
+        use super::*;
+
+        #[test]
+        fn test_from_directory() {
+            let dir = tempdir().unwrap();
+            fs::create_dir_all(dir.path().join("css")).unwrap();
+            fs::write(dir.path().join("index.html"), "<p>Hi</p>")
+                .unwrap();
+            fs::write(dir.path().join("css/site.css"), "body {}")
+                .unwrap();
+            fs::write(dir.path().join(DEFAULT_SERVICE_WORKER_FILE), "")
+                .unwrap();
+
+            let manifest =
+                BuildManifest::from_directory(dir.path()).unwrap();
+            assert_eq!(manifest.len(), 2);
+            assert_eq!(manifest.entries()[0].url, "css/site.css");
+            assert_eq!(manifest.entries()[0].kind, EntryKind::Asset);
+            assert_eq!(manifest.entries()[1].kind, EntryKind::Page);
+        }
+
+        #[cfg(unix)]
+        #[test]
+        fn test_from_directory_skips_symlinks() {
+            use std::os::unix::fs::symlink;
+
+            let outside = tempdir().unwrap();
+            fs::write(outside.path().join("secret.html"), "x").unwrap();
+            let dir = tempdir().unwrap();
+            fs::create_dir(dir.path().join("docs")).unwrap();
+            fs::write(dir.path().join("docs/index.html"), "<p>Hi</p>")
+                .unwrap();
+            symlink(dir.path(), dir.path().join("docs/loop")).unwrap();
+            symlink(outside.path(), dir.path().join("outside"))
+                .unwrap();
+            symlink(
+                outside.path().join("secret.html"),
+                dir.path().join("secret.html"),
+            )
+            .unwrap();
+
+            let manifest =
+                BuildManifest::from_directory(dir.path()).unwrap();
+            let urls: Vec<_> =
+                manifest.entries().iter().map(|e| &e.url).collect();
+            assert_eq!(urls, ["docs/index.html"]);
+        }
+
+        #[test]
+        fn test_write_offline_files() {
+            let dir = tempdir().unwrap();
+            let mut manifest = BuildManifest::new();
+            manifest.add_page("index.html", b"<p>Hi</p>");
+
+            let written = manifest
+                .write_offline_files(
+                    dir.path(),
+                    &PrecacheConfig::default(),
+                )
+                .unwrap();
+            assert_eq!(written.len(), 2);
+            assert!(dir.path().join(DEFAULT_PRECACHE_FILE).exists());
+            assert!(dir
+                .path()
+                .join(DEFAULT_SERVICE_WORKER_FILE)
+                .exists());
+        }
+
+        #[test]
+        fn test_write_offline_files_skips_disabled() {
+            let dir = tempdir().unwrap();
+            let config = PrecacheConfig {
+                service_worker_file: None,
+                ..Default::default()
+            };
+
+            let written = BuildManifest::new()
+                .write_offline_files(dir.path(), &config)
+                .unwrap();
+            assert_eq!(written.len(), 1);
+        }
+
+        #[test]
+        fn test_write_offline_files_dry_run() {
+            let dir = tempdir().unwrap();
+            let config = PrecacheConfig {
+                dry_run: true,
+                ..Default::default()
+            };
+
+            let written = BuildManifest::new()
+                .write_offline_files(dir.path(), &config)
+                .unwrap();
+            assert_eq!(written.len(), 2);
+            assert!(!written[0].exists());
+            assert!(!written[1].exists());
+        }
+
+        #[test]
+        fn test_write_offline_files_rejects_traversal() {
+            let dir = tempdir().unwrap();
+            let config = PrecacheConfig {
+                precache_file: Some("../escape.json".to_string()),
+                ..Default::default()
+            };
+
+            let result = BuildManifest::new()
+                .write_offline_files(dir.path(), &config);
+            assert!(matches!(result, Err(HtmlError::InvalidInput(_))));
+        }
+    }
+}
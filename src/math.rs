@@ -0,0 +1,101 @@
+// Copyright © 2025 HTML Generator. All rights reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Math markup for client-side renderers.
+//!
+//! With [`HtmlConfig::math`](crate::HtmlConfig::math) set, `$...$`,
+//! `$$...$$`, `` $`...`$ `` and ```` ```math ```` blocks are parsed as
+//! TeX and written the way Pandoc does, which KaTeX's auto-render
+//! extension and MathJax recognise without configuration:
+//!
+//! | Markdown | HTML |
+//! |----------|------|
+//! | `$x^2$` | `<span class="math inline">\(x^2\)</span>` |
+//! | `$$x^2$$` alone in a paragraph, or a `math` code block | `<div class="math display">\[x^2\]</div>` |
+//! | `$$x^2$$` within text | `<span class="math display">\[x^2\]</span>` |
+//!
+//! The TeX source is kept as escaped text, so pages remain readable
+//! without scripts.
+//!
+//! # Examples
+//!
+//! ```
+//! use html_generator::{conversion::convert, HtmlConfig};
+//!
+//! let config = HtmlConfig { math: true, ..HtmlConfig::default() };
+//! let html = convert("Euler: $e^{i\\pi} + 1 = 0$", &config)?;
+//! assert!(html.contains(r#"<span class="math inline">\(e^{i\pi} + 1 = 0\)</span>"#));
+//! # Ok::<(), html_generator::error::HtmlError>(())
+//! ```
+
+use crate::patterns::math::{MATH_BLOCK_REGEX, MATH_INLINE_REGEX};
+use regex::Captures;
+
+/// Rewrites the math rendered by the Markdown parser into
+/// `math inline` and `math display` elements.
+///
+/// # Arguments
+///
+/// * `html` - HTML rendered with the math extension
+///
+/// # Returns
+///
+/// The HTML with KaTeX- and MathJax-ready math.
+pub fn add_math_markup(html: &str) -> String {
+    let html =
+        MATH_BLOCK_REGEX.replace_all(html, |caps: &Captures<'_>| {
+            let tex = caps
+                .get(1)
+                .or_else(|| caps.get(2))
+                .map_or("", |m| m.as_str().trim_end_matches('\n'));
+            format!(r#"<div class="math display">\[{}\]</div>"#, tex)
+        });
+    MATH_INLINE_REGEX
+        .replace_all(&html, |caps: &Captures<'_>| {
+            let (open, close) = if &caps[1] == "inline" {
+                (r"\(", r"\)")
+            } else {
+                (r"\[", r"\]")
+            };
+            format!(
+                r#"<span class="math {}">{}{}{}</span>"#,
+                &caps[1], open, &caps[2], close
+            )
+        })
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conversion::convert;
+    use crate::HtmlConfig;
+
+    #[test]
+    fn test_math_markup() {
+        let config = HtmlConfig {
+            math: true,
+            ..HtmlConfig::default()
+        };
+        let html = convert(
+            "Let $a<b$ and $`c`$, so $$a^2$$ holds.\n\n$$\\sum_i x_i$$\n\n```math\nE = mc^2\n```\n\nCosts $5 and $6.",
+            &config,
+        )
+        .unwrap();
+        assert!(html.contains(
+            r#"Let <span class="math inline">\(a&lt;b\)</span> and <span class="math inline">\(c\)</span>, so <span class="math display">\[a^2\]</span> holds."#
+        ));
+        assert!(html.contains(
+            r#"<div class="math display">\[\sum_i x_i\]</div>"#
+        ));
+        assert!(html.contains(
+            r#"<div class="math display">\[E = mc^2\]</div>"#
+        ));
+        assert!(html.contains("Costs $5 and $6."));
+        assert!(!html.contains("data-math-style"));
+        assert_eq!(add_math_markup(&html), html);
+
+        let plain = convert("$x$", &HtmlConfig::default()).unwrap();
+        assert!(plain.contains("<p>$x$</p>"));
+    }
+}
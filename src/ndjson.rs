@@ -0,0 +1,390 @@
+// Copyright © 2025 HTML Generator. All rights reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Newline-delimited JSON (NDJSON) batch protocol.
+//!
+//! Available with the `ndjson` feature. This module lets long-lived
+//! processes stream conversion jobs to the library, one JSON object per
+//! line, and receive one JSON result per line in the same order. It is
+//! intended for build tools that keep a worker process alive and pipe
+//! work through stdin/stdout.
+//!
+//! # Job format
+//!
+//! ```json
+//! {"id": "a", "path": "docs/index.md", "content": "# Hi", "config": {"language": "fr-FR"}}
+//! ```
+//!
+//! - `id` (optional) is echoed back unchanged.
+//! - `content` is the Markdown to convert. When absent, the file at
+//!   `path` is read instead.
+//! - `path` (optional) identifies the job and is echoed back.
+//! - `config` (optional) overrides fields of the base [`HtmlConfig`].
+//!
+//! # Result format
+//!
+//! ```json
+//! {"id": "a", "path": "docs/index.md", "ok": true, "html": "<h1>Hi</h1>"}
+//! {"id": "b", "line": 2, "ok": false, "error": {"code": "invalid_input", "message": "..."}}
+//! ```
+//!
+//! A failing job never stops the stream; blank lines are ignored.
+//!
+//! # Examples
+//!
+//! ```
+//! use html_generator::ndjson::process_ndjson;
+//! use html_generator::MarkdownConfig;
+//!
+//! let input = "{\"id\": 1, \"content\": \"# Hello\"}\n";
+//! let mut output = Vec::new();
+//! let summary =
+//!     process_ndjson(input.as_bytes(), &mut output, &MarkdownConfig::default())?;
+//!
+//! assert_eq!(summary.succeeded, 1);
+//! assert!(String::from_utf8(output).unwrap().contains("\"ok\":true"));
+//! # Ok::<(), html_generator::error::HtmlError>(())
+//! ```
+
+use crate::error::{HtmlError, Result};
+use crate::{
+    markdown_to_html, HtmlConfig, MarkdownConfig, PartialHtmlConfig,
+};
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use std::{
+    fs,
+    io::{BufRead, Write},
+};
+
+/// Counts of jobs handled by [`process_ndjson`].
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct NdjsonSummary {
+    /// Number of jobs read, excluding blank lines.
+    pub processed: usize,
+    /// Number of jobs converted successfully.
+    pub succeeded: usize,
+    /// Number of jobs that produced an error result.
+    pub failed: usize,
+}
+
+/// Processes NDJSON jobs from `reader` and writes results to `writer`.
+///
+/// Each non-blank input line produces exactly one output line. The
+/// writer is flushed after every result so that a consumer can read
+/// results as soon as they are available.
+///
+/// # Arguments
+///
+/// * `reader` - Source of newline-delimited job objects
+/// * `writer` - Destination for newline-delimited result objects
+/// * `base` - Configuration that per-job `config` objects override
+///
+/// # Returns
+///
+/// A summary of the processed jobs.
+///
+/// # Errors
+///
+/// Returns an error only if reading from `reader` or writing to
+/// `writer` fails. Problems with individual jobs are reported as error
+/// results in the output stream.
+pub fn process_ndjson<R: BufRead, W: Write>(
+    reader: R,
+    mut writer: W,
+    base: &MarkdownConfig,
+) -> Result<NdjsonSummary> {
+    let mut summary = NdjsonSummary::default();
+
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        summary.processed += 1;
+
+        let result = process_line(&line, index + 1, base);
+        if result["ok"] == Value::Bool(true) {
+            summary.succeeded += 1;
+        } else {
+            summary.failed += 1;
+        }
+
+        serde_json::to_writer(&mut writer, &result).map_err(|e| {
+            HtmlError::UnexpectedError(format!(
+                "Failed to write NDJSON result: {}",
+                e
+            ))
+        })?;
+        writer.write_all(b"\n")?;
+        writer.flush()?;
+    }
+
+    Ok(summary)
+}
+
+/// Converts a single job line into its result object.
+fn process_line(
+    line: &str,
+    line_number: usize,
+    base: &MarkdownConfig,
+) -> Value {
+    let job = match serde_json::from_str::<Value>(line) {
+        Ok(Value::Object(job)) => job,
+        Ok(_) => {
+            return error_result(
+                &Map::new(),
+                line_number,
+                &HtmlError::InvalidInput(
+                    "Job must be a JSON object".to_string(),
+                ),
+            )
+        }
+        Err(e) => {
+            return error_result(
+                &Map::new(),
+                line_number,
+                &HtmlError::ParsingError(format!(
+                    "Invalid JSON: {}",
+                    e
+                )),
+            )
+        }
+    };
+
+    match run_job(&job, base) {
+        Ok(html) => {
+            let mut result = echo_fields(&job);
+            let _ = result.insert("ok".to_string(), Value::Bool(true));
+            let _ =
+                result.insert("html".to_string(), Value::String(html));
+            Value::Object(result)
+        }
+        Err(e) => error_result(&job, line_number, &e),
+    }
+}
+
+/// Runs a parsed job and returns the generated HTML.
+fn run_job(
+    job: &Map<String, Value>,
+    base: &MarkdownConfig,
+) -> Result<String> {
+    let mut config = base.clone();
+    if let Some(overrides) = job.get("config") {
+        config.html_config = config
+            .html_config
+            .merge(parse_config_overrides(overrides)?);
+        config.html_config.validate()?;
+    }
+
+    let content = match (job.get("content"), job.get("path")) {
+        (Some(Value::String(content)), _) => content.clone(),
+        (Some(_), _) => {
+            return Err(HtmlError::InvalidInput(
+                "`content` must be a string".to_string(),
+            ))
+        }
+        (None, Some(Value::String(path))) => {
+            HtmlConfig::validate_file_path(path)?;
+            fs::read_to_string(path)?
+        }
+        (None, _) => {
+            return Err(HtmlError::InvalidInput(
+                "Job requires `content` or a string `path`".to_string(),
+            ))
+        }
+    };
+
+    markdown_to_html(&content, Some(config))
+}
+
+/// Parses a JSON object of overrides into a [`PartialHtmlConfig`].
+///
+/// The object is read with the serde implementation of
+/// [`PartialHtmlConfig`], so keys and values are written as in
+/// configuration files and the JSON Schema, and `null` clears an
+/// optional field. Unknown keys and values of the wrong type are
+/// rejected so that typos surface as errors instead of being silently
+/// ignored.
+///
+/// # Errors
+///
+/// Returns [`HtmlError::InvalidInput`] for unknown keys, values of the
+/// wrong type and values that do not parse.
+///
+/// # Examples
+///
+/// ```
+/// use html_generator::ndjson::parse_config_overrides;
+/// use html_generator::HtmlConfig;
+///
+/// let overrides = serde_json::json!({"generate_toc": true});
+/// let config = HtmlConfig::default().merge(parse_config_overrides(&overrides)?);
+/// assert!(config.generate_toc);
+/// assert!(parse_config_overrides(&serde_json::json!({"toc_typo": true})).is_err());
+/// # Ok::<(), html_generator::error::HtmlError>(())
+/// ```
+pub fn parse_config_overrides(
+    overrides: &Value,
+) -> Result<PartialHtmlConfig> {
+    if !overrides.is_object() {
+        return Err(HtmlError::InvalidInput(
+            "`config` must be a JSON object".to_string(),
+        ));
+    }
+    PartialHtmlConfig::deserialize(overrides).map_err(|e| {
+        HtmlError::InvalidInput(format!(
+            "Invalid config override: {}",
+            e
+        ))
+    })
+}
+
+/// Copies the `id` and `path` fields of a job into a new result.
+fn echo_fields(job: &Map<String, Value>) -> Map<String, Value> {
+    let mut result = Map::new();
+    for key in ["id", "path"] {
+        if let Some(value) = job.get(key) {
+            let _ = result.insert(key.to_string(), value.clone());
+        }
+    }
+    result
+}
+
+/// Builds an error result for a job.
+fn error_result(
+    job: &Map<String, Value>,
+    line_number: usize,
+    error: &HtmlError,
+) -> Value {
+    let mut result = echo_fields(job);
+    let _ = result.insert("line".to_string(), json!(line_number));
+    let _ = result.insert("ok".to_string(), Value::Bool(false));
+    let _ = result.insert(
+        "error".to_string(),
+        json!({ "code": error.code(), "message": error.to_string() }),
+    );
+    Value::Object(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bidi::TextDirection;
+    use crate::emojis::EmojiPolicy;
+
+    /// Runs the protocol over `input` and returns the parsed results.
+    fn run(input: &str) -> (NdjsonSummary, Vec<Value>) {
+        let mut output = Vec::new();
+        let summary = process_ndjson(
+            input.as_bytes(),
+            &mut output,
+            &MarkdownConfig::default(),
+        )
+        .expect("Protocol should not fail");
+        let results = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        (summary, results)
+    }
+
+    #[test]
+    fn test_successful_job_echoes_id_and_path() {
+        let (summary, results) =
+            run(r##"{"id": 7, "path": "a.md", "content": "# Hi"}"##);
+
+        assert_eq!(summary.succeeded, 1);
+        assert_eq!(results[0]["id"], 7);
+        assert_eq!(results[0]["path"], "a.md");
+        assert_eq!(results[0]["ok"], true);
+        assert!(results[0]["html"]
+            .as_str()
+            .unwrap()
+            .contains("<h1>Hi</h1>"));
+    }
+
+    #[test]
+    fn test_failures_do_not_stop_the_stream() {
+        let input = "not json\n\n{\"id\": \"b\", \"content\": \"\"}\n[1]\n{\"content\": \"ok\"}\n";
+        let (summary, results) = run(input);
+
+        assert_eq!(
+            summary,
+            NdjsonSummary {
+                processed: 4,
+                succeeded: 1,
+                failed: 3
+            }
+        );
+        assert_eq!(results[0]["error"]["code"], "parsing");
+        assert_eq!(results[0]["line"], 1);
+        assert_eq!(results[1]["id"], "b");
+        assert_eq!(results[1]["error"]["code"], "invalid_input");
+        assert_eq!(results[1]["line"], 3);
+        assert_eq!(results[2]["error"]["code"], "invalid_input");
+        assert_eq!(results[3]["ok"], true);
+    }
+
+    #[test]
+    fn test_job_without_content_or_path() {
+        let (_, results) = run(r#"{"id": 1}"#);
+        assert_eq!(results[0]["ok"], false);
+    }
+
+    #[test]
+    fn test_config_overrides() {
+        let partial = parse_config_overrides(&json!({
+            "language": "fr-FR",
+            "minify_output": true,
+            "syntax_theme": null,
+            "max_input_size": 2048,
+            "emoji_policy": "remove",
+            "diagram_languages": {"d2": "d2"},
+            "limits": {"max_nesting_depth": 10},
+            "direction": "rtl"
+        }))
+        .unwrap();
+        let config = HtmlConfig::default().merge(partial);
+
+        assert_eq!(config.language, "fr-FR");
+        assert!(config.minify_output);
+        assert_eq!(config.syntax_theme, None);
+        assert_eq!(config.max_input_size, 2048);
+        assert_eq!(config.emoji_policy, EmojiPolicy::Remove);
+        assert_eq!(config.diagram_languages.len(), 1);
+        assert_eq!(config.limits.max_nesting_depth, 10);
+        assert_eq!(config.direction, Some(TextDirection::Rtl));
+
+        let partial =
+            parse_config_overrides(&json!({"direction": null}))
+                .unwrap();
+        assert_eq!(partial.direction, Some(None));
+        assert_eq!(partial.syntax_theme, None);
+    }
+
+    #[test]
+    fn test_config_overrides_are_validated() {
+        let (_, results) =
+            run(r#"{"content": "x", "config": {"language": "english"}}
+{"content": "x", "config": {"langauge": "fr-FR"}}
+{"content": "x", "config": {"minify_output": "yes"}}
+{"content": "x", "config": {"emoji_policy": "hide"}}"#);
+
+        for result in &results {
+            assert_eq!(result["ok"], false);
+            assert_eq!(result["error"]["code"], "invalid_input");
+        }
+        assert!(results[1]["error"]["message"]
+            .as_str()
+            .unwrap()
+            .contains("unknown field `langauge`"));
+    }
+
+    #[test]
+    fn test_path_traversal_is_rejected() {
+        let (_, results) = run(r#"{"path": "../secret.md"}"#);
+        assert_eq!(results[0]["error"]["code"], "invalid_input");
+    }
+}
@@ -0,0 +1,671 @@
+// Copyright © 2025 HTML Generator. All rights reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Registry of the precompiled regular expressions and CSS selectors.
+//!
+//! Every pattern the crate matches documents with is declared here,
+//! grouped by the module using it, and compiled on first use. The first
+//! conversion or validation therefore pays for compiling the patterns
+//! it needs. Servers can call [`warm_up`] at startup to pay that cost
+//! before the first request instead, and tests can call it to check
+//! that every pattern compiles.
+//!
+//! # Examples
+//!
+//! ```
+//! use html_generator::patterns::warm_up;
+//!
+//! let compiled = warm_up()?;
+//! assert!(compiled > 0);
+//! # Ok::<(), html_generator::error::HtmlError>(())
+//! ```
+
+use crate::accessibility::{try_create_regex, try_create_selector};
+use crate::error::{HtmlError, Result};
+use crate::regexes::static_regex;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use scraper::Selector;
+
+/// A compiled pattern, or the `None` left by one that failed to
+/// compile.
+trait Pattern {
+    /// Returns `true` if the pattern compiled.
+    fn is_compiled(&self) -> bool;
+}
+
+impl Pattern for Regex {
+    fn is_compiled(&self) -> bool {
+        true
+    }
+}
+
+impl Pattern for Selector {
+    fn is_compiled(&self) -> bool {
+        true
+    }
+}
+
+impl<T> Pattern for Option<T> {
+    fn is_compiled(&self) -> bool {
+        self.is_some()
+    }
+}
+
+/// Declares a module of `Lazy` patterns for each group, and
+/// `compile_all` compiling every pattern of every group.
+macro_rules! registry {
+    ($(
+        $(#[doc = $group_doc:literal])*
+        $(#[cfg($cfg:meta)])?
+        $group:ident {
+            $(
+                $(#[doc = $doc:literal])*
+                $name:ident: $ty:ty = $init:expr;
+            )+
+        }
+    )+) => {
+        $(
+            $(#[doc = $group_doc])*
+            $(#[cfg($cfg)])?
+            pub(crate) mod $group {
+                use super::*;
+
+                $(
+                    $(#[doc = $doc])*
+                    pub(crate) static $name: Lazy<$ty> =
+                        Lazy::new(|| $init);
+                )+
+            }
+        )+
+
+        /// Compiles every pattern, returning their number and the names
+        /// of those that failed to compile.
+        fn compile_all() -> (usize, Vec<&'static str>) {
+            let mut count = 0;
+            let mut failed = Vec::new();
+            $(
+                $(#[cfg($cfg)])?
+                {
+                    $(
+                        count += 1;
+                        if !Lazy::force(&$group::$name).is_compiled() {
+                            failed.push(concat!(
+                                stringify!($group),
+                                "::",
+                                stringify!($name)
+                            ));
+                        }
+                    )+
+                }
+            )+
+            (count, failed)
+        }
+    };
+}
+
+registry! {
+    /// Patterns of [`crate::accessibility`]
+    accessibility {
+        /// Selector for buttons without an ARIA label
+        BUTTON_SELECTOR: Option<Selector> = try_create_selector(
+            "button:not([aria-label])",
+        );
+
+        /// Selector for buttons that already have an ARIA label
+        LABELED_BUTTON_SELECTOR: Option<Selector> = try_create_selector(
+            "button[aria-label]",
+        );
+
+        /// Selector for navigation elements that already have an ARIA label
+        LABELED_NAV_SELECTOR: Option<Selector> = try_create_selector(
+            "nav[aria-label]",
+        );
+
+        /// Selector for headings
+        HEADING_SELECTOR: Option<Selector> = try_create_selector(
+            "h1, h2, h3, h4, h5, h6",
+        );
+
+        /// Selector for links
+        LINK_SELECTOR: Option<Selector> = try_create_selector("a[href]");
+
+        /// Selector for images, whose alt text names the links they are in
+        IMAGE_SELECTOR: Option<Selector> = try_create_selector("img[alt]");
+
+        /// Selector for every image, whose alt text is checked
+        ANY_IMAGE_SELECTOR: Option<Selector> = try_create_selector("img");
+
+        /// Selector for formulas, as written by [`crate::math`], KaTeX
+        /// and MathJax
+        MATH_FORMULA_SELECTOR: Option<Selector> = try_create_selector(
+            ".math, .katex, mjx-container",
+        );
+
+        /// Selector for MathML, which screen readers can read aloud
+        MATHML_SELECTOR: Option<Selector> = try_create_selector("math");
+
+        /// Selector for blocks holding user content, whose text direction is
+        /// checked
+        TEXT_BLOCK_SELECTOR: Option<Selector> = try_create_selector(
+            &crate::bidi::USER_CONTENT_BLOCKS.join(", "),
+        );
+
+        /// Selector for the root `html` element
+        HTML_ELEMENT_SELECTOR: Option<Selector> = try_create_selector("html");
+
+        /// Selector for elements declaring a language
+        LANG_SELECTOR: Option<Selector> = try_create_selector("[lang]");
+
+        /// Selector matching every element
+        ANY_ELEMENT_SELECTOR: Option<Selector> = try_create_selector("*");
+
+        /// Selector for navigation elements without ARIA attributes
+        NAV_SELECTOR: Option<Selector> = try_create_selector(
+            "nav:not([aria-label])",
+        );
+
+        /// Selector for the `body` element, whose content is wrapped in
+        /// `main`
+        BODY_SELECTOR: Option<Selector> = try_create_selector("body");
+
+        /// Selector for main landmarks
+        MAIN_LANDMARK_SELECTOR: Option<Selector> = try_create_selector(
+            "main, [role=main]",
+        );
+
+        /// Selector for candidate banner landmarks
+        BANNER_SELECTOR: Option<Selector> = try_create_selector(
+            "header, [role=banner]",
+        );
+
+        /// Selector for candidate contentinfo landmarks
+        CONTENTINFO_SELECTOR: Option<Selector> = try_create_selector(
+            "footer, [role=contentinfo]",
+        );
+
+        /// Selector for form elements without ARIA attributes
+        FORM_SELECTOR: Option<Selector> = try_create_selector(
+            "form:not([aria-labelledby])",
+        );
+
+        /// Regex for finding input elements
+        INPUT_REGEX: Option<Regex> = try_create_regex(r"<input[^>]*>");
+
+        /// Comprehensive selector for all ARIA attributes
+        ARIA_SELECTOR: Option<Selector> = try_create_selector(concat!(
+            "[aria-label], [aria-labelledby], [aria-describedby], ",
+            "[aria-hidden], [aria-expanded], [aria-haspopup], ",
+            "[aria-controls], [aria-pressed], [aria-checked], ",
+            "[aria-current], [aria-disabled], [aria-dropeffect], ",
+            "[aria-grabbed], [aria-invalid], [aria-live], ",
+            "[aria-owns], [aria-relevant], [aria-required], ",
+            "[aria-role], [aria-selected], [aria-valuemax], ",
+            "[aria-valuemin], [aria-valuenow], [aria-valuetext]"
+        ));
+
+        OPENING_TAG_REGEX: Regex = static_regex(
+            "opening tag regex",
+            r"<[A-Za-z][^>]*>",
+        );
+
+        // Quoted values are matched first so that words inside them are skipped
+        SHORTHAND_ATTRIBUTE_REGEX: Regex = static_regex(
+            "shorthand attribute regex",
+            r#""[^"]*"|'[^']*'|\b(disabled|checked|readonly|multiple|selected|autofocus|required)([\s>])"#,
+        );
+
+        ID_REGEX: Regex = static_regex("id regex", r#"id="([^"]+)""#);
+
+        // Regex to capture all key-value pairs in the tag
+        ATTRIBUTE_REGEX: Regex = static_regex(
+            "attribute regex",
+            r#"([^\s"'<>/=]+)(?:\s*=\s*(?:"[^"]*"|'[^']*'|[^\s"'<>=`]+))?"#,
+        );
+
+        TYPE_REGEX: Regex = static_regex(
+            "type regex",
+            r#"type=["']([^"']+)["']"#,
+        );
+
+        // Match primary language and optional subtags
+        LANGUAGE_CODE_REGEX: Regex = static_regex(
+            "language code regex",
+            r"(?i)^[a-z]{2,3}(-[a-z0-9]{2,8})*$",
+        );
+    }
+
+    /// Patterns of [`crate::anchors`]
+    anchors {
+        START_TAG_REGEX: Regex = static_regex(
+            "START_TAG_REGEX",
+            r"<[a-zA-Z][^>]*>",
+        );
+
+        ID_ATTRIBUTE_REGEX: Regex = static_regex(
+            "ID_ATTRIBUTE_REGEX",
+            r#"(\sid=")([^"]*)(")"#,
+        );
+
+        /// Attributes referring to IDs of the same page: fragment links and
+        /// space-separated ID lists.
+        ID_REFERENCE_REGEX: Regex = static_regex(
+            "ID_REFERENCE_REGEX",
+            r##"(\s(?:href="#|(?:for|aria-labelledby|aria-describedby|aria-details|aria-controls|aria-owns)="))([^"]*)(")"##,
+        );
+    }
+
+    /// Patterns of [`crate::archive`]
+    #[cfg(feature = "std")]
+    archive {
+        MEDIA_SRC_REGEX: Regex = static_regex(
+            "MEDIA_SRC_REGEX",
+            r#"(?i)(<(?:img|source|video|audio|input)\s[^>]*?\b(?:src|poster)=")([^"]*)(")"#,
+        );
+
+        LINK_TAG_REGEX: Regex = static_regex(
+            "LINK_TAG_REGEX",
+            r"(?i)<link\s[^>]*>",
+        );
+
+        STYLE_BLOCK_REGEX: Regex = static_regex(
+            "STYLE_BLOCK_REGEX",
+            r"(?is)(<style\b[^>]*>)(.*?)(</style\s*>)",
+        );
+
+        ATTRIBUTE_REGEX: Regex = static_regex(
+            "ATTRIBUTE_REGEX",
+            r#"\s([a-zA-Z-]+)\s*=\s*(?:"([^"]*)"|'([^']*)')"#,
+        );
+
+        CSS_URL_REGEX: Regex = static_regex(
+            "CSS_URL_REGEX",
+            r#"url\(\s*(?:"([^"]*)"|'([^']*)'|([^)"'\s]*))\s*\)"#,
+        );
+    }
+
+    /// Patterns of [`crate::bidi`]
+    bidi {
+        BLOCK_REGEX: Regex = static_regex(
+            "BLOCK_REGEX",
+            &format!(
+                r#"(?i)<({})\b((?:[^>"']|"[^"]*"|'[^']*')*)>"#,
+                crate::bidi::USER_CONTENT_BLOCKS.join("|")
+            ),
+        );
+
+        DIR_ATTRIBUTE_REGEX: Regex = static_regex(
+            "DIR_ATTRIBUTE_REGEX",
+            r#"(?i)\sdir\s*="#,
+        );
+    }
+
+    /// Patterns of [`crate::containers`]
+    containers {
+        OPENING_REGEX: Regex = static_regex(
+            "OPENING_REGEX",
+            r"^:::([A-Za-z][\w-]*?)([+-])?(?:[ \t]+(.*?))?[ \t]*$",
+        );
+
+        CLOSING_REGEX: Regex = static_regex("CLOSING_REGEX", r"^:::[ \t]*$");
+
+        FENCE_REGEX: Regex = static_regex(
+            "FENCE_REGEX",
+            r"^[ \t]{0,3}(`{3,}|~{3,})",
+        );
+    }
+
+    /// Patterns of [`crate::diagrams`]
+    diagrams {
+        DIAGRAM_BLOCK_REGEX: Regex = static_regex(
+            "DIAGRAM_BLOCK_REGEX",
+            r#"(?s)<pre><code class="language-([^"]+)">(.*?)</code></pre>"#,
+        );
+    }
+
+    /// Patterns of [`crate::footnotes`]
+    footnotes {
+        FOOTNOTE_ITEM_REGEX: Regex = static_regex(
+            "FOOTNOTE_ITEM_REGEX",
+            r#"(?s)<li id="(fn-[^"]+)">(.*?)</li>"#,
+        );
+
+        FOOTNOTE_REF_REGEX: Regex = static_regex(
+            "FOOTNOTE_REF_REGEX",
+            r##"<a href="#(fn-[^"]+)"([^>]*\sdata-footnote-ref[^>]*)>"##,
+        );
+
+        FOOTNOTE_BACKREF_REGEX: Regex = static_regex(
+            "FOOTNOTE_BACKREF_REGEX",
+            r##"<a href="#fnref-[^"]+"[^>]*\sclass="footnote-backref"[^>]*>"##,
+        );
+
+        FOOTNOTE_SECTION_REGEX: Regex = static_regex(
+            "FOOTNOTE_SECTION_REGEX",
+            r#"<section class="footnotes"[^>]*>"#,
+        );
+    }
+
+    /// Patterns of [`crate::front_matter`]
+    front_matter {
+        YAML_BLOCK_REGEX: Regex = static_regex(
+            "YAML_BLOCK_REGEX",
+            r"(?s)\A---[ \t]*\r?\n(?:(.*?)\r?\n)??---[ \t]*(?:\r?\n|\z)",
+        );
+
+        TOML_BLOCK_REGEX: Regex = static_regex(
+            "TOML_BLOCK_REGEX",
+            r"(?s)\A\+\+\+[ \t]*\r?\n(?:(.*?)\r?\n)??\+\+\+[ \t]*(?:\r?\n|\z)",
+        );
+    }
+
+    /// Patterns of [`crate::generator`]
+    generator {
+        CUSTOM_BLOCK_REGEX: Regex = static_regex(
+            "CUSTOM_BLOCK_REGEX",
+            r":::(\w+)\n([\s\S]*?)\n:::",
+        );
+
+        DETAILS_BLOCK_REGEX: Regex = static_regex(
+            "DETAILS_BLOCK_REGEX",
+            r"(?m)^:::details(?:[ \t]+(.*?))?[ \t]*\n([\s\S]*?)\n:::[ \t]*$",
+        );
+
+        MARKDOWN_CONTAINER_REGEX: Regex = static_regex(
+            "MARKDOWN_CONTAINER_REGEX",
+            r#"^(\s*<([A-Za-z][A-Za-z0-9-]*)\b[^>]*?)\s+markdown\s*=\s*(?:"1"|'1'|1)([^>]*>)\s*$"#,
+        );
+
+        HTML_TAG_REGEX: Regex = static_regex(
+            "HTML_TAG_REGEX",
+            r"<(/?)([A-Za-z][A-Za-z0-9-]*)\b[^>]*?(/?)>",
+        );
+
+        HEADING_TAG_REGEX: Regex = static_regex(
+            "HEADING_TAG_REGEX",
+            r"(?i)<(/?)h([1-6])\b",
+        );
+
+        FIRST_H1_REGEX: Regex = static_regex(
+            "FIRST_H1_REGEX",
+            r"(?is)<h1\b[^>]*>(.*?)</h1>\s*",
+        );
+
+        IMAGE_CLASS_REGEX: Regex = static_regex(
+            "IMAGE_CLASS_REGEX",
+            r#"!\[(.*?)\]\((.*?)\)\.class="(.*?)""#,
+        );
+    }
+
+    /// Patterns of [`crate::headings`]
+    headings {
+        HEADING_REGEX: Regex = static_regex(
+            "HEADING_REGEX",
+            r"(?s)<h([1-6])(\s[^>]*)?>(.*?)</h[1-6]>",
+        );
+
+        ID_REGEX: Regex = static_regex("ID_REGEX", r#"\sid="([^"]*)""#);
+
+        ANCHOR_LINK_REGEX: Regex = static_regex(
+            "ANCHOR_LINK_REGEX",
+            r#"(?s)\s*<a class="heading-anchor"[^>]*>.*?</a>"#,
+        );
+
+        TAG_REGEX: Regex = static_regex("TAG_REGEX", r"<[^>]*>");
+    }
+
+    /// Patterns of [`crate::highlight`]
+    highlight {
+        CODE_BLOCK_REGEX: Regex = static_regex(
+            "CODE_BLOCK_REGEX",
+            r#"(?s)<pre><code class="language-([^"]+)">(.*?)</code></pre>"#,
+        );
+    }
+
+    /// Patterns of [`crate::iframes`]
+    iframes {
+        IFRAME_REGEX: Regex = static_regex(
+            "IFRAME_REGEX",
+            r#"(?is)<iframe\b((?:[^>"']|"[^"]*"|'[^']*')*?)\s*/?>(?:.*?</iframe\s*>)?"#,
+        );
+
+        ATTRIBUTE_REGEX: Regex = static_regex(
+            "ATTRIBUTE_REGEX",
+            r#"\s+([^\s"'>/=]+)(?:\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s"'=<>`]+)))?"#,
+        );
+    }
+
+    /// Patterns of [`crate::includes`]
+    #[cfg(feature = "std")]
+    includes {
+        INCLUDE_REGEX: Regex = static_regex(
+            "INCLUDE_REGEX",
+            r#"\{\{\s*include\s+"([^"]+)"\s*\}\}"#,
+        );
+
+        FENCE_REGEX: Regex = static_regex(
+            "FENCE_REGEX",
+            r"^[ \t]{0,3}(`{3,}|~{3,})",
+        );
+    }
+
+    /// Patterns of [`crate::language`]
+    #[cfg(feature = "language-detection")]
+    language {
+        TAG_REGEX: Regex = static_regex(
+            "TAG_REGEX",
+            r#"<(/?)([A-Za-z][A-Za-z0-9-]*)((?:[^>"']|"[^"]*"|'[^']*')*)>"#,
+        );
+
+        LANG_ATTRIBUTE_REGEX: Regex = static_regex(
+            "LANG_ATTRIBUTE_REGEX",
+            r#"(?i)\slang\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s"'=<>`]+))"#,
+        );
+    }
+
+    /// Patterns of [`crate::limits`]
+    limits {
+        RAW_HTML_TAG_REGEX: Regex = static_regex(
+            "RAW_HTML_TAG_REGEX",
+            r"<(/?)([A-Za-z][A-Za-z0-9-]*)\b[^>]*?(/?)>",
+        );
+    }
+
+    /// Patterns of [`crate::links`]
+    #[cfg(feature = "std")]
+    links {
+        HREF_REGEX: Regex = static_regex(
+            "HREF_REGEX",
+            r#"(<a\s[^>]*?\bhref=")([^"]*)(")"#,
+        );
+    }
+
+    /// Patterns of [`crate::lsp`]
+    #[cfg(feature = "lsp")]
+    lsp {
+        TAG_REGEX: Regex = static_regex("TAG_REGEX", r"<[^>]*>");
+    }
+
+    /// Patterns of [`crate::math`]
+    math {
+        MATH_BLOCK_REGEX: Regex = static_regex(
+            "MATH_BLOCK_REGEX",
+            r#"(?s)<pre><code class="language-math" data-math-style="display">(.*?)</code></pre>|<p><(?:span|code) data-math-style="display">(.*?)</(?:span|code)></p>"#,
+        );
+
+        MATH_INLINE_REGEX: Regex = static_regex(
+            "MATH_INLINE_REGEX",
+            r#"(?s)<(?:span|code) data-math-style="(inline|display)">(.*?)</(?:span|code)>"#,
+        );
+    }
+
+    /// Patterns of [`crate::repair`]
+    repair {
+        TAG_REGEX: Regex = static_regex(
+            "TAG_REGEX",
+            r#"<!--[\s\S]*?-->|<(/?)([A-Za-z][A-Za-z0-9-]*)((?:[^>"']|"[^"]*"|'[^']*')*)>"#,
+        );
+
+        ATTRIBUTE_REGEX: Regex = static_regex(
+            "ATTRIBUTE_REGEX",
+            r#"([^\s"'<>/=]+)(?:\s*=\s*("[^"]*"|'[^']*'|[^\s"'<>=`]+))?"#,
+        );
+    }
+
+    /// Patterns of [`crate`]
+    root {
+        LANG_REGEX: Regex = static_regex(
+            "language code regex",
+            r"^[a-z]{2}(?:-[A-Z]{2})$",
+        );
+    }
+
+    /// Patterns of [`crate::seo`]
+    seo {
+        /// Selector for extracting the meta description
+        META_DESC_SELECTOR: Selector = Selector::parse(
+            "meta[name='description']",
+        )
+        .expect("Failed to compile meta description selector");
+
+        /// Selector for extracting the title
+        TITLE_SELECTOR: Selector = Selector::parse("title")
+            .expect("Failed to compile title selector");
+
+        /// Selector for extracting paragraphs
+        PARAGRAPH_SELECTOR: Selector = Selector::parse("p")
+            .expect("Failed to compile paragraph selector");
+    }
+
+    /// Patterns of [`crate::tables`]
+    tables {
+        TABLE_REGEX: Regex = static_regex(
+            "TABLE_REGEX",
+            r"(?s)<table(\s[^>]*)?>(.*?)</table>",
+        );
+
+        THEAD_REGEX: Regex = static_regex(
+            "THEAD_REGEX",
+            r"(?s)(<thead[^>]*>)(.*?)(</thead>)",
+        );
+
+        TH_REGEX: Regex = static_regex("TH_REGEX", r"<th(\s[^>]*)?>");
+
+        TBODY_REGEX: Regex = static_regex(
+            "TBODY_REGEX",
+            r"(?s)<tbody[^>]*>(.*?)</tbody>",
+        );
+
+        TR_REGEX: Regex = static_regex("TR_REGEX", r"(?s)<tr[\s>].*?</tr>");
+
+        ID_REGEX: Regex = static_regex("ID_REGEX", r#"\sid="([^"]*)""#);
+    }
+
+    /// Patterns of [`crate::templates`]
+    templates {
+        PLACEHOLDER_REGEX: Regex = static_regex(
+            "PLACEHOLDER_REGEX",
+            r"\{\{\s*([\w.-]+)\s*\}\}",
+        );
+
+        PARTIAL_REGEX: Regex = static_regex(
+            "PARTIAL_REGEX",
+            r"\{\{\s*>\s*([\w.-]+)\s*\}\}",
+        );
+
+        EXTENDS_REGEX: Regex = static_regex(
+            "EXTENDS_REGEX",
+            r"\A\s*\{\{#extends\s+([\w.-]+)\s*\}\}",
+        );
+
+        BLOCK_REGEX: Regex = static_regex(
+            "BLOCK_REGEX",
+            r"(?s)\{\{#block\s+([\w.-]+)\s*\}\}(.*?)\{\{/block\}\}",
+        );
+
+        H1_SELECTOR: Selector = Selector::parse("h1")
+            .expect("Failed to compile h1 selector");
+
+        PARAGRAPH_SELECTOR: Selector = Selector::parse("p")
+            .expect("Failed to compile paragraph selector");
+    }
+
+    /// Patterns of [`crate::utils`]
+    utils {
+        HEADER_REGEX: Regex = static_regex(
+            "HEADER_REGEX",
+            r"<(h[1-6])(?:\s[^>]*)?>(.+?)</h[1-6]>",
+        );
+
+        CONSECUTIVE_HYPHENS_REGEX: Regex = static_regex(
+            "CONSECUTIVE_HYPHENS_REGEX",
+            r"-{2,}",
+        );
+    }
+
+    /// Patterns of [`crate::writing_mode`]
+    writing_mode {
+        TAG_REGEX: Regex = static_regex(
+            "TAG_REGEX",
+            r#"<([A-Za-z][A-Za-z0-9-]*)((?:\s+[^\s"'>/=]+(?:\s*=\s*(?:"[^"]*"|'[^']*'|[^\s"'=<>`]+))?)*)\s*/?>"#,
+        );
+
+        ATTRIBUTE_REGEX: Regex = static_regex(
+            "ATTRIBUTE_REGEX",
+            r#"\s+([^\s"'>/=]+)(?:\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s"'=<>`]+)))?"#,
+        );
+    }
+}
+
+/// Compiles every regular expression and selector now rather than on
+/// first use.
+///
+/// Compiling is done once per process, so later calls return
+/// immediately.
+///
+/// # Returns
+///
+/// The number of patterns compiled for the enabled features.
+///
+/// # Errors
+///
+/// Returns [`HtmlError::SelectorParseError`] naming the selectors that
+/// failed to compile. The accessibility checks using them are reported
+/// as [`CheckStatus::SelectorUnavailable`].
+///
+/// # Panics
+///
+/// Panics if a regular expression fails to compile, which is a bug.
+///
+/// [`CheckStatus::SelectorUnavailable`]: crate::accessibility::CheckStatus::SelectorUnavailable
+pub fn warm_up() -> Result<usize> {
+    let (count, failed) = compile_all();
+    if failed.is_empty() {
+        Ok(count)
+    } else {
+        Err(HtmlError::SelectorParseError(
+            failed.join(", "),
+            "see the warnings logged while compiling".to_string(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_pattern_compiles() {
+        let (count, failed) = compile_all();
+        assert!(failed.is_empty(), "{:?}", failed);
+        assert!(count > 0);
+        assert_eq!(warm_up().unwrap(), count);
+        assert!(accessibility::HEADING_SELECTOR.is_some());
+    }
+
+    #[test]
+    fn test_pattern_trait() {
+        assert!(!None::<Selector>.is_compiled());
+        assert!(Some(Selector::parse("p").unwrap()).is_compiled());
+    }
+}
@@ -10,6 +10,7 @@
 //! - Non-blocking asynchronous HTML generation
 //! - Memory-efficient string handling
 //! - Thread-safe operations
+//! - Incremental rebuilds skipping unchanged Markdown
 //!
 //! # Performance Characteristics
 //!
@@ -30,17 +31,40 @@
 //! # Ok(())
 //! # }
 //! ```
+//!
+//! Skipping unchanged documents:
+//! ```
+//! # use html_generator::performance::{IncrementalCache, Freshness};
+//! let cache = IncrementalCache::in_memory(64);
+//! let first = cache.convert_if_changed("# Hello", None)?;
+//! let second = cache.convert_if_changed("# Hello", None)?;
+//! assert!(matches!(first, Freshness::Fresh(_)));
+//! assert!(matches!(second, Freshness::Cached(_)));
+//! # Ok::<(), html_generator::error::HtmlError>(())
+//! ```
 
-use crate::{HtmlError, Result};
-use comrak::{markdown_to_html, ComrakOptions};
+#[cfg(feature = "std")]
+use crate::cache::DiskCache;
+use crate::cache::{
+    convert_cached, convert_document_cached, CachedConversion,
+    LruCache, OutputCache,
+};
+#[cfg(feature = "std")]
+use crate::HtmlError;
+use crate::{MarkdownConfig, Result};
+use comrak::ComrakOptions;
+#[cfg(feature = "std")]
 use minify_html::{minify, Cfg};
+#[cfg(feature = "std")]
 use std::{fs, path::Path};
+#[cfg(feature = "std")]
 use tokio::task;
 
 /// Maximum allowed file size for minification (10 MB).
 pub const MAX_FILE_SIZE: usize = 10 * 1024 * 1024;
 
 /// Initial capacity for string buffers (1 KB).
+#[cfg(feature = "std")]
 const INITIAL_HTML_CAPACITY: usize = 1024;
 
 /// Configuration for HTML minification with optimized defaults.
@@ -48,12 +72,14 @@
 /// Provides a set of minification options that preserve HTML semantics
 /// while reducing file size. The configuration balances compression
 /// with standards compliance.
+#[cfg(feature = "std")]
 #[derive(Clone)]
 struct MinifyConfig {
     /// Internal minification configuration from minify-html crate
     cfg: Cfg,
 }
 
+#[cfg(feature = "std")]
 impl Default for MinifyConfig {
     fn default() -> Self {
         let mut cfg = Cfg::new();
@@ -74,6 +100,7 @@ fn default() -> Self {
     }
 }
 
+#[cfg(feature = "std")]
 impl std::fmt::Debug for MinifyConfig {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("MinifyConfig")
@@ -121,6 +148,7 @@ fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
 /// # Ok(())
 /// # }
 /// ```
+#[cfg(feature = "std")]
 pub fn minify_html(file_path: &Path) -> Result<String> {
     let metadata = fs::metadata(file_path).map_err(|e| {
         HtmlError::MinificationError(format!(
@@ -151,8 +179,46 @@ pub fn minify_html(file_path: &Path) -> Result<String> {
         }
     })?;
 
+    minify_html_str(&content)
+}
+
+/// Minifies HTML content held in memory.
+///
+/// Applies the same minification as [`minify_html`], e.g. to HTML read
+/// from standard input or generated by a conversion.
+///
+/// # Arguments
+///
+/// * `html` - The HTML to minify
+///
+/// # Returns
+///
+/// Returns the minified HTML content as a string if successful.
+///
+/// # Errors
+///
+/// Returns [`HtmlError::MinificationError`] if the content exceeds
+/// [`MAX_FILE_SIZE`] or the minified content is not valid UTF-8.
+///
+/// # Examples
+///
+/// ```
+/// # use html_generator::performance::minify_html_str;
+/// let minified = minify_html_str("<p>  Hello  </p>")?;
+/// assert_eq!(minified, "<p>Hello</p>");
+/// # Ok::<(), html_generator::error::HtmlError>(())
+/// ```
+#[cfg(feature = "std")]
+pub fn minify_html_str(html: &str) -> Result<String> {
+    if html.len() > MAX_FILE_SIZE {
+        return Err(HtmlError::MinificationError(format!(
+            "Input size {} bytes exceeds maximum of {MAX_FILE_SIZE} bytes",
+            html.len()
+        )));
+    }
+
     let config = MinifyConfig::default();
-    let minified = minify(content.as_bytes(), &config.cfg);
+    let minified = minify(html.as_bytes(), &config.cfg);
 
     String::from_utf8(minified).map_err(|e| {
         HtmlError::MinificationError(format!(
@@ -193,6 +259,7 @@ pub fn minify_html(file_path: &Path) -> Result<String> {
 /// # Ok(())
 /// # }
 /// ```
+#[cfg(feature = "std")]
 pub async fn async_generate_html(markdown: &str) -> Result<String> {
     // Optimize string allocation based on content size
     let markdown = if markdown.len() < INITIAL_HTML_CAPACITY {
@@ -206,7 +273,7 @@ pub async fn async_generate_html(markdown: &str) -> Result<String> {
 
     task::spawn_blocking(move || {
         let options = ComrakOptions::default();
-        Ok(markdown_to_html(&markdown, &options))
+        Ok(comrak::markdown_to_html(&markdown, &options))
     })
     .await
     .map_err(|e| HtmlError::MarkdownConversion {
@@ -244,10 +311,175 @@ pub async fn async_generate_html(markdown: &str) -> Result<String> {
 /// ```
 #[inline]
 pub fn generate_html(markdown: &str) -> Result<String> {
-    Ok(markdown_to_html(markdown, &ComrakOptions::default()))
+    Ok(comrak::markdown_to_html(
+        markdown,
+        &ComrakOptions::default(),
+    ))
+}
+
+/// Whether [`IncrementalCache::convert_if_changed`] converted the
+/// document or reused a previous result.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Freshness {
+    /// The document changed, or was never seen, and was converted.
+    Fresh(String),
+    /// The document was unchanged and its stored HTML was reused.
+    Cached(String),
+}
+
+impl Freshness {
+    /// Returns the HTML.
+    pub fn html(&self) -> &str {
+        match self {
+            Self::Fresh(html) | Self::Cached(html) => html,
+        }
+    }
+
+    /// Returns the HTML, consuming the result.
+    pub fn into_html(self) -> String {
+        match self {
+            Self::Fresh(html) | Self::Cached(html) => html,
+        }
+    }
+
+    /// Returns `true` if the stored HTML was reused.
+    pub fn is_cached(&self) -> bool {
+        matches!(self, Self::Cached(_))
+    }
+}
+
+impl From<CachedConversion> for Freshness {
+    fn from(conversion: CachedConversion) -> Self {
+        if conversion.hit {
+            Self::Cached(conversion.html)
+        } else {
+            Self::Fresh(conversion.html)
+        }
+    }
+}
+
+/// Cache skipping the conversion of unchanged Markdown.
+///
+/// Conversions go through [`markdown_to_html_cached`]: generated HTML is
+/// stored under a [`CacheKey`] of the Markdown, the configuration and
+/// the build of this crate, so editing a document, changing a setting
+/// or upgrading the crate all cause a fresh conversion. Entries live in
+/// any [`OutputCache`]: in memory, on disk with
+/// [`IncrementalCache::on_disk`] to carry them between runs, or in an
+/// external store.
+///
+/// Use [`IncrementalCache::convert_path_if_changed`] for documents that
+/// are edited, so the entry of their previous content is removed.
+///
+/// [`markdown_to_html_cached`]: crate::cache::markdown_to_html_cached
+/// [`CacheKey`]: crate::cache::CacheKey
+pub struct IncrementalCache {
+    store: Box<dyn OutputCache>,
 }
 
-#[cfg(test)]
+impl IncrementalCache {
+    /// Creates a cache keeping entries in `store`.
+    pub fn new(store: impl OutputCache + 'static) -> Self {
+        Self {
+            store: Box::new(store),
+        }
+    }
+
+    /// Creates a cache keeping at most `capacity` entries in memory.
+    pub fn in_memory(capacity: usize) -> Self {
+        Self::new(LruCache::new(capacity))
+    }
+
+    /// Creates a cache keeping entries as files in `dir`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`HtmlError::Io`] if the directory cannot be created.
+    #[cfg(feature = "std")]
+    pub fn on_disk(dir: impl AsRef<Path>) -> Result<Self> {
+        Ok(Self::new(DiskCache::new(dir.as_ref())?))
+    }
+
+    /// Converts `markdown` unless it was already converted with the same
+    /// configuration.
+    ///
+    /// # Arguments
+    ///
+    /// * `markdown` - The Markdown content
+    /// * `config` - Optional configuration for the conversion
+    ///
+    /// # Returns
+    ///
+    /// [`Freshness::Cached`] with the stored HTML if nothing changed,
+    /// otherwise [`Freshness::Fresh`] with the new HTML, which is stored.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`markdown_to_html`]; failures are
+    /// never stored.
+    ///
+    /// [`markdown_to_html`]: crate::markdown_to_html
+    pub fn convert_if_changed(
+        &self,
+        markdown: &str,
+        config: Option<MarkdownConfig>,
+    ) -> Result<Freshness> {
+        convert_cached(
+            markdown,
+            config.unwrap_or_default(),
+            &*self.store,
+        )
+        .map(Freshness::from)
+    }
+
+    /// Converts the document at `path` like
+    /// [`IncrementalCache::convert_if_changed`], and removes the entry
+    /// stored for its previous content when it changed.
+    ///
+    /// The key of the last conversion of each path is stored alongside
+    /// the HTML, so a store on disk keeps one entry per document across
+    /// runs instead of one per edit.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Key identifying the document, usually its source path
+    /// * `markdown` - The Markdown content
+    /// * `config` - Optional configuration for the conversion
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`markdown_to_html`]; failures are
+    /// never stored.
+    ///
+    /// [`markdown_to_html`]: crate::markdown_to_html
+    pub fn convert_path_if_changed(
+        &self,
+        path: &str,
+        markdown: &str,
+        config: Option<MarkdownConfig>,
+    ) -> Result<Freshness> {
+        convert_document_cached(
+            path,
+            markdown,
+            config.unwrap_or_default(),
+            &*self.store,
+        )
+        .map(Freshness::from)
+    }
+
+    /// Removes every stored entry, so the next conversions are fresh.
+    pub fn clear(&self) {
+        self.store.clear();
+    }
+}
+
+impl std::fmt::Debug for IncrementalCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IncrementalCache").finish_non_exhaustive()
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
 mod tests {
     use super::*;
     use std::fs::File;
@@ -292,6 +524,19 @@ fn test_minify_basic_html() {
             drop(dir);
         }
 
+        #[test]
+        fn test_minify_preserves_custom_attributes() {
+            let html = r#"<div data-user-id="5" hx-get="/x" x-on:click="go()"><p data-a='b c'>T</p></div>"#;
+            let (dir, file_path) = create_test_file(html);
+            let result = minify_html(&file_path).unwrap();
+            for attribute in
+                ["data-user-id", "hx-get", "x-on:click", "data-a"]
+            {
+                assert!(result.contains(attribute), "{}", attribute);
+            }
+            drop(dir);
+        }
+
         #[test]
         fn test_minify_with_comments() {
             let html =
@@ -428,6 +673,87 @@ fn test_sync_generate_html_large_content() {
         }
     }
 
+    mod incremental_cache_tests {
+        use super::*;
+        use crate::HtmlConfig;
+
+        #[test]
+        fn test_unchanged_markdown_is_cached() {
+            let cache = IncrementalCache::in_memory(8);
+            let first = cache.convert_if_changed("# Hi", None).unwrap();
+            let second =
+                cache.convert_if_changed("# Hi", None).unwrap();
+            let edited =
+                cache.convert_if_changed("# Hey", None).unwrap();
+
+            assert!(!first.is_cached());
+            assert!(second.is_cached());
+            assert_eq!(first.html(), second.html());
+            assert!(!edited.is_cached());
+
+            let config = MarkdownConfig {
+                html_config: HtmlConfig {
+                    minify_output: true,
+                    ..HtmlConfig::default()
+                },
+                ..MarkdownConfig::default()
+            };
+            assert!(!cache
+                .convert_if_changed("# Hi", Some(config))
+                .unwrap()
+                .is_cached());
+            cache.clear();
+            assert!(!cache
+                .convert_if_changed("# Hi", None)
+                .unwrap()
+                .is_cached());
+            assert!(cache.convert_if_changed("", None).is_err());
+        }
+
+        #[test]
+        fn test_disk_cache_survives_restarts() {
+            let dir = tempdir().unwrap();
+            let first = IncrementalCache::on_disk(dir.path())
+                .unwrap()
+                .convert_if_changed("# Hi", None)
+                .unwrap();
+            let second = IncrementalCache::on_disk(dir.path())
+                .unwrap()
+                .convert_if_changed("# Hi", None)
+                .unwrap();
+
+            assert_eq!(second, Freshness::Cached(first.into_html()));
+        }
+
+        #[test]
+        fn test_edits_replace_the_previous_entry() {
+            let dir = tempdir().unwrap();
+            let cache = IncrementalCache::on_disk(dir.path()).unwrap();
+            for markdown in ["# One", "# Two", "# Three"] {
+                assert!(!cache
+                    .convert_path_if_changed("a.md", markdown, None)
+                    .unwrap()
+                    .is_cached());
+            }
+            let _ = cache
+                .convert_path_if_changed("b.md", "# Other", None)
+                .unwrap();
+
+            // One entry and the key of its last conversion per path.
+            assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 4);
+            let reopened =
+                IncrementalCache::on_disk(dir.path()).unwrap();
+            assert!(reopened
+                .convert_path_if_changed("a.md", "# Three", None)
+                .unwrap()
+                .is_cached());
+            assert!(!reopened
+                .convert_if_changed("# Two", None)
+                .unwrap()
+                .is_cached());
+        }
+    }
+
     mod additional_tests {
         use super::*;
         use std::fs::File;
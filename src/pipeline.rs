@@ -0,0 +1,457 @@
+// Copyright © 2025 HTML Generator. All rights reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! HTML post-processing pipelines.
+//!
+//! After Markdown has been rendered, the generated HTML passes through a
+//! series of string-to-string steps such as emoji handling and Unicode
+//! cleanup. A [`Pipeline`] is an ordered list of such steps.
+//! [`Pipeline::from_config`] builds the pipeline used by
+//! [`convert`](crate::conversion::convert), and custom steps can be
+//! added with [`Pipeline::with_step`]. Registered
+//! [plugins](crate::plugins) are merged in with
+//! [`Pipeline::with_plugins`].
+//!
+//! Post-processing should be idempotent: running a pipeline on its own
+//! output must not change it again, or re-processing cached or partially
+//! generated pages drifts over time. [`verify_idempotent`] checks this
+//! and reports the first difference.
+//!
+//! # Examples
+//!
+//! ```
+//! use html_generator::pipeline::{verify_idempotent, Pipeline};
+//!
+//! let pipeline = Pipeline::new()
+//!     .with_step("trim", |html: &str| Ok(html.trim().to_string()));
+//! assert!(verify_idempotent("  <p>Hi</p>  ", &pipeline).is_ok());
+//!
+//! let pipeline = Pipeline::new()
+//!     .with_step("wrap", |html: &str| Ok(format!("<div>{}</div>", html)));
+//! let report = verify_idempotent("<p>Hi</p>", &pipeline).unwrap_err();
+//! assert_eq!(report.step.as_deref(), Some("wrap"));
+//! ```
+
+use crate::bidi::{add_auto_direction, set_direction};
+use crate::emojis::{apply_emoji_policy, EmojiCatalog, EmojiPolicy};
+use crate::error::Result;
+use crate::footnotes::{add_footnote_popovers, add_footnote_roles};
+use crate::math::add_math_markup;
+use crate::plugins::PluginRegistry;
+use crate::profiler::{profile, Profiler};
+use crate::serializer::{encode_entities, EntityEncoding};
+use crate::tables::{chunk_tables, enhance_tables};
+use crate::unicode::{normalize_nfc, strip_invisible_characters};
+use crate::writing_mode::apply_writing_mode;
+use crate::HtmlConfig;
+use alloc::{
+    boxed::Box,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt;
+
+/// A single post-processing step.
+pub type Step = dyn Fn(&str) -> Result<String> + Send + Sync;
+
+/// Number of characters of context shown around a difference.
+const CONTEXT_CHARS: usize = 40;
+
+/// An ordered list of named HTML post-processing steps.
+#[derive(Default)]
+pub struct Pipeline {
+    steps: Vec<(String, Box<Step>)>,
+}
+
+impl fmt::Debug for Pipeline {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Pipeline")
+            .field("steps", &self.step_names())
+            .finish()
+    }
+}
+
+impl Pipeline {
+    /// Creates an empty pipeline that returns its input unchanged.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builds the post-processing pipeline applied by
+    /// [`convert`](crate::conversion::convert) for `config`.
+    ///
+    /// Steps are only included when enabled in `config`, in this order:
+    /// `emoji_policy`, `strip_invisible_characters`, `normalize_unicode`,
+    /// `math`, `footnote_roles`, `footnote_popovers`, `enhance_tables`, `chunk_tables`,
+    /// `auto_direction`, `entity_encoding`, `writing_mode`, `direction`.
+    pub fn from_config(config: &HtmlConfig) -> Self {
+        let mut pipeline = Self::new();
+        let policy = config.emoji_policy;
+        if policy != EmojiPolicy::Keep {
+            pipeline =
+                pipeline.with_step("emoji_policy", move |html| {
+                    Ok(apply_emoji_policy(
+                        html,
+                        policy,
+                        EmojiCatalog::bundled(),
+                    ))
+                });
+        }
+        if config.strip_invisible_characters {
+            pipeline = pipeline
+                .with_step("strip_invisible_characters", |html| {
+                    Ok(strip_invisible_characters(html))
+                });
+        }
+        if config.normalize_unicode {
+            pipeline = pipeline
+                .with_step("normalize_unicode", |html| {
+                    Ok(normalize_nfc(html))
+                });
+        }
+        if config.math {
+            pipeline = pipeline
+                .with_step("math", |html| Ok(add_math_markup(html)));
+        }
+        let footnotes = config.markdown_extensions.footnotes
+            || config.footnote_popovers;
+        if footnotes && config.add_aria_attributes {
+            pipeline = pipeline.with_step("footnote_roles", |html| {
+                Ok(add_footnote_roles(html))
+            });
+        }
+        if config.footnote_popovers {
+            pipeline = pipeline
+                .with_step("footnote_popovers", |html| {
+                    Ok(add_footnote_popovers(html))
+                });
+        }
+        if config.enhance_tables {
+            pipeline = pipeline.with_step("enhance_tables", |html| {
+                Ok(enhance_tables(html))
+            });
+        }
+        let rows = config.table_chunk_rows;
+        if rows > 0 {
+            pipeline = pipeline
+                .with_step("chunk_tables", move |html| {
+                    Ok(chunk_tables(html, rows))
+                });
+        }
+        if config.auto_direction {
+            pipeline = pipeline.with_step("auto_direction", |html| {
+                Ok(add_auto_direction(html))
+            });
+        }
+        let encoding = config.entity_encoding;
+        if encoding != EntityEncoding::default() {
+            pipeline = pipeline
+                .with_step("entity_encoding", move |html| {
+                    Ok(encode_entities(html, &encoding))
+                });
+        }
+        let mode = config.writing_mode;
+        if mode.is_vertical() {
+            pipeline = pipeline
+                .with_step("writing_mode", move |html| {
+                    Ok(apply_writing_mode(html, mode))
+                });
+        }
+        if let Some(direction) = config.direction {
+            pipeline = pipeline.with_step("direction", move |html| {
+                Ok(set_direction(html, direction))
+            });
+        }
+        pipeline
+    }
+
+    /// Appends a step to the pipeline.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Name used in reports, e.g. `minify`
+    /// * `step` - The transformation
+    #[must_use]
+    pub fn with_step<F>(
+        mut self,
+        name: impl Into<String>,
+        step: F,
+    ) -> Self
+    where
+        F: Fn(&str) -> Result<String> + Send + Sync + 'static,
+    {
+        self.steps.push((name.into(), Box::new(step)));
+        self
+    }
+
+    /// Adds the steps of `plugins`, ordered by their constraints.
+    ///
+    /// # Arguments
+    ///
+    /// * `plugins` - See [`PluginRegistry`]
+    ///
+    /// # Errors
+    ///
+    /// Returns [`HtmlError::InvalidInput`](crate::error::HtmlError) if
+    /// the ordering constraints of the plugins form a cycle.
+    pub fn with_plugins(
+        self,
+        plugins: &PluginRegistry,
+    ) -> Result<Self> {
+        if plugins.is_empty() {
+            return Ok(self);
+        }
+        let order = plugins.order(&self.step_names())?;
+        let mut steps: Vec<_> =
+            self.steps.into_iter().map(Some).collect();
+        steps.extend(plugins.iter().map(|plugin| {
+            let name = plugin.name().to_string();
+            let plugin = plugin.clone();
+            let step: Box<Step> =
+                Box::new(move |html: &str| plugin.process(html));
+            Some((name, step))
+        }));
+        Ok(Self {
+            steps: order
+                .into_iter()
+                .filter_map(|index| steps[index].take())
+                .collect(),
+        })
+    }
+
+    /// Returns the names of the steps in order.
+    pub fn step_names(&self) -> Vec<&str> {
+        self.steps.iter().map(|(name, _)| name.as_str()).collect()
+    }
+
+    /// Returns the number of steps.
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    /// Returns `true` if the pipeline has no steps.
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// Runs every step in order.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error produced by a step.
+    pub fn run(&self, html: &str) -> Result<String> {
+        self.run_profiled(html, None)
+    }
+
+    /// Runs every step in order, timing each one under its name with
+    /// `profiler` if set.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error produced by a step.
+    pub fn run_profiled(
+        &self,
+        html: &str,
+        profiler: Option<&Profiler>,
+    ) -> Result<String> {
+        let mut html = html.to_string();
+        for (name, step) in &self.steps {
+            html = profile(profiler, name, || step(&html))?;
+        }
+        Ok(html)
+    }
+}
+
+/// Why [`verify_idempotent`] failed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffReport {
+    /// The step that changed already processed output, if a single step
+    /// could be identified.
+    pub step: Option<String>,
+    /// Output of the first run.
+    pub first_pass: String,
+    /// Output of running the pipeline again on `first_pass`.
+    pub second_pass: String,
+    /// Byte offset of the first difference in `first_pass`.
+    pub offset: usize,
+    /// 1-based line of the first difference.
+    pub line: usize,
+    /// 1-based column, in characters, of the first difference.
+    pub column: usize,
+    /// The error message, if a step failed instead.
+    pub error: Option<String>,
+}
+
+impl DiffReport {
+    /// Returns the text around the difference in both passes.
+    pub fn context(&self) -> (&str, &str) {
+        (
+            snippet(&self.first_pass, self.offset),
+            snippet(&self.second_pass, self.offset),
+        )
+    }
+}
+
+impl fmt::Display for DiffReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(error) = &self.error {
+            return write!(f, "Pipeline failed: {}", error);
+        }
+        let (expected, actual) = self.context();
+        write!(
+            f,
+            "Pipeline is not idempotent at line {}, column {}",
+            self.line, self.column
+        )?;
+        if let Some(step) = &self.step {
+            write!(f, " (step '{}')", step)?;
+        }
+        write!(f, ": expected {:?}, got {:?}", expected, actual)
+    }
+}
+
+/// Verifies that running `pipeline` on its own output changes nothing.
+///
+/// The pipeline is run on `html`, then again on the result. If the two
+/// outputs differ, the steps are replayed one by one on the first output
+/// to find the step responsible.
+///
+/// # Arguments
+///
+/// * `html` - The input HTML
+/// * `pipeline` - The pipeline to check
+///
+/// # Errors
+///
+/// Returns a [`DiffReport`] describing the first difference, or the
+/// error of a failing step.
+pub fn verify_idempotent(
+    html: &str,
+    pipeline: &Pipeline,
+) -> core::result::Result<(), DiffReport> {
+    let failed = |error: crate::error::HtmlError| DiffReport {
+        step: None,
+        first_pass: String::new(),
+        second_pass: String::new(),
+        offset: 0,
+        line: 0,
+        column: 0,
+        error: Some(error.to_string()),
+    };
+
+    let first_pass = pipeline.run(html).map_err(failed)?;
+    let second_pass = pipeline.run(&first_pass).map_err(failed)?;
+    if first_pass == second_pass {
+        return Ok(());
+    }
+
+    let offset = first_difference(&first_pass, &second_pass);
+    let prefix = &first_pass[..offset];
+    let line = prefix.matches('\n').count() + 1;
+    let column = prefix
+        .rsplit('\n')
+        .next()
+        .map_or(0, |line| line.chars().count())
+        + 1;
+    let step =
+        pipeline.steps.iter().find_map(|(name, step)| {
+            match step(&first_pass) {
+                Ok(output) if output != first_pass => {
+                    Some(name.clone())
+                }
+                _ => None,
+            }
+        });
+
+    Err(DiffReport {
+        step,
+        first_pass,
+        second_pass,
+        offset,
+        line,
+        column,
+        error: None,
+    })
+}
+
+/// Returns the byte offset of the first differing character.
+fn first_difference(a: &str, b: &str) -> usize {
+    a.char_indices()
+        .zip(b.chars())
+        .find(|((_, x), y)| x != y)
+        .map_or_else(|| a.len().min(b.len()), |((i, _), _)| i)
+}
+
+/// Returns up to [`CONTEXT_CHARS`] characters of `text` from `offset`.
+fn snippet(text: &str, offset: usize) -> &str {
+    let rest = text.get(offset..).unwrap_or("");
+    match rest.char_indices().nth(CONTEXT_CHARS) {
+        Some((end, _)) => &rest[..end],
+        None => rest,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_pipelines_are_idempotent() {
+        let config = HtmlConfig {
+            emoji_policy: EmojiPolicy::StripWithLabel,
+            normalize_unicode: true,
+            strip_invisible_characters: true,
+            enhance_tables: true,
+            table_chunk_rows: 1,
+            entity_encoding: EntityEncoding {
+                non_ascii: crate::serializer::NonAsciiEncoding::Named,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let pipeline = Pipeline::from_config(&config);
+        assert_eq!(
+            pipeline.step_names(),
+            vec![
+                "emoji_policy",
+                "strip_invisible_characters",
+                "normalize_unicode",
+                "enhance_tables",
+                "chunk_tables",
+                "entity_encoding"
+            ]
+        );
+
+        let html = "<p>Caf\u{65}\u{301} \u{200B}✅</p>\n<pre>✅</pre>\n<table><thead><tr><th>é</th></tr></thead><tbody><tr><td>1</td></tr><tr><td>2</td></tr></tbody></table>";
+        assert!(verify_idempotent(html, &pipeline).is_ok());
+        assert!(
+            Pipeline::from_config(&HtmlConfig::default()).is_empty()
+        );
+    }
+
+    #[test]
+    fn test_reports_first_difference_and_step() {
+        let pipeline = Pipeline::new()
+            .with_step("trim", |html: &str| Ok(html.trim().to_string()))
+            .with_step("counter", |html: &str| {
+                Ok(html.replacen("<hr>", "<hr><hr>", 1))
+            });
+
+        let report =
+            verify_idempotent("<p>a</p>\n<p>é<hr></p>", &pipeline)
+                .unwrap_err();
+        assert_eq!(report.step.as_deref(), Some("counter"));
+        assert_eq!((report.line, report.column), (2, 14));
+        assert_eq!(report.context(), ("/p>", "hr></p>"));
+        assert!(report.to_string().contains("step 'counter'"));
+    }
+
+    #[test]
+    fn test_step_errors_are_reported() {
+        let pipeline = Pipeline::new().with_step("fail", |_: &str| {
+            Err(crate::error::HtmlError::InvalidInput("bad".into()))
+        });
+        let report = verify_idempotent("x", &pipeline).unwrap_err();
+        assert_eq!(report.error.as_deref(), Some("Invalid input: bad"));
+    }
+}
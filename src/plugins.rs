@@ -0,0 +1,376 @@
+// Copyright © 2025 HTML Generator. All rights reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Post-processor plugins.
+//!
+//! Crates extending the generator implement [`PostProcessor`] and are
+//! registered as a named [`Plugin`] in
+//! [`HtmlConfig::plugins`](crate::HtmlConfig::plugins). Each conversion
+//! runs them in the post-processing [`Pipeline`](crate::pipeline::Pipeline)
+//! with the JSON options they were registered with.
+//!
+//! Plugins run after the built-in steps unless they are ordered with
+//! [`Plugin::before`] and [`Plugin::after`], which name built-in steps
+//! (see [`Pipeline::from_config`](crate::pipeline::Pipeline::from_config))
+//! or other plugins. Constraints on steps that are not part of a
+//! conversion, such as disabled built-in steps, are ignored; constraints
+//! that contradict each other fail the conversion.
+//!
+//! # Examples
+//!
+//! ```
+//! use html_generator::plugins::{Plugin, PostProcessor};
+//! use html_generator::{conversion::convert, HtmlConfig, Result};
+//! use serde_json::{json, Value};
+//!
+//! struct Banner;
+//!
+//! impl PostProcessor for Banner {
+//!     fn process(&self, html: &str, options: &Value) -> Result<String> {
+//!         let text = options["text"].as_str().unwrap_or("Draft");
+//!         Ok(format!("<p class=\"banner\">{}</p>\n{}", text, html))
+//!     }
+//! }
+//!
+//! let mut config = HtmlConfig::default();
+//! config.plugins.register(
+//!     Plugin::new("banner", Banner).with_options(json!({"text": "Preview"})),
+//! )?;
+//! let html = convert("Hello", &config)?;
+//! assert_eq!(html, "<p class=\"banner\">Preview</p>\n<p>Hello</p>\n");
+//! # Ok::<(), html_generator::error::HtmlError>(())
+//! ```
+
+use crate::error::{HtmlError, Result};
+use serde_json::Value;
+use std::fmt;
+use std::sync::Arc;
+
+/// Rewrites the generated HTML.
+pub trait PostProcessor: Send + Sync {
+    /// Processes `html`.
+    ///
+    /// # Arguments
+    ///
+    /// * `html` - The HTML produced by the previous steps
+    /// * `options` - The options of the [`Plugin`], `null` by default
+    ///
+    /// # Errors
+    ///
+    /// An error fails the conversion of the document.
+    fn process(&self, html: &str, options: &Value) -> Result<String>;
+}
+
+/// A named [`PostProcessor`] with its options and ordering constraints.
+#[derive(Clone)]
+pub struct Plugin {
+    name: String,
+    processor: Arc<dyn PostProcessor>,
+    options: Value,
+    before: Vec<String>,
+    after: Vec<String>,
+}
+
+impl Plugin {
+    /// Creates a plugin without options or ordering constraints.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Unique name, used in pipeline reports and constraints
+    /// * `processor` - The post-processor
+    pub fn new(
+        name: impl Into<String>,
+        processor: impl PostProcessor + 'static,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            processor: Arc::new(processor),
+            options: Value::Null,
+            before: Vec::new(),
+            after: Vec::new(),
+        }
+    }
+
+    /// Sets the options passed to the post-processor.
+    #[must_use]
+    pub fn with_options(mut self, options: Value) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Runs the plugin before the step or plugin named `step`.
+    #[must_use]
+    pub fn before(mut self, step: impl Into<String>) -> Self {
+        self.before.push(step.into());
+        self
+    }
+
+    /// Runs the plugin after the step or plugin named `step`.
+    #[must_use]
+    pub fn after(mut self, step: impl Into<String>) -> Self {
+        self.after.push(step.into());
+        self
+    }
+
+    /// Returns the name of the plugin.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the options of the plugin.
+    pub fn options(&self) -> &Value {
+        &self.options
+    }
+
+    /// Runs the post-processor with the options of the plugin.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the post-processor.
+    pub fn process(&self, html: &str) -> Result<String> {
+        self.processor.process(html, &self.options)
+    }
+}
+
+impl fmt::Debug for Plugin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Plugin")
+            .field("name", &self.name)
+            .field("options", &self.options)
+            .field("before", &self.before)
+            .field("after", &self.after)
+            .finish()
+    }
+}
+
+impl PartialEq for Plugin {
+    /// Plugins are equal if they share the post-processor instance,
+    /// name, options and constraints.
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+            && Arc::ptr_eq(&self.processor, &other.processor)
+            && self.options == other.options
+            && self.before == other.before
+            && self.after == other.after
+    }
+}
+
+impl Eq for Plugin {}
+
+/// Plugins in registration order.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PluginRegistry {
+    plugins: Vec<Plugin>,
+}
+
+impl PluginRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a plugin.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`HtmlError::InvalidInput`] if a plugin of the same name
+    /// is already registered.
+    pub fn register(&mut self, plugin: Plugin) -> Result<()> {
+        if self.get(&plugin.name).is_some() {
+            return Err(HtmlError::InvalidInput(format!(
+                "Plugin `{}` is already registered",
+                plugin.name
+            )));
+        }
+        self.plugins.push(plugin);
+        Ok(())
+    }
+
+    /// Replaces the options of the plugin named `name`.
+    ///
+    /// # Returns
+    ///
+    /// `false` if no such plugin is registered.
+    pub fn set_options(&mut self, name: &str, options: Value) -> bool {
+        match self.plugins.iter_mut().find(|p| p.name == name) {
+            Some(plugin) => {
+                plugin.options = options;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the plugin named `name`.
+    pub fn get(&self, name: &str) -> Option<&Plugin> {
+        self.plugins.iter().find(|plugin| plugin.name == name)
+    }
+
+    /// Returns the plugins in registration order.
+    pub fn iter(&self) -> impl Iterator<Item = &Plugin> {
+        self.plugins.iter()
+    }
+
+    /// Returns the number of plugins.
+    pub fn len(&self) -> usize {
+        self.plugins.len()
+    }
+
+    /// Returns `true` if no plugin is registered.
+    pub fn is_empty(&self) -> bool {
+        self.plugins.is_empty()
+    }
+
+    /// Orders the built-in `steps` and the plugins.
+    ///
+    /// Returns indices into `steps` followed by the plugins. Built-in
+    /// steps keep their order, plugins constrained relative to a
+    /// built-in step run as early as their constraints allow, and the
+    /// other plugins run after the built-in steps.
+    pub(crate) fn order(&self, steps: &[&str]) -> Result<Vec<usize>> {
+        let names: Vec<&str> = steps
+            .iter()
+            .copied()
+            .chain(self.plugins.iter().map(|p| p.name.as_str()))
+            .collect();
+        let index = |name: &str| names.iter().position(|n| *n == name);
+        let mut edges = vec![Vec::new(); names.len()];
+        for step in 1..steps.len() {
+            edges[step - 1].push(step);
+        }
+        for (offset, plugin) in self.plugins.iter().enumerate() {
+            let node = steps.len() + offset;
+            let before: Vec<_> =
+                plugin.before.iter().filter_map(|n| index(n)).collect();
+            let after: Vec<_> =
+                plugin.after.iter().filter_map(|n| index(n)).collect();
+            let anchored =
+                before.iter().chain(&after).any(|&n| n < steps.len());
+            if !anchored && !steps.is_empty() {
+                edges[steps.len() - 1].push(node);
+            }
+            edges[node].extend(before);
+            for after in after {
+                edges[after].push(node);
+            }
+        }
+
+        let mut incoming = vec![0; names.len()];
+        for targets in &edges {
+            for &target in targets {
+                incoming[target] += 1;
+            }
+        }
+        let mut order = Vec::with_capacity(names.len());
+        while let Some(node) = (steps.len()..names.len())
+            .chain(0..steps.len())
+            .find(|n| incoming[*n] == 0 && !order.contains(n))
+        {
+            order.push(node);
+            for &target in &edges[node] {
+                incoming[target] -= 1;
+            }
+        }
+        if order.len() < names.len() {
+            let stuck: Vec<_> = (steps.len()..names.len())
+                .filter(|n| !order.contains(n))
+                .map(|n| names[n])
+                .collect();
+            return Err(HtmlError::InvalidInput(format!(
+                "Plugin ordering constraints form a cycle: {}",
+                stuck.join(", ")
+            )));
+        }
+        Ok(order)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conversion::convert;
+    use crate::pipeline::Pipeline;
+    use crate::HtmlConfig;
+    use serde_json::json;
+
+    struct Append;
+
+    impl PostProcessor for Append {
+        fn process(
+            &self,
+            html: &str,
+            options: &Value,
+        ) -> Result<String> {
+            Ok(format!("{}{}", html, options.as_str().unwrap_or("!")))
+        }
+    }
+
+    fn names(pipeline: &Pipeline) -> Vec<&str> {
+        pipeline.step_names()
+    }
+
+    #[test]
+    fn test_plugins_are_ordered_by_constraints() {
+        let mut config = HtmlConfig {
+            math: true,
+            enhance_tables: true,
+            ..HtmlConfig::default()
+        };
+        for plugin in [
+            Plugin::new("last", Append),
+            Plugin::new("early", Append).before("math"),
+            Plugin::new("middle", Append)
+                .after("math")
+                .before("missing_step"),
+            Plugin::new("chained", Append).after("last"),
+        ] {
+            config.plugins.register(plugin).unwrap();
+        }
+        let pipeline = Pipeline::from_config(&config)
+            .with_plugins(&config.plugins)
+            .unwrap();
+        assert_eq!(
+            names(&pipeline),
+            [
+                "early",
+                "math",
+                "middle",
+                "enhance_tables",
+                "last",
+                "chained"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_options_and_errors() {
+        let mut config = HtmlConfig::default();
+        config
+            .plugins
+            .register(
+                Plugin::new("mark", Append).with_options(json!("?")),
+            )
+            .unwrap();
+        assert!(config
+            .plugins
+            .register(Plugin::new("mark", Append))
+            .is_err());
+        assert_eq!(convert("Hi", &config).unwrap(), "<p>Hi</p>\n?");
+
+        assert!(config.plugins.set_options("mark", json!("#")));
+        assert!(!config.plugins.set_options("other", json!("#")));
+        assert_eq!(convert("Hi", &config).unwrap(), "<p>Hi</p>\n#");
+
+        let mut cyclic = HtmlConfig::default();
+        cyclic
+            .plugins
+            .register(Plugin::new("a", Append).after("b"))
+            .unwrap();
+        cyclic
+            .plugins
+            .register(Plugin::new("b", Append).after("a"))
+            .unwrap();
+        let error = convert("Hi", &cyclic).unwrap_err();
+        assert!(error.to_string().contains("cycle: a, b"));
+    }
+}
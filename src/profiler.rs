@@ -0,0 +1,344 @@
+// Copyright © 2025 HTML Generator. All rights reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Per-stage timings of conversions.
+//!
+//! A [`Profiler`] set in
+//! [`HtmlConfig::profiler`](crate::HtmlConfig::profiler) records how
+//! long each stage of every conversion takes: Markdown rendering, syntax
+//! highlighting, heading IDs, each step of the post-processing
+//! [pipeline](crate::pipeline) (such as `footnote_roles`, which adds ARIA
+//! roles), hooks, URL policies and, in the file and batch APIs, writing
+//! the output. Clones share their recordings, so one profiler can follow
+//! a whole batch, and the file and batch APIs label the spans of each
+//! page with its path.
+//!
+//! Recordings are exported as [Chrome trace events], which
+//! `chrome://tracing`, Perfetto and speedscope display as a timeline
+//! with one row per document, or as folded stacks for `flamegraph.pl`
+//! and `inferno`.
+//!
+//! [Chrome trace events]: https://docs.google.com/document/d/1CvAClvFfyA5R-PhYUmn5OOQtYMH4h6I0nSsKchNAySU
+//!
+//! # Examples
+//!
+//! ```
+//! use html_generator::profiler::Profiler;
+//! use html_generator::{conversion::convert, HtmlConfig};
+//!
+//! let profiler = Profiler::new();
+//! let config = HtmlConfig {
+//!     profiler: Some(profiler.with_document("guide.md")),
+//!     ..HtmlConfig::default()
+//! };
+//! let _ = convert("# Guide\n\n```rust\nfn main() {}\n```\n", &config)?;
+//!
+//! let (stage, _) = &profiler.stage_totals()[0];
+//! println!("slowest stage: {}", stage);
+//! let trace = profiler.to_chrome_trace();
+//! assert!(trace["traceEvents"].as_array().map_or(false, |e| !e.is_empty()));
+//! # Ok::<(), html_generator::error::HtmlError>(())
+//! ```
+
+use serde_json::{json, Value};
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+/// Document label of conversions without a known source.
+pub const UNNAMED_DOCUMENT: &str = "<input>";
+
+/// A stage of a conversion and how long it took.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProfileSpan {
+    /// The converted document, see [`Profiler::with_document`].
+    pub document: String,
+    /// The stage, e.g. `markdown`, `highlight` or a pipeline step.
+    pub stage: String,
+    /// When the stage started, relative to the creation of the profiler.
+    pub start: Duration,
+    /// How long the stage took.
+    pub duration: Duration,
+}
+
+/// State shared by the clones of a [`Profiler`].
+#[derive(Debug)]
+struct ProfilerState {
+    origin: Instant,
+    spans: Mutex<Vec<ProfileSpan>>,
+}
+
+/// Records the stages of conversions, see the [module
+/// documentation](self).
+#[derive(Clone)]
+pub struct Profiler {
+    state: Arc<ProfilerState>,
+    document: Option<String>,
+}
+
+impl Default for Profiler {
+    fn default() -> Self {
+        Self {
+            state: Arc::new(ProfilerState {
+                origin: Instant::now(),
+                spans: Mutex::new(Vec::new()),
+            }),
+            document: None,
+        }
+    }
+}
+
+impl Profiler {
+    /// Creates a profiler without recordings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a profiler sharing the recordings of this one, labelling
+    /// its spans with `document`.
+    ///
+    /// The file and batch APIs call it with the path of each input.
+    #[must_use]
+    pub fn with_document(&self, document: impl Into<String>) -> Self {
+        Self {
+            state: Arc::clone(&self.state),
+            document: Some(document.into()),
+        }
+    }
+
+    /// Returns the label of the spans recorded by this profiler.
+    pub fn document(&self) -> Option<&str> {
+        self.document.as_deref()
+    }
+
+    /// Runs `f`, recording its duration as `stage`.
+    pub fn time<T>(&self, stage: &str, f: impl FnOnce() -> T) -> T {
+        let started = Instant::now();
+        let result = f();
+        let duration = started.elapsed();
+        let span = ProfileSpan {
+            document: self
+                .document
+                .clone()
+                .unwrap_or_else(|| UNNAMED_DOCUMENT.to_string()),
+            stage: stage.to_string(),
+            start: started.saturating_duration_since(self.state.origin),
+            duration,
+        };
+        self.lock().push(span);
+        result
+    }
+
+    /// Returns the recorded spans in the order they finished.
+    pub fn spans(&self) -> Vec<ProfileSpan> {
+        self.lock().clone()
+    }
+
+    /// Removes every recorded span.
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    /// Returns the total time of each stage, slowest first.
+    pub fn stage_totals(&self) -> Vec<(String, Duration)> {
+        self.totals(|span| &span.stage)
+    }
+
+    /// Returns the total time of each document, slowest first.
+    pub fn document_totals(&self) -> Vec<(String, Duration)> {
+        self.totals(|span| &span.document)
+    }
+
+    /// Returns the spans as a Chrome trace: complete (`X`) events with
+    /// microsecond timestamps, one thread per document.
+    pub fn to_chrome_trace(&self) -> Value {
+        let spans = self.spans();
+        let mut threads: Vec<&str> = Vec::new();
+        let mut events = Vec::with_capacity(spans.len());
+        for span in &spans {
+            let tid = match threads
+                .iter()
+                .position(|d| *d == span.document)
+            {
+                Some(index) => index + 1,
+                None => {
+                    threads.push(&span.document);
+                    events.push(json!({
+                        "name": "thread_name",
+                        "ph": "M",
+                        "pid": 1,
+                        "tid": threads.len(),
+                        "args": { "name": span.document },
+                    }));
+                    threads.len()
+                }
+            };
+            events.push(json!({
+                "name": span.stage,
+                "cat": "html-generator",
+                "ph": "X",
+                "pid": 1,
+                "tid": tid,
+                "ts": micros(span.start),
+                "dur": micros(span.duration),
+                "args": { "document": span.document },
+            }));
+        }
+        json!({ "traceEvents": events, "displayTimeUnit": "ms" })
+    }
+
+    /// Returns the spans as folded stacks, one `document;stage
+    /// microseconds` line per document and stage, as read by
+    /// `flamegraph.pl` and `inferno-flamegraph`.
+    pub fn to_folded_stacks(&self) -> String {
+        let mut stacks: BTreeMap<(String, String), Duration> =
+            BTreeMap::new();
+        for span in self.spans() {
+            *stacks.entry((span.document, span.stage)).or_default() +=
+                span.duration;
+        }
+        stacks
+            .into_iter()
+            .map(|((document, stage), duration)| {
+                format!(
+                    "{};{} {}\n",
+                    document.replace(';', ":"),
+                    stage,
+                    duration.as_micros()
+                )
+            })
+            .collect()
+    }
+
+    /// Sums the spans by `key`, slowest first.
+    fn totals(
+        &self,
+        key: impl Fn(&ProfileSpan) -> &String,
+    ) -> Vec<(String, Duration)> {
+        let mut totals: BTreeMap<String, Duration> = BTreeMap::new();
+        for span in self.lock().iter() {
+            *totals.entry(key(span).clone()).or_default() +=
+                span.duration;
+        }
+        let mut totals: Vec<_> = totals.into_iter().collect();
+        totals.sort_by_key(|(_, duration)| Reverse(*duration));
+        totals
+    }
+
+    /// Locks the spans, recovering from a poisoned lock.
+    fn lock(&self) -> MutexGuard<'_, Vec<ProfileSpan>> {
+        self.state
+            .spans
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl fmt::Debug for Profiler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Profiler")
+            .field("document", &self.document)
+            .field("spans", &self.lock().len())
+            .finish()
+    }
+}
+
+impl PartialEq for Profiler {
+    /// Profilers are equal if they share their recordings and label.
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.state, &other.state)
+            && self.document == other.document
+    }
+}
+
+impl Eq for Profiler {}
+
+/// Runs `f`, timing it as `stage` if a profiler is set.
+pub(crate) fn profile<T>(
+    profiler: Option<&Profiler>,
+    stage: &str,
+    f: impl FnOnce() -> T,
+) -> T {
+    match profiler {
+        Some(profiler) => profiler.time(stage, f),
+        None => f(),
+    }
+}
+
+/// Returns `duration` in microseconds, with fractions.
+fn micros(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1_000_000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conversion::convert;
+    use crate::HtmlConfig;
+
+    #[test]
+    fn test_conversion_stages_are_recorded() {
+        let profiler = Profiler::new();
+        let config = HtmlConfig {
+            math: true,
+            profiler: Some(profiler.with_document("a.md")),
+            ..HtmlConfig::default()
+        };
+        let _ = convert("# A\n\n```rust\nlet x = 1;\n```\n", &config)
+            .unwrap();
+        let config = HtmlConfig {
+            profiler: Some(profiler.clone()),
+            ..HtmlConfig::default()
+        };
+        let _ = convert("B", &config).unwrap();
+
+        let stages: Vec<_> = profiler
+            .spans()
+            .into_iter()
+            .filter(|span| span.document == "a.md")
+            .map(|span| span.stage)
+            .collect();
+        for stage in ["markdown", "highlight", "math"] {
+            assert!(stages.iter().any(|s| s == stage), "{}", stage);
+        }
+        let documents: Vec<_> = profiler
+            .document_totals()
+            .into_iter()
+            .map(|(document, _)| document)
+            .collect();
+        assert_eq!(documents.len(), 2);
+        assert!(documents.iter().any(|d| d == UNNAMED_DOCUMENT));
+
+        profiler.clear();
+        assert!(profiler.spans().is_empty());
+    }
+
+    #[test]
+    fn test_exports() {
+        let profiler = Profiler::new();
+        let page = profiler.with_document("docs;a.md");
+        page.time("markdown", || ());
+        page.time("markdown", || ());
+        profiler.with_document("b.md").time("highlight", || ());
+
+        let trace = profiler.to_chrome_trace();
+        let events = trace["traceEvents"].as_array().unwrap();
+        assert_eq!(events.len(), 5);
+        assert_eq!(events[0]["ph"], "M");
+        assert_eq!(events[0]["args"]["name"], "docs;a.md");
+        assert_eq!(events[1]["name"], "markdown");
+        assert_eq!(events[1]["tid"], 1);
+        assert_eq!(events[4]["tid"], 2);
+
+        let folded = profiler.to_folded_stacks();
+        let lines: Vec<_> = folded.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("b.md;highlight "));
+        assert!(lines[1].starts_with("docs:a.md;markdown "));
+
+        assert_eq!(page, profiler.with_document("docs;a.md"));
+        assert_ne!(page, Profiler::new().with_document("docs;a.md"));
+    }
+}
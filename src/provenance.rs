@@ -0,0 +1,198 @@
+// Copyright © 2025 HTML Generator. All rights reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Provenance comments.
+//!
+//! When a generated page looks wrong, the first questions are which
+//! version of the generator produced it and from which source. With
+//! [`HtmlConfig::provenance`](crate::HtmlConfig::provenance) set, the
+//! output starts with a comment answering both:
+//!
+//! ```html
+//! <!-- generated by html-generator v0.0.3 from docs/page.md at hash 6b9a0d4bd1b3c3f8 -->
+//! ```
+//!
+//! The hash identifies the Markdown source. The comment carries no
+//! timestamp unless one is given, so repeated builds of the same input
+//! stay byte-for-byte identical. Conversion never reads the clock:
+//! [`Provenance::timestamped`] takes the current time once, when the
+//! setting is created.
+//!
+//! [`convert_directory`](crate::batch::convert_directory) fills in the
+//! source path of each page. The comment is left out when
+//! [`HtmlConfig::minify_output`](crate::HtmlConfig::minify_output) is
+//! set, so production builds can keep the same configuration.
+//!
+//! # Examples
+//!
+//! ```
+//! use html_generator::provenance::Provenance;
+//!
+//! let provenance = Provenance::default()
+//!     .with_source("docs/page.md")
+//!     .with_timestamp(0);
+//! let comment = provenance.comment("# Page");
+//! assert!(comment.starts_with("<!-- generated by html-generator v"));
+//! assert!(comment.contains(" from docs/page.md at hash "));
+//! assert!(comment.ends_with(" on 1970-01-01T00:00:00Z -->"));
+//! ```
+
+use crate::build_info::CRATE_VERSION;
+use crate::utils::content_hash;
+use alloc::{format, string::String};
+
+/// Settings for the provenance comment.
+///
+/// The default is reproducible: no source path and no timestamp.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schema", schemars(deny_unknown_fields))]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
+pub struct Provenance {
+    /// Path of the Markdown source, as shown in the comment.
+    pub source: Option<String>,
+    /// Build time in seconds since the Unix epoch; `None` keeps the
+    /// output reproducible.
+    pub timestamp: Option<u64>,
+}
+
+impl Provenance {
+    /// Creates a setting stamped with the current time.
+    #[cfg(feature = "std")]
+    pub fn timestamped() -> Self {
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or_default();
+        Self::default().with_timestamp(timestamp)
+    }
+
+    /// Sets the source path shown in the comment.
+    #[must_use]
+    pub fn with_source(mut self, source: impl Into<String>) -> Self {
+        self.source = Some(source.into());
+        self
+    }
+
+    /// Sets the build time, in seconds since the Unix epoch.
+    #[must_use]
+    pub fn with_timestamp(mut self, timestamp: u64) -> Self {
+        self.timestamp = Some(timestamp);
+        self
+    }
+
+    /// Returns the provenance comment for a page converted from
+    /// `markdown`.
+    ///
+    /// Double hyphens in the source path are percent-encoded, so the
+    /// path cannot end the comment early.
+    pub fn comment(&self, markdown: &str) -> String {
+        let mut comment = format!(
+            "<!-- generated by html-generator v{}",
+            CRATE_VERSION
+        );
+        if let Some(source) = &self.source {
+            comment.push_str(" from ");
+            comment.push_str(&source.replace("--", "%2D%2D"));
+        }
+        comment.push_str(" at hash ");
+        comment.push_str(&content_hash(markdown.as_bytes()));
+        if let Some(timestamp) = self.timestamp {
+            comment.push_str(" on ");
+            comment.push_str(&format_timestamp(timestamp));
+        }
+        comment.push_str(" -->");
+        comment
+    }
+}
+
+/// Formats seconds since the Unix epoch as an RFC 3339 UTC time.
+fn format_timestamp(timestamp: u64) -> String {
+    let days = timestamp / 86_400;
+    let seconds = timestamp % 86_400;
+
+    // Civil date from days since 1970-01-01 (Howard Hinnant's
+    // algorithm), shifted to eras starting on 0000-03-01.
+    let days = days + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days % 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1_460
+        + day_of_era / 36_524
+        - day_of_era / 146_096)
+        / 365;
+    let day_of_year = day_of_era
+        - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        seconds / 3_600,
+        seconds % 3_600 / 60,
+        seconds % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conversion::convert;
+    use crate::HtmlConfig;
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(format_timestamp(0), "1970-01-01T00:00:00Z");
+        assert_eq!(
+            format_timestamp(951_782_400),
+            "2000-02-29T00:00:00Z"
+        );
+        assert_eq!(
+            format_timestamp(1_792_145_045),
+            "2026-10-16T10:04:05Z"
+        );
+    }
+
+    #[test]
+    fn test_comment_is_reproducible_and_safe() {
+        let provenance = Provenance::default().with_source("a-->b.md");
+        let comment = provenance.comment("# A");
+        assert_eq!(comment, provenance.comment("# A"));
+        assert_ne!(comment, provenance.comment("# B"));
+        assert!(comment.contains(" from a%2D%2D>b.md at hash "));
+        assert_eq!(comment.matches("-->").count(), 1);
+        assert!(!Provenance::default().comment("x").contains(" from "));
+    }
+
+    #[test]
+    fn test_convert_with_provenance() {
+        let config = HtmlConfig {
+            provenance: Some(Provenance::default()),
+            ..Default::default()
+        };
+        let html = convert("# Title", &config).unwrap();
+        assert!(html.starts_with("<!-- generated by html-generator v"));
+        assert!(html.contains("<h1>Title</h1>"));
+
+        let minified = HtmlConfig {
+            minify_output: true,
+            ..config
+        };
+        let html = convert("# Title", &minified).unwrap();
+        assert!(!html.contains("<!--"));
+        let html = convert("# Title", &HtmlConfig::default()).unwrap();
+        assert!(!html.contains("<!--"));
+    }
+}
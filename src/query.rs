@@ -0,0 +1,276 @@
+// Copyright © 2025 HTML Generator. All rights reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! A small, stable API for querying generated HTML.
+//!
+//! Post-processing output usually means selecting a few elements and
+//! reading their text or attributes. Doing that with the parser types
+//! used internally ties downstream crates to the exact parser version
+//! of this crate. [`Document`] and [`Element`] wrap those types and only
+//! expose strings, so the parser can be upgraded without breaking
+//! callers.
+//!
+//! # Examples
+//!
+//! ```
+//! use html_generator::query::Document;
+//!
+//! let document = Document::parse(
+//!     r#"<ul><li><a href="/a">First</a></li><li><a href="/b">Second</a></li></ul>"#,
+//! );
+//! let links: Vec<_> = document
+//!     .select("li a")?
+//!     .iter()
+//!     .map(|link| (link.text(), link.attr("href").unwrap_or_default()))
+//!     .collect();
+//! assert_eq!(links, [("First".to_string(), "/a"), ("Second".to_string(), "/b")]);
+//! # Ok::<(), html_generator::HtmlError>(())
+//! ```
+
+use crate::error::{HtmlError, Result};
+use crate::serializer::{
+    inner_html, is_document, outer_html, parse_html,
+};
+use scraper::{ElementRef, Html, Selector};
+
+/// Parses a CSS selector, reporting errors as
+/// [`HtmlError::SelectorParseError`].
+pub(crate) fn parse_selector(selector: &str) -> Result<Selector> {
+    Selector::parse(selector).map_err(|e| {
+        HtmlError::SelectorParseError(
+            selector.to_string(),
+            e.to_string(),
+        )
+    })
+}
+
+/// A parsed HTML document or fragment.
+#[derive(Debug, Clone)]
+pub struct Document {
+    html: Html,
+    is_document: bool,
+}
+
+impl Document {
+    /// Parses HTML.
+    ///
+    /// Input starting with a doctype or `<html>` is parsed as a full
+    /// document; anything else as a fragment, without adding `<html>`
+    /// or `<body>` elements to the output of [`Document::html`].
+    ///
+    /// # Arguments
+    ///
+    /// * `html` - The HTML to parse
+    pub fn parse(html: &str) -> Self {
+        Self {
+            html: parse_html(html),
+            is_document: is_document(html),
+        }
+    }
+
+    /// Returns the root element.
+    ///
+    /// For a fragment, this is a synthetic `<html>` element whose
+    /// children are the top-level nodes of the fragment.
+    pub fn root(&self) -> Element<'_> {
+        Element {
+            element: self.html.root_element(),
+        }
+    }
+
+    /// Returns all elements matching a CSS selector, in document order.
+    ///
+    /// # Arguments
+    ///
+    /// * `selector` - The CSS selector, e.g. `article > h2`
+    ///
+    /// # Errors
+    ///
+    /// Returns [`HtmlError::SelectorParseError`] if the selector is
+    /// invalid.
+    pub fn select(&self, selector: &str) -> Result<Vec<Element<'_>>> {
+        let selector = parse_selector(selector)?;
+        Ok(self.html.select(&selector).map(Element::new).collect())
+    }
+
+    /// Returns the first element matching a CSS selector, if any.
+    ///
+    /// # Arguments
+    ///
+    /// * `selector` - The CSS selector
+    ///
+    /// # Errors
+    ///
+    /// Returns [`HtmlError::SelectorParseError`] if the selector is
+    /// invalid.
+    pub fn select_first(
+        &self,
+        selector: &str,
+    ) -> Result<Option<Element<'_>>> {
+        let selector = parse_selector(selector)?;
+        Ok(self.html.select(&selector).next().map(Element::new))
+    }
+
+    /// Returns the text content of the document.
+    pub fn text(&self) -> String {
+        self.root().text()
+    }
+
+    /// Serializes a document from its `<html>` element, or the content
+    /// of a fragment.
+    pub fn html(&self) -> String {
+        if self.is_document {
+            outer_html(&self.html.root_element())
+        } else {
+            inner_html(&self.html.root_element())
+        }
+    }
+}
+
+/// An element of a [`Document`].
+#[derive(Debug, Clone, Copy)]
+pub struct Element<'a> {
+    element: ElementRef<'a>,
+}
+
+impl<'a> Element<'a> {
+    fn new(element: ElementRef<'a>) -> Self {
+        Self { element }
+    }
+
+    /// Returns the lowercase tag name, e.g. `a`.
+    pub fn name(&self) -> &'a str {
+        self.element.value().name()
+    }
+
+    /// Returns the value of an attribute, if present.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The attribute name
+    pub fn attr(&self, name: &str) -> Option<&'a str> {
+        self.element.value().attr(name)
+    }
+
+    /// Returns all attributes as `(name, value)` pairs.
+    pub fn attrs(&self) -> impl Iterator<Item = (&'a str, &'a str)> {
+        self.element.value().attrs()
+    }
+
+    /// Returns `true` if the element has the given class.
+    ///
+    /// # Arguments
+    ///
+    /// * `class` - The class name, compared case-sensitively
+    pub fn has_class(&self, class: &str) -> bool {
+        self.element.value().classes().any(|name| name == class)
+    }
+
+    /// Returns the concatenated text of the element and its
+    /// descendants.
+    pub fn text(&self) -> String {
+        self.element.text().collect()
+    }
+
+    /// Serializes the content of the element.
+    pub fn inner_html(&self) -> String {
+        inner_html(&self.element)
+    }
+
+    /// Serializes the element, including its own tags.
+    pub fn outer_html(&self) -> String {
+        outer_html(&self.element)
+    }
+
+    /// Returns the child elements, skipping text and comments.
+    pub fn children(&self) -> impl Iterator<Item = Element<'a>> {
+        self.element
+            .children()
+            .filter_map(ElementRef::wrap)
+            .map(Element::new)
+    }
+
+    /// Returns the parent element, if any.
+    pub fn parent(&self) -> Option<Element<'a>> {
+        self.element
+            .parent()
+            .and_then(ElementRef::wrap)
+            .map(Element::new)
+    }
+
+    /// Returns the descendants matching a CSS selector, in document
+    /// order.
+    ///
+    /// # Arguments
+    ///
+    /// * `selector` - The CSS selector
+    ///
+    /// # Errors
+    ///
+    /// Returns [`HtmlError::SelectorParseError`] if the selector is
+    /// invalid.
+    pub fn select(&self, selector: &str) -> Result<Vec<Element<'a>>> {
+        let selector = parse_selector(selector)?;
+        Ok(self.element.select(&selector).map(Element::new).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = concat!(
+        "<main><h1 class=\"title big\" id=\"top\">Title</h1>",
+        "<p>One <em>two</em></p><!-- note --><p data-x=\"1\">Three</p></main>"
+    );
+
+    #[test]
+    fn test_select_and_read() {
+        let document = Document::parse(PAGE);
+        let paragraphs = document.select("main > p").unwrap();
+        assert_eq!(paragraphs.len(), 2);
+        assert_eq!(paragraphs[0].text(), "One two");
+        assert_eq!(paragraphs[0].inner_html(), "One <em>two</em>");
+        assert_eq!(paragraphs[1].attr("data-x"), Some("1"));
+        assert_eq!(
+            paragraphs[1].attrs().collect::<Vec<_>>(),
+            [("data-x", "1")]
+        );
+
+        let heading = document.select_first("h1").unwrap().unwrap();
+        assert_eq!(heading.name(), "h1");
+        assert!(heading.has_class("big"));
+        assert!(!heading.has_class("Big"));
+        assert_eq!(heading.parent().unwrap().name(), "main");
+        assert!(document.select_first("table").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_navigation_and_scoped_selection() {
+        let document = Document::parse(PAGE);
+        let main = document.select_first("main").unwrap().unwrap();
+        let names: Vec<_> =
+            main.children().map(|child| child.name()).collect();
+        assert_eq!(names, ["h1", "p", "p"]);
+        assert_eq!(main.select("em").unwrap()[0].text(), "two");
+        assert_eq!(document.text(), "TitleOne twoThree");
+        assert_eq!(document.html(), PAGE);
+    }
+
+    #[test]
+    fn test_documents_and_errors() {
+        let html =
+            "<!DOCTYPE html><html><head></head><body><p>x</p></body></html>";
+        let document = Document::parse(html);
+        assert_eq!(document.root().name(), "html");
+        assert_eq!(
+            document.html(),
+            "<html><head></head><body><p>x</p></body></html>"
+        );
+        assert_eq!(document.select("body p").unwrap().len(), 1);
+        assert!(matches!(
+            document.select("p[["),
+            Err(HtmlError::SelectorParseError(_, _))
+        ));
+    }
+}
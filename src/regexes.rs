@@ -0,0 +1,154 @@
+// Copyright © 2025 HTML Generator. All rights reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Central construction of regular expressions.
+//!
+//! The `regex` crate matches in linear time, so crafted input cannot
+//! trigger catastrophic backtracking. Compiled programs and the lazy
+//! DFA can still grow large, especially for patterns built at runtime.
+//! Every regex in the crate is therefore built through this module,
+//! which applies the same size, DFA and nesting limits everywhere.
+//! Values interpolated into runtime patterns must be passed through
+//! [`regex::escape`].
+
+use crate::error::Result;
+use regex::{Regex, RegexBuilder};
+
+/// Maximum size of a compiled regex program in bytes.
+pub(crate) const REGEX_SIZE_LIMIT: usize = 1024 * 1024;
+
+/// Maximum size of the lazy DFA cache per regex in bytes.
+pub(crate) const DFA_SIZE_LIMIT: usize = 2 * 1024 * 1024;
+
+/// Maximum nesting depth of groups and repetitions in a pattern.
+pub(crate) const NEST_LIMIT: u32 = 32;
+
+/// Builds a regex with the crate-wide limits applied.
+///
+/// # Arguments
+///
+/// * `pattern` - The regex pattern
+///
+/// # Errors
+///
+/// Returns [`HtmlError::RegexCompilationError`](crate::error::HtmlError::RegexCompilationError)
+/// if the pattern is invalid or exceeds a limit.
+pub(crate) fn build_regex(pattern: &str) -> Result<Regex> {
+    Ok(RegexBuilder::new(pattern)
+        .size_limit(REGEX_SIZE_LIMIT)
+        .dfa_size_limit(DFA_SIZE_LIMIT)
+        .nest_limit(NEST_LIMIT)
+        .build()?)
+}
+
+/// Builds a regex from a pattern known at compile time.
+///
+/// Intended for `Lazy` statics.
+///
+/// # Panics
+///
+/// Panics with `name` in the message if the pattern does not compile.
+pub(crate) fn static_regex(name: &str, pattern: &str) -> Regex {
+    build_regex(pattern)
+        .unwrap_or_else(|e| panic!("Failed to compile {}: {}", name, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    /// Generous bound for the worst-case inputs below; quadratic or
+    /// exponential behaviour takes orders of magnitude longer.
+    const BUDGET: Duration = Duration::from_secs(10);
+
+    fn assert_fast<T>(name: &str, f: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let result = f();
+        assert!(
+            start.elapsed() < BUDGET,
+            "{} took {:?}",
+            name,
+            start.elapsed()
+        );
+        result
+    }
+
+    #[test]
+    fn test_build_regex_applies_limits() {
+        assert!(build_regex(r"^[a-z]+$").is_ok());
+        assert!(build_regex(&"(".repeat(64).to_string()).is_err());
+        assert!(build_regex(&format!(
+            "{}a{}",
+            "(?:".repeat(40),
+            ")".repeat(40)
+        ))
+        .is_err());
+        assert!(build_regex(r"\w{1000}\w{1000}\w{1000}").is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "Failed to compile BROKEN")]
+    fn test_static_regex_panics_with_name() {
+        let _ = static_regex("BROKEN", "(");
+    }
+
+    mod worst_case_tests {
+        use super::*;
+        use crate::accessibility::add_aria_attributes;
+        use crate::utils::extract_front_matter;
+        use crate::{markdown_to_html, MarkdownConfig};
+
+        #[test]
+        fn test_unterminated_front_matter() {
+            let input =
+                format!("---\n{}", "key: value\n".repeat(50_000));
+            let _ = assert_fast("front matter", || {
+                extract_front_matter(&input)
+            });
+        }
+
+        #[test]
+        fn test_unclosed_image_classes() {
+            let input = "![a](b).class=\"".repeat(20_000);
+            let _ = assert_fast("image classes", || {
+                markdown_to_html(
+                    &input,
+                    Some(MarkdownConfig::default()),
+                )
+            });
+        }
+
+        #[test]
+        fn test_unclosed_custom_blocks() {
+            let input = ":::note\n".repeat(2_000);
+            let _ = assert_fast("custom blocks", || {
+                markdown_to_html(
+                    &input,
+                    Some(MarkdownConfig::default()),
+                )
+            });
+        }
+
+        #[test]
+        fn test_attribute_heavy_inputs() {
+            let input = format!(
+                "<input {}><button disabled{}>",
+                "a= ".repeat(20_000),
+                " x".repeat(20_000)
+            );
+            let _ = assert_fast("attributes", || {
+                add_aria_attributes(&input, None)
+            });
+        }
+
+        #[test]
+        fn test_label_lookup_escapes_ids() {
+            let html = r#"<label for="a(b">A</label><input id="a(b" type="text">"#;
+            let result = assert_fast("label lookup", || {
+                add_aria_attributes(html, None)
+            });
+            assert!(result.is_ok());
+        }
+    }
+}
@@ -0,0 +1,403 @@
+// Copyright © 2025 HTML Generator. All rights reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Repair of malformed input HTML.
+//!
+//! Legacy content often relies on the error recovery of browsers:
+//! unclosed or misnested elements, stray end tags, uppercase names and
+//! unquoted or duplicated attributes. [`repair_html`] runs such input
+//! through the HTML5 parser and re-serializes it with balanced tags and
+//! normalized attributes, so that later passes such as validation and
+//! ARIA enhancement see the same tree a browser would. Each fix is
+//! reported as an [`HtmlRepair`].
+//!
+//! # Examples
+//!
+//! ```
+//! use html_generator::repair::{repair_html, RepairKind};
+//!
+//! let (html, repairs) =
+//!     repair_html("<DIV class=note><b>Hi</div></span>").unwrap();
+//! assert_eq!(html, r#"<div class="note"><b>Hi</b></div>"#);
+//! assert_eq!(repairs[0].kind, RepairKind::UppercaseName);
+//! assert_eq!(repairs[1].kind, RepairKind::UnquotedAttribute);
+//! assert_eq!(repairs[2].kind, RepairKind::MisnestedElement);
+//! assert_eq!(repairs[3].kind, RepairKind::StrayEndTag);
+//! ```
+
+use crate::constants::DEFAULT_MAX_INPUT_SIZE;
+use crate::diagnostics::Diagnostic;
+use crate::error::{HtmlError, Result};
+use crate::patterns::repair::{ATTRIBUTE_REGEX, TAG_REGEX};
+use crate::serializer::{
+    encode_entities, EntityEncoding, RAW_TEXT_ELEMENTS, VOID_ELEMENTS,
+};
+use std::fmt;
+
+/// Elements whose content is text up to their end tag.
+const TEXT_ONLY_ELEMENTS: &[&str] = &["textarea", "title"];
+
+/// Elements whose end tag may be omitted.
+const OPTIONAL_END_TAG_ELEMENTS: &[&str] = &[
+    "body", "caption", "colgroup", "dd", "dt", "head", "html", "li",
+    "optgroup", "option", "p", "rb", "rp", "rt", "rtc", "tbody", "td",
+    "tfoot", "th", "thead", "tr",
+];
+
+/// The kind of fix made by [`repair_html`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum RepairKind {
+    /// An element was still open at the end of the input.
+    UnclosedElement,
+    /// An element was closed implicitly by the end tag of an ancestor.
+    MisnestedElement,
+    /// An end tag without matching open element was dropped.
+    StrayEndTag,
+    /// A repeated attribute was dropped; the first value is kept.
+    DuplicateAttribute,
+    /// An attribute value was quoted.
+    UnquotedAttribute,
+    /// An element or attribute name was lowercased.
+    UppercaseName,
+}
+
+impl RepairKind {
+    /// Returns the diagnostic code of the repair, e.g.
+    /// `unclosed_element`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            RepairKind::UnclosedElement => "unclosed_element",
+            RepairKind::MisnestedElement => "misnested_element",
+            RepairKind::StrayEndTag => "stray_end_tag",
+            RepairKind::DuplicateAttribute => "duplicate_attribute",
+            RepairKind::UnquotedAttribute => "unquoted_attribute",
+            RepairKind::UppercaseName => "uppercase_name",
+        }
+    }
+}
+
+/// A fix made by [`repair_html`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HtmlRepair {
+    /// What was fixed.
+    pub kind: RepairKind,
+    /// The element or attribute name, as written in the input.
+    pub name: String,
+    /// Byte offset of the tag or attribute in the input.
+    pub offset: usize,
+    /// One-based line number.
+    pub line: usize,
+    /// One-based column, counted in characters.
+    pub column: usize,
+}
+
+impl HtmlRepair {
+    fn new(
+        html: &str,
+        kind: RepairKind,
+        name: &str,
+        offset: usize,
+    ) -> Self {
+        let before = &html[..offset];
+        let line_start =
+            before.rfind('\n').map_or(0, |index| index + 1);
+        Self {
+            kind,
+            name: name.to_string(),
+            offset,
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+        }
+    }
+
+    /// Converts the repair into an informational [`Diagnostic`] whose
+    /// code is that of its [`RepairKind`].
+    pub fn to_diagnostic(&self) -> Diagnostic {
+        Diagnostic::info(self.kind.code(), self.to_string())
+    }
+}
+
+impl fmt::Display for HtmlRepair {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            RepairKind::UnclosedElement => {
+                write!(f, "Closed unclosed <{}>", self.name)?
+            }
+            RepairKind::MisnestedElement => {
+                write!(f, "Closed misnested <{}>", self.name)?
+            }
+            RepairKind::StrayEndTag => {
+                write!(f, "Dropped stray </{}>", self.name)?
+            }
+            RepairKind::DuplicateAttribute => write!(
+                f,
+                "Dropped duplicate attribute '{}'",
+                self.name
+            )?,
+            RepairKind::UnquotedAttribute => {
+                write!(f, "Quoted the value of '{}'", self.name)?
+            }
+            RepairKind::UppercaseName => {
+                write!(f, "Lowercased '{}'", self.name)?
+            }
+        }
+        write!(f, " at line {}, column {}", self.line, self.column)
+    }
+}
+
+/// Repairs malformed HTML the way a browser would.
+///
+/// The input is parsed with the HTML5 parser and serialized again, so
+/// every element is closed and nested correctly, names are lowercase
+/// and attribute values are quoted. Input starting with a doctype or
+/// `<html>` is treated as a full document; anything else as a fragment.
+///
+/// The repairs are found by a separate scan of the input tags. It
+/// covers the common cases listed in [`RepairKind`]; rarer fixes made
+/// by the parser, such as moving content out of a table, are applied
+/// but not reported.
+///
+/// # Arguments
+///
+/// * `html` - The HTML to repair
+///
+/// # Returns
+///
+/// The repaired HTML and one [`HtmlRepair`] per fix, in input order.
+///
+/// # Errors
+///
+/// Returns [`HtmlError::InputTooLarge`] if the input exceeds
+/// [`DEFAULT_MAX_INPUT_SIZE`].
+pub fn repair_html(html: &str) -> Result<(String, Vec<HtmlRepair>)> {
+    if html.len() > DEFAULT_MAX_INPUT_SIZE {
+        return Err(HtmlError::InputTooLarge(html.len()));
+    }
+    let repairs = find_repairs(html);
+    let repaired = encode_entities(html, &EntityEncoding::default());
+    Ok((repaired, repairs))
+}
+
+/// Scans the tags of `html` for the fixes the parser will make.
+fn find_repairs(html: &str) -> Vec<HtmlRepair> {
+    let mut repairs = Vec::new();
+    let mut open: Vec<(String, usize)> = Vec::new();
+    let mut position = 0;
+
+    while let Some(caps) = TAG_REGEX.captures_at(html, position) {
+        let (start, mut end) = match caps.get(0) {
+            Some(tag) => (tag.start(), tag.end()),
+            None => break,
+        };
+        position = end;
+        let raw_name = match caps.get(2) {
+            Some(name) => name,
+            None => continue,
+        };
+        let name = raw_name.as_str().to_ascii_lowercase();
+        if raw_name.as_str() != name {
+            repairs.push(HtmlRepair::new(
+                html,
+                RepairKind::UppercaseName,
+                raw_name.as_str(),
+                raw_name.start(),
+            ));
+        }
+
+        if !caps[1].is_empty() {
+            match open.iter().rposition(|(open, _)| *open == name) {
+                Some(index) => {
+                    for (inner, offset) in open.drain(index..).skip(1) {
+                        if !OPTIONAL_END_TAG_ELEMENTS
+                            .contains(&inner.as_str())
+                        {
+                            repairs.push(HtmlRepair::new(
+                                html,
+                                RepairKind::MisnestedElement,
+                                &inner,
+                                offset,
+                            ));
+                        }
+                    }
+                }
+                None => repairs.push(HtmlRepair::new(
+                    html,
+                    RepairKind::StrayEndTag,
+                    &name,
+                    start,
+                )),
+            }
+            continue;
+        }
+
+        if let Some(attributes) = caps.get(3) {
+            check_attributes(
+                html,
+                attributes.as_str(),
+                attributes.start(),
+                &mut repairs,
+            );
+        }
+        if VOID_ELEMENTS.contains(&name.as_str()) {
+            continue;
+        }
+        if RAW_TEXT_ELEMENTS.contains(&name.as_str())
+            || TEXT_ONLY_ELEMENTS.contains(&name.as_str())
+        {
+            end = html[end..]
+                .to_ascii_lowercase()
+                .find(&format!("</{}", name))
+                .map_or(html.len(), |index| end + index);
+            position = end;
+        }
+        open.push((name, start));
+    }
+
+    for (name, offset) in open {
+        if !OPTIONAL_END_TAG_ELEMENTS.contains(&name.as_str()) {
+            repairs.push(HtmlRepair::new(
+                html,
+                RepairKind::UnclosedElement,
+                &name,
+                offset,
+            ));
+        }
+    }
+    repairs.sort_by_key(|repair| repair.offset);
+    repairs
+}
+
+/// Checks the attributes of a start tag, found at `offset` in `html`.
+fn check_attributes(
+    html: &str,
+    attributes: &str,
+    offset: usize,
+    repairs: &mut Vec<HtmlRepair>,
+) {
+    let mut seen: Vec<String> = Vec::new();
+    for caps in ATTRIBUTE_REGEX.captures_iter(attributes) {
+        let raw_name = match caps.get(1) {
+            Some(name) => name,
+            None => continue,
+        };
+        let name = raw_name.as_str().to_ascii_lowercase();
+        let at = offset + raw_name.start();
+        if seen.contains(&name) {
+            repairs.push(HtmlRepair::new(
+                html,
+                RepairKind::DuplicateAttribute,
+                raw_name.as_str(),
+                at,
+            ));
+            continue;
+        }
+        if raw_name.as_str() != name {
+            repairs.push(HtmlRepair::new(
+                html,
+                RepairKind::UppercaseName,
+                raw_name.as_str(),
+                at,
+            ));
+        }
+        let unquoted = caps.get(2).map_or(false, |value| {
+            !value.as_str().starts_with(['"', '\''])
+        });
+        if unquoted {
+            repairs.push(HtmlRepair::new(
+                html,
+                RepairKind::UnquotedAttribute,
+                raw_name.as_str(),
+                at,
+            ));
+        }
+        seen.push(name);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(html: &str) -> Vec<(RepairKind, String)> {
+        let (_, repairs) = repair_html(html).unwrap();
+        repairs
+            .into_iter()
+            .map(|repair| (repair.kind, repair.name))
+            .collect()
+    }
+
+    #[test]
+    fn test_balances_tags() {
+        let (html, repairs) =
+            repair_html("<p><b>bold <i>both</b> end</p><div>open")
+                .unwrap();
+        assert_eq!(
+            html,
+            "<p><b>bold <i>both</i></b><i> end</i></p><div><i>open</i></div>"
+        );
+        assert_eq!(
+            kinds("<p><b>bold <i>both</b> end</p><div>open"),
+            vec![
+                (RepairKind::MisnestedElement, "i".to_string()),
+                (RepairKind::UnclosedElement, "div".to_string()),
+            ]
+        );
+        assert_eq!(repairs[1].line, 1);
+        assert_eq!(repairs[1].column, 31);
+    }
+
+    #[test]
+    fn test_normalizes_attributes() {
+        let (html, _) = repair_html(
+            "<img SRC=a.png alt=\"x\" alt='y' data-x=1>\n<br/>",
+        )
+        .unwrap();
+        assert_eq!(
+            html,
+            "<img alt=\"x\" data-x=\"1\" src=\"a.png\">\n<br>"
+        );
+        assert_eq!(
+            kinds("<img SRC=a.png alt=\"x\" alt='y' data-x=1>"),
+            vec![
+                (RepairKind::UppercaseName, "SRC".to_string()),
+                (RepairKind::UnquotedAttribute, "SRC".to_string()),
+                (RepairKind::DuplicateAttribute, "alt".to_string()),
+                (RepairKind::UnquotedAttribute, "data-x".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_valid_html_needs_no_repairs() {
+        let html = concat!(
+            "<ul><li>one<li>two</ul><p>para",
+            "<script>if (a < b) { x = '</div>'; }</script>",
+            "<!-- <b> --><input disabled>"
+        );
+        assert!(kinds(html).is_empty());
+        assert!(kinds("<p>a</p></div>")
+            .contains(&(RepairKind::StrayEndTag, "div".to_string())));
+    }
+
+    #[test]
+    fn test_repairs_documents_and_reports_diagnostics() {
+        let (html, repairs) = repair_html(
+            "<!DOCTYPE html><HTML><body><main>text</body></html>",
+        )
+        .unwrap();
+        assert_eq!(
+            html,
+            "<!DOCTYPE html><html><head></head><body><main>text</main></body></html>"
+        );
+        let diagnostic = repairs[1].to_diagnostic();
+        assert_eq!(diagnostic.code, "misnested_element");
+        assert_eq!(
+            diagnostic.message,
+            "Closed misnested <main> at line 1, column 28"
+        );
+        assert!(matches!(
+            repair_html(&"a".repeat(DEFAULT_MAX_INPUT_SIZE + 1)),
+            Err(HtmlError::InputTooLarge(_))
+        ));
+    }
+}
@@ -0,0 +1,524 @@
+// Copyright © 2025 HTML Generator. All rights reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Removal of script URLs.
+//!
+//! Markdown allows raw HTML and arbitrary link targets, so a document
+//! written by an untrusted user can run scripts through links such as
+//! `[click](javascript:alert(1))`. With
+//! [`HtmlConfig::url_scheme_policy`](crate::HtmlConfig::url_scheme_policy)
+//! set, the generated HTML is parsed as browsers parse it, and the URLs
+//! of `href`, `src`, `srcset`, `action`, `formaction`, `poster`,
+//! `xlink:href`, `<object data>` and `<meta http-equiv="refresh">`
+//! attributes, and the `values`, `to` and `from` of SVG `<animate>` and
+//! `<set>` elements changing an `href`, using the `javascript:` or
+//! `vbscript:` scheme are removed
+//! or rejected. `data:` URLs are removed too, except raster images
+//! (`data:image/png`, `gif`, `jpeg`, `webp` or `avif`) in the `src`,
+//! `srcset` and `poster` of images, sources and videos.
+//!
+//! Browsers ignore case, whitespace, control characters and character
+//! references in a scheme, and accept `/` between attributes, so
+//! `JaVa&#x53;cript:`, `java\tscript:` and `<a/href="javascript:…">` are
+//! caught as well. Every removed URL is reported as a [`RemovedUrl`].
+//!
+//! The policy cannot be changed by
+//! [comment directives](crate::directives), since those are part of the
+//! untrusted document.
+//!
+//! # Examples
+//!
+//! ```
+//! use html_generator::sanitize::sanitize_urls;
+//!
+//! let (html, removed) = sanitize_urls(
+//!     r#"<a href="JavaScript:alert(1)">x</a><img src="data:image/png;base64,AA">"#,
+//! );
+//! assert_eq!(html, r#"<a>x</a><img src="data:image/png;base64,AA">"#);
+//! assert_eq!(removed[0].url, "JavaScript:alert(1)");
+//! ```
+
+use crate::diagnostics::Diagnostic;
+use crate::error::{HtmlError, Result};
+use scraper::node::Element;
+use scraper::{Html, Node};
+use std::{fmt, str::FromStr};
+
+/// Raster image types allowed in `data:` URLs of image attributes.
+const DATA_IMAGE_TYPES: &[&str] = &[
+    "data:image/png",
+    "data:image/gif",
+    "data:image/jpeg",
+    "data:image/webp",
+    "data:image/avif",
+];
+
+/// Elements showing the images of `data:` URLs in `src`, `srcset` or
+/// `poster`.
+const DATA_IMAGE_ELEMENTS: &[&str] =
+    &["img", "input", "source", "video"];
+
+/// What happens to script URLs in the generated HTML.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum UrlSchemePolicy {
+    /// Leave every URL alone.
+    Allow,
+    /// Remove the attributes holding script URLs, and the unsafe
+    /// candidates of `srcset`.
+    Strip,
+    /// Fail the conversion if the HTML holds a script URL.
+    Reject,
+}
+
+impl Default for UrlSchemePolicy {
+    fn default() -> Self {
+        UrlSchemePolicy::Allow
+    }
+}
+
+impl fmt::Display for UrlSchemePolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            UrlSchemePolicy::Allow => "allow",
+            UrlSchemePolicy::Strip => "strip",
+            UrlSchemePolicy::Reject => "reject",
+        })
+    }
+}
+
+impl FromStr for UrlSchemePolicy {
+    type Err = HtmlError;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value {
+            "allow" => Ok(UrlSchemePolicy::Allow),
+            "strip" => Ok(UrlSchemePolicy::Strip),
+            "reject" => Ok(UrlSchemePolicy::Reject),
+            _ => Err(HtmlError::InvalidInput(format!(
+                "Unknown URL scheme policy: {}",
+                value
+            ))),
+        }
+    }
+}
+
+/// A script URL found by [`sanitize_urls`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemovedUrl {
+    /// The lowercase name of the element, e.g. `a`.
+    pub element: String,
+    /// The lowercase name of the attribute, e.g. `href`.
+    pub attribute: String,
+    /// The URL as written in the HTML.
+    pub url: String,
+}
+
+impl RemovedUrl {
+    /// Converts the finding into a warning [`Diagnostic`] with the code
+    /// `unsafe_url`.
+    pub fn to_diagnostic(&self) -> Diagnostic {
+        Diagnostic::warning("unsafe_url", self.to_string())
+    }
+}
+
+impl fmt::Display for RemovedUrl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Removed unsafe URL '{}' from the {} attribute of <{}>",
+            self.url, self.attribute, self.element
+        )
+    }
+}
+
+/// Removes script URLs from `html`.
+///
+/// The HTML is parsed as browsers parse it, so attributes separated by
+/// `/` instead of whitespace, unquoted values and character references
+/// are all seen. Attributes holding a script URL are removed. In
+/// `srcset`, only the unsafe candidates are removed, and the attribute
+/// only if none is left. The HTML is returned unchanged if it holds no
+/// script URL, and serialized from the parsed tree otherwise, which
+/// writes the attributes of each element in alphabetical order.
+///
+/// # Arguments
+///
+/// * `html` - The HTML fragment to sanitize
+///
+/// # Returns
+///
+/// The sanitized HTML and the removed URLs, in document order.
+pub fn sanitize_urls(html: &str) -> (String, Vec<RemovedUrl>) {
+    let mut fragment = Html::parse_fragment(html);
+    let mut removed = Vec::new();
+    for node in fragment.tree.values_mut() {
+        if let Node::Element(element) = node {
+            sanitize_element(element, &mut removed);
+        }
+    }
+    if removed.is_empty() {
+        return (html.to_string(), removed);
+    }
+    (fragment.root_element().inner_html(), removed)
+}
+
+/// Removes the script URLs of the attributes of `element`, reporting
+/// them to `removed`.
+fn sanitize_element(
+    element: &mut Element,
+    removed: &mut Vec<RemovedUrl>,
+) {
+    let name = element.name().to_string();
+    let refresh = name == "meta"
+        && element.attr("http-equiv").map_or(false, |value| {
+            value.trim().eq_ignore_ascii_case("refresh")
+        });
+    // `<animate>` and `<set>` can give the link around them any URL
+    let animates_link = matches!(name.as_str(), "animate" | "set")
+        && element.attr("attributeName").map_or(false, |target| {
+            matches!(target.trim(), "href" | "xlink:href")
+        });
+    let attributes = std::mem::take(&mut element.attrs);
+    element.attrs = attributes
+        .into_iter()
+        .filter_map(|(attribute, mut value)| {
+            let local = attribute.local.to_string();
+            let mut report = |url: &str| {
+                removed.push(RemovedUrl {
+                    element: name.clone(),
+                    attribute: match &attribute.prefix {
+                        Some(prefix) => format!("{}:{}", prefix, local),
+                        None => local.clone(),
+                    },
+                    url: url.to_string(),
+                });
+            };
+            let url = match local.as_str() {
+                "href" | "src" | "action" | "formaction" | "poster" => {
+                    Some(&*value)
+                }
+                "data" if name == "object" => Some(&*value),
+                "content" if refresh => refresh_url(&value),
+                "to" | "from" if animates_link => Some(&*value),
+                "values" if animates_link => value
+                    .split(';')
+                    .find(|url| is_unsafe(&name, &local, url)),
+                "srcset" => {
+                    let candidates = srcset_candidates(&value);
+                    let kept: Vec<&str> = candidates
+                        .iter()
+                        .filter(|(url, _)| {
+                            let unsafe_url =
+                                is_unsafe(&name, &local, url);
+                            if unsafe_url {
+                                report(url);
+                            }
+                            !unsafe_url
+                        })
+                        .map(|(_, candidate)| *candidate)
+                        .collect();
+                    if kept.is_empty() && !candidates.is_empty() {
+                        return None;
+                    }
+                    if kept.len() < candidates.len() {
+                        let kept = kept.join(", ");
+                        value.clear();
+                        value.push_slice(&kept);
+                    }
+                    None
+                }
+                _ => None,
+            };
+            match url {
+                Some(url) if is_unsafe(&name, &local, url) => {
+                    report(url);
+                    None
+                }
+                _ => Some((attribute, value)),
+            }
+        })
+        .collect();
+}
+
+/// Returns the URL of the `content` of a `<meta http-equiv="refresh">`,
+/// e.g. `0; url='/next'`.
+fn refresh_url(content: &str) -> Option<&str> {
+    let (_, url) = content.split_once(|c| c == ';' || c == ',')?;
+    let url = url.trim_start();
+    let url = match url.get(..3) {
+        Some(prefix) if prefix.eq_ignore_ascii_case("url") => url[3..]
+            .trim_start()
+            .strip_prefix('=')
+            .map_or(url, str::trim_start),
+        _ => url,
+    };
+    Some(url.trim_matches(|c| c == '\'' || c == '"'))
+}
+
+/// Splits a `srcset` into its candidates, as URLs and the candidates
+/// with their descriptors.
+///
+/// URLs end at whitespace, not at commas, as in the HTML specification,
+/// so `data:` URLs holding commas are kept whole.
+fn srcset_candidates(srcset: &str) -> Vec<(&str, &str)> {
+    let mut candidates = Vec::new();
+    let mut rest = srcset;
+    loop {
+        rest = rest.trim_start_matches(|c: char| {
+            c.is_ascii_whitespace() || c == ','
+        });
+        if rest.is_empty() {
+            return candidates;
+        }
+        let url_end = rest
+            .find(|c: char| c.is_ascii_whitespace())
+            .unwrap_or(rest.len());
+        let url = &rest[..url_end];
+        if url.ends_with(',') {
+            candidates.push((
+                url.trim_end_matches(','),
+                url.trim_end_matches(','),
+            ));
+            rest = &rest[url_end..];
+            continue;
+        }
+        let mut depth = 0;
+        let end = rest[url_end..]
+            .find(|c: char| {
+                match c {
+                    '(' => depth += 1,
+                    ')' => depth -= 1,
+                    ',' => return depth <= 0,
+                    _ => {}
+                }
+                false
+            })
+            .map_or(rest.len(), |index| url_end + index);
+        candidates.push((url, rest[..end].trim_end()));
+        rest = &rest[end..];
+    }
+}
+
+/// Applies `policy` to `html`.
+///
+/// # Errors
+///
+/// Returns [`HtmlError::ValidationError`] naming the first script URL
+/// if `policy` is [`UrlSchemePolicy::Reject`].
+pub fn apply_url_scheme_policy(
+    html: String,
+    policy: UrlSchemePolicy,
+) -> Result<(String, Vec<RemovedUrl>)> {
+    if policy == UrlSchemePolicy::Allow {
+        return Ok((html, Vec::new()));
+    }
+    let (sanitized, removed) = sanitize_urls(&html);
+    match (policy, removed.first()) {
+        (UrlSchemePolicy::Reject, Some(first)) => {
+            Err(HtmlError::ValidationError(format!(
+                "Unsafe URL '{}' in the {} attribute of <{}>",
+                first.url, first.attribute, first.element
+            )))
+        }
+        _ => Ok((sanitized, removed)),
+    }
+}
+
+/// Returns `true` if `url` uses a scheme that is unsafe in `attribute`
+/// of `element`.
+fn is_unsafe(element: &str, attribute: &str, url: &str) -> bool {
+    let url: String = url
+        .chars()
+        .filter(|c| !c.is_ascii_whitespace() && !c.is_control())
+        .collect::<String>()
+        .to_ascii_lowercase();
+    let scheme = match url.find(|c| matches!(c, ':' | '/' | '?' | '#'))
+    {
+        Some(end) if url[end..].starts_with(':') => &url[..end],
+        _ => return false,
+    };
+    match scheme {
+        "javascript" | "vbscript" => true,
+        "data" => {
+            !DATA_IMAGE_ELEMENTS.contains(&element)
+                || !matches!(attribute, "src" | "srcset" | "poster")
+                || !DATA_IMAGE_TYPES.iter().any(|prefix| {
+                    url.starts_with(prefix)
+                        && url[prefix.len()..]
+                            .starts_with(|c| c == ';' || c == ',')
+                })
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conversion::convert_detailed;
+    use crate::HtmlConfig;
+
+    #[test]
+    fn test_obfuscated_schemes_are_removed() {
+        for href in [
+            "javascript:alert(1)",
+            " JAVASCRIPT:alert(1)",
+            "java\tscript:alert(1)",
+            "jav&#x61;script:alert(1)",
+            "javascript&colon;alert(1)",
+            "&#106;avascript:alert(1)",
+            "vbscript:msgbox",
+            "data:text/html,<script>",
+        ] {
+            let html =
+                format!("<a class=\"x\" href=\"{}\">y</a>", href);
+            let (sanitized, removed) = sanitize_urls(&html);
+            assert_eq!(sanitized, r#"<a class="x">y</a>"#, "{}", href);
+            assert_eq!(removed.len(), 1);
+        }
+        for href in [
+            "https://example.com/javascript:x",
+            "/path?q=javascript:x",
+            "#data:x",
+            "mailto:a@example.com",
+            "guide.md",
+        ] {
+            let html = format!("<a href=\"{}\">y</a>", href);
+            assert_eq!(sanitize_urls(&html).0, html);
+        }
+        for (html, sanitized) in [
+            (r#"<a/href="javascript:alert(1)">x</a>"#, "<a>x</a>"),
+            (
+                r#"<a title="t"/href="javascript:alert(1)">x</a>"#,
+                r#"<a title="t">x</a>"#,
+            ),
+            (
+                r#"<object data="data:text/html,<script>alert(1)</script>"></object>"#,
+                "<object></object>",
+            ),
+            (
+                r#"<object data="javascript:alert(1)"></object>"#,
+                "<object></object>",
+            ),
+            (r#"<embed src="data:image/png;base64,AA">"#, "<embed>"),
+            (
+                r#"<svg><a><animate attributeName="href" values="javascript:alert(1)"/></a></svg>"#,
+                r#"<svg><a><animate attributeName="href"></animate></a></svg>"#,
+            ),
+            (
+                r##"<svg><a><animate attributeName="xlink:href" values="#a; JavaScript:alert(1)"/></a></svg>"##,
+                r#"<svg><a><animate attributeName="xlink:href"></animate></a></svg>"#,
+            ),
+            (
+                r#"<svg><a><set attributeName="href" to="javascript:alert(1)"/></a></svg>"#,
+                r#"<svg><a><set attributeName="href"></set></a></svg>"#,
+            ),
+            (
+                r##"<svg><a><animate attributeName=" href " from="data:text/html,x" to="#b"/></a></svg>"##,
+                r##"<svg><a><animate attributeName=" href " to="#b"></animate></a></svg>"##,
+            ),
+            (
+                r#"<meta http-equiv="refresh" content="0;url=javascript:alert(1)">"#,
+                r#"<meta http-equiv="refresh">"#,
+            ),
+            (
+                r#"<meta http-equiv=Refresh content="0; URL = 'vbscript:x'">"#,
+                r#"<meta http-equiv="Refresh">"#,
+            ),
+        ] {
+            let (result, removed) = sanitize_urls(html);
+            assert_eq!(result, sanitized, "{}", html);
+            assert_eq!(removed.len(), 1, "{}", html);
+        }
+        let refresh =
+            r#"<meta http-equiv="refresh" content="5; url=/next">"#;
+        assert_eq!(sanitize_urls(refresh).0, refresh);
+        let fill = r#"<svg><animate attributeName="fill" to="javascript:x"/></svg>"#;
+        assert!(sanitize_urls(fill).1.is_empty());
+    }
+
+    #[test]
+    fn test_data_images_and_srcset() {
+        let html = concat!(
+            r#"<img src='data:image/gif;base64,R0' alt="a">"#,
+            r#"<img src="data:image/svg+xml,<svg onload=x>">"#,
+            r#"<a href="data:image/png;base64,AA">p</a>"#,
+            r#"<img srcset="a.png 1x, javascript:x 2x, data:image/webp;base64,AA 3x">"#,
+            r#"<img srcset="data:image/png;base64,A,B 1x,data:text/html,x,y 2x">"#,
+            r#"<img srcset="vbscript:x">"#,
+            r#"<video poster=javascript:x></video>"#
+        );
+        let (sanitized, removed) = sanitize_urls(html);
+        assert_eq!(
+            sanitized,
+            concat!(
+                r#"<img alt="a" src="data:image/gif;base64,R0">"#,
+                "<img>",
+                "<a>p</a>",
+                r#"<img srcset="a.png 1x, data:image/webp;base64,AA 3x">"#,
+                r#"<img srcset="data:image/png;base64,A,B 1x">"#,
+                "<img>",
+                "<video></video>"
+            )
+        );
+        let attributes: Vec<_> = removed
+            .iter()
+            .map(|r| (r.element.as_str(), r.attribute.as_str()))
+            .collect();
+        assert_eq!(
+            attributes,
+            [
+                ("img", "src"),
+                ("a", "href"),
+                ("img", "srcset"),
+                ("img", "srcset"),
+                ("img", "srcset"),
+                ("video", "poster")
+            ]
+        );
+        assert_eq!(removed[2].url, "javascript:x");
+        assert_eq!(removed[3].url, "data:text/html,x,y");
+        assert_eq!(removed[1].to_diagnostic().code, "unsafe_url");
+    }
+
+    #[test]
+    fn test_conversion_policies() {
+        let markdown =
+            "[a](javascript:alert(1)) <a href=\"vbscript:x\">b</a>";
+        let strip = HtmlConfig {
+            url_scheme_policy: UrlSchemePolicy::Strip,
+            ..Default::default()
+        };
+        let conversion = convert_detailed(markdown, &strip).unwrap();
+        assert!(!conversion.html.contains("script:"));
+        assert_eq!(conversion.removed_urls.len(), 2);
+
+        let reject = HtmlConfig {
+            url_scheme_policy: UrlSchemePolicy::Reject,
+            ..Default::default()
+        };
+        assert!(matches!(
+            convert_detailed(markdown, &reject),
+            Err(HtmlError::ValidationError(_))
+        ));
+        assert!(matches!(
+            convert_detailed(
+                "<div>\n<a title=\"t\"/href=\"javascript:alert(1)\">x</a>\n</div>",
+                &reject
+            ),
+            Err(HtmlError::ValidationError(_))
+        ));
+        assert!(convert_detailed("[a](https://x.org)", &reject).is_ok());
+
+        let allow =
+            convert_detailed(markdown, &HtmlConfig::default()).unwrap();
+        assert!(allow.html.contains("vbscript:x"));
+        assert!(allow.removed_urls.is_empty());
+
+        assert_eq!(
+            "strip".parse::<UrlSchemePolicy>().unwrap(),
+            UrlSchemePolicy::Strip
+        );
+        assert!("block".parse::<UrlSchemePolicy>().is_err());
+    }
+}
@@ -0,0 +1,199 @@
+// Copyright © 2025 HTML Generator. All rights reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! JSON Schemas of the configuration types.
+//!
+//! Available with the `schema` feature. The schemas describe
+//! [`HtmlConfig`], [`MarkdownConfig`] and, with the `std` feature,
+//! [`BatchConfig`] as configuration files: keys are the field names,
+//! options taking a fixed set of values are strings spelled as in
+//! comment directives, and every key is optional, since files are
+//! layered over the defaults. Unknown keys are rejected, so editors can
+//! flag typos, and the field documentation is included as descriptions
+//! for completion.
+//!
+//! Extension points configured in code, such as
+//! [`HtmlConfig::plugins`] and [`HtmlConfig::hooks`], are not part of
+//! the schemas.
+//!
+//! # Examples
+//!
+//! ```
+//! use html_generator::schema::html_config_schema;
+//!
+//! let schema = html_config_schema();
+//! assert_eq!(schema["title"], "HtmlConfig");
+//! assert_eq!(
+//!     schema["definitions"]["HighlightMode"]["enum"],
+//!     serde_json::json!(["inline", "classes", "none"])
+//! );
+//! std::fs::write(
+//!     std::env::temp_dir().join("html-generator.schema.json"),
+//!     serde_json::to_string_pretty(&schema)?,
+//! )?;
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+#[cfg(feature = "std")]
+use crate::batch::{BatchConfig, OutputNaming, SymlinkPolicy};
+use crate::bidi::TextDirection;
+use crate::emojis::EmojiPolicy;
+use crate::front_matter::FrontMatterFormat;
+use crate::headings::SlugStrategy;
+use crate::highlight::HighlightMode;
+use crate::sanitize::UrlSchemePolicy;
+use crate::serializer::NonAsciiEncoding;
+use crate::toc::TocPlacement;
+use crate::writing_mode::WritingMode;
+use crate::{HtmlConfig, MarkdownConfig};
+use schemars::r#gen::SchemaGenerator;
+use schemars::schema::{InstanceType, Schema, SchemaObject};
+use schemars::{schema_for, JsonSchema};
+use serde_json::Value;
+
+/// Returns the schema of a string taking one of `values`.
+fn string_enum(values: &[&str]) -> Schema {
+    SchemaObject {
+        instance_type: Some(InstanceType::String.into()),
+        enum_values: Some(
+            values.iter().map(|value| Value::from(*value)).collect(),
+        ),
+        ..SchemaObject::default()
+    }
+    .into()
+}
+
+/// Implements [`JsonSchema`] for enums written as strings.
+macro_rules! string_enum_schemas {
+    ($($ty:ident => [$($value:literal),+ $(,)?];)+) => {$(
+        impl JsonSchema for $ty {
+            fn schema_name() -> String {
+                stringify!($ty).to_string()
+            }
+
+            fn json_schema(_: &mut SchemaGenerator) -> Schema {
+                string_enum(&[$($value),+])
+            }
+        }
+    )+};
+}
+
+string_enum_schemas! {
+    HighlightMode => ["inline", "classes", "none"];
+    SlugStrategy => ["github", "unicode", "transliterate"];
+    UrlSchemePolicy => ["allow", "strip", "reject"];
+    EmojiPolicy => ["keep", "strip_with_label", "remove"];
+    TextDirection => ["ltr", "rtl", "auto"];
+    WritingMode => ["horizontal-tb", "vertical-rl", "vertical-lr"];
+    TocPlacement => ["prepend", "marker", "separate"];
+    NonAsciiEncoding => ["utf8", "named", "numeric"];
+    FrontMatterFormat => ["auto", "yaml", "toml", "json"];
+}
+
+#[cfg(feature = "std")]
+string_enum_schemas! {
+    OutputNaming => ["mirror", "pretty_urls"];
+    SymlinkPolicy => ["skip", "follow", "error"];
+}
+
+/// Returns the schema of [`HtmlConfig`] files.
+pub fn html_config_schema() -> Value {
+    config_schema::<HtmlConfig>()
+}
+
+/// Returns the schema of [`MarkdownConfig`] files.
+pub fn markdown_config_schema() -> Value {
+    config_schema::<MarkdownConfig>()
+}
+
+/// Returns the schema of [`BatchConfig`] files.
+#[cfg(feature = "std")]
+pub fn batch_config_schema() -> Value {
+    config_schema::<BatchConfig>()
+}
+
+/// Returns the schema of `T` with every key optional.
+fn config_schema<T: JsonSchema>() -> Value {
+    let mut schema =
+        serde_json::to_value(schema_for!(T)).unwrap_or(Value::Null);
+    remove_required(&mut schema);
+    schema
+}
+
+/// Removes the lists of required keys from `schema`.
+fn remove_required(schema: &mut Value) {
+    match schema {
+        Value::Object(object) => {
+            if object.get("required").map_or(false, Value::is_array) {
+                let _ = object.remove("required");
+            }
+            object.values_mut().for_each(remove_required);
+        }
+        Value::Array(items) => {
+            items.iter_mut().for_each(remove_required)
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::str::FromStr;
+
+    /// Checks that every value listed in the schema of `T` parses.
+    fn assert_values_parse<T: JsonSchema + FromStr>() {
+        let schema = serde_json::to_value(schema_for!(T)).unwrap();
+        let values = schema["enum"].as_array().unwrap();
+        assert!(!values.is_empty());
+        for value in values {
+            assert!(
+                value.as_str().unwrap().parse::<T>().is_ok(),
+                "{} does not parse {}",
+                T::schema_name(),
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn test_enum_values_match_from_str() {
+        assert_values_parse::<HighlightMode>();
+        assert_values_parse::<SlugStrategy>();
+        assert_values_parse::<UrlSchemePolicy>();
+        assert_values_parse::<EmojiPolicy>();
+        assert_values_parse::<TextDirection>();
+        assert_values_parse::<WritingMode>();
+        assert_values_parse::<TocPlacement>();
+    }
+
+    #[test]
+    fn test_config_schemas() {
+        let schema = html_config_schema();
+        let properties = schema["properties"].as_object().unwrap();
+        assert!(properties.contains_key("enable_syntax_highlighting"));
+        assert!(properties["toc"]["allOf"].is_array());
+        assert!(!properties.contains_key("plugins"));
+        assert_eq!(schema["additionalProperties"], false);
+        assert!(!schema.to_string().contains("\"required\""));
+        assert!(schema["definitions"]["TocConfig"]["properties"]
+            ["placement"]
+            .is_object());
+
+        let schema = markdown_config_schema();
+        assert!(schema["properties"]["html_config"].is_object());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_batch_config_schema() {
+        let schema = batch_config_schema();
+        assert!(schema["properties"]["profiles"]
+            ["additionalProperties"]
+            .is_object());
+        assert_eq!(
+            schema["definitions"]["OutputNaming"]["enum"][1],
+            "pretty_urls"
+        );
+    }
+}
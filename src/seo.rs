@@ -35,9 +35,11 @@
 use std::collections::HashMap;
 
 use crate::error::{HtmlError, Result, SeoErrorKind};
-use lazy_static::lazy_static;
-use regex::{Captures, Regex};
-use scraper::{Html, Selector};
+use crate::patterns::seo::{
+    META_DESC_SELECTOR, PARAGRAPH_SELECTOR, TITLE_SELECTOR,
+};
+use crate::utils::encode_entities;
+use scraper::Html;
 
 // Constants
 /// Maximum allowed size for HTML input (1MB)
@@ -49,27 +51,13 @@
 /// Default OpenGraph type
 const DEFAULT_OG_TYPE: &str = "website";
 
-// Compile regular expressions at compile time
-lazy_static! {
-    /// Regular expression for matching HTML special characters
-    static ref HTML_ESCAPES: Regex = Regex::new(r#"[&<>"']"#)
-        .expect("Failed to compile HTML escapes regex");
-
-    /// Regular expression for extracting meta description
-    static ref META_DESC_SELECTOR: Selector = Selector::parse("meta[name='description']")
-        .expect("Failed to compile meta description selector");
-
-    /// Regular expression for extracting title
-    static ref TITLE_SELECTOR: Selector = Selector::parse("title")
-        .expect("Failed to compile title selector");
-
-    /// Regular expression for extracting paragraphs
-    static ref PARAGRAPH_SELECTOR: Selector = Selector::parse("p")
-        .expect("Failed to compile paragraph selector");
-}
-
 /// Configuration options for structured data generation.
 #[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
 pub struct StructuredDataConfig {
     /// Additional key-value pairs to include in the structured data
     pub additional_data: Option<HashMap<String, String>>,
@@ -113,6 +101,10 @@ fn validate(&self) -> Result<()> {
 
 /// Builder for constructing meta tags.
 #[derive(Debug, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub struct MetaTagsBuilder {
     /// Title for the meta tags
     title: Option<String>,
@@ -269,15 +261,8 @@ fn validate_page_type(page_type: &str) -> Result<()> {
 /// );
 /// ```
 #[must_use]
-pub fn escape_html(s: &str) -> Cow<str> {
-    HTML_ESCAPES.replace_all(s, |caps: &Captures| match &caps[0] {
-        "&" => "&amp;",
-        "<" => "&lt;",
-        ">" => "&gt;",
-        "\"" => "&quot;",
-        "'" => "&#x27;",
-        _ => unreachable!("Regex only matches [&<>\"']"),
-    })
+pub fn escape_html(s: &str) -> Cow<'_, str> {
+    encode_entities(s)
 }
 
 /// Generates meta tags for SEO purposes.
@@ -734,4 +719,32 @@ fn invalid_additional_data_keys() {
             assert!(result.is_err());
         }
     }
+
+    #[cfg(feature = "serde")]
+    mod serde_support {
+        use super::*;
+        use serde_json::json;
+
+        #[test]
+        fn test_round_trips() {
+            let config: StructuredDataConfig =
+                serde_json::from_value(json!({"page_type": "Article"}))
+                    .unwrap();
+            assert_eq!(config.page_type, "Article");
+            assert!(config.additional_types.is_empty());
+            assert!(serde_json::from_value::<StructuredDataConfig>(
+                json!({"type": "Article"})
+            )
+            .is_err());
+
+            let builder = MetaTagsBuilder::new()
+                .with_title("Page")
+                .with_description("About the page");
+            let value = serde_json::to_value(&builder).unwrap();
+            assert_eq!(value["title"], "Page");
+            let builder: MetaTagsBuilder =
+                serde_json::from_value(value).unwrap();
+            assert!(builder.build().unwrap().contains("Page"));
+        }
+    }
 }
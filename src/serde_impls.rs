@@ -0,0 +1,171 @@
+// Copyright © 2025 HTML Generator. All rights reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Serde implementations of the configuration enums written as strings.
+//!
+//! Available with the `serde` feature. The enums are written with their
+//! `Display` and read with their `FromStr` implementations, so
+//! serialized configurations use the values of comment directives and
+//! of the JSON Schemas, e.g. `"classes"` for
+//! [`HighlightMode::CssClasses`].
+//!
+//! Overrides of [`PartialHtmlConfig`](crate::PartialHtmlConfig) that
+//! clear an optional field are read as `null`.
+
+use crate::bidi::TextDirection;
+use crate::emojis::EmojiPolicy;
+use crate::headings::SlugStrategy;
+use crate::highlight::HighlightMode;
+use crate::sanitize::UrlSchemePolicy;
+use crate::toc::TocPlacement;
+use crate::writing_mode::WritingMode;
+use serde::de::{self, Deserialize, Deserializer};
+use serde::{Serialize, Serializer};
+
+/// Implements [`Serialize`] and [`Deserialize`] for enums written as
+/// strings.
+macro_rules! string_enum_serde {
+    ($($ty:ident),+ $(,)?) => {$(
+        impl Serialize for $ty {
+            fn serialize<S: Serializer>(
+                &self,
+                serializer: S,
+            ) -> Result<S::Ok, S::Error> {
+                serializer.collect_str(self)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $ty {
+            fn deserialize<D: Deserializer<'de>>(
+                deserializer: D,
+            ) -> Result<Self, D::Error> {
+                String::deserialize(deserializer)?
+                    .parse()
+                    .map_err(de::Error::custom)
+            }
+        }
+    )+};
+}
+
+string_enum_serde! {
+    HighlightMode,
+    SlugStrategy,
+    UrlSchemePolicy,
+    EmojiPolicy,
+    TextDirection,
+    WritingMode,
+    TocPlacement,
+}
+
+/// Reads an override that can clear an optional field, so that `null`
+/// is `Some(None)` while a missing field stays `None` through
+/// `#[serde(default)]`.
+pub(crate) fn double_option<'de, T, D>(
+    deserializer: D,
+) -> Result<Option<Option<T>>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::front_matter::FrontMatterFormat;
+    use crate::serializer::NonAsciiEncoding;
+    use crate::{HtmlConfig, MarkdownConfig, PartialHtmlConfig};
+    use serde_json::json;
+
+    #[test]
+    fn test_enums_are_strings() {
+        assert_eq!(
+            serde_json::to_value(HighlightMode::CssClasses).unwrap(),
+            "classes"
+        );
+        assert_eq!(
+            serde_json::to_value(WritingMode::VerticalRl).unwrap(),
+            "vertical-rl"
+        );
+        assert_eq!(
+            serde_json::to_value(NonAsciiEncoding::Named).unwrap(),
+            "named"
+        );
+        assert_eq!(
+            serde_json::from_value::<EmojiPolicy>(json!(
+                "strip_with_label"
+            ))
+            .unwrap(),
+            EmojiPolicy::StripWithLabel
+        );
+        assert_eq!(
+            serde_json::from_value::<FrontMatterFormat>(json!("toml"))
+                .unwrap(),
+            FrontMatterFormat::Toml
+        );
+        let error =
+            serde_json::from_value::<SlugStrategy>(json!("kebab"))
+                .unwrap_err();
+        assert!(error.to_string().contains("kebab"));
+    }
+
+    #[test]
+    fn test_configs_round_trip_with_defaults() {
+        let config = MarkdownConfig {
+            html_config: HtmlConfig {
+                generate_toc: true,
+                direction: Some(TextDirection::Rtl),
+                ..HtmlConfig::default()
+            },
+            ..MarkdownConfig::default()
+        };
+        let value = serde_json::to_value(&config).unwrap();
+        assert_eq!(value["html_config"]["direction"], "rtl");
+        assert!(value["html_config"].get("plugins").is_none());
+        assert_eq!(
+            serde_json::from_value::<MarkdownConfig>(value).unwrap(),
+            config
+        );
+
+        let config: HtmlConfig = serde_json::from_value(json!({
+            "math": true,
+            "toc": {"placement": "separate"}
+        }))
+        .unwrap();
+        assert!(config.math);
+        assert_eq!(config.toc.placement, TocPlacement::Separate);
+        assert_eq!(config.toc.max_level, 6);
+        assert_eq!(config.language, HtmlConfig::default().language);
+
+        assert!(serde_json::from_value::<HtmlConfig>(json!({
+            "generate_tco": true
+        }))
+        .is_err());
+    }
+
+    #[test]
+    fn test_overrides_and_cache_keys_cover_every_setting() {
+        let keys = |value: serde_json::Value| -> Vec<String> {
+            let mut keys: Vec<String> =
+                value.as_object().unwrap().keys().cloned().collect();
+            keys.sort();
+            keys
+        };
+        let config = HtmlConfig::default();
+        let settings = keys(serde_json::to_value(&config).unwrap());
+        let overrides = keys(
+            serde_json::to_value(PartialHtmlConfig::default()).unwrap(),
+        );
+        assert_eq!(overrides, settings);
+
+        let mut description = String::new();
+        config.describe_settings(&mut description);
+        let mut described: Vec<String> = description
+            .lines()
+            .map(|line| line.split('=').next().unwrap().to_string())
+            .collect();
+        described.sort();
+        assert_eq!(described, settings);
+    }
+}
@@ -0,0 +1,536 @@
+// Copyright © 2025 HTML Generator. All rights reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Whitespace-exact HTML serialization.
+//!
+//! Transforms that rebuild an element from a parsed tree must write its
+//! content back without changing how it renders. Whitespace between
+//! inline elements such as `<em>` and `<code>` is significant, and the
+//! HTML parser silently drops a newline directly after `<pre>`,
+//! `<textarea>` and `<listing>`, so a naive serializer can join or split
+//! words. The functions in this module write text nodes exactly as
+//! parsed, escaping only what the HTML syntax requires, and restore the
+//! newline the parser would otherwise drop.
+//!
+//! # Examples
+//!
+//! ```
+//! use html_generator::serializer::inner_html;
+//! use scraper::Html;
+//!
+//! let fragment = Html::parse_fragment("<em>a</em> <code> b </code>c");
+//! assert_eq!(
+//!     inner_html(&fragment.root_element()),
+//!     "<em>a</em> <code> b </code>c"
+//! );
+//! ```
+
+use scraper::{ElementRef, Html, Node};
+
+/// Elements without content or closing tag.
+pub(crate) const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input",
+    "keygen", "link", "meta", "param", "source", "track", "wbr",
+];
+
+/// Elements whose text content is written without escaping.
+pub(crate) const RAW_TEXT_ELEMENTS: &[&str] = &[
+    "iframe",
+    "noembed",
+    "noframes",
+    "plaintext",
+    "script",
+    "style",
+    "xmp",
+];
+
+/// Elements whose first newline is dropped by the parser.
+const LEADING_NEWLINE_ELEMENTS: &[&str] =
+    &["listing", "pre", "textarea"];
+
+/// Named entities for U+00A0 to U+00FF, in code point order.
+const LATIN1_ENTITIES: [&str; 96] = [
+    "nbsp", "iexcl", "cent", "pound", "curren", "yen", "brvbar",
+    "sect", "uml", "copy", "ordf", "laquo", "not", "shy", "reg",
+    "macr", "deg", "plusmn", "sup2", "sup3", "acute", "micro", "para",
+    "middot", "cedil", "sup1", "ordm", "raquo", "frac14", "frac12",
+    "frac34", "iquest", "Agrave", "Aacute", "Acirc", "Atilde", "Auml",
+    "Aring", "AElig", "Ccedil", "Egrave", "Eacute", "Ecirc", "Euml",
+    "Igrave", "Iacute", "Icirc", "Iuml", "ETH", "Ntilde", "Ograve",
+    "Oacute", "Ocirc", "Otilde", "Ouml", "times", "Oslash", "Ugrave",
+    "Uacute", "Ucirc", "Uuml", "Yacute", "THORN", "szlig", "agrave",
+    "aacute", "acirc", "atilde", "auml", "aring", "aelig", "ccedil",
+    "egrave", "eacute", "ecirc", "euml", "igrave", "iacute", "icirc",
+    "iuml", "eth", "ntilde", "ograve", "oacute", "ocirc", "otilde",
+    "ouml", "divide", "oslash", "ugrave", "uacute", "ucirc", "uuml",
+    "yacute", "thorn", "yuml",
+];
+
+/// Other common named entities outside Latin-1.
+const OTHER_ENTITIES: &[(char, &str)] = &[
+    ('\u{152}', "OElig"),
+    ('\u{153}', "oelig"),
+    ('\u{160}', "Scaron"),
+    ('\u{161}', "scaron"),
+    ('\u{178}', "Yuml"),
+    ('\u{192}', "fnof"),
+    ('\u{2C6}', "circ"),
+    ('\u{2DC}', "tilde"),
+    ('\u{2002}', "ensp"),
+    ('\u{2003}', "emsp"),
+    ('\u{2009}', "thinsp"),
+    ('\u{200C}', "zwnj"),
+    ('\u{200D}', "zwj"),
+    ('\u{200E}', "lrm"),
+    ('\u{200F}', "rlm"),
+    ('\u{2013}', "ndash"),
+    ('\u{2014}', "mdash"),
+    ('\u{2018}', "lsquo"),
+    ('\u{2019}', "rsquo"),
+    ('\u{201A}', "sbquo"),
+    ('\u{201C}', "ldquo"),
+    ('\u{201D}', "rdquo"),
+    ('\u{201E}', "bdquo"),
+    ('\u{2020}', "dagger"),
+    ('\u{2021}', "Dagger"),
+    ('\u{2022}', "bull"),
+    ('\u{2026}', "hellip"),
+    ('\u{2030}', "permil"),
+    ('\u{2032}', "prime"),
+    ('\u{2033}', "Prime"),
+    ('\u{2039}', "lsaquo"),
+    ('\u{203A}', "rsaquo"),
+    ('\u{20AC}', "euro"),
+    ('\u{2122}', "trade"),
+    ('\u{2190}', "larr"),
+    ('\u{2191}', "uarr"),
+    ('\u{2192}', "rarr"),
+    ('\u{2193}', "darr"),
+    ('\u{2194}', "harr"),
+    ('\u{2212}', "minus"),
+    ('\u{221E}', "infin"),
+    ('\u{2260}', "ne"),
+    ('\u{2264}', "le"),
+    ('\u{2265}', "ge"),
+    ('\u{2665}', "hearts"),
+];
+
+/// How characters outside ASCII are written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum NonAsciiEncoding {
+    /// As UTF-8 characters, e.g. `é`.
+    Utf8,
+    /// As named entities such as `&eacute;` where HTML defines one for
+    /// the character, and as numeric references otherwise.
+    Named,
+    /// As decimal numeric references, e.g. `&#233;`.
+    Numeric,
+}
+
+impl Default for NonAsciiEncoding {
+    fn default() -> Self {
+        NonAsciiEncoding::Utf8
+    }
+}
+
+/// Controls which characters are written as entities.
+///
+/// `<` and `&` in text and `&` and `"` in attribute values are always
+/// escaped, since leaving them would change the document. The other
+/// options only change how the same content is spelled, for downstream
+/// systems with strict entity requirements. The default matches
+/// [`inner_html`] and [`outer_html`]: UTF-8 output, `>` escaped in text
+/// and left as is in attribute values. A no-break space is always
+/// written as an entity so that it stays visible in the source.
+///
+/// # Examples
+///
+/// ```
+/// use html_generator::serializer::{
+///     encode_entities, EntityEncoding, NonAsciiEncoding,
+/// };
+///
+/// let encoding = EntityEncoding {
+///     non_ascii: NonAsciiEncoding::Named,
+///     ..Default::default()
+/// };
+/// assert_eq!(
+///     encode_entities("<p title=\"Café\">Café — 5 &gt; 3</p>", &encoding),
+///     "<p title=\"Caf&eacute;\">Caf&eacute; &mdash; 5 &gt; 3</p>"
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schema", schemars(deny_unknown_fields))]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
+pub struct EntityEncoding {
+    /// How characters outside ASCII are written
+    pub non_ascii: NonAsciiEncoding,
+    /// Write `>` as `&gt;` in text
+    pub escape_gt_in_text: bool,
+    /// Write `<` and `>` as `&lt;` and `&gt;` in attribute values
+    pub escape_angle_brackets_in_attributes: bool,
+}
+
+impl Default for EntityEncoding {
+    fn default() -> Self {
+        Self {
+            non_ascii: NonAsciiEncoding::Utf8,
+            escape_gt_in_text: true,
+            escape_angle_brackets_in_attributes: false,
+        }
+    }
+}
+
+/// Serializes an element, including its own tags.
+///
+/// # Arguments
+///
+/// * `element` - The element to serialize
+pub fn outer_html(element: &ElementRef<'_>) -> String {
+    let mut html = String::new();
+    write_element(&mut html, element, &EntityEncoding::default());
+    html
+}
+
+/// Serializes the children of an element.
+///
+/// # Arguments
+///
+/// * `element` - The element whose content is serialized
+pub fn inner_html(element: &ElementRef<'_>) -> String {
+    let mut html = String::new();
+    write_children(&mut html, element, &EntityEncoding::default());
+    html
+}
+
+/// Re-encodes the entities of an HTML fragment or document.
+///
+/// The HTML is parsed, so existing entities are decoded first, and then
+/// serialized with `encoding`. Input starting with a doctype or `<html>`
+/// is treated as a full document; anything else as a fragment. Content
+/// of `<script>` and `<style>` elements is never encoded.
+///
+/// # Arguments
+///
+/// * `html` - The HTML to re-encode
+/// * `encoding` - Which characters to write as entities
+///
+/// # Returns
+///
+/// The re-encoded HTML.
+pub fn encode_entities(
+    html: &str,
+    encoding: &EntityEncoding,
+) -> String {
+    let mut output = String::with_capacity(html.len());
+    if is_document(html) {
+        let document = Html::parse_document(html);
+        for node in document.tree.root().children() {
+            match node.value() {
+                Node::Doctype(doctype) => {
+                    output.push_str("<!DOCTYPE ");
+                    output.push_str(doctype.name());
+                    output.push('>');
+                }
+                Node::Comment(comment) => {
+                    write_comment(&mut output, comment)
+                }
+                Node::Element(_) => {
+                    if let Some(element) = ElementRef::wrap(node) {
+                        write_element(&mut output, &element, encoding);
+                    }
+                }
+                _ => {}
+            }
+        }
+    } else {
+        let fragment = Html::parse_fragment(html);
+        write_children(&mut output, &fragment.root_element(), encoding);
+    }
+    output
+}
+
+/// Returns `true` if `html` starts with a doctype or `<html>` and is
+/// therefore parsed as a full document rather than a fragment.
+pub(crate) fn is_document(html: &str) -> bool {
+    let start = html
+        .trim_start()
+        .get(..9)
+        .unwrap_or("")
+        .to_ascii_lowercase();
+    start.starts_with("<!doctype") || start.starts_with("<html")
+}
+
+/// Parses `html` as a document or a fragment, see [`is_document`].
+pub(crate) fn parse_html(html: &str) -> Html {
+    if is_document(html) {
+        Html::parse_document(html)
+    } else {
+        Html::parse_fragment(html)
+    }
+}
+
+/// Writes `element` and its content.
+fn write_element(
+    html: &mut String,
+    element: &ElementRef<'_>,
+    encoding: &EntityEncoding,
+) {
+    let name = element.value().name();
+    html.push('<');
+    html.push_str(name);
+    for (attr, value) in element.value().attrs() {
+        html.push(' ');
+        html.push_str(attr);
+        html.push_str("=\"");
+        escape(html, value, true, encoding);
+        html.push('"');
+    }
+    html.push('>');
+    if VOID_ELEMENTS.contains(&name) {
+        return;
+    }
+    let starts_with_newline = element
+        .first_child()
+        .and_then(|child| child.value().as_text())
+        .map_or(false, |text| text.starts_with('\n'));
+    if starts_with_newline && LEADING_NEWLINE_ELEMENTS.contains(&name) {
+        html.push('\n');
+    }
+    write_children(html, element, encoding);
+    html.push_str("</");
+    html.push_str(name);
+    html.push('>');
+}
+
+/// Writes the children of `element`.
+fn write_children(
+    html: &mut String,
+    element: &ElementRef<'_>,
+    encoding: &EntityEncoding,
+) {
+    let raw_text = RAW_TEXT_ELEMENTS.contains(&element.value().name());
+    for child in element.children() {
+        match child.value() {
+            Node::Text(text) if raw_text => html.push_str(text),
+            Node::Text(text) => escape(html, text, false, encoding),
+            Node::Comment(comment) => write_comment(html, comment),
+            Node::Element(_) => {
+                if let Some(child) = ElementRef::wrap(child) {
+                    write_element(html, &child, encoding);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Writes a comment.
+fn write_comment(html: &mut String, comment: &str) {
+    html.push_str("<!--");
+    html.push_str(comment);
+    html.push_str("-->");
+}
+
+/// Escapes text content, or an attribute value if `attribute` is set.
+fn escape(
+    html: &mut String,
+    text: &str,
+    attribute: bool,
+    encoding: &EntityEncoding,
+) {
+    let angle_brackets =
+        attribute && encoding.escape_angle_brackets_in_attributes;
+    for c in text.chars() {
+        match c {
+            '&' => html.push_str("&amp;"),
+            '"' if attribute => html.push_str("&quot;"),
+            '<' if !attribute || angle_brackets => {
+                html.push_str("&lt;")
+            }
+            '>' if angle_brackets
+                || (!attribute && encoding.escape_gt_in_text) =>
+            {
+                html.push_str("&gt;")
+            }
+            '\u{A0}'
+                if encoding.non_ascii != NonAsciiEncoding::Numeric =>
+            {
+                html.push_str("&nbsp;")
+            }
+            c if c.is_ascii() => html.push(c),
+            c => match encoding.non_ascii {
+                NonAsciiEncoding::Utf8 => html.push(c),
+                NonAsciiEncoding::Named => match named_entity(c) {
+                    Some(name) => {
+                        html.push('&');
+                        html.push_str(name);
+                        html.push(';');
+                    }
+                    None => push_numeric(html, c),
+                },
+                NonAsciiEncoding::Numeric => push_numeric(html, c),
+            },
+        }
+    }
+}
+
+/// Writes `c` as a decimal numeric character reference.
+fn push_numeric(html: &mut String, c: char) {
+    html.push_str(&format!("&#{};", u32::from(c)));
+}
+
+/// Returns the HTML entity name of `c`, if it has a common one.
+fn named_entity(c: char) -> Option<&'static str> {
+    match u32::from(c) {
+        code @ 0xA0..=0xFF => {
+            Some(LATIN1_ENTITIES[(code - 0xA0) as usize])
+        }
+        _ => OTHER_ENTITIES
+            .iter()
+            .find(|(entity, _)| *entity == c)
+            .map(|(_, name)| *name),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use scraper::Html;
+
+    fn round_trip(html: &str) -> String {
+        inner_html(&Html::parse_fragment(html).root_element())
+    }
+
+    #[test]
+    fn test_adjacent_inline_elements() {
+        for html in [
+            "<em>a</em><strong>b</strong>",
+            "<em>a</em> <code>b</code>",
+            "x <em> a </em>\n<code>\tb</code>  y",
+            "<a href=\"/\">a</a>&nbsp;<b>b</b>",
+            "<span><em>a</em></span> <!-- c --> <br><i>d</i>",
+        ] {
+            assert_eq!(round_trip(html), html);
+        }
+    }
+
+    #[test]
+    fn test_escaping() {
+        assert_eq!(
+            round_trip(
+                r#"<p title='a "b" &amp; c'>1 &lt; 2 &amp;&gt;</p>"#
+            ),
+            r#"<p title="a &quot;b&quot; &amp; c">1 &lt; 2 &amp;&gt;</p>"#
+        );
+        assert_eq!(
+            round_trip("<style>a > b {}</style>"),
+            "<style>a > b {}</style>"
+        );
+    }
+
+    #[test]
+    fn test_leading_newline_is_kept() {
+        let html = "<pre>\n\ncode\n</pre><textarea>\n\nx</textarea>";
+        let serialized = round_trip(html);
+        assert_eq!(serialized, html);
+        assert_eq!(round_trip(&serialized), html);
+        assert_eq!(round_trip("<pre>\ncode</pre>"), "<pre>code</pre>");
+    }
+
+    #[test]
+    fn test_outer_html() {
+        let fragment =
+            Html::parse_fragment("<p class=\"x\"> a <br> b </p>");
+        let p = fragment
+            .select(&scraper::Selector::parse("p").unwrap())
+            .next()
+            .unwrap();
+        assert_eq!(outer_html(&p), "<p class=\"x\"> a <br> b </p>");
+    }
+
+    mod encode_entities_tests {
+        use super::*;
+
+        #[test]
+        fn test_non_ascii_styles() {
+            let html = "<p>Café ✓ — naïve</p>";
+            let utf8 = EntityEncoding::default();
+            assert_eq!(
+                encode_entities("<p>Caf&eacute;</p>", &utf8),
+                "<p>Café</p>"
+            );
+            assert_eq!(encode_entities(html, &utf8), html);
+
+            let named = EntityEncoding {
+                non_ascii: NonAsciiEncoding::Named,
+                ..Default::default()
+            };
+            assert_eq!(
+                encode_entities(html, &named),
+                "<p>Caf&eacute; &#10003; &mdash; na&iuml;ve</p>"
+            );
+
+            let numeric = EntityEncoding {
+                non_ascii: NonAsciiEncoding::Numeric,
+                ..Default::default()
+            };
+            assert_eq!(
+                encode_entities("<p>é&nbsp;x</p>", &numeric),
+                "<p>&#233;&#160;x</p>"
+            );
+        }
+
+        #[test]
+        fn test_text_and_attribute_escaping() {
+            let html = r#"<a title="a < b > c &amp; &quot;d&quot;">1 &lt; 2 &gt; 0 &amp;</a>"#;
+            assert_eq!(
+                encode_entities(html, &EntityEncoding::default()),
+                r#"<a title="a < b > c &amp; &quot;d&quot;">1 &lt; 2 &gt; 0 &amp;</a>"#
+            );
+            let strict = EntityEncoding {
+                escape_gt_in_text: false,
+                escape_angle_brackets_in_attributes: true,
+                ..Default::default()
+            };
+            assert_eq!(
+                encode_entities(html, &strict),
+                r#"<a title="a &lt; b &gt; c &amp; &quot;d&quot;">1 &lt; 2 > 0 &amp;</a>"#
+            );
+        }
+
+        #[test]
+        fn test_documents_and_raw_text() {
+            let numeric = EntityEncoding {
+                non_ascii: NonAsciiEncoding::Numeric,
+                ..Default::default()
+            };
+            assert_eq!(
+                encode_entities(
+                    "<!DOCTYPE html><html><head><script>let s = 'é';</script></head><body>é</body></html>",
+                    &numeric
+                ),
+                "<!DOCTYPE html><html><head><script>let s = 'é';</script></head><body>&#233;</body></html>"
+            );
+        }
+
+        #[test]
+        fn test_named_entity_table() {
+            assert_eq!(named_entity('\u{A0}'), Some("nbsp"));
+            assert_eq!(named_entity('\u{D7}'), Some("times"));
+            assert_eq!(named_entity('\u{FF}'), Some("yuml"));
+            assert_eq!(named_entity('\u{2122}'), Some("trade"));
+            assert_eq!(named_entity('✓'), None);
+        }
+    }
+}
@@ -0,0 +1,385 @@
+// Copyright © 2025 HTML Generator. All rights reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Long-lived conversion service with warm caches.
+//!
+//! [`ConversionService`] is designed for server and daemon processes
+//! that convert many documents over their lifetime. It keeps expensive
+//! state loaded between requests:
+//!
+//! - Syntax highlighting definitions and the shared patterns are loaded
+//!   once by [`ConversionService::warm_up`]
+//! - Generated HTML is kept in a bounded [`LruCache`] and reused while
+//!   the content of a path is unchanged; the entry of its previous
+//!   content is dropped when it changes and no other path still uses it
+//!
+//! The service is `Send + Sync` and can be shared behind an
+//! [`Arc`](std::sync::Arc).
+//!
+//! # Examples
+//!
+//! ```
+//! use html_generator::service::ConversionService;
+//! use html_generator::MarkdownConfig;
+//! use std::sync::Arc;
+//!
+//! let service = Arc::new(ConversionService::new(MarkdownConfig::default()));
+//! let html = service.convert("index.md", "# Hello")?;
+//! assert!(html.contains("<h1>Hello</h1>"));
+//!
+//! // The second request is served from the cache.
+//! let _ = service.convert("index.md", "# Hello")?;
+//! assert_eq!(service.stats().cache_hits, 1);
+//! # Ok::<(), html_generator::error::HtmlError>(())
+//! ```
+
+use crate::cache::{convert_cached, CacheKey, LruCache, OutputCache};
+use crate::error::Result;
+use crate::{markdown_to_html, HtmlConfig, MarkdownConfig};
+use std::{
+    collections::HashMap,
+    fs,
+    path::Path,
+    sync::{Mutex, MutexGuard},
+};
+
+/// Default number of documents a [`ConversionService`] keeps cached.
+pub const DEFAULT_CACHE_CAPACITY: usize = 1024;
+
+/// Counters describing the activity of a [`ConversionService`].
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct ServiceStats {
+    /// Number of conversion requests received.
+    pub requests: u64,
+    /// Requests answered from the output cache.
+    pub cache_hits: u64,
+    /// Requests that required a fresh conversion.
+    pub cache_misses: u64,
+    /// Number of entries removed by [`ConversionService::invalidate`]
+    /// and [`ConversionService::invalidate_all`].
+    pub invalidations: u64,
+    /// Number of entries dropped to stay within the cache capacity.
+    pub evictions: u64,
+    /// Number of outputs currently cached; paths with the same content
+    /// share one.
+    pub cached_entries: usize,
+}
+
+/// Mutable state shared between requests.
+#[derive(Debug, Default)]
+struct ServiceState {
+    /// The cache key of the last conversion of each path.
+    paths: HashMap<String, CacheKey>,
+    stats: ServiceStats,
+}
+
+/// A thread-safe conversion service that keeps caches warm.
+#[derive(Debug)]
+pub struct ConversionService {
+    config: MarkdownConfig,
+    cache: LruCache,
+    state: Mutex<ServiceState>,
+}
+
+impl ConversionService {
+    /// Creates a service that converts documents with `config`, caching
+    /// up to [`DEFAULT_CACHE_CAPACITY`] of them.
+    pub fn new(config: MarkdownConfig) -> Self {
+        Self {
+            config,
+            cache: LruCache::new(DEFAULT_CACHE_CAPACITY),
+            state: Mutex::new(ServiceState::default()),
+        }
+    }
+
+    /// Sets how many documents are cached, dropping the least recently
+    /// used ones beyond it; zero disables caching.
+    #[must_use]
+    pub fn with_cache_capacity(mut self, capacity: usize) -> Self {
+        self.cache = LruCache::new(capacity);
+        self
+    }
+
+    /// Returns the configuration used for every conversion.
+    pub fn config(&self) -> &MarkdownConfig {
+        &self.config
+    }
+
+    /// Loads shared resources ahead of the first request.
+    ///
+    /// Compiles the shared patterns, then converts a small document
+    /// with a fenced code block to force the syntax definitions and
+    /// themes to load.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a pattern fails to compile or the warm-up
+    /// conversion fails.
+    pub fn warm_up(&self) -> Result<()> {
+        let _ = crate::patterns::warm_up()?;
+        let _ = markdown_to_html(
+            "# Warm up\n\n```rust\nfn main() {}\n```\n",
+            Some(self.config.clone()),
+        )?;
+        Ok(())
+    }
+
+    /// Converts `content`, reusing the cached output when the content
+    /// has not changed since the previous request.
+    ///
+    /// Conversions whose configuration has hooks, plugins or other
+    /// callbacks are never cached, see [`CacheKey::new`].
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Key identifying the document, usually its source path
+    /// * `content` - The Markdown to convert
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if conversion fails. Failed conversions are not
+    /// cached.
+    pub fn convert(&self, path: &str, content: &str) -> Result<String> {
+        // Convert without holding the lock so requests run concurrently.
+        let conversion =
+            convert_cached(content, self.config.clone(), &self.cache);
+
+        let mut state = self.lock_state();
+        state.stats.requests += 1;
+        match &conversion {
+            Ok(conversion) if conversion.hit => {
+                state.stats.cache_hits += 1
+            }
+            _ => state.stats.cache_misses += 1,
+        }
+        let conversion = conversion?;
+        let previous = match conversion.key {
+            Some(key) => state.paths.insert(path.to_string(), key),
+            None => state.paths.remove(path),
+        };
+        if let Some(previous) = previous {
+            if !state.paths.values().any(|key| *key == previous) {
+                let _ = self.cache.remove(&previous);
+            }
+        }
+        if state.paths.len() > self.cache.capacity() {
+            let cache = &self.cache;
+            state.paths.retain(|_, key| cache.contains(key));
+        }
+        Ok(conversion.html)
+    }
+
+    /// Reads and converts the Markdown file at `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the path is unsafe, the file cannot be read,
+    /// or conversion fails.
+    pub fn convert_file(
+        &self,
+        path: impl AsRef<Path>,
+    ) -> Result<String> {
+        let path = path.as_ref();
+        HtmlConfig::validate_file_path(path)?;
+        let content = fs::read_to_string(path)?;
+        self.convert(&path.to_string_lossy(), &content)
+    }
+
+    /// Removes the cached output for `path`.
+    ///
+    /// The output stays cached while another path with the same content
+    /// still uses it.
+    ///
+    /// Returns `true` if an entry was removed.
+    pub fn invalidate(&self, path: &str) -> bool {
+        let mut state = self.lock_state();
+        let removed = match state.paths.remove(path) {
+            Some(key)
+                if !state.paths.values().any(|other| *other == key) =>
+            {
+                self.cache.remove(&key)
+            }
+            _ => false,
+        };
+        if removed {
+            state.stats.invalidations += 1;
+        }
+        removed
+    }
+
+    /// Removes every cached output.
+    pub fn invalidate_all(&self) {
+        let mut state = self.lock_state();
+        let count = self.cache.len() as u64;
+        self.cache.clear();
+        state.paths.clear();
+        state.stats.invalidations += count;
+    }
+
+    /// Returns a snapshot of the service counters.
+    pub fn stats(&self) -> ServiceStats {
+        let state = self.lock_state();
+        let cache = self.cache.stats();
+        ServiceStats {
+            evictions: cache.evictions,
+            cached_entries: cache.entries,
+            ..state.stats
+        }
+    }
+
+    /// Locks the shared state, recovering from a poisoned lock.
+    ///
+    /// The state only holds caches and counters, so it stays consistent
+    /// even if another thread panicked while holding the lock.
+    fn lock_state(&self) -> MutexGuard<'_, ServiceState> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{sync::Arc, thread};
+
+    #[test]
+    fn test_service_is_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<ConversionService>();
+    }
+
+    #[test]
+    fn test_cache_hit_and_miss() {
+        let service = ConversionService::new(MarkdownConfig::default());
+        let first = service.convert("a.md", "# One").unwrap();
+        let second = service.convert("a.md", "# One").unwrap();
+        let changed = service.convert("a.md", "# Two").unwrap();
+
+        assert_eq!(first, second);
+        assert!(changed.contains("Two"));
+        let stats = service.stats();
+        assert_eq!(stats.requests, 3);
+        assert_eq!(stats.cache_hits, 1);
+        assert_eq!(stats.cache_misses, 2);
+        assert_eq!(stats.cached_entries, 1);
+    }
+
+    #[test]
+    fn test_invalidate() {
+        let service = ConversionService::new(MarkdownConfig::default());
+        let _ = service.convert("a.md", "# One").unwrap();
+        let _ = service.convert("b.md", "# Two").unwrap();
+
+        assert!(service.invalidate("a.md"));
+        assert!(!service.invalidate("a.md"));
+        let _ = service.convert("a.md", "# One").unwrap();
+        assert_eq!(service.stats().cache_hits, 0);
+
+        service.invalidate_all();
+        let stats = service.stats();
+        assert_eq!(stats.cached_entries, 0);
+        assert_eq!(stats.invalidations, 3);
+    }
+
+    #[test]
+    fn test_cache_is_bounded() {
+        let service = ConversionService::new(MarkdownConfig::default())
+            .with_cache_capacity(2);
+        for (path, content) in
+            [("a.md", "# A"), ("b.md", "# B"), ("c.md", "# C")]
+        {
+            let _ = service.convert(path, content).unwrap();
+        }
+
+        let stats = service.stats();
+        assert_eq!(stats.cached_entries, 2);
+        assert_eq!(stats.evictions, 1);
+        assert!(!service.invalidate("a.md"));
+        assert!(service.invalidate("c.md"));
+    }
+
+    #[test]
+    fn test_conversions_with_hooks_are_not_cached() {
+        let config = MarkdownConfig {
+            html_config: HtmlConfig {
+                hooks: crate::hooks::PipelineHooks::new()
+                    .after_enhance(|_| Ok(())),
+                ..HtmlConfig::default()
+            },
+            ..MarkdownConfig::default()
+        };
+        let service = ConversionService::new(config);
+        let _ = service.convert("a.md", "# One").unwrap();
+        let _ = service.convert("a.md", "# One").unwrap();
+
+        let stats = service.stats();
+        assert_eq!((stats.cache_hits, stats.cache_misses), (0, 2));
+        assert_eq!(stats.cached_entries, 0);
+    }
+
+    #[test]
+    fn test_failed_conversion_is_not_cached() {
+        let service = ConversionService::new(MarkdownConfig::default());
+        assert!(service.convert("empty.md", "").is_err());
+        assert_eq!(service.stats().cached_entries, 0);
+    }
+
+    #[test]
+    fn test_shared_across_threads() {
+        let service =
+            Arc::new(ConversionService::new(MarkdownConfig::default()));
+        let handles: Vec<_> = (0..4)
+            .map(|i| {
+                let service = Arc::clone(&service);
+                thread::spawn(move || {
+                    service
+                        .convert(
+                            &format!("{}.md", i),
+                            &format!("# Shared {}", i),
+                        )
+                        .unwrap()
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            assert!(handle.join().unwrap().contains("Shared"));
+        }
+        assert_eq!(service.stats().cached_entries, 4);
+    }
+
+    #[test]
+    fn test_shared_output_survives_change_of_one_path() {
+        let service = ConversionService::new(MarkdownConfig::default());
+        let _ = service.convert("a.md", "# Same").unwrap();
+        let _ = service.convert("b.md", "# Same").unwrap();
+        let _ = service.convert("a.md", "# Changed").unwrap();
+
+        let _ = service.convert("b.md", "# Same").unwrap();
+        let stats = service.stats();
+        assert_eq!(stats.cache_hits, 2);
+        assert_eq!(stats.cached_entries, 2);
+    }
+
+    #[test]
+    fn test_invalidate_keeps_output_shared_with_other_paths() {
+        let service = ConversionService::new(MarkdownConfig::default());
+        let _ = service.convert("a.md", "# Same").unwrap();
+        let _ = service.convert("b.md", "# Same").unwrap();
+
+        assert!(!service.invalidate("a.md"));
+        assert_eq!(service.stats().cached_entries, 1);
+        let _ = service.convert("b.md", "# Same").unwrap();
+        assert_eq!(service.stats().cache_hits, 2);
+        assert!(service.invalidate("b.md"));
+        assert_eq!(service.stats().cached_entries, 0);
+    }
+
+    #[test]
+    fn test_warm_up() {
+        let service = ConversionService::new(MarkdownConfig::default());
+        assert!(service.warm_up().is_ok());
+        assert_eq!(service.stats().requests, 0);
+    }
+}
@@ -0,0 +1,298 @@
+// Copyright © 2025 HTML Generator. All rights reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Markup hooks for interactive tables.
+//!
+//! Sorting and filtering long tables is a job for client scripts, but
+//! scripts are simpler and more robust when the markup already says
+//! which parts are interactive. [`enhance_tables`] decorates every table
+//! with:
+//!
+//! - an `id`, so controls can refer to it;
+//! - `scope="col"` and `data-sortable` on column headers, and
+//!   `scope="row"` on row headers;
+//! - a hidden toolbar before the table holding a labelled search input
+//!   for filtering rows. Scripts remove the `hidden` attribute once they
+//!   take over, so readers without JavaScript never see a control that
+//!   does nothing.
+//!
+//! Tables that already carry `data-filterable` are left alone, so the
+//! transform is idempotent.
+//!
+//! Tables with thousands of rows make pages slow to load and to scroll.
+//! [`chunk_tables`] keeps the first rows of such a table in place and
+//! moves the rest into collapsed "show more" sections, each holding a
+//! copy of the table header. The first table records the total number
+//! of rows in `data-row-count`, so scripts can paginate or virtualize
+//! the data instead.
+//!
+//! # Examples
+//!
+//! ```
+//! use html_generator::tables::enhance_tables;
+//!
+//! let html = enhance_tables(
+//!     "<table>\n<thead>\n<tr>\n<th>Name</th>\n</tr>\n</thead>\n</table>",
+//! );
+//! assert!(html.contains(r#"<table id="table-1" data-filterable>"#));
+//! assert!(html.contains(r#"<th scope="col" data-sortable>Name</th>"#));
+//! assert!(html.contains(r#"aria-controls="table-1""#));
+//! ```
+
+use crate::patterns::tables::{
+    ID_REGEX, TABLE_REGEX, TBODY_REGEX, THEAD_REGEX, TH_REGEX, TR_REGEX,
+};
+use regex::Captures;
+
+/// Adds sorting and filtering hooks to every table in `html`.
+///
+/// Tables without an `id` are numbered `table-1`, `table-2`, … in
+/// document order. Header cells that already have a `scope` keep it.
+///
+/// # Arguments
+///
+/// * `html` - The HTML to decorate
+///
+/// # Returns
+///
+/// The HTML with decorated tables; everything else is unchanged.
+pub fn enhance_tables(html: &str) -> String {
+    let mut count = 0;
+    TABLE_REGEX
+        .replace_all(html, |caps: &Captures<'_>| {
+            count += 1;
+            let attributes = caps.get(1).map_or("", |m| m.as_str());
+            if attributes.contains("data-filterable") {
+                return caps[0].to_string();
+            }
+            let (id, attributes) = match ID_REGEX.captures(attributes) {
+                Some(id) => (id[1].to_string(), attributes.to_string()),
+                None => {
+                    let id = format!("table-{}", count);
+                    (
+                        id.clone(),
+                        format!(" id=\"{}\"{}", id, attributes),
+                    )
+                }
+            };
+            format!(
+                "{}<table{} data-filterable>{}</table>",
+                toolbar(&id),
+                attributes,
+                scope_headers(&caps[2])
+            )
+        })
+        .into_owned()
+}
+
+/// Splits tables with more than `rows_per_chunk` body rows into
+/// chunks.
+///
+/// The first chunk stays in the original table, along with its caption
+/// and header, and the table gains a
+/// `data-row-count` attribute with the total number of body rows. Each
+/// further chunk becomes a table of its own, with the same attributes
+/// except `id` and a copy of the header, inside a
+/// `<details class="table-chunk">` element summarised as
+/// "Show rows 101–200 of 1500". Tables that already have
+/// `data-row-count` are left alone.
+///
+/// # Arguments
+///
+/// * `html` - The HTML to process
+/// * `rows_per_chunk` - The maximum number of rows shown at once; `0`
+///   leaves every table unchanged
+pub fn chunk_tables(html: &str, rows_per_chunk: usize) -> String {
+    if rows_per_chunk == 0 {
+        return html.to_string();
+    }
+    TABLE_REGEX
+        .replace_all(html, |caps: &Captures<'_>| {
+            let attributes = caps.get(1).map_or("", |m| m.as_str());
+            let rows: Vec<&str> = TBODY_REGEX
+                .captures_iter(&caps[2])
+                .flat_map(|body| {
+                    let body = body.get(1).map_or("", |m| m.as_str());
+                    TR_REGEX.find_iter(body).map(|row| row.as_str())
+                })
+                .collect();
+            if rows.len() <= rows_per_chunk
+                || attributes.contains("data-row-count")
+            {
+                return caps[0].to_string();
+            }
+
+            let head = THEAD_REGEX
+                .find(&caps[2])
+                .map_or("", |head| head.as_str());
+            let prefix = TBODY_REGEX
+                .find(&caps[2])
+                .map_or("", |body| caps[2][..body.start()].trim());
+            let chunk_attributes = ID_REGEX.replace(attributes, "");
+            let total = rows.len();
+            let mut output = String::with_capacity(caps[0].len() * 2);
+            for (index, chunk) in rows.chunks(rows_per_chunk).enumerate()
+            {
+                let body = chunk.join("\n");
+                if index == 0 {
+                    output.push_str(&format!(
+                        "<table{} data-row-count=\"{}\">\n{}\n<tbody>\n{}\n</tbody>\n</table>",
+                        attributes, total, prefix, body
+                    ));
+                    continue;
+                }
+                let first = index * rows_per_chunk + 1;
+                output.push_str(&format!(
+                    "\n<details class=\"table-chunk\"><summary>Show rows {}\u{2013}{} of {}</summary>\n<table{}>\n{}\n<tbody>\n{}\n</tbody>\n</table>\n</details>",
+                    first,
+                    first + chunk.len() - 1,
+                    total,
+                    chunk_attributes,
+                    head,
+                    body
+                ));
+            }
+            output
+        })
+        .into_owned()
+}
+
+/// Returns the filter toolbar for the table `id`.
+fn toolbar(id: &str) -> String {
+    format!(
+        concat!(
+            r#"<div class="table-toolbar" data-table-toolbar="{id}" hidden>"#,
+            r#"<label for="{id}-filter">Filter rows</label> "#,
+            r#"<input type="search" id="{id}-filter" class="table-filter" aria-controls="{id}">"#,
+            "</div>"
+        ),
+        id = id
+    )
+}
+
+/// Adds `scope` to header cells, and `data-sortable` to column headers.
+fn scope_headers(body: &str) -> String {
+    let mut output = String::with_capacity(body.len());
+    let mut last = 0;
+    for head in THEAD_REGEX.captures_iter(body) {
+        let whole = head.get(0).map_or(0..0, |m| m.range());
+        output.push_str(&scope_cells(
+            &body[last..whole.start],
+            "row",
+            "",
+        ));
+        output.push_str(&head[1]);
+        output.push_str(&scope_cells(
+            &head[2],
+            "col",
+            " data-sortable",
+        ));
+        output.push_str(&head[3]);
+        last = whole.end;
+    }
+    output.push_str(&scope_cells(&body[last..], "row", ""));
+    output
+}
+
+/// Adds `scope` and `extra` to the `th` tags of `html`.
+fn scope_cells(html: &str, scope: &str, extra: &str) -> String {
+    TH_REGEX
+        .replace_all(html, |caps: &Captures<'_>| {
+            let attributes = caps.get(1).map_or("", |m| m.as_str());
+            let scope = if attributes.contains("scope=") {
+                String::new()
+            } else {
+                format!(" scope=\"{}\"", scope)
+            };
+            format!("<th{}{}{}>", scope, extra, attributes)
+        })
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_enhance_generated_table() {
+        let html = concat!(
+            "<div class=\"table-responsive\"><table class=\"table\">\n",
+            "<thead>\n<tr>\n<th align=\"left\">A</th>\n<th scope=\"colgroup\">B</th>\n</tr>\n</thead>\n",
+            "<tbody>\n<tr>\n<th>Row</th>\n<td>1</td>\n</tr>\n</tbody>\n",
+            "</table></div>"
+        );
+        let enhanced = enhance_tables(html);
+        assert!(enhanced.contains(
+            r#"<div class="table-responsive"><div class="table-toolbar" data-table-toolbar="table-1" hidden>"#
+        ));
+        assert!(enhanced.contains(
+            r#"<table id="table-1" class="table" data-filterable>"#
+        ));
+        assert!(enhanced.contains(
+            r#"<th scope="col" data-sortable align="left">A</th>"#
+        ));
+        assert!(enhanced
+            .contains(r#"<th data-sortable scope="colgroup">B</th>"#));
+        assert!(enhanced.contains(r#"<th scope="row">Row</th>"#));
+        assert_eq!(enhance_tables(&enhanced), enhanced);
+    }
+
+    #[test]
+    fn test_existing_ids_and_numbering() {
+        let html =
+            r#"<table id="prices"></table><p>x</p><table></table>"#;
+        let enhanced = enhance_tables(html);
+        assert!(enhanced.contains(r#"aria-controls="prices""#));
+        assert!(enhanced
+            .contains(r#"<table id="table-2" data-filterable>"#));
+        assert_eq!(
+            enhance_tables("<p>No tables</p>"),
+            "<p>No tables</p>"
+        );
+    }
+
+    #[test]
+    fn test_convert_with_directive() {
+        let config = crate::HtmlConfig {
+            comment_directives: true,
+            ..Default::default()
+        };
+        let markdown =
+            "<!-- html-generator: tables=on -->\n| A |\n|---|\n| 1 |";
+        let html =
+            crate::conversion::convert(markdown, &config).unwrap();
+        assert!(html.contains("data-sortable"));
+        let html =
+            crate::conversion::convert("| A |\n|---|\n| 1 |", &config)
+                .unwrap();
+        assert!(!html.contains("data-sortable"));
+    }
+
+    #[test]
+    fn test_chunk_large_tables() {
+        let rows: String = (1..=5)
+            .map(|row| format!("<tr>\n<td>{}</td>\n</tr>\n", row))
+            .collect();
+        let html = format!(
+            "<table id=\"t\" class=\"table\">\n<thead>\n<tr><th>N</th></tr>\n</thead>\n<tbody>\n{}</tbody>\n</table>",
+            rows
+        );
+        let chunked = chunk_tables(&html, 2);
+        assert!(chunked.starts_with(
+            r#"<table id="t" class="table" data-row-count="5">"#
+        ));
+        assert_eq!(chunked.matches("<thead>").count(), 3);
+        assert_eq!(
+            chunked.matches("<table class=\"table\">").count(),
+            2
+        );
+        assert!(chunked
+            .contains("<summary>Show rows 3\u{2013}4 of 5</summary>"));
+        assert!(chunked
+            .contains("<summary>Show rows 5\u{2013}5 of 5</summary>"));
+        assert_eq!(chunked.matches("<tr>").count(), 8);
+        assert_eq!(chunk_tables(&chunked, 2), chunked);
+        assert_eq!(chunk_tables(&html, 5), html);
+        assert_eq!(chunk_tables(&html, 0), html);
+    }
+}
@@ -0,0 +1,523 @@
+// Copyright © 2025 HTML Generator. All rights reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Complete HTML documents around generated fragments.
+//!
+//! Conversions produce HTML fragments. A [`DocumentTemplate`] wraps a
+//! fragment in a complete document: `<html lang>`, a `<head>` with the
+//! title, meta tags and JSON-LD structured data generated from the
+//! page, and links to the configured stylesheets and scripts.
+//!
+//! The layout is HTML with `{{name}}` placeholders:
+//!
+//! | Placeholder | Value |
+//! |-------------|-------|
+//! | `{{lang}}` | The `lang` front matter key, or [`DocumentTemplate::lang`] |
+//! | `{{title}}` | The `title` front matter key, or the text of the first `h1` |
+//! | `{{head}}` | The generated meta tags, links, scripts and structured data |
+//! | `{{body}}` | The HTML fragment |
+//!
+//! Any other placeholder is replaced by the front matter key of that
+//! name, escaped; placeholders without a value are left unchanged.
+//! The fragment itself is never searched for placeholders.
+//!
+//! # Partials and inheritance
+//!
+//! Templates registered with [`DocumentTemplate::with_partial`] are
+//! included with `{{> name}}`, e.g. a header shared by several layouts.
+//! Partials may include other partials and contain placeholders.
+//!
+//! A layout starting with `{{#extends name}}` inherits the named
+//! template: its `{{#block name}}…{{/block}}` sections replace the
+//! blocks of the same name in the base, whose own content is the
+//! default, and the rest of the layout is ignored. Bases may extend
+//! other templates, the nearest override winning. Blocks cannot be
+//! nested. Inclusion and inheritance are limited to
+//! [`MAX_TEMPLATE_DEPTH`] levels, which also stops cycles.
+//!
+//! # Examples
+//!
+//! ```
+//! use html_generator::front_matter::FrontMatter;
+//! use html_generator::templates::DocumentTemplate;
+//!
+//! let template = DocumentTemplate::new()
+//!     .with_lang("en-GB")
+//!     .with_stylesheet("/style.css");
+//! let metadata = FrontMatter {
+//!     description: Some("A short guide".to_string()),
+//!     ..FrontMatter::default()
+//! };
+//! let html = template.render("<h1>Guide</h1>\n<p>Text</p>\n", &metadata)?;
+//! assert!(html.starts_with("<!DOCTYPE html>\n<html lang=\"en-GB\">"));
+//! assert!(html.contains("<title>Guide</title>"));
+//! assert!(html.contains(r#"<meta name="description" content="A short guide">"#));
+//! assert!(html.contains(r#"<link rel="stylesheet" href="/style.css">"#));
+//! # Ok::<(), html_generator::error::HtmlError>(())
+//! ```
+//!
+//! A layout extending a base layout with a shared partial:
+//!
+//! ```
+//! use html_generator::front_matter::FrontMatter;
+//! use html_generator::templates::DocumentTemplate;
+//!
+//! let template = DocumentTemplate::new()
+//!     .with_partial("nav", "<nav><a href=\"/\">Home</a></nav>")
+//!     .with_partial(
+//!         "base",
+//!         "<header>{{> nav}}</header>\n{{#block main}}<main>{{body}}</main>{{/block}}",
+//!     )
+//!     .with_layout("{{#extends base}}{{#block main}}<article>{{body}}</article>{{/block}}");
+//! let html = template.render("<p>Text</p>", &FrontMatter::default())?;
+//! assert_eq!(
+//!     html,
+//!     "<header><nav><a href=\"/\">Home</a></nav></header>\n<article><p>Text</p></article>"
+//! );
+//! # Ok::<(), html_generator::error::HtmlError>(())
+//! ```
+
+use crate::error::{HtmlError, Result};
+use crate::front_matter::FrontMatter;
+use crate::patterns::templates::{
+    BLOCK_REGEX, EXTENDS_REGEX, H1_SELECTOR, PARAGRAPH_SELECTOR,
+    PARTIAL_REGEX, PLACEHOLDER_REGEX,
+};
+use crate::seo::{
+    escape_html, generate_structured_data, MetaTagsBuilder,
+    StructuredDataConfig,
+};
+use alloc::collections::BTreeMap;
+use regex::Captures;
+use scraper::{Html, Selector};
+use serde_json::Value;
+
+/// How many levels of partials or `{{#extends}}` may be nested.
+pub const MAX_TEMPLATE_DEPTH: usize = 8;
+
+/// The layout used when [`DocumentTemplate::layout`] is not set.
+pub const DEFAULT_LAYOUT: &str = "<!DOCTYPE html>
+<html lang=\"{{lang}}\">
+<head>
+<meta charset=\"utf-8\">
+<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">
+<title>{{title}}</title>
+{{head}}
+</head>
+<body>
+{{body}}
+</body>
+</html>
+";
+
+/// Settings wrapping HTML fragments in complete documents.
+#[derive(Debug, Clone)]
+pub struct DocumentTemplate {
+    /// Language of documents whose front matter has no `lang` key.
+    pub lang: String,
+    /// Layout with `{{name}}` placeholders, replacing
+    /// [`DEFAULT_LAYOUT`].
+    pub layout: Option<String>,
+    /// URLs of the stylesheets linked in the head.
+    pub stylesheets: Vec<String>,
+    /// URLs of the scripts loaded, deferred, in the head.
+    pub scripts: Vec<String>,
+    /// Whether to add title, description and author meta tags. They are
+    /// only added to pages with both a title and a description, taken
+    /// from the front matter or else the first `h1` and paragraph.
+    pub meta_tags: bool,
+    /// Settings of the JSON-LD structured data added to pages with a
+    /// title and a description; `None` leaves it out.
+    pub structured_data: Option<StructuredDataConfig>,
+    /// Named templates, included with `{{> name}}` or extended with
+    /// `{{#extends name}}`.
+    pub partials: BTreeMap<String, String>,
+}
+
+impl Default for DocumentTemplate {
+    fn default() -> Self {
+        Self {
+            lang: "en".to_string(),
+            layout: None,
+            stylesheets: Vec::new(),
+            scripts: Vec::new(),
+            meta_tags: true,
+            structured_data: None,
+            partials: BTreeMap::new(),
+        }
+    }
+}
+
+impl DocumentTemplate {
+    /// Creates a template with the default layout, in English, adding
+    /// meta tags but no structured data.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the language of documents without a `lang` front matter key.
+    #[must_use]
+    pub fn with_lang(mut self, lang: impl Into<String>) -> Self {
+        self.lang = lang.into();
+        self
+    }
+
+    /// Sets the layout, see the [module documentation](self).
+    #[must_use]
+    pub fn with_layout(mut self, layout: impl Into<String>) -> Self {
+        self.layout = Some(layout.into());
+        self
+    }
+
+    /// Adds a named template, see the [module documentation](self).
+    #[must_use]
+    pub fn with_partial(
+        mut self,
+        name: impl Into<String>,
+        template: impl Into<String>,
+    ) -> Self {
+        let _ = self.partials.insert(name.into(), template.into());
+        self
+    }
+
+    /// Adds a stylesheet link.
+    #[must_use]
+    pub fn with_stylesheet(mut self, href: impl Into<String>) -> Self {
+        self.stylesheets.push(href.into());
+        self
+    }
+
+    /// Adds a deferred script.
+    #[must_use]
+    pub fn with_script(mut self, src: impl Into<String>) -> Self {
+        self.scripts.push(src.into());
+        self
+    }
+
+    /// Adds JSON-LD structured data with the given settings.
+    #[must_use]
+    pub fn with_structured_data(
+        mut self,
+        config: StructuredDataConfig,
+    ) -> Self {
+        self.structured_data = Some(config);
+        self
+    }
+
+    /// Wraps `body` in a complete document.
+    ///
+    /// # Arguments
+    ///
+    /// * `body` - The HTML fragment
+    /// * `metadata` - The front matter of the page
+    ///
+    /// # Returns
+    ///
+    /// The HTML document.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the meta tags or structured data cannot be
+    /// generated, e.g. because the structured data settings are invalid,
+    /// or [`HtmlError::InvalidInput`] if the layout uses an unknown
+    /// partial or nests them deeper than [`MAX_TEMPLATE_DEPTH`].
+    pub fn render(
+        &self,
+        body: &str,
+        metadata: &FrontMatter,
+    ) -> Result<String> {
+        let fragment = Html::parse_fragment(body);
+        let first_text = |selector: &Selector| {
+            fragment
+                .select(selector)
+                .next()
+                .map(|element| {
+                    element
+                        .text()
+                        .collect::<String>()
+                        .trim()
+                        .to_string()
+                })
+                .filter(|text| !text.is_empty())
+        };
+        let title =
+            metadata.title.clone().or_else(|| first_text(&H1_SELECTOR));
+        let description = metadata
+            .description
+            .clone()
+            .or_else(|| first_text(&PARAGRAPH_SELECTOR));
+        let lang = match metadata.get("lang") {
+            Some(Value::String(lang)) => lang,
+            _ => self.lang.clone(),
+        };
+
+        let mut head = Vec::new();
+        if let (Some(title), Some(description)) = (&title, &description)
+        {
+            if self.meta_tags {
+                let mut builder = MetaTagsBuilder::new()
+                    .with_title(title.as_str())
+                    .with_description(description.as_str());
+                if let Some(author) = &metadata.author {
+                    builder =
+                        builder.add_meta_tag("author", author.as_str());
+                }
+                head.push(builder.build()?);
+            }
+            if let Some(config) = &self.structured_data {
+                let page = format!(
+                    "<title>{}</title><meta name=\"description\" content=\"{}\">",
+                    escape_html(title),
+                    escape_html(description)
+                );
+                head.push(generate_structured_data(
+                    &page,
+                    Some(config.clone()),
+                )?);
+            }
+        }
+        head.extend(self.stylesheets.iter().map(|href| {
+            format!(
+                r#"<link rel="stylesheet" href="{}">"#,
+                escape_html(href)
+            )
+        }));
+        head.extend(self.scripts.iter().map(|src| {
+            format!(
+                r#"<script src="{}" defer></script>"#,
+                escape_html(src)
+            )
+        }));
+        let head = head.join("\n");
+        let body = body.trim_end();
+
+        let layout = self.expand_layout()?;
+        Ok(PLACEHOLDER_REGEX
+            .replace_all(&layout, |caps: &Captures<'_>| {
+                let value = match &caps[1] {
+                    "body" => return body.to_string(),
+                    "head" => return head.clone(),
+                    "lang" => Some(lang.clone()),
+                    "title" => Some(title.clone().unwrap_or_default()),
+                    key => match metadata.get(key) {
+                        Some(Value::String(text)) => Some(text),
+                        Some(Value::Number(number)) => {
+                            Some(number.to_string())
+                        }
+                        Some(Value::Bool(flag)) => {
+                            Some(flag.to_string())
+                        }
+                        _ => None,
+                    },
+                };
+                value.map_or_else(
+                    || caps[0].to_string(),
+                    |value| escape_html(&value).into_owned(),
+                )
+            })
+            .into_owned())
+    }
+
+    /// Returns the layout with its inheritance resolved and its partials
+    /// included.
+    fn expand_layout(&self) -> Result<String> {
+        let layout = self.layout.as_deref().unwrap_or(DEFAULT_LAYOUT);
+        let layout = self.inherit(layout, BTreeMap::new(), 0)?;
+        self.include_partials(&layout, 0)
+    }
+
+    /// Follows the `{{#extends}}` chain of `layout`, collecting block
+    /// overrides nearest first, and fills the blocks of the base.
+    fn inherit<'a>(
+        &'a self,
+        layout: &'a str,
+        mut overrides: BTreeMap<&'a str, &'a str>,
+        depth: usize,
+    ) -> Result<String> {
+        let base = match EXTENDS_REGEX
+            .captures(layout)
+            .and_then(|caps| caps.get(1))
+        {
+            Some(base) => base.as_str(),
+            None => {
+                return Ok(BLOCK_REGEX
+                    .replace_all(layout, |caps: &Captures<'_>| {
+                        overrides.get(&caps[1]).map_or_else(
+                            || caps[2].to_string(),
+                            |block| block.to_string(),
+                        )
+                    })
+                    .into_owned())
+            }
+        };
+        for caps in BLOCK_REGEX.captures_iter(layout) {
+            if let (Some(name), Some(block)) =
+                (caps.get(1), caps.get(2))
+            {
+                let _ = overrides
+                    .entry(name.as_str())
+                    .or_insert(block.as_str());
+            }
+        }
+        let base = self.partial(base, depth)?;
+        self.inherit(base, overrides, depth + 1)
+    }
+
+    /// Replaces the `{{> name}}` tags of `template` with their partials,
+    /// themselves expanded.
+    fn include_partials(
+        &self,
+        template: &str,
+        depth: usize,
+    ) -> Result<String> {
+        let mut error = None;
+        let expanded = PARTIAL_REGEX.replace_all(
+            template,
+            |caps: &Captures<'_>| {
+                let partial =
+                    self.partial(&caps[1], depth).and_then(|partial| {
+                        self.include_partials(partial, depth + 1)
+                    });
+                partial.unwrap_or_else(|e| {
+                    let _ = error.get_or_insert(e);
+                    String::new()
+                })
+            },
+        );
+        match error {
+            Some(error) => Err(error),
+            None => Ok(expanded.into_owned()),
+        }
+    }
+
+    /// Returns the partial `name`, used at nesting level `depth`.
+    fn partial(&self, name: &str, depth: usize) -> Result<&str> {
+        if depth >= MAX_TEMPLATE_DEPTH {
+            return Err(HtmlError::InvalidInput(format!(
+                "Partial '{}' nested more than {} levels deep",
+                name, MAX_TEMPLATE_DEPTH
+            )));
+        }
+        self.partials.get(name).map(String::as_str).ok_or_else(|| {
+            HtmlError::InvalidInput(format!(
+                "Unknown partial: {}",
+                name
+            ))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::markdown_to_html_with_metadata;
+
+    #[test]
+    fn test_default_layout() {
+        let markdown = "---\ntitle: Tips & tricks\nauthor: Ada\nlang: fr\n---\n# Heading\n\nFirst paragraph.\n";
+        let (body, metadata) =
+            markdown_to_html_with_metadata(markdown, None).unwrap();
+        let html = DocumentTemplate::new()
+            .with_script("/app.js")
+            .with_structured_data(StructuredDataConfig::default())
+            .render(&body, &metadata)
+            .unwrap();
+        assert!(html.starts_with("<!DOCTYPE html>\n<html lang=\"fr\">"));
+        assert!(html.contains("<title>Tips &amp; tricks</title>"));
+        assert!(html.contains(
+            r#"<meta name="description" content="First paragraph.">"#
+        ));
+        assert!(html.contains(r#"<meta name="author" content="Ada">"#));
+        assert!(html.contains(r#""name": "Tips & tricks""#));
+        assert!(
+            html.contains(r#"<script src="/app.js" defer></script>"#)
+        );
+        assert!(html
+            .ends_with("<p>First paragraph.</p>\n</body>\n</html>\n"));
+    }
+
+    #[test]
+    fn test_custom_layout_placeholders() {
+        let mut metadata = FrontMatter::default();
+        let _ = metadata
+            .extra
+            .insert("section".to_string(), Value::from("<Docs>"));
+        let _ = metadata
+            .extra
+            .insert("draft".to_string(), Value::from(true));
+        let template = DocumentTemplate::new().with_layout(
+            "<main data-section=\"{{ section }}\" data-draft=\"{{draft}}\">{{body}}</main>{{missing}}[{{head}}]",
+        );
+        let html =
+            template.render("<p>{{section}}</p>\n", &metadata).unwrap();
+        assert_eq!(
+            html,
+            "<main data-section=\"&lt;Docs&gt;\" data-draft=\"true\"><p>{{section}}</p></main>{{missing}}[]"
+        );
+    }
+
+    #[test]
+    fn test_partials() {
+        let mut metadata = FrontMatter::default();
+        let _ = metadata
+            .extra
+            .insert("site".to_string(), Value::from("Docs"));
+        let template = DocumentTemplate::new()
+            .with_partial(
+                "header",
+                "<header>{{> logo}}{{site}}</header>",
+            )
+            .with_partial("logo", "<img src=\"/logo.svg\" alt=\"\">")
+            .with_layout("{{> header}}\n{{ > footer }}{{body}}")
+            .with_partial("footer", "");
+        assert_eq!(
+            template.render("<p>{{> header}}</p>", &metadata).unwrap(),
+            "<header><img src=\"/logo.svg\" alt=\"\">Docs</header>\n<p>{{> header}}</p>"
+        );
+    }
+
+    #[test]
+    fn test_layout_inheritance() {
+        let template = DocumentTemplate::new()
+            .with_partial(
+                "base",
+                "<title>{{#block title}}{{title}}{{/block}}</title>\n{{#block nav}}<nav></nav>{{/block}}\n{{#block main}}{{body}}{{/block}}",
+            )
+            .with_partial(
+                "docs",
+                "{{#extends base}}\n{{#block main}}<main>{{body}}</main>{{/block}}\n{{#block title}}Docs{{/block}}",
+            )
+            .with_layout(
+                "{{#extends docs}}ignored{{#block title}}Guide{{/block}}",
+            );
+        assert_eq!(
+            template
+                .render("<p>Text</p>", &FrontMatter::default())
+                .unwrap(),
+            "<title>Guide</title>\n<nav></nav>\n<main><p>Text</p></main>"
+        );
+    }
+
+    #[test]
+    fn test_invalid_partials() {
+        let metadata = FrontMatter::default();
+        let unknown =
+            DocumentTemplate::new().with_layout("{{> missing}}");
+        assert!(matches!(
+            unknown.render("", &metadata),
+            Err(HtmlError::InvalidInput(_))
+        ));
+
+        let cycle = DocumentTemplate::new()
+            .with_partial("a", "{{> b}}")
+            .with_partial("b", "{{> a}}")
+            .with_layout("{{> a}}");
+        assert!(cycle.render("", &metadata).is_err());
+
+        let circular = DocumentTemplate::new()
+            .with_partial("a", "{{#extends b}}")
+            .with_partial("b", "{{#extends a}}")
+            .with_layout("{{#extends a}}");
+        assert!(circular.render("", &metadata).is_err());
+    }
+}
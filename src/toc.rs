@@ -0,0 +1,564 @@
+// Copyright © 2025 HTML Generator. All rights reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Tables of contents.
+//!
+//! With [`HtmlConfig::generate_toc`](crate::HtmlConfig::generate_toc)
+//! set, the generator lists the headings of a page in a table of
+//! contents. [`TocConfig`] chooses which heading levels are listed, the
+//! element wrapping the list and where it goes:
+//!
+//! - [`TocPlacement::Prepend`] inserts it before the content;
+//! - [`TocPlacement::Marker`] replaces a paragraph holding only
+//!   `[TOC]`;
+//! - [`TocPlacement::Separate`] leaves the content alone, for templates
+//!   placing the table of contents in a sidebar.
+//!
+//! The table of contents is also returned in
+//! [`Conversion::toc`](crate::conversion::Conversion::toc). Listed
+//! headings without an `id` get one derived from their text, so every
+//! entry has a link target.
+//!
+//! Long reference pages also benefit from an "on this page" list at the
+//! start of each section. With
+//! [`HtmlConfig::section_tocs`](crate::HtmlConfig::section_tocs) set,
+//! [`insert_section_tocs`] adds one after every `h2`, listing the `h3`
+//! headings of its section with the same IDs and entry markup as the
+//! main table of contents.
+//!
+//! # Examples
+//!
+//! ```
+//! use html_generator::headings::SlugStrategy;
+//! use html_generator::toc::{insert_toc, TocConfig, TocPlacement};
+//!
+//! let config = TocConfig {
+//!     placement: TocPlacement::Marker,
+//!     min_level: 2,
+//!     ..TocConfig::default()
+//! };
+//! let (html, toc) = insert_toc(
+//!     "<h1>Guide</h1>\n<p>[TOC]</p>\n<h2>Install</h2>",
+//!     &config,
+//!     SlugStrategy::default(),
+//! );
+//! assert_eq!(
+//!     html,
+//!     concat!(
+//!         "<h1>Guide</h1>\n",
+//!         r##"<nav class="toc" aria-label="Table of contents"><ul><li class="toc-h2"><a href="#install">Install</a></li></ul></nav>"##,
+//!         "\n",
+//!         r#"<h2 id="install">Install</h2>"#
+//!     )
+//! );
+//! assert!(toc.is_some());
+//! ```
+
+use crate::error::{HtmlError, Result};
+use crate::headings::{
+    heading_id, heading_text, HeadingIds, SlugStrategy,
+};
+use crate::patterns::headings::HEADING_REGEX;
+use crate::seo::escape_html;
+use regex::Captures;
+use std::{fmt, str::FromStr};
+
+/// Paragraph marking where [`TocPlacement::Marker`] puts the table of
+/// contents.
+pub const TOC_MARKER: &str = "<p>[TOC]</p>";
+
+/// Class of the local tables of contents added by
+/// [`insert_section_tocs`].
+pub const SECTION_TOC_CLASS: &str = "section-toc";
+
+/// Where the table of contents is placed.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum TocPlacement {
+    /// Before the content.
+    Prepend,
+    /// In place of a paragraph holding only `[TOC]`; pages without the
+    /// marker get no table of contents.
+    Marker,
+    /// Not inserted; only returned to the caller.
+    Separate,
+}
+
+impl Default for TocPlacement {
+    fn default() -> Self {
+        TocPlacement::Prepend
+    }
+}
+
+impl fmt::Display for TocPlacement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            TocPlacement::Prepend => "prepend",
+            TocPlacement::Marker => "marker",
+            TocPlacement::Separate => "separate",
+        })
+    }
+}
+
+impl FromStr for TocPlacement {
+    type Err = HtmlError;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value {
+            "prepend" => Ok(TocPlacement::Prepend),
+            "marker" => Ok(TocPlacement::Marker),
+            "separate" => Ok(TocPlacement::Separate),
+            _ => Err(HtmlError::InvalidInput(format!(
+                "Unknown TOC placement `{}`",
+                value
+            ))),
+        }
+    }
+}
+
+/// Settings for the generated table of contents.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schema", schemars(deny_unknown_fields))]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
+pub struct TocConfig {
+    /// Where the table of contents goes.
+    pub placement: TocPlacement,
+    /// Highest heading level listed, from 1 to 6.
+    pub min_level: u8,
+    /// Lowest heading level listed, from 1 to 6.
+    pub max_level: u8,
+    /// Name of the element wrapping the list, e.g. `nav` or `div`.
+    pub element: String,
+    /// Class of the wrapping element, escaped when written.
+    pub class: String,
+}
+
+impl Default for TocConfig {
+    fn default() -> Self {
+        Self {
+            placement: TocPlacement::default(),
+            min_level: 1,
+            max_level: 6,
+            element: "nav".to_string(),
+            class: "toc".to_string(),
+        }
+    }
+}
+
+impl TocConfig {
+    /// Checks that the levels and element name are valid.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`HtmlError::InvalidInput`] if a level is outside 1 to 6,
+    /// `min_level` exceeds `max_level`, or the element name is not
+    /// alphanumeric.
+    pub fn validate(&self) -> Result<()> {
+        if !(1..=6).contains(&self.min_level)
+            || !(1..=6).contains(&self.max_level)
+            || self.min_level > self.max_level
+        {
+            return Err(HtmlError::InvalidInput(format!(
+                "Invalid TOC heading range: h{} to h{}",
+                self.min_level, self.max_level
+            )));
+        }
+        if self.element.is_empty()
+            || !self.element.chars().all(|c| c.is_ascii_alphanumeric())
+        {
+            return Err(HtmlError::InvalidInput(format!(
+                "Invalid TOC element: {}",
+                self.element
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Generates a table of contents and places it in `html`.
+///
+/// Headings between `config.min_level` and `config.max_level` are
+/// listed in document order, as links with the class `toc-h<level>`.
+/// Listed headings without an `id` get a unique one derived from their
+/// text, as with [`add_heading_ids`](crate::headings::add_heading_ids).
+///
+/// # Arguments
+///
+/// * `html` - The generated HTML
+/// * `config` - The table of contents settings
+/// * `slugs` - How missing heading IDs are derived from heading text
+///
+/// # Returns
+///
+/// The HTML, with heading IDs added and the table of contents placed
+/// according to `config.placement`, and the table of contents itself,
+/// or `None` if no heading is listed.
+pub fn insert_toc(
+    html: &str,
+    config: &TocConfig,
+    slugs: SlugStrategy,
+) -> (String, Option<String>) {
+    let mut ids = HeadingIds::for_page(html, slugs);
+    let mut entries = String::new();
+
+    let html =
+        HEADING_REGEX.replace_all(html, |caps: &Captures<'_>| {
+            let level = caps[1].parse::<u8>().unwrap_or_default();
+            if level < config.min_level || level > config.max_level {
+                return caps[0].to_string();
+            }
+            let (heading, entry) = identify(caps, &mut ids);
+            entries.push_str(&entry);
+            heading
+        });
+
+    if entries.is_empty() {
+        return (html.replace(TOC_MARKER, ""), None);
+    }
+    let label = if config.element == "nav" {
+        r#" aria-label="Table of contents""#
+    } else {
+        ""
+    };
+    let toc = format!(
+        r#"<{element} class="{class}"{label}><ul>{entries}</ul></{element}>"#,
+        element = config.element,
+        class = escape_html(&config.class),
+        label = label,
+        entries = entries
+    );
+    let html = match config.placement {
+        TocPlacement::Prepend => format!("{}\n{}", toc, html),
+        TocPlacement::Marker => html.replacen(TOC_MARKER, &toc, 1),
+        TocPlacement::Separate => html.into_owned(),
+    };
+    (html, Some(toc))
+}
+
+/// Adds a local table of contents after every `h2` with `h3` headings
+/// in its section.
+///
+/// A section runs until the next `h1` or `h2`. Its table of contents is
+/// a `<nav class="section-toc">` labelled after the `h2`, with the same
+/// entries as [`insert_toc`] would list. Listed headings without an
+/// `id` get one, and sections already followed by a local table of
+/// contents are left alone.
+///
+/// # Arguments
+///
+/// * `html` - The generated HTML
+/// * `slugs` - How missing heading IDs are derived from heading text
+///
+/// # Returns
+///
+/// The HTML with section tables of contents.
+pub fn insert_section_tocs(html: &str, slugs: SlugStrategy) -> String {
+    let mut ids = HeadingIds::for_page(html, slugs);
+    let mut output = String::with_capacity(html.len());
+    let mut section: Option<Section> = None;
+    let mut last = 0;
+
+    for caps in HEADING_REGEX.captures_iter(html) {
+        let whole = caps.get(0).map_or(0..0, |m| m.range());
+        output.push_str(&html[last..whole.start]);
+        last = whole.end;
+        match &caps[1] {
+            "1" | "2" => {
+                if let Some(section) = section.take() {
+                    section.insert_into(&mut output);
+                }
+                output.push_str(&caps[0]);
+                let listed = html[whole.end..]
+                    .trim_start()
+                    .starts_with(&format!(
+                        r#"<nav class="{}""#,
+                        SECTION_TOC_CLASS
+                    ));
+                if &caps[1] == "2" && !listed {
+                    section = Some(Section {
+                        position: output.len(),
+                        title: heading_text(&caps[3]),
+                        entries: String::new(),
+                    });
+                }
+            }
+            "3" if section.is_some() => {
+                let (heading, entry) = identify(&caps, &mut ids);
+                if let Some(section) = section.as_mut() {
+                    section.entries.push_str(&entry);
+                }
+                output.push_str(&heading);
+            }
+            _ => output.push_str(&caps[0]),
+        }
+    }
+    output.push_str(&html[last..]);
+    if let Some(section) = section {
+        section.insert_into(&mut output);
+    }
+    output
+}
+
+/// An `h2` section collected by [`insert_section_tocs`].
+#[derive(Debug)]
+struct Section {
+    /// Offset in the output just after the `h2`.
+    position: usize,
+    /// Plain text of the `h2`.
+    title: String,
+    /// Entries for the `h3` headings of the section.
+    entries: String,
+}
+
+impl Section {
+    /// Inserts the table of contents of the section, if it lists any
+    /// heading.
+    fn insert_into(self, output: &mut String) {
+        if self.entries.is_empty() {
+            return;
+        }
+        output.insert_str(
+            self.position,
+            &format!(
+                r#"<nav class="{}" aria-label="On this page: {}"><ul>{}</ul></nav>"#,
+                SECTION_TOC_CLASS,
+                self.title.replace('"', "&quot;"),
+                self.entries
+            ),
+        );
+    }
+}
+
+/// Returns a heading with an `id`, adding one if needed, and its table
+/// of contents entry.
+///
+/// Headings without text are returned unchanged with an empty entry,
+/// since a link to them would have no name.
+fn identify(
+    caps: &Captures<'_>,
+    ids: &mut HeadingIds,
+) -> (String, String) {
+    let level = &caps[1];
+    let attributes = caps.get(2).map_or("", |m| m.as_str());
+    let text = heading_text(&caps[3]);
+    if text.trim().is_empty() {
+        return (caps[0].to_string(), String::new());
+    }
+    let (id, heading) = match heading_id(attributes) {
+        Some(id) => (id, caps[0].to_string()),
+        None => {
+            let id = ids.next(&text);
+            let heading = format!(
+                r#"<h{level} id="{id}"{attributes}>{content}</h{level}>"#,
+                level = level,
+                id = id,
+                attributes = attributes,
+                content = &caps[3]
+            );
+            (id, heading)
+        }
+    };
+    let entry = format!(
+        r##"<li class="toc-h{}"><a href="#{}">{}</a></li>"##,
+        level, id, text
+    );
+    (heading, entry)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conversion::convert_detailed;
+    use crate::HtmlConfig;
+
+    #[test]
+    fn test_prepend_with_level_range() {
+        let config = TocConfig {
+            max_level: 2,
+            element: "div".to_string(),
+            class: "contents".to_string(),
+            ..TocConfig::default()
+        };
+        let (html, toc) = insert_toc(
+            r#"<h1>A <em>b</em></h1><h2 id="x">X</h2><h3>Deep</h3><h2>A b</h2><h2>A B</h2>"#,
+            &config,
+            SlugStrategy::default(),
+        );
+        let toc = toc.unwrap();
+        assert_eq!(
+            toc,
+            concat!(
+                r#"<div class="contents"><ul>"#,
+                r##"<li class="toc-h1"><a href="#a-b">A b</a></li>"##,
+                r##"<li class="toc-h2"><a href="#x">X</a></li>"##,
+                r##"<li class="toc-h2"><a href="#a-b-1">A b</a></li>"##,
+                r##"<li class="toc-h2"><a href="#a-b-2">A B</a></li>"##,
+                "</ul></div>"
+            )
+        );
+        assert!(html.starts_with(&toc));
+        assert!(html.contains("<h3>Deep</h3>"));
+        assert!(html.contains(r#"<h2 id="a-b-2">A B</h2>"#));
+    }
+
+    #[test]
+    fn test_marker_and_separate_placement() {
+        let marker = TocConfig {
+            placement: TocPlacement::Marker,
+            ..TocConfig::default()
+        };
+        let slugs = SlugStrategy::default();
+        let (html, toc) = insert_toc("<h2>Only</h2>", &marker, slugs);
+        assert_eq!(html, r#"<h2 id="only">Only</h2>"#);
+        assert!(toc.is_some());
+        let (html, toc) =
+            insert_toc("<p>[TOC]</p><p>x</p>", &marker, slugs);
+        assert_eq!(html, "<p>x</p>");
+        assert!(toc.is_none());
+
+        let separate = TocConfig {
+            placement: TocPlacement::Separate,
+            ..TocConfig::default()
+        };
+        let config = HtmlConfig {
+            generate_toc: true,
+            toc: separate,
+            ..Default::default()
+        };
+        let conversion =
+            convert_detailed("# Title\n\n## Part", &config).unwrap();
+        assert!(!conversion.html.contains("toc-h1"));
+        assert!(conversion.html.contains(r#"<h2 id="part">"#));
+        assert!(conversion.toc.unwrap().contains(r##"href="#part""##));
+        assert!(convert_detailed("# T", &HtmlConfig::default())
+            .unwrap()
+            .toc
+            .is_none());
+    }
+
+    #[test]
+    fn test_class_is_escaped() {
+        let config = TocConfig {
+            class: r#"toc" onmouseover="alert(1)"#.to_string(),
+            ..TocConfig::default()
+        };
+        let (_, toc) = insert_toc(
+            "<h2>Install</h2>",
+            &config,
+            SlugStrategy::default(),
+        );
+        assert!(toc.unwrap().starts_with(
+            r#"<nav class="toc&quot; onmouseover=&quot;alert(1)" aria-label="Table of contents">"#
+        ));
+    }
+
+    #[test]
+    fn test_empty_headings_are_not_listed() {
+        let (html, toc) = insert_toc(
+            "<h2></h2><h2><em> </em></h2><h2>Setup</h2>",
+            &TocConfig::default(),
+            SlugStrategy::default(),
+        );
+        let toc = toc.unwrap();
+        assert!(!toc.contains("></a>"));
+        assert_eq!(toc.matches("<li").count(), 1);
+        assert!(html.contains("<h2></h2>"));
+
+        let (html, toc) = insert_toc(
+            "<h1></h1>",
+            &TocConfig::default(),
+            SlugStrategy::default(),
+        );
+        assert_eq!((html.as_str(), toc), ("<h1></h1>", None));
+
+        let html = convert_detailed(
+            "#\n\n## Setup",
+            &HtmlConfig {
+                generate_toc: true,
+                ..Default::default()
+            },
+        )
+        .unwrap()
+        .html;
+        assert!(!html.contains("></a>"));
+    }
+
+    #[test]
+    fn test_validate_and_parse() {
+        assert!(TocConfig::default().validate().is_ok());
+        for config in [
+            TocConfig {
+                min_level: 0,
+                ..TocConfig::default()
+            },
+            TocConfig {
+                min_level: 4,
+                max_level: 3,
+                ..TocConfig::default()
+            },
+            TocConfig {
+                element: "div onclick".to_string(),
+                ..TocConfig::default()
+            },
+        ] {
+            assert!(config.validate().is_err());
+        }
+        assert_eq!(
+            "marker".parse::<TocPlacement>().unwrap(),
+            TocPlacement::Marker
+        );
+        assert!("top".parse::<TocPlacement>().is_err());
+        assert_eq!(TocPlacement::Separate.to_string(), "separate");
+    }
+
+    #[test]
+    fn test_section_tocs() {
+        let html = concat!(
+            "<h1>Reference</h1><h3>Orphan</h3>",
+            "<h2>Types</h2>\n<p>x</p><h3>Point</h3><h4>Fields</h4>",
+            r#"<h3 id="size">Size</h3>"#,
+            "<h2>Empty</h2><p>y</p>",
+            "<h2>Traits</h2><h3>Point</h3>"
+        );
+        let slugs = SlugStrategy::default();
+        let once = insert_section_tocs(html, slugs);
+        assert_eq!(
+            once,
+            concat!(
+                "<h1>Reference</h1><h3>Orphan</h3>",
+                r#"<h2>Types</h2><nav class="section-toc" aria-label="On this page: Types"><ul>"#,
+                r##"<li class="toc-h3"><a href="#point">Point</a></li>"##,
+                r##"<li class="toc-h3"><a href="#size">Size</a></li>"##,
+                "</ul></nav>\n<p>x</p>",
+                r#"<h3 id="point">Point</h3><h4>Fields</h4>"#,
+                r#"<h3 id="size">Size</h3>"#,
+                "<h2>Empty</h2><p>y</p>",
+                r#"<h2>Traits</h2><nav class="section-toc" aria-label="On this page: Traits"><ul>"#,
+                r##"<li class="toc-h3"><a href="#point-1">Point</a></li>"##,
+                "</ul></nav>",
+                r#"<h3 id="point-1">Point</h3>"#
+            )
+        );
+        assert_eq!(insert_section_tocs(&once, slugs), once);
+    }
+
+    #[test]
+    fn test_convert_with_section_and_page_tocs() {
+        let config = HtmlConfig {
+            generate_toc: true,
+            section_tocs: true,
+            ..Default::default()
+        };
+        let html = crate::conversion::convert(
+            "## Setup\n\n### Install",
+            &config,
+        )
+        .unwrap();
+        assert_eq!(html.matches(r##"href="#install""##).count(), 2);
+        assert!(html.contains(r#"<h3 id="install">"#));
+    }
+}
@@ -0,0 +1,490 @@
+// Copyright © 2025 HTML Generator. All rights reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Unicode normalization and invisible character detection.
+//!
+//! Rendered documents can hide characters that change how text reads
+//! without being visible, most notably the bidirectional controls used
+//! in "Trojan Source" attacks. This module finds such characters,
+//! optionally strips them, and normalizes text to Unicode
+//! Normalization Form C (NFC) so that visually identical strings are
+//! also byte-identical.
+//!
+//! Zero width joiners and non-joiners are not reported, as they are
+//! required by emoji sequences and several scripts.
+//!
+//! [`repair_mojibake`] fixes UTF-8 text that was mistakenly decoded as
+//! Windows-1252, such as `â€™` for `’` or `Ã©` for `é`, which is common
+//! in content migrated from legacy systems.
+//!
+//! # Examples
+//!
+//! ```
+//! use html_generator::unicode::{
+//!     find_invisible_characters, normalize_nfc, InvisibleKind,
+//! };
+//!
+//! let findings = find_invisible_characters("let x = \u{202E}1;");
+//! assert_eq!(findings.len(), 1);
+//! assert_eq!(findings[0].kind, InvisibleKind::BidiControl);
+//!
+//! assert_eq!(normalize_nfc("e\u{301}"), "\u{e9}");
+//! ```
+
+use crate::diagnostics::Diagnostic;
+use std::fmt;
+use unicode_normalization::{
+    is_nfc_quick, IsNormalized, UnicodeNormalization,
+};
+
+/// The category of a suspicious invisible character.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum InvisibleKind {
+    /// A bidirectional formatting control, e.g. `U+202E`.
+    BidiControl,
+    /// A zero width character, e.g. `U+200B`.
+    ZeroWidth,
+    /// A Unicode tag character (`U+E0000`–`U+E007F`).
+    Tag,
+    /// Another character that renders as blank space.
+    Filler,
+}
+
+/// A suspicious invisible character found in text.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct InvisibleCharacter {
+    /// The character itself.
+    pub character: char,
+    /// The Unicode name of the character.
+    pub name: &'static str,
+    /// The category of the character.
+    pub kind: InvisibleKind,
+    /// Byte offset of the character in the scanned text.
+    pub offset: usize,
+    /// One-based line number.
+    pub line: usize,
+    /// One-based column, counted in characters.
+    pub column: usize,
+}
+
+impl InvisibleCharacter {
+    /// Returns the character's code point.
+    pub fn codepoint(&self) -> u32 {
+        u32::from(self.character)
+    }
+
+    /// Converts the finding into a warning [`Diagnostic`].
+    ///
+    /// Bidirectional controls use the code `bidi_control`; all other
+    /// kinds use `invisible_character`.
+    pub fn to_diagnostic(&self) -> Diagnostic {
+        let code = match self.kind {
+            InvisibleKind::BidiControl => "bidi_control",
+            _ => "invisible_character",
+        };
+        Diagnostic::warning(code, self.to_string())
+    }
+}
+
+impl fmt::Display for InvisibleCharacter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "U+{:04X} {} at line {}, column {}",
+            self.codepoint(),
+            self.name,
+            self.line,
+            self.column
+        )
+    }
+}
+
+/// Classifies `c` if it is a suspicious invisible character.
+///
+/// Returns the category and Unicode name, or `None` for ordinary
+/// characters.
+pub fn classify(c: char) -> Option<(InvisibleKind, &'static str)> {
+    use InvisibleKind::*;
+
+    let classified = match c {
+        '\u{061C}' => (BidiControl, "ARABIC LETTER MARK"),
+        '\u{200E}' => (BidiControl, "LEFT-TO-RIGHT MARK"),
+        '\u{200F}' => (BidiControl, "RIGHT-TO-LEFT MARK"),
+        '\u{202A}' => (BidiControl, "LEFT-TO-RIGHT EMBEDDING"),
+        '\u{202B}' => (BidiControl, "RIGHT-TO-LEFT EMBEDDING"),
+        '\u{202C}' => (BidiControl, "POP DIRECTIONAL FORMATTING"),
+        '\u{202D}' => (BidiControl, "LEFT-TO-RIGHT OVERRIDE"),
+        '\u{202E}' => (BidiControl, "RIGHT-TO-LEFT OVERRIDE"),
+        '\u{2066}' => (BidiControl, "LEFT-TO-RIGHT ISOLATE"),
+        '\u{2067}' => (BidiControl, "RIGHT-TO-LEFT ISOLATE"),
+        '\u{2068}' => (BidiControl, "FIRST STRONG ISOLATE"),
+        '\u{2069}' => (BidiControl, "POP DIRECTIONAL ISOLATE"),
+        '\u{00AD}' => (ZeroWidth, "SOFT HYPHEN"),
+        '\u{180E}' => (ZeroWidth, "MONGOLIAN VOWEL SEPARATOR"),
+        '\u{200B}' => (ZeroWidth, "ZERO WIDTH SPACE"),
+        '\u{2060}' => (ZeroWidth, "WORD JOINER"),
+        '\u{2061}' => (ZeroWidth, "FUNCTION APPLICATION"),
+        '\u{2062}' => (ZeroWidth, "INVISIBLE TIMES"),
+        '\u{2063}' => (ZeroWidth, "INVISIBLE SEPARATOR"),
+        '\u{2064}' => (ZeroWidth, "INVISIBLE PLUS"),
+        '\u{FEFF}' => (ZeroWidth, "ZERO WIDTH NO-BREAK SPACE"),
+        '\u{E0000}'..='\u{E007F}' => (Tag, "TAG CHARACTER"),
+        '\u{115F}' => (Filler, "HANGUL CHOSEONG FILLER"),
+        '\u{1160}' => (Filler, "HANGUL JUNGSEONG FILLER"),
+        '\u{3164}' => (Filler, "HANGUL FILLER"),
+        '\u{FFA0}' => (Filler, "HALFWIDTH HANGUL FILLER"),
+        _ => return None,
+    };
+    Some(classified)
+}
+
+/// Finds suspicious invisible characters in `text`.
+///
+/// A byte order mark at the very start of the text is ignored.
+///
+/// # Arguments
+///
+/// * `text` - The text to scan
+///
+/// # Returns
+///
+/// The findings in order of appearance.
+pub fn find_invisible_characters(
+    text: &str,
+) -> Vec<InvisibleCharacter> {
+    let mut findings = Vec::new();
+    let mut line = 1;
+    let mut column = 0;
+    for (offset, character) in text.char_indices() {
+        if character == '\n' {
+            line += 1;
+            column = 0;
+            continue;
+        }
+        column += 1;
+        if offset == 0 && character == '\u{FEFF}' {
+            continue;
+        }
+        if let Some((kind, name)) = classify(character) {
+            findings.push(InvisibleCharacter {
+                character,
+                name,
+                kind,
+                offset,
+                line,
+                column,
+            });
+        }
+    }
+    findings
+}
+
+/// Removes every character reported by [`find_invisible_characters`].
+pub fn strip_invisible_characters(text: &str) -> String {
+    text.char_indices()
+        .filter(|&(offset, character)| {
+            (offset == 0 && character == '\u{FEFF}')
+                || classify(character).is_none()
+        })
+        .map(|(_, character)| character)
+        .collect()
+}
+
+/// A mis-encoded sequence fixed by [`repair_mojibake`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MojibakeRepair {
+    /// The mis-encoded text, e.g. `"â€™"`.
+    pub original: String,
+    /// The repaired text, e.g. `"’"`.
+    pub repaired: String,
+    /// Byte offset of the original text in the input.
+    pub offset: usize,
+    /// One-based line number.
+    pub line: usize,
+    /// One-based column, counted in characters.
+    pub column: usize,
+}
+
+impl MojibakeRepair {
+    /// Converts the repair into an informational [`Diagnostic`] with
+    /// the code `mojibake`.
+    pub fn to_diagnostic(&self) -> Diagnostic {
+        Diagnostic::info("mojibake", self.to_string())
+    }
+}
+
+impl fmt::Display for MojibakeRepair {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Repaired '{}' as '{}' at line {}, column {}",
+            self.original, self.repaired, self.line, self.column
+        )
+    }
+}
+
+/// Returns the Windows-1252 byte for `c`, if it has one.
+///
+/// The five bytes Windows-1252 leaves undefined are accepted as the
+/// matching C1 control characters, which is how most decoders pass
+/// them through.
+fn windows_1252_byte(c: char) -> Option<u8> {
+    let byte = match c {
+        '\u{0000}'..='\u{007F}' | '\u{00A0}'..='\u{00FF}' => c as u8,
+        '\u{0081}' | '\u{008D}' | '\u{008F}' | '\u{0090}'
+        | '\u{009D}' => c as u8,
+        '€' => 0x80,
+        '‚' => 0x82,
+        'ƒ' => 0x83,
+        '„' => 0x84,
+        '…' => 0x85,
+        '†' => 0x86,
+        '‡' => 0x87,
+        'ˆ' => 0x88,
+        '‰' => 0x89,
+        'Š' => 0x8A,
+        '‹' => 0x8B,
+        'Œ' => 0x8C,
+        'Ž' => 0x8E,
+        '‘' => 0x91,
+        '’' => 0x92,
+        '“' => 0x93,
+        '”' => 0x94,
+        '•' => 0x95,
+        '–' => 0x96,
+        '—' => 0x97,
+        '˜' => 0x98,
+        '™' => 0x99,
+        'š' => 0x9A,
+        '›' => 0x9B,
+        'œ' => 0x9C,
+        'ž' => 0x9E,
+        'Ÿ' => 0x9F,
+        _ => return None,
+    };
+    Some(byte)
+}
+
+/// Decodes the mis-encoded UTF-8 sequence starting at `chars[0]`.
+///
+/// Returns the repaired character and the number of input characters
+/// it replaces.
+fn decode_mojibake(chars: &[char]) -> Option<(char, usize)> {
+    let lead = windows_1252_byte(*chars.first()?)?;
+    let len = match lead {
+        0xC2..=0xDF => 2,
+        0xE0..=0xEF => 3,
+        0xF0..=0xF4 => 4,
+        _ => return None,
+    };
+    let mut bytes = vec![lead];
+    for &c in chars.get(1..len)? {
+        match windows_1252_byte(c)? {
+            byte @ 0x80..=0xBF => bytes.push(byte),
+            _ => return None,
+        }
+    }
+    let decoded = std::str::from_utf8(&bytes).ok()?.chars().next()?;
+    Some((decoded, len))
+}
+
+/// Repairs UTF-8 text that was decoded as Windows-1252.
+///
+/// Each run of characters that re-encodes to a valid multi-byte UTF-8
+/// sequence is replaced by the character it encodes. Text that was
+/// mis-decoded twice needs two passes.
+///
+/// # Arguments
+///
+/// * `text` - The text to repair
+///
+/// # Returns
+///
+/// The repaired text and one [`MojibakeRepair`] per fixed sequence.
+///
+/// # Examples
+///
+/// ```
+/// use html_generator::unicode::repair_mojibake;
+///
+/// let (text, repairs) = repair_mojibake("Itâ€™s cafÃ©");
+/// assert_eq!(text, "It’s café");
+/// assert_eq!(repairs.len(), 2);
+/// ```
+pub fn repair_mojibake(text: &str) -> (String, Vec<MojibakeRepair>) {
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let plain: Vec<char> = chars.iter().map(|&(_, c)| c).collect();
+    let mut output = String::with_capacity(text.len());
+    let mut repairs = Vec::new();
+    let mut line = 1;
+    let mut column = 0;
+    let mut index = 0;
+    while index < plain.len() {
+        let c = plain[index];
+        if c == '\n' {
+            line += 1;
+            column = 0;
+        } else {
+            column += 1;
+        }
+
+        match decode_mojibake(&plain[index..]) {
+            Some((repaired, len)) => {
+                output.push(repaired);
+                repairs.push(MojibakeRepair {
+                    original: plain[index..index + len]
+                        .iter()
+                        .collect(),
+                    repaired: repaired.to_string(),
+                    offset: chars[index].0,
+                    line,
+                    column,
+                });
+                column += len - 1;
+                index += len;
+            }
+            None => {
+                output.push(c);
+                index += 1;
+            }
+        }
+    }
+    (output, repairs)
+}
+
+/// Normalizes `text` to Unicode Normalization Form C.
+pub fn normalize_nfc(text: &str) -> String {
+    match is_nfc_quick(text.chars()) {
+        IsNormalized::Yes => text.to_string(),
+        _ => text.nfc().collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod detection_tests {
+        use super::*;
+
+        #[test]
+        fn test_finds_bidi_controls_with_position() {
+            let text = "ok\nif x \u{202E} y\u{2066}";
+            let findings = find_invisible_characters(text);
+
+            assert_eq!(findings.len(), 2);
+            assert_eq!(findings[0].codepoint(), 0x202E);
+            assert_eq!(findings[0].kind, InvisibleKind::BidiControl);
+            assert_eq!((findings[0].line, findings[0].column), (2, 6));
+            assert_eq!(
+                findings[0].offset,
+                text.find('\u{202E}').unwrap()
+            );
+            assert_eq!(findings[1].name, "LEFT-TO-RIGHT ISOLATE");
+        }
+
+        #[test]
+        fn test_zero_width_and_tag_characters() {
+            let kinds: Vec<_> = find_invisible_characters(
+                "a\u{200B}b\u{E0041}c\u{3164}",
+            )
+            .into_iter()
+            .map(|finding| finding.kind)
+            .collect();
+            assert_eq!(
+                kinds,
+                vec![
+                    InvisibleKind::ZeroWidth,
+                    InvisibleKind::Tag,
+                    InvisibleKind::Filler
+                ]
+            );
+        }
+
+        #[test]
+        fn test_joiners_and_leading_bom_are_allowed() {
+            assert!(find_invisible_characters(
+                "\u{FEFF}👨\u{200D}👩 \u{200C}"
+            )
+            .is_empty());
+            assert_eq!(find_invisible_characters("a\u{FEFF}").len(), 1);
+        }
+
+        #[test]
+        fn test_to_diagnostic() {
+            let findings =
+                find_invisible_characters("\u{202E}\u{200B}");
+            let bidi = findings[0].to_diagnostic();
+            assert_eq!(bidi.code, "bidi_control");
+            assert_eq!(
+                bidi.message,
+                "U+202E RIGHT-TO-LEFT OVERRIDE at line 1, column 1"
+            );
+            assert_eq!(
+                findings[1].to_diagnostic().code,
+                "invisible_character"
+            );
+        }
+    }
+
+    mod mojibake_tests {
+        use super::*;
+
+        #[test]
+        fn test_repairs_common_sequences() {
+            let (text, repairs) =
+                repair_mojibake("â€œQuotedâ€\u{9d} â€” naÃ¯ve 5Â°C");
+            assert_eq!(text, "“Quoted” — naïve 5°C");
+            assert_eq!(repairs.len(), 5);
+            assert_eq!(repairs[0].original, "â€œ");
+            assert_eq!(repairs[0].repaired, "“");
+        }
+
+        #[test]
+        fn test_reports_positions() {
+            let (_, repairs) = repair_mojibake("ok\nthe cafÃ©");
+            assert_eq!(repairs[0].offset, 10);
+            assert_eq!((repairs[0].line, repairs[0].column), (2, 8));
+            let diagnostic = repairs[0].to_diagnostic();
+            assert_eq!(diagnostic.code, "mojibake");
+            assert_eq!(
+                diagnostic.message,
+                "Repaired 'Ã©' as 'é' at line 2, column 8"
+            );
+        }
+
+        #[test]
+        fn test_leaves_valid_text_alone() {
+            let text = "Déjà vu — “quotes” and Ã alone, ©2025 Â";
+            let (repaired, repairs) = repair_mojibake(text);
+            assert_eq!(repaired, text);
+            assert!(repairs.is_empty());
+        }
+
+        #[test]
+        fn test_four_byte_sequences() {
+            let (text, _) = repair_mojibake("ðŸ˜€");
+            assert_eq!(text, "😀");
+        }
+    }
+
+    mod cleanup_tests {
+        use super::*;
+
+        #[test]
+        fn test_strip_invisible_characters() {
+            assert_eq!(
+                strip_invisible_characters(
+                    "\u{FEFF}a\u{202E}b\u{200B}c"
+                ),
+                "\u{FEFF}abc"
+            );
+        }
+
+        #[test]
+        fn test_normalize_nfc() {
+            assert_eq!(normalize_nfc("Cafe\u{301}"), "Caf\u{e9}");
+            assert_eq!(normalize_nfc("plain"), "plain");
+        }
+    }
+}
@@ -7,31 +7,29 @@
 //! extracting front matter from Markdown content and formatting HTML headers.
 
 use crate::error::{HtmlError, Result};
+use crate::front_matter::{parse_front_matter, FrontMatterFormat};
+use crate::patterns::utils::{CONSECUTIVE_HYPHENS_REGEX, HEADER_REGEX};
+use crate::query::parse_selector;
+use crate::serializer::{outer_html, parse_html};
 use once_cell::sync::Lazy;
-use regex::Regex;
 use scraper::ElementRef;
-use std::collections::HashMap;
-
-static FRONT_MATTER_REGEX: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(r"(?ms)^---\s*\n(.*?)\n---\s*\n")
-        .expect("Failed to compile FRONT_MATTER_REGEX")
-});
-
-static HEADER_REGEX: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(r"<(h[1-6])(?:\s[^>]*)?>(.+?)</h[1-6]>")
-        .expect("Failed to compile HEADER_REGEX")
-});
-
-static CONSECUTIVE_HYPHENS_REGEX: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(r"-{2,}")
-        .expect("Failed to compile CONSECUTIVE_HYPHENS_REGEX")
-});
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use unicode_normalization::{
+    char::is_combining_mark, UnicodeNormalization,
+};
 
 /// Maximum allowed input size (in bytes) to prevent DOS attacks
 const MAX_INPUT_SIZE: usize = 1_000_000; // 1 MB
 
 /// Extracts front matter from Markdown content.
 ///
+/// YAML (`---`), TOML (`+++`) and JSON (`{ ... }`) front matter are
+/// recognised; see [`parse_front_matter`] to read the metadata instead
+/// of discarding it, and
+/// [`split_front_matter`](crate::front_matter::split_front_matter) to
+/// borrow the body instead of copying it.
+///
 /// # Arguments
 ///
 /// * `content` - A string slice that holds the content to process.
@@ -63,38 +61,12 @@ pub fn extract_front_matter(content: &str) -> Result<String> {
         return Err(HtmlError::InputTooLarge(content.len()));
     }
 
-    if content.starts_with("---") {
-        if let Some(captures) = FRONT_MATTER_REGEX.captures(content) {
-            let front_matter = captures
-                .get(1)
-                .ok_or_else(|| {
-                    HtmlError::InvalidFrontMatterFormat(
-                        "Missing front matter match".to_string(),
-                    )
-                })?
-                .as_str();
-
-            for line in front_matter.lines() {
-                if !line.trim().contains(':') {
-                    return Err(HtmlError::InvalidFrontMatterFormat(
-                        format!(
-                            "Invalid line in front matter: {}",
-                            line
-                        ),
-                    ));
-                }
-            }
-
-            let remaining_content =
-                &content[captures.get(0).unwrap().end()..];
-            Ok(remaining_content.trim().to_string())
-        } else {
-            Err(HtmlError::InvalidFrontMatterFormat(
-                "Invalid front matter format".to_string(),
-            ))
-        }
-    } else {
+    let (_, body) =
+        parse_front_matter(content, FrontMatterFormat::Auto)?;
+    if body.len() == content.len() {
         Ok(content.to_string())
+    } else {
+        Ok(body.trim().to_string())
     }
 }
 
@@ -210,6 +182,54 @@ pub fn generate_table_of_contents(html: &str) -> Result<String> {
     Ok(toc)
 }
 
+/// Extracts the first element matching a CSS selector.
+///
+/// Use this to keep only the main content of a converted or legacy
+/// document, such as its `article` or `#content`, before further
+/// processing. Input starting with a doctype or `<html>` is parsed as a
+/// full document; anything else as a fragment.
+///
+/// # Arguments
+///
+/// * `html` - The HTML to search
+/// * `css_selector` - The CSS selector, e.g. `main > article`
+///
+/// # Returns
+///
+/// * `Result<String>` - The matching element, including its own tags.
+///
+/// # Errors
+///
+/// Returns [`HtmlError::SelectorParseError`] if the selector is invalid
+/// and [`HtmlError::MissingHtmlElement`] if it matches nothing.
+///
+/// # Examples
+///
+/// ```
+/// use html_generator::utils::extract_fragment;
+///
+/// let html = "<nav>Menu</nav><article><h1>Post</h1></article>";
+/// let article = extract_fragment(html, "article").unwrap();
+/// assert_eq!(article, "<article><h1>Post</h1></article>");
+/// assert!(extract_fragment(html, "#content").is_err());
+/// ```
+pub fn extract_fragment(
+    html: &str,
+    css_selector: &str,
+) -> Result<String> {
+    let selector = parse_selector(css_selector)?;
+    parse_html(html)
+        .select(&selector)
+        .next()
+        .map(|element| outer_html(&element))
+        .ok_or_else(|| {
+            HtmlError::MissingHtmlElement(format!(
+                "No element matches '{}'",
+                css_selector
+            ))
+        })
+}
+
 /// Check if an ARIA role is valid for a given element.
 ///
 /// # Arguments
@@ -262,6 +282,12 @@ pub fn is_valid_language_code(lang: &str) -> bool {
 
 /// Generates an ID from the given content.
 ///
+/// Letters and digits are lowercased and every run of other characters
+/// becomes one hyphen, without leading or trailing hyphens. ASCII
+/// content is processed byte by byte in a single pass; other content is
+/// lowercased first, as some characters lowercase differently at the
+/// end of a word.
+///
 /// # Arguments
 ///
 /// * `content` - The content to generate the ID from.
@@ -269,18 +295,555 @@ pub fn is_valid_language_code(lang: &str) -> bool {
 /// # Returns
 ///
 /// * `String` - The generated ID.
-fn generate_id(content: &str) -> String {
+pub(crate) fn generate_id(content: &str) -> String {
+    let mut id = String::with_capacity(content.len());
+    let mut separated = false;
+    if content.is_ascii() {
+        for &byte in content.as_bytes() {
+            push_id_char(
+                &mut id,
+                &mut separated,
+                char::from(byte.to_ascii_lowercase()),
+            );
+        }
+    } else {
+        for c in content.to_lowercase().chars() {
+            push_id_char(&mut id, &mut separated, c);
+        }
+    }
+    id
+}
+
+/// Appends `c` to `id` if it is alphanumeric, preceded by a hyphen if
+/// other characters were skipped since the last one.
+fn push_id_char(id: &mut String, separated: &mut bool, c: char) {
+    if c.is_alphanumeric() {
+        if *separated && !id.is_empty() {
+            id.push('-');
+        }
+        *separated = false;
+        id.push(c);
+    } else {
+        *separated = true;
+    }
+}
+
+/// Maximum length of a slug produced by [`slugify_path`].
+const MAX_SLUG_LENGTH: usize = 80;
+
+/// File names reserved by Windows, regardless of extension.
+const RESERVED_FILE_NAMES: &[&str] = &[
+    "con", "prn", "aux", "nul", "com1", "com2", "com3", "com4", "com5",
+    "com6", "com7", "com8", "com9", "lpt1", "lpt2", "lpt3", "lpt4",
+    "lpt5", "lpt6", "lpt7", "lpt8", "lpt9",
+];
+
+/// Returns the ASCII transliteration of characters that do not
+/// decompose into a base letter and combining marks.
+fn transliterate(c: char) -> Option<&'static str> {
+    let ascii = match c {
+        'ß' => "ss",
+        'æ' | 'Æ' => "ae",
+        'œ' | 'Œ' => "oe",
+        'ø' | 'Ø' => "o",
+        'đ' | 'Đ' | 'ð' | 'Ð' => "d",
+        'ł' | 'Ł' => "l",
+        'þ' | 'Þ' => "th",
+        'ı' => "i",
+        '&' => "and",
+        _ => return None,
+    };
+    Some(ascii)
+}
+
+/// Converts a title or file name into a slug that is safe to use as a
+/// file name on every major platform.
+///
+/// Accented letters are transliterated to ASCII (`é` becomes `e`, `ß`
+/// becomes `ss`), every other character outside `[a-z0-9]` becomes a
+/// hyphen, and the result is limited to 80 characters. Names reserved
+/// by Windows, such as `con` or `lpt1`, get a `-page` suffix, and an
+/// empty result becomes `untitled`.
+///
+/// The extension is not treated specially; pass the file stem when
+/// slugifying a file name. Use [`PathSlugger`] to avoid collisions.
+///
+/// # Arguments
+///
+/// * `name` - The title or file name to convert.
+///
+/// # Returns
+///
+/// * `String` - The slug.
+///
+/// # Examples
+///
+/// ```
+/// use html_generator::utils::slugify_path;
+///
+/// assert_eq!(slugify_path("Crème Brûlée & Straße"), "creme-brulee-and-strasse");
+/// assert_eq!(slugify_path("CON"), "con-page");
+/// assert_eq!(slugify_path("???"), "untitled");
+/// ```
+pub fn slugify_path(name: &str) -> String {
+    let mut slug = ascii_slug(name);
+    if slug.len() > MAX_SLUG_LENGTH {
+        slug.truncate(MAX_SLUG_LENGTH);
+        slug.truncate(slug.trim_end_matches('-').len());
+    }
+
+    if slug.is_empty() {
+        "untitled".to_string()
+    } else if RESERVED_FILE_NAMES.contains(&slug.as_str()) {
+        format!("{}-page", slug)
+    } else {
+        slug
+    }
+}
+
+/// Converts `text` into a lowercase ASCII slug, transliterating
+/// accented letters and replacing every other character outside
+/// `[a-z0-9]` with a hyphen.
+pub(crate) fn ascii_slug(text: &str) -> String {
+    let mut slug = String::with_capacity(text.len());
+    for c in text.nfkd() {
+        if is_combining_mark(c) {
+            continue;
+        }
+        match transliterate(c) {
+            Some(ascii) => slug.push_str(ascii),
+            None if c.is_ascii_alphanumeric() => {
+                slug.push(c.to_ascii_lowercase());
+            }
+            None => slug.push('-'),
+        }
+    }
     CONSECUTIVE_HYPHENS_REGEX
-        .replace_all(
-            &content
-                .to_lowercase()
-                .replace(|c: char| !c.is_alphanumeric(), "-"),
-            "-",
-        )
+        .replace_all(&slug, "-")
         .trim_matches('-')
         .to_string()
 }
 
+/// Produces unique path slugs within one directory.
+///
+/// The first use of a slug is returned unchanged; later uses receive a
+/// numeric suffix (`-2`, `-3`, ...).
+///
+/// # Examples
+///
+/// ```
+/// use html_generator::utils::PathSlugger;
+///
+/// let mut slugger = PathSlugger::new();
+/// assert_eq!(slugger.slugify("Hello World"), "hello-world");
+/// assert_eq!(slugger.slugify("hello, world!"), "hello-world-2");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct PathSlugger {
+    used: HashSet<String>,
+}
+
+impl PathSlugger {
+    /// Creates a slugger with no slugs in use.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a slug for `name` that has not been returned before.
+    pub fn slugify(&mut self, name: &str) -> String {
+        let base = slugify_path(name);
+        let mut slug = base.clone();
+        let mut counter = 2;
+        while !self.used.insert(slug.clone()) {
+            slug = format!("{}-{}", base, counter);
+            counter += 1;
+        }
+        slug
+    }
+
+    /// Marks `name` as used without slugifying it, so later slugs
+    /// for the same name receive a suffix.
+    ///
+    /// Returns `false` if `name` was already used.
+    pub fn reserve(&mut self, name: &str) -> bool {
+        self.used.insert(name.to_string())
+    }
+}
+
+/// Returns the value of `key` in a front matter block, if any.
+///
+/// Strings, numbers and booleans are returned as text; other values,
+/// empty strings and invalid front matter are treated as missing.
+#[cfg(feature = "std")]
+pub(crate) fn front_matter_value(
+    content: &str,
+    key: &str,
+) -> Option<String> {
+    let (metadata, _) =
+        parse_front_matter(content, FrontMatterFormat::Auto).ok()?;
+    let value = match metadata.get(key)? {
+        serde_json::Value::String(value) => value.trim().to_string(),
+        serde_json::Value::Number(value) => value.to_string(),
+        serde_json::Value::Bool(value) => value.to_string(),
+        _ => return None,
+    };
+    (!value.is_empty()).then(|| value)
+}
+
+/// Matches a `/`-separated relative path against a glob pattern.
+///
+/// Supported syntax:
+///
+/// - `*` matches any run of characters except `/`
+/// - `**` matches any run of characters, including `/`; `**/` also
+///   matches zero directories
+/// - `?` matches a single character other than `/`
+///
+/// A pattern without a `/` is matched against the file name only, so
+/// `*.png` matches `img/logo.png`. A leading `/` anchors the pattern to
+/// the root and is otherwise ignored.
+///
+/// # Arguments
+///
+/// * `pattern` - The glob pattern.
+/// * `path` - The relative path, using `/` separators.
+///
+/// # Returns
+///
+/// * `bool` - `true` if the path matches the pattern.
+///
+/// # Examples
+///
+/// ```
+/// use html_generator::utils::glob_match;
+///
+/// assert!(glob_match("*.css", "css/site.css"));
+/// assert!(glob_match("images/**", "images/2025/a.png"));
+/// assert!(!glob_match("/*.css", "css/site.css"));
+/// ```
+pub fn glob_match(pattern: &str, path: &str) -> bool {
+    let path = path.trim_start_matches('/');
+    let (pattern, subject) = match pattern.strip_prefix('/') {
+        Some(anchored) => (anchored, path),
+        None if !pattern.contains('/') => {
+            (pattern, path.rsplit('/').next().unwrap_or(path))
+        }
+        None => (pattern, path),
+    };
+    let pattern: Vec<char> = pattern.chars().collect();
+    let subject: Vec<char> = subject.chars().collect();
+    glob_match_chars(&pattern, &subject)
+}
+
+/// Recursive matcher behind [`glob_match`].
+fn glob_match_chars(pattern: &[char], subject: &[char]) -> bool {
+    match pattern.first() {
+        None => subject.is_empty(),
+        Some('*') if pattern.get(1) == Some(&'*') => {
+            let rest = &pattern[2..];
+            if let Some(after_slash) = rest.strip_prefix(&['/']) {
+                if glob_match_chars(after_slash, subject) {
+                    return true;
+                }
+            }
+            (0..=subject.len())
+                .any(|i| glob_match_chars(rest, &subject[i..]))
+        }
+        Some('*') => {
+            let rest = &pattern[1..];
+            for i in 0..=subject.len() {
+                if glob_match_chars(rest, &subject[i..]) {
+                    return true;
+                }
+                if subject.get(i) == Some(&'/') {
+                    break;
+                }
+            }
+            false
+        }
+        Some('?') => {
+            matches!(subject.first(), Some(c) if *c != '/')
+                && glob_match_chars(&pattern[1..], &subject[1..])
+        }
+        Some(c) => {
+            subject.first() == Some(c)
+                && glob_match_chars(&pattern[1..], &subject[1..])
+        }
+    }
+}
+
+/// The characters of the HTML5 named character references, by name
+/// without the leading `&`, e.g. `amp;` and the legacy `amp`.
+static NAMED_ENTITIES: Lazy<HashMap<&'static str, &'static str>> =
+    Lazy::new(|| {
+        entities::ENTITIES
+            .iter()
+            .map(|entity| (&entity.entity[1..], entity.characters))
+            .collect()
+    });
+
+/// Length of the longest entity name, `CounterClockwiseContourIntegral`.
+const MAX_ENTITY_NAME: usize = 31;
+
+/// Characters of the numeric references 0x80 to 0x9F, which browsers
+/// read as Windows-1252.
+const WINDOWS_1252: [char; 32] = [
+    '\u{20AC}', '\u{81}', '\u{201A}', '\u{192}', '\u{201E}',
+    '\u{2026}', '\u{2020}', '\u{2021}', '\u{2C6}', '\u{2030}',
+    '\u{160}', '\u{2039}', '\u{152}', '\u{8D}', '\u{17D}', '\u{8F}',
+    '\u{90}', '\u{2018}', '\u{2019}', '\u{201C}', '\u{201D}',
+    '\u{2022}', '\u{2013}', '\u{2014}', '\u{2DC}', '\u{2122}',
+    '\u{161}', '\u{203A}', '\u{153}', '\u{9D}', '\u{17E}', '\u{178}',
+];
+
+/// Decodes the character references of HTML text.
+///
+/// All HTML5 named references are supported, including the legacy ones
+/// without a semicolon such as `&copy`, as well as decimal and
+/// hexadecimal numeric references. Numeric references are read as
+/// browsers do: invalid code points become U+FFFD and 0x80 to 0x9F are
+/// read as Windows-1252. A `&` starting no reference is kept.
+///
+/// # Arguments
+///
+/// * `text` - The text, with any tags already removed
+///
+/// # Returns
+///
+/// The decoded text, borrowed when it has no `&`.
+///
+/// # Examples
+///
+/// ```
+/// use html_generator::utils::decode_entities;
+///
+/// assert_eq!(
+///     decode_entities("Fish &amp; Chips &mdash; &euro;5 &#x2713; &NotEqualTilde;"),
+///     "Fish & Chips \u{2014} \u{20AC}5 \u{2713} \u{2242}\u{338}"
+/// );
+/// assert_eq!(decode_entities("AT&T &copy 2025"), "AT&T \u{A9} 2025");
+/// ```
+pub fn decode_entities(text: &str) -> Cow<'_, str> {
+    let mut ampersand = match text.find('&') {
+        Some(index) => index,
+        None => return Cow::Borrowed(text),
+    };
+    let mut decoded = String::with_capacity(text.len());
+    let mut copied = 0;
+    loop {
+        decoded.push_str(&text[copied..ampersand]);
+        copied = ampersand + 1;
+        match decode_reference(&text[copied..]) {
+            Some((characters, length)) => {
+                decoded.push_str(&characters);
+                copied += length;
+            }
+            None => decoded.push('&'),
+        }
+        ampersand = match text[copied..].find('&') {
+            Some(index) => copied + index,
+            None => break,
+        };
+    }
+    decoded.push_str(&text[copied..]);
+    Cow::Owned(decoded)
+}
+
+/// Decodes the character reference at the start of `reference`, which
+/// follows a `&`, returning its characters and length.
+fn decode_reference(
+    reference: &str,
+) -> Option<(Cow<'static, str>, usize)> {
+    if let Some(number) = reference.strip_prefix('#') {
+        let (digits, radix, prefix) =
+            match number.strip_prefix(['x', 'X']) {
+                Some(hex) => (hex, 16, 2),
+                None => (number, 10, 1),
+            };
+        let length = digits
+            .find(|c: char| !c.is_digit(radix))
+            .unwrap_or(digits.len());
+        if length == 0 {
+            return None;
+        }
+        let code = u32::from_str_radix(&digits[..length], radix)
+            .unwrap_or(u32::MAX);
+        let c = match code {
+            0x80..=0x9F => WINDOWS_1252[(code - 0x80) as usize],
+            code => char::from_u32(code)
+                .filter(|c| *c != '\0')
+                .unwrap_or(char::REPLACEMENT_CHARACTER),
+        };
+        let semicolon = usize::from(digits[length..].starts_with(';'));
+        return Some((
+            Cow::Owned(c.to_string()),
+            prefix + length + semicolon,
+        ));
+    }
+    let name_length = reference
+        .find(|c: char| !c.is_ascii_alphanumeric())
+        .unwrap_or(reference.len())
+        .min(MAX_ENTITY_NAME);
+    if reference[name_length..].starts_with(';') {
+        if let Some(characters) =
+            NAMED_ENTITIES.get(&reference[..=name_length])
+        {
+            return Some((Cow::Borrowed(characters), name_length + 1));
+        }
+    }
+    // Legacy references without a semicolon, longest first
+    (2..=name_length).rev().find_map(|length| {
+        NAMED_ENTITIES
+            .get(&reference[..length])
+            .map(|characters| (Cow::Borrowed(*characters), length))
+    })
+}
+
+/// Escapes text for HTML content and quoted attribute values.
+///
+/// `&`, `<`, `>`, `"` and `'` are written as `&amp;`, `&lt;`, `&gt;`,
+/// `&quot;` and `&#x27;`, and other characters are kept, so
+/// [`decode_entities`] restores the text.
+///
+/// # Arguments
+///
+/// * `text` - The text to escape
+///
+/// # Returns
+///
+/// The escaped text, borrowed when nothing needed escaping.
+///
+/// # Examples
+///
+/// ```
+/// use html_generator::utils::{decode_entities, encode_entities};
+///
+/// let text = r#"<a title="Fish & 'Chips'">"#;
+/// let encoded = encode_entities(text);
+/// assert_eq!(
+///     encoded,
+///     "&lt;a title=&quot;Fish &amp; &#x27;Chips&#x27;&quot;&gt;"
+/// );
+/// assert_eq!(decode_entities(&encoded), text);
+/// ```
+#[must_use]
+pub fn encode_entities(text: &str) -> Cow<'_, str> {
+    let first = match text.find(['&', '<', '>', '"', '\'']) {
+        Some(index) => index,
+        None => return Cow::Borrowed(text),
+    };
+    let mut encoded = String::with_capacity(text.len() + 16);
+    encoded.push_str(&text[..first]);
+    for c in text[first..].chars() {
+        match c {
+            '&' => encoded.push_str("&amp;"),
+            '<' => encoded.push_str("&lt;"),
+            '>' => encoded.push_str("&gt;"),
+            '"' => encoded.push_str("&quot;"),
+            '\'' => encoded.push_str("&#x27;"),
+            c => encoded.push(c),
+        }
+    }
+    Cow::Owned(encoded)
+}
+
+/// Computes a stable 64-bit FNV-1a hash of the given bytes.
+///
+/// The result is rendered as a 16-character lowercase hexadecimal
+/// string. Unlike `std::hash`, the value is identical across builds,
+/// platforms and Rust versions, which makes it suitable for cache keys
+/// and asset revisions that are persisted to disk.
+///
+/// # Arguments
+///
+/// * `bytes` - The data to hash.
+///
+/// # Returns
+///
+/// * `String` - The hexadecimal digest.
+pub(crate) fn content_hash(bytes: &[u8]) -> String {
+    const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    let hash = bytes.iter().fold(FNV_OFFSET, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(FNV_PRIME)
+    });
+    format!("{:016x}", hash)
+}
+
+/// Encodes bytes as standard, padded Base64 (RFC 4648).
+///
+/// # Arguments
+///
+/// * `bytes` - The data to encode.
+///
+/// # Returns
+///
+/// * `String` - The Base64 text.
+#[cfg(feature = "std")]
+pub(crate) fn base64_encode(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] =
+        b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut encoded = String::with_capacity((bytes.len() + 2) / 3 * 4);
+    for chunk in bytes.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = u32::from(b[0]) << 16
+            | u32::from(b[1]) << 8
+            | u32::from(b[2]);
+        for (index, shift) in [18, 12, 6, 0].iter().enumerate() {
+            if index <= chunk.len() {
+                encoded.push(char::from(
+                    ALPHABET[(n >> shift) as usize & 63],
+                ));
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+/// Returns the lowercase scheme and host of an external link.
+pub(crate) fn external_host(href: &str) -> Option<(String, String)> {
+    let (scheme, rest) = match href.split_once("://") {
+        Some((scheme, rest)) => (scheme.to_ascii_lowercase(), rest),
+        None => ("https".to_string(), href.strip_prefix("//")?),
+    };
+    if scheme != "http" && scheme != "https" {
+        return None;
+    }
+    let authority =
+        rest.split(['/', '?', '#']).next().unwrap_or_default();
+    let host = authority.rsplit('@').next().unwrap_or_default();
+    let host = match host.rfind(':') {
+        Some(index) if !host.ends_with(']') => &host[..index],
+        _ => host,
+    };
+    let host = host.trim_end_matches('.').to_ascii_lowercase();
+    if host.is_empty() {
+        None
+    } else {
+        Some((scheme, host))
+    }
+}
+
+/// Returns `true` if `host` is `domain` or one of its subdomains.
+pub(crate) fn domain_matches(host: &str, domain: &str) -> bool {
+    let domain = domain.trim_start_matches("*.").trim_matches('.');
+    !domain.is_empty()
+        && (host.eq_ignore_ascii_case(domain)
+            || host.len() > domain.len()
+                && host.as_bytes()[host.len() - domain.len() - 1]
+                    == b'.'
+                && host[host.len() - domain.len()..]
+                    .eq_ignore_ascii_case(domain))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -354,6 +917,16 @@ fn test_valid_front_matter_with_extra_content() {
             assert_eq!(result.unwrap(), "# Title\n\nContent");
         }
 
+        #[test]
+        fn test_toml_and_json_front_matter() {
+            let toml = "+++\ntitle = \"Page\"\n+++\n\n# Title";
+            assert_eq!(extract_front_matter(toml).unwrap(), "# Title");
+            let json = "{ \"title\": \"Page\" }\n# Title";
+            assert_eq!(extract_front_matter(json).unwrap(), "# Title");
+            let text = "{braces} in text";
+            assert_eq!(extract_front_matter(text).unwrap(), text);
+        }
+
         #[test]
         fn test_extract_front_matter_with_mid_document_delimiter() {
             let content = "# Title\nContent\n---\nkey: value\n---";
@@ -625,6 +1198,16 @@ fn test_generate_id_with_numeric_content() {
             assert_eq!(result, "12345");
         }
 
+        #[test]
+        fn test_generate_id_with_unicode_content() {
+            assert_eq!(generate_id("Über -- Straße!"), "über-straße");
+            assert_eq!(generate_id("ΟΔΟΣ"), "οδο\u{3c2}");
+            assert_eq!(
+                generate_id("日本語 の 見出し"),
+                "日本語-の-見出し"
+            );
+        }
+
         #[test]
         fn test_is_valid_language_code() {
             assert!(is_valid_language_code("en"));
@@ -646,12 +1229,16 @@ fn test_is_valid_language_code_non_ascii() {
         /// Additional tests for `extract_front_matter` function.
         #[test]
         fn test_extract_front_matter_empty_delimiters() {
-            let content = "------\n# Missing proper front matter";
+            let content = "---\n# Missing proper front matter";
             let result = extract_front_matter(content);
             assert!(matches!(
                 result,
                 Err(HtmlError::InvalidFrontMatterFormat(_))
             ));
+
+            // A thematic break is not a front matter delimiter
+            let content = "------\n# Missing proper front matter";
+            assert_eq!(extract_front_matter(content).unwrap(), content);
         }
 
         #[test]
@@ -757,4 +1344,250 @@ fn test_generate_id_symbols_only() {
             assert_eq!(result, "");
         }
     }
+
+    /// Tests for `content_hash` function.
+    mod slugify_path_tests {
+        use super::*;
+
+        #[test]
+        fn test_transliterates_non_ascii() {
+            assert_eq!(slugify_path("Ça va? Ærø"), "ca-va-aero");
+            assert_eq!(slugify_path("Łódź Æon"), "lodz-aeon");
+        }
+
+        #[test]
+        fn test_reserved_names() {
+            assert_eq!(slugify_path("nul"), "nul-page");
+            assert_eq!(slugify_path("LPT1"), "lpt1-page");
+            assert_eq!(slugify_path("console"), "console");
+        }
+
+        #[test]
+        fn test_length_is_limited() {
+            let slug = slugify_path(&"word ".repeat(40));
+            assert!(slug.len() <= MAX_SLUG_LENGTH);
+            assert!(!slug.ends_with('-'));
+        }
+
+        #[test]
+        fn test_path_separators_are_removed() {
+            assert_eq!(slugify_path("../etc/passwd"), "etc-passwd");
+            assert_eq!(slugify_path("a\\b:c"), "a-b-c");
+        }
+
+        #[test]
+        fn test_collisions_get_suffixes() {
+            let mut slugger = PathSlugger::new();
+            assert_eq!(slugger.slugify("Intro"), "intro");
+            assert_eq!(slugger.slugify("intro"), "intro-2");
+            assert_eq!(slugger.slugify("INTRO!"), "intro-3");
+            assert_eq!(slugger.slugify("intro-2"), "intro-2-2");
+        }
+
+        #[cfg(feature = "std")]
+        #[test]
+        fn test_front_matter_value() {
+            assert_eq!(
+                front_matter_value(
+                    "---\ntitle: \"My Page\"\n---\n# Hi",
+                    "title"
+                ),
+                Some("My Page".to_string())
+            );
+            assert_eq!(
+                front_matter_value(
+                    "---\nauthor: me\nslug: ''\n---\n# Hi",
+                    "title"
+                ),
+                None
+            );
+            assert_eq!(
+                front_matter_value(
+                    "---\nauthor: me\nslug: ''\n---\n# Hi",
+                    "slug"
+                ),
+                None
+            );
+            assert_eq!(
+                front_matter_value("title: x\n# Hi", "title"),
+                None
+            );
+            assert_eq!(
+                front_matter_value(
+                    "+++\nslug = \"about\"\n+++\n# Hi",
+                    "slug"
+                ),
+                Some("about".to_string())
+            );
+            assert_eq!(
+                front_matter_value("{\"weight\": 2}\n# Hi", "weight"),
+                Some("2".to_string())
+            );
+        }
+    }
+
+    mod glob_match_tests {
+        use super::*;
+
+        #[test]
+        fn test_file_name_patterns() {
+            assert!(glob_match("*.png", "logo.png"));
+            assert!(glob_match("*.png", "img/deep/logo.png"));
+            assert!(glob_match("logo.???", "img/logo.svg"));
+            assert!(!glob_match("*.png", "logo.png.bak"));
+        }
+
+        #[test]
+        fn test_path_patterns() {
+            assert!(glob_match("img/*.png", "img/a.png"));
+            assert!(!glob_match("img/*.png", "img/x/a.png"));
+            assert!(glob_match("img/**/*.png", "img/a.png"));
+            assert!(glob_match("img/**/*.png", "img/x/y/a.png"));
+            assert!(glob_match("**/drafts/**", "blog/drafts/a.md"));
+            assert!(glob_match("node_modules/**", "node_modules/a/b"));
+        }
+
+        #[test]
+        fn test_anchored_patterns() {
+            assert!(glob_match("/robots.txt", "robots.txt"));
+            assert!(!glob_match("/robots.txt", "sub/robots.txt"));
+            assert!(glob_match("robots.txt", "sub/robots.txt"));
+        }
+    }
+
+    mod entity_tests {
+        use super::*;
+
+        #[test]
+        fn test_decodes_named_references() {
+            assert_eq!(entities::ENTITIES.len(), 2231);
+            assert_eq!(
+                decode_entities("&lt;&gt;&quot;&apos;&nbsp;&hellip;"),
+                "<>\"'\u{A0}\u{2026}"
+            );
+            assert_eq!(
+                decode_entities("&CounterClockwiseContourIntegral;"),
+                "\u{2233}"
+            );
+            assert_eq!(decode_entities("&fjlig;"), "fj");
+            assert_eq!(
+                decode_entities("&notin; &notit;"),
+                "\u{2209} \u{AC}it;"
+            );
+            assert_eq!(decode_entities("&AMP &ampx"), "& &x");
+        }
+
+        #[test]
+        fn test_decodes_numeric_references() {
+            assert_eq!(decode_entities("&#65;&#x42;&#X43"), "ABC");
+            assert_eq!(
+                decode_entities("&#x80;&#150;"),
+                "\u{20AC}\u{2013}"
+            );
+            assert_eq!(
+                decode_entities("&#0;&#xD800;&#1114112;&#99999999999;"),
+                "\u{FFFD}".repeat(4)
+            );
+        }
+
+        #[test]
+        fn test_keeps_text_without_references() {
+            assert!(matches!(
+                decode_entities("plain"),
+                Cow::Borrowed(_)
+            ));
+            assert_eq!(
+                decode_entities("a & b &; &# &#x; &bogus; &"),
+                "a & b &; &# &#x; &bogus; &"
+            );
+            assert!(matches!(
+                encode_entities("plain"),
+                Cow::Borrowed(_)
+            ));
+        }
+
+        #[test]
+        fn test_encode_round_trips() {
+            let text = "<p class=\"a\">Tom & Jerry's é</p>";
+            assert_eq!(
+                encode_entities(text),
+                "&lt;p class=&quot;a&quot;&gt;Tom &amp; Jerry&#x27;s é&lt;/p&gt;"
+            );
+            assert_eq!(decode_entities(&encode_entities(text)), text);
+        }
+    }
+
+    mod content_hash_tests {
+        use super::*;
+
+        #[test]
+        fn test_content_hash_known_vectors() {
+            assert_eq!(content_hash(b""), "cbf29ce484222325");
+            assert_eq!(content_hash(b"a"), "af63dc4c8601ec8c");
+        }
+
+        #[test]
+        fn test_content_hash_differs_for_different_input() {
+            assert_ne!(
+                content_hash(b"page one"),
+                content_hash(b"page two")
+            );
+            assert_eq!(content_hash(b"same"), content_hash(b"same"));
+        }
+    }
+
+    #[cfg(feature = "std")]
+    mod base64_tests {
+        use super::*;
+
+        #[test]
+        fn test_base64_rfc4648_vectors() {
+            let vectors = [
+                ("", ""),
+                ("f", "Zg=="),
+                ("fo", "Zm8="),
+                ("foo", "Zm9v"),
+                ("foob", "Zm9vYg=="),
+                ("fooba", "Zm9vYmE="),
+                ("foobar", "Zm9vYmFy"),
+            ];
+            for (input, expected) in vectors {
+                assert_eq!(base64_encode(input.as_bytes()), expected);
+            }
+            assert_eq!(base64_encode(&[0xff, 0xfe]), "//4=");
+        }
+    }
+
+    mod extract_fragment_tests {
+        use super::*;
+
+        #[test]
+        fn test_extracts_first_match_from_documents() {
+            let html = concat!(
+                "<!DOCTYPE html><html><body><div id=\"content\">",
+                "<p>One <em>two</em></p></div><div id=\"content\">x</div>",
+                "</body></html>"
+            );
+            assert_eq!(
+                extract_fragment(html, "#content").unwrap(),
+                "<div id=\"content\"><p>One <em>two</em></p></div>"
+            );
+            assert_eq!(
+                extract_fragment(html, "body > div p").unwrap(),
+                "<p>One <em>two</em></p>"
+            );
+        }
+
+        #[test]
+        fn test_reports_missing_and_invalid_selectors() {
+            assert!(matches!(
+                extract_fragment("<p>text</p>", "article"),
+                Err(HtmlError::MissingHtmlElement(_))
+            ));
+            assert!(matches!(
+                extract_fragment("<p>text</p>", "p[["),
+                Err(HtmlError::SelectorParseError(selector, _)) if selector == "p[["
+            ));
+        }
+    }
 }
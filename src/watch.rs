@@ -0,0 +1,312 @@
+// Copyright © 2025 HTML Generator. All rights reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Regeneration of HTML when Markdown files change.
+//!
+//! Available with the `watch` feature. [`watch_markdown`] converts a
+//! Markdown file, passes the result to a callback, and converts it again
+//! whenever the file or, with
+//! [`MarkdownConfig::expand_includes`](crate::MarkdownConfig::expand_includes),
+//! one of its [includes](crate::includes) changes. Bursts of changes,
+//! such as an editor writing a temporary file and renaming it, are
+//! debounced into one conversion. Changes to other files are ignored,
+//! unless the last conversion failed, in which case any change in the
+//! directory of the file is tried.
+//!
+//! Watching stops when the returned [`MarkdownWatcher`] is dropped.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use html_generator::watch::watch_markdown;
+//! use html_generator::MarkdownConfig;
+//!
+//! let _watcher = watch_markdown("docs/guide.md", MarkdownConfig::default(), |result| {
+//!     match result {
+//!         Ok(html) => println!("{} bytes", html.len()),
+//!         Err(error) => eprintln!("{}", error),
+//!     }
+//! })?;
+//! std::thread::park();
+//! # Ok::<(), html_generator::error::HtmlError>(())
+//! ```
+
+use crate::batch::SymlinkPolicy;
+use crate::conversion::convert;
+use crate::error::{HtmlError, Result};
+use crate::includes::expand_includes_tracked;
+use crate::MarkdownConfig;
+use notify::{
+    Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher,
+};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// How long changes are collected before converting again.
+pub const DEFAULT_DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// Watches a Markdown file, see [`watch_markdown`].
+///
+/// Dropping it stops watching and waits for a running conversion to
+/// finish.
+pub struct MarkdownWatcher {
+    path: PathBuf,
+    watcher: Option<RecommendedWatcher>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl MarkdownWatcher {
+    /// Returns the canonical path of the watched file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl fmt::Debug for MarkdownWatcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MarkdownWatcher")
+            .field("path", &self.path)
+            .finish()
+    }
+}
+
+impl Drop for MarkdownWatcher {
+    fn drop(&mut self) {
+        // Dropping the watcher disconnects the channel, ending the thread.
+        drop(self.watcher.take());
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Converts `path` now and whenever it or its includes change, with a
+/// debounce of [`DEFAULT_DEBOUNCE`].
+///
+/// # Arguments
+///
+/// * `path` - The Markdown file
+/// * `config` - The configuration of every conversion
+/// * `callback` - Receives the HTML, or the error, of each conversion;
+///   it runs on a background thread
+///
+/// # Returns
+///
+/// The watcher, which stops watching when dropped.
+///
+/// # Errors
+///
+/// Returns [`HtmlError::Io`] if the file does not exist or cannot be
+/// watched. Conversion errors are passed to `callback` instead.
+pub fn watch_markdown<F>(
+    path: impl AsRef<Path>,
+    config: MarkdownConfig,
+    callback: F,
+) -> Result<MarkdownWatcher>
+where
+    F: FnMut(Result<String>) + Send + 'static,
+{
+    watch_markdown_with_debounce(
+        path,
+        config,
+        DEFAULT_DEBOUNCE,
+        callback,
+    )
+}
+
+/// Watches `path` like [`watch_markdown`], collecting changes for
+/// `debounce` before converting again.
+///
+/// # Errors
+///
+/// Returns the same errors as [`watch_markdown`].
+pub fn watch_markdown_with_debounce<F>(
+    path: impl AsRef<Path>,
+    mut config: MarkdownConfig,
+    debounce: Duration,
+    mut callback: F,
+) -> Result<MarkdownWatcher>
+where
+    F: FnMut(Result<String>) + Send + 'static,
+{
+    let path = fs::canonicalize(path.as_ref())?;
+    let root = path.parent().map(Path::to_path_buf).unwrap_or_default();
+    crate::set_document_source(&mut config.html_config, &Some(&path));
+
+    let (sender, events) = mpsc::channel();
+    let mut watcher =
+        notify::recommended_watcher(sender).map_err(watch_error)?;
+    watcher
+        .watch(&root, RecursiveMode::Recursive)
+        .map_err(watch_error)?;
+
+    let file = path.clone();
+    let thread = thread::Builder::new()
+        .name("html-generator-watch".to_string())
+        .spawn(move || {
+            let mut dependencies =
+                regenerate(&file, &root, &config, &mut callback);
+            while let Ok(event) = events.recv() {
+                let mut changed = affects(&event, &dependencies);
+                loop {
+                    match events.recv_timeout(debounce) {
+                        Ok(event) => {
+                            changed |= affects(&event, &dependencies);
+                        }
+                        Err(RecvTimeoutError::Timeout) => break,
+                        Err(RecvTimeoutError::Disconnected) => return,
+                    }
+                }
+                if changed {
+                    dependencies = regenerate(
+                        &file,
+                        &root,
+                        &config,
+                        &mut callback,
+                    );
+                }
+            }
+        })?;
+
+    Ok(MarkdownWatcher {
+        path,
+        watcher: Some(watcher),
+        thread: Some(thread),
+    })
+}
+
+/// Converts `path`, passes the result to `callback` and returns the
+/// files the conversion read, or nothing if it failed.
+fn regenerate<F>(
+    path: &Path,
+    root: &Path,
+    config: &MarkdownConfig,
+    callback: &mut F,
+) -> Vec<PathBuf>
+where
+    F: FnMut(Result<String>),
+{
+    let mut dependencies = vec![path.to_path_buf()];
+    let result = fs::read_to_string(path)
+        .map_err(HtmlError::Io)
+        .and_then(|content| {
+            if !config.expand_includes {
+                return Ok(content);
+            }
+            let (content, included) = expand_includes_tracked(
+                &content,
+                root,
+                path.file_name().map(Path::new),
+                config.max_include_depth,
+                SymlinkPolicy::default(),
+            )?;
+            dependencies
+                .extend(included.iter().map(|file| root.join(file)));
+            Ok(content)
+        })
+        .and_then(|content| {
+            convert(&content, &config.conversion_config())
+        });
+    let failed = result.is_err();
+    callback(result);
+    if failed {
+        Vec::new()
+    } else {
+        dependencies
+    }
+}
+
+/// Returns `true` if `event` changes one of `dependencies`, or any file
+/// if there are none.
+fn affects(
+    event: &notify::Result<Event>,
+    dependencies: &[PathBuf],
+) -> bool {
+    let event = match event {
+        Ok(event) => event,
+        Err(_) => return false,
+    };
+    if matches!(event.kind, EventKind::Access(_)) {
+        return false;
+    }
+    dependencies.is_empty()
+        || event.paths.iter().any(|path| {
+            dependencies.contains(path)
+                || fs::canonicalize(path)
+                    .map_or(false, |path| dependencies.contains(&path))
+        })
+}
+
+/// Converts an error of the file watcher.
+fn watch_error(error: notify::Error) -> HtmlError {
+    HtmlError::Io(io::Error::new(
+        io::ErrorKind::Other,
+        format!("Failed to watch files: {}", error),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc::Receiver;
+    use tempfile::tempdir;
+
+    const TIMEOUT: Duration = Duration::from_secs(10);
+
+    /// Returns the next HTML produced by the watcher.
+    fn next_html(results: &Receiver<Result<String>>) -> String {
+        results.recv_timeout(TIMEOUT).unwrap().unwrap()
+    }
+
+    #[test]
+    fn test_regenerates_on_changes_to_file_and_includes() {
+        let dir = tempdir().unwrap();
+        let page = dir.path().join("page.md");
+        fs::write(&page, "# Page\n{{include \"part.md\"}}\n").unwrap();
+        fs::write(dir.path().join("part.md"), "First").unwrap();
+        let config = MarkdownConfig {
+            expand_includes: true,
+            ..MarkdownConfig::default()
+        };
+
+        let (sender, results) = mpsc::channel();
+        let watcher = watch_markdown_with_debounce(
+            &page,
+            config,
+            Duration::from_millis(50),
+            move |result| {
+                let _ = sender.send(result);
+            },
+        )
+        .unwrap();
+        assert!(watcher.path().ends_with("page.md"));
+        assert!(next_html(&results).contains("<p>First</p>"));
+
+        fs::write(dir.path().join("part.md"), "Second").unwrap();
+        assert!(next_html(&results).contains("<p>Second</p>"));
+
+        fs::write(&page, "# Renamed\n").unwrap();
+        assert!(next_html(&results).contains("Renamed"));
+
+        fs::write(dir.path().join("other.md"), "Other").unwrap();
+        assert!(results
+            .recv_timeout(Duration::from_millis(500))
+            .is_err());
+    }
+
+    #[test]
+    fn test_missing_file() {
+        let dir = tempdir().unwrap();
+        let result = watch_markdown(
+            dir.path().join("missing.md"),
+            MarkdownConfig::default(),
+            |_| {},
+        );
+        assert!(matches!(result, Err(HtmlError::Io(_))));
+    }
+}
@@ -0,0 +1,356 @@
+// Copyright © 2025 HTML Generator. All rights reserved.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Vertical writing for Chinese, Japanese and Korean text.
+//!
+//! A vertical [`HtmlConfig::writing_mode`] wraps the output in a
+//! `<div>` with a `writing-mode-*` class, styled by [`STYLESHEET`], so
+//! the page keeps to a strict `style-src` policy.
+//!
+//! Physical layout, such as `align="left"` on table cells or
+//! `margin-left` in a `style` attribute, stays horizontal when the text
+//! turns vertical. [`check_writing_mode`] reports such markup with its
+//! logical replacement; conversions in a vertical writing mode report
+//! these conflicts as diagnostics.
+//!
+//! [`HtmlConfig::writing_mode`]: crate::HtmlConfig::writing_mode
+//!
+//! # Examples
+//!
+//! ```
+//! use html_generator::writing_mode::{check_writing_mode, WritingMode};
+//! use html_generator::{conversion::convert, HtmlConfig};
+//!
+//! let config = HtmlConfig {
+//!     writing_mode: WritingMode::VerticalRl,
+//!     ..HtmlConfig::default()
+//! };
+//! let html = convert("| 名前 |\n|:-----|\n| 山田 |", &config)?;
+//! assert!(html.starts_with(r#"<div class="writing-mode-vertical-rl">"#));
+//!
+//! let conflicts = check_writing_mode(&html);
+//! assert_eq!(conflicts.len(), 2);
+//! assert_eq!(conflicts[0].replacement, "text-align: start");
+//! # Ok::<(), html_generator::error::HtmlError>(())
+//! ```
+
+use crate::diagnostics::Diagnostic;
+use crate::error::{HtmlError, Result};
+use crate::patterns::writing_mode::{ATTRIBUTE_REGEX, TAG_REGEX};
+use std::fmt;
+use std::str::FromStr;
+
+/// CSS for the classes written by [`apply_writing_mode`].
+pub const STYLESHEET: &str = "\
+.writing-mode-vertical-rl { writing-mode: vertical-rl; }
+.writing-mode-vertical-lr { writing-mode: vertical-lr; }
+.writing-mode-vertical-rl pre,
+.writing-mode-vertical-lr pre { writing-mode: horizontal-tb; }
+";
+
+/// Physical CSS properties and their logical equivalents in a
+/// horizontal, left-to-right page.
+const PHYSICAL_PROPERTIES: &[(&str, &str)] = &[
+    ("width", "inline-size"),
+    ("height", "block-size"),
+    ("min-width", "min-inline-size"),
+    ("min-height", "min-block-size"),
+    ("max-width", "max-inline-size"),
+    ("max-height", "max-block-size"),
+    ("left", "inset-inline-start"),
+    ("right", "inset-inline-end"),
+    ("top", "inset-block-start"),
+    ("bottom", "inset-block-end"),
+    ("margin-left", "margin-inline-start"),
+    ("margin-right", "margin-inline-end"),
+    ("margin-top", "margin-block-start"),
+    ("margin-bottom", "margin-block-end"),
+    ("padding-left", "padding-inline-start"),
+    ("padding-right", "padding-inline-end"),
+    ("padding-top", "padding-block-start"),
+    ("padding-bottom", "padding-block-end"),
+    ("border-left", "border-inline-start"),
+    ("border-right", "border-inline-end"),
+    ("border-top", "border-block-start"),
+    ("border-bottom", "border-block-end"),
+    ("overflow-x", "overflow-inline"),
+    ("overflow-y", "overflow-block"),
+];
+
+/// Direction in which lines of text run.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum WritingMode {
+    /// Horizontal lines, stacked top to bottom.
+    HorizontalTb,
+    /// Vertical lines, stacked right to left, as in Japanese novels.
+    VerticalRl,
+    /// Vertical lines, stacked left to right, as in Mongolian.
+    VerticalLr,
+}
+
+impl WritingMode {
+    /// Returns `true` for the vertical writing modes.
+    pub fn is_vertical(&self) -> bool {
+        *self != WritingMode::HorizontalTb
+    }
+}
+
+impl Default for WritingMode {
+    fn default() -> Self {
+        WritingMode::HorizontalTb
+    }
+}
+
+impl fmt::Display for WritingMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            WritingMode::HorizontalTb => "horizontal-tb",
+            WritingMode::VerticalRl => "vertical-rl",
+            WritingMode::VerticalLr => "vertical-lr",
+        })
+    }
+}
+
+impl FromStr for WritingMode {
+    type Err = HtmlError;
+
+    /// Parses `horizontal-tb`, `vertical-rl` or `vertical-lr`.
+    fn from_str(value: &str) -> Result<Self> {
+        match value {
+            "horizontal-tb" => Ok(WritingMode::HorizontalTb),
+            "vertical-rl" => Ok(WritingMode::VerticalRl),
+            "vertical-lr" => Ok(WritingMode::VerticalLr),
+            _ => Err(HtmlError::InvalidInput(format!(
+                "Unknown writing mode: {}",
+                value
+            ))),
+        }
+    }
+}
+
+/// Wraps `html` in a `<div>` with the class of a vertical `mode`.
+///
+/// # Arguments
+///
+/// * `html` - The HTML fragment
+/// * `mode` - The writing mode; horizontal leaves `html` unchanged
+///
+/// # Returns
+///
+/// The wrapped fragment.
+pub fn apply_writing_mode(html: &str, mode: WritingMode) -> String {
+    if mode.is_vertical() {
+        format!(r#"<div class="writing-mode-{}">{}</div>"#, mode, html)
+    } else {
+        html.to_string()
+    }
+}
+
+/// Markup that assumes horizontal text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WritingModeConflict {
+    /// Name of the element, e.g. `th`.
+    pub element: String,
+    /// The physical declaration, e.g. `align="left"` or
+    /// `margin-left: 1em`.
+    pub declaration: String,
+    /// The logical property to use instead, e.g. `text-align: start`.
+    pub replacement: String,
+}
+
+impl WritingModeConflict {
+    /// Converts the finding into a warning [`Diagnostic`] with the code
+    /// `writing_mode_conflict`.
+    pub fn to_diagnostic(&self) -> Diagnostic {
+        Diagnostic::warning("writing_mode_conflict", self.to_string())
+    }
+}
+
+impl fmt::Display for WritingModeConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "<{}> uses `{}`, which stays horizontal in vertical text; use `{}`",
+            self.element, self.declaration, self.replacement
+        )
+    }
+}
+
+/// Finds the `align` attributes and inline styles of `html` that
+/// assume horizontal text.
+///
+/// # Arguments
+///
+/// * `html` - The HTML to check
+///
+/// # Returns
+///
+/// The conflicts, in document order.
+pub fn check_writing_mode(html: &str) -> Vec<WritingModeConflict> {
+    let mut conflicts = Vec::new();
+    for tag in TAG_REGEX.captures_iter(html) {
+        let element = tag[1].to_ascii_lowercase();
+        for attribute in ATTRIBUTE_REGEX.captures_iter(&tag[2]) {
+            let value = attribute
+                .get(2)
+                .or_else(|| attribute.get(3))
+                .or_else(|| attribute.get(4))
+                .map_or("", |m| m.as_str());
+            match attribute[1].to_ascii_lowercase().as_str() {
+                "align" => {
+                    if let Some(side) = logical_side(value) {
+                        conflicts.push(WritingModeConflict {
+                            element: element.clone(),
+                            declaration: format!(
+                                r#"align="{}""#,
+                                value
+                            ),
+                            replacement: format!(
+                                "text-align: {}",
+                                side
+                            ),
+                        });
+                    }
+                }
+                "style" => {
+                    for declaration in value.split(';') {
+                        if let Some(replacement) =
+                            logical_declaration(declaration)
+                        {
+                            conflicts.push(WritingModeConflict {
+                                element: element.clone(),
+                                declaration: declaration
+                                    .trim()
+                                    .to_string(),
+                                replacement,
+                            });
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    conflicts
+}
+
+/// Returns the logical side for the physical side `value`.
+fn logical_side(value: &str) -> Option<&'static str> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "left" => Some("start"),
+        "right" => Some("end"),
+        _ => None,
+    }
+}
+
+/// Returns the logical replacement of a physical CSS declaration.
+fn logical_declaration(declaration: &str) -> Option<String> {
+    let (property, value) = declaration.split_once(':')?;
+    let property = property.trim().to_ascii_lowercase();
+    let value = value.trim();
+    match property.as_str() {
+        "text-align" => logical_side(value)
+            .map(|side| format!("text-align: {}", side)),
+        "float" | "clear" => logical_side(value)
+            .map(|side| format!("{}: inline-{}", property, side)),
+        _ => PHYSICAL_PROPERTIES
+            .iter()
+            .find(|(physical, _)| {
+                property == *physical
+                    || property
+                        .strip_prefix(physical)
+                        .map_or(false, |rest| rest.starts_with('-'))
+                        && physical.starts_with("border-")
+            })
+            .map(|(physical, logical)| {
+                format!(
+                    "{}{}: {}",
+                    logical,
+                    &property[physical.len()..],
+                    value
+                )
+            }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conversion::convert;
+    use crate::HtmlConfig;
+
+    #[test]
+    fn test_mode_round_trip_and_wrapper() {
+        for mode in [
+            WritingMode::HorizontalTb,
+            WritingMode::VerticalRl,
+            WritingMode::VerticalLr,
+        ] {
+            assert_eq!(
+                mode.to_string().parse::<WritingMode>().unwrap(),
+                mode
+            );
+        }
+        assert!("vertical".parse::<WritingMode>().is_err());
+        assert_eq!(
+            apply_writing_mode("<p>x</p>", WritingMode::HorizontalTb),
+            "<p>x</p>"
+        );
+        assert_eq!(
+            apply_writing_mode("<p>x</p>", WritingMode::VerticalLr),
+            r#"<div class="writing-mode-vertical-lr"><p>x</p></div>"#
+        );
+    }
+
+    #[test]
+    fn test_physical_layout_is_reported() {
+        let conflicts = check_writing_mode(concat!(
+            r#"<div style="margin-left: 1em; color: red;WIDTH:50%">"#,
+            r#"<td align=right><img style='float: left' src="a.png">"#,
+            r#"<p style="border-top-width: 2px; text-align: center">"#,
+            r#"<span style="border-top-color: red; margin: 0">"#
+        ));
+        let found: Vec<_> = conflicts
+            .iter()
+            .map(|c| (c.element.as_str(), c.replacement.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("div", "margin-inline-start: 1em"),
+                ("div", "inline-size: 50%"),
+                ("td", "text-align: end"),
+                ("img", "float: inline-start"),
+                ("p", "border-block-start-width: 2px"),
+                ("span", "border-block-start-color: red"),
+            ]
+        );
+        assert_eq!(conflicts[0].declaration, "margin-left: 1em");
+        assert_eq!(
+            conflicts[2].to_diagnostic().code,
+            "writing_mode_conflict"
+        );
+    }
+
+    #[test]
+    fn test_generated_widgets_are_checked() {
+        let config = HtmlConfig {
+            writing_mode: WritingMode::VerticalRl,
+            generate_toc: true,
+            enhance_tables: true,
+            ..HtmlConfig::default()
+        };
+        let html = convert(
+            "# 見出し\n\n## 節\n\n| 左 | 中 | 右 |\n|:--|:-:|--:|\n| 1 | 2 | 3 |\n\n```rust\nfn main() {}\n```\n",
+            &config,
+        )
+        .unwrap();
+        assert!(html
+            .starts_with(r#"<div class="writing-mode-vertical-rl">"#));
+        let conflicts = check_writing_mode(&html);
+        assert!(conflicts
+            .iter()
+            .all(|c| c.element == "th" || c.element == "td"));
+        assert_eq!(conflicts.len(), 4);
+    }
+}
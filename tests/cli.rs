@@ -0,0 +1,128 @@
+//! Tests of the `html-generator` command line tool.
+
+#![cfg(feature = "cli")]
+
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+/// Runs the tool with `args`, writing `input` to its standard input.
+fn run(args: &[&str], input: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_html-generator"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+#[test]
+fn test_convert_from_stdin() {
+    let output = run(&["convert"], "# Hello\n\nWorld");
+    assert_eq!(output.status.code(), Some(0));
+    assert!(stdout(&output).contains("<h1"));
+    assert!(stdout(&output).contains("<p>World</p>"));
+}
+
+#[test]
+fn test_convert_with_config_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("config.json");
+    std::fs::write(&config, r#"{"generate_toc": true}"#).unwrap();
+    let input = dir.path().join("page.md");
+    std::fs::write(&input, "# One\n\n## Two\n").unwrap();
+    let html = dir.path().join("page.html");
+
+    let output = run(
+        &[
+            "convert",
+            input.to_str().unwrap(),
+            "--config",
+            config.to_str().unwrap(),
+            "-o",
+            html.to_str().unwrap(),
+        ],
+        "",
+    );
+    assert_eq!(output.status.code(), Some(0));
+    assert!(std::fs::read_to_string(&html).unwrap().contains("<nav"));
+
+    std::fs::write(&config, "{").unwrap();
+    let output =
+        run(&["convert", "--config", config.to_str().unwrap()], "# A");
+    assert_eq!(output.status.code(), Some(3));
+    assert!(String::from_utf8_lossy(&output.stderr)
+        .contains("Invalid configuration file"));
+}
+
+#[test]
+fn test_validate_wcag_exit_codes() {
+    let output = run(&["validate-wcag"], "<p>Accessible</p>");
+    assert_eq!(output.status.code(), Some(0));
+
+    let output =
+        run(&["validate-wcag", "--fail-on", "warning"], "<p>A</p>");
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stdout(&output).contains("[language_declaration]"));
+
+    let output = run(
+        &["validate-wcag"],
+        "<html lang=\"en\">\n<h1>A</h1><h3>B</h3>",
+    );
+    assert!(stdout(&output)
+        .starts_with("stdin:2:11: warning [heading_structure]"));
+
+    let output = run(&["validate-wcag", "--level", "z"], "");
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn test_validate_wcag_formats() {
+    let output =
+        run(&["validate-wcag", "--format", "json"], "<p>A</p>");
+    let json: serde_json::Value =
+        serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(json["issues"][0]["issue_type"], "language_declaration");
+
+    let output = run(
+        &["validate-wcag", "--format", "sarif", "--fail-on", "warning"],
+        "<p>A</p>",
+    );
+    assert_eq!(output.status.code(), Some(1));
+    let sarif: serde_json::Value =
+        serde_json::from_str(&stdout(&output)).unwrap();
+    let result = &sarif["runs"][0]["results"][0];
+    assert_eq!(result["ruleId"], "language_declaration");
+    assert_eq!(
+        result["locations"][0]["physicalLocation"]["artifactLocation"]
+            ["uri"],
+        "stdin"
+    );
+}
+
+#[test]
+fn test_minify_seo_and_toc() {
+    let output = run(&["minify"], "<p>\n  Hi\n</p>\n");
+    assert_eq!(stdout(&output).trim(), "<p>Hi</p>");
+
+    let html = "<html><head><title>Page</title></head><body><p>About the page.</p></body></html>";
+    let output = run(&["seo"], html);
+    assert_eq!(output.status.code(), Some(0));
+    assert!(stdout(&output).contains("<meta"));
+
+    let output = run(&["toc"], "---\ntitle: T\n---\n# One\n\n## Two\n");
+    assert_eq!(output.status.code(), Some(0));
+    assert!(stdout(&output).contains("Two"));
+    assert!(!stdout(&output).contains("title: T"));
+}
@@ -31,6 +31,8 @@
 //!
 //! Ensure that the `html_generator` library is correctly configured and that all dependencies are installed before running the tests.
 
+#![cfg(feature = "std")]
+
 use html_generator::{
     markdown_file_to_html, markdown_to_html, MarkdownConfig,
     OutputDestination,
@@ -0,0 +1,32 @@
+#!/bin/bash
+
+# Download the Unicode emoji data for a given version into data/.
+#
+# Usage: tools/update_emoji_data.sh <version>   (e.g. 15.1)
+#
+# The file is saved as data/emoji-data-<version>.txt and can be loaded
+# with `EmojiCatalog::from_file`. Only single code point and sequence
+# rows are used; code point ranges are skipped by the parser.
+
+set -euo pipefail
+
+version="${1:-}"
+if [[ -z "${version}" ]]; then
+    echo "Usage: $0 <unicode-emoji-version>" >&2
+    exit 1
+fi
+
+data_dir="$(dirname "$0")/../data"
+target="${data_dir}/emoji-data-${version}.txt"
+url="https://www.unicode.org/Public/${version}.0/ucd/emoji/emoji-data.txt"
+
+# Emoji versions before 13.0 were published separately from the UCD.
+major="${version%%.*}"
+if [[ "${major}" -lt 13 ]]; then
+    url="https://www.unicode.org/Public/emoji/${version}/emoji-data.txt"
+fi
+
+mkdir -p "${data_dir}"
+curl --fail --silent --show-error --location "${url}" --output "${target}"
+
+printf "✅ Saved emoji data \033[1m%s\033[0m to %s\n" "${version}" "${target}"